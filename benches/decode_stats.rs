@@ -0,0 +1,32 @@
+//! Guards overhead of decode statistics.
+//!
+//! Run with `cargo bench --bench decode_stats`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use argosy::DecodeHistogram;
+
+/// Maximum average cost of recording one sample.
+const MAX_RECORD_COST: Duration = Duration::from_nanos(50);
+
+const SAMPLES: u32 = 10_000_000;
+
+fn main() {
+    let histogram = DecodeHistogram::new();
+
+    let start = Instant::now();
+    for i in 0..SAMPLES {
+        histogram.record(black_box(Duration::from_nanos(i as u64)), black_box(64));
+    }
+    let per_record = start.elapsed() / SAMPLES;
+
+    assert_eq!(histogram.count(), SAMPLES as u64);
+    println!("DecodeHistogram::record: {per_record:?} per sample");
+    assert!(
+        per_record <= MAX_RECORD_COST,
+        "Recording decode sample takes {per_record:?}, more than {MAX_RECORD_COST:?}"
+    );
+}
@@ -0,0 +1,89 @@
+//! Importers library for the pipeline example.
+//!
+//! Built as `cdylib` and loaded by the store listed in `argosy.toml`.
+
+use std::path::Path;
+
+use argosy_import::{
+    DepSlot, Dependencies, DescriptorWriter, ImportError, Importer, OutputSink, Sources,
+};
+use serde_json::Value;
+
+/// Imports JSON descriptors as is,
+/// except references to other assets that are replaced with their ids.
+///
+/// Reference is an object `{ "$asset": "<source>", "$target": "<asset name>" }`.
+pub struct JsonImporter {
+    pub target: &'static str,
+}
+
+impl Importer for JsonImporter {
+    fn name(&self) -> &str {
+        self.target
+    }
+
+    fn formats(&self) -> &[&str] {
+        &["json"]
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+
+    fn target(&self) -> &str {
+        self.target
+    }
+
+    fn description(&self) -> &str {
+        "Copies JSON descriptor resolving asset references"
+    }
+
+    fn import(
+        &self,
+        source: &Path,
+        output: &Path,
+        _sources: &mut dyn Sources,
+        dependencies: &mut dyn Dependencies,
+        sink: &mut OutputSink,
+    ) -> Result<(), ImportError> {
+        let other = |reason: String| ImportError::Other { reason };
+
+        let text = std::fs::read_to_string(source).map_err(|err| other(err.to_string()))?;
+        let mut value: Value = serde_json::from_str(&text).map_err(|err| other(err.to_string()))?;
+
+        let mut writer = DescriptorWriter::new(dependencies);
+        let mut slots = Vec::new();
+        visit_refs(&mut value, &mut |reference| {
+            let source = reference["$asset"].as_str().unwrap_or_default();
+            let target = reference["$target"].as_str().unwrap_or_default();
+            slots.push(writer.dep(source, target));
+        });
+        let deps = writer.finish()?;
+
+        let mut slots = slots.into_iter();
+        visit_refs(&mut value, &mut |reference| {
+            let slot: DepSlot = slots.next().unwrap();
+            *reference = Value::String(deps.id(slot).to_string());
+        });
+
+        sink.write_descriptor(output, &value)
+    }
+}
+
+/// Calls `f` for each asset reference in `value`.
+fn visit_refs(value: &mut Value, f: &mut impl FnMut(&mut Value)) {
+    match value {
+        Value::Object(object) if object.contains_key("$asset") => f(value),
+        Value::Object(object) => object.values_mut().for_each(|value| visit_refs(value, f)),
+        Value::Array(array) => array.iter_mut().for_each(|value| visit_refs(value, f)),
+        _ => {}
+    }
+}
+
+pub static LEVEL: JsonImporter = JsonImporter { target: "Level" };
+pub static SPRITE: JsonImporter = JsonImporter { target: "Sprite" };
+
+argosy_import::make_argosy_importers_library! {
+    &LEVEL;
+    &SPRITE;
+}
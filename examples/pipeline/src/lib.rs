@@ -0,0 +1,168 @@
+//! End-to-end pipeline example.
+//!
+//! Assets are imported by the store with importers loaded from a dynamic library,
+//! then loaded from the store by path and built with a GPU-like builder.
+//!
+//! # Example
+//!
+//! ```
+//! let dir = std::env::temp_dir().join(format!("argosy-pipeline-{}", std::process::id()));
+//! let report = argosy_pipeline_example::run(&dir).unwrap();
+//! assert_eq!(report, "Level 'Forest' with hero texture #0 16x32. 2048 bytes uploaded");
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use argosy::{Asset, AssetBuild, AssetName, Loader};
+use argosy_pipeline_importer::JsonImporter;
+use argosy_store::{Store, StoreInfo};
+
+/// Fake GPU that textures are uploaded to.
+#[derive(Default)]
+pub struct Gpu {
+    textures: Vec<[u8; 4]>,
+    uploaded: usize,
+}
+
+impl Gpu {
+    /// Uploads texture filled with `color` and returns its index.
+    fn upload(&mut self, width: u32, height: u32, color: [u8; 4]) -> usize {
+        self.textures.push(color);
+        self.uploaded += width as usize * height as usize * 4;
+        self.textures.len() - 1
+    }
+
+    /// Returns number of bytes uploaded so far.
+    pub fn uploaded(&self) -> usize {
+        self.uploaded
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct SpriteInfo {
+    width: u32,
+    height: u32,
+    color: [u8; 4],
+}
+
+/// Sprite which texture lives on the [`Gpu`].
+#[derive(Clone, Debug)]
+pub struct Sprite {
+    pub texture: usize,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Asset for Sprite {
+    type Decoded = SpriteInfo;
+    type DecodeError = serde_json::Error;
+    type BuildError = std::convert::Infallible;
+    type Fut = std::future::Ready<Result<SpriteInfo, serde_json::Error>>;
+
+    fn asset_name() -> AssetName {
+        AssetName::new("Sprite")
+    }
+
+    fn decode(bytes: Box<[u8]>, _: &Loader) -> Self::Fut {
+        std::future::ready(serde_json::from_slice(&bytes))
+    }
+}
+
+impl AssetBuild<Gpu> for Sprite {
+    fn build(gpu: &mut Gpu, info: SpriteInfo) -> Result<Self, std::convert::Infallible> {
+        Ok(Sprite {
+            texture: gpu.upload(info.width, info.height, info.color),
+            width: info.width,
+            height: info.height,
+        })
+    }
+}
+
+/// Level that references hero sprite imported from another source.
+#[derive(Clone, Debug, Asset)]
+pub struct Level {
+    pub title: String,
+
+    #[asset(external)]
+    pub hero: Sprite,
+}
+
+/// Fixture sources of the example.
+const FIXTURES: &[&str] = &["level.json", "hero.json"];
+
+/// Returns path to the importers library built alongside the example.
+fn importers_lib() -> Option<PathBuf> {
+    let filename = format!(
+        "{}argosy_pipeline_importer{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    );
+
+    // Binaries, tests and doctests are placed in the profile directory or one level below.
+    let exe = std::env::current_exe().ok()?;
+    exe.ancestors()
+        .skip(1)
+        .take(3)
+        .map(|dir| dir.join(&filename))
+        .find(|path| path.is_file())
+}
+
+/// Copies fixtures into `dir` and writes store configuration
+/// that loads importers from the library.
+///
+/// Returns path to the store configuration.
+pub fn prepare(dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+
+    std::fs::create_dir_all(dir.join("temp"))?;
+    for fixture in FIXTURES {
+        std::fs::copy(assets.join(fixture), dir.join(fixture))?;
+    }
+
+    let lib = importers_lib();
+    let importers: Vec<&Path> = lib.as_deref().into_iter().collect();
+
+    let info_path = dir.join("argosy.toml");
+    StoreInfo::new(None, None, Some(Path::new("temp")), &importers).write(&info_path)?;
+    Ok(info_path)
+}
+
+/// Imports fixtures in `dir`, loads the level and builds it.
+/// Returns description of the built level.
+pub fn run(dir: &Path) -> Result<String, Box<dyn Error>> {
+    let info_path = prepare(dir)?;
+    let mut store = Store::open(&info_path)?;
+
+    if store.importers().is_empty() {
+        // Library is not built, e.g. when only this package is compiled.
+        store.register_importer(Box::new(JsonImporter { target: "Level" }));
+        store.register_importer(Box::new(JsonImporter { target: "Sprite" }));
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+
+    // Import ahead of time, like an asset build step would.
+    // Hero sprite is imported as dependency of the level.
+    runtime.block_on(store.store("level.json", None, "Level"))?;
+
+    let loader = Loader::builder().with(store).build();
+    let mut gpu = Gpu::default();
+
+    let level = runtime.block_on(async {
+        let mut level = loader.load::<Level, _>("level.json").await?;
+        level.build(&mut gpu)
+    })?;
+
+    Ok(format!(
+        "Level '{}' with hero texture #{} {}x{}. {} bytes uploaded",
+        level.title,
+        level.hero.texture,
+        level.hero.width,
+        level.hero.height,
+        gpu.uploaded(),
+    ))
+}
@@ -0,0 +1,10 @@
+fn main() {
+    let dir = std::env::temp_dir().join("argosy-pipeline-example");
+    match argosy_pipeline_example::run(&dir) {
+        Ok(report) => println!("{report}"),
+        Err(err) => {
+            eprintln!("Pipeline failed. {err}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,6 +1,6 @@
+use std::time::Duration;
 
-
-use argosy::{Asset, AssetData, AssetDriver, AssetField, Error, Loader, Source};
+use argosy::{Asset, AssetData, AssetField, AssetProperties, Error, Loader, Source};
 use argosy_id::AssetId;
 use futures::future::BoxFuture;
 
@@ -37,12 +37,14 @@ impl Source for TestSource {
                 Ok(Some(AssetData {
                     bytes: (*b"{}").into(),
                     version: 0,
+                    properties: AssetProperties::new(),
                 }))
             }),
             AssetId(id) if id.get() == 2 => Box::pin(async {
                 Ok(Some(AssetData {
                     bytes: (*b"{ \"foo\": 1, \"bar\": { \"foo\": 1 } }").into(),
                     version: 0,
+                    properties: AssetProperties::new(),
                 }))
             }),
             _ => Box::pin(async { Ok(None) }),
@@ -51,15 +53,19 @@ impl Source for TestSource {
 
     fn update<'a>(
         &'a self,
-        id: AssetId,
+        _id: AssetId,
         _version: u64,
     ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
-        self.load(id)
+        // Test data never changes.
+        Box::pin(async { Ok(None) })
     }
 }
 
 fn main() {
-    let loader = Loader::builder().with(TestSource).build();
+    let loader = Loader::builder()
+        .with(TestSource)
+        .with_build_wait_warning(Duration::from_secs(1))
+        .build();
 
     let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
@@ -67,16 +73,13 @@ fn main() {
         .unwrap();
 
     runtime.block_on(async move {
-        let with_foo = loader.load::<WithFoo, _>("WithFoo");
-
-        let with_foo_driver: AssetDriver = with_foo.clone().driver();
-
-        tokio::spawn(async move {
-            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-            with_foo_driver.await.build(&mut ());
-        });
-
-        let with_foo = with_foo.ready().await.unwrap();
+        // Nothing else builds the asset, so build it here.
+        let with_foo = loader
+            .load::<WithFoo, _>("WithFoo")
+            .ready()
+            .ready_or_build(())
+            .await
+            .unwrap();
         println!("{with_foo:?}");
 
         let with_foo = loader.load::<WithFoo, _>("WithFoo").ready().await.unwrap();
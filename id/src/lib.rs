@@ -11,6 +11,50 @@ use serde::{
 
 /// 64-bit id value.
 /// FFI-safe.
+///
+/// Textual form is zero-padded 16-digit lowercase hex.
+/// It is used by [`Display`] and human-readable serialization formats.
+/// Parsing accepts both padded and unpadded hex.
+///
+/// Human-readable formats also accept ids written as integers.
+/// Hex strings are recommended, as numbers beyond 2^53 lose precision
+/// in many tools that treat JSON numbers as doubles.
+///
+/// ```
+/// # use argosy_id::AssetId;
+/// let id = AssetId::new(0xabc).unwrap();
+/// assert_eq!(id.to_string(), "0000000000000abc");
+/// assert_eq!(id.to_padded_hex(), "0000000000000abc");
+/// assert_eq!(format!("{:x}", id), "abc");
+/// assert_eq!(format!("{:08X}", id), "00000ABC");
+/// assert_eq!(format!("{:#x}", id), "0xabc");
+///
+/// assert_eq!("0000000000000abc".parse::<AssetId>().unwrap(), id);
+/// assert_eq!("abc".parse::<AssetId>().unwrap(), id);
+/// assert!("0".parse::<AssetId>().is_err());
+///
+/// // Human-readable formats use padded hex string.
+/// let json = serde_json::to_string(&id).unwrap();
+/// assert_eq!(json, "\"0000000000000abc\"");
+/// assert_eq!(serde_json::from_str::<AssetId>(&json).unwrap(), id);
+/// assert_eq!(serde_json::from_str::<AssetId>("\"abc\"").unwrap(), id);
+/// assert_eq!(serde_json::from_str::<AssetId>("2748").unwrap(), id);
+/// assert!(serde_json::from_str::<AssetId>("0").is_err());
+///
+/// // Numbers are accepted in the whole 64-bit range and never rounded.
+/// let max = serde_json::from_str::<AssetId>("18446744073709551615").unwrap();
+/// assert_eq!(max.value().get(), u64::MAX);
+/// let err = serde_json::from_str::<AssetId>("18446744073709551616").unwrap_err();
+/// assert!(err.to_string().contains("out of 64-bit range"), "{err}");
+/// assert!(serde_json::from_str::<AssetId>("2748.0").is_err());
+/// assert!(serde_json::from_str::<AssetId>("-1").is_err());
+///
+/// // Binary formats use integer.
+/// let bytes = bincode::serialize(&id).unwrap();
+/// assert_eq!(bytes, 0xabcu64.to_le_bytes());
+/// assert_eq!(bincode::deserialize::<AssetId>(&bytes).unwrap(), id);
+/// assert!(bincode::deserialize::<AssetId>(&0u64.to_le_bytes()).is_err());
+/// ```
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct AssetId(pub NonZeroU64);
@@ -21,11 +65,8 @@ impl Serialize for AssetId {
     where
         S: Serializer,
     {
-        use std::io::Write;
-
         if serializer.is_human_readable() {
-            let mut hex = [0u8; 16];
-            write!(std::io::Cursor::new(&mut hex[..]), "{:016x}", self.0).expect("Must fit");
+            let hex = self.padded_hex();
             let hex = std::str::from_utf8(&hex).expect("Must be UTF-8");
             serializer.serialize_str(hex)
         } else {
@@ -67,6 +108,43 @@ impl<'de> serde::de::Visitor<'de> for AssetIdVisitor {
         }
     }
 
+    #[inline(always)]
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match u64::try_from(v) {
+            Ok(v) => self.visit_u64(v),
+            Err(_) => Err(out_of_range(v)),
+        }
+    }
+
+    #[inline(always)]
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match u64::try_from(v) {
+            Ok(v) => self.visit_u64(v),
+            Err(_) => Err(out_of_range(v)),
+        }
+    }
+
+    /// Numbers that don't fit into 64-bit integer are parsed as floats.
+    /// They are rejected instead of rounding to a different id.
+    #[inline(always)]
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        if v.fract() == 0.0 && v >= u64::MAX as f64 {
+            return Err(out_of_range(v));
+        }
+        Err(E::custom(format_args!(
+            "AssetId must be an integer, found number {v}. Write ids as hex strings"
+        )))
+    }
+
     #[inline(always)]
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
@@ -74,6 +152,33 @@ impl<'de> serde::de::Visitor<'de> for AssetIdVisitor {
     {
         v.parse().map_err(E::custom)
     }
+
+    /// `serde_json` with `arbitrary_precision` feature passes numbers
+    /// that don't fit into 64-bit integer as a single-entry map.
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        match map.next_key::<String>()? {
+            Some(key) if key == SERDE_JSON_NUMBER_TOKEN => {
+                let number = map.next_value::<String>()?;
+                match number.parse::<u64>() {
+                    Ok(v) => self.visit_u64(v),
+                    Err(_) => Err(out_of_range(number)),
+                }
+            }
+            _ => Err(A::Error::invalid_type(Unexpected::Map, &self)),
+        }
+    }
+}
+
+/// Key of the map that `serde_json` uses to pass arbitrary precision numbers.
+const SERDE_JSON_NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+fn out_of_range<E: Error>(v: impl Display) -> E {
+    E::custom(format_args!(
+        "AssetId number {v} is out of 64-bit range. Write ids as hex strings"
+    ))
 }
 
 impl<'de> Deserialize<'de> for AssetId {
@@ -82,7 +187,12 @@ impl<'de> Deserialize<'de> for AssetId {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_u64(AssetIdVisitor)
+        if deserializer.is_human_readable() {
+            // Either hex string or integer.
+            deserializer.deserialize_any(AssetIdVisitor)
+        } else {
+            deserializer.deserialize_u64(AssetIdVisitor)
+        }
     }
 }
 
@@ -124,6 +234,27 @@ impl AssetId {
     pub fn value(&self) -> NonZeroU64 {
         self.0
     }
+
+    /// Returns zero-padded 16-digit lowercase hex representation of the id.
+    ///
+    /// This is the same as [`Display`] output and human-readable serialized form.
+    #[inline]
+    pub fn to_padded_hex(&self) -> String {
+        let hex = self.padded_hex();
+        std::str::from_utf8(&hex).expect("Must be UTF-8").to_owned()
+    }
+
+    #[inline(always)]
+    fn padded_hex(&self) -> [u8; 16] {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+        let value = self.0.get();
+        let mut hex = [0u8; 16];
+        for (i, digit) in hex.iter_mut().enumerate() {
+            *digit = DIGITS[(value >> ((15 - i) * 4)) as usize & 0xf];
+        }
+        hex
+    }
 }
 
 impl From<NonZeroU64> for AssetId {
@@ -147,7 +278,7 @@ impl TryFrom<u64> for AssetId {
 impl Debug for AssetId {
     #[inline(always)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        LowerHex::fmt(&self.0.get(), f)
+        Display::fmt(self, f)
     }
 }
 
@@ -168,6 +299,7 @@ impl LowerHex for AssetId {
 impl Display for AssetId {
     #[inline(always)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        LowerHex::fmt(&self.0.get(), f)
+        let hex = self.padded_hex();
+        f.pad(std::str::from_utf8(&hex).expect("Must be UTF-8"))
     }
 }
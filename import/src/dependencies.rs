@@ -1,7 +1,7 @@
 use argosy_id::AssetId;
 
 /// Single dependency for a asset.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Dependency {
     /// Source path.
     pub source: String,
@@ -0,0 +1,179 @@
+use std::ops::Index;
+
+use argosy_id::AssetId;
+
+use crate::{ensure, Dependencies, Dependency, ImportError};
+
+/// Reference to a dependency in a descriptor.
+///
+/// Serializes as [`AssetId`] of the dependency,
+/// i.e. hex string in human-readable formats like JSON.
+/// Use it for fields that are loaded with `#[asset(external)]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct DepRef(pub AssetId);
+
+impl DepRef {
+    /// Returns id of the dependency.
+    pub fn id(&self) -> AssetId {
+        self.0
+    }
+}
+
+/// Slot of a dependency requested with [`DescriptorWriter::dep`].
+///
+/// Resolved to [`DepRef`] by indexing [`ResolvedDeps`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DepSlot(usize);
+
+/// Collects dependencies of a descriptor.
+///
+/// Importer requests all dependencies first and calls [`DescriptorWriter::finish`].
+/// If some are not imported yet, it returns [`ImportError::Requires`]
+/// that should be returned from the importer as is.
+/// Store imports them and runs the importer again.
+/// Once all are available, resolved ids are used to fill descriptor fields.
+///
+/// # Example
+///
+/// ```
+/// # use std::collections::HashMap;
+/// use argosy_import::{
+///     DepRef, Dependencies, DescriptorFormat, DescriptorWriter, ImportError, OutputSink,
+/// };
+/// use argosy_id::AssetId;
+///
+/// #[derive(serde::Serialize)]
+/// struct Material {
+///     albedo: DepRef,
+///     normal: DepRef,
+/// }
+///
+/// fn import_material(
+///     output: &std::path::Path,
+///     dependencies: &mut dyn Dependencies,
+///     sink: &mut OutputSink,
+/// ) -> Result<(), ImportError> {
+///     let mut writer = DescriptorWriter::new(dependencies);
+///     let albedo = writer.dep("albedo.png", "texture");
+///     let normal = writer.dep("normal.png", "texture");
+///     let deps = writer.finish()?;
+///
+///     let material = Material {
+///         albedo: deps[albedo],
+///         normal: deps[normal],
+///     };
+///     sink.write_descriptor(output, &material)
+/// }
+///
+/// /// Dependencies imported by the store so far.
+/// struct Imported(HashMap<String, AssetId>);
+///
+/// impl Dependencies for Imported {
+///     fn get(&mut self, source: &str, _target: &str) -> Option<AssetId> {
+///         self.0.get(source).copied()
+///     }
+/// }
+///
+/// let output = std::env::temp_dir().join("argosy-descriptor-writer-example.json");
+/// let mut sink = OutputSink::new(DescriptorFormat::Json);
+/// let mut imported = Imported(HashMap::new());
+/// imported.0.insert("albedo.png".to_owned(), AssetId::new(1).unwrap());
+///
+/// // First pass. Normal map is not imported yet.
+/// match import_material(&output, &mut imported, &mut sink) {
+///     Err(ImportError::Requires { sources, dependencies }) => {
+///         assert!(sources.is_empty());
+///         assert_eq!(dependencies.len(), 1);
+///         assert_eq!(dependencies[0].source, "normal.png");
+///     }
+///     _ => panic!("Dependency must be required"),
+/// }
+/// assert!(!sink.is_descriptor());
+///
+/// // Second pass after store imported the dependency.
+/// imported.0.insert("normal.png".to_owned(), AssetId::new(2).unwrap());
+/// assert!(import_material(&output, &mut imported, &mut sink).is_ok());
+/// assert!(sink.is_descriptor());
+///
+/// let json: serde_json::Value = serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+/// assert_eq!(json["albedo"], AssetId::new(1).unwrap().to_string());
+/// assert_eq!(json["normal"], AssetId::new(2).unwrap().to_string());
+/// # std::fs::remove_file(output).unwrap();
+/// ```
+pub struct DescriptorWriter<'a> {
+    dependencies: &'a mut dyn Dependencies,
+    requested: Vec<Dependency>,
+    ids: Vec<Option<AssetId>>,
+    missing: Vec<Dependency>,
+}
+
+impl<'a> DescriptorWriter<'a> {
+    /// Returns new writer that resolves dependencies with `dependencies`.
+    pub fn new(dependencies: &'a mut dyn Dependencies) -> Self {
+        DescriptorWriter {
+            dependencies,
+            requested: Vec::new(),
+            ids: Vec::new(),
+            missing: Vec::new(),
+        }
+    }
+
+    /// Requests dependency with specified source and target format.
+    /// Returns slot that is resolved after [`DescriptorWriter::finish`].
+    ///
+    /// Same dependency requested twice gets the same slot.
+    pub fn dep(&mut self, source: &str, target: &str) -> DepSlot {
+        if let Some(index) = self
+            .requested
+            .iter()
+            .position(|dep| dep.source == source && dep.target == target)
+        {
+            return DepSlot(index);
+        }
+
+        let id = self
+            .dependencies
+            .get_or_append(source, target, &mut self.missing);
+
+        self.requested.push(Dependency {
+            source: source.to_owned(),
+            target: target.to_owned(),
+        });
+        self.ids.push(id);
+        DepSlot(self.ids.len() - 1)
+    }
+
+    /// Returns resolved dependencies.
+    /// If any dependency is missing, returns [`ImportError::Requires`] with all missing dependencies.
+    pub fn finish(self) -> Result<ResolvedDeps, ImportError> {
+        ensure(Vec::new(), self.missing)?;
+
+        Ok(ResolvedDeps {
+            refs: self.ids.into_iter().map(|id| DepRef(id.unwrap())).collect(),
+        })
+    }
+}
+
+/// Dependencies resolved by [`DescriptorWriter::finish`].
+///
+/// Index with [`DepSlot`] to get [`DepRef`].
+#[derive(Clone, Debug)]
+pub struct ResolvedDeps {
+    refs: Vec<DepRef>,
+}
+
+impl ResolvedDeps {
+    /// Returns id of the dependency in the slot.
+    pub fn id(&self, slot: DepSlot) -> AssetId {
+        self.refs[slot.0].0
+    }
+}
+
+impl Index<DepSlot> for ResolvedDeps {
+    type Output = DepRef;
+
+    fn index(&self, slot: DepSlot) -> &DepRef {
+        &self.refs[slot.0]
+    }
+}
@@ -1,4 +1,10 @@
-use std::{marker::PhantomData, mem::size_of, path::PathBuf};
+use std::{
+    any::Any,
+    marker::PhantomData,
+    mem::{align_of, offset_of, size_of},
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
 
 #[cfg(any(unix, target_os = "wasi"))]
 use std::ffi::{OsStr, OsString};
@@ -18,12 +24,14 @@ use std::{
 use argosy_id::AssetId;
 
 use crate::{
-    dependencies::Dependencies,
+    dependencies::{Dependencies, Dependency},
     importer::{ImportError, Importer},
+    output::{DescriptorFormat, OutputSink},
     sources::Sources,
 };
 
 const PATH_BUF_LEN_START: usize = 1024;
+const RESULT_BUF_LEN_START: usize = 8192;
 pub const ANY_BUF_LEN_LIMIT: usize = 65536;
 
 pub const REQUIRES: i32 = 1;
@@ -73,11 +81,19 @@ unsafe extern "C" fn dependencies_get_ffi<D: Dependencies>(
 
     let d = &mut *(dependencies as *mut D);
 
-    match d.get(source, target) {
-        None => return NOT_FOUND,
-        Some(id) => {
+    // Panic must not unwind into the library.
+    match catch_unwind(AssertUnwindSafe(|| d.get(source, target))) {
+        Err(panic) => {
+            tracing::error!(
+                "`Dependencies::get` panicked. {}",
+                panic_message(panic.as_ref())
+            );
+            OTHER_ERROR
+        }
+        Ok(None) => NOT_FOUND,
+        Ok(Some(id)) => {
             std::ptr::write(id_ptr, id.value().get());
-            return SUCCESS;
+            SUCCESS
         }
     }
 }
@@ -119,6 +135,7 @@ impl Dependencies for DependenciesFFI<'_> {
             },
             NOT_FOUND => None,
             NOT_UTF8 => panic!("Source is not UTF8 while stored in `str`"),
+            OTHER_ERROR => panic!("`Dependencies::get` panicked in the host"),
             _ => panic!("Unexpected return code from `Sources::get` FFI: {}", result),
         }
     }
@@ -135,7 +152,7 @@ pub type SourcesGetFn = unsafe extern "C" fn(
     path_len: *mut u32,
 ) -> i32;
 
-unsafe extern "C" fn sources_get_ffi<'a, S: Sources>(
+unsafe extern "C" fn sources_get_ffi<S: Sources>(
     sources: *mut SourcesOpaque,
     source_ptr: *const u8,
     source_len: u32,
@@ -150,9 +167,14 @@ unsafe extern "C" fn sources_get_ffi<'a, S: Sources>(
 
     let f = &mut *(sources as *mut S);
 
-    match f.get(source) {
-        None => return NOT_FOUND,
-        Some(path) => {
+    // Panic must not unwind into the library.
+    match catch_unwind(AssertUnwindSafe(|| f.get(source))) {
+        Err(panic) => {
+            tracing::error!("`Sources::get` panicked. {}", panic_message(panic.as_ref()));
+            OTHER_ERROR
+        }
+        Ok(None) => NOT_FOUND,
+        Ok(Some(path)) => {
             let os_str = path.as_os_str();
 
             #[cfg(any(unix, target_os = "wasi"))]
@@ -172,7 +194,7 @@ unsafe extern "C" fn sources_get_ffi<'a, S: Sources>(
             std::ptr::copy_nonoverlapping(path.as_ptr(), path_ptr, path.len() as u32 as usize);
             *path_len = path.len() as u32;
 
-            return SUCCESS;
+            SUCCESS
         }
     }
 }
@@ -234,6 +256,7 @@ impl Sources for SourcesFFI<'_> {
             }
             NOT_FOUND => None,
             NOT_UTF8 => panic!("Source is not UTF8 while stored in `str`"),
+            OTHER_ERROR => panic!("`Sources::get` panicked in the host"),
             _ => panic!("Unexpected return code from `Sources::get` FFI: {}", result),
         }
     }
@@ -252,6 +275,10 @@ pub type ImporterImportFn = unsafe extern "C" fn(
     sources_get: SourcesGetFn,
     dependencies: *mut DependenciesOpaque,
     dependencies_get: DependenciesGetFn,
+    descriptor_format: u32,
+    config_ptr: *const u8,
+    config_len: u32,
+    descriptor: *mut u8,
     result_ptr: *mut u8,
     result_len: *mut u32,
 ) -> i32;
@@ -266,6 +293,10 @@ unsafe extern "C" fn importer_import_ffi<I: Importer>(
     sources_get: SourcesGetFn,
     dependencies: *mut DependenciesOpaque,
     dependencies_get: DependenciesGetFn,
+    descriptor_format: u32,
+    config_ptr: *const u8,
+    config_len: u32,
+    descriptor: *mut u8,
     result_ptr: *mut u8,
     result_len: *mut u32,
 ) -> i32 {
@@ -294,13 +325,31 @@ unsafe extern "C" fn importer_import_ffi<I: Importer>(
         marker: PhantomData,
     };
 
+    let mut sink = OutputSink::new(DescriptorFormat::from_ffi(descriptor_format));
+    if !config_ptr.is_null() {
+        let config = std::slice::from_raw_parts(config_ptr, config_len as usize);
+        sink.set_config(String::from_utf8_lossy(config));
+    }
+
     let importer = &*(importer as *const I);
-    let result = importer.import(
-        source.as_ref(),
-        output.as_ref(),
-        &mut sources,
-        &mut dependencies,
-    );
+
+    // Panic must not unwind into the host.
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        importer.import(
+            source.as_ref(),
+            output.as_ref(),
+            &mut sources,
+            &mut dependencies,
+            &mut sink,
+        )
+    }))
+    .unwrap_or_else(|panic| {
+        Err(ImportError::Other {
+            reason: format!("Importer panicked. {}", panic_message(panic.as_ref())),
+        })
+    });
+
+    *descriptor = u8::from(sink.is_descriptor());
 
     match result {
         Ok(()) => SUCCESS,
@@ -329,7 +378,7 @@ unsafe extern "C" fn importer_import_ffi<I: Importer>(
             let result = std::slice::from_raw_parts_mut(result_ptr, len_required);
             let mut offset = 0;
 
-            write_u32(result, &mut offset, source.len() as u32);
+            write_u32(result, &mut offset, sources.len() as u32);
             for source in sources {
                 write_slice(result, &mut offset, source.as_bytes());
             }
@@ -361,6 +410,7 @@ pub const MAX_EXTENSION_LEN: usize = 16;
 pub const MAX_EXTENSION_COUNT: usize = 16;
 pub const MAX_FFI_NAME_LEN: usize = 64;
 pub const MAX_FORMATS_COUNT: usize = 32;
+pub const MAX_FFI_DESCRIPTION_LEN: usize = 256;
 
 #[repr(C)]
 pub struct ImporterFFI {
@@ -370,8 +420,54 @@ pub struct ImporterFFI {
     pub formats: [[u8; MAX_FFI_NAME_LEN]; MAX_FORMATS_COUNT],
     pub target: [u8; MAX_FFI_NAME_LEN],
     pub extensions: [[u8; MAX_EXTENSION_LEN]; MAX_EXTENSION_COUNT],
+    pub description: [u8; MAX_FFI_DESCRIPTION_LEN],
+    pub version: u32,
 }
 
+/// Hashes sizes, alignments and field offsets of FFI structs.
+macro_rules! layout_hash {
+    ($($ty:ident { $($field:ident),* $(,)? })*) => {{
+        let mut hash = layout_hash_u64(0xcbf2_9ce4_8422_2325, size_of::<OsChar>() as u64);
+        $(
+            hash = layout_hash_u64(hash, size_of::<$ty>() as u64);
+            hash = layout_hash_u64(hash, align_of::<$ty>() as u64);
+            $(
+                hash = layout_hash_u64(hash, offset_of!($ty, $field) as u64);
+            )*
+        )*
+        hash
+    }};
+}
+
+/// FNV-1a step over bytes of `value`.
+const fn layout_hash_u64(mut hash: u64, value: u64) -> u64 {
+    let bytes = value.to_le_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
+
+/// Layout of FFI structs.
+///
+/// Exported by importers library and checked by the host before reading any [`ImporterFFI`],
+/// so that libraries built with different field order or buffer limits are rejected.
+pub const FFI_LAYOUT: u64 = layout_hash! {
+    ImporterFFI {
+        importer,
+        import,
+        name,
+        formats,
+        target,
+        extensions,
+        description,
+        version,
+    }
+};
+
 /// Exporting non thread-safe importers breaks the contract of the FFI.
 /// The potential unsoundness is covered by `load_dylib_importers` unsafety.
 /// There is no way to guarantee that dynamic library will uphold the contract,
@@ -380,7 +476,7 @@ unsafe impl Send for ImporterFFI {}
 unsafe impl Sync for ImporterFFI {}
 
 impl ImporterFFI {
-    pub fn new<'a, I>(importer: &'static I) -> Self
+    pub fn new<I>(importer: &'static I) -> Self
     where
         I: Importer,
     {
@@ -388,6 +484,8 @@ impl ImporterFFI {
         let formats = importer.formats();
         let target = importer.target();
         let extensions = importer.extensions();
+        let description = importer.description();
+        let version = importer.version();
 
         let importer = importer as *const I as *const ImporterOpaque;
 
@@ -421,6 +519,11 @@ impl ImporterFFI {
             "Importer extensions should fit into {} bytes",
             MAX_EXTENSION_LEN,
         );
+        assert!(
+            description.len() <= MAX_FFI_DESCRIPTION_LEN,
+            "Importer description should fit into {} bytes",
+            MAX_FFI_DESCRIPTION_LEN,
+        );
 
         assert!(!name.is_empty(), "Importer name should not be empty");
         assert!(!formats.is_empty(), "Importer formats should not be empty");
@@ -442,6 +545,10 @@ impl ImporterFFI {
             extensions.iter().all(|e| !e.contains('\0')),
             "Importer extensions should not contain '\\0' byte"
         );
+        assert!(
+            !description.contains('\0'),
+            "Importer description should not contain '\\0' byte"
+        );
 
         let mut name_buf = [0; MAX_FFI_NAME_LEN];
         name_buf[..name.len()].copy_from_slice(name.as_bytes());
@@ -460,6 +567,9 @@ impl ImporterFFI {
             extensions_buf[i][..extension.len()].copy_from_slice(extension.as_bytes());
         }
 
+        let mut description_buf = [0; MAX_FFI_DESCRIPTION_LEN];
+        description_buf[..description.len()].copy_from_slice(description.as_bytes());
+
         ImporterFFI {
             importer,
             import: importer_import_ffi::<I>,
@@ -467,8 +577,262 @@ impl ImporterFFI {
             formats: formats_buf,
             target: target_buf,
             extensions: extensions_buf,
+            description: description_buf,
+            version,
         }
     }
+
+    /// Calls the importer through FFI, as host does for importers loaded from a library.
+    ///
+    /// Panics in `sources` and `dependencies` never unwind into the importer,
+    /// the importer gets an error instead.
+    /// Panic in the importer is returned as [`ImportError::Other`].
+    ///
+    /// # Safety
+    ///
+    /// Library that exported this value must be loaded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_import::{Dependencies, ImportError, Importer, ImporterFFI, OutputSink, Sources};
+    /// # use std::path::{Path, PathBuf};
+    /// struct Include;
+    ///
+    /// impl Importer for Include {
+    ///     fn name(&self) -> &str { "Include" }
+    ///     fn formats(&self) -> &[&str] { &["text"] }
+    ///     fn extensions(&self) -> &[&str] { &[] }
+    ///     fn target(&self) -> &str { "text" }
+    ///     fn import(
+    ///         &self,
+    ///         _: &Path,
+    ///         _: &Path,
+    ///         sources: &mut dyn Sources,
+    ///         _: &mut dyn Dependencies,
+    ///         _: &mut OutputSink,
+    ///     ) -> Result<(), ImportError> {
+    ///         sources.get("header.txt");
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// struct Panicking;
+    ///
+    /// impl Sources for Panicking {
+    ///     fn get(&mut self, _: &str) -> Option<PathBuf> {
+    ///         panic!("Host is broken")
+    ///     }
+    /// }
+    ///
+    /// struct NoDependencies;
+    ///
+    /// impl Dependencies for NoDependencies {
+    ///     fn get(&mut self, _: &str, _: &str) -> Option<argosy_id::AssetId> {
+    ///         None
+    ///     }
+    /// }
+    ///
+    /// let ffi = ImporterFFI::new(&Include);
+    /// let mut sink = OutputSink::new(argosy_import::DescriptorFormat::Json);
+    ///
+    /// let result = unsafe {
+    ///     ffi.import(
+    ///         Path::new("source.txt"),
+    ///         Path::new("output.txt"),
+    ///         &mut Panicking,
+    ///         &mut NoDependencies,
+    ///         &mut sink,
+    ///     )
+    /// };
+    ///
+    /// match result {
+    ///     Err(ImportError::Other { reason }) => assert!(reason.contains("panicked")),
+    ///     _ => panic!("Host panic must be reported as error"),
+    /// }
+    /// ```
+    pub unsafe fn import(
+        &self,
+        source: &Path,
+        output: &Path,
+        sources: &mut dyn Sources,
+        dependencies: &mut dyn Dependencies,
+        sink: &mut OutputSink,
+    ) -> Result<(), ImportError> {
+        call_import(
+            self.importer,
+            self.import,
+            source,
+            output,
+            sources,
+            dependencies,
+            sink,
+        )
+    }
+}
+
+/// Calls importer through FFI.
+///
+/// Panics in `sources` and `dependencies` are reported to the importer as errors
+/// and panics in the importer are returned as [`ImportError::Other`].
+///
+/// # Safety
+///
+/// `importer` and `import` must come from the same [`ImporterFFI`]
+/// and library that exported it must be loaded.
+pub(crate) unsafe fn call_import(
+    importer: *const ImporterOpaque,
+    import: ImporterImportFn,
+    source: &Path,
+    output: &Path,
+    mut sources: &mut dyn Sources,
+    mut dependencies: &mut dyn Dependencies,
+    sink: &mut OutputSink,
+) -> Result<(), ImportError> {
+    let sources = &mut sources;
+    let dependencies = &mut dependencies;
+
+    let os_str = source.as_os_str();
+
+    #[cfg(any(unix, target_os = "wasi"))]
+    let source: &[u8] = os_str.as_bytes();
+
+    #[cfg(windows)]
+    let os_str_wide = os_str.encode_wide().collect::<Vec<u16>>();
+
+    #[cfg(windows)]
+    let source: &[u16] = &*os_str_wide;
+
+    let os_str = output.as_os_str();
+
+    #[cfg(any(unix, target_os = "wasi"))]
+    let output: &[u8] = os_str.as_bytes();
+
+    #[cfg(windows)]
+    let os_str_wide = os_str.encode_wide().collect::<Vec<u16>>();
+
+    #[cfg(windows)]
+    let output: &[u16] = &*os_str_wide;
+
+    let sources = SourcesFFI::new(sources);
+    let dependencies = DependenciesFFI::new(dependencies);
+
+    let mut result_buf = Vec::new();
+    let mut result_len = RESULT_BUF_LEN_START as u32;
+    let mut result = BUFFER_IS_TOO_SMALL;
+    let mut descriptor = 0u8;
+
+    while result == BUFFER_IS_TOO_SMALL {
+        if result_len > ANY_BUF_LEN_LIMIT as u32 {
+            return Err(ImportError::Other {
+                reason: format!(
+                    "Result does not fit into limit '{}', '{}' required",
+                    ANY_BUF_LEN_LIMIT, result_len
+                ),
+            });
+        }
+        result_buf.resize(result_len as usize, 0);
+
+        result = import(
+            importer,
+            source.as_ptr(),
+            source.len() as u32,
+            output.as_ptr(),
+            output.len() as u32,
+            sources.opaque,
+            sources.get,
+            dependencies.opaque,
+            dependencies.get,
+            sink.descriptor_format().to_ffi(),
+            sink.config().map_or(std::ptr::null(), str::as_ptr),
+            sink.config().map_or(0, |config| config.len() as u32),
+            &mut descriptor,
+            result_buf.as_mut_ptr(),
+            &mut result_len,
+        );
+    }
+
+    if descriptor != 0 {
+        sink.set_descriptor();
+    }
+
+    match result {
+        SUCCESS => Ok(()),
+        REQUIRES => {
+            let mut sources = Vec::new();
+            let mut dependencies = Vec::new();
+
+            let mut buffer = &result_buf[..result_len as usize];
+
+            let source_count = read_u32(&mut buffer);
+            for _ in 0..source_count {
+                let Ok(source) = core::str::from_utf8(read_slice(&mut buffer)) else {
+                    return Err(ImportError::Other { reason: "`Importer::import` requires sources, but one of the strings is not UTF-8".to_owned() });
+                };
+
+                sources.push(source.into());
+            }
+
+            let dependency_count = read_u32(&mut buffer);
+            for _ in 0..dependency_count {
+                let Ok(source) = core::str::from_utf8(read_slice(&mut buffer)) else {
+                    return Err(ImportError::Other { reason: "`Importer::import` requires dependencies, but one of the strings is not UTF-8".to_owned() });
+                };
+                let Ok(target) = core::str::from_utf8(read_slice(&mut buffer)) else {
+                    return Err(ImportError::Other { reason: "`Importer::import` requires dependencies, but one of the strings is not UTF-8".to_owned() });
+                };
+                dependencies.push(Dependency {
+                    source: source.into(),
+                    target: target.into(),
+                });
+            }
+            Err(ImportError::Requires {
+                sources,
+                dependencies,
+            })
+        }
+        OTHER_ERROR => {
+            debug_assert!(result_len <= result_buf.len() as u32);
+
+            let error = &result_buf[..result_len as usize];
+            let error_lossy = String::from_utf8_lossy(error);
+
+            Err(ImportError::Other {
+                reason: error_lossy.into_owned(),
+            })
+        }
+        _ => Err(ImportError::Other {
+            reason: format!(
+                "Unexpected return code from `Importer::import` FFI: {}",
+                result
+            ),
+        }),
+    }
+}
+
+/// Returns message of the panic payload.
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    match panic.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => match panic.downcast_ref::<String>() {
+            Some(message) => message,
+            None => "Box<dyn Any>",
+        },
+    }
+}
+
+fn read_u32(buffer: &mut &[u8]) -> u32 {
+    let mut array = [0; 4];
+    array.copy_from_slice(&buffer[..4]);
+    *buffer = &buffer[4..];
+    u32::from_le_bytes(array)
+}
+
+fn read_slice<'a>(buffer: &mut &'a [u8]) -> &'a [u8] {
+    let len = read_u32(buffer) as usize;
+    let slice = &buffer[..len];
+    *buffer = &buffer[len..];
+    slice
 }
 
 fn write_u32(buffer: &mut [u8], offset: &mut usize, value: u32) {
@@ -478,6 +842,6 @@ fn write_u32(buffer: &mut [u8], offset: &mut usize, value: u32) {
 
 fn write_slice(buffer: &mut [u8], offset: &mut usize, value: &[u8]) {
     write_u32(buffer, offset, value.len() as u32);
-    buffer[*offset..][..4].copy_from_slice(value);
+    buffer[*offset..][..value.len()].copy_from_slice(value);
     *offset += value.len();
 }
@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use crate::{Dependencies, Dependency, Sources};
+use crate::{Dependencies, Dependency, OutputSink, Sources};
 
 /// Error of `Importer::import` method.
 pub enum ImportError {
@@ -34,15 +34,33 @@ pub trait Importer: Send + Sync {
     /// Returns target format importer produces.
     fn target(&self) -> &str;
 
+    /// Returns human-readable description of the importer.
+    /// Editors may show it when user picks an importer.
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// Returns version of the importer.
+    /// Store reimports assets imported with older version
+    /// when importer output changes.
+    fn version(&self) -> u32 {
+        0
+    }
+
     /// Reads data from `source` path and writes result at `output` path.
     /// Implementation may request additional sources and dependencies.
     /// If some are missing it **should** return `Err(ImportError::Requires { .. })`
     /// with as much information as possible.
+    ///
+    /// Importers that produce serialized descriptors should write them
+    /// in format requested by `sink` and mark output as descriptor,
+    /// see [`OutputSink::write_descriptor`].
     fn import(
         &self,
         source: &Path,
         output: &Path,
         sources: &mut dyn Sources,
         dependencies: &mut dyn Dependencies,
+        sink: &mut OutputSink,
     ) -> Result<(), ImportError>;
 }
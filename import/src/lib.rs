@@ -29,6 +29,7 @@
 //!         output: &std::path::Path,
 //!         _sources: &mut dyn argosy_import::Sources,
 //!         _dependencies: &mut dyn argosy_import::Dependencies,
+//!         _sink: &mut argosy_import::OutputSink,
 //!     ) -> Result<(), argosy_import::ImportError> {
 //!         match std::fs::copy(source, output) {
 //!           Ok(_) => Ok(()),
@@ -46,21 +47,31 @@
 //! ```
 
 mod dependencies;
+#[cfg(feature = "descriptor")]
+mod descriptor;
 mod ffi;
 mod importer;
+mod output;
 mod sources;
 
 #[cfg(feature = "libloading")]
 pub mod loading;
 
-pub use ffi::ImporterFFI;
+pub use ffi::{ImporterFFI, FFI_LAYOUT};
 
 pub use self::{
     dependencies::{Dependencies, Dependency},
     importer::{ImportError, Importer},
+    output::{DescriptorFormat, OutputSink},
     sources::Sources,
 };
 
+#[cfg(feature = "descriptor")]
+pub use self::{
+    descriptor::{DepRef, DepSlot, DescriptorWriter, ResolvedDeps},
+    output::write_descriptor,
+};
+
 /// Helper function to emit an error if sources or dependencies are missing.
 pub fn ensure(sources: Vec<String>, dependencies: Vec<Dependency>) -> Result<(), ImportError> {
     if sources.is_empty() && dependencies.is_empty() {
@@ -102,6 +113,11 @@ macro_rules! make_argosy_importers_library {
             $crate::version()
         }
 
+        #[no_mangle]
+        pub unsafe extern "C" fn argosy_importer_ffi_layout() -> u64 {
+            $crate::FFI_LAYOUT
+        }
+
         #[no_mangle]
         pub unsafe extern "C" fn argosy_export_importers(buffer: *mut $crate::ImporterFFI, mut cap: u32) -> u32 {
             let mut len = 0;
@@ -6,45 +6,35 @@ use std::{
     sync::Arc,
 };
 
-#[cfg(unix)]
-use std::os::unix::ffi::OsStrExt;
-
-#[cfg(target_os = "wasi")]
-use std::os::wasi::ffi::OsStrExt;
-
-#[cfg(windows)]
-use std::os::windows::ffi::OsStrExt;
-
 use crate::{
-    ffi::{
-        DependenciesFFI, ImporterFFI, ImporterImportFn, ImporterOpaque, SourcesFFI,
-        ANY_BUF_LEN_LIMIT, BUFFER_IS_TOO_SMALL, MAX_EXTENSION_COUNT, MAX_FFI_NAME_LEN,
-        MAX_FORMATS_COUNT, OTHER_ERROR, REQUIRES, SUCCESS,
-    },
+    ffi::{call_import, ImporterFFI, ImporterImportFn, ImporterOpaque, FFI_LAYOUT},
     importer::Importer,
-    version, Dependencies, Dependency, ImportError, Sources, MAGIC,
+    version, Dependencies, ImportError, OutputSink, Sources, MAGIC,
 };
 
-const RESULT_BUF_LEN_START: usize = 8192;
-
 type MagicType = u32;
-const MAGIC_NAME: &'static str = "ARGOSY_DYLIB_MAGIC";
+const MAGIC_NAME: &str = "ARGOSY_DYLIB_MAGIC";
 
 type VersionFnType = unsafe extern "C" fn() -> u32;
-const VERSION_FN_NAME: &'static str = "argosy_importer_ffi_version_minor";
+const VERSION_FN_NAME: &str = "argosy_importer_ffi_version_minor";
+
+type LayoutFnType = unsafe extern "C" fn() -> u64;
+const LAYOUT_FN_NAME: &str = "argosy_importer_ffi_layout";
 
 type ExportImportersFnType = unsafe extern "C" fn(buffer: *mut ImporterFFI, count: u32) -> u32;
-const EXPORT_IMPORTERS_FN_NAME: &'static str = "argosy_export_importers";
+const EXPORT_IMPORTERS_FN_NAME: &str = "argosy_export_importers";
 
 pub struct DylibImporter {
     _path: Arc<Path>,
     _library: Arc<libloading::Library>,
     importer: *const ImporterOpaque,
     import: ImporterImportFn,
-    name: [u8; MAX_FFI_NAME_LEN],
-    formats: [Box<str>; MAX_FORMATS_COUNT],
-    target: [u8; MAX_FFI_NAME_LEN],
-    extensions: [Box<str>; MAX_EXTENSION_COUNT],
+    name: Box<str>,
+    formats: Vec<Box<str>>,
+    target: Box<str>,
+    extensions: Vec<Box<str>>,
+    description: Box<str>,
+    version: u32,
 }
 
 /// Exporting non thread-safe importers breaks the contract of the FFI.
@@ -61,21 +51,34 @@ impl DylibImporter {
             _library: library,
             importer: importer.importer,
             import: importer.import,
-            name: importer.name,
-            formats: importer
-                .formats
-                .map(|format| unsafe { std::str::from_utf8_unchecked(&format).into() }),
-            target: importer.target,
-            extensions: importer
-                .extensions
-                .map(|extension| unsafe { std::str::from_utf8_unchecked(&extension).into() }),
+            name: ffi_str(&importer.name),
+            formats: ffi_strs(&importer.formats),
+            target: ffi_str(&importer.target),
+            extensions: ffi_strs(&importer.extensions),
+            description: ffi_str(&importer.description),
+            version: importer.version,
         }
     }
 }
 
+/// Reads string from zero-padded FFI buffer.
+fn ffi_str(buffer: &[u8]) -> Box<str> {
+    let len = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..len]).into()
+}
+
+/// Reads non-empty strings from array of zero-padded FFI buffers.
+fn ffi_strs<const N: usize>(buffers: &[[u8; N]]) -> Vec<Box<str>> {
+    buffers
+        .iter()
+        .map(|buffer| ffi_str(buffer))
+        .filter(|string| !string.is_empty())
+        .collect()
+}
+
 impl Importer for DylibImporter {
     fn name(&self) -> &str {
-        unsafe { std::str::from_utf8_unchecked(&self.name) }
+        &self.name
     }
 
     fn formats(&self) -> &[&str] {
@@ -85,7 +88,7 @@ impl Importer for DylibImporter {
     }
 
     fn target(&self) -> &str {
-        unsafe { std::str::from_utf8_unchecked(&self.target) }
+        &self.target
     }
 
     fn extensions(&self) -> &[&str] {
@@ -97,124 +100,32 @@ impl Importer for DylibImporter {
         }
     }
 
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn version(&self) -> u32 {
+        self.version
+    }
+
     fn import(
         &self,
         source: &Path,
         output: &Path,
-        mut sources: &mut dyn Sources,
-        mut dependencies: &mut dyn Dependencies,
+        sources: &mut dyn Sources,
+        dependencies: &mut dyn Dependencies,
+        sink: &mut OutputSink,
     ) -> Result<(), ImportError> {
-        let sources = &mut sources;
-        let dependencies = &mut dependencies;
-
-        let os_str = source.as_os_str();
-
-        #[cfg(any(unix, target_os = "wasi"))]
-        let source: &[u8] = os_str.as_bytes();
-
-        #[cfg(windows)]
-        let os_str_wide = os_str.encode_wide().collect::<Vec<u16>>();
-
-        #[cfg(windows)]
-        let source: &[u16] = &*os_str_wide;
-
-        let os_str = output.as_os_str();
-
-        #[cfg(any(unix, target_os = "wasi"))]
-        let output: &[u8] = os_str.as_bytes();
-
-        #[cfg(windows)]
-        let os_str_wide = os_str.encode_wide().collect::<Vec<u16>>();
-
-        #[cfg(windows)]
-        let output: &[u16] = &*os_str_wide;
-
-        let sources = SourcesFFI::new(sources);
-        let dependencies = DependenciesFFI::new(dependencies);
-
-        let mut result_buf = Vec::new();
-        let mut result_len = RESULT_BUF_LEN_START as u32;
-        let mut result = BUFFER_IS_TOO_SMALL;
-
-        while result == BUFFER_IS_TOO_SMALL {
-            if result_len > ANY_BUF_LEN_LIMIT as u32 {
-                return Err(ImportError::Other {
-                    reason: format!(
-                        "Result does not fit into limit '{}', '{}' required",
-                        ANY_BUF_LEN_LIMIT, result_len
-                    ),
-                });
-            }
-            result_buf.resize(result_len as usize, 0);
-
-            result = unsafe {
-                (self.import)(
-                    self.importer,
-                    source.as_ptr(),
-                    source.len() as u32,
-                    output.as_ptr(),
-                    output.len() as u32,
-                    sources.opaque,
-                    sources.get,
-                    dependencies.opaque,
-                    dependencies.get,
-                    result_buf.as_mut_ptr(),
-                    &mut result_len,
-                )
-            };
-        }
-
-        match result {
-            SUCCESS => Ok(()),
-            REQUIRES => {
-                let mut sources = Vec::new();
-                let mut dependencies = Vec::new();
-
-                let mut buffer = &result_buf[..result_len as usize];
-
-                let source_count = read_u32(&mut buffer);
-                for _ in 0..source_count {
-                    let Ok(source) = core::str::from_utf8(read_slice(&mut buffer)) else {
-                    return Err(ImportError::Other { reason: "`Importer::import` requires sources, but one of the strings is not UTF-8".to_owned() });
-                };
-
-                    sources.push(source.into());
-                }
-
-                let dependency_count = read_u32(&mut buffer);
-                for _ in 0..dependency_count {
-                    let Ok(source) = core::str::from_utf8(read_slice(&mut buffer)) else {
-                    return Err(ImportError::Other { reason: "`Importer::import` requires dependencies, but one of the strings is not UTF-8".to_owned() });
-                };
-                    let Ok(target) = core::str::from_utf8(read_slice(&mut buffer)) else {
-                    return Err(ImportError::Other { reason: "`Importer::import` requires dependencies, but one of the strings is not UTF-8".to_owned() });
-                };
-                    dependencies.push(Dependency {
-                        source: source.into(),
-                        target: target.into(),
-                    });
-                }
-                Err(ImportError::Requires {
-                    sources,
-                    dependencies,
-                })
-            }
-            OTHER_ERROR => {
-                debug_assert!(result_len <= result_buf.len() as u32);
-
-                let error = &result_buf[..result_len as usize];
-                let error_lossy = String::from_utf8_lossy(error);
-
-                Err(ImportError::Other {
-                    reason: error_lossy.into_owned(),
-                })
-            }
-            _ => Err(ImportError::Other {
-                reason: format!(
-                    "Unexpected return code from `Importer::import` FFI: {}",
-                    result
-                ),
-            }),
+        unsafe {
+            call_import(
+                self.importer,
+                self.import,
+                source,
+                output,
+                sources,
+                dependencies,
+                sink,
+            )
         }
     }
 }
@@ -227,6 +138,8 @@ pub enum LoadingError {
     MagicValueMismatch,
     VersionSymbolNotFound,
     VersionMismatch,
+    LayoutSymbolNotFound,
+    LayoutMismatch { expected: u64, found: u64 },
     ExportImportersSymbolNotFound,
 }
 
@@ -245,6 +158,14 @@ impl Display for LoadingError {
                 write!(f, "'argosy_importer_ffi_version_minor' symbol not found")
             }
             LoadingError::VersionMismatch => write!(f, "Version mismatch"),
+            LoadingError::LayoutSymbolNotFound => {
+                write!(f, "'argosy_importer_ffi_layout' symbol not found")
+            }
+            LoadingError::LayoutMismatch { expected, found } => write!(
+                f,
+                "FFI layout {:016x} does not match expected layout {:016x}",
+                found, expected
+            ),
             LoadingError::ExportImportersSymbolNotFound => {
                 write!(f, "'argosy_export_importers' symbol not found")
             }
@@ -262,6 +183,12 @@ impl Error for LoadingError {
 }
 
 /// Load importers from dynamic library at specified path.
+///
+/// # Safety
+///
+/// There is no possible way to guarantee that dylib does not break safety contracts.
+/// Loaded library must be built with `make_argosy_importers_library!` macro
+/// and compatible version of this crate.
 pub unsafe fn load_importers(
     lib_path: &Path,
 ) -> Result<impl Iterator<Item = DylibImporter>, LoadingError> {
@@ -291,6 +218,13 @@ pub unsafe fn load_importers(
         return Err(LoadingError::VersionMismatch);
     }
 
+    // Layout must match before any `ImporterFFI` is read.
+    let lib_ffi_layout = lib
+        .get::<LayoutFnType>(LAYOUT_FN_NAME.as_bytes())
+        .map_err(|_| LoadingError::LayoutSymbolNotFound)?;
+
+    check_layout(lib_ffi_layout())?;
+
     let export_importers = lib
         .get::<ExportImportersFnType>(EXPORT_IMPORTERS_FN_NAME.as_bytes())
         .map_err(|_| LoadingError::ExportImportersSymbolNotFound)?;
@@ -322,16 +256,31 @@ pub unsafe fn load_importers(
     }))
 }
 
-fn read_u32(buffer: &mut &[u8]) -> u32 {
-    let mut array = [0; 4];
-    array.copy_from_slice(&buffer[..4]);
-    *buffer = &buffer[4..];
-    u32::from_le_bytes(array)
-}
-
-fn read_slice<'a>(buffer: &mut &'a [u8]) -> &'a [u8] {
-    let len = read_u32(buffer) as usize;
-    let slice = &buffer[..len];
-    *buffer = &buffer[len..];
-    slice
+/// Checks that FFI layout reported by importers library matches layout of this crate.
+///
+/// Libraries built with different field order or buffer limits
+/// of FFI structs report different layout.
+///
+/// # Example
+///
+/// ```
+/// # use argosy_import::{loading::{check_layout, LoadingError}, FFI_LAYOUT};
+/// assert!(check_layout(FFI_LAYOUT).is_ok());
+///
+/// match check_layout(FFI_LAYOUT ^ 1) {
+///     Err(LoadingError::LayoutMismatch { expected, found }) => {
+///         assert_eq!(expected, FFI_LAYOUT);
+///         assert_eq!(found, FFI_LAYOUT ^ 1);
+///     }
+///     _ => panic!("Layout mismatch must be detected"),
+/// }
+/// ```
+pub fn check_layout(layout: u64) -> Result<(), LoadingError> {
+    if layout != FFI_LAYOUT {
+        return Err(LoadingError::LayoutMismatch {
+            expected: FFI_LAYOUT,
+            found: layout,
+        });
+    }
+    Ok(())
 }
@@ -0,0 +1,131 @@
+#[cfg(feature = "descriptor")]
+use std::path::Path;
+
+#[cfg(feature = "descriptor")]
+use crate::ImportError;
+
+/// Serialization format of descriptor artifacts.
+///
+/// JSON is easy to inspect during development,
+/// bincode is smaller and faster to decode in shipping builds.
+/// Loader detects format of descriptors automatically.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "descriptor",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum DescriptorFormat {
+    /// Descriptor is written as JSON document.
+    #[default]
+    Json,
+
+    /// Descriptor is encoded with bincode.
+    Bincode,
+}
+
+impl DescriptorFormat {
+    pub(crate) fn to_ffi(self) -> u32 {
+        match self {
+            DescriptorFormat::Json => 0,
+            DescriptorFormat::Bincode => 1,
+        }
+    }
+
+    pub(crate) fn from_ffi(value: u32) -> Self {
+        match value {
+            1 => DescriptorFormat::Bincode,
+            _ => DescriptorFormat::Json,
+        }
+    }
+}
+
+/// Details of the importer output.
+///
+/// Tells importer in which format to write descriptors
+/// and lets it report that output is a serialized descriptor
+/// rather than opaque blob.
+/// Store re-imports descriptors when requested format changes.
+pub struct OutputSink {
+    format: DescriptorFormat,
+    descriptor: bool,
+    config: Option<String>,
+}
+
+impl OutputSink {
+    /// Returns new sink that requests descriptors in specified format.
+    pub fn new(format: DescriptorFormat) -> Self {
+        OutputSink {
+            format,
+            descriptor: false,
+            config: None,
+        }
+    }
+
+    /// Sets importer configuration.
+    pub fn set_config(&mut self, config: impl Into<String>) {
+        self.config = Some(config.into());
+    }
+
+    /// Returns importer configuration, if any.
+    ///
+    /// Store passes configuration of the importer from the active profile
+    /// as TOML table serialized to string.
+    pub fn config(&self) -> Option<&str> {
+        self.config.as_deref()
+    }
+
+    /// Returns format in which descriptors should be written.
+    pub fn descriptor_format(&self) -> DescriptorFormat {
+        self.format
+    }
+
+    /// Marks output as serialized descriptor.
+    pub fn set_descriptor(&mut self) {
+        self.descriptor = true;
+    }
+
+    /// Returns `true` if output is marked as serialized descriptor.
+    pub fn is_descriptor(&self) -> bool {
+        self.descriptor
+    }
+
+    /// Writes descriptor in requested format and marks output as descriptor.
+    #[cfg(feature = "descriptor")]
+    pub fn write_descriptor<T>(&mut self, path: &Path, value: &T) -> Result<(), ImportError>
+    where
+        T: serde::Serialize,
+    {
+        write_descriptor(path, value, self.format)?;
+        self.set_descriptor();
+        Ok(())
+    }
+}
+
+/// Writes descriptor to the file in specified format.
+#[cfg(feature = "descriptor")]
+pub fn write_descriptor<T>(
+    path: &Path,
+    value: &T,
+    format: DescriptorFormat,
+) -> Result<(), ImportError>
+where
+    T: serde::Serialize,
+{
+    let bytes = match format {
+        DescriptorFormat::Json => {
+            serde_json::to_vec_pretty(value).map_err(|err| ImportError::Other {
+                reason: format!("Failed to serialize descriptor to JSON. {err}"),
+            })?
+        }
+        DescriptorFormat::Bincode => {
+            bincode::serialize(value).map_err(|err| ImportError::Other {
+                reason: format!("Failed to serialize descriptor with bincode. {err}"),
+            })?
+        }
+    };
+
+    std::fs::write(path, bytes).map_err(|err| ImportError::Other {
+        reason: format!("Failed to write descriptor to '{}'. {err}", path.display()),
+    })
+}
@@ -0,0 +1,178 @@
+#ifndef ARGOSY_H
+#define ARGOSY_H
+
+/* Generated with cbindgen from src/capi.rs. Do not edit manually. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Handle is not resolved yet.
+ */
+#define ARGOSY_PENDING 0
+
+/**
+ * Handle is resolved with loaded asset or asset is built.
+ */
+#define ARGOSY_READY 1
+
+/**
+ * Handle is resolved with error or arguments are invalid.
+ */
+#define ARGOSY_ERROR -1
+
+/**
+ * Handle to the asset requested from C.
+ */
+typedef struct ArgosyHandle ArgosyHandle;
+
+/**
+ * Loader created from C.
+ */
+typedef struct ArgosyLoader ArgosyLoader;
+
+/**
+ * Functions to clone and drop built assets of one type.
+ */
+typedef struct ArgosyAssetVTable {
+  /**
+   * Clones asset and returns pointer to the clone.
+   */
+  void *(*clone)(const void *asset);
+  /**
+   * Drops asset.
+   */
+  void (*drop)(void *asset);
+} ArgosyAssetVTable;
+
+/**
+ * Built asset.
+ */
+typedef struct ArgosyAsset {
+  /**
+   * Opaque pointer to the asset.
+   */
+  void *ptr;
+  /**
+   * Functions to clone and drop the asset.
+   */
+  const struct ArgosyAssetVTable *vtable;
+} ArgosyAsset;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates loader from JSON configuration.
+ *
+ * Configuration lists sources, e.g.
+ * `{ "sources": [{ "kind": "files", "root": "assets" }, { "kind": "archive", "path": "assets.pak" }] }`.
+ * Null `config_json` creates loader without sources.
+ *
+ * Returns null if configuration is invalid.
+ *
+ * # Safety
+ *
+ * `config_json` must be null or point to NUL-terminated string.
+ */
+struct ArgosyLoader *argosy_loader_create(const char *config_json);
+
+/**
+ * Destroys loader.
+ * Handles created by the loader stay valid, but pending ones are never resolved.
+ *
+ * # Safety
+ *
+ * `loader` must be null or returned by [`argosy_loader_create`] and not destroyed yet.
+ */
+void argosy_loader_destroy(struct ArgosyLoader *loader);
+
+/**
+ * Drives loading tasks when argosy is built without `tokio` feature.
+ * Returns number of unfinished loading tasks.
+ *
+ * With `tokio` feature tasks run on a background thread and this function returns zero.
+ *
+ * # Safety
+ *
+ * `loader` must be null or valid loader.
+ */
+size_t argosy_loader_pump(struct ArgosyLoader *loader);
+
+/**
+ * Requests asset of registered type with specified id.
+ *
+ * Returns null if type is not registered or id is zero.
+ *
+ * # Safety
+ *
+ * `loader` must be null or valid loader.
+ * `type_name` must be null or point to NUL-terminated string.
+ */
+struct ArgosyHandle *argosy_load(struct ArgosyLoader *loader,
+                                 const char *type_name,
+                                 uint64_t id);
+
+/**
+ * Polls handle without blocking.
+ *
+ * Returns [`ARGOSY_PENDING`] while asset is loading,
+ * [`ARGOSY_READY`] when asset is loaded and can be built
+ * and [`ARGOSY_ERROR`] if loading failed.
+ *
+ * # Safety
+ *
+ * `handle` must be null or valid handle.
+ */
+int32_t argosy_handle_poll(struct ArgosyHandle *handle);
+
+/**
+ * Builds loaded asset with `builder` and writes it to `out`.
+ *
+ * Returns [`ARGOSY_READY`] on success,
+ * [`ARGOSY_PENDING`] if asset is not loaded yet
+ * and [`ARGOSY_ERROR`] if loading or building failed.
+ * Built asset must be dropped with its vtable.
+ *
+ * # Safety
+ *
+ * `handle` must be null or valid handle.
+ * `builder` must point to builder of the type the asset was registered with
+ * or be null if that type is zero-sized.
+ * `out` must be null or valid for writes.
+ */
+int32_t argosy_handle_build(struct ArgosyHandle *handle,
+                            void *builder,
+                            struct ArgosyAsset *out);
+
+/**
+ * Takes error message of the failed handle.
+ *
+ * Writes NUL-terminated message into `buf`, truncated to fit `len` bytes,
+ * and returns full length of the message without NUL.
+ * Returns zero if there is no error message.
+ *
+ * # Safety
+ *
+ * `handle` must be null or valid handle.
+ * `buf` must be valid for writes of `len` bytes.
+ */
+size_t argosy_handle_take_error(struct ArgosyHandle *handle,
+                                char *buf,
+                                size_t len);
+
+/**
+ * Destroys handle.
+ *
+ * # Safety
+ *
+ * `handle` must be null or valid handle that is not destroyed yet.
+ */
+void argosy_handle_destroy(struct ArgosyHandle *handle);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ARGOSY_H */
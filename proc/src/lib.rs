@@ -26,15 +26,37 @@ struct Parsed {
     decode_field_errors: proc_macro2::TokenStream,
     build_error: syn::Ident,
     build_field_errors: proc_macro2::TokenStream,
+    decode_error_arms: proc_macro2::TokenStream,
+    build_error_arms: proc_macro2::TokenStream,
     builder_bounds: proc_macro2::TokenStream,
+    field_build_traits: proc_macro2::TokenStream,
     info_fields: proc_macro2::TokenStream,
     info_to_futures_fields: proc_macro2::TokenStream,
     futures_fields: proc_macro2::TokenStream,
     futures_to_decoded_fields: proc_macro2::TokenStream,
     decoded_fields: proc_macro2::TokenStream,
+
+    /// Statements that build fields into local bindings in build order.
     decoded_to_asset_fields: proc_macro2::TokenStream,
+
+    /// Same as `decoded_to_asset_fields`, but each field is built
+    /// with builder locked for that field only.
+    decoded_to_asset_fields_async: proc_macro2::TokenStream,
+
+    /// Fields of the asset initialized from bindings of built fields.
+    built_fields: proc_macro2::TokenStream,
     serde_attributes: Vec<syn::Attribute>,
     name: Option<syn::LitStr>,
+    checked: Option<syn::Ident>,
+    json_only: Option<syn::Ident>,
+    schema_fields: Vec<String>,
+
+    /// First `serde(flatten)` attribute on a field.
+    flattened: Option<syn::Attribute>,
+
+    /// Names of info fields in serialized form.
+    /// `None` if they can't be determined from serde attributes.
+    info_field_names: Option<proc_macro2::TokenStream>,
 }
 
 fn parse(item: proc_macro::TokenStream) -> syn::Result<Parsed> {
@@ -56,6 +78,8 @@ fn parse(item: proc_macro::TokenStream) -> syn::Result<Parsed> {
         .collect::<Vec<_>>();
 
     let mut name_arg = None;
+    let mut checked_arg = None;
+    let mut json_only_arg = None;
 
     for idx in &asset_attributes {
         let attr = &derive_input.attrs[*idx];
@@ -74,9 +98,25 @@ fn parse(item: proc_macro::TokenStream) -> syn::Result<Parsed> {
 
                     Ok(())
                 }
+                i if i == "checked" => {
+                    if !stream.is_empty() {
+                        return Err(syn::Error::new(stream.span(), "Expected end of arguments"));
+                    }
+
+                    checked_arg = Some(i);
+                    Ok(())
+                }
+                i if i == "json_only" => {
+                    if !stream.is_empty() {
+                        return Err(syn::Error::new(stream.span(), "Expected end of arguments"));
+                    }
+
+                    json_only_arg = Some(i);
+                    Ok(())
+                }
                 i => Err(syn::Error::new_spanned(
                     i,
-                    "Unexpected ident. Expected: 'name'",
+                    "Unexpected ident. Expected: 'name', 'checked' or 'json_only'",
                 )),
             }
         })?;
@@ -91,7 +131,10 @@ fn parse(item: proc_macro::TokenStream) -> syn::Result<Parsed> {
 
     let mut decode_field_errors = proc_macro2::TokenStream::new();
     let mut build_field_errors = proc_macro2::TokenStream::new();
+    let mut decode_error_arms = proc_macro2::TokenStream::new();
+    let mut build_error_arms = proc_macro2::TokenStream::new();
     let mut builder_bounds = proc_macro2::TokenStream::new();
+    let mut field_build_traits = proc_macro2::TokenStream::new();
 
     let info = quote::format_ident!("{}Info", derive_input.ident);
     let mut info_fields = proc_macro2::TokenStream::new();
@@ -104,11 +147,20 @@ fn parse(item: proc_macro::TokenStream) -> syn::Result<Parsed> {
     let decoded = quote::format_ident!("{}Decoded", derive_input.ident);
     let mut decoded_fields = proc_macro2::TokenStream::new();
     let mut decoded_to_asset_fields = proc_macro2::TokenStream::new();
+    let mut decoded_to_asset_fields_async = proc_macro2::TokenStream::new();
+    let mut built_fields = proc_macro2::TokenStream::new();
+    let mut ordered_fields = Vec::new();
 
     let decode_error = quote::format_ident!("{}DecodeError", derive_input.ident);
     let build_error = quote::format_ident!("{}BuildError", derive_input.ident);
 
     let mut complex: bool = false;
+    let mut schema_fields = Vec::new();
+    let mut flattened = None;
+    let mut info_field_names = match struct_serde_renames(&derive_input)? {
+        true => None,
+        false => Some(proc_macro2::TokenStream::new()),
+    };
 
     let data_struct = match &derive_input.data {
         syn::Data::Struct(data) => data,
@@ -127,6 +179,11 @@ fn parse(item: proc_macro::TokenStream) -> syn::Result<Parsed> {
     };
 
     for (index, field) in data_struct.fields.iter().enumerate() {
+        let key = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => index.to_string(),
+        };
+
         let asset_attributes = field
             .attrs
             .iter()
@@ -145,12 +202,32 @@ fn parse(item: proc_macro::TokenStream) -> syn::Result<Parsed> {
             .iter()
             .filter(|attr| attr.path.is_ident("serde"));
 
+        if flattened.is_none() {
+            flattened = flatten_attribute(field)?.cloned();
+        }
+
+        // `cfg` attributes must be forwarded to every generated item that mentions the field.
+        // Doc comments are forwarded to generated structs fields.
+        let cfg_attributes = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("cfg"))
+            .collect::<Vec<_>>();
+
+        let doc_attributes = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("doc"))
+            .collect::<Vec<_>>();
+
         let ty = &field.ty;
 
         complex = true;
 
         let mut is_external = false;
         let mut as_type_arg = None;
+        let mut build_after = Vec::new();
+        let mut needs = Vec::new();
 
         for idx in &asset_attributes {
             let attribute = &field.attrs[*idx];
@@ -183,117 +260,328 @@ fn parse(item: proc_macro::TokenStream) -> syn::Result<Parsed> {
 
                         Ok(())
                     }
+                    i if i == "build_after" || i == "needs" => {
+                        let _eq = stream.parse::<syn::Token![=]>()?;
+                        let other = stream.parse::<syn::LitStr>()?;
+
+                        if !stream.is_empty() {
+                            return Err(syn::Error::new(
+                                stream.span(),
+                                "Expected end of arguments",
+                            ));
+                        }
+
+                        if other.value() == key {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "Field can't be built after itself",
+                            ));
+                        }
+
+                        if i == "needs" {
+                            needs.push(other);
+                        } else {
+                            build_after.push(other);
+                        }
+                        Ok(())
+                    }
                     i => Err(syn::Error::new_spanned(
                         i,
-                        "Unexpected ident. Expected: 'external'",
+                        "Unexpected ident. Expected: 'external', 'build_after' or 'needs'",
                     )),
                 }
             })?;
         }
 
-        let as_type = as_type_arg.as_ref().unwrap_or(ty);
+        let external_as = match &as_type_arg {
+            None => ExternalAs::Whole { ty, from: ty },
+            Some(as_type) => ExternalAs::new(ty, as_type)?,
+        };
+        let as_type = external_as.loaded();
 
         let kind = match is_external {
             true => quote::quote!(::argosy::proc_macro::External),
             false => quote::quote!(::argosy::proc_macro::Inlined),
         };
 
+        match (serde_field_names(field)?, &mut info_field_names) {
+            (Some(names), Some(info_field_names)) => {
+                for name in names {
+                    info_field_names.extend(quote::quote!(#(#cfg_attributes)* #name,));
+                }
+            }
+            (None, _) => info_field_names = None,
+            (_, None) => {}
+        }
+
+        schema_fields.push(match &field.ident {
+            Some(ident) => format!("{ident}:{is_external}"),
+            None => format!("{index}:{is_external}"),
+        });
+
         match &field.ident {
             Some(ident) => {
                 let error_variant = quote::format_ident!("{}Error", snake_to_pascal(ident));
                 let decode_error_text = syn::LitStr::new(
-                    &format!("Failed to decode asset field '{ident}'. {{0}}"),
+                    &format!("Failed to decode asset field '{ident}'. {{}}"),
                     ident.span(),
                 );
                 let build_error_text = syn::LitStr::new(
-                    &format!("Failed to build asset field '{ident}'. {{0}}"),
+                    &format!("Failed to build asset field '{ident}'. {{}}"),
                     ident.span(),
                 );
 
                 decode_field_errors.extend(quote::quote!(
-                    #[error(#decode_error_text)]
+                    #(#cfg_attributes)*
                     #error_variant(<#as_type as ::argosy::proc_macro::AssetField<#kind>>::DecodeError),
                 ));
+                decode_error_arms.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    Self::#error_variant(ref err) => ::core::write!(f, #decode_error_text, err),
+                ));
                 build_field_errors.extend(quote::quote!(
-                    #[error(#build_error_text)]
+                    #(#cfg_attributes)*
                     #error_variant(<#as_type as ::argosy::proc_macro::AssetField<#kind>>::BuildError),
                 ));
+                build_error_arms.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    Self::#error_variant(ref err) => ::core::write!(f, #build_error_text, err),
+                ));
 
+                let field_build = quote::format_ident!(
+                    "{}{}FieldBuild",
+                    derive_input.ident,
+                    snake_to_pascal(ident)
+                );
+                let context = match needs.is_empty() {
+                    true => None,
+                    false => Some(quote::format_ident!(
+                        "{}{}Context",
+                        derive_input.ident,
+                        snake_to_pascal(ident)
+                    )),
+                };
+                field_build_traits.extend(field_build_trait(
+                    &derive_input.ident,
+                    &field_build,
+                    &ident.to_string(),
+                    &kind,
+                    &as_type,
+                    context.as_ref(),
+                    &cfg_attributes,
+                )?);
+                let asset = &derive_input.ident;
                 builder_bounds.extend(quote::quote!(
-                    for<'build> ::argosy::proc_macro::FieldBuilder<'build, BuilderGenericParameter>: ::argosy::proc_macro::AssetFieldBuild<#kind, #as_type>,
+                    #asset: #field_build<BuilderGenericParameter>,
                 ));
                 info_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    #(#doc_attributes)*
                     #(#serde_attributes)*
                     pub #ident: <#as_type as ::argosy::proc_macro::AssetField<#kind>>::Info,
                 ));
                 futures_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    #(#doc_attributes)*
                     pub #ident: <#as_type as ::argosy::proc_macro::AssetField<#kind>>::Fut,
                 ));
                 decoded_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    #(#doc_attributes)*
                     pub #ident: <#as_type as ::argosy::proc_macro::AssetField<#kind>>::Decoded,
                 ));
                 info_to_futures_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
                     #ident: <#as_type as ::argosy::proc_macro::AssetField<#kind>>::decode(info.#ident, loader),
                 ));
                 futures_to_decoded_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
                     #ident: futures.#ident.await.map_err(|err| #decode_error::#error_variant(err))?,
                 ));
-                decoded_to_asset_fields.extend(quote::quote!(
-                    #ident: <#ty as ::argosy::proc_macro::From<#as_type>>::from(
-                        <_ as ::argosy::proc_macro::AssetFieldBuild<#kind, #as_type>>::build(::argosy::proc_macro::FieldBuilder(builder), decoded.#ident)
-                            .map_err(|err| #build_error::#error_variant(err))?
-                    ),
+                let context_arg = match &context {
+                    None => proc_macro2::TokenStream::new(),
+                    Some(context) => {
+                        let mut context_fields = proc_macro2::TokenStream::new();
+                        let mut context_values = proc_macro2::TokenStream::new();
+
+                        for other in &needs {
+                            let Some(needed) = data_struct.fields.iter().find(|field| {
+                                field
+                                    .ident
+                                    .as_ref()
+                                    .is_some_and(|ident| *ident == other.value())
+                            }) else {
+                                return Err(syn::Error::new_spanned(
+                                    other,
+                                    format!("Unknown field '{}'", other.value()),
+                                ));
+                            };
+                            let needed_ident = needed.ident.as_ref().unwrap();
+                            let needed_ty = &needed.ty;
+                            let needed_binding = built_binding(&other.value());
+
+                            context_fields.extend(quote::quote!(
+                                pub #needed_ident: &'a #needed_ty,
+                            ));
+                            context_values.extend(quote::quote!(
+                                #needed_ident: &#needed_binding,
+                            ));
+                        }
+
+                        let context_doc = format!(
+                            "Fields of `{asset}` that are built before field `{ident}` and passed to its builder."
+                        );
+                        field_build_traits.extend(quote::quote!(
+                            #(#cfg_attributes)*
+                            #[doc = #context_doc]
+                            #[derive(::core::clone::Clone, ::core::marker::Copy)]
+                            pub struct #context<'a> { #context_fields }
+                        ));
+
+                        quote::quote!(#context { #context_values },)
+                    }
+                };
+
+                let built = external_as.convert(quote::quote!(
+                    <#asset as #field_build<BuilderGenericParameter>>::build_field(builder, #context_arg decoded.#ident)
+                        .map_err(|err| #build_error::#error_variant(err))?
+                ));
+                let binding = built_binding(&key);
+                built_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    #ident: #binding,
                 ));
+                ordered_fields.push(OrderedField {
+                    key,
+                    after: build_after.into_iter().chain(needs).collect(),
+                    build: quote::quote!(
+                        #(#cfg_attributes)*
+                        let #binding = #built;
+                    ),
+                    build_async: quote::quote!(
+                        #(#cfg_attributes)*
+                        let #binding = {
+                            let value = {
+                                let mut guard = shared.lock().await;
+                                let builder = &mut *guard;
+                                #built
+                            };
+                            ::argosy::proc_macro::yield_now().await;
+                            value
+                        };
+                    ),
+                });
             }
             None => {
                 let error_variant = syn::Ident::new(&format!("Field{}Error", index), field.span());
                 let decode_error_text = syn::LitStr::new(
-                    &format!("Failed to decode asset field '{index}'. {{0}}"),
+                    &format!("Failed to decode asset field '{index}'. {{}}"),
                     field.span(),
                 );
                 let build_error_text = syn::LitStr::new(
-                    &format!("Failed to load asset field '{index}'. {{0}}"),
+                    &format!("Failed to load asset field '{index}'. {{}}"),
                     field.span(),
                 );
 
                 decode_field_errors.extend(quote::quote!(
-                    #[error(#decode_error_text)]
+                    #(#cfg_attributes)*
                     #error_variant(<#as_type as ::argosy::proc_macro::AssetField<#kind>>::DecodeError),
                 ));
+                decode_error_arms.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    Self::#error_variant(ref err) => ::core::write!(f, #decode_error_text, err),
+                ));
                 build_field_errors.extend(quote::quote!(
-                    #[error(#build_error_text)]
+                    #(#cfg_attributes)*
                     #error_variant(<#as_type as ::argosy::proc_macro::AssetField<#kind>>::BuildError),
                 ));
+                build_error_arms.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    Self::#error_variant(ref err) => ::core::write!(f, #build_error_text, err),
+                ));
 
+                let field_build = quote::format_ident!("{}Field{}Build", derive_input.ident, index);
+                if let Some(other) = needs.first() {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "`asset(needs = \"<field>\")` is supported only for structs with named fields",
+                    ));
+                }
+                field_build_traits.extend(field_build_trait(
+                    &derive_input.ident,
+                    &field_build,
+                    &index.to_string(),
+                    &kind,
+                    &as_type,
+                    None,
+                    &cfg_attributes,
+                )?);
+                let asset = &derive_input.ident;
                 builder_bounds.extend(quote::quote!(
-                    for<'build> ::argosy::proc_macro::FieldBuilder<'build, BuilderGenericParameter>: ::argosy::proc_macro::AssetFieldBuild<#kind, #as_type>,
+                    #asset: #field_build<BuilderGenericParameter>,
                 ));
                 info_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    #(#doc_attributes)*
                     #(#serde_attributes)*
                     pub <#as_type as ::argosy::proc_macro::AssetField<#kind>>::Info,
                 ));
                 futures_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    #(#doc_attributes)*
                     pub <#as_type as ::argosy::proc_macro::AssetField<#kind>>::Fut,
                 ));
                 decoded_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    #(#doc_attributes)*
                     pub <#as_type as ::argosy::proc_macro::AssetField<#kind>>::Decoded,
                 ));
                 info_to_futures_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
                     <#as_type as ::argosy::proc_macro::AssetField<#kind>>::decode(info.#index, loader),
                 ));
                 futures_to_decoded_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
                     futures.#index.await.map_err(|err| #decode_error::#error_variant(err))?,
                 ));
-                decoded_to_asset_fields.extend(quote::quote!(
-                    <#ty as ::argosy::proc_macro::From<#as_type>>::from(
-                        <_ as ::argosy::proc_macro::AssetFieldBuild<#kind, #as_type>>::build(::argosy::proc_macro::FieldBuilder(builder), decoded.#index)
-                            .map_err(|err| #build_error::#error_variant(err))?
-                    ),
+                let built = external_as.convert(quote::quote!(
+                    <#asset as #field_build<BuilderGenericParameter>>::build_field(builder, decoded.#index)
+                        .map_err(|err| #build_error::#error_variant(err))?
                 ));
+                let binding = built_binding(&key);
+                built_fields.extend(quote::quote!(
+                    #(#cfg_attributes)*
+                    #binding,
+                ));
+                ordered_fields.push(OrderedField {
+                    key,
+                    after: build_after,
+                    build: quote::quote!(
+                        #(#cfg_attributes)*
+                        let #binding = #built;
+                    ),
+                    build_async: quote::quote!(
+                        #(#cfg_attributes)*
+                        let #binding = {
+                            let value = {
+                                let mut guard = shared.lock().await;
+                                let builder = &mut *guard;
+                                #built
+                            };
+                            ::argosy::proc_macro::yield_now().await;
+                            value
+                        };
+                    ),
+                });
             }
         }
     }
 
+    for index in build_order(&ordered_fields)? {
+        decoded_to_asset_fields.extend(ordered_fields[index].build.clone());
+        decoded_to_asset_fields_async.extend(ordered_fields[index].build_async.clone());
+    }
+
     Ok(Parsed {
         complex,
         derive_input,
@@ -304,15 +592,25 @@ fn parse(item: proc_macro::TokenStream) -> syn::Result<Parsed> {
         decode_field_errors,
         build_error,
         build_field_errors,
+        decode_error_arms,
+        build_error_arms,
         builder_bounds,
+        field_build_traits,
         info_fields,
         info_to_futures_fields,
         futures_fields,
         futures_to_decoded_fields,
         decoded_fields,
         decoded_to_asset_fields,
+        decoded_to_asset_fields_async,
+        built_fields,
         serde_attributes,
         name: name_arg,
+        checked: checked_arg,
+        json_only: json_only_arg,
+        schema_fields,
+        flattened,
+        info_field_names,
     })
 }
 
@@ -327,22 +625,90 @@ fn asset_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
         build_error,
         decode_field_errors,
         build_field_errors,
+        decode_error_arms,
+        build_error_arms,
         builder_bounds,
+        field_build_traits,
         info_fields,
         info_to_futures_fields,
         futures_fields,
         futures_to_decoded_fields,
         decoded_fields,
         decoded_to_asset_fields,
+        decoded_to_asset_fields_async,
+        built_fields,
         serde_attributes,
         name,
+        checked,
+        json_only,
+        schema_fields,
+        flattened,
+        info_field_names,
     } = parsed;
 
+    // Bincode can't deserialize flattened fields.
+    // Trivial assets are decoded without loader, others take format and strictness from it.
+    let (deserialize_info, decode_info) = match (json_only, flattened) {
+        (Some(_), _) => (
+            quote::quote!(::argosy::proc_macro::deserialize_info_json_checked),
+            quote::quote!(::argosy::proc_macro::decode_info_json_checked),
+        ),
+        (None, Some(flattened)) => {
+            return Err(syn::Error::new_spanned(
+                flattened,
+                "Flattened fields can't be deserialized from bincode. Add `#[asset(json_only)]` attribute to the struct to decode it only from JSON",
+            ))
+        }
+        (None, None) => (
+            quote::quote!(::argosy::proc_macro::deserialize_info_checked),
+            quote::quote!(::argosy::proc_macro::decode_info_checked),
+        ),
+    };
+
     let name = match name {
         None => derive_input.ident.to_string(),
         Some(name) => name.value(),
     };
 
+    let mut info_fields = info_fields;
+    let mut info_field_names = info_field_names;
+    let mut check_info = proc_macro2::TokenStream::new();
+    let mut checked_impl = proc_macro2::TokenStream::new();
+
+    if let Some(checked) = checked {
+        if !matches!(
+            derive_input.data,
+            syn::Data::Struct(syn::DataStruct {
+                fields: syn::Fields::Named(_),
+                ..
+            })
+        ) {
+            return Err(syn::Error::new_spanned(
+                checked,
+                "`asset(checked)` attribute is supported only for structs with named fields",
+            ));
+        }
+
+        let schema = schema_hash(&name, &schema_fields);
+        let ty = &derive_input.ident;
+
+        info_fields.extend(quote::quote!(
+            #[serde(rename = "__argosy_schema", default)]
+            pub __argosy_schema: ::argosy::proc_macro::SchemaStamp,
+        ));
+        if let Some(info_field_names) = &mut info_field_names {
+            info_field_names.extend(quote::quote!("__argosy_schema",));
+        }
+        check_info = quote::quote!(
+            ::argosy::proc_macro::check_schema(#name, #schema, info.__argosy_schema)?;
+        );
+        checked_impl = quote::quote!(
+            impl ::argosy::proc_macro::CheckedAsset for #ty {
+                const SCHEMA: u64 = #schema;
+            }
+        );
+    }
+
     let data_struct = match &derive_input.data {
         syn::Data::Struct(data) => data,
         _ => unreachable!(),
@@ -350,6 +716,33 @@ fn asset_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
 
     let ty = &derive_input.ident;
 
+    let info_field_names = match info_field_names {
+        None => quote::quote!(::argosy::proc_macro::Option::None),
+        Some(names) => quote::quote!(::argosy::proc_macro::Option::Some(&[#names])),
+    };
+
+    // Builds fields one by one, releasing the builder between them.
+    let async_build_impl = if cfg!(feature = "async-build") {
+        quote::quote! {
+            impl<BuilderGenericParameter> ::argosy::proc_macro::AsyncAssetBuild<BuilderGenericParameter> for #ty
+            where
+                BuilderGenericParameter: ::core::marker::Send,
+                #builder_bounds
+            {
+                fn build_async(shared: &::argosy::proc_macro::AsyncShared<BuilderGenericParameter>, decoded: #decoded) -> ::argosy::proc_macro::BoxFuture<'_, ::argosy::proc_macro::Result<#ty, #build_error>> {
+                    ::argosy::proc_macro::Box::pin(async move {
+                        #decoded_to_asset_fields_async
+                        ::argosy::proc_macro::Ok::<#ty, #build_error>(#ty {
+                            #built_fields
+                        })
+                    })
+                }
+            }
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
     let tokens = match data_struct.fields {
         syn::Fields::Unit => quote::quote! {
             #[derive(::argosy::proc_macro::Deserialize)]
@@ -359,11 +752,13 @@ fn asset_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
             impl ::argosy::proc_macro::TrivialAsset for #ty {
                 type Error = ::argosy::proc_macro::Infallible;
 
-                fn name() -> &'static str {
-                    #name
+                fn asset_name() -> ::argosy::proc_macro::AssetName {
+                    ::argosy::proc_macro::AssetName::new(#name)
                 }
 
                 fn decode(bytes: ::argosy::proc_macro::Box<[u8]>) -> Result<Self, ::argosy::proc_macro::Infallible> {
+                    // Unit info is encoded as zero bytes of bincode or JSON `null`,
+                    // so payload is ignored instead of checked.
                     ::argosy::proc_macro::Ok(#ty)
                 }
             }
@@ -388,8 +783,15 @@ fn asset_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
                 }
             }
         },
-        syn::Fields::Unnamed(_) => todo!("Not yet implemented"),
+        syn::Fields::Unnamed(ref fields) => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                "Tuple structs are not supported by derive(Asset) macro",
+            ))
+        }
         syn::Fields::Named(_) if complex => quote::quote! {
+            #field_build_traits
+
             #[derive(::argosy::proc_macro::Deserialize)]
             #(#serde_attributes)*
             pub struct #info { #info_fields }
@@ -398,33 +800,66 @@ fn asset_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
 
             pub struct #decoded { #decoded_fields }
 
-            #[derive(::argosy::proc_macro::Debug, ::argosy::proc_macro::Error)]
+            #[derive(::argosy::proc_macro::Debug)]
             pub enum #decode_error {
-                #[error("Failed to deserialize asset info. {0:#}")]
-                Info(#[source]::argosy::proc_macro::DecodeError),
+                Info(::argosy::proc_macro::DecodeError),
 
                 #decode_field_errors
             }
 
-            #[derive(::argosy::proc_macro::Debug, ::argosy::proc_macro::Error)]
+            impl ::core::fmt::Display for #decode_error {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match *self {
+                        Self::Info(ref err) => ::core::write!(f, "Failed to deserialize asset info. {:#}", err),
+                        #decode_error_arms
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #decode_error {
+                fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                    match *self {
+                        Self::Info(ref err) => ::core::option::Option::Some(err),
+                        #[allow(unreachable_patterns)]
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            }
+
+            #[derive(::argosy::proc_macro::Debug)]
             pub enum #build_error {
                 #build_field_errors
             }
 
+            impl ::core::fmt::Display for #build_error {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match *self {
+                        #build_error_arms
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #build_error {}
+
             impl ::argosy::proc_macro::Asset for #ty {
                 type BuildError = #build_error;
                 type DecodeError = #decode_error;
                 type Decoded = #decoded;
                 type Fut = ::argosy::proc_macro::BoxFuture<'static, ::argosy::proc_macro::Result<#decoded, #decode_error>>;
 
-                fn name() -> &'static str {
-                    #name
+                fn asset_name() -> ::argosy::proc_macro::AssetName {
+                    ::argosy::proc_macro::AssetName::new(#name)
                 }
 
                 fn decode(bytes: ::argosy::proc_macro::Box<[u8]>, loader: &::argosy::proc_macro::Loader) -> Self::Fut {
                     use ::argosy::proc_macro::{DecodeError, Box, Result, Ok, Err};
 
-                    let result: Result<#info, #decode_error> = ::argosy::proc_macro::deserialize_info(&*bytes).map_err(#decode_error::Info);
+                    let result: Result<#info, #decode_error> = #decode_info(&*bytes, #info_field_names, loader)
+                        .and_then(|info: #info| {
+                            #check_info
+                            Ok(info)
+                        })
+                        .map_err(#decode_error::Info);
 
                     match result {
                         Ok(info) => {
@@ -445,12 +880,15 @@ fn asset_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
                 #builder_bounds
             {
                 fn build(builder: &mut BuilderGenericParameter, decoded: #decoded) -> ::argosy::proc_macro::Result<#ty, #build_error> {
+                    #decoded_to_asset_fields
                     ::argosy::proc_macro::Ok(#ty {
-                        #decoded_to_asset_fields
+                        #built_fields
                     })
                 }
             }
 
+            #async_build_impl
+
             impl ::argosy::proc_macro::AssetField<::argosy::proc_macro::Inlined> for #ty {
                 type BuildError = #build_error;
                 type DecodeError = #decode_error;
@@ -479,8 +917,9 @@ fn asset_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
             {
                 fn build(self, decoded: #decoded) -> ::argosy::proc_macro::Result<#ty, #build_error> {
                     let builder = self.0;
+                    #decoded_to_asset_fields
                     ::argosy::proc_macro::Ok(#ty {
-                        #decoded_to_asset_fields
+                        #built_fields
                     })
                 }
             }
@@ -493,17 +932,20 @@ fn asset_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
             impl ::argosy::proc_macro::TrivialAsset for #ty {
                 type Error = ::argosy::proc_macro::DecodeError;
 
-                fn name() -> &'static str {
-                    #name
+                fn asset_name() -> ::argosy::proc_macro::AssetName {
+                    ::argosy::proc_macro::AssetName::new(#name)
                 }
 
                 fn decode(bytes: ::argosy::proc_macro::Box<[u8]>) -> ::argosy::proc_macro::Result<Self, ::argosy::proc_macro::DecodeError> {
                     use ::argosy::proc_macro::{Ok, Err};
 
-                    let decoded: #info = ::argosy::proc_macro::deserialize_info(&*bytes)?;
+                    let info: #info = #deserialize_info(&*bytes, #info_field_names)?;
+                    #check_info
+                    let decoded = info;
 
+                    #decoded_to_asset_fields
                     Ok(#ty {
-                        #decoded_to_asset_fields
+                        #built_fields
                     })
                 }
             }
@@ -520,8 +962,9 @@ fn asset_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
 
                     let decoded = info;
 
+                    #decoded_to_asset_fields
                     ready(Ok(#ty {
-                        #decoded_to_asset_fields
+                        #built_fields
                     }))
                 }
             }
@@ -534,7 +977,10 @@ fn asset_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
         },
     };
 
-    Ok(tokens)
+    Ok(quote::quote! {
+        #tokens
+        #checked_impl
+    })
 }
 
 fn asset_field_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
@@ -548,15 +994,25 @@ fn asset_field_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
         build_error,
         decode_field_errors,
         build_field_errors,
+        decode_error_arms,
+        build_error_arms,
         builder_bounds,
+        field_build_traits,
         info_fields,
         info_to_futures_fields,
         futures_fields,
         futures_to_decoded_fields,
         decoded_fields,
         decoded_to_asset_fields,
+        decoded_to_asset_fields_async: _,
+        built_fields,
         serde_attributes,
         name,
+        checked,
+        json_only,
+        schema_fields: _,
+        flattened: _,
+        info_field_names: _,
     } = parsed;
 
     if let Some(name) = name {
@@ -566,6 +1022,20 @@ fn asset_field_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
         ));
     };
 
+    if let Some(checked) = checked {
+        return Err(syn::Error::new_spanned(
+            checked,
+            "`derive(AssetField)` does not accept `asset(checked)` attribute",
+        ));
+    };
+
+    if let Some(json_only) = json_only {
+        return Err(syn::Error::new_spanned(
+            json_only,
+            "`derive(AssetField)` does not accept `asset(json_only)` attribute",
+        ));
+    };
+
     let ty = &derive_input.ident;
 
     let data_struct = match &derive_input.data {
@@ -600,24 +1070,51 @@ fn asset_field_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
             }
         },
 
-        syn::Fields::Unnamed(_) => todo!("Not yet implemented"),
+        syn::Fields::Unnamed(ref fields) => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                "Tuple structs are not supported by derive(AssetField) macro",
+            ))
+        }
         syn::Fields::Named(_) if complex => quote::quote! {
+            #field_build_traits
+
             #[derive(::argosy::proc_macro::Serialize, ::argosy::proc_macro::Deserialize)]
             #(#serde_attributes)*
             pub struct #info { #info_fields }
 
             pub struct #decoded { #decoded_fields }
 
-            #[derive(::argosy::proc_macro::Debug, ::argosy::proc_macro::Error)]
+            #[derive(::argosy::proc_macro::Debug)]
             pub enum #decode_error {
                 #decode_field_errors
             }
 
-            #[derive(::argosy::proc_macro::Debug, ::argosy::proc_macro::Error)]
+            impl ::core::fmt::Display for #decode_error {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match *self {
+                        #decode_error_arms
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #decode_error {}
+
+            #[derive(::argosy::proc_macro::Debug)]
             pub enum #build_error {
                 #build_field_errors
             }
 
+            impl ::core::fmt::Display for #build_error {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match *self {
+                        #build_error_arms
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #build_error {}
+
             impl ::argosy::proc_macro::AssetField<::argosy::proc_macro::Inlined> for #ty {
                 type BuildError = #build_error;
                 type DecodeError = #decode_error;
@@ -646,8 +1143,9 @@ fn asset_field_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
             {
                 fn build(self, decoded: #decoded) -> ::argosy::proc_macro::Result<#ty, #build_error> {
                     let builder = self.0;
+                    #decoded_to_asset_fields
                     ::argosy::proc_macro::Ok(#ty {
-                        #decoded_to_asset_fields
+                        #built_fields
                     })
                 }
             }
@@ -669,8 +1167,9 @@ fn asset_field_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
 
                     let decoded = info;
 
+                    #decoded_to_asset_fields
                     ready(Ok(#ty {
-                        #decoded_to_asset_fields
+                        #built_fields
                     }))
                 }
             }
@@ -686,6 +1185,332 @@ fn asset_field_impl(parsed: Parsed) -> syn::Result<proc_macro2::TokenStream> {
     Ok(tokens)
 }
 
+/// Computes stable hash of the asset schema.
+/// Uses FNV-1a to not depend on compiler version.
+/// Returns `true` if struct-level serde attributes change field names.
+fn struct_serde_renames(derive_input: &syn::DeriveInput) -> syn::Result<bool> {
+    for attr in &derive_input.attrs {
+        if !attr.path.is_ident("serde") {
+            continue;
+        }
+
+        if let syn::Meta::List(list) = attr.parse_meta()? {
+            for nested in &list.nested {
+                let path = match nested {
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(meta)) => &meta.path,
+                    syn::NestedMeta::Meta(syn::Meta::List(meta)) => &meta.path,
+                    _ => continue,
+                };
+
+                if path.is_ident("rename_all") || path.is_ident("tag") {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Returns `serde` attribute that flattens the field.
+fn flatten_attribute(field: &syn::Field) -> syn::Result<Option<&syn::Attribute>> {
+    for attr in &field.attrs {
+        if !attr.path.is_ident("serde") {
+            continue;
+        }
+
+        if let syn::Meta::List(list) = attr.parse_meta()? {
+            for nested in &list.nested {
+                if let syn::NestedMeta::Meta(syn::Meta::Path(path)) = nested {
+                    if path.is_ident("flatten") {
+                        return Ok(Some(attr));
+                    }
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Returns names field is deserialized from according to its serde attributes.
+/// Returns `None` if names can't be determined, e.g. for flattened fields.
+fn serde_field_names(field: &syn::Field) -> syn::Result<Option<Vec<String>>> {
+    let Some(ident) = &field.ident else {
+        return Ok(None);
+    };
+
+    let mut name = ident.to_string();
+    let mut aliases = Vec::new();
+
+    for attr in &field.attrs {
+        if !attr.path.is_ident("serde") {
+            continue;
+        }
+
+        let syn::Meta::List(list) = attr.parse_meta()? else {
+            continue;
+        };
+
+        for nested in &list.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) => {
+                    if path.is_ident("flatten") {
+                        return Ok(None);
+                    }
+                    if path.is_ident("skip") || path.is_ident("skip_deserializing") {
+                        return Ok(Some(Vec::new()));
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(meta)) => {
+                    if let syn::Lit::Str(lit) = &meta.lit {
+                        if meta.path.is_ident("rename") {
+                            name = lit.value();
+                        } else if meta.path.is_ident("alias") {
+                            aliases.push(lit.value());
+                        }
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(meta)) if meta.path.is_ident("rename") => {
+                    for nested in &meta.nested {
+                        if let syn::NestedMeta::Meta(syn::Meta::NameValue(meta)) = nested {
+                            if let (true, syn::Lit::Str(lit)) =
+                                (meta.path.is_ident("deserialize"), &meta.lit)
+                            {
+                                name = lit.value();
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    aliases.insert(0, name);
+    Ok(Some(aliases))
+}
+
+fn schema_hash(name: &str, fields: &[String]) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET;
+    for part in std::iter::once(name).chain(fields.iter().map(String::as_str)) {
+        for byte in part.bytes().chain(Some(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+/// Generates helper trait that builds the field with the builder.
+/// It is named after the field, so that unsatisfied builder bounds point at the field.
+///
+/// Fields with `asset(needs = "<field>")` attributes are built with `context`
+/// that references fields they need.
+/// Generates helper trait that bounds builder of the field.
+///
+/// Predicates of where-clauses can't have `cfg` attributes,
+/// so when the field is disabled the helper trait is implemented unconditionally
+/// and the bound on it is satisfied.
+fn field_build_trait(
+    asset: &syn::Ident,
+    helper: &syn::Ident,
+    field: &str,
+    kind: &proc_macro2::TokenStream,
+    as_type: &proc_macro2::TokenStream,
+    context: Option<&syn::Ident>,
+    cfg_attributes: &[&syn::Attribute],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let tokens =
+        field_build_trait_items(asset, helper, field, kind, as_type, context, cfg_attributes);
+    if cfg_attributes.is_empty() {
+        return Ok(tokens);
+    }
+
+    let predicates = cfg_attributes
+        .iter()
+        .map(|attr| attr.parse_args::<proc_macro2::TokenStream>())
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote::quote! {
+        #tokens
+
+        #[cfg(not(all(#(#predicates),*)))]
+        #[doc(hidden)]
+        pub trait #helper<B> {}
+
+        #[cfg(not(all(#(#predicates),*)))]
+        impl<B> #helper<B> for #asset {}
+    })
+}
+
+fn field_build_trait_items(
+    asset: &syn::Ident,
+    helper: &syn::Ident,
+    field: &str,
+    kind: &proc_macro2::TokenStream,
+    as_type: &proc_macro2::TokenStream,
+    context: Option<&syn::Ident>,
+    cfg_attributes: &[&syn::Attribute],
+) -> proc_macro2::TokenStream {
+    let message = format!("field `{field}` of `{asset}` cannot be built with builder `{{B}}`");
+    let label = format!("field `{field}` of `{asset}` requires builder `{{B}}`");
+
+    let Some(context) = context else {
+        let note = format!(
+            "implement `AssetBuild<{{B}}>` for the type of field `{field}` if it is an asset, or `AssetFieldBuild` for `FieldBuilder<'_, {{B}}>`"
+        );
+
+        return quote::quote! {
+            #(#cfg_attributes)*
+            #[doc(hidden)]
+            #[diagnostic::on_unimplemented(message = #message, label = #label, note = #note)]
+            pub trait #helper<B> {
+                fn build_field(
+                    builder: &mut B,
+                    decoded: <#as_type as ::argosy::proc_macro::AssetField<#kind>>::Decoded,
+                ) -> ::argosy::proc_macro::Result<#as_type, <#as_type as ::argosy::proc_macro::AssetField<#kind>>::BuildError>;
+            }
+
+            #(#cfg_attributes)*
+            impl<B> #helper<B> for #asset
+            where
+                for<'build> ::argosy::proc_macro::FieldBuilder<'build, B>: ::argosy::proc_macro::AssetFieldBuild<#kind, #as_type>,
+            {
+                #[inline]
+                fn build_field(
+                    builder: &mut B,
+                    decoded: <#as_type as ::argosy::proc_macro::AssetField<#kind>>::Decoded,
+                ) -> ::argosy::proc_macro::Result<#as_type, <#as_type as ::argosy::proc_macro::AssetField<#kind>>::BuildError> {
+                    <::argosy::proc_macro::FieldBuilder<'_, B> as ::argosy::proc_macro::AssetFieldBuild<#kind, #as_type>>::build(::argosy::proc_macro::FieldBuilder(builder, ()), decoded)
+                }
+            }
+        };
+    };
+
+    let note = format!(
+        "implement `AssetFieldBuild` for `FieldBuilder<'_, {{B}}, {context}<'_>>` to build field `{field}` with fields it needs"
+    );
+
+    quote::quote! {
+        #(#cfg_attributes)*
+        #[doc(hidden)]
+        #[diagnostic::on_unimplemented(message = #message, label = #label, note = #note)]
+        pub trait #helper<B> {
+            fn build_field(
+                builder: &mut B,
+                context: #context<'_>,
+                decoded: <#as_type as ::argosy::proc_macro::AssetField<#kind>>::Decoded,
+            ) -> ::argosy::proc_macro::Result<#as_type, <#as_type as ::argosy::proc_macro::AssetField<#kind>>::BuildError>;
+        }
+
+        #(#cfg_attributes)*
+        impl<B> #helper<B> for #asset
+        where
+            for<'build, 'context> ::argosy::proc_macro::FieldBuilder<'build, B, #context<'context>>: ::argosy::proc_macro::AssetFieldBuild<#kind, #as_type>,
+        {
+            #[inline]
+            fn build_field(
+                builder: &mut B,
+                context: #context<'_>,
+                decoded: <#as_type as ::argosy::proc_macro::AssetField<#kind>>::Decoded,
+            ) -> ::argosy::proc_macro::Result<#as_type, <#as_type as ::argosy::proc_macro::AssetField<#kind>>::BuildError> {
+                <::argosy::proc_macro::FieldBuilder<'_, B, #context<'_>> as ::argosy::proc_macro::AssetFieldBuild<#kind, #as_type>>::build(::argosy::proc_macro::FieldBuilder(builder, context), decoded)
+            }
+        }
+    }
+}
+
+/// Field of derived asset to build in order.
+struct OrderedField {
+    /// Field name, or index for tuple structs.
+    key: String,
+
+    /// Fields that must be built before this one.
+    after: Vec<syn::LitStr>,
+
+    /// Statement that builds the field into its binding.
+    build: proc_macro2::TokenStream,
+
+    /// Same as `build`, but locks shared builder for the field.
+    build_async: proc_macro2::TokenStream,
+}
+
+/// Returns name of the local binding that holds built field.
+fn built_binding(key: &str) -> syn::Ident {
+    quote::format_ident!("__argosy_built_{}", key)
+}
+
+/// Sorts fields topologically by `build_after` and `needs` attributes.
+///
+/// Fields are built in declaration order unless attributes require otherwise.
+/// Among fields that can be built next, the one declared first is built first.
+fn build_order(fields: &[OrderedField]) -> syn::Result<Vec<usize>> {
+    let mut after = Vec::with_capacity(fields.len());
+    for field in fields {
+        let mut deps = Vec::with_capacity(field.after.len());
+        for other in &field.after {
+            match fields.iter().position(|f| f.key == other.value()) {
+                None => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        format!("Unknown field '{}'", other.value()),
+                    ))
+                }
+                Some(dep) => deps.push(dep),
+            }
+        }
+        after.push(deps);
+    }
+
+    let mut built = vec![false; fields.len()];
+    let mut order = Vec::with_capacity(fields.len());
+
+    while order.len() < fields.len() {
+        let next = (0..fields.len())
+            .find(|&index| !built[index] && after[index].iter().all(|&dep| built[dep]));
+
+        match next {
+            Some(index) => {
+                built[index] = true;
+                order.push(index);
+            }
+            None => {
+                // Every remaining field waits for another one,
+                // follow them until some field repeats to find the cycle.
+                let mut path = Vec::new();
+                let mut index = (0..fields.len()).find(|&index| !built[index]).unwrap();
+
+                while !path.iter().any(|&(visited, _)| visited == index) {
+                    let position = after[index].iter().position(|&dep| !built[dep]).unwrap();
+                    path.push((index, position));
+                    index = after[index][position];
+                }
+
+                let start = path
+                    .iter()
+                    .position(|&(visited, _)| visited == index)
+                    .unwrap();
+                let cycle = path[start..]
+                    .iter()
+                    .map(|&(visited, _)| fields[visited].key.as_str())
+                    .chain(Some(fields[index].key.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("' -> '");
+
+                let (last, position) = *path.last().unwrap();
+                return Err(syn::Error::new_spanned(
+                    &fields[last].after[position],
+                    format!("Fields are built after each other in a cycle: '{cycle}'"),
+                ));
+            }
+        }
+    }
+
+    Ok(order)
+}
+
 fn snake_to_pascal(input: &syn::Ident) -> syn::Ident {
     let mut result = String::new();
     let mut upper = true;
@@ -708,3 +1533,182 @@ fn snake_to_pascal(input: &syn::Ident) -> syn::Ident {
     }
     syn::Ident::new(&result, input.span())
 }
+
+/// Wrapper of the field type that `external(as T)` is applied inside.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Wrapper {
+    Option,
+    Vec,
+    ArcSlice,
+}
+
+/// Containers that are not supported as wrappers of `external(as T)` fields.
+const UNSUPPORTED_WRAPPERS: &[&str] = &[
+    "Arc", "Box", "Rc", "VecDeque", "HashMap", "BTreeMap", "HashSet", "BTreeSet",
+];
+
+/// Returns single generic type argument of the last path segment.
+fn single_type_arg(segment: &syn::PathSegment) -> Option<&syn::Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first()? {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Splits supported wrapper type into wrapper and element type.
+fn split_wrapper(ty: &syn::Type) -> Option<(Wrapper, &syn::Type)> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+
+    let segment = path.path.segments.last()?;
+    let arg = single_type_arg(segment)?;
+
+    if segment.ident == "Option" {
+        Some((Wrapper::Option, arg))
+    } else if segment.ident == "Vec" {
+        Some((Wrapper::Vec, arg))
+    } else if segment.ident == "Arc" {
+        match arg {
+            syn::Type::Slice(slice) => Some((Wrapper::ArcSlice, &slice.elem)),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Checks if type is a container that `external(as T)` can't be applied inside.
+fn is_unsupported_wrapper(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path.path.segments.last().is_some_and(|segment| {
+            !segment.arguments.is_empty()
+                && UNSUPPORTED_WRAPPERS
+                    .iter()
+                    .any(|name| segment.ident == name)
+        }),
+        syn::Type::Array(_) | syn::Type::Slice(_) | syn::Type::Reference(_) => true,
+        syn::Type::Tuple(tuple) => !tuple.elems.is_empty(),
+        syn::Type::Paren(paren) => is_unsupported_wrapper(&paren.elem),
+        syn::Type::Group(group) => is_unsupported_wrapper(&group.elem),
+        _ => false,
+    }
+}
+
+/// Conversion of the built value of `external(as T)` field into the field type.
+enum ExternalAs<'a> {
+    /// Field is converted as a whole.
+    Whole {
+        ty: &'a syn::Type,
+        from: &'a syn::Type,
+    },
+
+    /// Each element of the wrapper is converted.
+    Elements {
+        wrapper: Wrapper,
+        ty: &'a syn::Type,
+        elem: &'a syn::Type,
+        from: &'a syn::Type,
+    },
+}
+
+impl<'a> ExternalAs<'a> {
+    /// Resolves conversion for the field of type `ty` with `external(as from)` attribute.
+    ///
+    /// For `Option<_>`, `Vec<_>` and `Arc<[_]>` fields `from` is applied inside the wrapper.
+    /// `from` may be spelled either as element type or wrapped the same way.
+    fn new(ty: &'a syn::Type, from: &'a syn::Type) -> syn::Result<Self> {
+        let Some((wrapper, elem)) = split_wrapper(ty) else {
+            if is_unsupported_wrapper(ty) {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "`external(as ...)` supports only `Option<_>`, `Vec<_>` and `Arc<[_]>` wrappers",
+                ));
+            }
+            return Ok(ExternalAs::Whole { ty, from });
+        };
+
+        if split_wrapper(elem).is_some() || is_unsupported_wrapper(elem) {
+            return Err(syn::Error::new_spanned(
+                elem,
+                "`external(as ...)` does not support nested wrappers",
+            ));
+        }
+
+        let from = match split_wrapper(from) {
+            None if is_unsupported_wrapper(from) => {
+                return Err(syn::Error::new_spanned(
+                    from,
+                    "`external(as ...)` type must be element type of the field wrapper",
+                ))
+            }
+            None => from,
+            Some((from_wrapper, from_elem)) if from_wrapper == wrapper => from_elem,
+            Some(_) => {
+                return Err(syn::Error::new_spanned(
+                    from,
+                    "`external(as ...)` type must be element type of the field wrapper",
+                ))
+            }
+        };
+
+        Ok(ExternalAs::Elements {
+            wrapper,
+            ty,
+            elem,
+            from,
+        })
+    }
+
+    /// Returns type that is loaded for the field.
+    fn loaded(&self) -> proc_macro2::TokenStream {
+        match *self {
+            ExternalAs::Whole { from, .. } => quote::quote!(#from),
+            ExternalAs::Elements { wrapper, from, .. } => match wrapper {
+                Wrapper::Option => quote::quote!(::argosy::proc_macro::Option<#from>),
+                Wrapper::Vec => quote::quote!(::argosy::proc_macro::Vec<#from>),
+                Wrapper::ArcSlice => quote::quote!(::argosy::proc_macro::Arc<[#from]>),
+            },
+        }
+    }
+
+    /// Returns expression that converts `built` value of the loaded type into the field type.
+    fn convert(&self, built: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match *self {
+            ExternalAs::Whole { ty, from } => {
+                quote::quote!(<#ty as ::argosy::proc_macro::From<#from>>::from(#built))
+            }
+            ExternalAs::Elements {
+                wrapper,
+                ty,
+                elem,
+                from,
+            } => {
+                let from_fn = quote::quote!(<#elem as ::argosy::proc_macro::From<#from>>::from);
+                match wrapper {
+                    Wrapper::Option => quote::quote!(
+                        ::argosy::proc_macro::Option::map(#built, #from_fn)
+                    ),
+                    Wrapper::Vec => quote::quote!(
+                        ::core::iter::Iterator::collect::<#ty>(
+                            ::core::iter::Iterator::map(::core::iter::IntoIterator::into_iter(#built), #from_fn)
+                        )
+                    ),
+                    Wrapper::ArcSlice => quote::quote!(
+                        ::core::iter::Iterator::collect::<#ty>(
+                            ::core::iter::Iterator::map(::core::iter::Iterator::cloned(<[#from]>::iter(&#built)), #from_fn)
+                        )
+                    ),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc,
+};
+
+use tokio::sync::Notify;
+
+use crate::progress::{Progress, ProgressCell};
+
+struct Inner {
+    aborted: AtomicBool,
+    notify: Notify,
+
+    /// Number of handles waiting for the load.
+    interest: AtomicUsize,
+
+    /// Progress reported by the decoder.
+    progress: ProgressCell,
+}
+
+/// Signal that is raised when loading of an asset should be aborted.
+///
+/// It is raised when [`Loader::cancel`] is called for the asset
+/// or when all handles waiting for the asset are dropped.
+/// Decoders may check it with [`AbortSignal::is_aborted`] or await
+/// [`AbortSignal::aborted`] and return an error early.
+/// Decoding that fails after abort leaves no trace in the loader,
+/// next request for the asset starts loading anew.
+///
+/// Signal for the asset being decoded is returned by [`Loader::abort_signal`].
+///
+/// [`Loader::cancel`]: crate::Loader::cancel
+/// [`Loader::abort_signal`]: crate::Loader::abort_signal
+#[derive(Clone)]
+pub struct AbortSignal {
+    inner: Arc<Inner>,
+}
+
+impl AbortSignal {
+    pub(crate) fn new() -> Self {
+        AbortSignal {
+            inner: Arc::new(Inner {
+                aborted: AtomicBool::new(false),
+                notify: Notify::new(),
+                interest: AtomicUsize::new(0),
+                progress: ProgressCell::new(),
+            }),
+        }
+    }
+
+    /// Returns `true` if loading should be aborted.
+    #[inline]
+    pub fn is_aborted(&self) -> bool {
+        self.inner.aborted.load(Ordering::Acquire)
+    }
+
+    /// Waits until signal is raised.
+    pub async fn aborted(&self) {
+        // Future receives notifications since creation.
+        let notified = self.inner.notify.notified();
+        if self.is_aborted() {
+            return;
+        }
+        notified.await;
+    }
+
+    pub(crate) fn abort(&self) {
+        self.inner.aborted.store(true, Ordering::Release);
+        self.inner.notify.notify_waiters();
+    }
+
+    #[inline]
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// Returns `true` if any handle is interested in the asset.
+    #[inline]
+    pub(crate) fn has_interest(&self) -> bool {
+        self.inner.interest.load(Ordering::Acquire) > 0
+    }
+
+    #[inline]
+    pub(crate) fn report_progress(&self, done: u64, total: Option<u64>) {
+        self.inner.progress.report(done, total);
+    }
+
+    #[inline]
+    pub(crate) fn progress(&self) -> Option<Progress> {
+        self.inner.progress.get()
+    }
+
+    /// Registers interest in the load.
+    pub(crate) fn interest(&self) -> Interest {
+        self.inner.interest.fetch_add(1, Ordering::Relaxed);
+        Interest {
+            signal: self.clone(),
+        }
+    }
+}
+
+/// Interest of a handle in the load.
+/// Signal is raised when last interest is dropped.
+pub(crate) struct Interest {
+    signal: AbortSignal,
+}
+
+impl Interest {
+    /// Returns progress reported by the decoder.
+    #[inline]
+    pub fn progress(&self) -> Option<Progress> {
+        self.signal.progress()
+    }
+}
+
+impl Clone for Interest {
+    fn clone(&self) -> Self {
+        self.signal.interest()
+    }
+}
+
+impl Drop for Interest {
+    fn drop(&mut self) {
+        if self.signal.inner.interest.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.signal.abort();
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use hashbrown::HashMap;
+use parking_lot::RwLock;
+
+/// Maximum number of aliases followed for single path.
+/// Longer chains are likely cyclic.
+pub(crate) const MAX_PATH_ALIAS_DEPTH: usize = 8;
+
+/// Path remapping table.
+pub(crate) struct PathAliases {
+    table: RwLock<HashMap<Arc<str>, Arc<str>>>,
+}
+
+impl PathAliases {
+    pub fn new(aliases: impl IntoIterator<Item = (Arc<str>, Arc<str>)>) -> Self {
+        PathAliases {
+            table: RwLock::new(aliases.into_iter().collect()),
+        }
+    }
+
+    /// Returns path the `path` is remapped to.
+    pub fn get(&self, path: &str) -> Option<Arc<str>> {
+        let table = self.table.read();
+        if table.is_empty() {
+            return None;
+        }
+        table.get(path).cloned()
+    }
+
+    /// Remaps `from` path to `to`.
+    /// Returns previous target of `from` path.
+    pub fn set(&self, from: Arc<str>, to: Arc<str>) -> Option<Arc<str>> {
+        self.table.write().insert(from, to)
+    }
+
+    /// Removes remapping of `from` path.
+    /// Returns its target.
+    pub fn remove(&self, from: &str) -> Option<Arc<str>> {
+        self.table.write().remove(from)
+    }
+
+    /// Returns all remappings sorted by remapped path.
+    pub fn list(&self) -> Vec<(Arc<str>, Arc<str>)> {
+        let mut aliases: Vec<_> = self
+            .table
+            .read()
+            .iter()
+            .map(|(from, to)| (from.clone(), to.clone()))
+            .collect();
+        aliases.sort();
+        aliases
+    }
+}
@@ -4,7 +4,7 @@ use std::{
 };
 
 use {
-    crate::loader::Loader,
+    crate::{format::with_decode_context, loader::Loader, names::AssetName},
     std::{error::Error, future::Future},
 };
 
@@ -26,20 +26,255 @@ pub trait Asset: Clone + Sized + Send + Sync + 'static {
     type Fut: Future<Output = Result<Self::Decoded, Self::DecodeError>> + Send;
 
     /// Asset name.
-    fn name() -> &'static str;
+    ///
+    /// Sources see only this name when looking assets up by path,
+    /// so it must be unique among asset types used with the same loader.
+    /// Register types with [`LoaderBuilder::register_asset`] to check it.
+    ///
+    /// Static names are made with [`AssetName::new`],
+    /// names computed at runtime with [`AssetName::intern`].
+    ///
+    /// Implementations must provide either this method or deprecated [`Asset::name`].
+    ///
+    /// [`LoaderBuilder::register_asset`]: crate::LoaderBuilder::register_asset
+    #[inline]
+    fn asset_name() -> AssetName {
+        #[allow(deprecated)]
+        AssetName::new(Self::name())
+    }
+
+    /// Asset name as static string.
+    #[deprecated(note = "implement `asset_name` instead")]
+    #[inline]
+    fn name() -> &'static str {
+        Self::asset_name().as_str()
+    }
 
     /// Decode asset from bytes loaded from asset source.
+    ///
+    /// `loader` describes the decoded asset, like [`Loader::decoding_format`]
+    /// and [`Loader::strict_descriptors`].
+    /// Returned future may be polled after other assets are decoded,
+    /// so values needed by it must be taken from `loader` before it is returned.
     fn decode(bytes: Box<[u8]>, loader: &Loader) -> Self::Fut;
 }
 
 /// Asset building trait.
 ///
 /// There should be at least on implementation of this trait for each `Asset` type.
+///
+/// Building asset with a builder it does not support is reported with the asset type.
+/// For derived assets error also names the field that cannot be built,
+/// with helper trait like `MaterialAlbedoFieldBuild` for field `albedo` of `Material`.
+#[diagnostic::on_unimplemented(
+    message = "asset `{Self}` cannot be built with builder `{B}`",
+    label = "`{Self}` does not implement `AssetBuild<{B}>`",
+    note = "implement `AssetBuild<{B}>` for `{Self}`, or derive `Asset` if all its fields can be built with `{B}`"
+)]
 pub trait AssetBuild<B>: Asset {
     /// Build asset instance using decoded representation.
     fn build(builder: &mut B, decoded: Self::Decoded) -> Result<Self, Self::BuildError>;
 }
 
+/// Asset building trait for a family of builders.
+///
+/// `F` is usually a trait object type, e.g. `dyn GpuBuilder`,
+/// so that single implementation serves all builders that implement the trait.
+/// Note that `dyn GpuBuilder` in type arguments is `dyn GpuBuilder + 'static`.
+/// [`impl_asset_build_via!`] implements [`AssetBuild`] for each such builder.
+///
+/// Assets with fields of such assets need no special care,
+/// `derive(Asset)` requires fields to implement [`AssetBuild`] for the builder.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// trait GpuBuilder {
+///     fn create_texture(&mut self, size: u32) -> String;
+/// }
+///
+/// struct VulkanCtx;
+///
+/// impl GpuBuilder for VulkanCtx {
+///     fn create_texture(&mut self, size: u32) -> String {
+///         format!("vk-{size}")
+///     }
+/// }
+///
+/// /// Headless builder for tests.
+/// struct NullCtx;
+///
+/// impl GpuBuilder for NullCtx {
+///     fn create_texture(&mut self, _size: u32) -> String {
+///         "null".to_owned()
+///     }
+/// }
+///
+/// #[derive(Clone)]
+/// struct Texture(String);
+///
+/// impl LeafAsset for Texture {
+///     type Decoded = u32;
+///     type DecodeError = std::num::ParseIntError;
+///     type BuildError = std::convert::Infallible;
+///
+///     fn asset_name() -> AssetName {
+///         AssetName::new("Texture")
+///     }
+///
+///     fn decode(bytes: Box<[u8]>) -> Result<u32, Self::DecodeError> {
+///         String::from_utf8_lossy(&bytes).trim().parse()
+///     }
+/// }
+///
+/// impl AssetBuildVia<dyn GpuBuilder> for Texture {
+///     fn build_via(gpu: &mut (dyn GpuBuilder + 'static), size: u32) -> Result<Self, Self::BuildError> {
+///         Ok(Texture(gpu.create_texture(size)))
+///     }
+/// }
+///
+/// impl_asset_build_via!(Texture, dyn GpuBuilder);
+///
+/// #[derive(Clone, Asset)]
+/// struct Material {
+///     #[asset(external)]
+///     albedo: Texture,
+/// }
+///
+/// fn albedo<B: GpuBuilder + 'static>(builder: &mut B) -> Result<String, Error> {
+///     let source = MemorySource::new();
+///     source.insert(AssetId::new(1).unwrap(), &br#"{ "albedo": 2 }"#[..]);
+///     source.insert(AssetId::new(2).unwrap(), &b"512"[..]);
+///
+///     let loader = Loader::builder().with(source).build();
+///
+///     tokio::runtime::Builder::new_current_thread()
+///         .build()
+///         .unwrap()
+///         .block_on(async {
+///             let mut material = loader.load::<Material, _>(AssetId::new(1).unwrap()).await?;
+///             Ok(material.build(builder)?.albedo.0)
+///         })
+/// }
+///
+/// assert_eq!(albedo(&mut VulkanCtx)?, "vk-512");
+/// assert_eq!(albedo(&mut NullCtx)?, "null");
+/// # }
+/// # Ok::<_, Error>(())
+/// ```
+///
+/// Assets are built only with builders of the family.
+///
+/// [`TrivialAsset`]s are built with any builder already,
+/// so they can't be built via a family.
+pub trait AssetBuildVia<F: ?Sized>: Asset {
+    /// Build asset instance using decoded representation.
+    fn build_via(builder: &mut F, decoded: Self::Decoded) -> Result<Self, Self::BuildError>;
+}
+
+/// Implements [`AssetBuild`] for each builder of the family
+/// with [`AssetBuildVia`] implementation of the asset.
+///
+/// `impl_asset_build_via!(Texture, dyn GpuBuilder)` implements `AssetBuild<B>`
+/// for `Texture` and every `B: GpuBuilder + 'static`.
+///
+/// See [`AssetBuildVia`] for example.
+#[macro_export]
+macro_rules! impl_asset_build_via {
+    ($asset:ty, dyn $family:path) => {
+        impl<B: $family + 'static> $crate::AssetBuild<B> for $asset {
+            #[inline]
+            fn build(
+                builder: &mut B,
+                decoded: <Self as $crate::Asset>::Decoded,
+            ) -> ::core::result::Result<Self, <Self as $crate::Asset>::BuildError> {
+                <Self as $crate::AssetBuildVia<dyn $family>>::build_via(builder, decoded)
+            }
+        }
+    };
+}
+
+/// Asset type that stores hash of its schema in the asset info.
+///
+/// Implemented by `derive(Asset)` with `#[asset(checked)]` attribute.
+/// Decoding info with different schema hash fails with
+/// [`DecodeError::SchemaMismatch`].
+/// Info without schema hash is decoded as usual.
+///
+/// Only self-describing formats, like JSON, carry schema hash.
+/// Bincode info has the same layout as without the attribute
+/// and its schema is not checked.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// #[asset(checked)]
+/// struct Speed {
+///     value: f32,
+/// }
+///
+/// #[derive(Clone, Asset)]
+/// #[asset(checked)]
+/// struct Color {
+///     value: f32,
+/// }
+///
+/// let source = MemorySource::new();
+/// let loader = Loader::builder().with(source.clone()).build();
+///
+/// // Artifact built for `Color` is referenced as `Speed`.
+/// let id = AssetId::new(1).unwrap();
+/// let info = format!(r#"{{ "value": 1.0, "__argosy_schema": {} }}"#, Color::SCHEMA);
+/// source.insert(id, info.into_bytes());
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let error = loader.load::<Speed, _>(id).await.err().unwrap();
+///         match error.downcast_ref::<SpeedDecodeError>() {
+///             Some(SpeedDecodeError::Info(DecodeError::SchemaMismatch { expected, found })) => {
+///                 assert_eq!(*expected, Speed::SCHEMA);
+///                 assert_eq!(*found, Color::SCHEMA);
+///             }
+///             _ => panic!("Unexpected error {error}"),
+///         }
+///
+///         // Bincode artifact has no schema hash.
+///         let id = AssetId::new(2).unwrap();
+///         source.insert(id, 1.5f32.to_le_bytes().to_vec());
+///         let mut speed = loader.load::<Speed, _>(id).await.unwrap();
+///         assert_eq!(speed.build(&mut ()).unwrap().value, 1.5);
+///     });
+/// # }
+/// ```
+///
+/// [`DecodeError::SchemaMismatch`]: crate::DecodeError::SchemaMismatch
+pub trait CheckedAsset: Asset {
+    /// Stable hash of the asset name and its fields.
+    const SCHEMA: u64;
+
+    /// Name of the info field that contains schema hash.
+    const SCHEMA_FIELD: &'static str = "__argosy_schema";
+}
+
+/// Asset type that stores multiple named sub-assets in one artifact.
+/// For example sprite sheet with named frames
+/// or shader file with multiple entry points.
+///
+/// Sub-assets are loaded with [`Loader::load_sub`].
+/// Each sub-asset is cached separately,
+/// while raw artifact data is loaded once and shared between them.
+pub trait SubAsset: Asset {
+    /// Decode sub-asset with specified name from bytes of the artifact.
+    fn decode_sub(bytes: &[u8], sub: &str, loader: &Loader) -> Self::Fut;
+}
+
 /// Leaf assets have no dependencies.
 /// For this reason their `decode` function is always sync and do not take `Loader` argument.
 pub trait LeafAsset: Clone + Sized + Send + Sync + 'static {
@@ -56,7 +291,20 @@ pub trait LeafAsset: Clone + Sized + Send + Sync + 'static {
     type BuildError: Error + Send + Sync + 'static;
 
     /// Asset name.
-    fn name() -> &'static str;
+    ///
+    /// Implementations must provide either this method or deprecated [`LeafAsset::name`].
+    #[inline]
+    fn asset_name() -> AssetName {
+        #[allow(deprecated)]
+        AssetName::new(Self::name())
+    }
+
+    /// Asset name as static string.
+    #[deprecated(note = "implement `asset_name` instead")]
+    #[inline]
+    fn name() -> &'static str {
+        Self::asset_name().as_str()
+    }
 
     /// Decode asset from bytes loaded from asset source.
     fn decode(bytes: Box<[u8]>) -> Result<Self::Decoded, Self::DecodeError>;
@@ -65,13 +313,41 @@ pub trait LeafAsset: Clone + Sized + Send + Sync + 'static {
 /// Trivial assets have no dependencies and do not require building.
 /// They are decoded directly from bytes.
 /// They implement `AssetBuild<B>` for any `B`.
+#[diagnostic::on_unimplemented(
+    message = "asset `{Self}` cannot be built with the builder",
+    label = "`{Self}` implements neither `AssetBuild` for the builder nor `TrivialAsset`",
+    note = "implement `AssetBuild<B>` for `{Self}`, where `B` is the builder type"
+)]
 pub trait TrivialAsset: Clone + Sized + Send + Sync + 'static {
     type Error: Error + Send + Sync + 'static;
 
     /// Asset name.
-    fn name() -> &'static str;
+    ///
+    /// Implementations must provide either this method or deprecated [`TrivialAsset::name`].
+    #[inline]
+    fn asset_name() -> AssetName {
+        #[allow(deprecated)]
+        AssetName::new(Self::name())
+    }
+
+    /// Asset name as static string.
+    #[deprecated(note = "implement `asset_name` instead")]
+    #[inline]
+    fn name() -> &'static str {
+        Self::asset_name().as_str()
+    }
 
     /// Decode asset directly.
+    ///
+    /// Hand-authored payloads may be empty or start with UTF-8 BOM.
+    /// Derived implementations for structs with fields strip BOM before parsing JSON
+    /// and reject empty, whitespace-only and `null` payloads
+    /// with [`DecodeError::EmptyPayload`] and [`DecodeError::NullPayload`],
+    /// manual implementations should do the same.
+    /// Derived implementations for unit structs ignore payload.
+    ///
+    /// [`DecodeError::EmptyPayload`]: crate::DecodeError::EmptyPayload
+    /// [`DecodeError::NullPayload`]: crate::DecodeError::NullPayload
     fn decode(bytes: Box<[u8]>) -> Result<Self, Self::Error>;
 }
 
@@ -86,13 +362,17 @@ where
 
     /// Asset name.
     #[inline]
-    fn name() -> &'static str {
-        <A as LeafAsset>::name()
+    fn asset_name() -> AssetName {
+        <A as LeafAsset>::asset_name()
     }
 
     #[inline]
-    fn decode(bytes: Box<[u8]>, _: &Loader) -> Ready<Result<A::Decoded, A::DecodeError>> {
-        ready(<A as LeafAsset>::decode(bytes))
+    fn decode(bytes: Box<[u8]>, loader: &Loader) -> Ready<Result<A::Decoded, A::DecodeError>> {
+        ready(with_decode_context(
+            loader.decoding_format(),
+            loader.strict_descriptors(),
+            || <A as LeafAsset>::decode(bytes),
+        ))
     }
 }
 
@@ -106,8 +386,8 @@ where
 
     /// Asset name.
     #[inline]
-    fn name() -> &'static str {
-        <A as TrivialAsset>::name()
+    fn asset_name() -> AssetName {
+        <A as TrivialAsset>::asset_name()
     }
 
     #[inline]
@@ -0,0 +1,134 @@
+use std::{
+    pin::pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use tokio::sync::Notify;
+
+/// Limit of decoded but not built asset bytes.
+///
+/// Decoding waits while the limit is exceeded,
+/// until building brings the amount below low-water mark.
+pub(crate) struct Backpressure {
+    max_bytes: usize,
+    resume_bytes: usize,
+    critical_priority: i32,
+
+    /// Bytes of decoded assets that are not built yet.
+    unbuilt: AtomicUsize,
+
+    /// Set when limit is exceeded, cleared below low-water mark.
+    throttled: AtomicBool,
+
+    /// Number of decodes waiting for builds.
+    parked: AtomicUsize,
+
+    drained: Notify,
+}
+
+impl Backpressure {
+    pub fn new(max_bytes: usize, resume_bytes: usize, critical_priority: i32) -> Self {
+        Backpressure {
+            max_bytes,
+            resume_bytes: resume_bytes.min(max_bytes),
+            critical_priority,
+            unbuilt: AtomicUsize::new(0),
+            throttled: AtomicBool::new(false),
+            parked: AtomicUsize::new(0),
+            drained: Notify::new(),
+        }
+    }
+
+    /// Returns bytes of decoded assets that are not built yet.
+    pub fn unbuilt(&self) -> usize {
+        self.unbuilt.load(Ordering::Relaxed)
+    }
+
+    /// Returns number of decodes waiting for builds.
+    pub fn parked(&self) -> usize {
+        self.parked.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if decoding waits for builds.
+    pub fn is_throttled(&self) -> bool {
+        self.throttled.load(Ordering::Relaxed)
+    }
+
+    /// Reserves bytes for asset that is about to be decoded.
+    /// Waits while limit is exceeded, unless `priority` is critical.
+    ///
+    /// Single asset larger than the limit is decoded when nothing else is unbuilt.
+    pub async fn reserve(self: &Arc<Self>, bytes: usize, priority: i32) -> Reservation {
+        if priority >= self.critical_priority {
+            self.unbuilt.fetch_add(bytes, Ordering::AcqRel);
+            return self.reservation(bytes);
+        }
+
+        loop {
+            let mut drained = pin!(self.drained.notified());
+            drained.as_mut().enable();
+
+            if !self.throttled.load(Ordering::Acquire) {
+                let mut current = self.unbuilt.load(Ordering::Acquire);
+                while current == 0 || current.saturating_add(bytes) <= self.max_bytes {
+                    match self.unbuilt.compare_exchange_weak(
+                        current,
+                        current + bytes,
+                        Ordering::AcqRel,
+                        Ordering::Acquire,
+                    ) {
+                        Ok(_) => return self.reservation(bytes),
+                        Err(actual) => current = actual,
+                    }
+                }
+
+                if !self.throttled.swap(true, Ordering::AcqRel) {
+                    tracing::warn!(
+                        "Building is the bottleneck, {} bytes of decoded assets are not built",
+                        current
+                    );
+                }
+
+                // Builds may have drained before the flag was set.
+                if self.unbuilt.load(Ordering::Acquire) <= self.resume_bytes {
+                    self.throttled.store(false, Ordering::Release);
+                    continue;
+                }
+            }
+
+            self.parked.fetch_add(1, Ordering::Relaxed);
+            drained.await;
+            self.parked.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    fn reservation(self: &Arc<Self>, bytes: usize) -> Reservation {
+        Reservation {
+            pressure: self.clone(),
+            bytes,
+        }
+    }
+
+    fn release(&self, bytes: usize) {
+        let left = self.unbuilt.fetch_sub(bytes, Ordering::AcqRel) - bytes;
+        if left <= self.resume_bytes && self.throttled.swap(false, Ordering::AcqRel) {
+            self.drained.notify_waiters();
+        }
+    }
+}
+
+/// Bytes of decoded asset counted against the limit.
+/// Released when asset leaves decoded state.
+pub(crate) struct Reservation {
+    pressure: Arc<Backpressure>,
+    bytes: usize,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        self.pressure.release(self.bytes);
+    }
+}
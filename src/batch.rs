@@ -0,0 +1,148 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use tokio::sync::Notify;
+
+use crate::error::Error;
+
+struct Shared {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+/// Token to cancel batch operations, like [`Loader::prewarm`].
+///
+/// Clones share the state, cancelling any of them cancels all.
+/// Cancellation can't be undone.
+///
+/// [`Loader::prewarm`]: crate::Loader::prewarm
+///
+/// # Example
+///
+/// ```
+/// # use argosy::CancelToken;
+/// let token = CancelToken::new();
+/// let clone = token.clone();
+/// assert!(!clone.is_cancelled());
+///
+/// token.cancel();
+/// assert!(clone.is_cancelled());
+///
+/// // Resolves immediately once cancelled.
+/// futures::executor::block_on(clone.cancelled());
+/// ```
+#[derive(Clone)]
+pub struct CancelToken {
+    shared: Arc<Shared>,
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        CancelToken::new()
+    }
+}
+
+impl std::fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancelToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl CancelToken {
+    /// Returns new token that is not cancelled.
+    pub fn new() -> Self {
+        CancelToken {
+            shared: Arc::new(Shared {
+                cancelled: AtomicBool::new(false),
+                notify: Notify::new(),
+            }),
+        }
+    }
+
+    /// Cancels operations that use this token.
+    pub fn cancel(&self) {
+        self.shared.cancelled.store(true, Ordering::Release);
+        self.shared.notify.notify_waiters();
+    }
+
+    /// Returns `true` if token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Resolves when token is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            // Future receives notifications since creation.
+            let notified = self.shared.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Outcome of single item of a batch operation.
+#[derive(Clone, Debug)]
+pub enum BatchItem<E = Error> {
+    /// Item is done.
+    Done,
+
+    /// Item failed with an error.
+    Failed(E),
+
+    /// Batch was cancelled before item was done.
+    Cancelled,
+}
+
+impl<E> BatchItem<E> {
+    /// Returns `true` if item is done.
+    pub fn is_done(&self) -> bool {
+        matches!(self, BatchItem::Done)
+    }
+
+    /// Returns `true` if item was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, BatchItem::Cancelled)
+    }
+}
+
+/// Per-item outcome of a batch operation.
+///
+/// Items are in the order of the batch.
+/// Items that were not done when batch was cancelled are [`BatchItem::Cancelled`].
+#[derive(Clone, Debug)]
+pub struct BatchOutcome<E = Error> {
+    /// Outcomes of the items.
+    pub items: Vec<BatchItem<E>>,
+}
+
+impl<E> BatchOutcome<E> {
+    /// Returns number of items that are done.
+    pub fn succeeded(&self) -> usize {
+        self.items.iter().filter(|item| item.is_done()).count()
+    }
+
+    /// Returns number of items that failed.
+    pub fn failed(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| matches!(item, BatchItem::Failed(_)))
+            .count()
+    }
+
+    /// Returns number of items that were cancelled.
+    pub fn cancelled(&self) -> usize {
+        self.items.iter().filter(|item| item.is_cancelled()).count()
+    }
+
+    /// Returns `true` if all items are done.
+    pub fn is_complete(&self) -> bool {
+        self.items.iter().all(BatchItem::is_done)
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+/// Unparks thread blocked on a future.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls `future` on the current thread until it completes,
+/// parking the thread while it is pending.
+///
+/// `before_poll` is called with the waker of the thread before each poll,
+/// so that loading tasks can be driven and wake the thread.
+pub(crate) fn block_on<F>(future: F, mut before_poll: impl FnMut(&Waker)) -> F::Output
+where
+    F: Future,
+{
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        before_poll(&waker);
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        // Spurious unparks only cause extra poll.
+        thread::park();
+    }
+}
+
+/// Returns handle to the runtime that runs loading tasks
+/// spawned outside of any tokio runtime.
+///
+/// Runtime is started on a background thread on first use and runs until the process exits.
+#[cfg(feature = "tokio")]
+pub(crate) fn background_runtime() -> tokio::runtime::Handle {
+    static RUNTIME: std::sync::OnceLock<tokio::runtime::Handle> = std::sync::OnceLock::new();
+
+    RUNTIME
+        .get_or_init(|| {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_time()
+                .build()
+                .expect("Failed to build background runtime for loading tasks");
+            let handle = runtime.handle().clone();
+
+            thread::Builder::new()
+                .name("argosy-loader".to_owned())
+                .spawn(move || runtime.block_on(std::future::pending::<()>()))
+                .expect("Failed to spawn thread for loading tasks");
+
+            handle
+        })
+        .clone()
+}
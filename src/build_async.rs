@@ -0,0 +1,247 @@
+use std::{
+    any::Any,
+    convert::Infallible,
+    future::{ready, Future},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use argosy_id::AssetId;
+use futures::future::BoxFuture;
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::{
+    asset::{Asset, TrivialAsset},
+    cache::Entry,
+    error::{BuildInProgress, Cancelled, Error, ErrorCode, ErrorStage},
+    handle::{Handle, State},
+    key::KindKey,
+    loader::{AssetShard, AssetState, DecodedState, EntryStatus, ErasedDecodedState},
+    transition::{debug_check, EntryKind, Event},
+};
+
+/// Builder shared between tasks behind async mutex.
+///
+/// Assets built with [`LoadedAsset::build_async`] lock it separately for each field
+/// and yield between fields, so other tasks can use the builder
+/// while large asset is being built.
+///
+/// [`LoadedAsset::build_async`]: crate::LoadedAsset::build_async
+pub struct AsyncShared<B>(pub Arc<Mutex<B>>);
+
+impl<B> Clone for AsyncShared<B> {
+    #[inline]
+    fn clone(&self) -> Self {
+        AsyncShared(self.0.clone())
+    }
+}
+
+impl<B> AsyncShared<B> {
+    /// Wraps builder into async mutex.
+    pub fn new(builder: B) -> Self {
+        AsyncShared(Arc::new(Mutex::new(builder)))
+    }
+
+    /// Locks the builder.
+    pub async fn lock(&self) -> MutexGuard<'_, B> {
+        self.0.lock().await
+    }
+}
+
+/// Asset building trait for builders shared behind async mutex.
+///
+/// Implemented by `derive(Asset)`. Generated implementation locks the builder
+/// to build each field and yields before next field.
+/// Fields are built with [`AssetBuild`] implementations,
+/// so external assets are built whole while builder is locked.
+///
+/// [`AssetBuild`]: crate::AssetBuild
+pub trait AsyncAssetBuild<B>: Asset {
+    /// Build asset instance using decoded representation.
+    fn build_async(
+        builder: &AsyncShared<B>,
+        decoded: Self::Decoded,
+    ) -> BoxFuture<'_, Result<Self, Self::BuildError>>;
+}
+
+impl<A, B> AsyncAssetBuild<B> for A
+where
+    A: TrivialAsset,
+    B: Send,
+{
+    #[inline(always)]
+    fn build_async(_: &AsyncShared<B>, decoded: A) -> BoxFuture<'_, Result<A, Infallible>> {
+        Box::pin(ready(Ok(decoded)))
+    }
+}
+
+/// Future that yields to other tasks once.
+pub struct YieldNow(bool);
+
+/// Returns future that yields to other tasks once.
+#[inline]
+pub fn yield_now() -> YieldNow {
+    YieldNow(false)
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Decoded asset taken from the cache entry to be built asynchronously.
+///
+/// Entry is marked as cancelled if build is dropped before it finishes.
+struct Taken {
+    shard: AssetShard,
+    key_hash: u64,
+    kind: KindKey,
+    id: AssetId,
+    decoded: ErasedDecodedState,
+    finished: bool,
+}
+
+impl Taken {
+    fn finish(mut self, result: Result<Arc<dyn Any + Send + Sync>, Error>) {
+        self.finished = true;
+        self.publish(result);
+    }
+
+    /// Replaces loaded entry with the result, unless entry was reloaded.
+    fn publish(&self, result: Result<Arc<dyn Any + Send + Sync>, Error>) {
+        let mut locked_shard = self.shard.lock();
+        let raw_entry = locked_shard.entry(self.key_hash, |k| k.eq_key(self.kind, self.id));
+
+        if let Entry::Occupied(mut entry) = raw_entry {
+            if let AssetState::Loaded {
+                decoded, metadata, ..
+            } = entry.get_mut()
+            {
+                if Arc::ptr_eq(decoded, &self.decoded) {
+                    let (event, to) = match &result {
+                        Ok(_) => (Event::Built, EntryStatus::Ready),
+                        Err(_) => (Event::Failed, EntryStatus::Error),
+                    };
+                    debug_check(EntryKind::Asset, Some(EntryStatus::Loaded), event, Some(to));
+                    *entry.get_mut() = match result {
+                        Ok(asset) => AssetState::Ready {
+                            asset,
+                            metadata: metadata.clone(),
+                        },
+                        Err(error) => AssetState::Error { error },
+                    };
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Taken {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.publish(Err(Error::new(Cancelled { id: self.id })
+                .with_code(ErrorCode::Cancelled)
+                .with_stage(ErrorStage::Build)));
+        }
+    }
+}
+
+/// Returns decoded state of the cache entry if asset is not built yet.
+/// Otherwise returns the result of the build.
+fn loaded_state<A: Asset>(
+    shard: &AssetShard,
+    key_hash: u64,
+    kind: KindKey,
+    id: AssetId,
+) -> Result<ErasedDecodedState, Result<A, Error>> {
+    let mut locked_shard = shard.lock();
+    match locked_shard.entry(key_hash, |k| k.eq_key(kind, id)) {
+        Entry::Occupied(mut entry) => match entry.get_mut() {
+            AssetState::Loaded { decoded, .. } => Ok(decoded.clone()),
+            AssetState::Ready { asset, .. } => Err(Ok(asset.downcast_ref::<A>().unwrap().clone())),
+            AssetState::Error { error } => Err(Err(error.clone())),
+            // Entry was removed and maybe loading started anew.
+            AssetState::Unloaded { .. } | AssetState::Missing => {
+                Err(Err(
+                    Error::new(Cancelled { id }).with_code(ErrorCode::Cancelled)
+                ))
+            }
+        },
+        Entry::Vacant(_) => Err(Err(
+            Error::new(Cancelled { id }).with_code(ErrorCode::Cancelled)
+        )),
+    }
+}
+
+/// Builds loaded asset with builder shared behind async mutex.
+pub(crate) async fn build<A, B>(handle: &Handle, builder: &AsyncShared<B>) -> Result<A, Error>
+where
+    A: AsyncAssetBuild<B>,
+{
+    let (key_hash, shard) = match &handle.state {
+        State::Loaded {
+            key_hash, shard, ..
+        } => (*key_hash, shard),
+        State::Ready { asset, .. } => return Ok(asset.downcast_ref::<A>().unwrap().clone()),
+        State::Missing { error } | State::Error { error } => return Err(error.clone()),
+        State::Searching { .. } | State::Loading { .. } => {
+            unreachable!("`poll_load` must be used first")
+        }
+    };
+
+    let id = handle
+        .id
+        .expect("This state can be reached only with known id");
+
+    let decoded = match loaded_state::<A>(shard, key_hash, handle.kind, id) {
+        Ok(decoded) => decoded,
+        Err(result) => return result,
+    };
+
+    let value = decoded
+        .lock()
+        .downcast_mut::<DecodedState<A>>()
+        .unwrap()
+        .take();
+
+    let Some(value) = value else {
+        // Either built synchronously meanwhile or taken by another asynchronous build.
+        return match loaded_state::<A>(shard, key_hash, handle.kind, id) {
+            Ok(_) => Err(Error::new(BuildInProgress { id })
+                .with_code(ErrorCode::BuildFailed)
+                .with_stage(ErrorStage::Build)),
+            Err(result) => result,
+        };
+    };
+
+    let taken = Taken {
+        shard: shard.clone(),
+        key_hash,
+        kind: handle.kind,
+        id,
+        decoded,
+        finished: false,
+    };
+
+    let result = A::build_async(builder, value).await.map_err(|err| {
+        Error::new(err)
+            .or_code(ErrorCode::BuildFailed)
+            .with_stage(ErrorStage::Build)
+    });
+
+    taken.finish(
+        result
+            .clone()
+            .map(|asset| Arc::new(asset) as Arc<dyn Any + Send + Sync>),
+    );
+    result
+}
@@ -0,0 +1,246 @@
+use std::{
+    any::TypeId, cmp::Reverse, collections::BTreeMap, marker::PhantomData, ops::Bound,
+    time::Instant,
+};
+
+use argosy_id::AssetId;
+use hashbrown::HashMap;
+
+use crate::handle::{AssetDriver, DriveAsset, LocalAssetDriver, LocalDrive, NoBuilderDrive};
+
+/// Position of the item in the queue.
+/// Higher priority first, then insertion order.
+type Slot = (Reverse<i32>, u64);
+
+/// Queue of assets built with one builder in order of priority.
+///
+/// Assets of any type that can be built with the builder are enqueued as [`AssetDriver`]s.
+/// Draining builds loaded assets with higher priority first
+/// and assets of the same priority in order they were enqueued.
+/// Assets that are not loaded yet stay in the queue.
+/// Queue of [`LocalDrive`] accepts [`LocalAssetDriver`]s as well and is not `Send`.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use std::any::TypeId;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone)]
+/// struct Texture {
+///     name: String,
+/// }
+///
+/// impl Asset for Texture {
+///     type Decoded = String;
+///     type DecodeError = std::str::Utf8Error;
+///     type BuildError = std::convert::Infallible;
+///     type Fut = std::future::Ready<Result<String, std::str::Utf8Error>>;
+///
+///     fn asset_name() -> AssetName {
+///         AssetName::new("Texture")
+///     }
+///
+///     fn decode(bytes: Box<[u8]>, _: &Loader) -> Self::Fut {
+///         std::future::ready(std::str::from_utf8(&bytes).map(str::to_owned))
+///     }
+/// }
+///
+/// /// Builder records order in which textures are built.
+/// impl AssetBuild<Vec<String>> for Texture {
+///     fn build(built: &mut Vec<String>, name: String) -> Result<Texture, std::convert::Infallible> {
+///         built.push(name.clone());
+///         Ok(Texture { name })
+///     }
+/// }
+///
+/// let id = |value| AssetId::new(value).unwrap();
+///
+/// let source = MemorySource::new();
+/// for (value, name) in [(1, "lod"), (2, "ui"), (3, "far"), (4, "hud")] {
+///     source.insert(id(value), name.as_bytes());
+/// }
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let mut queue = BuildQueue::new();
+///         for (value, priority) in [(1, 0), (2, 10), (3, 0), (4, 10)] {
+///             let driver = loader.load::<Texture, _>(id(value)).driver::<SimpleDrive<Vec<String>>>();
+///             queue.enqueue_with_priority(driver, priority);
+///         }
+///
+///         // Distant object comes close.
+///         assert!(queue.promote(TypeId::of::<Texture>(), id(3), 20));
+///
+///         loader.wait_idle().await;
+///
+///         // No time left this frame.
+///         let mut built = Vec::new();
+///         assert_eq!(queue.drain_until(&mut built, std::time::Instant::now()), 0);
+///         assert_eq!(queue.len(), 4);
+///
+///         assert_eq!(queue.drain(&mut built), 4);
+///         assert_eq!(built, ["far", "ui", "hud", "lod"]);
+///         assert!(queue.is_empty());
+///     });
+/// # }
+/// ```
+pub struct BuildQueue<D: DriveAsset = NoBuilderDrive> {
+    /// Queued drivers in order they are built.
+    items: BTreeMap<Slot, AssetDriver<D>>,
+
+    /// Slots of queued drivers with known asset id.
+    index: HashMap<(TypeId, AssetId), Slot>,
+
+    /// Insertion counter.
+    next: u64,
+
+    /// Queue of [`LocalDrive`] stays on its thread.
+    _drive: PhantomData<D>,
+}
+
+impl<D> Default for BuildQueue<D>
+where
+    D: DriveAsset,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> BuildQueue<D>
+where
+    D: DriveAsset,
+{
+    /// Returns empty queue.
+    pub fn new() -> Self {
+        BuildQueue {
+            items: BTreeMap::new(),
+            index: HashMap::new(),
+            next: 0,
+            _drive: PhantomData,
+        }
+    }
+
+    /// Returns number of queued assets.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if no assets are queued.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Enqueues asset with default priority `0`.
+    pub fn enqueue(&mut self, driver: AssetDriver<D>) {
+        self.enqueue_with_priority(driver, 0);
+    }
+
+    /// Enqueues asset with specified priority.
+    /// Assets with higher priority are built first.
+    ///
+    /// If the same asset is already queued,
+    /// `driver` is dropped and queued asset gets new priority as with [`BuildQueue::promote`].
+    pub fn enqueue_with_priority(&mut self, driver: AssetDriver<D>, priority: i32) {
+        if let Some(id) = driver.asset_id() {
+            if self.promote(driver.type_id(), id, priority) {
+                return;
+            }
+        }
+
+        let slot = (Reverse(priority), self.next);
+        self.next += 1;
+
+        if let Some(id) = driver.asset_id() {
+            self.index.insert((driver.type_id(), id), slot);
+        }
+        self.items.insert(slot, driver);
+    }
+
+    /// Changes priority of the queued asset.
+    /// Asset keeps its insertion order among assets with the same priority.
+    ///
+    /// Returns `false` if asset is not queued.
+    /// Assets requested by path are found only after their id is resolved,
+    /// i.e. after the queue was drained once since the asset was found.
+    pub fn promote(&mut self, type_id: TypeId, id: AssetId, priority: i32) -> bool {
+        let Some(slot) = self.index.get_mut(&(type_id, id)) else {
+            return false;
+        };
+
+        let driver = self.items.remove(slot).unwrap();
+        slot.0 = Reverse(priority);
+        self.items.insert(*slot, driver);
+        true
+    }
+
+    /// Builds all loaded assets in order of priority.
+    /// Returns number of built assets.
+    pub fn drain(&mut self, builder: &mut D::Builder<'_>) -> usize {
+        self.drain_impl(builder, None)
+    }
+
+    /// Builds loaded assets in order of priority until `deadline`.
+    /// Deadline is checked before each build,
+    /// so assets that are not built in time are left for the next call.
+    /// Returns number of built assets.
+    pub fn drain_until(&mut self, builder: &mut D::Builder<'_>, deadline: Instant) -> usize {
+        self.drain_impl(builder, Some(deadline))
+    }
+
+    fn drain_impl(&mut self, builder: &mut D::Builder<'_>, deadline: Option<Instant>) -> usize {
+        let mut built = 0;
+        let mut cursor = Bound::Unbounded;
+
+        while let Some((&slot, driver)) = self.items.range_mut((cursor, Bound::Unbounded)).next() {
+            cursor = Bound::Excluded(slot);
+
+            let known = driver.asset_id().is_some();
+            let loaded = driver.poll_loaded();
+
+            let key = driver.asset_id().map(|id| (driver.type_id(), id));
+            if let (false, Some(key)) = (known, key) {
+                self.index.insert(key, slot);
+            }
+
+            let Some(loaded) = loaded else {
+                continue;
+            };
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+
+            self.items.remove(&slot);
+            if let Some(key) = key {
+                self.index.remove(&key);
+            }
+
+            loaded.build(builder);
+            built += 1;
+        }
+
+        built
+    }
+}
+
+impl<B> BuildQueue<LocalDrive<B>> {
+    /// Enqueues local driver with default priority `0`.
+    pub fn enqueue_local(&mut self, driver: LocalAssetDriver<LocalDrive<B>>) {
+        self.enqueue_with_priority(driver.into_driver(), 0);
+    }
+
+    /// Enqueues local driver with specified priority.
+    /// See [`BuildQueue::enqueue_with_priority`].
+    pub fn enqueue_local_with_priority(
+        &mut self,
+        driver: LocalAssetDriver<LocalDrive<B>>,
+        priority: i32,
+    ) {
+        self.enqueue_with_priority(driver.into_driver(), priority);
+    }
+}
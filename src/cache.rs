@@ -0,0 +1,483 @@
+use std::{
+    collections::BTreeMap,
+    hash::{BuildHasherDefault, Hash, Hasher},
+    sync::Arc,
+};
+
+use hashbrown::hash_map::{HashMap, RawEntryMut};
+
+use crate::{
+    key::{KindKey, PathKey, TypeKey},
+    loader::{AssetState, PathState},
+};
+
+/// Storage for entries of the loader cache.
+///
+/// Loader splits cache into shards.
+/// Each shard has its own backend instance and guards it with a mutex.
+///
+/// Keys and values are opaque to the backend.
+/// Instead of hashing keys backend receives precomputed hash
+/// and a function to compare stored keys with the requested one.
+///
+/// Backend may evict entries on its own, e.g. to limit memory usage.
+/// Next request for evicted asset loads it from sources again.
+/// Loads that are in flight when their entry is evicted
+/// and handles that were not built yet resolve with [`Cancelled`] error
+/// or [`Evicted`] error for assets requested with path.
+///
+/// [`Cancelled`]: crate::Cancelled
+/// [`Evicted`]: crate::Evicted
+pub trait CacheBackend<K, V>: Send + 'static {
+    /// Returns entry for which `eq` returns `true`.
+    fn get(&self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(&K, &V)>;
+
+    /// Returns entry for which `eq` returns `true`.
+    fn get_mut(&mut self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(&K, &mut V)>;
+
+    /// Inserts new entry.
+    /// Loader never inserts entry with the key that is already present.
+    fn insert(&mut self, hash: u64, key: K, value: V);
+
+    /// Removes and returns entry for which `eq` returns `true`.
+    fn remove(&mut self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(K, V)>;
+
+    /// Retains only entries for which `f` returns `true`.
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &mut V) -> bool);
+}
+
+/// Creates cache backends for the loader.
+///
+/// See [`LoaderBuilder::with_cache_backend`].
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// /// Cache backend that scans all entries.
+/// struct VecCache<K, V>(Vec<(u64, K, V)>);
+///
+/// impl<K: Send + 'static, V: Send + 'static> CacheBackend<K, V> for VecCache<K, V> {
+///     fn get(&self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(&K, &V)> {
+///         let (_, k, v) = self.0.iter().find(|(h, k, _)| *h == hash && eq(k))?;
+///         Some((k, v))
+///     }
+///
+///     fn get_mut(&mut self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(&K, &mut V)> {
+///         let (_, k, v) = self.0.iter_mut().find(|(h, k, _)| *h == hash && eq(k))?;
+///         Some((k, v))
+///     }
+///
+///     fn insert(&mut self, hash: u64, key: K, value: V) {
+///         self.0.push((hash, key, value));
+///     }
+///
+///     fn remove(&mut self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(K, V)> {
+///         let index = self.0.iter().position(|(h, k, _)| *h == hash && eq(k))?;
+///         let (_, k, v) = self.0.swap_remove(index);
+///         Some((k, v))
+///     }
+///
+///     fn retain(&mut self, f: &mut dyn FnMut(&K, &mut V) -> bool) {
+///         self.0.retain_mut(|(_, k, v)| f(k, v));
+///     }
+/// }
+///
+/// struct VecCacheFactory;
+///
+/// impl CacheBackendFactory for VecCacheFactory {
+///     fn new_backend<K: Send + 'static, V: Send + 'static>(&self) -> Box<dyn CacheBackend<K, V>> {
+///         Box::new(VecCache(Vec::new()))
+///     }
+/// }
+///
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let source = MemorySource::new();
+/// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 7 }"#[..]);
+///
+/// let loader = Loader::builder()
+///     .with(source)
+///     .with_cache_backend(VecCacheFactory)
+///     .build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let mut number = loader.load::<Number, _>("number").await?;
+///         assert_eq!(number.build(&mut ())?.value, 7);
+///
+///         // Loaded again from cache.
+///         let mut number = loader.load::<Number, _>("number").poll_loaded().unwrap()?;
+///         assert_eq!(number.build(&mut ())?.value, 7);
+///         Ok::<_, Error>(())
+///     })
+///     .unwrap();
+/// # }
+/// ```
+///
+/// [`LoaderBuilder::with_cache_backend`]: crate::LoaderBuilder::with_cache_backend
+pub trait CacheBackendFactory: Send + Sync + 'static {
+    /// Returns new empty cache backend.
+    fn new_backend<K, V>(&self) -> Box<dyn CacheBackend<K, V>>
+    where
+        K: Send + 'static,
+        V: Send + 'static;
+}
+
+/// Default cache backend.
+/// Keeps all entries in a hash map.
+pub struct HashMapCache<K, V> {
+    map: HashMap<Hashed<K>, V, BuildHasherDefault<PassThrough>>,
+}
+
+impl<K, V> Default for HashMapCache<K, V> {
+    fn default() -> Self {
+        HashMapCache {
+            map: HashMap::default(),
+        }
+    }
+}
+
+impl<K, V> HashMapCache<K, V> {
+    /// Returns new empty [`HashMapCache`].
+    pub fn new() -> Self {
+        HashMapCache::default()
+    }
+}
+
+impl<K, V> CacheBackend<K, V> for HashMapCache<K, V>
+where
+    K: Send + 'static,
+    V: Send + 'static,
+{
+    fn get(&self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(&K, &V)> {
+        let (key, value) = self
+            .map
+            .raw_entry()
+            .from_hash(hash, |hashed| hashed.hash == hash && eq(&hashed.key))?;
+        Some((&key.key, value))
+    }
+
+    fn get_mut(&mut self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(&K, &mut V)> {
+        match self
+            .map
+            .raw_entry_mut()
+            .from_hash(hash, |hashed| hashed.hash == hash && eq(&hashed.key))
+        {
+            RawEntryMut::Vacant(_) => None,
+            RawEntryMut::Occupied(entry) => {
+                let (key, value) = entry.into_key_value();
+                Some((&key.key, value))
+            }
+        }
+    }
+
+    fn insert(&mut self, hash: u64, key: K, value: V) {
+        // Keys are never compared, each insert adds new entry.
+        if let RawEntryMut::Vacant(entry) = self.map.raw_entry_mut().from_hash(hash, |_| false) {
+            entry.insert_hashed_nocheck(hash, Hashed { hash, key }, value);
+        }
+    }
+
+    fn remove(&mut self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(K, V)> {
+        match self
+            .map
+            .raw_entry_mut()
+            .from_hash(hash, |hashed| hashed.hash == hash && eq(&hashed.key))
+        {
+            RawEntryMut::Vacant(_) => None,
+            RawEntryMut::Occupied(entry) => {
+                let (key, value) = entry.remove_entry();
+                Some((key.key, value))
+            }
+        }
+    }
+
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &mut V) -> bool) {
+        self.map.retain(|hashed, value| f(&hashed.key, value));
+    }
+}
+
+/// Factory for [`HashMapCache`] backends.
+/// Used by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HashMapCacheFactory;
+
+impl CacheBackendFactory for HashMapCacheFactory {
+    fn new_backend<K, V>(&self) -> Box<dyn CacheBackend<K, V>>
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        Box::new(HashMapCache::new())
+    }
+}
+
+/// Key with precomputed hash.
+struct Hashed<K> {
+    hash: u64,
+    key: K,
+}
+
+impl<K> Hash for Hashed<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+/// Hasher that returns precomputed hash as is.
+#[derive(Default)]
+struct PassThrough(u64);
+
+impl Hasher for PassThrough {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(byte);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = value;
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Creates backends for both loader caches.
+pub(crate) trait LoaderCacheFactory: Send + Sync {
+    fn asset_backend(&self) -> Box<dyn CacheBackend<TypeKey, AssetState>>;
+    fn path_backend(&self) -> Box<dyn CacheBackend<PathKey, PathState>>;
+}
+
+impl<F> LoaderCacheFactory for F
+where
+    F: CacheBackendFactory,
+{
+    fn asset_backend(&self) -> Box<dyn CacheBackend<TypeKey, AssetState>> {
+        self.new_backend()
+    }
+
+    fn path_backend(&self) -> Box<dyn CacheBackend<PathKey, PathState>> {
+        self.new_backend()
+    }
+}
+
+/// Identity of path entry in [`BoundedPathCache`].
+/// Path of the key is never moved while entry exists, so its address identifies the entry.
+type PathIdentity = (KindKey, usize);
+
+fn path_identity(key: &PathKey) -> PathIdentity {
+    (key.kind, Arc::as_ptr(&key.path) as *const u8 as usize)
+}
+
+/// Path cache backend that evicts least recently used entries
+/// when number of entries exceeds capacity.
+///
+/// Only entries with resolved path that are not referenced by any handle are evicted.
+/// Entries being searched for are never evicted.
+pub(crate) struct BoundedPathCache {
+    inner: Box<dyn CacheBackend<PathKey, PathState>>,
+    capacity: usize,
+    tick: u64,
+
+    /// Last access tick of each entry.
+    recency: HashMap<PathIdentity, u64>,
+
+    /// Entries ordered by last access.
+    order: BTreeMap<u64, (u64, PathIdentity)>,
+}
+
+impl BoundedPathCache {
+    pub(crate) fn new(inner: Box<dyn CacheBackend<PathKey, PathState>>, capacity: usize) -> Self {
+        BoundedPathCache {
+            inner,
+            capacity,
+            tick: 0,
+            recency: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    fn touch(&mut self, hash: u64, identity: PathIdentity) {
+        self.tick += 1;
+        if let Some(old) = self.recency.insert(identity, self.tick) {
+            self.order.remove(&old);
+        }
+        self.order.insert(self.tick, (hash, identity));
+    }
+
+    fn forget(&mut self, identity: PathIdentity) {
+        if let Some(old) = self.recency.remove(&identity) {
+            self.order.remove(&old);
+        }
+    }
+
+    /// Evicts least recently used entries until number of entries fits capacity
+    /// or no more entries can be evicted.
+    fn evict(&mut self) {
+        let mut excess = self.recency.len().saturating_sub(self.capacity);
+        if excess == 0 {
+            return;
+        }
+
+        let mut evicted = Vec::new();
+        for (&tick, &(hash, identity)) in &self.order {
+            if excess == 0 {
+                break;
+            }
+
+            let evictable = match self
+                .inner
+                .get(hash, &mut |key| path_identity(key) == identity)
+            {
+                None => true,
+                Some((_, PathState::Unloaded { .. })) => false,
+                // Key path is shared with handles requested with this path.
+                Some((key, _)) => Arc::strong_count(&key.path) == 1,
+            };
+
+            if evictable {
+                evicted.push((tick, hash, identity));
+                excess -= 1;
+            }
+        }
+
+        for (tick, hash, identity) in evicted {
+            self.order.remove(&tick);
+            self.recency.remove(&identity);
+            self.inner
+                .remove(hash, &mut |key| path_identity(key) == identity);
+        }
+    }
+}
+
+impl CacheBackend<PathKey, PathState> for BoundedPathCache {
+    fn get(
+        &self,
+        hash: u64,
+        eq: &mut dyn FnMut(&PathKey) -> bool,
+    ) -> Option<(&PathKey, &PathState)> {
+        self.inner.get(hash, eq)
+    }
+
+    fn get_mut(
+        &mut self,
+        hash: u64,
+        eq: &mut dyn FnMut(&PathKey) -> bool,
+    ) -> Option<(&PathKey, &mut PathState)> {
+        let identity = path_identity(self.inner.get(hash, eq)?.0);
+        self.touch(hash, identity);
+        self.inner.get_mut(hash, eq)
+    }
+
+    fn insert(&mut self, hash: u64, key: PathKey, value: PathState) {
+        let identity = path_identity(&key);
+        self.inner.insert(hash, key, value);
+        self.touch(hash, identity);
+        self.evict();
+    }
+
+    fn remove(
+        &mut self,
+        hash: u64,
+        eq: &mut dyn FnMut(&PathKey) -> bool,
+    ) -> Option<(PathKey, PathState)> {
+        let (key, value) = self.inner.remove(hash, eq)?;
+        self.forget(path_identity(&key));
+        Some((key, value))
+    }
+
+    fn retain(&mut self, f: &mut dyn FnMut(&PathKey, &mut PathState) -> bool) {
+        let mut removed = Vec::new();
+        self.inner.retain(&mut |key, value| {
+            let keep = f(key, value);
+            if !keep {
+                removed.push(path_identity(key));
+            }
+            keep
+        });
+
+        for identity in removed {
+            self.forget(identity);
+        }
+    }
+}
+
+/// View into a single cache entry.
+pub(crate) enum Entry<'a, K, V, F> {
+    Occupied(OccupiedEntry<'a, K, V, F>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub(crate) struct OccupiedEntry<'a, K, V, F> {
+    backend: &'a mut dyn CacheBackend<K, V>,
+    hash: u64,
+    eq: F,
+}
+
+impl<'a, K, V, F> OccupiedEntry<'a, K, V, F>
+where
+    K: 'static,
+    V: 'static,
+    F: FnMut(&K) -> bool,
+{
+    pub fn key(&mut self) -> &K {
+        self.backend.get(self.hash, &mut self.eq).unwrap().0
+    }
+
+    pub fn get(&mut self) -> &V {
+        self.backend.get(self.hash, &mut self.eq).unwrap().1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.backend.get_mut(self.hash, &mut self.eq).unwrap().1
+    }
+
+    pub fn into_mut(mut self) -> &'a mut V {
+        self.backend.get_mut(self.hash, &mut self.eq).unwrap().1
+    }
+
+    pub fn remove(mut self) -> (K, V) {
+        self.backend.remove(self.hash, &mut self.eq).unwrap()
+    }
+}
+
+pub(crate) struct VacantEntry<'a, K, V> {
+    backend: &'a mut dyn CacheBackend<K, V>,
+    hash: u64,
+}
+
+impl<'a, K: 'static, V: 'static> VacantEntry<'a, K, V> {
+    pub fn insert(self, key: K, value: V) {
+        self.backend.insert(self.hash, key, value);
+    }
+}
+
+impl<K: 'static, V: 'static> dyn CacheBackend<K, V> {
+    /// Returns entry for which `eq` returns `true`, occupied or vacant.
+    pub(crate) fn entry<F>(&mut self, hash: u64, mut eq: F) -> Entry<'_, K, V, F>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        if self.get(hash, &mut eq).is_some() {
+            Entry::Occupied(OccupiedEntry {
+                backend: self,
+                hash,
+                eq,
+            })
+        } else {
+            Entry::Vacant(VacantEntry {
+                backend: self,
+                hash,
+            })
+        }
+    }
+}
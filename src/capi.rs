@@ -0,0 +1,585 @@
+//! C API for embedding the loader into engines written in other languages.
+//!
+//! Asset types are registered on the Rust side with [`register_asset`]
+//! and addressed from C by [`Asset::asset_name`].
+//! C side creates loader from JSON configuration, requests assets by id,
+//! polls handles and builds assets into opaque pointers
+//! that are cloned and dropped through [`ArgosyAssetVTable`].
+//!
+//! Header is generated with `cbindgen --config cbindgen.toml --output include/argosy.h`.
+//!
+//! # Example
+//!
+//! ```
+//! # use std::ffi::{c_void, CStr};
+//! # use argosy::{capi::*, *};
+//! #[derive(Clone, Asset)]
+//! struct Number {
+//!     value: u32,
+//! }
+//!
+//! register_asset::<Number, ()>();
+//!
+//! let dir = std::env::temp_dir().join(format!("argosy-capi-{}", std::process::id()));
+//! std::fs::create_dir_all(&dir).unwrap();
+//! std::fs::write(dir.join(AssetId::new(1).unwrap().to_padded_hex()), r#"{ "value": 7 }"#).unwrap();
+//!
+//! let config = format!(r#"{{ "sources": [{{ "kind": "files", "root": {:?} }}] }}"#, dir);
+//! let config = std::ffi::CString::new(config).unwrap();
+//!
+//! unsafe {
+//!     let loader = argosy_loader_create(config.as_ptr());
+//!     assert!(!loader.is_null());
+//!
+//!     /// Polls handle until it is resolved.
+//!     unsafe fn wait(loader: *mut ArgosyLoader, handle: *mut ArgosyHandle) -> i32 {
+//!         for _ in 0..1000 {
+//!             argosy_loader_pump(loader);
+//!             match argosy_handle_poll(handle) {
+//!                 ARGOSY_PENDING => std::thread::sleep(std::time::Duration::from_millis(1)),
+//!                 status => return status,
+//!             }
+//!         }
+//!         panic!("Asset is not loaded in time");
+//!     }
+//!
+//!     let handle = argosy_load(loader, c"Number".as_ptr(), 1);
+//!     assert_eq!(wait(loader, handle), ARGOSY_READY);
+//!
+//!     let mut asset = std::mem::MaybeUninit::<ArgosyAsset>::uninit();
+//!     assert_eq!(argosy_handle_build(handle, std::ptr::null_mut(), asset.as_mut_ptr()), ARGOSY_READY);
+//!     let asset = asset.assume_init();
+//!     assert_eq!((*asset.ptr.cast::<Number>()).value, 7);
+//!
+//!     let copy = ((*asset.vtable).clone)(asset.ptr);
+//!     ((*asset.vtable).drop)(asset.ptr);
+//!     assert_eq!((*copy.cast::<Number>()).value, 7);
+//!     ((*asset.vtable).drop)(copy);
+//!     argosy_handle_destroy(handle);
+//!
+//!     // Missing asset resolves with error.
+//!     let handle = argosy_load(loader, c"Number".as_ptr(), 2);
+//!     assert_eq!(wait(loader, handle), ARGOSY_ERROR);
+//!
+//!     let mut buf = [0; 256];
+//!     let len = argosy_handle_take_error(handle, buf.as_mut_ptr(), buf.len());
+//!     let message = CStr::from_ptr(buf.as_ptr()).to_str().unwrap();
+//!     assert_eq!(message.len(), len);
+//!     assert!(message.starts_with("Failed to load asset"), "{message}");
+//!     assert_eq!(argosy_handle_take_error(handle, buf.as_mut_ptr(), buf.len()), 0);
+//!     argosy_handle_destroy(handle);
+//!
+//!     // Unknown types are rejected.
+//!     assert!(argosy_load(loader, c"Unknown".as_ptr(), 1).is_null());
+//!
+//!     argosy_loader_destroy(loader);
+//! }
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+//!
+//! Header is kept in sync with the code.
+//!
+//! ```
+//! let dir = env!("CARGO_MANIFEST_DIR");
+//! let config = cbindgen::Config::from_file(format!("{dir}/cbindgen.toml")).unwrap();
+//!
+//! let mut generated = Vec::new();
+//! cbindgen::Builder::new()
+//!     .with_config(config)
+//!     .with_src(format!("{dir}/src/capi.rs"))
+//!     .generate()
+//!     .unwrap()
+//!     .write(&mut generated);
+//!
+//! let header = std::fs::read_to_string(format!("{dir}/include/argosy.h")).unwrap();
+//! assert!(
+//!     header == String::from_utf8(generated).unwrap(),
+//!     "include/argosy.h is outdated. Regenerate it with cbindgen"
+//! );
+//! ```
+
+use std::{
+    ffi::{c_char, c_void, CStr},
+    marker::PhantomData,
+    path::PathBuf,
+    task::{Context, Poll},
+};
+
+use argosy_id::AssetId;
+use futures::{task::noop_waker_ref, Future};
+use parking_lot::RwLock;
+
+use crate::{
+    asset::{Asset, AssetBuild},
+    error::Error,
+    handle::{AssetHandle, LoadedAsset},
+    loader::Loader,
+    names::AssetName,
+    source::{archive::ArchiveSource, fs::FileSource},
+};
+
+/// Handle is not resolved yet.
+pub const ARGOSY_PENDING: i32 = 0;
+
+/// Handle is resolved with loaded asset or asset is built.
+pub const ARGOSY_READY: i32 = 1;
+
+/// Handle is resolved with error or arguments are invalid.
+pub const ARGOSY_ERROR: i32 = -1;
+
+/// Functions to clone and drop built assets of one type.
+#[repr(C)]
+pub struct ArgosyAssetVTable {
+    /// Clones asset and returns pointer to the clone.
+    pub clone: unsafe extern "C" fn(asset: *const c_void) -> *mut c_void,
+
+    /// Drops asset.
+    pub drop: unsafe extern "C" fn(asset: *mut c_void),
+}
+
+/// Built asset.
+#[repr(C)]
+pub struct ArgosyAsset {
+    /// Opaque pointer to the asset.
+    pub ptr: *mut c_void,
+
+    /// Functions to clone and drop the asset.
+    pub vtable: *const ArgosyAssetVTable,
+}
+
+/// Loader created from C.
+pub struct ArgosyLoader {
+    loader: Loader,
+
+    #[cfg(feature = "tokio")]
+    driver: Driver,
+}
+
+/// Handle to the asset requested from C.
+pub struct ArgosyHandle {
+    handle: Box<dyn ErasedHandle>,
+    status: i32,
+    error: Option<String>,
+}
+
+/// Configuration of the loader created from C.
+#[derive(Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LoaderConfig {
+    sources: Vec<SourceConfig>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+enum SourceConfig {
+    /// [`FileSource`] with files in `root` directory.
+    Files { root: PathBuf },
+
+    /// [`ArchiveSource`] opened from `path`.
+    Archive { path: PathBuf },
+}
+
+/// Runs loading tasks on a background thread.
+#[cfg(feature = "tokio")]
+struct Driver {
+    handle: tokio::runtime::Handle,
+    shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(feature = "tokio")]
+impl Driver {
+    fn new() -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()?;
+        let handle = runtime.handle().clone();
+
+        let (shutdown, stop) = tokio::sync::oneshot::channel();
+        let thread = std::thread::Builder::new()
+            .name("argosy-capi".to_owned())
+            .spawn(move || {
+                let _ = runtime.block_on(stop);
+            })?;
+
+        Ok(Driver {
+            handle,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Drop for Driver {
+    fn drop(&mut self) {
+        self.shutdown.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Type-erased asset handle.
+trait ErasedHandle: Send {
+    /// Polls handle without blocking.
+    fn poll(&mut self) -> Poll<Result<(), Error>>;
+
+    /// Builds loaded asset.
+    ///
+    /// # Safety
+    ///
+    /// `builder` must point to valid builder of the registered type
+    /// or be null if builder type is zero-sized.
+    unsafe fn build(&mut self, builder: *mut c_void) -> Result<ArgosyAsset, Error>;
+}
+
+struct TypedHandle<A, B> {
+    handle: AssetHandle<A>,
+    loaded: Option<LoadedAsset<A>>,
+    builder: PhantomData<fn(&mut B)>,
+}
+
+impl<A, B> ErasedHandle for TypedHandle<A, B>
+where
+    A: AssetBuild<B>,
+    B: 'static,
+{
+    fn poll(&mut self) -> Poll<Result<(), Error>> {
+        if self.loaded.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+
+        let mut cx = Context::from_waker(noop_waker_ref());
+        match std::pin::Pin::new(&mut self.handle).poll(&mut cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            Poll::Ready(Ok(loaded)) => {
+                self.loaded = Some(loaded);
+                Poll::Ready(Ok(()))
+            }
+        }
+    }
+
+    unsafe fn build(&mut self, builder: *mut c_void) -> Result<ArgosyAsset, Error> {
+        let loaded = self.loaded.as_mut().expect("Asset must be loaded");
+
+        let builder = if builder.is_null() && std::mem::size_of::<B>() == 0 {
+            std::ptr::NonNull::<B>::dangling().as_ptr()
+        } else {
+            builder.cast::<B>()
+        };
+
+        let asset = loaded.build(&mut *builder)?;
+        Ok(ArgosyAsset {
+            ptr: Box::into_raw(Box::new(asset)).cast(),
+            vtable: &VTableOf::<A>::VTABLE,
+        })
+    }
+}
+
+struct VTableOf<A>(PhantomData<A>);
+
+impl<A: Asset> VTableOf<A> {
+    const VTABLE: ArgosyAssetVTable = ArgosyAssetVTable {
+        clone: clone_asset::<A>,
+        drop: drop_asset::<A>,
+    };
+}
+
+unsafe extern "C" fn clone_asset<A: Asset>(asset: *const c_void) -> *mut c_void {
+    let asset = &*asset.cast::<A>();
+    Box::into_raw(Box::new(asset.clone())).cast()
+}
+
+unsafe extern "C" fn drop_asset<A: Asset>(asset: *mut c_void) {
+    drop(Box::from_raw(asset.cast::<A>()));
+}
+
+type LoadFn = fn(&Loader, AssetId) -> Box<dyn ErasedHandle>;
+
+/// Registered asset types.
+static REGISTRY: RwLock<Vec<(AssetName, LoadFn)>> = parking_lot::const_rwlock(Vec::new());
+
+fn load_typed<A, B>(loader: &Loader, id: AssetId) -> Box<dyn ErasedHandle>
+where
+    A: AssetBuild<B>,
+    B: 'static,
+{
+    Box::new(TypedHandle::<A, B> {
+        handle: loader.load(id),
+        loaded: None,
+        builder: PhantomData,
+    })
+}
+
+/// Registers asset type to be loaded from C by [`Asset::asset_name`].
+/// Assets are built with builder of type `B` that C side passes as pointer.
+///
+/// Registering another type with the same name replaces previous one.
+pub fn register_asset<A, B>()
+where
+    A: AssetBuild<B>,
+    B: 'static,
+{
+    let mut registry = REGISTRY.write();
+    let load: LoadFn = load_typed::<A, B>;
+    match registry
+        .iter_mut()
+        .find(|(name, _)| *name == A::asset_name())
+    {
+        Some(entry) => entry.1 = load,
+        None => registry.push((A::asset_name(), load)),
+    }
+}
+
+fn create_loader(config: Option<&CStr>) -> Result<ArgosyLoader, String> {
+    let config: LoaderConfig = match config {
+        None => LoaderConfig::default(),
+        Some(config) => serde_json::from_slice(config.to_bytes())
+            .map_err(|err| format!("Invalid loader config. {err}"))?,
+    };
+
+    let mut builder = Loader::builder();
+    for source in config.sources {
+        match source {
+            SourceConfig::Files { root } => builder.add(FileSource::new(root)),
+            SourceConfig::Archive { path } => {
+                builder.add(ArchiveSource::open(path).map_err(|err| err.to_string())?)
+            }
+        };
+    }
+
+    Ok(ArgosyLoader {
+        loader: builder.build(),
+
+        #[cfg(feature = "tokio")]
+        driver: Driver::new().map_err(|err| format!("Failed to start loader thread. {err}"))?,
+    })
+}
+
+/// Creates loader from JSON configuration.
+///
+/// Configuration lists sources, e.g.
+/// `{ "sources": [{ "kind": "files", "root": "assets" }, { "kind": "archive", "path": "assets.pak" }] }`.
+/// Null `config_json` creates loader without sources.
+///
+/// Returns null if configuration is invalid.
+///
+/// # Safety
+///
+/// `config_json` must be null or point to NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn argosy_loader_create(config_json: *const c_char) -> *mut ArgosyLoader {
+    let config = (!config_json.is_null()).then(|| CStr::from_ptr(config_json));
+
+    match create_loader(config) {
+        Ok(loader) => Box::into_raw(Box::new(loader)),
+        Err(err) => {
+            tracing::error!("Failed to create loader. {err}");
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Destroys loader.
+/// Handles created by the loader stay valid, but pending ones are never resolved.
+///
+/// # Safety
+///
+/// `loader` must be null or returned by [`argosy_loader_create`] and not destroyed yet.
+#[no_mangle]
+pub unsafe extern "C" fn argosy_loader_destroy(loader: *mut ArgosyLoader) {
+    if !loader.is_null() {
+        drop(Box::from_raw(loader));
+    }
+}
+
+/// Drives loading tasks when argosy is built without `tokio` feature.
+/// Returns number of unfinished loading tasks.
+///
+/// With `tokio` feature tasks run on a background thread and this function returns zero.
+///
+/// # Safety
+///
+/// `loader` must be null or valid loader.
+#[no_mangle]
+pub unsafe extern "C" fn argosy_loader_pump(loader: *mut ArgosyLoader) -> usize {
+    let Some(loader) = loader.as_ref() else {
+        return 0;
+    };
+
+    #[cfg(feature = "tokio")]
+    {
+        let _ = loader;
+        0
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    loader.loader.pump()
+}
+
+/// Requests asset of registered type with specified id.
+///
+/// Returns null if type is not registered or id is zero.
+///
+/// # Safety
+///
+/// `loader` must be null or valid loader.
+/// `type_name` must be null or point to NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn argosy_load(
+    loader: *mut ArgosyLoader,
+    type_name: *const c_char,
+    id: u64,
+) -> *mut ArgosyHandle {
+    let Some(loader) = loader.as_ref() else {
+        return std::ptr::null_mut();
+    };
+    if type_name.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Some(id) = AssetId::new(id) else {
+        return std::ptr::null_mut();
+    };
+
+    let type_name = CStr::from_ptr(type_name).to_bytes();
+    let load = REGISTRY
+        .read()
+        .iter()
+        .find(|(name, _)| name.as_bytes() == type_name)
+        .map(|(_, load)| *load);
+
+    let Some(load) = load else {
+        tracing::error!(
+            "Asset type '{}' is not registered",
+            String::from_utf8_lossy(type_name)
+        );
+        return std::ptr::null_mut();
+    };
+
+    #[cfg(feature = "tokio")]
+    let _guard = loader.driver.handle.enter();
+
+    Box::into_raw(Box::new(ArgosyHandle {
+        handle: load(&loader.loader, id),
+        status: ARGOSY_PENDING,
+        error: None,
+    }))
+}
+
+/// Polls handle without blocking.
+///
+/// Returns [`ARGOSY_PENDING`] while asset is loading,
+/// [`ARGOSY_READY`] when asset is loaded and can be built
+/// and [`ARGOSY_ERROR`] if loading failed.
+///
+/// # Safety
+///
+/// `handle` must be null or valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn argosy_handle_poll(handle: *mut ArgosyHandle) -> i32 {
+    let Some(handle) = handle.as_mut() else {
+        return ARGOSY_ERROR;
+    };
+
+    if handle.status == ARGOSY_PENDING {
+        match handle.handle.poll() {
+            Poll::Pending => {}
+            Poll::Ready(Ok(())) => handle.status = ARGOSY_READY,
+            Poll::Ready(Err(err)) => handle.fail(err),
+        }
+    }
+    handle.status
+}
+
+/// Builds loaded asset with `builder` and writes it to `out`.
+///
+/// Returns [`ARGOSY_READY`] on success,
+/// [`ARGOSY_PENDING`] if asset is not loaded yet
+/// and [`ARGOSY_ERROR`] if loading or building failed.
+/// Built asset must be dropped with its vtable.
+///
+/// # Safety
+///
+/// `handle` must be null or valid handle.
+/// `builder` must point to builder of the type the asset was registered with
+/// or be null if that type is zero-sized.
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn argosy_handle_build(
+    handle: *mut ArgosyHandle,
+    builder: *mut c_void,
+    out: *mut ArgosyAsset,
+) -> i32 {
+    if handle.is_null() || out.is_null() {
+        return ARGOSY_ERROR;
+    }
+
+    let status = argosy_handle_poll(handle);
+    if status != ARGOSY_READY {
+        return status;
+    }
+
+    let handle = &mut *handle;
+    match handle.handle.build(builder) {
+        Ok(asset) => {
+            out.write(asset);
+            ARGOSY_READY
+        }
+        Err(err) => {
+            handle.fail(err);
+            ARGOSY_ERROR
+        }
+    }
+}
+
+/// Takes error message of the failed handle.
+///
+/// Writes NUL-terminated message into `buf`, truncated to fit `len` bytes,
+/// and returns full length of the message without NUL.
+/// Returns zero if there is no error message.
+///
+/// # Safety
+///
+/// `handle` must be null or valid handle.
+/// `buf` must be valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn argosy_handle_take_error(
+    handle: *mut ArgosyHandle,
+    buf: *mut c_char,
+    len: usize,
+) -> usize {
+    let Some(handle) = handle.as_mut() else {
+        return 0;
+    };
+    let Some(error) = handle.error.take() else {
+        return 0;
+    };
+
+    if !buf.is_null() && len > 0 {
+        let written = error.len().min(len - 1);
+        std::ptr::copy_nonoverlapping(error.as_ptr().cast(), buf, written);
+        buf.add(written).write(0);
+    }
+    error.len()
+}
+
+/// Destroys handle.
+///
+/// # Safety
+///
+/// `handle` must be null or valid handle that is not destroyed yet.
+#[no_mangle]
+pub unsafe extern "C" fn argosy_handle_destroy(handle: *mut ArgosyHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+impl ArgosyHandle {
+    fn fail(&mut self, error: Error) {
+        self.status = ARGOSY_ERROR;
+        self.error = Some(error.to_string().replace('\0', " "));
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    any::TypeId,
+    collections::{HashSet, VecDeque},
+    hash::BuildHasher,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use argosy_id::AssetId;
+use futures::future::BoxFuture;
+use hashbrown::HashMap;
+use parking_lot::RwLock;
+
+use crate::{
+    asset::AssetBuild,
+    error::{Error, ErrorCode, ErrorStage},
+    key::KindKey,
+    loader::Loader,
+    publish::Staged,
+};
+
+/// Decodes fresh data of the asset and builds it.
+/// Resolves to `None` if asset is missing.
+type ErasedRebuild =
+    Arc<dyn Fn(Loader, AssetId) -> BoxFuture<'static, Result<Option<Staged>, Error>> + Send + Sync>;
+
+/// Asset types rebuilt when their dependencies reload.
+#[derive(Default)]
+pub(crate) struct Cascades {
+    rebuilds: RwLock<HashMap<TypeId, ErasedRebuild>>,
+}
+
+impl Cascades {
+    pub fn enable<A, B>(&self, builder: Arc<Mutex<B>>)
+    where
+        A: AssetBuild<B>,
+        B: Send + 'static,
+    {
+        let rebuild: ErasedRebuild = Arc::new(move |loader: Loader, id| {
+            let builder = builder.clone();
+            Box::pin(async move {
+                let Some((decoded, version)) = loader.redecode::<A>(id).await? else {
+                    return Ok(None);
+                };
+
+                let mut builder = builder.lock().unwrap_or_else(PoisonError::into_inner);
+                let asset = A::build(&mut builder, decoded).map_err(|err| {
+                    Error::new(err)
+                        .or_code(ErrorCode::BuildFailed)
+                        .with_stage(ErrorStage::Build)
+                })?;
+                Ok(Some(Staged::new(&loader, id, asset, Some(version))))
+            })
+        });
+
+        self.rebuilds.write().insert(TypeId::of::<A>(), rebuild);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rebuilds.read().is_empty()
+    }
+
+    /// Returns rebuild function for the asset kind if cascade is enabled for it.
+    pub fn get(&self, kind: KindKey) -> Option<ErasedRebuild> {
+        // Dynamic assets are not rebuilt.
+        if kind.name_hash != 0 {
+            return None;
+        }
+        self.rebuilds.read().get(&kind.type_id).cloned()
+    }
+}
+
+/// Returns assets that depend on reloaded ones directly or transitively.
+/// Reloaded assets are not included, even if they depend on each other.
+pub(crate) fn dependents<S: BuildHasher>(
+    dependencies: &HashMap<AssetId, Vec<AssetId>, S>,
+    reloaded: &[AssetId],
+) -> HashSet<AssetId> {
+    let mut parents: HashMap<AssetId, Vec<AssetId>> = HashMap::new();
+    for (&parent, children) in dependencies {
+        for &child in children {
+            parents.entry(child).or_default().push(parent);
+        }
+    }
+
+    let mut found = HashSet::new();
+    let mut queue: VecDeque<AssetId> = reloaded.iter().copied().collect();
+    while let Some(id) = queue.pop_front() {
+        for &parent in parents.get(&id).into_iter().flatten() {
+            if !reloaded.contains(&parent) && found.insert(parent) {
+                queue.push_back(parent);
+            }
+        }
+    }
+    found
+}
+
+/// Orders assets so that each is rebuilt after its stale dependencies.
+///
+/// Assets in a cycle cannot be ordered, first of them is rebuilt
+/// while some of its dependencies are still stale, which is reported with `true`.
+pub(crate) fn rebuild_order<S: BuildHasher>(
+    dependencies: &HashMap<AssetId, Vec<AssetId>, S>,
+    stale: &HashSet<AssetId>,
+    targets: &[AssetId],
+) -> Vec<(AssetId, bool)> {
+    // Stale targets reachable from each target through stale assets.
+    let reachable: HashMap<AssetId, HashSet<AssetId>> = targets
+        .iter()
+        .map(|&target| {
+            let mut visited = HashSet::new();
+            let mut stack = vec![target];
+            while let Some(id) = stack.pop() {
+                for &child in dependencies.get(&id).into_iter().flatten() {
+                    if stale.contains(&child) && visited.insert(child) {
+                        stack.push(child);
+                    }
+                }
+            }
+            visited.retain(|id| *id != target && targets.contains(id));
+            (target, visited)
+        })
+        .collect();
+
+    let mut pending: Vec<AssetId> = targets.to_vec();
+    let mut order = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|target| reachable[target].iter().all(|id| !pending.contains(id)));
+
+        let (index, early) = match ready {
+            Some(index) => (index, false),
+            None => (0, true),
+        };
+        order.push((pending.remove(index), early));
+    }
+    order
+}
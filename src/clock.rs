@@ -0,0 +1,304 @@
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "test-util")]
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use futures::future::BoxFuture;
+#[cfg(feature = "test-util")]
+use parking_lot::Mutex;
+
+/// Source of time for time-dependent policies of the loader.
+///
+/// Retry intervals, deadlines, staggered source queries,
+/// build wait warnings and auto-unload grace periods
+/// read time and sleep only through the clock set with [`LoaderBuilder::set_clock`].
+/// Tests may set `VirtualClock` to run them without real waiting.
+///
+/// [`LoaderBuilder::set_clock`]: crate::LoaderBuilder::set_clock
+pub trait Clock: Send + Sync + 'static {
+    /// Returns current time.
+    fn now(&self) -> Instant;
+
+    /// Returns future that resolves after `duration` passes.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Returns future that resolves when `deadline` comes.
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<'static, ()> {
+        self.sleep(deadline.saturating_duration_since(self.now()))
+    }
+}
+
+/// Clock used by default.
+///
+/// With `tokio` feature it is tokio's time, paused and advanced with tokio's test utilities.
+/// Without it sleeping blocks a helper thread.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        #[cfg(feature = "tokio")]
+        return tokio::time::Instant::now().into_std();
+
+        #[cfg(not(feature = "tokio"))]
+        return Instant::now();
+    }
+
+    #[cfg(feature = "tokio")]
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    #[cfg(feature = "tokio")]
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep_until(deadline.into()))
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        use futures::FutureExt;
+
+        let (tx, rx) = futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            std::thread::sleep(duration);
+            let _ = tx.send(());
+        });
+        Box::pin(rx.map(|_| ()))
+    }
+}
+
+/// Clock that moves only when advanced manually.
+///
+/// Sleeps resolve when the clock is advanced past their deadlines,
+/// so timing-dependent behavior is tested deterministically and without real waiting.
+/// Clones share the same time.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use std::time::Duration;
+/// # use futures::FutureExt;
+/// let clock = VirtualClock::new();
+/// let start = clock.now();
+///
+/// let mut short = clock.sleep(Duration::from_millis(10));
+/// let mut long = clock.sleep(Duration::from_millis(30));
+/// assert_eq!(clock.sleepers(), 2);
+///
+/// clock.advance(Duration::from_millis(5));
+/// assert!((&mut short).now_or_never().is_none());
+///
+/// assert_eq!(clock.advance_to_next(), Some(Duration::from_millis(5)));
+/// assert!((&mut short).now_or_never().is_some());
+/// assert!((&mut long).now_or_never().is_none());
+///
+/// assert_eq!(clock.advance_to_next(), Some(Duration::from_millis(20)));
+/// assert!(long.now_or_never().is_some());
+/// assert_eq!(clock.now() - start, Duration::from_millis(30));
+/// assert_eq!(clock.advance_to_next(), None);
+/// ```
+///
+/// Retries of missing assets run on virtual time too.
+///
+/// ```
+/// # use argosy::*;
+/// # use std::time::Duration;
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// async fn settle() {
+///     for _ in 0..20 {
+///         tokio::task::yield_now().await;
+///     }
+/// }
+///
+/// let clock = VirtualClock::new();
+/// let source = MemorySource::new();
+/// let loader = Loader::builder()
+///     .with(source.clone())
+///     .with_clock(clock.clone())
+///     .build();
+///
+/// let retry = LoadOptions::new().with_missing(MissingPolicy::RetryAfter(Duration::from_secs(5)));
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let start = clock.now();
+///         let id = AssetId::new(1).unwrap();
+///         let number = loader.load_with_options::<Number, _>(id, retry);
+///         settle().await;
+///
+///         // Sources are asked again every 5 seconds.
+///         for _ in 0..3 {
+///             assert_eq!(clock.advance_to_next(), Some(Duration::from_secs(5)));
+///             settle().await;
+///         }
+///
+///         source.insert(id, &br#"{ "value": 3 }"#[..]);
+///         assert_eq!(clock.advance_to_next(), Some(Duration::from_secs(5)));
+///         settle().await;
+///
+///         assert_eq!(number.await?.build(&mut ())?.value, 3);
+///         assert_eq!(clock.now() - start, Duration::from_secs(20));
+///
+///         // Deadline cuts retries short.
+///         let options = retry.with_deadline(Duration::from_secs(12));
+///         let missing = loader.load_with_options::<Number, _>(AssetId::new(2).unwrap(), options);
+///         settle().await;
+///
+///         let mut schedule = Vec::new();
+///         while let Some(step) = clock.advance_to_next() {
+///             schedule.push(step.as_secs());
+///             settle().await;
+///         }
+///         assert_eq!(schedule, [5, 5, 2]);
+///         assert!(missing.await.err().unwrap().is::<NotFound>());
+///         Ok::<_, Error>(())
+///     })?;
+/// # Ok::<_, Error>(())
+/// ```
+#[cfg(feature = "test-util")]
+#[derive(Clone)]
+pub struct VirtualClock {
+    inner: Arc<Mutex<VirtualTime>>,
+}
+
+#[cfg(feature = "test-util")]
+struct VirtualTime {
+    now: Instant,
+
+    /// Sleeps by deadline and registration order.
+    sleepers: BTreeMap<(Instant, u64), Option<Waker>>,
+    next_sleeper: u64,
+}
+
+#[cfg(feature = "test-util")]
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl VirtualClock {
+    /// Returns new [`VirtualClock`] stopped at current time.
+    pub fn new() -> Self {
+        VirtualClock {
+            inner: Arc::new(Mutex::new(VirtualTime {
+                now: Instant::now(),
+                sleepers: BTreeMap::new(),
+                next_sleeper: 0,
+            })),
+        }
+    }
+
+    /// Moves the clock forward and wakes sleeps which deadlines have come.
+    pub fn advance(&self, duration: Duration) {
+        let wakers: Vec<Waker> = {
+            let mut time = self.inner.lock();
+            time.now += duration;
+
+            let now = time.now;
+            let later = time.sleepers.split_off(&(now, u64::MAX));
+            let due = std::mem::replace(&mut time.sleepers, later);
+            due.into_values().flatten().collect()
+        };
+
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Moves the clock to the nearest deadline of pending sleeps and wakes them.
+    /// Returns how far the clock moved or `None` if nothing sleeps.
+    pub fn advance_to_next(&self) -> Option<Duration> {
+        let duration = self.next_deadline()?;
+        self.advance(duration);
+        Some(duration)
+    }
+
+    /// Returns time left until the nearest deadline of pending sleeps.
+    pub fn next_deadline(&self) -> Option<Duration> {
+        let time = self.inner.lock();
+        let (&(deadline, _), _) = time.sleepers.first_key_value()?;
+        Some(deadline.saturating_duration_since(time.now))
+    }
+
+    /// Returns number of pending sleeps.
+    pub fn sleepers(&self) -> usize {
+        self.inner.lock().sleepers.len()
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        self.inner.lock().now
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let deadline = self.inner.lock().now + duration;
+        self.sleep_until(deadline)
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<'static, ()> {
+        let mut time = self.inner.lock();
+        if deadline <= time.now {
+            return Box::pin(std::future::ready(()));
+        }
+
+        let key = (deadline, time.next_sleeper);
+        time.next_sleeper += 1;
+        time.sleepers.insert(key, None);
+
+        Box::pin(VirtualSleep {
+            time: self.inner.clone(),
+            key,
+        })
+    }
+}
+
+/// Sleep of [`VirtualClock`].
+#[cfg(feature = "test-util")]
+struct VirtualSleep {
+    time: Arc<Mutex<VirtualTime>>,
+    key: (Instant, u64),
+}
+
+#[cfg(feature = "test-util")]
+impl Future for VirtualSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut time = self.time.lock();
+        match time.sleepers.get_mut(&self.key) {
+            None => Poll::Ready(()),
+            Some(waker) => {
+                match waker {
+                    Some(waker) if waker.will_wake(cx.waker()) => {}
+                    _ => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Drop for VirtualSleep {
+    fn drop(&mut self) {
+        self.time.lock().sleepers.remove(&self.key);
+    }
+}
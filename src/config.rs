@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use crate::{loader::SourceStrategy, source::Source};
+
+/// Sources and runtime settings of the loader.
+///
+/// Applied to running loader with [`Loader::apply_config`].
+/// Settings left unset are not changed.
+///
+/// [`Loader::apply_config`]: crate::Loader::apply_config
+#[derive(Clone, Default)]
+pub struct LoaderConfig {
+    pub(crate) sources: Vec<SourceConfig>,
+    pub(crate) strategy: Option<SourceStrategy>,
+    pub(crate) wait_for_sources: Option<bool>,
+}
+
+/// Labeled source of the [`LoaderConfig`].
+#[derive(Clone)]
+pub(crate) struct SourceConfig {
+    pub label: Arc<str>,
+    pub fingerprint: Arc<str>,
+    pub source: Arc<dyn Source>,
+}
+
+impl LoaderConfig {
+    /// Returns empty [`LoaderConfig`].
+    pub fn new() -> Self {
+        LoaderConfig::default()
+    }
+
+    /// Adds source with unique `label`.
+    /// Sources are prioritized in order they are added.
+    ///
+    /// `fingerprint` identifies source settings, like endpoint URL or archive path.
+    /// Mounted source with the same label and fingerprint is kept as is,
+    /// otherwise it is replaced with `source`.
+    pub fn add_source(
+        &mut self,
+        label: impl Into<Arc<str>>,
+        fingerprint: impl Into<Arc<str>>,
+        source: impl Source,
+    ) -> &mut Self {
+        self.sources.push(SourceConfig {
+            label: label.into(),
+            fingerprint: fingerprint.into(),
+            source: Arc::new(source),
+        });
+        self
+    }
+
+    /// Adds source with unique `label`.
+    ///
+    /// See [`LoaderConfig::add_source`].
+    pub fn with_source(
+        mut self,
+        label: impl Into<Arc<str>>,
+        fingerprint: impl Into<Arc<str>>,
+        source: impl Source,
+    ) -> Self {
+        self.add_source(label, fingerprint, source);
+        self
+    }
+
+    /// Sets how sources are queried for assets.
+    pub fn set_source_strategy(&mut self, strategy: SourceStrategy) -> &mut Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// Sets how sources are queried for assets.
+    pub fn with_source_strategy(mut self, strategy: SourceStrategy) -> Self {
+        self.set_source_strategy(strategy);
+        self
+    }
+
+    /// Sets whether loads of missing assets wait for new sources.
+    ///
+    /// See [`Loader::set_wait_for_sources`].
+    ///
+    /// [`Loader::set_wait_for_sources`]: crate::Loader::set_wait_for_sources
+    pub fn set_wait_for_sources(&mut self, wait: bool) -> &mut Self {
+        self.wait_for_sources = Some(wait);
+        self
+    }
+
+    /// Sets whether loads of missing assets wait for new sources.
+    ///
+    /// See [`Loader::set_wait_for_sources`].
+    ///
+    /// [`Loader::set_wait_for_sources`]: crate::Loader::set_wait_for_sources
+    pub fn with_wait_for_sources(mut self, wait: bool) -> Self {
+        self.set_wait_for_sources(wait);
+        self
+    }
+}
+
+/// Changes made by [`Loader::apply_config`].
+///
+/// [`Loader::apply_config`]: crate::Loader::apply_config
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ConfigDiff {
+    /// Labels of added sources.
+    pub mounted: Vec<Arc<str>>,
+
+    /// Labels of removed sources.
+    pub unmounted: Vec<Arc<str>>,
+
+    /// Labels of sources replaced due to changed fingerprint.
+    pub replaced: Vec<Arc<str>>,
+
+    /// Whether sources kept their labels but changed priority.
+    pub reordered: bool,
+
+    /// Whether source strategy changed.
+    pub strategy_changed: bool,
+
+    /// Whether waiting for sources was enabled or disabled.
+    pub wait_for_sources_changed: bool,
+}
+
+impl ConfigDiff {
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.mounted.is_empty()
+            && self.unmounted.is_empty()
+            && self.replaced.is_empty()
+            && !self.reordered
+            && !self.strategy_changed
+            && !self.wait_for_sources_changed
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use argosy_id::AssetId;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+use smallvec::SmallVec;
+
+use crate::{
+    error::{Error, TypeConflict},
+    key::KindKey,
+};
+
+/// Policy for an asset requested as different types.
+///
+/// Each type decodes asset data on its own,
+/// which most likely fails for all but one of them.
+/// Sub-assets of the same artifact are not conflicting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TypeConflictPolicy {
+    /// Asset is loaded as each requested type.
+    Allow,
+
+    /// Asset is loaded as each requested type
+    /// and a warning naming both types is emitted.
+    #[default]
+    Warn,
+
+    /// Asset requested as another type fails with [`TypeConflict`] error.
+    Deny,
+}
+
+/// Kinds of an asset with their names.
+type KnownKinds = SmallVec<[(KindKey, Arc<str>); 1]>;
+
+/// Types each asset was requested as.
+/// Types are remembered for the loader lifetime,
+/// unloading assets does not forget them.
+pub(crate) struct AssetKinds {
+    policy: TypeConflictPolicy,
+    kinds: Mutex<HashMap<AssetId, KnownKinds>>,
+}
+
+impl AssetKinds {
+    pub fn new(policy: TypeConflictPolicy) -> Self {
+        AssetKinds {
+            policy,
+            kinds: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records that asset is requested as `kind`.
+    /// Returns error if asset is requested as another kind already
+    /// and policy denies it.
+    pub fn check(&self, kind: KindKey, name: &str, id: AssetId) -> Result<(), Error> {
+        if self.policy == TypeConflictPolicy::Allow {
+            return Ok(());
+        }
+
+        let mut kinds = self.kinds.lock();
+        let known = kinds.entry(id).or_default();
+
+        if let Some((_, existing)) = known.iter().find(|(known, _)| *known != kind) {
+            match self.policy {
+                TypeConflictPolicy::Allow => unreachable!(),
+                TypeConflictPolicy::Warn => tracing::warn!(
+                    "Asset '{}' is requested as '{}' while it is already requested as '{}'",
+                    id,
+                    name,
+                    existing
+                ),
+                TypeConflictPolicy::Deny => {
+                    return Err(Error::new(TypeConflict {
+                        id,
+                        existing_type: existing.clone(),
+                        requested_type: name.into(),
+                    }))
+                }
+            }
+        }
+
+        if !known.iter().any(|(known, _)| *known == kind) {
+            known.push((kind, name.into()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use argosy_id::AssetId;
+
+use crate::asset::Asset;
+
+/// Persistent storage for decoded assets.
+///
+/// Configured with [`LoaderBuilder::with_decode_cache`].
+/// Assets loaded with [`Loader::load_cached`] are looked up here
+/// before decoding and stored after successful decoding.
+/// Entries are keyed by asset name, id and version reported by the source.
+///
+/// [`LoaderBuilder::with_decode_cache`]: crate::LoaderBuilder::with_decode_cache
+/// [`Loader::load_cached`]: crate::Loader::load_cached
+pub trait DecodeCache: Send + Sync + 'static {
+    /// Returns encoded decoded asset if cached.
+    fn get(&self, name: &str, id: AssetId, version: u64) -> Option<Vec<u8>>;
+
+    /// Stores encoded decoded asset.
+    /// Replaces entry for the same asset with any version.
+    fn put(&self, name: &str, id: AssetId, version: u64, bytes: &[u8]);
+}
+
+/// Asset type which decoded representation can be stored in [`DecodeCache`].
+///
+/// Decoding of such assets should be deterministic and expensive enough
+/// to outweigh encoding and reading the cache.
+/// Assets loaded from the cache skip [`Asset::decode`],
+/// so assets requested during decoding are not loaded.
+pub trait CacheableDecode: Asset {
+    /// Encodes decoded asset to store in the cache.
+    fn encode_decoded(decoded: &Self::Decoded) -> Vec<u8>;
+
+    /// Decodes asset from cached bytes.
+    /// Returns `None` if bytes can't be decoded,
+    /// in which case asset is decoded from source data.
+    fn decode_cached(bytes: &[u8]) -> Option<Self::Decoded>;
+}
+
+#[cfg(feature = "fs")]
+pub use self::dir::DirDecodeCache;
+
+#[cfg(feature = "fs")]
+mod dir {
+    use std::path::{Path, PathBuf};
+
+    use argosy_id::AssetId;
+
+    use super::DecodeCache;
+
+    /// [`DecodeCache`] that stores entries as files in a directory.
+    ///
+    /// Each asset gets one file that holds its version followed by encoded data.
+    /// I/O errors are logged and treated as cache misses.
+    pub struct DirDecodeCache {
+        root: PathBuf,
+    }
+
+    impl DirDecodeCache {
+        /// Returns cache that stores entries under `root` directory.
+        /// Directory is created when first entry is stored.
+        pub fn new(root: impl Into<PathBuf>) -> Self {
+            DirDecodeCache { root: root.into() }
+        }
+
+        /// Returns directory where entries are stored.
+        pub fn root(&self) -> &Path {
+            &self.root
+        }
+
+        fn entry_path(&self, name: &str, id: AssetId) -> PathBuf {
+            let name: String = name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            self.root.join(name).join(format!("{id:x}"))
+        }
+    }
+
+    impl DecodeCache for DirDecodeCache {
+        fn get(&self, name: &str, id: AssetId, version: u64) -> Option<Vec<u8>> {
+            let path = self.entry_path(name, id);
+            let mut bytes = match std::fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed to read decode cache entry '{}'. {err}",
+                        path.display()
+                    );
+                    return None;
+                }
+            };
+
+            if bytes.len() < 8 || bytes[..8] != version.to_le_bytes() {
+                return None;
+            }
+            bytes.drain(..8);
+            Some(bytes)
+        }
+
+        fn put(&self, name: &str, id: AssetId, version: u64, bytes: &[u8]) {
+            let path = self.entry_path(name, id);
+            let mut data = Vec::with_capacity(8 + bytes.len());
+            data.extend_from_slice(&version.to_le_bytes());
+            data.extend_from_slice(bytes);
+
+            let result = std::fs::create_dir_all(path.parent().unwrap())
+                .and_then(|()| std::fs::write(&path, data));
+
+            if let Err(err) = result {
+                tracing::warn!(
+                    "Failed to write decode cache entry '{}'. {err}",
+                    path.display()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    any::{Any, TypeId},
+    collections::VecDeque,
+    fmt,
+    sync::Arc,
+};
+
+use argosy_id::AssetId;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+
+use crate::{
+    asset::Asset,
+    handle::{AssetHandle, LoadedAsset},
+    names::AssetName,
+};
+
+/// Maximum number of development warnings kept by the loader.
+/// Oldest warnings are dropped first.
+pub(crate) const MAX_DEV_WARNINGS: usize = 256;
+
+/// Warning recorded by the loader in development mode.
+///
+/// See [`Loader::dev_warnings`].
+///
+/// [`Loader::dev_warnings`]: crate::Loader::dev_warnings
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DevWarning {
+    /// Id of the asset with the field, if known.
+    pub parent: Option<AssetId>,
+
+    /// Name of the asset type of the field.
+    pub field: AssetName,
+
+    /// Id of the missing asset replaced with placeholder.
+    pub missing: AssetId,
+}
+
+impl fmt::Display for DevWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.parent {
+            Some(parent) => write!(f, "Asset {parent} refers to missing ")?,
+            None => f.write_str("Missing ")?,
+        }
+        write!(f, "{} {}, placeholder is used", self.field, self.missing)
+    }
+}
+
+/// Placeholder assets by asset type.
+#[derive(Default)]
+pub(crate) struct Placeholders {
+    assets: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Placeholders {
+    pub fn register<A: Asset>(&mut self, asset: A) {
+        self.assets.insert(TypeId::of::<A>(), Arc::new(asset));
+    }
+}
+
+/// Development mode state shared by loader clones.
+pub(crate) struct DevMode {
+    placeholders: Placeholders,
+    warnings: Mutex<VecDeque<DevWarning>>,
+}
+
+impl DevMode {
+    pub fn new(placeholders: Placeholders) -> Self {
+        DevMode {
+            placeholders,
+            warnings: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Returns placeholder to use if asset `missing` referenced from `parent` is not found.
+    pub fn placeholder<A: Asset>(
+        self: &Arc<Self>,
+        parent: Option<AssetId>,
+        missing: AssetId,
+    ) -> Option<Placeholder<A>> {
+        let asset = self.placeholders.assets.get(&TypeId::of::<A>())?;
+        let asset = asset.downcast_ref::<A>().unwrap().clone();
+
+        Some(Placeholder {
+            asset,
+            warning: DevWarning {
+                parent,
+                field: A::asset_name(),
+                missing,
+            },
+            dev: self.clone(),
+        })
+    }
+
+    pub fn warnings(&self) -> Vec<DevWarning> {
+        self.warnings.lock().iter().cloned().collect()
+    }
+
+    fn warn(&self, warning: DevWarning) {
+        tracing::warn!("{warning}");
+
+        let mut warnings = self.warnings.lock();
+        if warnings.len() == MAX_DEV_WARNINGS {
+            warnings.pop_front();
+        }
+        warnings.push_back(warning);
+    }
+}
+
+/// Placeholder for a missing asset.
+pub(crate) struct Placeholder<A> {
+    asset: A,
+    warning: DevWarning,
+    dev: Arc<DevMode>,
+}
+
+impl<A> Placeholder<A> {
+    /// Records warning and returns placeholder in place of the asset of the handle.
+    pub fn resolve(self, handle: &AssetHandle<A>) -> LoadedAsset<A> {
+        self.dev.warn(self.warning);
+        handle.placeholder(self.asset)
+    }
+}
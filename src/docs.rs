@@ -0,0 +1,149 @@
+//! Transitions of loader cache entries for documentation and debugging.
+//!
+//! Loader keeps two kinds of cache entries.
+//! Path entries resolve paths to asset ids,
+//! asset entries hold loaded assets.
+//! [`TRANSITIONS`] lists every state change of both,
+//! debug builds of the loader check their changes against it.
+//!
+//! See [`Loader::dump_entry_graph`] to render the current cache.
+//!
+//! # Example
+//!
+//! ```
+//! # use argosy::{docs::*, EntryStatus};
+//! let states = [
+//!     None,
+//!     Some(EntryStatus::Pending),
+//!     Some(EntryStatus::Loaded),
+//!     Some(EntryStatus::Ready),
+//!     Some(EntryStatus::Missing),
+//!     Some(EntryStatus::Error),
+//! ];
+//! let events = [
+//!     Event::Request,
+//!     Event::Deny,
+//!     Event::Found,
+//!     Event::Decoded,
+//!     Event::NotFound,
+//!     Event::Failed,
+//!     Event::Retry,
+//!     Event::Restart,
+//!     Event::Cancel,
+//!     Event::Built,
+//!     Event::Reload,
+//!     Event::Publish,
+//!     Event::Remove,
+//! ];
+//!
+//! for entry in [EntryKind::Path, EntryKind::Asset] {
+//!     for from in states {
+//!         for event in events {
+//!             // Transitions are deterministic.
+//!             let rows = TRANSITIONS
+//!                 .iter()
+//!                 .filter(|t| t.entry == entry && t.from == from && t.event == event)
+//!                 .count();
+//!             assert!(rows <= 1, "{entry:?} {from:?} {event:?}");
+//!
+//!             match transition(entry, from, event) {
+//!                 // Only pending entries become pending.
+//!                 Some(Some(EntryStatus::Pending)) => assert!(matches!(
+//!                     event,
+//!                     Event::Request | Event::Retry | Event::Restart
+//!                 )),
+//!                 // Pending entries are removed only by cancellation.
+//!                 Some(None) if from == Some(EntryStatus::Pending) => {
+//!                     assert_eq!(event, Event::Cancel)
+//!                 }
+//!                 _ => {}
+//!             }
+//!         }
+//!     }
+//!
+//!     // Path entries are never ready or failed.
+//!     if entry == EntryKind::Path {
+//!         assert!(TRANSITIONS.iter().filter(|t| t.entry == entry).all(|t| {
+//!             !matches!(t.to, Some(EntryStatus::Ready | EntryStatus::Error))
+//!         }));
+//!     }
+//!
+//!     // Each state is reachable from absent entry and can be removed or left.
+//!     let mut reached = vec![None];
+//!     while let Some(t) = TRANSITIONS
+//!         .iter()
+//!         .find(|t| t.entry == entry && reached.contains(&t.from) && !reached.contains(&t.to))
+//!     {
+//!         reached.push(t.to);
+//!     }
+//!     for state in reached.iter().flatten() {
+//!         assert!(TRANSITIONS
+//!             .iter()
+//!             .any(|t| t.entry == entry && t.from == Some(*state) && t.to != Some(*state)));
+//!     }
+//!     let expected = match entry {
+//!         EntryKind::Path => 4,
+//!         EntryKind::Asset => 6,
+//!     };
+//!     assert_eq!(reached.len(), expected);
+//! }
+//!
+//! // Diagram is rendered from the same table.
+//! let dot = state_machine_dot();
+//! assert!(dot.starts_with("digraph"));
+//! assert!(dot.contains(r#"asset_Loaded -> asset_Ready [label="Built"];"#));
+//! assert!(dot.contains(r#"asset_Ready -> asset_Loaded [label="Reload"];"#));
+//! assert!(dot.contains(r#"path_Pending -> path_Loaded [label="Found"];"#));
+//! assert_eq!(dot.matches(" -> ").count(), TRANSITIONS.len());
+//! ```
+//!
+//! [`Loader::dump_entry_graph`]: crate::Loader::dump_entry_graph
+
+use std::fmt::Write;
+
+use crate::loader::EntryStatus;
+
+pub use crate::transition::{transition, EntryKind, Event, Transition, TRANSITIONS};
+
+/// Returns designed state transition diagram of loader cache entries
+/// in DOT format.
+pub fn state_machine_dot() -> String {
+    let mut dot = String::from("digraph argosy {\n");
+    for (entry, prefix) in [(EntryKind::Path, "path"), (EntryKind::Asset, "asset")] {
+        writeln!(dot, "  subgraph cluster_{prefix} {{").unwrap();
+        writeln!(dot, "    label=\"{entry:?} entries\";").unwrap();
+        writeln!(dot, "    {prefix}_Absent [shape=point];").unwrap();
+
+        for t in TRANSITIONS.iter().filter(|t| t.entry == entry) {
+            writeln!(
+                dot,
+                "    {prefix}_{} -> {prefix}_{} [label=\"{:?}\"];",
+                state_name(t.from),
+                state_name(t.to),
+                t.event
+            )
+            .unwrap();
+        }
+        dot.push_str("  }\n");
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn state_name(state: Option<EntryStatus>) -> String {
+    match state {
+        None => "Absent".to_owned(),
+        Some(status) => format!("{status:?}"),
+    }
+}
+
+/// Returns fill color of the node in entry state.
+pub(crate) fn state_color(status: EntryStatus) -> &'static str {
+    match status {
+        EntryStatus::Pending => "lightyellow",
+        EntryStatus::Loaded => "lightblue",
+        EntryStatus::Ready => "palegreen",
+        EntryStatus::Missing => "lightgray",
+        EntryStatus::Error => "salmon",
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    any::{Any, TypeId},
+    collections::hash_map::DefaultHasher,
+    fmt,
+    future::{ready, Future, Ready},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use futures::future::BoxFuture;
+use hashbrown::HashMap;
+
+use crate::{
+    asset::{Asset, AssetBuild},
+    error::Error,
+    key::KindKey,
+    loader::{AssetKind, ErasedDecodedState, Loader},
+    names::AssetName,
+    update::ReloadDecode,
+};
+
+/// Value of a dynamic asset.
+///
+/// Wraps any shared value and provides helper downcasts.
+#[derive(Clone)]
+pub struct DynValue(Arc<dyn Any + Send + Sync>);
+
+impl fmt::Debug for DynValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DynValue(..)")
+    }
+}
+
+impl DynValue {
+    /// Wraps a value.
+    #[inline]
+    pub fn new<T>(value: T) -> Self
+    where
+        T: Any + Send + Sync,
+    {
+        DynValue(Arc::new(value))
+    }
+
+    /// Wraps already shared value.
+    #[inline]
+    pub fn from_arc(value: Arc<dyn Any + Send + Sync>) -> Self {
+        DynValue(value)
+    }
+
+    /// Checks if the value is of given type.
+    #[inline]
+    pub fn is<T: Any>(&self) -> bool {
+        self.0.is::<T>()
+    }
+
+    /// Returns reference to the value if it is of given type.
+    #[inline]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+
+    /// Returns shared value if it is of given type.
+    /// Returns `self` back otherwise.
+    #[inline]
+    pub fn downcast<T>(self) -> Result<Arc<T>, Self>
+    where
+        T: Any + Send + Sync,
+    {
+        if self.0.is::<T>() {
+            Ok(self.0.downcast().unwrap())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Returns shared value.
+    #[inline]
+    pub fn into_inner(self) -> Arc<dyn Any + Send + Sync> {
+        self.0
+    }
+}
+
+type DecodeFn =
+    dyn Fn(Box<[u8]>, &Loader) -> BoxFuture<'static, Result<DynValue, Error>> + Send + Sync;
+type BuildFn = dyn Fn(&mut dyn Any, DynValue) -> Result<DynValue, Error> + Send + Sync;
+
+struct Inner {
+    name: AssetName,
+    name_hash: u64,
+    decode: Box<DecodeFn>,
+    builds: HashMap<TypeId, Box<BuildFn>>,
+}
+
+/// Describes asset kind defined at runtime.
+///
+/// Allows loading assets whose types are not known at compile time,
+/// e.g. defined by scripts or plugins.
+/// Assets are loaded with [`Loader::load_dyn`] and are represented by [`DynValue`].
+///
+/// Assets of different kinds are cached separately, keyed by descriptor name.
+/// Descriptors with the same name share the cache.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use futures::future::BoxFuture;
+/// # #[cfg(feature = "tokio")] {
+/// # struct Bytes;
+/// # impl Source for Bytes {
+/// #     fn find<'a>(&'a self, _: &'a str, _: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+/// #         Box::pin(async { None })
+/// #     }
+/// #     fn load<'a>(&'a self, _: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+/// #         Box::pin(async { Ok(Some(AssetData { bytes: (*b"hello").into(), version: 0, properties: AssetProperties::new() })) })
+/// #     }
+/// #     fn update<'a>(&'a self, id: AssetId, _: u64) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+/// #         self.load(id)
+/// #     }
+/// # }
+/// let text = DynAssetDescriptor::builder("text", |bytes, _| async move {
+///     Ok(DynValue::new(String::from_utf8_lossy(&bytes).into_owned()))
+/// })
+/// .with_build(|log: &mut Vec<String>, value| {
+///     log.push(value.downcast_ref::<String>().unwrap().clone());
+///     Ok(value)
+/// })
+/// .build();
+///
+/// let length = DynAssetDescriptor::builder("length", |bytes, _| async move {
+///     Ok(DynValue::new(bytes.len()))
+/// })
+/// .build();
+///
+/// let loader = Loader::builder().with(Bytes).build();
+/// let id = AssetId::new(1).unwrap();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let mut log = Vec::<String>::new();
+///
+///         // Same id is loaded separately for each kind.
+///         let text = loader.load_dyn(&text, id).await?.build(&mut log)?;
+///         let length = loader.load_dyn(&length, id).await?.build(&mut ())?;
+///
+///         assert_eq!(text.downcast_ref::<String>().unwrap(), "hello");
+///         assert_eq!(*length.downcast_ref::<usize>().unwrap(), 5);
+///         assert_eq!(log, ["hello"]);
+///         Ok::<_, Error>(())
+///     })
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DynAssetDescriptor {
+    inner: Arc<Inner>,
+}
+
+impl DynAssetDescriptor {
+    /// Returns [`DynAssetDescriptorBuilder`] for asset kind with given name
+    /// and closure to decode assets from bytes loaded from asset source.
+    pub fn builder<F, Fut>(name: impl Into<Box<str>>, decode: F) -> DynAssetDescriptorBuilder
+    where
+        F: Fn(Box<[u8]>, &Loader) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<DynValue, Error>> + Send + 'static,
+    {
+        DynAssetDescriptorBuilder {
+            name: name.into(),
+            decode: Box::new(move |bytes, loader| Box::pin(decode(bytes, loader))),
+            builds: HashMap::new(),
+        }
+    }
+
+    /// Returns asset kind name.
+    #[inline]
+    pub fn name(&self) -> AssetName {
+        self.inner.name
+    }
+}
+
+/// Builder for [`DynAssetDescriptor`].
+pub struct DynAssetDescriptorBuilder {
+    name: Box<str>,
+    decode: Box<DecodeFn>,
+    builds: HashMap<TypeId, Box<BuildFn>>,
+}
+
+impl DynAssetDescriptorBuilder {
+    /// Adds closure to build decoded assets with builder of type `B`.
+    ///
+    /// If there is no closure for builder type,
+    /// decoded value is used as asset as is.
+    pub fn add_build<B, F>(&mut self, build: F) -> &mut Self
+    where
+        B: 'static,
+        F: Fn(&mut B, DynValue) -> Result<DynValue, Error> + Send + Sync + 'static,
+    {
+        self.builds.insert(
+            TypeId::of::<B>(),
+            Box::new(move |builder, value| build(builder.downcast_mut().unwrap(), value)),
+        );
+        self
+    }
+
+    /// Adds closure to build decoded assets with builder of type `B`.
+    ///
+    /// If there is no closure for builder type,
+    /// decoded value is used as asset as is.
+    pub fn with_build<B, F>(mut self, build: F) -> Self
+    where
+        B: 'static,
+        F: Fn(&mut B, DynValue) -> Result<DynValue, Error> + Send + Sync + 'static,
+    {
+        self.add_build(build);
+        self
+    }
+
+    /// Builds and returns new [`DynAssetDescriptor`] instance.
+    pub fn build(self) -> DynAssetDescriptor {
+        let mut hasher = DefaultHasher::new();
+        self.name.hash(&mut hasher);
+
+        DynAssetDescriptor {
+            inner: Arc::new(Inner {
+                name_hash: hasher.finish(),
+                name: AssetName::intern(&self.name),
+                decode: self.decode,
+                builds: self.builds,
+            }),
+        }
+    }
+}
+
+/// Marker type that keys dynamic assets in the loader caches.
+enum DynAsset {}
+
+impl AssetKind for DynAssetDescriptor {
+    type Fut = BoxFuture<'static, Result<ErasedDecodedState, Error>>;
+
+    #[inline]
+    fn key(&self) -> KindKey {
+        KindKey::dynamic(TypeId::of::<DynAsset>(), self.inner.name_hash)
+    }
+
+    #[inline]
+    fn name(&self) -> &str {
+        self.inner.name.as_str()
+    }
+
+    fn decode(&self, bytes: Box<[u8]>, loader: &Loader) -> Self::Fut {
+        let fut = (self.inner.decode)(bytes, loader);
+        let descriptor = self.clone();
+
+        Box::pin(async move {
+            let value = fut.await?;
+            let decoded = DynDecoded { value, descriptor };
+            Ok(Arc::new(spin::Mutex::new(Some(decoded))) as ErasedDecodedState)
+        })
+    }
+
+    fn reload_decode(&self) -> Option<ReloadDecode> {
+        let descriptor = self.clone();
+        Some(Arc::new(move |bytes, loader| {
+            descriptor.decode(bytes, loader)
+        }))
+    }
+}
+
+/// Decoded representation of a dynamic asset.
+pub struct DynDecoded {
+    value: DynValue,
+    descriptor: DynAssetDescriptor,
+}
+
+/// Error returned when [`DynValue`] is loaded as regular asset
+/// instead of using [`Loader::load_dyn`].
+#[derive(Debug, thiserror::Error)]
+#[error("Dynamic assets must be loaded with `Loader::load_dyn`")]
+pub struct NoDescriptor;
+
+impl Asset for DynValue {
+    type Decoded = DynDecoded;
+    type DecodeError = Error;
+    type BuildError = Error;
+    type Fut = Ready<Result<DynDecoded, Error>>;
+
+    #[inline]
+    fn asset_name() -> AssetName {
+        AssetName::new("DynAsset")
+    }
+
+    #[inline]
+    fn decode(_bytes: Box<[u8]>, _loader: &Loader) -> Self::Fut {
+        ready(Err(Error::new(NoDescriptor)))
+    }
+}
+
+impl<B> AssetBuild<B> for DynValue
+where
+    B: 'static,
+{
+    fn build(builder: &mut B, decoded: DynDecoded) -> Result<DynValue, Error> {
+        match decoded.descriptor.inner.builds.get(&TypeId::of::<B>()) {
+            None => Ok(decoded.value),
+            Some(build) => build(builder, decoded.value),
+        }
+    }
+}
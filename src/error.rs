@@ -1,10 +1,64 @@
-use std::{fmt, sync::Arc};
+use std::{any::Any, fmt, sync::Arc};
 
 use argosy_id::AssetId;
 
-use crate::asset::Asset;
+use crate::{asset::Asset, names::AssetName};
 
 /// Error value that is returned from fallible methods when asset is missing.
+///
+/// Handle makes this error once when it finds asset missing,
+/// polling it again returns the same error without allocating.
+///
+/// # Example
+///
+/// ```
+/// # use std::{alloc::{GlobalAlloc, Layout, System}, sync::atomic::{AtomicUsize, Ordering}};
+/// # use argosy::*;
+/// # use futures::FutureExt;
+/// # #[cfg(feature = "tokio")] {
+/// /// Allocator that counts allocations.
+/// struct Counting;
+///
+/// static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+///
+/// unsafe impl GlobalAlloc for Counting {
+///     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+///         ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+///         System.alloc(layout)
+///     }
+///
+///     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+///         System.dealloc(ptr, layout)
+///     }
+/// }
+///
+/// #[global_allocator]
+/// static GLOBAL: Counting = Counting;
+///
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let loader = Loader::builder().with(MemorySource::new()).build();
+///
+/// let mut handle = tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let mut handle = loader.load::<Number, _>("missing");
+///         assert!((&mut handle).await.err().unwrap().is_not_found());
+///         handle
+///     });
+///
+/// let mut cx = std::task::Context::from_waker(futures::task::noop_waker_ref());
+/// let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+/// for _ in 0..1000 {
+///     assert!(matches!(handle.poll_unpin(&mut cx), std::task::Poll::Ready(Err(_))));
+/// }
+/// assert_eq!(ALLOCATIONS.load(Ordering::Relaxed), allocations);
+/// # }
+/// ```
 #[derive(thiserror::Error)]
 pub struct NotFound {
     /// Path that was used to search for the asset.
@@ -35,6 +89,128 @@ impl fmt::Debug for NotFound {
     }
 }
 
+impl NotFound {
+    /// Returns [`Error`] with this value and stage at which asset was found missing.
+    pub(crate) fn into_error(self) -> Error {
+        let stage = match self.id {
+            None => ErrorStage::Find,
+            Some(_) => ErrorStage::SourceLoad,
+        };
+        Error::new(self)
+            .with_code(ErrorCode::NotFound)
+            .with_stage(stage)
+    }
+}
+
+/// Error value that is returned from fallible methods when asset loading was cancelled.
+///
+/// See [`Loader::cancel`] and [`AbortSignal`].
+///
+/// [`Loader::cancel`]: crate::Loader::cancel
+/// [`AbortSignal`]: crate::AbortSignal
+#[derive(Debug, thiserror::Error)]
+#[error("Loading of asset '{id}' was cancelled")]
+pub struct Cancelled {
+    /// Asset identifier.
+    pub id: AssetId,
+}
+
+/// Error value that is returned when asset requested with path
+/// was evicted from the loader cache before it was found.
+///
+/// Reported with [`ErrorCode::Cancelled`].
+/// See [`CacheBackend`].
+///
+/// [`CacheBackend`]: crate::CacheBackend
+#[derive(Debug, thiserror::Error)]
+#[error("Search of asset '{path}' was cancelled by eviction")]
+pub struct Evicted {
+    /// Path of the asset.
+    pub path: Arc<str>,
+}
+
+/// Error value that is returned when asset is built
+/// while it is being built asynchronously.
+///
+/// See [`LoadedAsset::build_async`].
+///
+/// [`LoadedAsset::build_async`]: crate::LoadedAsset::build_async
+#[cfg(feature = "async-build")]
+#[derive(Debug, thiserror::Error)]
+#[error("Asset '{id}' is being built asynchronously")]
+pub struct BuildInProgress {
+    /// Asset identifier.
+    pub id: AssetId,
+}
+
+/// Error value that is returned when relative path cannot be resolved
+/// because asset being decoded was not requested with a path.
+///
+/// See [`Loader::load_relative`].
+///
+/// [`Loader::load_relative`]: crate::Loader::load_relative
+#[derive(Debug, thiserror::Error)]
+#[error("Cannot resolve relative path '{path}' without path of the parent asset")]
+pub struct NoParentPath {
+    /// Relative path that was requested.
+    pub path: Arc<str>,
+
+    /// Identifier of the asset being decoded.
+    /// `None` if loader is not decoding any asset.
+    pub parent: Option<AssetId>,
+}
+
+/// Error value that is returned when asset is requested as a type
+/// while it is already requested as another one.
+///
+/// See [`TypeConflictPolicy::Deny`].
+///
+/// [`TypeConflictPolicy::Deny`]: crate::TypeConflictPolicy::Deny
+#[derive(Debug, thiserror::Error)]
+#[error("Asset '{id}' is requested as '{requested_type}' while it is already requested as '{existing_type}'")]
+pub struct TypeConflict {
+    /// Asset identifier.
+    pub id: AssetId,
+
+    /// Name of the type asset was requested as first.
+    pub existing_type: Arc<str>,
+
+    /// Name of the type that is denied.
+    pub requested_type: Arc<str>,
+}
+
+/// Error value that is returned when configuration
+/// has several sources with the same label.
+///
+/// See [`Loader::apply_config`].
+///
+/// [`Loader::apply_config`]: crate::Loader::apply_config
+#[derive(Debug, thiserror::Error)]
+#[error("Source label '{label}' is used more than once")]
+pub struct DuplicateSourceLabel {
+    /// Duplicate label.
+    pub label: Arc<str>,
+}
+
+/// Error value that is returned when two asset types
+/// registered with the loader have the same [`Asset::asset_name`].
+///
+/// See [`LoaderBuilder::register_asset`].
+///
+/// [`LoaderBuilder::register_asset`]: crate::LoaderBuilder::register_asset
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("Asset name '{name}' is used by both '{first}' and '{second}'")]
+pub struct DuplicateAssetName {
+    /// Asset name.
+    pub name: AssetName,
+
+    /// Name of the type registered first.
+    pub first: &'static str,
+
+    /// Name of the type registered with the same asset name.
+    pub second: &'static str,
+}
+
 /// Error that can be returned from methods of handlers.
 /// This type wraps any error that can occur during asset loading and building.
 ///
@@ -48,82 +224,379 @@ impl fmt::Debug for NotFound {
 ///
 /// If asset decoding failed, the error would contain [`A::DecodeError`].
 /// If asset building failed, the error would contain [`A::BuildError`].
+///
+/// Stage at which the error occurred is available with [`Error::stage`].
+/// Kind of failure is available with [`Error::code`],
+/// which is matched without downcasting.
 #[derive(Clone)]
-#[repr(transparent)]
-pub struct Error(Arc<dyn std::error::Error + Send + Sync>);
+pub struct Error {
+    inner: Arc<dyn std::error::Error + Send + Sync>,
+    stage: ErrorStage,
+    code: ErrorCode,
+}
+
+/// Stage of asset loading at which an [`Error`] occurred.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// #[derive(Clone, Asset)]
+/// #[asset(checked)]
+/// struct Checked {
+///     value: u32,
+/// }
+///
+/// /// Asset that never builds.
+/// #[derive(Clone)]
+/// struct Broken;
+///
+/// impl Asset for Broken {
+///     type Decoded = ();
+///     type DecodeError = std::convert::Infallible;
+///     type BuildError = std::io::Error;
+///     type Fut = std::future::Ready<Result<(), std::convert::Infallible>>;
+///
+///     fn asset_name() -> AssetName {
+///         AssetName::new("Broken")
+///     }
+///
+///     fn decode(_: Box<[u8]>, _: &Loader) -> Self::Fut {
+///         std::future::ready(Ok(()))
+///     }
+/// }
+///
+/// impl AssetBuild<()> for Broken {
+///     fn build(_: &mut (), _: ()) -> Result<Broken, std::io::Error> {
+///         Err(std::io::ErrorKind::Other.into())
+///     }
+/// }
+///
+/// /// Source that fails to load anything.
+/// struct Failing;
+///
+/// impl Source for Failing {
+///     fn find<'a>(&'a self, _: &'a str, _: &'a str) -> futures::future::BoxFuture<'a, Option<AssetId>> {
+///         Box::pin(async { None })
+///     }
+///
+///     fn load<'a>(&'a self, id: AssetId) -> futures::future::BoxFuture<'a, Result<Option<AssetData>, Error>> {
+///         Box::pin(async move {
+///             match id.value().get() {
+///                 100 => Err(Error::new(std::io::Error::from(std::io::ErrorKind::PermissionDenied))),
+///                 101 => Err(Error::new(std::io::Error::from(std::io::ErrorKind::InvalidData))
+///                     .with_code(ErrorCode::TooLarge)),
+///                 // Slow load that is cancelled before it finishes.
+///                 200 => {
+///                     for _ in 0..10 {
+///                         tokio::task::yield_now().await;
+///                     }
+///                     Ok(None)
+///                 }
+///                 _ => Ok(None),
+///             }
+///         })
+///     }
+///
+///     fn update<'a>(&'a self, _: AssetId, _: u64) -> futures::future::BoxFuture<'a, Result<Option<AssetData>, Error>> {
+///         Box::pin(async { Ok(None) })
+///     }
+/// }
+///
+/// let id = |value| AssetId::new(value).unwrap();
+///
+/// let source = MemorySource::new();
+/// source.insert(id(1), &br#"{ "value": "seven" }"#[..]);
+/// source.insert(id(2), format!(r#"{{ "value": 1, "__argosy_schema": {} }}"#, Checked::SCHEMA ^ 1).into_bytes());
+/// source.insert(id(3), &b""[..]);
+///
+/// let loader = Loader::builder().with(Failing).with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let err = loader.load::<Number, _>("missing").await.err().unwrap();
+///         assert_eq!(err.stage(), ErrorStage::Find);
+///         assert_eq!(err.code(), ErrorCode::NotFound);
+///
+///         let err = loader.load::<Number, _>(id(4)).await.err().unwrap();
+///         assert_eq!(err.stage(), ErrorStage::SourceLoad);
+///         assert_eq!(err.code(), ErrorCode::NotFound);
+///
+///         let err = loader.load::<Number, _>(id(100)).await.err().unwrap();
+///         assert_eq!(err.stage(), ErrorStage::SourceLoad);
+///         assert_eq!(err.code(), ErrorCode::SourceIo);
+///         assert!(err.is::<std::io::Error>());
+///
+///         // Codes set by sources are kept.
+///         let err = loader.load::<Number, _>(id(101)).await.err().unwrap();
+///         assert_eq!(err.stage(), ErrorStage::SourceLoad);
+///         assert_eq!(err.code(), ErrorCode::TooLarge);
+///
+///         let err = loader.load::<Number, _>(id(1)).await.err().unwrap();
+///         assert_eq!(err.stage(), ErrorStage::Decode);
+///         assert_eq!(err.code(), ErrorCode::DecodeFailed);
+///         assert!(err.is_decode_error::<Number>());
+///
+///         let err = loader.load::<Checked, _>(id(2)).await.err().unwrap();
+///         assert_eq!(err.stage(), ErrorStage::Validate);
+///         assert_eq!(err.code(), ErrorCode::SchemaMismatch);
+///         assert!(err.is_decode_error::<Checked>());
+///
+///         let err = loader.load::<Broken, _>(id(3)).await.unwrap().build(&mut ()).err().unwrap();
+///         assert_eq!(err.stage(), ErrorStage::Build);
+///         assert_eq!(err.code(), ErrorCode::BuildFailed);
+///         assert!(err.is_build_error::<Broken>());
+///
+///         let handle = loader.load::<Number, _>(id(200));
+///         tokio::task::yield_now().await;
+///         assert!(loader.cancel::<Number>(id(200)));
+///         let err = handle.await.err().unwrap();
+///         assert_eq!(err.code(), ErrorCode::Cancelled);
+///         assert!(err.is_cancelled());
+///
+///         // Errors created by user code have unknown stage and code.
+///         assert_eq!(Error::new(std::fmt::Error).stage(), ErrorStage::Unknown);
+///         assert_eq!(Error::new(std::fmt::Error).code(), ErrorCode::Unknown);
+///     });
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ErrorStage {
+    /// Stage is not known.
+    /// Errors created with [`Error::new`] have this stage.
+    #[default]
+    Unknown,
+
+    /// Asset requested by path was not found in any source.
+    Find,
+
+    /// Source failed to load asset data or no source has the asset.
+    SourceLoad,
+
+    /// Asset data failed to decode.
+    Decode,
+
+    /// Asset data is decoded but does not match expected schema.
+    /// See [`CheckedAsset`].
+    ///
+    /// [`CheckedAsset`]: crate::CheckedAsset
+    Validate,
+
+    /// Decoded asset failed to build.
+    Build,
+}
+
+/// Kind of failure an [`Error`] reports.
+///
+/// Codes are stable and documented, unlike types of wrapped errors,
+/// so they are matched without downcasting.
+/// Numeric values never change and are not reused.
+///
+/// See [`ErrorStage`] example for codes of each failure in the loader.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// let error = Error::new(std::io::Error::from(std::io::ErrorKind::TimedOut))
+///     .with_code(ErrorCode::Timeout);
+///
+/// match error.code() {
+///     ErrorCode::NotFound => unreachable!(),
+///     ErrorCode::Timeout => {}
+///     code => panic!("unexpected {code}"),
+/// }
+/// assert_eq!(ErrorCode::Timeout as u32, 6);
+///
+/// // Wrapped error is still available.
+/// assert!(error.is::<std::io::Error>());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u32)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// Kind of failure is not known.
+    /// Errors created with [`Error::new`] have this code.
+    #[default]
+    Unknown = 0,
+
+    /// Asset is not found in any source.
+    /// Also reported when [`LoadOptions::deadline`] passes.
+    ///
+    /// [`LoadOptions::deadline`]: crate::LoadOptions::deadline
+    NotFound = 1,
+
+    /// Source failed to load asset data.
+    SourceIo = 2,
+
+    /// Asset data failed to decode.
+    DecodeFailed = 3,
+
+    /// Decoded asset failed to build.
+    BuildFailed = 4,
+
+    /// Loading or building of the asset was cancelled.
+    Cancelled = 5,
+
+    /// Operation took too long.
+    Timeout = 6,
+
+    /// Asset data is too large.
+    TooLarge = 7,
+
+    /// Assets depend on each other cyclically.
+    Cyclic = 8,
+
+    /// Asset data does not match expected schema.
+    /// See [`CheckedAsset`].
+    ///
+    /// [`CheckedAsset`]: crate::CheckedAsset
+    SchemaMismatch = 9,
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
 
 impl Error {
     /// Creates a new [`Error`] from any error type.
+    ///
+    /// If `error` is already an [`Error`] it is returned as is.
     pub fn new<E>(error: E) -> Self
     where
         E: std::error::Error + Send + Sync + 'static,
     {
-        Error(Arc::new(error))
+        let mut error = Some(error);
+        if let Some(error) = (&mut error as &mut dyn Any).downcast_mut::<Option<Error>>() {
+            return error.take().unwrap();
+        }
+        Error {
+            inner: Arc::new(error.unwrap()),
+            stage: ErrorStage::Unknown,
+            code: ErrorCode::Unknown,
+        }
+    }
+
+    /// Returns this error with specified code.
+    #[inline]
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Returns this error with specified code
+    /// unless it already has a known one.
+    #[inline]
+    pub(crate) fn or_code(self, code: ErrorCode) -> Self {
+        match self.code {
+            ErrorCode::Unknown => self.with_code(code),
+            _ => self,
+        }
+    }
+
+    /// Returns code of this error.
+    #[inline]
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// Returns this error tagged with specified stage.
+    #[inline]
+    pub(crate) fn with_stage(mut self, stage: ErrorStage) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    /// Returns stage of asset loading at which this error occurred.
+    #[inline]
+    pub fn stage(&self) -> ErrorStage {
+        self.stage
     }
 
     /// Checks if this error is of given type.
     #[inline]
     pub fn is<E: std::error::Error + 'static>(&self) -> bool {
-        self.0.is::<E>()
+        self.inner.is::<E>()
     }
 
     /// Checks if this error is [`NotFound`].
     #[inline]
     pub fn is_not_found(&self) -> bool {
-        self.0.is::<NotFound>()
+        self.code == ErrorCode::NotFound || self.inner.is::<NotFound>()
+    }
+
+    /// Checks if this error is [`Cancelled`].
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.code == ErrorCode::Cancelled || self.inner.is::<Cancelled>()
     }
 
     /// Checks if this error is [`DecodeError`] for given asset type.
     #[inline]
     pub fn is_decode_error<A: Asset>(&self) -> bool {
-        self.0.is::<A::DecodeError>()
+        match self.code {
+            ErrorCode::Unknown | ErrorCode::DecodeFailed | ErrorCode::SchemaMismatch => {
+                self.inner.is::<A::DecodeError>()
+            }
+            _ => false,
+        }
     }
 
     /// Checks if this error is [`BuildError`] for given asset type.
     #[inline]
     pub fn is_build_error<A: Asset>(&self) -> bool {
-        self.0.is::<A::BuildError>()
+        self.inner.is::<A::BuildError>()
     }
 
     /// Downcasts this error to the original error type if guessed correctly.
     #[inline]
     pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
-        self.0.downcast_ref()
+        self.inner.downcast_ref()
     }
 
     /// Downcasts this error to [`NotFound`] if it is [`NotFound`].
     #[inline]
     pub fn get_not_found(&self) -> Option<&NotFound> {
-        self.0.downcast_ref()
+        self.inner.downcast_ref()
     }
 
     /// Downcasts this error to [`DecodeError`] for given asset type if it is [`DecodeError`].
     #[inline]
     pub fn get_decode_error<A: Asset>(&self) -> Option<&A::DecodeError> {
-        self.0.downcast_ref()
+        self.inner.downcast_ref()
     }
 
     /// Downcasts this error to [`BuildError`] for given asset type if it is [`BuildError`].
     #[inline]
     pub fn get_build_error<A: Asset>(&self) -> Option<&A::BuildError> {
-        self.0.downcast_ref()
+        self.inner.downcast_ref()
     }
 }
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&*self.0, f)
+        fmt::Debug::fmt(&*self.inner, f)
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Display::fmt(&*self.0, f)
+        fmt::Display::fmt(&*self.inner, f)
     }
 }
 
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        self.0.source()
+        self.inner.source()
     }
 }
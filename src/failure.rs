@@ -0,0 +1,74 @@
+use std::{collections::VecDeque, sync::Arc, time::SystemTime};
+
+use argosy_id::AssetId;
+use parking_lot::Mutex;
+
+use crate::error::Error;
+
+/// Default maximum number of failures kept by the loader.
+pub(crate) const DEFAULT_MAX_FAILURES: usize = 64;
+
+/// Failed load that no handle observed.
+///
+/// See [`Loader::take_failures`].
+///
+/// [`Loader::take_failures`]: crate::Loader::take_failures
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct FailureRecord {
+    /// Name of the asset type.
+    pub name: String,
+
+    /// Path with which asset was requested, if any.
+    pub path: Option<Arc<str>>,
+
+    /// Id of the asset, if known.
+    pub id: Option<AssetId>,
+
+    /// Error of the load. [`NotFound`] for missing assets.
+    ///
+    /// [`NotFound`]: crate::NotFound
+    pub error: Error,
+
+    /// Time the load failed.
+    pub timestamp: SystemTime,
+}
+
+/// Bounded buffer of failures, oldest are dropped first.
+pub(crate) struct Failures {
+    max: usize,
+    records: Mutex<VecDeque<FailureRecord>>,
+}
+
+impl Failures {
+    pub fn new(max: usize) -> Self {
+        Failures {
+            max,
+            records: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn record(&self, name: &str, path: Option<Arc<str>>, id: Option<AssetId>, error: Error) {
+        if self.max == 0 {
+            return;
+        }
+
+        let record = FailureRecord {
+            name: name.to_owned(),
+            path,
+            id,
+            error,
+            timestamp: SystemTime::now(),
+        };
+
+        let mut records = self.records.lock();
+        if records.len() >= self.max {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    pub fn take(&self) -> Vec<FailureRecord> {
+        self.records.lock().drain(..).collect()
+    }
+}
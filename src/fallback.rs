@@ -0,0 +1,166 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use argosy_id::AssetId;
+
+use crate::{
+    asset::Asset,
+    error::Error,
+    handle::{AssetHandle, LoadedAsset},
+    key::Key,
+    loader::Loader,
+};
+
+/// Key stored by [`AssetFallback`] until it is tried.
+#[derive(Clone)]
+enum OwnedKey {
+    Path(Arc<str>),
+    Id(AssetId),
+}
+
+impl OwnedKey {
+    fn new(key: Key<'_>) -> Self {
+        match key {
+            Key::Path(path) => OwnedKey::Path(path.into()),
+            Key::Id(id) => OwnedKey::Id(id),
+        }
+    }
+
+    fn as_key(&self) -> Key<'_> {
+        match self {
+            OwnedKey::Path(path) => Key::Path(path),
+            OwnedKey::Id(id) => Key::Id(*id),
+        }
+    }
+}
+
+/// Handle that tries several keys in order.
+///
+/// Returned by [`Loader::load_first`].
+/// If asset with current key is missing, next key is loaded.
+/// Any other error is reported immediately.
+/// Each key is loaded as with [`Loader::load`], sharing cache with other handles.
+///
+/// Resolves to the first asset found or to not found error of the last key.
+pub struct AssetFallback<A> {
+    loader: Loader,
+    current: AssetHandle<A>,
+    current_key: OwnedKey,
+    remaining: VecDeque<OwnedKey>,
+    resolved: bool,
+}
+
+impl<A> Unpin for AssetFallback<A> {}
+
+impl<A> fmt::Debug for AssetFallback<A>
+where
+    A: Asset,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({}", A::asset_name(), self.current_key.as_key())?;
+        for key in &self.remaining {
+            write!(f, " | {}", key.as_key())?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl<A> AssetFallback<A>
+where
+    A: Asset,
+{
+    pub(crate) fn new<'a, K>(loader: &Loader, keys: impl IntoIterator<Item = K>) -> Self
+    where
+        K: Into<Key<'a>>,
+    {
+        let mut remaining: VecDeque<_> = keys
+            .into_iter()
+            .map(|key| OwnedKey::new(key.into()))
+            .collect();
+
+        let current_key = remaining
+            .pop_front()
+            .expect("At least one key must be provided");
+
+        AssetFallback {
+            loader: loader.clone(),
+            current: loader.load(current_key.as_key()),
+            current_key,
+            remaining,
+            resolved: false,
+        }
+    }
+
+    /// Returns key that is being loaded now.
+    pub fn current_key(&self) -> Key<'_> {
+        self.current_key.as_key()
+    }
+
+    /// Returns key of the asset that was found.
+    /// Returns `None` until asset is loaded.
+    pub fn resolved_key(&self) -> Option<Key<'_>> {
+        self.resolved.then(|| self.current_key.as_key())
+    }
+
+    /// Handles result of the current key.
+    /// Switches to the next key and returns `None` if asset is missing.
+    fn on_result(
+        &mut self,
+        result: Result<LoadedAsset<A>, Error>,
+    ) -> Option<Result<LoadedAsset<A>, Error>> {
+        match result {
+            Err(err) if err.is_not_found() => match self.remaining.pop_front() {
+                None => Some(Err(err)),
+                Some(next) => {
+                    self.current = self.loader.load(next.as_key());
+                    self.current_key = next;
+                    None
+                }
+            },
+            Err(err) => Some(Err(err)),
+            Ok(loaded) => {
+                self.resolved = true;
+                Some(Ok(loaded))
+            }
+        }
+    }
+
+    /// Polls for asset to be loaded.
+    /// Returns some result with loaded asset handle or error.
+    /// Returns none if no asset is loaded yet.
+    pub fn poll_loaded(&mut self) -> Option<Result<LoadedAsset<A>, Error>> {
+        loop {
+            let result = self.current.poll_loaded()?;
+            if let Some(result) = self.on_result(result) {
+                return Some(result);
+            }
+        }
+    }
+}
+
+impl<A> Future for AssetFallback<A>
+where
+    A: Asset,
+{
+    type Output = Result<LoadedAsset<A>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<LoadedAsset<A>, Error>> {
+        let me = self.get_mut();
+        loop {
+            // Handle of the next key is polled with the same waker right away.
+            let result = match Pin::new(&mut me.current).poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => result,
+            };
+            if let Some(result) = me.on_result(result) {
+                return Poll::Ready(result);
+            }
+        }
+    }
+}
@@ -1,22 +1,35 @@
 use std::{
+    borrow::Borrow,
+    collections::HashMap,
     convert::Infallible,
     future::{ready, Future, Ready},
+    hash::Hash,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
 };
 
 use argosy_id::AssetId;
-use futures::future::TryJoinAll;
+use futures::{
+    future::{FusedFuture, TryFuture, TryJoinAll},
+    ready,
+};
 
 use crate::{
     asset::{Asset, AssetBuild},
+    dev::Placeholder,
     error::Error,
     handle::{AssetHandle, LoadedAsset},
     loader::Loader,
 };
 
-pub struct FieldBuilder<'a, B>(pub &'a mut B);
+/// Builder of asset fields.
+///
+/// Wraps builder of the asset and context of the field.
+/// Context of fields with `#[asset(needs = "<field>")]` attributes
+/// references fields of the same asset that are already built,
+/// context of other fields is `()`.
+pub struct FieldBuilder<'a, B, C = ()>(pub &'a mut B, pub C);
 
 #[doc(hidden)]
 pub enum External {}
@@ -27,7 +40,12 @@ pub enum Inlined {}
 /// This trait can be derived for types to allow using them as asset fields.
 ///
 /// It is auto-implemented for all types that implement `serde::de::DeserializeOwned`.
-/// As well as `Option<A>` where `A: AssetField` and `Arc<[A]>` where `A: AssetField`.
+/// As well as `Option<A>`, `Vec<A>` and `Arc<[A]>` where `A: AssetField`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as asset field of kind `{K}`",
+    label = "`{Self}` does not implement `AssetField<{K}>`",
+    note = "derive `AssetField` or implement `serde::Deserialize` for inlined fields, derive `Asset` for `#[asset(external)]` fields"
+)]
 pub trait AssetField<K = Inlined>: Clone + Sized + Send + Sync + 'static {
     /// Deserializable data.
     type Info: serde::de::DeserializeOwned;
@@ -50,6 +68,11 @@ pub trait AssetField<K = Inlined>: Clone + Sized + Send + Sync + 'static {
 /// Builder trait for asset fields.
 ///
 /// It is auto-implemented for all types that implement `serde::de::DeserializeOwned`.
+#[diagnostic::on_unimplemented(
+    message = "asset field `{A}` cannot be built with `{Self}`",
+    label = "`{Self}` does not implement `AssetFieldBuild<{K}, {A}>`",
+    note = "implement `AssetBuild<B>` for `{A}` if it is an asset, where `B` is the builder wrapped in `FieldBuilder`"
+)]
 pub trait AssetFieldBuild<K, A: AssetField<K>> {
     /// Build asset instance using decoded representation and `Resources`.
     fn build(self, decoded: A::Decoded) -> Result<A, A::BuildError>;
@@ -74,10 +97,11 @@ where
     }
 }
 
-impl<B, A> AssetFieldBuild<External, Option<A>> for FieldBuilder<'_, B>
+impl<B, C, A> AssetFieldBuild<External, Option<A>> for FieldBuilder<'_, B, C>
 where
+    C: Copy,
     A: AssetField<External>,
-    for<'a> FieldBuilder<'a, B>: AssetFieldBuild<External, A>,
+    for<'a> FieldBuilder<'a, B, C>: AssetFieldBuild<External, A>,
 {
     #[inline]
     fn build(self, maybe_decoded: Option<A::Decoded>) -> Result<Option<A>, A::BuildError> {
@@ -128,20 +152,377 @@ where
     }
 }
 
-impl<B, A> AssetFieldBuild<External, Arc<[A]>> for FieldBuilder<'_, B>
+impl<B, C, A> AssetFieldBuild<External, Arc<[A]>> for FieldBuilder<'_, B, C>
 where
+    C: Copy,
     A: AssetField<External>,
-    for<'a> FieldBuilder<'a, B>: AssetFieldBuild<External, A>,
+    for<'a> FieldBuilder<'a, B, C>: AssetFieldBuild<External, A>,
 {
     #[inline]
     fn build(self, decoded: Vec<A::Decoded>) -> Result<Arc<[A]>, A::BuildError> {
         decoded
             .into_iter()
-            .map(move |decoded| FieldBuilder(self.0).build(decoded))
+            .map(move |decoded| FieldBuilder(self.0, self.1).build(decoded))
+            .collect()
+    }
+}
+
+impl<A> AssetField<External> for Vec<A>
+where
+    A: AssetField<External>,
+{
+    type Info = Vec<A::Info>;
+    type Decoded = Vec<A::Decoded>;
+    type DecodeError = A::DecodeError;
+    type BuildError = A::BuildError;
+    type Fut = TryJoinAll<A::Fut>;
+
+    #[inline]
+    fn decode(info: Vec<A::Info>, loader: &Loader) -> Self::Fut {
+        info.into_iter()
+            .map(|info| A::decode(info, loader))
+            .collect()
+    }
+}
+
+impl<B, C, A> AssetFieldBuild<External, Vec<A>> for FieldBuilder<'_, B, C>
+where
+    C: Copy,
+    A: AssetField<External>,
+    for<'a> FieldBuilder<'a, B, C>: AssetFieldBuild<External, A>,
+{
+    #[inline]
+    fn build(self, decoded: Vec<A::Decoded>) -> Result<Vec<A>, A::BuildError> {
+        decoded
+            .into_iter()
+            .map(move |decoded| FieldBuilder(self.0, self.1).build(decoded))
+            .collect()
+    }
+}
+
+impl<K, A> AssetField<External> for HashMap<K, A>
+where
+    K: serde::de::DeserializeOwned + Eq + Hash + Clone + Send + Sync + 'static,
+    A: AssetField<External>,
+{
+    type Info = HashMap<K, A::Info>;
+    type Decoded = Vec<(K, A::Decoded)>;
+    type DecodeError = A::DecodeError;
+    type BuildError = A::BuildError;
+    type Fut = KeyedFuture<K, A::Fut>;
+
+    #[inline]
+    fn decode(info: HashMap<K, A::Info>, loader: &Loader) -> Self::Fut {
+        let (keys, futs): (Vec<_>, Vec<_>) = info
+            .into_iter()
+            .map(|(key, info)| (key, A::decode(info, loader)))
+            .unzip();
+        KeyedFuture::new(keys, futs)
+    }
+}
+
+impl<B, C, K, A> AssetFieldBuild<External, HashMap<K, A>> for FieldBuilder<'_, B, C>
+where
+    C: Copy,
+    K: serde::de::DeserializeOwned + Eq + Hash + Clone + Send + Sync + 'static,
+    A: AssetField<External>,
+    for<'a> FieldBuilder<'a, B, C>: AssetFieldBuild<External, A>,
+{
+    #[inline]
+    fn build(self, decoded: Vec<(K, A::Decoded)>) -> Result<HashMap<K, A>, A::BuildError> {
+        decoded
+            .into_iter()
+            .map(move |(key, decoded)| Ok((key, FieldBuilder(self.0, self.1).build(decoded)?)))
             .collect()
     }
 }
 
+/// Joins futures keeping keys associated with their outputs.
+pub struct KeyedFuture<K, F: TryFuture> {
+    keys: Vec<K>,
+    futs: TryJoinAll<F>,
+}
+
+impl<K, F, R, E> KeyedFuture<K, F>
+where
+    F: Future<Output = Result<R, E>>,
+{
+    fn new(keys: Vec<K>, futs: Vec<F>) -> Self {
+        KeyedFuture {
+            keys,
+            futs: futs.into_iter().collect(),
+        }
+    }
+}
+
+// Keys are never pinned.
+impl<K, F> Unpin for KeyedFuture<K, F>
+where
+    F: TryFuture,
+    TryJoinAll<F>: Unpin,
+{
+}
+
+impl<K, F, R, E> Future for KeyedFuture<K, F>
+where
+    F: Future<Output = Result<R, E>>,
+{
+    type Output = Result<Vec<(K, R)>, E>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = self.get_mut();
+        match Pin::new(&mut me.futs).poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => Poll::Ready(result.map(|outputs| {
+                std::mem::take(&mut me.keys)
+                    .into_iter()
+                    .zip(outputs)
+                    .collect()
+            })),
+        }
+    }
+}
+
+/// Map of external assets that remembers ids of the assets.
+///
+/// Deserialized as map from keys to [`AssetId`]s.
+/// After building, assets can be looked up by key,
+/// and keys by id of the asset, e.g. to find what to update on hot-reload.
+/// Several keys may reference the same asset,
+/// they all are returned from [`KeyedAssets::keys_of`].
+///
+/// Serialized as map from keys to [`AssetId`]s as well.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use std::collections::HashMap;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// struct Prop {
+///     mass: u32,
+/// }
+///
+/// #[derive(Clone, Asset)]
+/// struct Level {
+///     #[asset(external)]
+///     props: KeyedAssets<String, Prop>,
+///
+///     /// Plain map when ids are not needed.
+///     #[asset(external)]
+///     spawns: HashMap<String, Prop>,
+/// }
+///
+/// let chair = AssetId::new(1).unwrap();
+/// let table = AssetId::new(2).unwrap();
+///
+/// let source = MemorySource::new();
+/// source.insert(chair, &br#"{ "mass": 5 }"#[..]);
+/// source.insert(table, &br#"{ "mass": 20 }"#[..]);
+/// source.insert_with_path(
+///     "level",
+///     AssetId::new(3).unwrap(),
+///     &br#"{ "props": { "chair": 1, "stool": 1, "table": 2 }, "spawns": { "crate": 2 } }"#[..],
+/// );
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let level = loader.load::<Level, _>("level").await?.build(&mut ())?;
+///         assert_eq!(level.spawns["crate"].mass, 20);
+///
+///         let props = &level.props;
+///
+///         assert_eq!(props.len(), 3);
+///         assert_eq!(props.get("table").unwrap().mass, 20);
+///         assert_eq!(props.id_of("stool"), Some(chair));
+///         assert_eq!(props.key_of(table).map(String::as_str), Some("table"));
+///
+///         // Both keys are indexed for shared asset.
+///         let mut keys = props.keys_of(chair).to_vec();
+///         keys.sort();
+///         assert_eq!(keys, ["chair", "stool"]);
+///         assert!(props.keys_of(AssetId::new(4).unwrap()).is_empty());
+///
+///         // Serialized back as it was deserialized.
+///         let json = serde_json::to_string(props).unwrap();
+///         let ids: HashMap<String, AssetId> = serde_json::from_str(&json).unwrap();
+///         let expected = HashMap::from([
+///             ("chair".to_owned(), chair),
+///             ("stool".to_owned(), chair),
+///             ("table".to_owned(), table),
+///         ]);
+///         assert_eq!(ids, expected);
+///         Ok::<_, Error>(())
+///     })?;
+/// # }
+/// # Ok::<_, Error>(())
+/// ```
+#[derive(Clone)]
+pub struct KeyedAssets<K, A> {
+    assets: HashMap<K, (AssetId, A)>,
+    keys: HashMap<AssetId, Vec<K>>,
+}
+
+impl<K, A> KeyedAssets<K, A>
+where
+    K: Eq + Hash,
+{
+    /// Returns asset with specified key.
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&A>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.assets.get(key).map(|(_, asset)| asset)
+    }
+
+    /// Returns id of the asset with specified key.
+    #[inline]
+    pub fn id_of<Q>(&self, key: &Q) -> Option<AssetId>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.assets.get(key).map(|(id, _)| *id)
+    }
+
+    /// Returns key of the asset with specified id.
+    ///
+    /// If several keys reference the asset, returns any of them.
+    #[inline]
+    pub fn key_of(&self, id: AssetId) -> Option<&K> {
+        self.keys_of(id).first()
+    }
+
+    /// Returns all keys of the asset with specified id, in unspecified order.
+    #[inline]
+    pub fn keys_of(&self, id: AssetId) -> &[K] {
+        self.keys.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns iterator over keys and assets.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &A)> + '_ {
+        self.assets.iter().map(|(key, (_, asset))| (key, asset))
+    }
+
+    /// Returns number of keys.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    /// Returns `true` if there are no keys.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+}
+
+impl<K, A> serde::Serialize for KeyedAssets<K, A>
+where
+    K: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(self.assets.iter().map(|(key, (id, _))| (key, id)))
+    }
+}
+
+impl<K, A> AssetField<External> for KeyedAssets<K, A>
+where
+    K: serde::de::DeserializeOwned + Eq + Hash + Clone + Send + Sync + 'static,
+    A: Asset,
+{
+    type Info = HashMap<K, AssetId>;
+    type Decoded = Vec<((K, AssetId), LoadedAsset<A>)>;
+    type DecodeError = Error;
+    type BuildError = Error;
+    type Fut = KeyedFuture<(K, AssetId), ExternalField<A>>;
+
+    #[inline]
+    fn decode(info: HashMap<K, AssetId>, loader: &Loader) -> Self::Fut {
+        let (keys, futs): (Vec<_>, Vec<_>) = info
+            .into_iter()
+            .map(|(key, id)| ((key, id), <A as AssetField<External>>::decode(id, loader)))
+            .unzip();
+        KeyedFuture::new(keys, futs)
+    }
+}
+
+impl<B, K, A> AssetFieldBuild<External, KeyedAssets<K, A>> for FieldBuilder<'_, B>
+where
+    K: serde::de::DeserializeOwned + Eq + Hash + Clone + Send + Sync + 'static,
+    A: Asset + AssetBuild<B>,
+{
+    fn build(
+        self,
+        decoded: Vec<((K, AssetId), LoadedAsset<A>)>,
+    ) -> Result<KeyedAssets<K, A>, Error> {
+        let mut assets = HashMap::with_capacity(decoded.len());
+        let mut keys = HashMap::<AssetId, Vec<K>>::new();
+
+        for ((key, id), mut loaded) in decoded {
+            let asset = loaded.build(self.0)?;
+            keys.entry(id).or_default().push(key.clone());
+            assets.insert(key, (id, asset));
+        }
+
+        Ok(KeyedAssets { assets, keys })
+    }
+}
+
+/// Future of external asset field.
+///
+/// Resolves as [`AssetHandle`] does, except that missing asset is replaced with placeholder
+/// in development mode.
+/// See [`LoaderBuilder::set_dev_placeholders`].
+///
+/// [`LoaderBuilder::set_dev_placeholders`]: crate::LoaderBuilder::set_dev_placeholders
+pub struct ExternalField<A> {
+    handle: AssetHandle<A>,
+    placeholder: Option<Placeholder<A>>,
+}
+
+impl<A> Unpin for ExternalField<A> {}
+
+impl<A> Future for ExternalField<A>
+where
+    A: Clone,
+{
+    type Output = Result<LoadedAsset<A>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<LoadedAsset<A>, Error>> {
+        let me = self.get_mut();
+
+        let result = ready!(Pin::new(&mut me.handle).poll(cx));
+        match (result, me.placeholder.take()) {
+            (Err(err), Some(placeholder)) if err.is_not_found() => {
+                Poll::Ready(Ok(placeholder.resolve(&me.handle)))
+            }
+            (result, _) => Poll::Ready(result),
+        }
+    }
+}
+
+impl<A> FusedFuture for ExternalField<A>
+where
+    A: Clone,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.handle.is_terminated()
+    }
+}
+
 impl<A> AssetField<External> for A
 where
     A: Asset,
@@ -150,11 +531,14 @@ where
     type Decoded = LoadedAsset<A>;
     type DecodeError = Error;
     type BuildError = Error;
-    type Fut = AssetHandle<A>;
+    type Fut = ExternalField<A>;
 
     #[inline(always)]
     fn decode(id: AssetId, loader: &Loader) -> Self::Fut {
-        loader.load(id)
+        ExternalField {
+            handle: loader.load(id),
+            placeholder: loader.placeholder(id),
+        }
     }
 }
 
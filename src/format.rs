@@ -0,0 +1,194 @@
+use std::{cell::Cell, ops::RangeInclusive};
+
+use crate::DecodeError;
+
+/// Serialization format of asset data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AssetFormat {
+    /// Asset data is JSON document.
+    Json,
+
+    /// Asset data is encoded with bincode.
+    Bincode,
+}
+
+thread_local! {
+    static FORMAT_OVERRIDE: Cell<Option<AssetFormat>> = const { Cell::new(None) };
+    static STRICT_DESCRIPTORS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Returns format requested for asset being decoded on this thread, if any.
+pub(crate) fn format_override() -> Option<AssetFormat> {
+    FORMAT_OVERRIDE.with(Cell::get)
+}
+
+/// Returns `true` if asset being decoded on this thread
+/// must be rejected on unknown fields.
+pub(crate) fn strict_descriptors() -> bool {
+    STRICT_DESCRIPTORS.with(Cell::get)
+}
+
+/// Calls `f` with format and descriptor strictness set for this thread.
+///
+/// Only for synchronous decoding, like [`LeafAsset::decode`],
+/// where values can't be observed by other decodes polled on this thread.
+///
+/// [`LeafAsset::decode`]: crate::LeafAsset::decode
+pub(crate) fn with_decode_context<R>(
+    format: Option<AssetFormat>,
+    strict: bool,
+    f: impl FnOnce() -> R,
+) -> R {
+    struct Restore(Option<AssetFormat>, bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            FORMAT_OVERRIDE.with(|cell| cell.set(self.0));
+            STRICT_DESCRIPTORS.with(|cell| cell.set(self.1));
+        }
+    }
+
+    let _restore = Restore(
+        FORMAT_OVERRIDE.with(|cell| cell.replace(format)),
+        STRICT_DESCRIPTORS.with(|cell| cell.replace(strict)),
+    );
+    f()
+}
+
+/// Header of the artifact that tells format and version of the payload.
+///
+/// Artifacts written by older versions of serializers may fail to decode
+/// with cryptic errors. Enveloped artifacts of unsupported version
+/// fail with [`DecodeError::ArtifactTooOld`] instead.
+/// Artifacts without envelope are decoded with format detection as before.
+///
+/// Envelope is recognized and stripped by assets
+/// that use derived implementation of [`Asset`].
+///
+/// [`Asset`]: crate::Asset
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let id = |n| AssetId::new(n).unwrap();
+/// let json = ArtifactEnvelope::new(AssetFormat::Json);
+/// let bincode = ArtifactEnvelope::new(AssetFormat::Bincode);
+/// let stale = ArtifactEnvelope {
+///     version: 0,
+///     format: AssetFormat::Bincode,
+/// };
+///
+/// let source = MemorySource::new();
+/// source.insert(id(1), json.wrap(br#"{ "value": 1 }"#));
+/// source.insert(id(2), bincode.wrap(&2u32.to_le_bytes()));
+/// source.insert(id(3), &br#"{ "value": 3 }"#[..]);
+/// source.insert(id(4), stale.wrap(&4u32.to_le_bytes()));
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         for n in 1..=3 {
+///             let mut number = loader.load::<Number, _>(id(n)).await?;
+///             assert_eq!(number.build(&mut ())?.value, n as u32);
+///         }
+///
+///         let err = loader.load::<Number, _>(id(4)).await.err().unwrap();
+///         let err = std::error::Error::source(&err).unwrap();
+///         match err.downcast_ref::<DecodeError>() {
+///             Some(DecodeError::ArtifactTooOld { found: 0, .. }) => {}
+///             _ => panic!("Unexpected error {err}"),
+///         }
+///         Ok::<_, Error>(())
+///     })?;
+/// # }
+/// # Ok::<_, Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ArtifactEnvelope {
+    /// Version of the payload format.
+    pub version: u16,
+
+    /// Serialization format of the payload.
+    pub format: AssetFormat,
+}
+
+impl ArtifactEnvelope {
+    /// Bytes that start enveloped artifact.
+    /// Zero byte can't start a JSON document.
+    pub const MAGIC: [u8; 4] = *b"\0ARG";
+
+    /// Version written by this crate.
+    pub const VERSION: u16 = 1;
+
+    /// Versions this crate can decode.
+    pub const SUPPORTED: RangeInclusive<u16> = 1..=Self::VERSION;
+
+    /// Length of the envelope header in bytes.
+    pub const LEN: usize = 7;
+
+    /// Returns envelope of the current version.
+    pub fn new(format: AssetFormat) -> Self {
+        ArtifactEnvelope {
+            version: Self::VERSION,
+            format,
+        }
+    }
+
+    /// Returns header bytes.
+    pub fn header(&self) -> [u8; Self::LEN] {
+        let [v0, v1] = self.version.to_le_bytes();
+        let [m0, m1, m2, m3] = Self::MAGIC;
+        let format = match self.format {
+            AssetFormat::Json => 0,
+            AssetFormat::Bincode => 1,
+        };
+        [m0, m1, m2, m3, v0, v1, format]
+    }
+
+    /// Returns payload prefixed with header.
+    pub fn wrap(&self, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::LEN + payload.len());
+        bytes.extend_from_slice(&self.header());
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    /// Parses envelope at the start of the artifact.
+    ///
+    /// Returns envelope and payload,
+    /// or `None` if artifact is not enveloped.
+    /// Fails if envelope version or format is not supported.
+    pub fn parse(bytes: &[u8]) -> Result<Option<(Self, &[u8])>, DecodeError> {
+        if bytes.len() < Self::LEN || bytes[..4] != Self::MAGIC {
+            return Ok(None);
+        }
+
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if !Self::SUPPORTED.contains(&version) {
+            return Err(DecodeError::ArtifactTooOld {
+                found: version,
+                supported: Self::SUPPORTED,
+            });
+        }
+
+        let format = match bytes[6] {
+            0 => AssetFormat::Json,
+            1 => AssetFormat::Bincode,
+            format => return Err(DecodeError::UnknownPayloadFormat { format }),
+        };
+
+        Ok(Some((
+            ArtifactEnvelope { version, format },
+            &bytes[Self::LEN..],
+        )))
+    }
+}
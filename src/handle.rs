@@ -1,21 +1,39 @@
 use core::fmt;
 use std::{
     any::{Any, TypeId},
+    cell::{OnceCell, RefCell},
     future::Future,
+    marker::PhantomData,
+    ops::Deref,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     task::{Context, Poll, Waker},
 };
 
 use ahash::RandomState;
 use argosy_id::AssetId;
-use hashbrown::hash_map::RawEntryMut;
+use futures::future::FusedFuture;
 
 use crate::{
+    abort::Interest,
     asset::{Asset, AssetBuild},
-    error::{Error, NotFound},
-    key::hash_id_key_erased,
-    loader::{AssetShard, AssetState, DecodedState, PathShard, PathState},
+    cache::Entry,
+    error::{Cancelled, Error, ErrorCode, ErrorStage, Evicted, NotFound},
+    key::{hash_id_key, KindKey, TypeKey},
+    loader::{AssetShard, AssetState, DecodedState, EntryStatus, PathShard, PathState},
+    progress::Progress,
+    source::AssetProperties,
+    transition::{debug_check, EntryKind, Event},
+    unload::{AutoUnload, Retain},
+};
+
+#[cfg(feature = "async-build")]
+use crate::{
+    build_async::{AsyncAssetBuild, AsyncShared},
+    error::BuildInProgress,
 };
 
 #[derive(Clone)]
@@ -25,35 +43,142 @@ pub(crate) enum State {
         path_shard: PathShard,
         asset_shards: Arc<[AssetShard]>,
         random_state: RandomState,
+        auto_unload: Option<Arc<AutoUnload>>,
     },
     Loading {
         key_hash: u64,
         shard: AssetShard,
+
+        /// Keeps the load from being aborted.
+        interest: Option<Interest>,
     },
     Loaded {
         key_hash: u64,
         shard: AssetShard,
+        metadata: AssetMetadata,
+
+        /// Keeps decoded asset from being dropped as orphaned.
+        interest: Option<Interest>,
     },
     Ready {
+        key_hash: u64,
+        shard: AssetShard,
         asset: Arc<dyn Any + Send + Sync>,
+        metadata: AssetMetadata,
     },
     Error {
         error: Error,
     },
-    Missing,
+    Missing {
+        /// [`NotFound`] error made once for the handle.
+        error: Error,
+    },
+}
+
+impl State {
+    /// Returns state of missing asset.
+    pub(crate) fn missing(id: Option<AssetId>, path: Option<Arc<str>>) -> Self {
+        State::Missing {
+            error: NotFound { id, path }.into_error(),
+        }
+    }
+}
+
+/// Information about loaded asset.
+///
+/// Can be used by external cache policies to decide what assets to keep.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetMetadata {
+    /// Opaque version of the asset data reported by the source.
+    pub version: u64,
+
+    /// Index of the source that provided the asset data,
+    /// in order sources were added to the loader.
+    /// Index is taken at the time of loading,
+    /// it changes when sources are removed or reordered.
+    pub source_index: usize,
+
+    /// Label of the source that provided the asset data, if it has one.
+    pub source_label: Option<Arc<str>>,
+
+    /// Serial number of the source that provided the asset data.
+    pub(crate) source_serial: u64,
+
+    /// Length of raw asset data before decoding.
+    pub bytes_len: usize,
+
+    /// Properties of the asset reported by the source.
+    pub properties: AssetProperties,
+
+    /// Set when dependencies of the asset reload.
+    pub(crate) stale: StaleFlag,
+
+    /// Set when the asset is reloaded from newer data.
+    pub(crate) superseded: StaleFlag,
+}
+
+impl AssetMetadata {
+    /// Returns `true` if dependencies of the asset were reloaded
+    /// after it was decoded.
+    ///
+    /// See [`Loader::enable_cascade`].
+    ///
+    /// [`Loader::enable_cascade`]: crate::Loader::enable_cascade
+    #[inline]
+    pub fn is_stale(&self) -> bool {
+        self.stale.get()
+    }
+}
+
+/// Staleness of the asset value, shared by clones of its metadata.
+///
+/// Values with different staleness compare equal,
+/// so that metadata compares by its data.
+#[derive(Clone, Default)]
+pub(crate) struct StaleFlag(Arc<AtomicBool>);
+
+impl StaleFlag {
+    #[inline]
+    pub fn get(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub fn set(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+impl PartialEq for StaleFlag {
+    #[inline]
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for StaleFlag {}
+
+impl fmt::Debug for StaleFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.get(), f)
+    }
 }
 
 /// Internal implementation of asset handle types.
 #[derive(Clone)]
 pub struct Handle {
-    pub(crate) type_id: TypeId,
+    pub(crate) kind: KindKey,
     pub(crate) id: Option<AssetId>,
     pub(crate) path: Option<Arc<str>>,
+
+    /// Keeps asset from being unloaded automatically.
+    pub(crate) retain: Option<Retain>,
     pub(crate) state: State,
 }
 
+/// What handle is waiting for.
 #[derive(Clone, Copy, PartialEq, Eq)]
-enum PollFor {
+pub(crate) enum PollFor {
     Id,
     Load,
     Ready,
@@ -66,15 +191,155 @@ impl Handle {
             return Ok(id);
         }
         match &self.state {
-            State::Missing => Err(Error::new(NotFound {
-                id: None,
-                path: self.path.clone(),
-            })),
-            State::Error { error } => Err(error.clone()),
+            State::Missing { error } | State::Error { error } => Err(error.clone()),
             _ => unreachable!(),
         }
     }
 
+    /// Returns progress reported by the decoder.
+    #[inline]
+    fn progress(&self) -> Option<Progress> {
+        match &self.state {
+            State::Loading {
+                interest: Some(interest),
+                ..
+            }
+            | State::Loaded {
+                interest: Some(interest),
+                ..
+            } => interest.progress(),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if asset is loaded and its dependencies were reloaded since.
+    /// If `held` is set, checks value held by the handle instead of the cached one.
+    fn is_stale(&self, held: bool) -> bool {
+        match &self.state {
+            State::Loaded {
+                key_hash,
+                shard,
+                metadata,
+                ..
+            } if !held => {
+                let id = self
+                    .id
+                    .expect("This state can be reached only with known id");
+
+                let mut locked_shard = shard.lock();
+                match locked_shard.entry(*key_hash, |k| k.eq_key(self.kind, id)) {
+                    Entry::Occupied(mut entry) => match entry.get() {
+                        AssetState::Loaded { metadata, .. }
+                        | AssetState::Ready { metadata, .. } => metadata.is_stale(),
+                        _ => false,
+                    },
+                    Entry::Vacant(_) => metadata.is_stale(),
+                }
+            }
+            State::Loaded { metadata, .. } | State::Ready { metadata, .. } => metadata.is_stale(),
+            _ => false,
+        }
+    }
+
+    /// Returns handle to loading state if the asset it observed was reloaded since,
+    /// so that next poll observes the new data.
+    /// Returns `true` if handle is reset.
+    fn reset_superseded(&mut self) -> bool {
+        match &self.state {
+            State::Loaded {
+                key_hash,
+                shard,
+                metadata,
+                ..
+            }
+            | State::Ready {
+                key_hash,
+                shard,
+                metadata,
+                ..
+            } if metadata.superseded.get() => {
+                self.state = State::Loading {
+                    key_hash: *key_hash,
+                    shard: shard.clone(),
+                    interest: None,
+                };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns value of the asset property if asset is loaded.
+    #[inline]
+    fn property(&self, key: &str) -> Option<Arc<str>> {
+        match &self.state {
+            State::Loaded { metadata, .. } | State::Ready { metadata, .. } => {
+                metadata.properties.get(key).cloned()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns state of the loader cache entry this handle refers to.
+    /// Returns `None` if the entry was removed from the cache.
+    fn status(&self) -> Option<EntryStatus> {
+        match &self.state {
+            State::Searching {
+                key_hash,
+                path_shard,
+                ..
+            } => {
+                let path = self.path.as_deref()?;
+                let locked_shard = path_shard.lock();
+                let (_, state) = locked_shard.get(*key_hash, &mut |k| k.eq_key(self.kind, path))?;
+                Some(state.status())
+            }
+            State::Loading {
+                key_hash, shard, ..
+            }
+            | State::Loaded {
+                key_hash, shard, ..
+            } => {
+                let id = self.id?;
+                let locked_shard = shard.lock();
+                let (_, state) = locked_shard.get(*key_hash, &mut |k| k.eq_key(self.kind, id))?;
+                Some(state.status())
+            }
+            State::Ready { .. } => Some(EntryStatus::Ready),
+            State::Error { .. } => Some(EntryStatus::Error),
+            State::Missing { .. } => Some(EntryStatus::Missing),
+        }
+    }
+
+    /// Returns metadata of loaded asset.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic if called before `poll(PollFor::Load)` returned `true`
+    /// or if asset is missing or failed to load.
+    #[inline]
+    fn metadata(&self) -> AssetMetadata {
+        match &self.state {
+            State::Loaded { metadata, .. } | State::Ready { metadata, .. } => metadata.clone(),
+            _ => unreachable!("`poll_load` must be used first"),
+        }
+    }
+
+    /// Resolves handle with [`Cancelled`] error
+    /// when cache entry it points to was removed,
+    /// e.g. with [`Loader::retry`] or by cache backend.
+    ///
+    /// [`Loader::retry`]: crate::Loader::retry
+    fn removed<E, R>(&mut self, id: AssetId, err: E) -> R
+    where
+        E: FnOnce(&Error) -> R,
+    {
+        let error = Error::new(Cancelled { id }).with_code(ErrorCode::Cancelled);
+        let result = err(&error);
+        self.state = State::Error { error };
+        result
+    }
+
     /// Polls asset handle for loading progress.
     fn poll(&mut self, poll_for: PollFor, waker: Option<&Waker>) -> bool {
         match &mut self.state {
@@ -83,6 +348,7 @@ impl Handle {
                 path_shard,
                 asset_shards,
                 random_state,
+                auto_unload,
             } => {
                 let path = self
                     .path
@@ -90,26 +356,24 @@ impl Handle {
                     .expect("This state is only reachable when asset is requested with path");
 
                 let mut locked_shard = path_shard.lock();
-                let raw_entry = locked_shard
-                    .raw_entry_mut()
-                    .from_hash(*key_hash, |k| k.eq_key_erased(self.type_id, path));
+                let raw_entry = locked_shard.entry(*key_hash, |k| k.eq_key(self.kind, path));
 
                 match raw_entry {
-                    RawEntryMut::Vacant(_) => {
-                        panic!("This state is only reachable when asset is requested with path")
+                    Entry::Vacant(_) => {
+                        // Entry was evicted by cache backend.
+                        drop(locked_shard);
+                        self.state = State::Error {
+                            error: Error::new(Evicted {
+                                path: Arc::from(path),
+                            })
+                            .with_code(ErrorCode::Cancelled),
+                        };
+                        return true;
                     }
-                    RawEntryMut::Occupied(mut entry) => match entry.get_mut() {
-                        PathState::Unloaded {
-                            id_wakers,
-                            asset_wakers,
-                        } => {
-                            match poll_for {
-                                PollFor::Id | PollFor::Load => {
-                                    waker.map(|waker| id_wakers.push(waker.clone()));
-                                }
-                                PollFor::Ready => {
-                                    waker.map(|waker| asset_wakers.push(waker.clone()));
-                                }
+                    Entry::Occupied(mut entry) => match entry.get_mut() {
+                        PathState::Unloaded { wakers, .. } => {
+                            if let Some(waker) = waker {
+                                wakers.push(poll_for, waker.clone())
                             }
                             return false;
                         }
@@ -118,24 +382,38 @@ impl Handle {
                             drop(locked_shard);
                             self.id = Some(id);
 
-                            let key_hash = hash_id_key_erased(self.type_id, id, &*random_state);
+                            let key_hash = hash_id_key(self.kind, id, &*random_state);
 
                             let shard =
                                 asset_shards[key_hash as usize % asset_shards.len()].clone();
 
-                            self.state = State::Loading { key_hash, shard };
+                            if let Some(auto_unload) = auto_unload {
+                                self.retain = Some(auto_unload.retain(
+                                    TypeKey::new(self.kind, id),
+                                    key_hash,
+                                    &shard,
+                                ));
+                            }
+
+                            self.state = State::Loading {
+                                key_hash,
+                                shard,
+                                interest: None,
+                            };
                             if poll_for == PollFor::Id {
                                 return true;
                             }
                         }
                         PathState::Missing => {
                             drop(locked_shard);
-                            self.state = State::Missing;
+                            self.state = State::missing(None, self.path.clone());
                             return true;
                         }
                     },
                 }
             }
+            // Asset requested with path may fail without known id.
+            State::Missing { .. } | State::Error { .. } => return true,
             _ => {
                 debug_assert!(self.id.is_some());
 
@@ -147,62 +425,107 @@ impl Handle {
 
         match &mut self.state {
             State::Searching { .. } => unreachable!(),
-            State::Loaded { .. } if poll_for != PollFor::Ready => {
-                return true;
+            State::Loaded { .. } if poll_for != PollFor::Ready => true,
+            State::Loading {
+                key_hash, shard, ..
             }
-            State::Loading { key_hash, shard } | State::Loaded { key_hash, shard } => {
+            | State::Loaded {
+                key_hash, shard, ..
+            } => {
                 let id = self
                     .id
                     .expect("This state can be reached only with known id");
                 let mut locked_shard = shard.lock();
-                let raw_entry = locked_shard
-                    .raw_entry_mut()
-                    .from_hash(*key_hash, |k| k.eq_key_erased(self.type_id, id));
+                let raw_entry = locked_shard.entry(*key_hash, |k| k.eq_key(self.kind, id));
 
                 match raw_entry {
-                    RawEntryMut::Vacant(_) => {
-                        unreachable!("AssetResult existence guarantee entry is not vacant")
+                    Entry::Vacant(_) => {
+                        // Load was cancelled.
+                        drop(locked_shard);
+                        self.state = State::Error {
+                            error: Error::new(Cancelled { id }).with_code(ErrorCode::Cancelled),
+                        };
+                        true
                     }
-                    RawEntryMut::Occupied(mut entry) => match entry.get_mut() {
-                        AssetState::Unloaded { wakers } => {
-                            waker.map(|waker| wakers.push(waker.clone()));
+                    Entry::Occupied(mut entry) => match entry.get_mut() {
+                        AssetState::Unloaded { wakers, abort } => {
+                            if let Some(waker) = waker {
+                                wakers.push(waker.clone())
+                            }
+                            let abort = abort.clone();
+                            drop(locked_shard);
+
+                            if let State::Loading {
+                                interest: interest @ None,
+                                ..
+                            } = &mut self.state
+                            {
+                                *interest = Some(abort.interest());
+                            }
                             false
                         }
-                        AssetState::Loaded { wakers, .. } if poll_for == PollFor::Ready => {
-                            waker.map(|waker| wakers.push(waker.clone()));
+                        AssetState::Loaded {
+                            wakers,
+                            metadata,
+                            abort,
+                            #[cfg(feature = "tokio")]
+                            build_wait,
+                            ..
+                        } if poll_for == PollFor::Ready => {
+                            if let Some(waker) = waker {
+                                wakers.push(waker.clone());
+
+                                #[cfg(feature = "tokio")]
+                                if let Some(build_wait) = build_wait {
+                                    build_wait.start();
+                                }
+                            }
+                            let metadata = metadata.clone();
+                            let interest = abort.interest();
                             drop(locked_shard);
                             self.state = State::Loaded {
                                 key_hash: *key_hash,
                                 shard: shard.clone(),
+                                metadata,
+                                interest: Some(interest),
                             };
                             false
                         }
-                        AssetState::Loaded { .. } => {
+                        AssetState::Loaded {
+                            metadata, abort, ..
+                        } => {
+                            let metadata = metadata.clone();
+                            let interest = abort.interest();
                             drop(locked_shard);
                             self.state = State::Loaded {
                                 key_hash: *key_hash,
                                 shard: shard.clone(),
+                                metadata,
+                                interest: Some(interest),
                             };
                             true
                         }
-                        AssetState::Ready { .. } => {
+                        AssetState::Ready { metadata, .. } => {
+                            let metadata = metadata.clone();
                             drop(locked_shard);
                             self.state = State::Loaded {
                                 key_hash: *key_hash,
                                 shard: shard.clone(),
+                                metadata,
+                                interest: None,
                             };
                             true
                         }
                         AssetState::Missing => {
                             drop(locked_shard);
-                            self.state = State::Missing;
-                            return true;
+                            self.state = State::missing(self.id, self.path.clone());
+                            true
                         }
                         AssetState::Error { error } => {
                             let error = error.clone();
                             drop(locked_shard);
                             self.state = State::Error { error };
-                            return true;
+                            true
                         }
                     },
                 }
@@ -214,42 +537,43 @@ impl Handle {
     /// Builds loaded asset if not yet built.
     /// Uses appropriate closure to make result value.
     /// If asset is built `get` is called.
-    /// If asset is missing `missing` is called.
-    /// If asset load or build failed `err` is called.
+    /// If asset is missing or its load or build failed `err` is called.
     ///
     /// # Panics
     ///
     /// This function may panic if called before `poll(PollFor::Load)` returned `true`.
-    fn build<F, G, M, E, R>(&mut self, build_fn: F, get: G, missing: M, err: E) -> R
+    fn build<F, G, E, R>(&mut self, build_fn: F, get: G, err: E) -> R
     where
         F: FnOnce(
             &mut (dyn Any + Send + Sync),
         ) -> Option<Result<Arc<dyn Any + Send + Sync>, Error>>,
         G: FnOnce(&Arc<dyn Any + Send + Sync>) -> R,
-        M: FnOnce(Option<AssetId>, Option<&Arc<str>>) -> R,
         E: FnOnce(&Error) -> R,
     {
         match &mut self.state {
             State::Searching { .. } | State::Loading { .. } => {
                 unreachable!("`poll_load` must be used first")
             }
-            State::Loaded { key_hash, shard } => {
+            State::Loaded {
+                key_hash, shard, ..
+            } => {
                 let id = self
                     .id
                     .expect("This state can be reached only with known id");
 
                 let mut locked_shard = shard.lock();
-                let raw_entry = locked_shard
-                    .raw_entry_mut()
-                    .from_hash(*key_hash, |k| k.eq_key_erased(self.type_id, id));
+                let raw_entry = locked_shard.entry(*key_hash, |k| k.eq_key(self.kind, id));
 
                 match raw_entry {
-                    RawEntryMut::Vacant(_) => {
-                        unreachable!("AssetResult existence guarantee entry is not vacant")
+                    Entry::Vacant(_) => {
+                        drop(locked_shard);
+                        self.removed(id, err)
                     }
-                    RawEntryMut::Occupied(mut entry) => match entry.get_mut() {
+                    Entry::Occupied(mut entry) => match entry.get_mut() {
                         AssetState::Unloaded { .. } => {
-                            unreachable!("`poll_load` must be used first")
+                            // Entry was removed and loading started anew.
+                            drop(locked_shard);
+                            self.removed(id, err)
                         }
                         AssetState::Ready { asset, .. } => {
                             let result = get(asset);
@@ -266,34 +590,52 @@ impl Handle {
                             let mut locked_shard = shard.lock();
                             drop(lock);
 
-                            let raw_entry = locked_shard
-                                .raw_entry_mut()
-                                .from_hash(*key_hash, |k| k.eq_key_erased(self.type_id, id));
+                            let raw_entry =
+                                locked_shard.entry(*key_hash, |k| k.eq_key(self.kind, id));
 
                             match raw_entry {
-                                RawEntryMut::Vacant(_) => unreachable!(),
-                                RawEntryMut::Occupied(mut entry) => match entry.get_mut() {
+                                Entry::Vacant(_) => {
+                                    drop(locked_shard);
+                                    self.removed(id, err)
+                                }
+                                Entry::Occupied(mut entry) => match entry.get_mut() {
                                     AssetState::Unloaded { .. } | AssetState::Missing => {
-                                        unreachable!()
+                                        // Entry was removed while asset was built.
+                                        drop(locked_shard);
+                                        self.removed(id, err)
                                     }
-                                    AssetState::Error { error } => err(&error),
+                                    AssetState::Error { error } => err(error),
                                     AssetState::Ready { asset, .. } => get(asset),
-                                    AssetState::Loaded {
-                                        source, version, ..
-                                    } => match opt {
+                                    AssetState::Loaded { metadata, .. } => match opt {
+                                        #[cfg(not(feature = "async-build"))]
                                         None => unreachable!(),
+                                        #[cfg(feature = "async-build")]
+                                        None => err(&Error::new(BuildInProgress { id })
+                                            .with_code(ErrorCode::BuildFailed)
+                                            .with_stage(ErrorStage::Build)),
                                         Some(result) => match result {
                                             Ok(asset) => {
                                                 let out = get(&asset);
+                                                debug_check(
+                                                    EntryKind::Asset,
+                                                    Some(EntryStatus::Loaded),
+                                                    Event::Built,
+                                                    Some(EntryStatus::Ready),
+                                                );
                                                 *entry.get_mut() = AssetState::Ready {
                                                     asset,
-                                                    source: *source,
-                                                    version: *version,
+                                                    metadata: metadata.clone(),
                                                 };
                                                 out
                                             }
                                             Err(error) => {
                                                 let out = err(&error);
+                                                debug_check(
+                                                    EntryKind::Asset,
+                                                    Some(EntryStatus::Loaded),
+                                                    Event::Failed,
+                                                    Some(EntryStatus::Error),
+                                                );
                                                 *entry.get_mut() = AssetState::Error { error };
                                                 out
                                             }
@@ -304,8 +646,14 @@ impl Handle {
                         }
                         AssetState::Missing => {
                             drop(locked_shard);
-                            self.state = State::Missing;
-                            missing(self.id, self.path.as_ref())
+                            let error = NotFound {
+                                id: self.id,
+                                path: self.path.clone(),
+                            }
+                            .into_error();
+                            let result = err(&error);
+                            self.state = State::Missing { error };
+                            result
                         }
                         AssetState::Error { error } => {
                             let error = error.clone();
@@ -317,48 +665,50 @@ impl Handle {
                     },
                 }
             }
-            State::Ready { asset } => get(asset),
-            State::Missing => missing(self.id, self.path.as_ref()),
-            State::Error { error } => err(error),
+            State::Ready { asset, .. } => get(asset),
+            State::Missing { error } | State::Error { error } => err(error),
         }
     }
 
     /// If asset is loaded and built `get` is called.
-    /// If asset is missing `missing` is called.
-    /// If asset load or build failed `err` is called.
+    /// If asset is missing or its load or build failed `err` is called.
     ///
     /// # Panics
     ///
     /// This function may panic if called before `poll(PollFor::Build)` returned `true`.
-    fn get<G, M, E, R>(&mut self, get: G, missing: M, err: E) -> R
+    fn get<G, E, R>(&mut self, get: G, err: E) -> R
     where
         G: FnOnce(&Arc<dyn Any + Send + Sync>) -> R,
-        M: FnOnce(Option<AssetId>, Option<&Arc<str>>) -> R,
         E: FnOnce(&Error) -> R,
     {
         match &mut self.state {
             State::Searching { .. } | State::Loading { .. } => {
                 unreachable!("`poll_load(..)` must be used first")
             }
-            State::Loaded { key_hash, shard } => {
+            State::Loaded {
+                key_hash, shard, ..
+            } => {
                 let id = self
                     .id
                     .expect("This state can be reached only with known id");
                 let mut locked_shard = shard.lock();
-                let raw_entry = locked_shard
-                    .raw_entry_mut()
-                    .from_hash(*key_hash, |k| k.eq_key_erased(self.type_id, id));
+                let raw_entry = locked_shard.entry(*key_hash, |k| k.eq_key(self.kind, id));
 
                 match raw_entry {
-                    RawEntryMut::Vacant(_) => {
-                        unreachable!("AssetResult existence guarantee entry is not vacant")
+                    Entry::Vacant(_) => {
+                        drop(locked_shard);
+                        self.removed(id, err)
                     }
-                    RawEntryMut::Occupied(mut entry) => match entry.get_mut() {
+                    Entry::Occupied(mut entry) => match entry.get_mut() {
                         AssetState::Unloaded { .. } => {
-                            unreachable!("`poll(..)` must be used first")
+                            // Entry was removed and loading started anew.
+                            drop(locked_shard);
+                            self.removed(id, err)
                         }
                         AssetState::Loaded { .. } => {
-                            unreachable!("`poll(true, ..)` must be used first")
+                            // Entry was removed and loaded anew.
+                            drop(locked_shard);
+                            self.removed(id, err)
                         }
                         AssetState::Ready { asset, .. } => {
                             let result = get(asset);
@@ -367,8 +717,14 @@ impl Handle {
                         }
                         AssetState::Missing => {
                             drop(locked_shard);
-                            self.state = State::Missing;
-                            missing(self.id, self.path.as_ref())
+                            let error = NotFound {
+                                id: self.id,
+                                path: self.path.clone(),
+                            }
+                            .into_error();
+                            let result = err(&error);
+                            self.state = State::Missing { error };
+                            result
                         }
                         AssetState::Error { error } => {
                             let error = error.clone();
@@ -380,9 +736,8 @@ impl Handle {
                     },
                 }
             }
-            State::Ready { asset } => get(asset),
-            State::Missing => missing(self.id, self.path.as_ref()),
-            State::Error { error } => err(error),
+            State::Ready { asset, .. } => get(asset),
+            State::Missing { error } | State::Error { error } => err(error),
         }
     }
 }
@@ -398,6 +753,225 @@ impl Handle {
 /// using `AssetHandle::driver()`. This way drivers for any asset types that share
 /// the same builder type can be stored in the same collection and
 /// polled together.
+///
+/// Handle requested with path waits for the asset to be found first.
+/// Tasks waiting for id are woken when the path is resolved.
+/// Tasks waiting for loaded or ready asset are woken
+/// only when asset reaches that state, or when it fails.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use futures::{future::BoxFuture, task::ArcWake};
+/// # use std::{future::Future, pin::Pin, sync::{atomic::{AtomicUsize, Ordering}, Arc}, task::{Context, Poll}};
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// /// Source that holds lookups and loads until permitted.
+/// /// Fails to load if `broken` is set.
+/// struct Gated {
+///     inner: MemorySource,
+///     broken: bool,
+///     finds: Arc<tokio::sync::Semaphore>,
+///     loads: Arc<tokio::sync::Semaphore>,
+/// }
+///
+/// impl Source for Gated {
+///     fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+///         Box::pin(async move {
+///             self.finds.acquire().await.unwrap().forget();
+///             self.inner.find(path, asset).await
+///         })
+///     }
+///
+///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+///         Box::pin(async move {
+///             self.loads.acquire().await.unwrap().forget();
+///             if self.broken {
+///                 return Err(Error::new(std::io::Error::from(std::io::ErrorKind::InvalidData)));
+///             }
+///             self.inner.load(id).await
+///         })
+///     }
+///
+///     fn update<'a>(
+///         &'a self,
+///         id: AssetId,
+///         version: u64,
+///     ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+///         self.inner.update(id, version)
+///     }
+/// }
+///
+/// /// Counts wakes of the task.
+/// struct Wakes(AtomicUsize);
+///
+/// impl ArcWake for Wakes {
+///     fn wake_by_ref(wakes: &Arc<Self>) {
+///         wakes.0.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// struct Waiter<F> {
+///     future: F,
+///     wakes: Arc<Wakes>,
+/// }
+///
+/// impl<F: Future + Unpin> Waiter<F> {
+///     fn new(future: F) -> Self {
+///         Waiter { future, wakes: Arc::new(Wakes(AtomicUsize::new(0))) }
+///     }
+///
+///     fn wakes(&self) -> usize {
+///         self.wakes.0.load(Ordering::SeqCst)
+///     }
+///
+///     fn poll(&mut self) -> Poll<F::Output> {
+///         let waker = futures::task::waker(self.wakes.clone());
+///         Pin::new(&mut self.future).poll(&mut Context::from_waker(&waker))
+///     }
+/// }
+///
+/// async fn settle() {
+///     for _ in 0..20 {
+///         tokio::task::yield_now().await;
+///     }
+/// }
+///
+/// /// State of the asset entry when path is resolved.
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Entry {
+///     Absent,
+///     Loading,
+///     Loaded,
+///     Ready,
+///     Missing,
+///     Error,
+/// }
+///
+/// async fn check(entry: Entry) -> Result<(), Error> {
+///     let id = AssetId::new(1).unwrap();
+///     let inner = MemorySource::new();
+///     inner.insert_with_path("number", id, &br#"{ "value": 5 }"#[..]);
+///     if entry == Entry::Missing {
+///         inner.remove(id);
+///     }
+///
+///     let finds = Arc::new(tokio::sync::Semaphore::new(0));
+///     let loads = Arc::new(tokio::sync::Semaphore::new(0));
+///     let loader = Loader::builder()
+///         .with(Gated {
+///             inner,
+///             broken: entry == Entry::Error,
+///             finds: finds.clone(),
+///             loads: loads.clone(),
+///         })
+///         .build();
+///
+///     // Put asset entry into expected state.
+///     let mut by_id = Waiter::new(loader.load::<Number, _>(id));
+///     let mut loaded = None;
+///     if entry == Entry::Absent {
+///         drop(by_id);
+///     } else {
+///         assert!(by_id.poll().is_pending());
+///         if entry != Entry::Loading {
+///             loads.add_permits(1);
+///             settle().await;
+///             match by_id.poll() {
+///                 Poll::Ready(Ok(asset)) => loaded = Some(asset),
+///                 Poll::Ready(Err(_)) => {}
+///                 Poll::Pending => unreachable!(),
+///             }
+///         }
+///         if entry == Entry::Ready {
+///             loaded.as_mut().unwrap().build(&mut ())?;
+///         }
+///     }
+///
+///     // Wait for each state before the path is resolved.
+///     let mut lookup = Waiter::new(loader.load::<Number, _>("number").id());
+///     let mut load = Waiter::new(loader.load::<Number, _>("number"));
+///     let mut ready = Waiter::new(loader.load::<Number, _>("number").ready());
+///     assert!(lookup.poll().is_pending());
+///     assert!(load.poll().is_pending());
+///     assert!(ready.poll().is_pending());
+///
+///     finds.add_permits(1);
+///     settle().await;
+///
+///     // Id is known now, only waiter for it is woken.
+///     assert_eq!(lookup.wakes(), 1);
+///     assert!(matches!(lookup.poll(), Poll::Ready(Ok(found)) if found == id));
+///
+///     let terminal = matches!(entry, Entry::Missing | Entry::Error);
+///     match entry {
+///         Entry::Absent | Entry::Loading => {
+///             assert_eq!(load.wakes(), 0);
+///             assert_eq!(ready.wakes(), 0);
+///             loads.add_permits(1);
+///             settle().await;
+///             assert_eq!(load.wakes(), 1);
+///             assert!(matches!(load.poll(), Poll::Ready(Ok(_))));
+///         }
+///         Entry::Loaded => {
+///             assert_eq!(load.wakes(), 1);
+///             assert!(matches!(load.poll(), Poll::Ready(Ok(_))));
+///             assert_eq!(ready.wakes(), 0);
+///         }
+///         Entry::Ready | Entry::Missing | Entry::Error => {
+///             assert_eq!(load.wakes(), 1);
+///             assert_eq!(ready.wakes(), 1);
+///             assert_eq!(matches!(load.poll(), Poll::Ready(Ok(_))), !terminal);
+///         }
+///     }
+///
+///     if entry == Entry::Absent || entry == Entry::Loading {
+///         // Asset is decoded, waiter for ready asset waits for it to be built.
+///         assert!(ready.poll().is_pending());
+///         let wakes = ready.wakes();
+///         loader.load::<Number, _>(id).await?.build(&mut ())?;
+///         assert_eq!(ready.wakes(), wakes + 1);
+///     } else if entry == Entry::Loaded {
+///         loaded.as_mut().unwrap().build(&mut ())?;
+///         assert_eq!(ready.wakes(), 1);
+///     }
+///
+///     match ready.poll() {
+///         Poll::Ready(Ok(number)) => assert_eq!(number.value, 5),
+///         Poll::Ready(Err(_)) => assert!(terminal),
+///         Poll::Pending => unreachable!(),
+///     }
+///
+///     // Waiters created after the path is resolved see the same states.
+///     let mut late = Waiter::new(loader.load::<Number, _>("number").ready());
+///     assert_eq!(matches!(late.poll(), Poll::Ready(Ok(_))), !terminal);
+///     Ok(())
+/// }
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         for entry in [
+///             Entry::Absent,
+///             Entry::Loading,
+///             Entry::Loaded,
+///             Entry::Ready,
+///             Entry::Missing,
+///             Entry::Error,
+///         ] {
+///             check(entry).await?;
+///         }
+///         Ok::<_, Error>(())
+///     })?;
+/// # }
+/// # Ok::<_, Error>(())
+/// ```
 #[derive(Clone)]
 pub struct AssetHandle<A> {
     /// If asset is already loaded and built this field contains it.
@@ -405,6 +979,9 @@ pub struct AssetHandle<A> {
 
     /// Internal handle implementation.
     handle: Handle,
+
+    /// Set when handle resolved as future.
+    done: bool,
 }
 
 impl<A> Unpin for AssetHandle<A> {}
@@ -417,10 +994,10 @@ where
         match (&self.handle.id, &self.handle.path) {
             (None, None) => unreachable!(),
             (_, Some(path)) => {
-                write!(f, "{}({})", A::name(), path)
+                write!(f, "{}({})", A::asset_name(), path)
             }
             (Some(id), _) => {
-                write!(f, "{}({})", A::name(), id)
+                write!(f, "{}({})", A::asset_name(), id)
             }
         }
     }
@@ -428,13 +1005,17 @@ where
 
 impl<A> PartialEq for AssetHandle<A> {
     fn eq(&self, other: &Self) -> bool {
-        match (self.handle.id, other.handle.id) {
-            (Some(id1), Some(id2)) => return id1 == id2,
-            _ => {}
+        // Different sub-assets of the same artifact.
+        if self.handle.kind != other.handle.kind {
+            return false;
+        }
+        if let (Some(id1), Some(id2)) = (self.handle.id, other.handle.id) {
+            return id1 == id2;
         }
-        match (self.handle.path.as_deref(), other.handle.path.as_deref()) {
-            (Some(path1), Some(path2)) => return path1 == path2,
-            _ => {}
+        if let (Some(path1), Some(path2)) =
+            (self.handle.path.as_deref(), other.handle.path.as_deref())
+        {
+            return path1 == path2;
         }
 
         // It maybe refer to the same asset, but one handle is fetched with id
@@ -448,6 +1029,7 @@ impl<A> AssetHandle<A> {
         AssetHandle {
             result: None,
             handle,
+            done: false,
         }
     }
 }
@@ -459,9 +1041,42 @@ impl<A> AssetHandle<A> {
     pub fn id(self) -> AssetLookup {
         AssetLookup {
             handle: self.handle,
+            done: false,
         }
     }
 
+    /// Returns value of the asset property reported by the source.
+    /// Returns `None` if property is not set
+    /// or if asset is not loaded yet.
+    #[inline]
+    pub fn property(&self, key: &str) -> Option<Arc<str>> {
+        self.handle.property(key)
+    }
+
+    /// Returns `true` if asset is loaded by this handle
+    /// and its dependencies were reloaded after it was decoded.
+    ///
+    /// Handle that already resolved to the asset keeps the old value,
+    /// load the asset again to get the value rebuilt with [`Loader::enable_cascade`].
+    ///
+    /// [`Loader::enable_cascade`]: crate::Loader::enable_cascade
+    #[inline]
+    pub fn is_stale(&self) -> bool {
+        self.handle.is_stale(self.result.is_some())
+    }
+
+    /// Returns decoding progress reported with [`Loader::report_progress`].
+    ///
+    /// Reads the progress without locking and without polling the handle.
+    /// Returns `None` if decoder did not report progress
+    /// or asset is not being loaded by this handle.
+    ///
+    /// [`Loader::report_progress`]: crate::Loader::report_progress
+    #[inline]
+    pub fn progress(&self) -> Option<Progress> {
+        self.handle.progress()
+    }
+
     /// Polls for asset loaded via path to be identified.
     /// Returns some result with asset or error.
     /// Returns none if asset is not yet identified.
@@ -479,9 +1094,112 @@ impl<A> AssetHandle<A> {
     }
 }
 
+#[cfg(feature = "serde-handles")]
+impl<A> serde::Serialize for AssetHandle<A> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.handle.serialize(serializer)
+    }
+}
+
 /// Future to wait for asset loaded via path to be identified.
+///
+/// Futures waiting for the asset itself are not woken when asset is identified.
+/// They are woken once asset is loaded.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use std::{future::Future, pin::Pin, sync::{Arc, atomic::{AtomicBool, AtomicUsize, Ordering}}, task::{Context, Wake, Waker}};
+/// # #[cfg(feature = "tokio")] {
+/// static GATE: AtomicBool = AtomicBool::new(false);
+///
+/// /// Asset that is decoded only after gate is open.
+/// #[derive(Clone)]
+/// struct Gated;
+///
+/// impl Asset for Gated {
+///     type Decoded = ();
+///     type DecodeError = std::convert::Infallible;
+///     type BuildError = std::convert::Infallible;
+///     type Fut = futures::future::BoxFuture<'static, Result<(), std::convert::Infallible>>;
+///
+///     fn asset_name() -> AssetName {
+///         AssetName::new("Gated")
+///     }
+///
+///     fn decode(_bytes: Box<[u8]>, _loader: &Loader) -> Self::Fut {
+///         Box::pin(async {
+///             while !GATE.load(Ordering::SeqCst) {
+///                 tokio::task::yield_now().await;
+///             }
+///             Ok(())
+///         })
+///     }
+/// }
+///
+/// impl AssetBuild<()> for Gated {
+///     fn build(_: &mut (), _: ()) -> Result<Self, std::convert::Infallible> {
+///         Ok(Gated)
+///     }
+/// }
+///
+/// /// Waker that counts wake-ups.
+/// #[derive(Default)]
+/// struct Counter(AtomicUsize);
+///
+/// impl Wake for Counter {
+///     fn wake(self: Arc<Self>) {
+///         self.0.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// let source = MemorySource::new();
+/// source.insert_with_path("gated", AssetId::new(1).unwrap(), &b""[..]);
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let mut loaded = loader.load::<Gated, _>("gated");
+///         let mut lookup = loaded.clone().id();
+///
+///         let id_counter = Arc::new(Counter::default());
+///         let load_counter = Arc::new(Counter::default());
+///         let id_waker = Waker::from(id_counter.clone());
+///         let load_waker = Waker::from(load_counter.clone());
+///
+///         assert!(Pin::new(&mut lookup).poll(&mut Context::from_waker(&id_waker)).is_pending());
+///         assert!(Pin::new(&mut loaded).poll(&mut Context::from_waker(&load_waker)).is_pending());
+///
+///         for _ in 0..10 {
+///             tokio::task::yield_now().await;
+///         }
+///
+///         // Asset is identified, but not loaded yet.
+///         assert_eq!(id_counter.0.load(Ordering::SeqCst), 1);
+///         assert_eq!(load_counter.0.load(Ordering::SeqCst), 0);
+///         assert!(Pin::new(&mut lookup).poll(&mut Context::from_waker(&id_waker)).is_ready());
+///
+///         GATE.store(true, Ordering::SeqCst);
+///         for _ in 0..10 {
+///             tokio::task::yield_now().await;
+///         }
+///
+///         // Asset is loaded.
+///         assert_eq!(id_counter.0.load(Ordering::SeqCst), 1);
+///         assert_eq!(load_counter.0.load(Ordering::SeqCst), 1);
+///         assert!(Pin::new(&mut loaded).poll(&mut Context::from_waker(&load_waker)).is_ready());
+///     });
+/// # }
+/// ```
 pub struct AssetLookup {
     handle: Handle,
+    done: bool,
 }
 
 impl Future for AssetLookup {
@@ -490,17 +1208,27 @@ impl Future for AssetLookup {
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let me = self.get_mut();
         if let Some(id) = me.handle.id {
+            me.done = true;
             return Poll::Ready(Ok(id));
         }
 
-        if !me.handle.poll(PollFor::Id, Some(cx.waker())) {
+        // Handle in final state resolves without locking.
+        if !me.done && !me.handle.poll(PollFor::Id, Some(cx.waker())) {
             return Poll::Pending;
         }
 
+        me.done = true;
         Poll::Ready(me.handle.id())
     }
 }
 
+impl FusedFuture for AssetLookup {
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
 impl<A> AssetHandle<A>
 where
     A: Clone + 'static,
@@ -512,6 +1240,7 @@ where
         AssetFuture {
             result: self.result,
             handle: self.handle,
+            done: false,
         }
     }
 
@@ -533,12 +1262,6 @@ where
                 let asset = asset.downcast_ref::<A>().unwrap();
                 Ok(asset.clone())
             },
-            |id, path| {
-                Err(Error::new(NotFound {
-                    path: path.cloned(),
-                    id,
-                }))
-            },
             |err| Err(err.clone()),
         );
 
@@ -548,9 +1271,68 @@ where
 }
 
 /// Future to wait for asset to be ready.
+///
+/// All asset futures implement [`FusedFuture`]
+/// and resolve to the same result when polled after completion.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use std::task::Poll;
+/// # use futures::future::FusedFuture;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let source = MemorySource::new();
+/// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 5 }"#[..]);
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let mut lookup = loader.load::<Number, _>("number").id();
+///         let mut handle = loader.load::<Number, _>("number");
+///         let mut driver = loader.load::<Number, _>("number").driver::<SimpleDrive<()>>();
+///         let mut ready = loader.load::<Number, _>("number").ready();
+///
+///         let mut value = None;
+///         loop {
+///             futures::select! {
+///                 id = lookup => assert_eq!(id?, AssetId::new(1).unwrap()),
+///                 loaded = handle => assert_eq!(loaded?.build(&mut ())?.value, 5),
+///                 loaded = driver => loaded.build(&mut ()),
+///                 number = ready => value = Some(number?.value),
+///                 complete => break,
+///             }
+///         }
+///         assert_eq!(value, Some(5));
+///
+///         assert!(lookup.is_terminated());
+///         assert!(handle.is_terminated());
+///         assert!(driver.is_terminated());
+///         assert!(ready.is_terminated());
+///
+///         // Polling after completion repeats the result.
+///         for _ in 0..2 {
+///             assert!(matches!(futures::poll!(&mut lookup), Poll::Ready(Ok(id)) if id == AssetId::new(1).unwrap()));
+///             assert!(matches!(futures::poll!(&mut handle), Poll::Ready(Ok(_))));
+///             assert!(matches!(futures::poll!(&mut driver), Poll::Ready(_)));
+///             assert!(matches!(futures::poll!(&mut ready), Poll::Ready(Ok(Number { value: 5 }))));
+///         }
+///         Ok::<_, Error>(())
+///     })
+///     .unwrap();
+/// # }
+/// ```
 pub struct AssetFuture<A> {
     result: Option<Result<A, Error>>,
     handle: Handle,
+    done: bool,
 }
 
 impl<A> Unpin for AssetFuture<A> {}
@@ -565,6 +1347,7 @@ where
         let me = self.get_mut();
 
         if let Some(result) = me.result.clone() {
+            me.done = true;
             return Poll::Ready(result);
         }
 
@@ -577,20 +1360,77 @@ where
                 let asset = asset.downcast_ref::<A>().unwrap();
                 Ok(asset.clone())
             },
-            |id, path| {
-                Err(Error::new(NotFound {
-                    path: path.cloned(),
-                    id,
-                }))
-            },
             |err| Err(err.clone()),
         );
 
         me.result = Some(result.clone());
+        me.done = true;
         Poll::Ready(result)
     }
 }
 
+impl<A> AssetFuture<A> {
+    /// Returns a future that builds the asset with provided builder
+    /// if it is loaded but not built yet, instead of waiting for something else to build it.
+    /// Resolves to asset or error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 3 }"#[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         // No other task builds the number.
+    ///         let ready = loader.load::<Number, _>("number").ready();
+    ///         let number = ready.ready_or_build(()).await?;
+    ///         assert_eq!(number.value, 3);
+    ///
+    ///         // Built asset is shared with other handles.
+    ///         let number = loader.load::<Number, _>("number").ready().await?;
+    ///         assert_eq!(number.value, 3);
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn ready_or_build<B>(self, builder: B) -> AssetBuilt<A, B>
+    where
+        A: AssetBuild<B>,
+    {
+        AssetBuilt {
+            handle: AssetHandle {
+                result: self.result,
+                handle: self.handle,
+                done: false,
+            },
+            builder,
+        }
+    }
+}
+
+impl<A> FusedFuture for AssetFuture<A>
+where
+    A: Clone + 'static,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
 impl<A> AssetHandle<A>
 where
     A: Clone,
@@ -619,11 +1459,7 @@ where
         }
 
         match &self.handle.state {
-            State::Error { error } => Some(Err(error.clone())),
-            State::Missing => Some(Err(Error::new(NotFound {
-                id: self.handle.id.clone(),
-                path: self.handle.path.clone(),
-            }))),
+            State::Error { error } | State::Missing { error } => Some(Err(error.clone())),
             State::Searching { .. } => unreachable!(),
             _ => Some(Ok(LoadedAsset {
                 result: None,
@@ -640,6 +1476,11 @@ where
     where
         A: AssetBuild<B>,
     {
+        // Asset reloaded with `Loader::poll_updates` is built anew.
+        if self.handle.reset_superseded() {
+            self.result = None;
+        }
+
         if let Some(result) = self.result.clone() {
             return Some(result);
         }
@@ -648,51 +1489,153 @@ where
             return None;
         }
 
-        let result = self.handle.build(
-            move |decoded| {
-                let decoded = decoded.downcast_mut::<DecodedState<A>>().unwrap().take()?;
+        Some(self.build_loaded(builder))
+    }
 
-                match A::build(builder, decoded) {
-                    Ok(asset) => Some(Ok(Arc::new(asset.clone()))),
-                    Err(err) => {
-                        let err = Error::new(err);
-                        Some(Err(err.clone()))
-                    }
-                }
-            },
-            |asset| {
+    /// Returns a future to wait for asset to be loaded and build it with provided builder.
+    /// Resolves to asset or error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 3 }"#[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let number = loader.load::<Number, _>("number").built(()).await?;
+    ///         assert_eq!(number.value, 3);
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn built<B>(self, builder: B) -> AssetBuilt<A, B>
+    where
+        A: AssetBuild<B>,
+    {
+        AssetBuilt {
+            handle: self,
+            builder,
+        }
+    }
+
+    /// Builds asset after handle is polled to loaded state.
+    fn build_loaded<B>(&mut self, builder: &mut B) -> Result<A, Error>
+    where
+        A: AssetBuild<B>,
+    {
+        let result = self.handle.build(
+            move |decoded| {
+                let decoded = decoded.downcast_mut::<DecodedState<A>>().unwrap().take()?;
+
+                match A::build(builder, decoded) {
+                    Ok(asset) => Some(Ok(Arc::new(asset.clone()))),
+                    Err(err) => {
+                        let err = Error::new(err)
+                            .or_code(ErrorCode::BuildFailed)
+                            .with_stage(ErrorStage::Build);
+                        Some(Err(err.clone()))
+                    }
+                }
+            },
+            |asset| {
                 let asset = asset.downcast_ref::<A>().unwrap();
                 Ok(asset.clone())
             },
-            |id, path| {
-                Err(Error::new(NotFound {
-                    path: path.cloned(),
-                    id,
-                }))
-            },
             |err| Err(err.clone()),
         );
 
         self.result = Some(result.clone());
-        Some(result)
+        result
     }
 }
 
-impl<A> Future for AssetHandle<A> {
+/// Future to wait for asset to be loaded and built.
+pub struct AssetBuilt<A, B> {
+    handle: AssetHandle<A>,
+    builder: B,
+}
+
+// Builder is never pinned.
+impl<A, B> Unpin for AssetBuilt<A, B> {}
+
+impl<A, B> Future for AssetBuilt<A, B>
+where
+    A: AssetBuild<B>,
+{
+    type Output = Result<A, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<A, Error>> {
+        let me = self.get_mut();
+
+        if let Some(result) = me.handle.result.clone() {
+            return Poll::Ready(result);
+        }
+
+        if !me.handle.handle.poll(PollFor::Load, Some(cx.waker())) {
+            return Poll::Pending;
+        }
+
+        Poll::Ready(me.handle.build_loaded(&mut me.builder))
+    }
+}
+
+impl<A, B> FusedFuture for AssetBuilt<A, B>
+where
+    A: AssetBuild<B>,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.handle.result.is_some()
+    }
+}
+
+impl<A> AssetHandle<A> {
+    /// Returns loaded asset that yields `asset` instead of the asset of this handle.
+    pub(crate) fn placeholder(&self, asset: A) -> LoadedAsset<A> {
+        LoadedAsset {
+            result: Some(Ok(asset)),
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+impl<A> Future for AssetHandle<A>
+where
+    A: Clone,
+{
     type Output = Result<LoadedAsset<A>, Error>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<LoadedAsset<A>, Error>> {
         let me = self.get_mut();
-        if !me.handle.poll(PollFor::Load, Some(cx.waker())) {
+
+        // Handle in final state resolves without locking.
+        if !me.done && !me.handle.poll(PollFor::Load, Some(cx.waker())) {
             return Poll::Pending;
         }
+        me.done = true;
+
+        if let Some(result) = &me.result {
+            return Poll::Ready(result.clone().map(|asset| LoadedAsset {
+                result: Some(Ok(asset)),
+                handle: me.handle.clone(),
+            }));
+        }
 
         match &me.handle.state {
-            State::Error { error } => Poll::Ready(Err(error.clone())),
-            State::Missing => Poll::Ready(Err(Error::new(NotFound {
-                id: me.handle.id.clone(),
-                path: me.handle.path.clone(),
-            }))),
+            State::Error { error } | State::Missing { error } => Poll::Ready(Err(error.clone())),
             State::Searching { .. } => unreachable!(),
             _ => Poll::Ready(Ok(LoadedAsset {
                 result: None,
@@ -702,8 +1645,58 @@ impl<A> Future for AssetHandle<A> {
     }
 }
 
+impl<A> FusedFuture for AssetHandle<A>
+where
+    A: Clone,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
 /// Handle returned by awaiting on `AssetHandle::loaded()`.
 /// The asset is loaded and can be built.
+///
+/// Clones share the loaded asset, so it can be kept to build again later.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let source = MemorySource::new();
+/// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 7 }"#[..]);
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let mut loaded = loader.load::<Number, _>("number").await?;
+///         assert_eq!(loaded.id(), AssetId::new(1));
+///         assert_eq!(loaded.path(), Some("number"));
+///         assert_eq!(loaded.loader_state(), Some(EntryStatus::Loaded));
+///
+///         let mut copy = loaded.clone();
+///         assert_eq!(loaded.build(&mut ())?.value, 7);
+///         assert_eq!(copy.loader_state(), Some(EntryStatus::Ready));
+///         assert_eq!(copy.build(&mut ())?.value, 7);
+///
+///         // Back to the handle, e.g. to wait for it again later.
+///         let handle = copy.into_handle();
+///         assert_eq!(handle.await?.build(&mut ())?.value, 7);
+///         Ok::<_, Error>(())
+///     })?;
+/// # }
+/// # Ok::<_, Error>(())
+/// ```
+#[derive(Clone)]
 pub struct LoadedAsset<A> {
     /// If asset is already loaded and built this field contains it.
     result: Option<Result<A, Error>>,
@@ -711,6 +1704,65 @@ pub struct LoadedAsset<A> {
     handle: Handle,
 }
 
+#[cfg(feature = "serde-handles")]
+impl<A> serde::Serialize for LoadedAsset<A> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.handle.serialize(serializer)
+    }
+}
+
+impl<A> LoadedAsset<A> {
+    /// Returns id of the loaded asset.
+    #[inline]
+    pub fn id(&self) -> Option<AssetId> {
+        self.handle.id
+    }
+
+    /// Returns path the asset was requested with, if any.
+    #[inline]
+    pub fn path(&self) -> Option<&str> {
+        self.handle.path.as_deref()
+    }
+
+    /// Returns state of the asset in the loader cache.
+    /// Returns `None` if asset was unloaded from the cache.
+    #[inline]
+    pub fn loader_state(&self) -> Option<EntryStatus> {
+        self.handle.status()
+    }
+
+    /// Converts back into asset handle.
+    #[inline]
+    pub fn into_handle(self) -> AssetHandle<A> {
+        AssetHandle {
+            result: self.result,
+            handle: self.handle,
+            done: false,
+        }
+    }
+
+    /// Returns metadata of the loaded asset.
+    #[inline]
+    pub fn metadata(&self) -> AssetMetadata {
+        self.handle.metadata()
+    }
+
+    /// Returns value of the asset property reported by the source.
+    #[inline]
+    pub fn property(&self, key: &str) -> Option<Arc<str>> {
+        self.handle.property(key)
+    }
+
+    /// Returns `true` if dependencies of the asset were reloaded after it was decoded.
+    #[inline]
+    pub fn is_stale(&self) -> bool {
+        self.handle.is_stale(self.result.is_some())
+    }
+}
+
 impl<A> LoadedAsset<A>
 where
     A: Asset,
@@ -735,7 +1787,9 @@ where
                 match A::build(builder, decoded) {
                     Ok(asset) => Some(Ok(Arc::new(asset.clone()))),
                     Err(err) => {
-                        let err = Error::new(err);
+                        let err = Error::new(err)
+                            .or_code(ErrorCode::BuildFailed)
+                            .with_stage(ErrorStage::Build);
                         Some(Err(err.clone()))
                     }
                 }
@@ -744,21 +1798,225 @@ where
                 let asset = asset.downcast_ref::<A>().unwrap();
                 Ok(asset.clone())
             },
-            |id, path| {
-                Err(Error::new(NotFound {
-                    path: path.cloned(),
-                    id,
-                }))
-            },
             |err| Err(err.clone()),
         )
     }
 }
 
+#[cfg(feature = "async-build")]
+impl<A> LoadedAsset<A>
+where
+    A: Asset,
+{
+    /// Build loaded asset with builder shared behind async mutex.
+    /// Returns result with asset or error.
+    ///
+    /// Builder is locked for each field separately,
+    /// so other tasks may use it between fields.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// #[derive(Default)]
+    /// struct Renderer {
+    ///     log: Vec<&'static str>,
+    /// }
+    ///
+    /// #[derive(Clone)]
+    /// struct Texture;
+    ///
+    /// impl Asset for Texture {
+    ///     type Decoded = ();
+    ///     type DecodeError = std::convert::Infallible;
+    ///     type BuildError = std::convert::Infallible;
+    ///     type Fut = std::future::Ready<Result<(), std::convert::Infallible>>;
+    ///
+    ///     fn asset_name() -> AssetName {
+    ///         AssetName::new("Texture")
+    ///     }
+    ///
+    ///     fn decode(_: Box<[u8]>, _: &Loader) -> Self::Fut {
+    ///         std::future::ready(Ok(()))
+    ///     }
+    /// }
+    ///
+    /// impl AssetBuild<Renderer> for Texture {
+    ///     fn build(renderer: &mut Renderer, _: ()) -> Result<Texture, std::convert::Infallible> {
+    ///         renderer.log.push("texture");
+    ///         Ok(Texture)
+    ///     }
+    /// }
+    ///
+    /// #[derive(Clone, Asset)]
+    /// struct Material {
+    ///     #[asset(external)]
+    ///     albedo: Texture,
+    ///     #[asset(external)]
+    ///     normal: Texture,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert(AssetId::new(1).unwrap(), &b""[..]);
+    /// source.insert(AssetId::new(2).unwrap(), &b""[..]);
+    /// source.insert_with_path("brick", AssetId::new(3).unwrap(), &br#"{ "albedo": "1", "normal": "2" }"#[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// let renderer = AsyncShared::new(Renderer::default());
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let mut material = loader.load::<Material, _>("brick").await?;
+    ///
+    ///         // Competing task acquires the builder between fields.
+    ///         let (material, ()) = futures::join!(material.build_async(&renderer), async {
+    ///             renderer.lock().await.log.push("frame");
+    ///         });
+    ///         material?;
+    ///
+    ///         assert_eq!(renderer.lock().await.log, ["texture", "frame", "texture"]);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub async fn build_async<B>(&mut self, builder: &AsyncShared<B>) -> Result<A, Error>
+    where
+        A: AsyncAssetBuild<B>,
+    {
+        if let Some(result) = &self.result {
+            match result {
+                Ok(asset) => return Ok(asset.clone()),
+                Err(error) => return Err(error.clone()),
+            }
+        }
+
+        crate::build_async::build(&self.handle, builder).await
+    }
+}
+
+impl<A> LoadedAsset<A> {
+    /// Returns wrapper that builds the asset with `builder` on first access.
+    #[inline]
+    pub fn auto<B>(self, builder: &RefCell<B>) -> AutoAsset<'_, A, B> {
+        AutoAsset::new(self, builder)
+    }
+}
+
+/// Loaded asset that is built on first access.
+///
+/// Holds [`LoadedAsset`] and reference to the builder.
+/// Asset is built when it is accessed first time and the result is cached.
+///
+/// Dereferencing panics if asset fails to build.
+/// Use [`AutoAsset::try_deref`] to handle build errors.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use std::cell::RefCell;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let source = MemorySource::new();
+/// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 7 }"#[..]);
+/// let loader = Loader::builder().with(source).build();
+///
+/// let builder = RefCell::new(());
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let number = loader.load::<Number, _>("number").await?.auto(&builder);
+///
+///         // Built here.
+///         assert_eq!(number.value, 7);
+///         assert_eq!(number.try_deref()?.value, 7);
+///         Ok::<_, Error>(())
+///     })
+///     .unwrap();
+/// # }
+/// ```
+pub struct AutoAsset<'b, A, B> {
+    loaded: RefCell<LoadedAsset<A>>,
+    builder: &'b RefCell<B>,
+    result: OnceCell<Result<A, Error>>,
+}
+
+impl<'b, A, B> AutoAsset<'b, A, B> {
+    /// Returns wrapper that builds `loaded` asset with `builder` on first access.
+    #[inline]
+    pub fn new(loaded: LoadedAsset<A>, builder: &'b RefCell<B>) -> Self {
+        AutoAsset {
+            loaded: RefCell::new(loaded),
+            builder,
+            result: OnceCell::new(),
+        }
+    }
+
+    /// Returns metadata of the loaded asset.
+    #[inline]
+    pub fn metadata(&self) -> AssetMetadata {
+        self.loaded.borrow().metadata()
+    }
+}
+
+impl<A, B> AutoAsset<'_, A, B>
+where
+    A: AssetBuild<B>,
+{
+    /// Builds asset if not yet built and returns reference to it.
+    /// Returns error if asset fails to build.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if builder is borrowed when asset is being built.
+    pub fn try_deref(&self) -> Result<&A, Error> {
+        let result = self.result.get_or_init(|| {
+            let mut builder = self.builder.borrow_mut();
+            self.loaded.borrow_mut().build(&mut *builder)
+        });
+
+        match result {
+            Ok(asset) => Ok(asset),
+            Err(error) => Err(error.clone()),
+        }
+    }
+}
+
+impl<A, B> Deref for AutoAsset<'_, A, B>
+where
+    A: AssetBuild<B>,
+{
+    type Target = A;
+
+    /// Builds asset if not yet built and returns reference to it.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if asset fails to build
+    /// or if builder is borrowed when asset is being built.
+    fn deref(&self) -> &A {
+        match self.try_deref() {
+            Ok(asset) => asset,
+            Err(error) => panic!("Failed to build asset. {}", error),
+        }
+    }
+}
+
+/// Selects builder type for [`AssetDriver`].
 pub trait DriveAsset {
+    /// Builder passed to build the asset.
     type Builder<'a>;
 }
 
+/// Drive type for assets built with builder `B`.
 pub enum SimpleDrive<B> {
     #[doc(hidden)]
     _Unused(B),
@@ -768,31 +2026,89 @@ impl<B> DriveAsset for SimpleDrive<B> {
     type Builder<'a> = B;
 }
 
+/// Drive type for assets built without builder, i.e. with `()`.
 pub enum NoBuilderDrive {}
 
 impl DriveAsset for NoBuilderDrive {
     type Builder<'a> = ();
 }
 
+/// Drive type for builders bound to a thread, e.g. holding raw graphics context.
+///
+/// Same as [`SimpleDrive`], but builder is not required to be `Send`
+/// and [`BuildQueue`] of this drive type stays on its thread.
+///
+/// [`BuildQueue`]: crate::BuildQueue
+pub enum LocalDrive<B> {
+    #[doc(hidden)]
+    _Unused(B, PhantomData<*const ()>),
+}
+
+impl<B> DriveAsset for LocalDrive<B> {
+    type Builder<'a> = B;
+}
+
 impl<A> AssetHandle<A>
 where
     A: Asset,
 {
     /// Returns a future to wait for asset to be loaded
     /// erasing asset type but providing specific builder type.
+    ///
+    /// Driver may be moved to other threads and built there,
+    /// so builder must be `Send`.
+    /// Use [`AssetHandle::local_driver`] for builders bound to a thread.
+    ///
+    /// ```compile_fail
+    /// # use argosy::*;
+    /// # use std::rc::Rc;
+    /// # #[derive(Clone, Asset)]
+    /// # struct Number { value: u32 }
+    /// # let loader = Loader::builder().build();
+    /// let driver = loader.load::<Number, _>("number").driver::<SimpleDrive<Rc<()>>>();
+    /// ```
     #[inline]
     pub fn driver<D>(self) -> AssetDriver<D>
     where
         D: DriveAsset,
+        for<'a> D::Builder<'a>: Send,
         A: for<'a> AssetBuild<D::Builder<'a>>,
     {
         AssetDriver {
             handle: self.handle,
             build_fn: build_fn::<A, D>,
+            done: false,
+        }
+    }
+
+    /// Returns a future to wait for asset to be loaded
+    /// erasing asset type but providing specific builder type.
+    ///
+    /// Unlike [`AssetHandle::driver`] builder may be bound to a thread,
+    /// and returned driver is not `Send`.
+    #[inline]
+    pub fn local_driver<D>(self) -> LocalAssetDriver<D>
+    where
+        D: DriveAsset,
+        A: for<'a> AssetBuild<D::Builder<'a>>,
+    {
+        LocalAssetDriver {
+            driver: AssetDriver {
+                handle: self.handle,
+                build_fn: build_fn::<A, D>,
+                done: false,
+            },
+            _local: PhantomData,
         }
     }
 }
 
+/// Type-erased function that builds decoded asset with builder provided by `D`.
+type BuildFn<D> = fn(
+    decoded: &mut (dyn Any + Send + Sync),
+    builder: &mut <D as DriveAsset>::Builder<'_>,
+) -> Option<Result<Arc<dyn Any + Send + Sync>, Error>>;
+
 /// Future to wait for asset to be loaded.
 /// Unlike `AssetHandle` it is
 /// parametrized with builder type instead of asset type.
@@ -801,16 +2117,27 @@ where
 /// but not get asset instance at the end.
 pub struct AssetDriver<D: DriveAsset = NoBuilderDrive> {
     handle: Handle,
-    build_fn: fn(
-        decoded: &mut (dyn Any + Send + Sync),
-        builder: &mut D::Builder<'_>,
-    ) -> Option<Result<Arc<dyn Any + Send + Sync>, Error>>,
+    build_fn: BuildFn<D>,
+    done: bool,
 }
 
 impl<D> AssetDriver<D>
 where
     D: DriveAsset,
 {
+    /// Returns id of the asset.
+    /// Returns `None` if asset was requested by path and id is not resolved yet.
+    #[inline]
+    pub fn asset_id(&self) -> Option<AssetId> {
+        self.handle.id
+    }
+
+    /// Returns [`TypeId`] of the asset type.
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.handle.kind.type_id
+    }
+
     /// Polls for asset to be loaded.
     /// Returns `true` if asset is loaded.
     /// Returns `false` if asset is not yet loaded.
@@ -835,12 +2162,8 @@ where
             return false;
         }
 
-        self.handle.build(
-            |decoded| (self.build_fn)(decoded, builder),
-            |_| {},
-            |_, _| {},
-            |_| {},
-        );
+        self.handle
+            .build(|decoded| (self.build_fn)(decoded, builder), |_| {}, |_| {});
         true
     }
 }
@@ -854,9 +2177,12 @@ where
     #[inline]
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<LoadedAssetDriver<D>> {
         let me = self.get_mut();
-        if !me.handle.poll(PollFor::Load, Some(cx.waker())) {
+
+        // Handle in final state resolves without locking.
+        if !me.done && !me.handle.poll(PollFor::Load, Some(cx.waker())) {
             return Poll::Pending;
         }
+        me.done = true;
 
         Poll::Ready(LoadedAssetDriver {
             handle: me.handle.clone(),
@@ -865,16 +2191,165 @@ where
     }
 }
 
+impl<D> FusedFuture for AssetDriver<D>
+where
+    D: DriveAsset,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+/// Future to wait for asset to be loaded, bound to the thread it was created on.
+///
+/// Same as [`AssetDriver`], but can be driven with builders that are not `Send`.
+/// Created with [`AssetHandle::local_driver`].
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use std::{cell::RefCell, rc::Rc};
+/// # #[cfg(feature = "tokio")] {
+/// /// Graphics context that must stay on its thread.
+/// #[derive(Default)]
+/// struct GlContext {
+///     textures: Rc<RefCell<Vec<String>>>,
+/// }
+///
+/// #[derive(Clone)]
+/// struct Texture;
+///
+/// impl Asset for Texture {
+///     type Decoded = String;
+///     type DecodeError = std::str::Utf8Error;
+///     type BuildError = std::convert::Infallible;
+///     type Fut = std::future::Ready<Result<String, std::str::Utf8Error>>;
+///
+///     fn asset_name() -> AssetName {
+///         AssetName::new("Texture")
+///     }
+///
+///     fn decode(bytes: Box<[u8]>, _: &Loader) -> Self::Fut {
+///         std::future::ready(std::str::from_utf8(&bytes).map(str::to_owned))
+///     }
+/// }
+///
+/// impl AssetBuild<GlContext> for Texture {
+///     fn build(gl: &mut GlContext, name: String) -> Result<Texture, std::convert::Infallible> {
+///         gl.textures.borrow_mut().push(name);
+///         Ok(Texture)
+///     }
+/// }
+///
+/// let source = MemorySource::new();
+/// source.insert(AssetId::new(1).unwrap(), &b"brick"[..]);
+/// source.insert(AssetId::new(2).unwrap(), &b"grass"[..]);
+/// let loader = Loader::builder().with(source).build();
+///
+/// let mut gl = GlContext::default();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let driver = loader.load::<Texture, _>(AssetId::new(1).unwrap()).local_driver::<LocalDrive<GlContext>>();
+///         driver.await.build(&mut gl);
+///
+///         let mut queue = BuildQueue::<LocalDrive<GlContext>>::new();
+///         let driver = loader.load::<Texture, _>(AssetId::new(2).unwrap()).local_driver::<LocalDrive<GlContext>>();
+///         queue.enqueue_local(driver);
+///         loader.wait_idle().await;
+///         assert_eq!(queue.drain(&mut gl), 1);
+///     });
+///
+/// assert_eq!(*gl.textures.borrow(), ["brick", "grass"]);
+/// # }
+/// ```
+///
+/// Local driver can't be sent to other thread.
+///
+/// ```compile_fail
+/// # use argosy::*;
+/// # #[derive(Clone, Asset)]
+/// # struct Number { value: u32 }
+/// # let loader = Loader::builder().build();
+/// let driver = loader.load::<Number, _>("number").local_driver::<LocalDrive<()>>();
+/// std::thread::spawn(move || drop(driver));
+/// ```
+pub struct LocalAssetDriver<D: DriveAsset = NoBuilderDrive> {
+    driver: AssetDriver<D>,
+    _local: PhantomData<*const ()>,
+}
+
+impl<D> LocalAssetDriver<D>
+where
+    D: DriveAsset,
+{
+    /// Returns id of the asset.
+    /// Returns `None` if asset was requested by path and id is not resolved yet.
+    #[inline]
+    pub fn asset_id(&self) -> Option<AssetId> {
+        self.driver.asset_id()
+    }
+
+    /// Returns [`TypeId`] of the asset type.
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.driver.type_id()
+    }
+
+    /// Polls for asset to be loaded.
+    /// Returns `true` if asset is loaded.
+    /// Returns `false` if asset is not yet loaded.
+    #[inline]
+    pub fn poll_loaded(&mut self) -> Option<LoadedAssetDriver<D>> {
+        self.driver.poll_loaded()
+    }
+
+    /// Polls for asset and builds it if loaded.
+    /// Returns `true` if asset is loaded and built.
+    /// Returns `false` if asset is not yet loaded.
+    #[inline]
+    pub fn poll_build(&mut self, builder: &mut D::Builder<'_>) -> bool {
+        self.driver.poll_build(builder)
+    }
+
+    pub(crate) fn into_driver(self) -> AssetDriver<D> {
+        self.driver
+    }
+}
+
+impl<D> Future for LocalAssetDriver<D>
+where
+    D: DriveAsset,
+{
+    type Output = LoadedAssetDriver<D>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<LoadedAssetDriver<D>> {
+        Pin::new(&mut self.get_mut().driver).poll(cx)
+    }
+}
+
+impl<D> FusedFuture for LocalAssetDriver<D>
+where
+    D: DriveAsset,
+{
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.driver.is_terminated()
+    }
+}
+
 /// Handle returned by awaiting on `AssetDriver`.
 /// The asset is loaded and can be built.
 /// Unlike `LoadedAsset` it is
 /// parametrized with builder type instead of asset type.
 pub struct LoadedAssetDriver<D: DriveAsset = NoBuilderDrive> {
     handle: Handle,
-    build_fn: fn(
-        decoded: &mut (dyn Any + Send + Sync),
-        builder: &mut D::Builder<'_>,
-    ) -> Option<Result<Arc<dyn Any + Send + Sync>, Error>>,
+    build_fn: BuildFn<D>,
 }
 
 impl<D> LoadedAssetDriver<D>
@@ -883,12 +2358,8 @@ where
 {
     #[inline]
     pub fn build(mut self, builder: &mut D::Builder<'_>) {
-        self.handle.build(
-            |decoded| (self.build_fn)(decoded, builder),
-            |_| {},
-            |_, _| {},
-            |_| {},
-        )
+        self.handle
+            .build(|decoded| (self.build_fn)(decoded, builder), |_| {}, |_| {})
     }
 }
 
@@ -905,7 +2376,9 @@ where
     match A::build(builder, decoded) {
         Ok(asset) => Some(Ok(Arc::new(asset.clone()))),
         Err(err) => {
-            let err = Error::new(err);
+            let err = Error::new(err)
+                .or_code(ErrorCode::BuildFailed)
+                .with_stage(ErrorStage::Build);
             Some(Err(err.clone()))
         }
     }
@@ -9,84 +9,141 @@ use argosy_id::AssetId;
 
 use crate::asset::Asset;
 
-#[derive(Clone, PartialEq, Eq, Hash)]
-pub(crate) struct TypeKey {
+/// Identifies kind of assets in the loader caches.
+///
+/// Asset types are identified by their [`TypeId`].
+/// Dynamic asset kinds share the same type and are distinguished by name hash.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct KindKey {
     pub type_id: TypeId,
+    pub name_hash: u64,
+}
+
+impl KindKey {
+    #[inline(always)]
+    pub fn of<A: Asset>() -> Self {
+        KindKey {
+            type_id: TypeId::of::<A>(),
+            name_hash: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn dynamic(type_id: TypeId, name_hash: u64) -> Self {
+        KindKey { type_id, name_hash }
+    }
+}
+
+/// Key of the asset cache entry.
+///
+/// Sequence number is not part of the key identity.
+#[derive(Clone)]
+pub(crate) struct TypeKey {
+    pub kind: KindKey,
     pub id: AssetId,
+
+    /// Sequence number assigned to the entry on insertion.
+    pub sequence: u64,
+}
+
+impl PartialEq for TypeKey {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.id == other.id
+    }
+}
+
+impl Eq for TypeKey {}
+
+impl Hash for TypeKey {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind.hash(state);
+        self.id.hash(state);
+    }
 }
 
 impl TypeKey {
     #[inline(always)]
-    pub fn new<A: Asset>(asset: AssetId) -> Self {
+    pub fn new(kind: KindKey, asset: AssetId) -> Self {
         TypeKey {
-            type_id: TypeId::of::<A>(),
+            kind,
             id: asset,
+            sequence: 0,
         }
     }
 
     #[inline(always)]
-    pub fn eq_key<A: Asset>(&self, asset: AssetId) -> bool {
-        self.type_id == TypeId::of::<A>() && self.id == asset
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = sequence;
+        self
     }
 
     #[inline(always)]
-    pub fn eq_key_erased(&self, type_id: TypeId, asset: AssetId) -> bool {
-        self.type_id == type_id && self.id == asset
+    pub fn eq_key(&self, kind: KindKey, asset: AssetId) -> bool {
+        self.kind == kind && self.id == asset
     }
 }
 
 #[inline(always)]
-pub fn hash_id_key<A>(id: AssetId, state: &impl BuildHasher) -> u64
-where
-    A: Asset,
-{
+pub fn hash_id_key(kind: KindKey, id: AssetId, state: &impl BuildHasher) -> u64 {
     let mut hasher = state.build_hasher();
-    TypeId::of::<A>().hash(&mut hasher);
+    kind.hash(&mut hasher);
     id.hash(&mut hasher);
     hasher.finish()
 }
 
-#[inline(always)]
-pub fn hash_id_key_erased(type_id: TypeId, id: AssetId, state: &impl BuildHasher) -> u64 {
-    let mut hasher = state.build_hasher();
-    type_id.hash(&mut hasher);
-    id.hash(&mut hasher);
-    hasher.finish()
+/// Key of the path cache entry.
+///
+/// Sequence number is not part of the key identity.
+#[derive(Clone)]
+pub(crate) struct PathKey {
+    pub kind: KindKey,
+    pub path: Arc<str>,
+
+    /// Sequence number assigned to the entry on insertion.
+    pub sequence: u64,
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
-pub struct PathKey {
-    pub type_id: TypeId,
-    pub path: Arc<str>,
+impl PartialEq for PathKey {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.path == other.path
+    }
+}
+
+impl Eq for PathKey {}
+
+impl Hash for PathKey {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind.hash(state);
+        self.path.hash(state);
+    }
 }
 
 impl PathKey {
     #[inline(always)]
-    pub fn new<A: Asset>(asset: Arc<str>) -> Self {
+    pub fn new(kind: KindKey, asset: Arc<str>, sequence: u64) -> Self {
         PathKey {
-            type_id: TypeId::of::<A>(),
+            kind,
             path: asset,
+            sequence,
         }
     }
 
     #[inline(always)]
-    pub fn eq_key<A: Asset>(&self, asset: &str) -> bool {
-        self.type_id == TypeId::of::<A>() && *self.path == *asset
-    }
-
-    #[inline(always)]
-    pub fn eq_key_erased(&self, type_id: TypeId, asset: &str) -> bool {
-        self.type_id == type_id && *self.path == *asset
+    pub fn eq_key(&self, kind: KindKey, asset: &str) -> bool {
+        self.kind == kind && *self.path == *asset
     }
 }
 
-pub fn hash_path_key<A, H>(path: &str, state: &mut H)
-where
-    A: Asset,
-    H: Hasher,
-{
-    TypeId::of::<A>().hash(state);
-    path.hash(state);
+#[inline(always)]
+pub fn hash_path_key(kind: KindKey, path: &str, state: &impl BuildHasher) -> u64 {
+    let mut hasher = state.build_hasher();
+    kind.hash(&mut hasher);
+    path.hash(&mut hasher);
+    hasher.finish()
 }
 
 #[derive(Clone, Copy)]
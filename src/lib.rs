@@ -15,11 +15,23 @@
 //!
 //! Creates structures to act as two loading stages of asset and implement asset using those.
 //! First stages must be deserializable with serde.
-//! All fields with `#[external]` must implement `AssetField<External>`. Which has blanket impl for `Asset` implementors and some wrappers, like `Option<A>` and `Arc<[A]>` where `A: Asset`.
+//! All fields with `#[external]` must implement `AssetField<External>`. Which has blanket impl for `Asset` implementors and some wrappers, like `Option<A>`, `Arc<[A]>` and `HashMap<K, A>` where `A: Asset`.
+//! [`KeyedAssets<K, A>`] additionally indexes keys by asset ids.
 //! All fields without special attributes must implement `AssetField<Inlined>`.
 //! Types that implement `DeserializeOwned` automatically implement `AssetField<Inlined>`.
 //! It can be derived using `derive(AssetField)`. They can in turn contain fields with `#[external]` attributes. Also implemented for wrappers like `Option<A>` and `Arc<[A]>`.
 //! All fields transiently with `#[external]` attribute will be decoded as `AssetId` and then loaded recursively.
+//! `#[cfg(...)]` attributes and doc comments on fields are forwarded to all generated structures.
+//! `#[asset(external(as T))]` attribute loads asset `T` and converts it into the field type with `From`.
+//! For `Option<_>`, `Vec<_>` and `Arc<[_]>` fields `T` is loaded and converted per element.
+//! Other containers and nested wrappers are rejected at compile time.
+//! `#[asset(checked)]` attribute on asset struct stores schema hash in the info and verifies it on decode, see [`CheckedAsset`].
+//! `#[serde(flatten)]` fields are supported in JSON only, since bincode can't deserialize them.
+//! Assets with flattened fields must have `#[asset(json_only)]` attribute, their bincode artifacts are rejected with [`DecodeError::JsonOnly`].
+//! Fields are built in declaration order.
+//! `#[asset(build_after = "other")]` attribute builds the field after field `other`, cyclic order is rejected at compile time.
+//! `#[asset(needs = "other")]` attribute additionally passes built `other` to the field builder,
+//! as context of [`FieldBuilder`] of generated type named after the asset and the field, like `RigSkinContext` for field `skin` of `Rig`.
 //!
 //! # Example
 //!
@@ -56,33 +68,561 @@
 //!     baz: Baz,
 //! }
 //! ```
+//!
+//! `external(as T)` applied inside wrappers.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use argosy::*;
+//! # #[cfg(feature = "tokio")] {
+//! #[derive(Clone, Asset)]
+//! struct Texture {
+//!     size: u32,
+//! }
+//!
+//! /// Built from loaded texture.
+//! #[derive(Clone)]
+//! struct Icon(u32);
+//!
+//! impl From<Texture> for Icon {
+//!     fn from(texture: Texture) -> Self {
+//!         Icon(texture.size)
+//!     }
+//! }
+//!
+//! #[derive(Clone, Asset)]
+//! struct Menu {
+//!     #[asset(external(as Texture))]
+//!     icon: Option<Icon>,
+//!
+//!     #[asset(external(as Texture))]
+//!     items: Vec<Icon>,
+//!
+//!     #[asset(external(as Texture))]
+//!     shared: Arc<[Icon]>,
+//! }
+//!
+//! let source = MemorySource::new();
+//! source.insert(AssetId::new(1).unwrap(), &br#"{ "size": 16 }"#[..]);
+//! source.insert(AssetId::new(2).unwrap(), &br#"{ "size": 32 }"#[..]);
+//! source.insert_with_path("menu", AssetId::new(3).unwrap(), &br#"{ "icon": null, "items": [1, 2], "shared": [2] }"#[..]);
+//! let loader = Loader::builder().with(source).build();
+//!
+//! tokio::runtime::Builder::new_current_thread()
+//!     .build()
+//!     .unwrap()
+//!     .block_on(async {
+//!         let menu = loader.load::<Menu, _>("menu").await?.build(&mut ())?;
+//!         assert!(menu.icon.is_none());
+//!         assert_eq!(menu.items.iter().map(|icon| icon.0).collect::<Vec<_>>(), [16, 32]);
+//!         assert_eq!(menu.shared[0].0, 32);
+//!         Ok::<_, Error>(())
+//!     })?;
+//! # }
+//! # Ok::<_, Error>(())
+//! ```
+//!
+//! Only `Option<_>`, `Vec<_>` and `Arc<[_]>` wrappers are supported, and they can't be nested.
+//!
+//! Settings shared by several asset types can be flattened into their descriptors.
+//!
+//! ```
+//! # use argosy::*;
+//! # #[cfg(feature = "tokio")] {
+//! #[derive(Clone, Asset)]
+//! struct Texture {
+//!     size: u32,
+//! }
+//!
+//! #[derive(Clone, AssetField)]
+//! struct CommonSettings {
+//!     #[asset(external)]
+//!     icon: Texture,
+//!     tag: String,
+//! }
+//!
+//! #[derive(Clone, Asset)]
+//! #[asset(json_only)]
+//! struct Weapon {
+//!     #[serde(flatten)]
+//!     common: CommonSettings,
+//!     damage: u32,
+//! }
+//!
+//! let source = MemorySource::new();
+//! source.insert(AssetId::new(1).unwrap(), &br#"{ "size": 16 }"#[..]);
+//! source.insert_with_path("sword", AssetId::new(2).unwrap(), &br#"{ "icon": "1", "tag": "blade", "damage": 7 }"#[..]);
+//! source.insert_with_path(
+//!     "axe",
+//!     AssetId::new(3).unwrap(),
+//!     ArtifactEnvelope::new(AssetFormat::Bincode).wrap(&[1, 0, 0, 0, 0, 0, 0, 0]),
+//! );
+//! let loader = Loader::builder().with(source).build();
+//!
+//! tokio::runtime::Builder::new_current_thread()
+//!     .build()
+//!     .unwrap()
+//!     .block_on(async {
+//!         let sword = loader.load::<Weapon, _>("sword").await?.build(&mut ())?;
+//!         assert_eq!((sword.common.icon.size, &*sword.common.tag, sword.damage), (16, "blade", 7));
+//!
+//!         let Err(err) = loader.load::<Weapon, _>("axe").await else {
+//!             panic!("Bincode artifact must be rejected");
+//!         };
+//!         assert!(matches!(err.get_decode_error::<Weapon>(), Some(WeaponDecodeError::Info(DecodeError::JsonOnly))));
+//!         Ok::<_, Error>(())
+//!     })?;
+//! # }
+//! # Ok::<_, Error>(())
+//! ```
+//!
+//! ```compile_fail
+//! # use argosy::*;
+//! # #[derive(Clone, AssetField)]
+//! # struct CommonSettings {
+//! #     tag: String,
+//! # }
+//! #[derive(Clone, Asset)]
+//! struct Weapon {
+//!     // Error: flattened fields require `#[asset(json_only)]`.
+//!     #[serde(flatten)]
+//!     common: CommonSettings,
+//!     damage: u32,
+//! }
+//! ```
+//!
+//! Fields that need other fields of the same asset are built after them.
+//!
+//! ```
+//! # use argosy::*;
+//! # #[cfg(feature = "tokio")] {
+//! /// Records order in which parts of assets are built.
+//! struct Log(Vec<&'static str>);
+//!
+//! #[derive(Clone)]
+//! struct Skeleton {
+//!     bones: u32,
+//! }
+//!
+//! impl LeafAsset for Skeleton {
+//!     type Decoded = u32;
+//!     type DecodeError = std::num::ParseIntError;
+//!     type BuildError = std::convert::Infallible;
+//!
+//!     fn asset_name() -> AssetName {
+//!         AssetName::new("Skeleton")
+//!     }
+//!
+//!     fn decode(bytes: Box<[u8]>) -> Result<u32, Self::DecodeError> {
+//!         String::from_utf8_lossy(&bytes).trim().parse()
+//!     }
+//! }
+//!
+//! impl AssetBuild<Log> for Skeleton {
+//!     fn build(log: &mut Log, bones: u32) -> Result<Self, std::convert::Infallible> {
+//!         log.0.push("skeleton");
+//!         Ok(Skeleton { bones })
+//!     }
+//! }
+//!
+//! /// Binds vertices to bones of the skeleton.
+//! #[derive(Clone, serde::Deserialize)]
+//! struct Skin {
+//!     bone: u32,
+//! }
+//!
+//! #[derive(Clone, Asset)]
+//! struct Rig {
+//!     /// Declared first, but built after the skeleton.
+//!     #[asset(needs = "skeleton")]
+//!     skin: Skin,
+//!
+//!     #[asset(external)]
+//!     skeleton: Skeleton,
+//! }
+//!
+//! impl AssetFieldBuild<Inlined, Skin> for FieldBuilder<'_, Log, RigSkinContext<'_>> {
+//!     fn build(self, skin: Skin) -> Result<Skin, std::convert::Infallible> {
+//!         let FieldBuilder(log, context) = self;
+//!         assert_eq!(context.skeleton.bones, 4);
+//!         assert!(skin.bone < context.skeleton.bones);
+//!         log.0.push("skin");
+//!         Ok(skin)
+//!     }
+//! }
+//!
+//! let source = MemorySource::new();
+//! source.insert(AssetId::new(1).unwrap(), &b"4"[..]);
+//! source.insert_with_path("rig", AssetId::new(2).unwrap(), &br#"{ "skin": { "bone": 3 }, "skeleton": 1 }"#[..]);
+//! let loader = Loader::builder().with(source).build();
+//!
+//! tokio::runtime::Builder::new_current_thread()
+//!     .build()
+//!     .unwrap()
+//!     .block_on(async {
+//!         let mut log = Log(Vec::new());
+//!         let rig = loader.load::<Rig, _>("rig").await?.build(&mut log)?;
+//!         assert_eq!((rig.skin.bone, rig.skeleton.bones), (3, 4));
+//!         assert_eq!(log.0, ["skeleton", "skin"]);
+//!         Ok::<_, Error>(())
+//!     })?;
+//! # }
+//! # Ok::<_, Error>(())
+//! ```
+//!
+//! ```compile_fail
+//! # use argosy::*;
+//! #[derive(Clone, Asset)]
+//! struct Rig {
+//!     // Error: fields are built after each other in a cycle.
+//!     #[asset(build_after = "bones")]
+//!     skin: u32,
+//!
+//!     #[asset(build_after = "skin")]
+//!     bones: u32,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use argosy::*;
+//! #[derive(Clone, Asset)]
+//! struct Rig {
+//!     // Error: unknown field.
+//!     #[asset(build_after = "skeleton")]
+//!     skin: u32,
+//! }
+//! ```
+//!
+//! # Descriptor format
+//!
+//! Asset descriptors are JSON or bincode.
+//! In JSON, references to external assets should be written as hex strings, e.g. `"8000000000000001"`.
+//! Integers are accepted too and are parsed exactly in the whole 64-bit range.
+//! Numbers that are out of range or not integers are rejected with [`DecodeError`]
+//! instead of being rounded to a different id.
+//!
+//! ```
+//! # use argosy::*;
+//! # #[cfg(feature = "tokio")] {
+//! #[derive(Clone, Asset)]
+//! struct Texture {
+//!     size: u32,
+//! }
+//!
+//! #[derive(Clone, Asset)]
+//! struct Sprite {
+//!     #[asset(external)]
+//!     texture: Texture,
+//! }
+//!
+//! let source = MemorySource::new();
+//! source.insert(AssetId::new(0x8000000000000001).unwrap(), &br#"{ "size": 16 }"#[..]);
+//! source.insert_with_path("hex", AssetId::new(1).unwrap(), &br#"{ "texture": "8000000000000001" }"#[..]);
+//! source.insert_with_path("number", AssetId::new(2).unwrap(), &br#"{ "texture": 9223372036854775809 }"#[..]);
+//! source.insert_with_path("overflow", AssetId::new(3).unwrap(), &br#"{ "texture": 18446744073709551616 }"#[..]);
+//! let loader = Loader::builder().with(source).build();
+//!
+//! tokio::runtime::Builder::new_current_thread()
+//!     .build()
+//!     .unwrap()
+//!     .block_on(async {
+//!         for path in ["hex", "number"] {
+//!             let sprite = loader.load::<Sprite, _>(path).await?.build(&mut ())?;
+//!             assert_eq!(sprite.texture.size, 16);
+//!         }
+//!
+//!         let Err(err) = loader.load::<Sprite, _>("overflow").await else {
+//!             panic!("Out of range id must be rejected");
+//!         };
+//!         assert!(format!("{err:?}").contains("out of 64-bit range"), "{err:?}");
+//!         Ok::<_, Error>(())
+//!     })?;
+//! # }
+//! # Ok::<_, Error>(())
+//! ```
+//!
+//! # Features
+//!
+//! | Feature                    | Default | Enables                                                    |
+//! |----------------------------|---------|------------------------------------------------------------|
+//! | `tokio`                    | yes     | Loading tasks spawned on tokio runtime, time-based options |
+//! | `fs`                       | yes     | [`FileSource`] and [`ArchiveSource::open`]                 |
+//! | `serde-handles`            | no      | Serialization of asset handles and [`PendingHandle`]       |
+//! | `json-arbitrary-precision` | no      | `arbitrary_precision` feature of `serde_json`              |
+//! | `json-preserve-order`      | no      | `preserve_order` feature of `serde_json`                   |
+//! | `capi`                     | no      | C API in [`capi`], implies `fs`                            |
+//! | `test-util`                | no      | [`ChaosSource`], [`DelaySource`] and [`VirtualClock`]      |
+//! | `async-build`              | no      | Building with builders behind async mutex, [`AsyncShared`] |
+//! | `debug-statemachine`       | no      | [`Loader::dump_entry_graph`] and [`docs`]                  |
+//! | `zip`                      | no      | [`ZipSource`]                                              |
+//!
+//! Time-based options are [`MissingPolicy::RetryAfter`], [`LoadOptions::deadline`]
+//! and [`SourceStrategy::Staggered`].
+//!
+//! With default features disabled argosy needs neither async runtime nor file system,
+//! leaving embedder in control of every dependency.
+//! Loading tasks make progress only when `Loader::pump` is called,
+//! and automatic unloading happens there too.
+//! Tokio is still used for its runtime-independent synchronization primitives.
+//!
+//! Versions of [`futures`] and [`tokio`] used by argosy are re-exported.
+//! Everything required to implement [`Source`] is in [`source::prelude`].
+//!
+//! ```
+//! # use argosy::*;
+//! # use futures::FutureExt;
+//! #[derive(Clone, Asset)]
+//! struct Number {
+//!     value: u32,
+//! }
+//!
+//! let source = MemorySource::new();
+//! source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 7 }"#[..]);
+//! let loader = Loader::builder().with(source).build();
+//!
+//! #[cfg(not(feature = "tokio"))]
+//! {
+//!     let mut handle = loader.load::<Number, _>("number");
+//!     assert!((&mut handle).now_or_never().is_none());
+//!
+//!     // Nothing is loaded until loader is pumped.
+//!     assert_eq!(loader.pump(), 0);
+//!     let mut number = handle.now_or_never().unwrap()?;
+//!     assert_eq!(number.build(&mut ())?.value, 7);
+//! }
+//!
+//! #[cfg(feature = "tokio")]
+//! tokio::runtime::Builder::new_current_thread()
+//!     .enable_time()
+//!     .build()
+//!     .unwrap()
+//!     .block_on(async {
+//!         let options = LoadOptions::new()
+//!             .with_missing(MissingPolicy::RetryAfter(std::time::Duration::from_millis(10)))
+//!             .with_deadline(std::time::Duration::from_secs(1));
+//!         let mut number = loader.load_with_options::<Number, _>("number", options).await?;
+//!         assert_eq!(number.build(&mut ())?.value, 7);
+//!         Ok::<_, Error>(())
+//!     })?;
+//!
+//! #[cfg(feature = "fs")]
+//! let _ = Loader::builder().with(FileSource::new("assets"));
+//! # Ok::<_, Error>(())
+//! ```
 
+mod abort;
+mod alias;
 mod asset;
+mod backpressure;
+mod batch;
+mod blocking;
+#[cfg(feature = "async-build")]
+mod build_async;
+mod build_queue;
+mod cache;
+#[cfg(feature = "capi")]
+pub mod capi;
+mod cascade;
+mod clock;
+mod config;
+mod conflict;
+mod decode_cache;
+mod dev;
+#[cfg(feature = "debug-statemachine")]
+pub mod docs;
+mod dynamic;
 mod error;
+mod failure;
+mod fallback;
 mod field;
+mod format;
 mod handle;
 mod key;
 mod loader;
-mod source;
+mod lookup;
+mod names;
+#[cfg(feature = "serde-handles")]
+mod pending;
+mod prefetch;
+mod progress;
+mod publish;
+#[cfg(not(feature = "tokio"))]
+mod pump;
+mod reload;
+mod replay;
+pub mod source;
+mod stats;
+mod tenant;
+mod transition;
+mod typed_id;
+mod unload;
+mod update;
+mod usage;
 
 pub use self::{
-    asset::{Asset, AssetBuild, LeafAsset, TrivialAsset},
-    error::{Error, NotFound},
-    field::{AssetField, AssetFieldBuild},
+    abort::AbortSignal,
+    asset::{Asset, AssetBuild, AssetBuildVia, CheckedAsset, LeafAsset, SubAsset, TrivialAsset},
+    batch::{BatchItem, BatchOutcome, CancelToken},
+    build_queue::BuildQueue,
+    cache::{CacheBackend, CacheBackendFactory, HashMapCache, HashMapCacheFactory},
+    clock::{Clock, SystemClock},
+    config::{ConfigDiff, LoaderConfig},
+    conflict::TypeConflictPolicy,
+    decode_cache::{CacheableDecode, DecodeCache},
+    dev::DevWarning,
+    dynamic::{DynAssetDescriptor, DynAssetDescriptorBuilder, DynValue, NoDescriptor},
+    error::{
+        Cancelled, DuplicateAssetName, DuplicateSourceLabel, Error, ErrorCode, ErrorStage, Evicted,
+        NoParentPath, NotFound, TypeConflict,
+    },
+    failure::FailureRecord,
+    fallback::AssetFallback,
+    field::{AssetField, AssetFieldBuild, External, FieldBuilder, Inlined, KeyedAssets},
+    format::{ArtifactEnvelope, AssetFormat},
     handle::{
-        AssetDriver, AssetFuture, AssetHandle, AssetLookup, DriveAsset, LoadedAsset,
-        LoadedAssetDriver, SimpleDrive,
+        AssetBuilt, AssetDriver, AssetFuture, AssetHandle, AssetLookup, AssetMetadata, AutoAsset,
+        DriveAsset, LoadedAsset, LoadedAssetDriver, LocalAssetDriver, LocalDrive, NoBuilderDrive,
+        SimpleDrive,
     },
     key::Key,
-    loader::{Loader, LoaderBuilder},
-    source::{AssetData, Source},
+    loader::{
+        EntryStatus, EntrySummary, LoadOptions, Loader, LoaderBuilder, LoaderStats, MissingPolicy,
+        SourceStrategy,
+    },
+    lookup::PathLookup,
+    names::AssetName,
+    prefetch::{PrefetchEntry, PrefetchStatus},
+    progress::Progress,
+    publish::Publish,
+    reload::{ReloadAction, ReloadEvent, ReloadOutcome, ReloadSink},
+    replay::{
+        RecordingSink, ReplayData, ReplayEntry, ReplayLog, ReplayLogError, ReplayRecorder,
+        ReplayRequest, ReplayResponse, REPLAY_LOG_VERSION,
+    },
+    source::{
+        archive::{ArchiveError, ArchiveSource, EmbeddedSource, ResidencyAdvisor},
+        inline::InlineAssets,
+        memory::MemorySource,
+        namespaced::NamespacedSource,
+        replay::{ReplayHandle, ReplaySource, ReplayTiming, ReplayedError, UnexpectedRequest},
+        tar::TarSource,
+        Advice, AssetBytes, AssetData, AssetProperties, Source,
+    },
+    stats::{DecodeHistogram, TypeStats, DECODE_BUCKETS},
+    tenant::{TenantLoader, TenantShared},
+    typed_id::TypedAssetId,
+    usage::{AssetUsage, UsageSink},
+};
+
+#[cfg(feature = "fs")]
+pub use self::{decode_cache::DirDecodeCache, source::fs::FileSource, usage::UsageLog};
+
+#[cfg(feature = "serde-handles")]
+pub use self::pending::PendingHandle;
+
+#[cfg(feature = "zip")]
+pub use self::source::zip::ZipSource;
+
+#[cfg(feature = "async-build")]
+pub use self::{
+    build_async::{AsyncAssetBuild, AsyncShared},
+    error::BuildInProgress,
+};
+
+#[cfg(feature = "test-util")]
+pub use self::{
+    clock::VirtualClock,
+    source::{
+        chaos::{ChaosSource, ChaosSourceBuilder, ChaosStats, ChaosStatsHandle, InjectedFault},
+        delay::DelaySource,
+    },
 };
 
 pub use argosy_id::AssetId;
 
 pub use argosy_proc::{self as proc, Asset, AssetField};
 
+/// Version of the `futures` crate used by argosy.
+/// Use it to implement [`Source`] to avoid version mismatch.
+pub use futures;
+
+/// Version of the `tokio` crate used by argosy.
+pub use tokio;
+
+/// Commonly used items.
+///
+/// ```
+/// use argosy::prelude::*;
+/// ```
+pub mod prelude {
+    pub use crate::{
+        Asset, AssetBuild, AssetField, AssetHandle, AssetId, DynAssetDescriptor, DynValue, Error,
+        Key, Loader, Source,
+    };
+}
+
 /// Error type used by derive-macro.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// /// Unit assets ignore payload.
+/// #[derive(Clone, Asset)]
+/// struct Marker;
+///
+/// #[derive(Clone, Asset)]
+/// struct Config {
+///     name: Option<String>,
+///     size: Option<u32>,
+/// }
+///
+/// let payloads: [&[u8]; 5] = [
+///     b"",
+///     b" \r\n\t",
+///     b"\xEF\xBB\xBF{ \"size\": 3 }",
+///     b"\xEF\xBB\xBF{ \"size\": ",
+///     b" null ",
+/// ];
+///
+/// let source = MemorySource::new();
+/// for (index, payload) in payloads.iter().enumerate() {
+///     source.insert(AssetId::new(index as u64 + 1).unwrap(), *payload);
+/// }
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         for index in 0..payloads.len() {
+///             let id = AssetId::new(index as u64 + 1).unwrap();
+///             loader.load::<Marker, _>(id).await?;
+///         }
+///
+///         let config = |index: u64| loader.load::<Config, _>(AssetId::new(index).unwrap());
+///
+///         let err = config(1).await.err().unwrap();
+///         assert!(matches!(err.get_decode_error::<Config>(), Some(ConfigDecodeError::Info(DecodeError::EmptyPayload))));
+///         let err = config(2).await.err().unwrap();
+///         assert!(matches!(err.get_decode_error::<Config>(), Some(ConfigDecodeError::Info(DecodeError::EmptyPayload))));
+///
+///         // BOM is skipped.
+///         let loaded = config(3).await?.build(&mut ())?;
+///         assert_eq!((loaded.name, loaded.size), (None, Some(3)));
+///
+///         // Payload with BOM is JSON, it is not retried as bincode.
+///         let err = config(4).await.err().unwrap();
+///         assert!(matches!(err.get_decode_error::<Config>(), Some(ConfigDecodeError::Info(DecodeError::Json(_)))));
+///
+///         let err = config(5).await.err().unwrap();
+///         assert!(matches!(err.get_decode_error::<Config>(), Some(ConfigDecodeError::Info(DecodeError::NullPayload))));
+///         Ok::<_, Error>(())
+///     })?;
+/// # }
+/// # Ok::<_, Error>(())
+/// ```
 #[derive(::std::fmt::Debug, thiserror::Error)]
 pub enum DecodeError {
     #[error("Failed to deserialize asset info from json")]
@@ -90,6 +630,35 @@ pub enum DecodeError {
 
     #[error("Failed to deserialize asset info from bincode")]
     Bincode(#[source] bincode::Error),
+
+    #[error("Asset info schema {found:016x} does not match expected schema {expected:016x}")]
+    SchemaMismatch { expected: u64, found: u64 },
+
+    #[error("Artifact envelope version {found} is not in supported range {supported:?}. Artifact must be reimported")]
+    ArtifactTooOld {
+        found: u16,
+        supported: std::ops::RangeInclusive<u16>,
+    },
+
+    #[error("Artifact envelope has unknown payload format {format}")]
+    UnknownPayloadFormat { format: u8 },
+
+    #[error("Unknown field '{field}' in asset info, expected one of {expected:?}")]
+    UnknownField {
+        field: String,
+        expected: &'static [&'static str],
+    },
+
+    #[error("Asset info has flattened fields and can be deserialized only from json, not bincode")]
+    JsonOnly,
+
+    /// Payload is empty or contains only whitespace.
+    #[error("Asset payload is empty, expected asset info as JSON object or bincode")]
+    EmptyPayload,
+
+    /// Payload is JSON `null`.
+    #[error("Asset payload is JSON `null`, expected asset info as JSON object")]
+    NullPayload,
 }
 
 #[doc(hidden)]
@@ -99,7 +668,10 @@ pub mod proc_macro {
         convert::{From, Infallible},
         fmt::Debug,
         future::{ready, Ready},
+        option::Option,
         result::Result::{self, Err, Ok},
+        sync::Arc,
+        vec::Vec,
     };
 
     pub use futures::future::BoxFuture;
@@ -108,36 +680,247 @@ pub mod proc_macro {
     pub use thiserror::Error;
 
     pub use crate::{
-        asset::{Asset, AssetBuild, TrivialAsset},
-        field::{AssetField, AssetFieldBuild, External, FieldBuilder, Inlined},
+        asset::{Asset, AssetBuild, AssetBuildVia, CheckedAsset, TrivialAsset},
+        field::{AssetField, AssetFieldBuild, External, ExternalField, FieldBuilder, Inlined},
         loader::Loader,
+        names::AssetName,
         DecodeError,
     };
 
+    #[cfg(feature = "async-build")]
+    pub use crate::build_async::{yield_now, AsyncAssetBuild, AsyncShared};
+
+    /// Schema hash stored in asset info.
+    ///
+    /// Only self-describing formats, like JSON, carry the hash.
+    /// In other formats, like bincode, it takes no bytes,
+    /// so layout of their artifacts is not changed by `#[asset(checked)]`.
+    #[derive(Clone, Copy, Default)]
+    pub enum SchemaStamp {
+        /// Self-describing info has no schema hash.
+        #[default]
+        Missing,
+
+        /// Info format can't carry schema hash.
+        Unsupported,
+
+        /// Schema hash found in the info.
+        Found(u64),
+    }
+
+    impl<'de> Deserialize<'de> for SchemaStamp {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            if !deserializer.is_human_readable() {
+                return Ok(SchemaStamp::Unsupported);
+            }
+            let found = Option::<u64>::deserialize(deserializer)?;
+            Ok(found.map_or(SchemaStamp::Missing, SchemaStamp::Found))
+        }
+    }
+
+    /// Verifies schema hash stored in asset info.
+    ///
+    /// Info without schema hash is accepted to keep older artifacts loadable.
+    /// Missing hash is reported once per asset type.
+    pub fn check_schema(
+        name: &'static str,
+        expected: u64,
+        found: SchemaStamp,
+    ) -> Result<(), DecodeError> {
+        static UNSTAMPED: parking_lot::Mutex<Vec<&'static str>> =
+            parking_lot::Mutex::new(Vec::new());
+
+        match found {
+            SchemaStamp::Missing => {
+                let mut unstamped = UNSTAMPED.lock();
+                if !unstamped.contains(&name) {
+                    unstamped.push(name);
+                    tracing::warn!(
+                        "Asset '{}' info has no schema hash, schema is not checked",
+                        name
+                    );
+                }
+                Ok(())
+            }
+            SchemaStamp::Unsupported => Ok(()),
+            SchemaStamp::Found(found) if found != expected => {
+                Err(DecodeError::SchemaMismatch { expected, found })
+            }
+            SchemaStamp::Found(_) => Ok(()),
+        }
+    }
+
     #[inline(always)]
     pub fn deserialize_info<T: serde::de::DeserializeOwned>(
         bytes: &[u8],
     ) -> Result<T, DecodeError> {
-        if bytes.is_empty() {
-            // Zero-length is definitely bincode.
-            match bincode::deserialize(&*bytes) {
-                Ok(value) => Ok(value),
-                Err(err) => Err(DecodeError::Bincode(err)),
-            }
-        } else {
-            match serde_json::from_slice(&*bytes) {
-                Ok(value) => Ok(value),
-                Err(err) => match err.classify() {
-                    Category::Syntax => {
-                        // That's not json. Bincode then.
-                        match bincode::deserialize(&*bytes) {
-                            Ok(value) => Ok(value),
-                            Err(err) => Err(DecodeError::Bincode(err)),
-                        }
+        deserialize_info_checked(bytes, None)
+    }
+
+    /// Deserializes asset info.
+    ///
+    /// If loader requires strict descriptors, JSON info with fields not listed in `fields`
+    /// is rejected. Info is not checked if `fields` are unknown.
+    ///
+    /// Format and strictness are taken from the enclosing [`LeafAsset::decode`] call.
+    ///
+    /// [`LeafAsset::decode`]: crate::LeafAsset::decode
+    pub fn deserialize_info_checked<T: serde::de::DeserializeOwned>(
+        bytes: &[u8],
+        fields: Option<&'static [&'static str]>,
+    ) -> Result<T, DecodeError> {
+        deserialize_info_with(
+            bytes,
+            fields,
+            false,
+            crate::format::format_override(),
+            crate::format::strict_descriptors(),
+        )
+    }
+
+    /// Deserializes asset info that can be decoded only from JSON.
+    ///
+    /// Used for assets with `#[asset(json_only)]` attribute.
+    pub fn deserialize_info_json_checked<T: serde::de::DeserializeOwned>(
+        bytes: &[u8],
+        fields: Option<&'static [&'static str]>,
+    ) -> Result<T, DecodeError> {
+        deserialize_info_with(
+            bytes,
+            fields,
+            true,
+            crate::format::format_override(),
+            crate::format::strict_descriptors(),
+        )
+    }
+
+    /// Deserializes asset info with format and strictness of the `loader`.
+    pub fn decode_info_checked<T: serde::de::DeserializeOwned>(
+        bytes: &[u8],
+        fields: Option<&'static [&'static str]>,
+        loader: &Loader,
+    ) -> Result<T, DecodeError> {
+        deserialize_info_with(
+            bytes,
+            fields,
+            false,
+            loader.decoding_format(),
+            loader.strict_descriptors(),
+        )
+    }
+
+    /// Deserializes asset info that can be decoded only from JSON
+    /// with format and strictness of the `loader`.
+    pub fn decode_info_json_checked<T: serde::de::DeserializeOwned>(
+        bytes: &[u8],
+        fields: Option<&'static [&'static str]>,
+        loader: &Loader,
+    ) -> Result<T, DecodeError> {
+        deserialize_info_with(
+            bytes,
+            fields,
+            true,
+            loader.decoding_format(),
+            loader.strict_descriptors(),
+        )
+    }
+
+    #[inline(always)]
+    fn deserialize_info_with<T: serde::de::DeserializeOwned>(
+        bytes: &[u8],
+        fields: Option<&'static [&'static str]>,
+        json_only: bool,
+        format: Option<crate::AssetFormat>,
+        strict: bool,
+    ) -> Result<T, DecodeError> {
+        match fields {
+            Some(fields) if strict => deserialize_info_impl(bytes, json_only, format, |bytes| {
+                let value: serde_json::Value = serde_json::from_slice(bytes)?;
+                if let Some(object) = value.as_object() {
+                    if let Some(field) = object.keys().find(|key| !fields.contains(&key.as_str())) {
+                        return Ok(Err(DecodeError::UnknownField {
+                            field: field.clone(),
+                            expected: fields,
+                        }));
                     }
-                    _ => Err(DecodeError::Json(err)),
-                },
+                }
+                serde_json::from_value(value).map(Ok)
+            }),
+            _ => deserialize_info_impl(bytes, json_only, format, |bytes| {
+                serde_json::from_slice(bytes).map(Ok)
+            }),
+        }
+    }
+
+    /// Deserializes asset info with `json` function for JSON
+    /// and bincode otherwise, unless `json_only` is set.
+    #[inline(always)]
+    fn deserialize_info_impl<T: serde::de::DeserializeOwned>(
+        bytes: &[u8],
+        json_only: bool,
+        format: Option<crate::AssetFormat>,
+        json: impl FnOnce(&[u8]) -> Result<Result<T, DecodeError>, serde_json::Error>,
+    ) -> Result<T, DecodeError> {
+        let (bytes, envelope) = match crate::ArtifactEnvelope::parse(bytes)? {
+            Some((envelope, payload)) => (payload, Some(envelope.format)),
+            None => (bytes, None),
+        };
+
+        // Format requested with `Loader::load_as` takes precedence over envelope and detection.
+        let format = format.or(envelope);
+        if format == Some(crate::AssetFormat::Bincode) {
+            if json_only {
+                return Err(DecodeError::JsonOnly);
             }
+            return bincode::deserialize(bytes).map_err(DecodeError::Bincode);
+        }
+
+        // Some editors save JSON files with UTF-8 BOM.
+        let (bytes, bom) = match bytes.strip_prefix(UTF8_BOM) {
+            Some(bytes) => (bytes, true),
+            None => (bytes, false),
+        };
+
+        check_payload(bytes)?;
+
+        if format == Some(crate::AssetFormat::Json) || json_only || bom {
+            return json(bytes).map_err(DecodeError::Json)?;
+        }
+
+        match json(bytes) {
+            Ok(result) => result,
+            Err(err) => match err.classify() {
+                Category::Syntax => {
+                    // That's not json. Bincode then.
+                    match bincode::deserialize(bytes) {
+                        Ok(value) => Ok(value),
+                        Err(err) => Err(DecodeError::Bincode(err)),
+                    }
+                }
+                _ => Err(DecodeError::Json(err)),
+            },
+        }
+    }
+
+    const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+    /// Rejects payloads that are authoring accidents rather than asset info.
+    ///
+    /// Empty and whitespace-only payloads are not valid JSON,
+    /// and asset info is never encoded into zero bytes of bincode.
+    /// Top-level JSON `null` would be accepted by infos with optional fields only,
+    /// silently producing asset with no data.
+    fn check_payload(bytes: &[u8]) -> Result<(), DecodeError> {
+        let trimmed = bytes.trim_ascii();
+        if trimmed.is_empty() {
+            return Err(DecodeError::EmptyPayload);
+        }
+        if trimmed == b"null" {
+            return Err(DecodeError::NullPayload);
         }
+        Ok(())
     }
 }
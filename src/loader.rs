@@ -1,43 +1,159 @@
 use std::{
     any::{Any, TypeId},
-    hash::{BuildHasher, Hasher},
-    sync::Arc,
-    task::Waker,
+    borrow::Cow,
+    collections::{hash_map::DefaultHasher, HashSet, VecDeque},
+    convert::Infallible,
+    future::{poll_fn, Future},
+    hash::{Hash, Hasher},
+    io::Write,
+    marker::PhantomData,
+    pin::{pin, Pin},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Poll, Waker},
+    time::Duration,
 };
 
 use ahash::RandomState;
 use argosy_id::AssetId;
-use hashbrown::hash_map::{HashMap, RawEntryMut};
-use parking_lot::Mutex;
+use futures::{
+    future::{select, Either, FutureExt, Map},
+    StreamExt,
+};
+use hashbrown::hash_map::HashMap;
+use parking_lot::{Mutex, RwLock};
 use smallvec::SmallVec;
+use tokio::sync::{watch, Notify, OnceCell};
 use tracing::Instrument;
 
 use crate::{
-    error::Error,
-    handle::{AssetHandle, Handle, State},
-    key::{hash_path_key, PathKey},
+    abort::AbortSignal,
+    alias::{PathAliases, MAX_PATH_ALIAS_DEPTH},
+    backpressure::{Backpressure, Reservation},
+    batch::{BatchItem, BatchOutcome, CancelToken},
+    cache::{CacheBackend, CacheBackendFactory, Entry, HashMapCacheFactory, LoaderCacheFactory},
+    cascade::{dependents, rebuild_order, Cascades},
+    clock::{Clock, SystemClock},
+    config::{ConfigDiff, LoaderConfig},
+    conflict::{AssetKinds, TypeConflictPolicy},
+    decode_cache::{CacheableDecode, DecodeCache},
+    dev::{DevMode, DevWarning, Placeholder, Placeholders},
+    dynamic::{DynAssetDescriptor, DynValue},
+    error::{
+        Cancelled, DuplicateAssetName, DuplicateSourceLabel, Error, ErrorCode, ErrorStage,
+        NoParentPath, NotFound,
+    },
+    failure::{FailureRecord, Failures, DEFAULT_MAX_FAILURES},
+    fallback::AssetFallback,
+    format::AssetFormat,
+    handle::{AssetHandle, AssetMetadata, Handle, PollFor, StaleFlag, State},
+    key::{hash_path_key, KindKey, PathKey},
+    lookup::{PathLookup, PathLookups},
+    names::{AssetName, AssetNames},
+    prefetch::{PrefetchEntry, Prefetcher, DEFAULT_MAX_PREFETCHES},
+    publish::{Publish, Staged},
+    reload::{ErasedAction, ReloadAction, ReloadEvent, ReloadHooks, ReloadOutcome, ReloadSink},
+    replay::{RecordedData, Recorder, Recording, RecordingSink, ReplayRequest},
+    stats::{DecodeStats, TypeStats},
+    tenant::{FetchCache, Tenancy, Tenant, TenantLoader, TenantShared, DEFAULT_TENANT_FETCH_TTL},
+    transition::{debug_check, EntryKind, Event},
+    unload::{AutoUnload, Retain},
+    update::{
+        ReloadDecode, Reloaders, UpdateScheduler, DEFAULT_MAX_UPDATES_PER_TICK,
+        DEFAULT_MAX_UPDATE_INTERVAL,
+    },
+    usage::{AssetUsage, UsageRecorder, UsageSink},
 };
 
+#[cfg(not(feature = "tokio"))]
+use crate::pump::Tasks;
+
 use crate::{
-    asset::Asset,
+    asset::{Asset, AssetBuild, SubAsset},
     key::{hash_id_key, Key, TypeKey},
-    source::Source,
+    source::{
+        archive::{write_archive_entry, write_archive_header},
+        inline::InlineAssets,
+        Advice, AssetBytes, AssetData, AssetProperties, Source,
+    },
+    DecodeError,
 };
 
 /// This is default number of shards per CPU for shared hash map of asset states.
 const DEFAULT_SHARDS_PER_CPU: usize = 8;
 
-struct Data {
-    bytes: Box<[u8]>,
+/// Number of recently loaded artifacts which raw data is kept for sub-asset loads.
+const SHARED_DATA_CAPACITY: usize = 16;
+
+pub(crate) struct Data {
+    bytes: AssetBytes,
     version: u64,
     source: usize,
+    slot: SourceSlot,
+    properties: AssetProperties,
+}
+
+impl RecordedData for Data {
+    fn parts(&self) -> (&[u8], u64, &AssetProperties) {
+        (&self.bytes, self.version, &self.properties)
+    }
+}
+
+/// Raw data of artifacts shared by sub-asset loads.
+/// Cell is initialized by the first load of the artifact.
+type SharedData = Mutex<VecDeque<(AssetId, Arc<OnceCell<Arc<Data>>>)>>;
+
+/// Raw asset data loaded for decoding.
+enum RawData {
+    Owned(Data),
+    Shared(Arc<Data>),
+}
+
+impl RawData {
+    fn data(&self) -> &Data {
+        match self {
+            RawData::Owned(data) => data,
+            RawData::Shared(data) => data,
+        }
+    }
 }
 
+/// Source added to [`LoaderBuilder`] with optional label.
+type LabeledSource = (Option<Arc<str>>, Box<dyn Source>);
+
 /// Builder for [`Loader`].
 /// Allows configure asset loader with required [`Source`]s.
 pub struct LoaderBuilder {
     num_shards: usize,
-    sources: Vec<Box<dyn Source>>,
+    sources: Vec<LabeledSource>,
+    cache_backend: Box<dyn LoaderCacheFactory>,
+    auto_unload: Option<Duration>,
+    path_cache_capacity: Option<usize>,
+    source_strategy: SourceStrategy,
+    decode_cache: Option<Arc<dyn DecodeCache>>,
+    decode_stats: bool,
+    usage: Option<Box<dyn UsageSink>>,
+    recording: Option<Box<dyn RecordingSink>>,
+    asset_names: AssetNames,
+    strict_asset_names: bool,
+    dev_placeholders: bool,
+    placeholders: Placeholders,
+    reload: ReloadHooks,
+    max_prefetches: usize,
+    max_prefetch_bytes: usize,
+    max_updates_per_tick: usize,
+    max_update_interval: u64,
+    max_failures: usize,
+    type_conflicts: TypeConflictPolicy,
+    unbuilt_limit: Option<(usize, usize, i32)>,
+    clock: Arc<dyn Clock>,
+    strict_descriptors: bool,
+    path_aliases: Vec<(Arc<str>, Arc<str>)>,
+    tenant_fetch_ttl: Duration,
+    #[cfg(feature = "tokio")]
+    build_wait_warning: Option<Duration>,
 }
 
 impl Default for LoaderBuilder {
@@ -55,30 +171,83 @@ impl LoaderBuilder {
         LoaderBuilder {
             num_shards,
             sources: Vec::new(),
+            cache_backend: Box::new(HashMapCacheFactory),
+            auto_unload: None,
+            path_cache_capacity: None,
+            source_strategy: SourceStrategy::Sequential,
+            decode_cache: None,
+            decode_stats: false,
+            usage: None,
+            recording: None,
+            asset_names: AssetNames::default(),
+            strict_asset_names: true,
+            dev_placeholders: false,
+            placeholders: Placeholders::default(),
+            reload: ReloadHooks::default(),
+            max_prefetches: DEFAULT_MAX_PREFETCHES,
+            max_prefetch_bytes: usize::MAX,
+            max_updates_per_tick: DEFAULT_MAX_UPDATES_PER_TICK,
+            max_update_interval: DEFAULT_MAX_UPDATE_INTERVAL,
+            max_failures: DEFAULT_MAX_FAILURES,
+            type_conflicts: TypeConflictPolicy::Warn,
+            unbuilt_limit: None,
+            clock: Arc::new(SystemClock),
+            strict_descriptors: false,
+            path_aliases: Vec::new(),
+            tenant_fetch_ttl: DEFAULT_TENANT_FETCH_TTL,
+            #[cfg(feature = "tokio")]
+            build_wait_warning: None,
         }
     }
 
     /// Adds provided source to the loader.
     pub fn add(&mut self, source: impl Source) -> &mut Self {
-        self.sources.push(Box::new(source));
+        self.sources.push((None, Box::new(source)));
         self
     }
 
     /// Adds provided source to the loader.
     pub fn with(mut self, source: impl Source) -> Self {
-        self.sources.push(Box::new(source));
+        self.sources.push((None, Box::new(source)));
         self
     }
 
     /// Adds provided source to the loader.
     pub fn add_dyn(&mut self, source: Box<dyn Source>) -> &mut Self {
-        self.sources.push(source);
+        self.sources.push((None, source));
         self
     }
 
     /// Adds provided source to the loader.
     pub fn wit_dyn(mut self, source: Box<dyn Source>) -> Self {
-        self.sources.push(source);
+        self.sources.push((None, source));
+        self
+    }
+
+    /// Adds provided source to the loader with a label.
+    /// Replaces source added earlier with the same label.
+    ///
+    /// Labeled sources can be replaced and removed at runtime,
+    /// see [`Loader::mount`] and [`Loader::apply_config`].
+    pub fn add_labeled(&mut self, label: impl Into<Arc<str>>, source: impl Source) -> &mut Self {
+        let label = label.into();
+        let source: Box<dyn Source> = Box::new(source);
+        match self
+            .sources
+            .iter_mut()
+            .find(|(existing, _)| existing.as_deref() == Some(&*label))
+        {
+            Some(existing) => existing.1 = source,
+            None => self.sources.push((Some(label), source)),
+        }
+        self
+    }
+
+    /// Adds provided source to the loader with a label.
+    ///
+    /// See [`LoaderBuilder::add_labeled`].
+    pub fn with_labeled(mut self, label: impl Into<Arc<str>>, source: impl Source) -> Self {
+        self.add_labeled(label, source);
         self
     }
 
@@ -106,212 +275,6333 @@ impl LoaderBuilder {
         self
     }
 
-    /// Builds and returns new [`Loader`] instance.
-    pub fn build(self) -> Loader {
-        let random_state = RandomState::new();
-        let sources: Arc<[_]> = self.sources.into();
+    /// Sets factory for cache backends of the loader.
+    ///
+    /// Each cache shard gets own backend instance.
+    /// By default [`HashMapCache`] is used.
+    ///
+    /// [`HashMapCache`]: crate::HashMapCache
+    pub fn set_cache_backend(&mut self, factory: impl CacheBackendFactory) -> &mut Self {
+        self.cache_backend = Box::new(factory);
+        self
+    }
 
-        let asset_shards: Vec<AssetShard> = (0..self.num_shards)
-            .map(|_| Arc::new(Mutex::new(HashMap::with_hasher(random_state.clone()))))
-            .collect();
+    /// Sets factory for cache backends of the loader.
+    ///
+    /// Each cache shard gets own backend instance.
+    /// By default [`HashMapCache`] is used.
+    ///
+    /// [`HashMapCache`]: crate::HashMapCache
+    pub fn with_cache_backend(mut self, factory: impl CacheBackendFactory) -> Self {
+        self.set_cache_backend(factory);
+        self
+    }
 
-        let path_shards: Vec<PathShard> = (0..self.num_shards)
-            .map(|_| Arc::new(Mutex::new(HashMap::with_hasher(random_state.clone()))))
-            .collect();
+    /// Enables automatic unloading of assets.
+    ///
+    /// Built asset is removed from the cache when `grace` period passes
+    /// after its last handle is dropped, unless new handle is requested in between.
+    /// Next request loads the asset anew.
+    /// Assets that are not built yet, missing or failed are not unloaded.
+    ///
+    /// Unloading is performed by a background task,
+    /// spawned on the tokio runtime of the first load.
+    /// Runtime must have time driver enabled.
+    /// Without `tokio` feature assets are unloaded by `Loader::pump`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use futures::future::BoxFuture;
+    /// # use std::{sync::atomic::{AtomicUsize, Ordering}, time::Duration};
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// static LOADS: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// /// Source that counts loads.
+    /// struct Counting(MemorySource);
+    ///
+    /// impl Source for Counting {
+    ///     fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+    ///         self.0.find(path, asset)
+    ///     }
+    ///
+    ///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         LOADS.fetch_add(1, Ordering::Relaxed);
+    ///         self.0.load(id)
+    ///     }
+    ///
+    ///     fn update<'a>(&'a self, id: AssetId, version: u64) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         self.0.update(id, version)
+    ///     }
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 7 }"#[..]);
+    ///
+    /// let loader = Loader::builder()
+    ///     .with(Counting(source))
+    ///     .with_auto_unload(Duration::from_millis(10))
+    ///     .build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .enable_time()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let mut number = loader.load::<Number, _>("number").await?;
+    ///         assert_eq!(number.build(&mut ())?.value, 7);
+    ///
+    ///         // Asset is kept while handle is alive.
+    ///         tokio::time::sleep(Duration::from_millis(50)).await;
+    ///         let mut again = loader.load::<Number, _>("number").await?;
+    ///         assert_eq!(again.build(&mut ())?.value, 7);
+    ///         assert_eq!(LOADS.load(Ordering::Relaxed), 1);
+    ///
+    ///         // And unloaded after the last handle is dropped.
+    ///         drop(number);
+    ///         drop(again);
+    ///         tokio::time::sleep(Duration::from_millis(50)).await;
+    ///         let mut number = loader.load::<Number, _>("number").await?;
+    ///         assert_eq!(number.build(&mut ())?.value, 7);
+    ///         assert_eq!(LOADS.load(Ordering::Relaxed), 2);
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn set_auto_unload(&mut self, grace: Duration) -> &mut Self {
+        self.auto_unload = Some(grace);
+        self
+    }
 
-        Loader {
-            sources,
-            random_state,
-            asset_cache: asset_shards.into(),
-            path_cache: path_shards.into(),
-        }
+    /// Sets persistent cache for decoded assets.
+    ///
+    /// Used for assets loaded with [`Loader::load_cached`].
+    pub fn set_decode_cache(&mut self, cache: impl DecodeCache) -> &mut Self {
+        self.decode_cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Sets persistent cache for decoded assets.
+    ///
+    /// See [`LoaderBuilder::set_decode_cache`].
+    pub fn with_decode_cache(mut self, cache: impl DecodeCache) -> Self {
+        self.set_decode_cache(cache);
+        self
     }
-}
 
-pub(crate) type AssetShard = Arc<Mutex<HashMap<TypeKey, AssetState, RandomState>>>;
-pub(crate) type PathShard = Arc<Mutex<HashMap<PathKey, PathState, RandomState>>>;
+    /// Enables or disables collection of decode statistics per asset type.
+    ///
+    /// Disabled by default.
+    /// When enabled, each decoding costs a few atomic operations.
+    /// See [`Loader::type_stats`].
+    pub fn set_decode_stats(&mut self, enabled: bool) -> &mut Self {
+        self.decode_stats = enabled;
+        self
+    }
 
-/// Virtual storage for all available assets.
-#[derive(Clone)]
-pub struct Loader {
-    /// Array of available asset sources.
-    sources: Arc<[Box<dyn Source>]>,
+    /// Enables or disables collection of decode statistics per asset type.
+    ///
+    /// See [`LoaderBuilder::set_decode_stats`].
+    pub fn with_decode_stats(mut self, enabled: bool) -> Self {
+        self.set_decode_stats(enabled);
+        self
+    }
 
-    /// Hasher to pick a shard.
-    random_state: RandomState,
+    /// Enables recording of assets used by the loader.
+    ///
+    /// Each successfully decoded asset is reported to `sink` once,
+    /// with its type name, id and path, if requested with one.
+    /// Recorded usages are available with [`Loader::usage_snapshot`].
+    pub fn set_usage_recording(&mut self, sink: impl UsageSink) -> &mut Self {
+        self.usage = Some(Box::new(sink));
+        self
+    }
 
-    /// Cache with asset states.
-    asset_cache: Arc<[AssetShard]>,
+    /// Enables recording of assets used by the loader.
+    ///
+    /// See [`LoaderBuilder::set_usage_recording`].
+    pub fn with_usage_recording(mut self, sink: impl UsageSink) -> Self {
+        self.set_usage_recording(sink);
+        self
+    }
 
-    /// Cache with path states.
-    path_cache: Arc<[PathShard]>,
-}
+    /// Enables recording of interactions with sources.
+    ///
+    /// Each find, load and update request made to the sources is reported to `sink`
+    /// with the answer of the sources and time it took.
+    /// Requests are recorded for all sources together,
+    /// so the log can be replayed with single [`ReplaySource`].
+    ///
+    /// See [`ReplaySource`] for example.
+    ///
+    /// [`ReplaySource`]: crate::ReplaySource
+    pub fn set_recording(&mut self, sink: impl RecordingSink) -> &mut Self {
+        self.recording = Some(Box::new(sink));
+        self
+    }
 
-pub(crate) type DecodedState<A> = Option<<A as Asset>::Decoded>;
+    /// Enables recording of interactions with sources.
+    ///
+    /// See [`LoaderBuilder::set_recording`].
+    pub fn with_recording(mut self, sink: impl RecordingSink) -> Self {
+        self.set_recording(sink);
+        self
+    }
 
-pub(crate) enum AssetState {
-    /// Not yet loaded asset.
-    Unloaded {
-        wakers: WakeOnDrop,
-    },
-    Loaded {
-        // Contains `DecodedState<A>`
-        decoded: Arc<spin::Mutex<dyn Any + Send + Sync>>,
-        version: u64,
-        source: usize,
-        wakers: WakeOnDrop,
-    },
-    Ready {
-        // Contains `A`
-        asset: Arc<dyn Any + Send + Sync>,
-        version: u64,
-        source: usize,
-    },
-    /// All sources reported that asset is missing.
-    Missing,
-    Error {
-        error: Error,
-    },
-}
+    /// Enables automatic unloading of assets.
+    ///
+    /// See [`LoaderBuilder::set_auto_unload`].
+    pub fn with_auto_unload(mut self, grace: Duration) -> Self {
+        self.set_auto_unload(grace);
+        self
+    }
+
+    /// Limits number of cached path lookups.
+    ///
+    /// By default every path ever requested stays in the cache.
+    /// With the limit set, least recently requested paths are forgotten
+    /// when number of cached paths exceeds `capacity`.
+    /// Capacity is split evenly among shards.
+    ///
+    /// Only paths that are not referenced by any handle are forgotten.
+    /// Paths being searched for are never forgotten.
+    /// Requesting forgotten path searches sources for it again.
+    /// This also means that forgotten missing path is looked up again,
+    /// as if [`Loader::forget_failures`] was called for it.
+    ///
+    /// Assets themselves are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use futures::future::BoxFuture;
+    /// # use std::sync::Arc;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// /// Source that holds lookup of "slow" path until notified.
+    /// struct Gated(MemorySource, Arc<tokio::sync::Notify>);
+    ///
+    /// impl Source for Gated {
+    ///     fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+    ///         Box::pin(async move {
+    ///             if path == "slow" {
+    ///                 self.1.notified().await;
+    ///             }
+    ///             self.0.find(path, asset).await
+    ///         })
+    ///     }
+    ///
+    ///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         self.0.load(id)
+    ///     }
+    ///
+    ///     fn update<'a>(&'a self, id: AssetId, version: u64) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         self.0.update(id, version)
+    ///     }
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// for (i, path) in ["slow", "a", "b", "c"].into_iter().enumerate() {
+    ///     let bytes = format!(r#"{{ "value": {} }}"#, i);
+    ///     source.insert_with_path(path, AssetId::new(i as u64 + 1).unwrap(), bytes.as_bytes());
+    /// }
+    /// let gate = Arc::new(tokio::sync::Notify::new());
+    ///
+    /// let loader = Loader::builder()
+    ///     .with(Gated(source, gate.clone()))
+    ///     .with_num_shards(1)
+    ///     .with_path_cache_capacity(2)
+    ///     .build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let slow = loader.load::<Number, _>("slow");
+    ///
+    ///         for path in ["a", "b", "c"] {
+    ///             loader.load::<Number, _>(path).await?;
+    ///         }
+    ///
+    ///         // Least recently used path is forgotten.
+    ///         assert_eq!(loader.stats().path_cache_len, 2);
+    ///
+    ///         // Path being searched for is kept.
+    ///         gate.notify_one();
+    ///         let mut slow = slow.await?;
+    ///         assert_eq!(slow.build(&mut ())?.value, 0);
+    ///
+    ///         // Forgotten path is searched again.
+    ///         let mut a = loader.load::<Number, _>("a").await?;
+    ///         assert_eq!(a.build(&mut ())?.value, 1);
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn set_path_cache_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.path_cache_capacity = Some(capacity);
+        self
+    }
+
+    /// Limits number of cached path lookups.
+    ///
+    /// See [`LoaderBuilder::set_path_cache_capacity`].
+    pub fn with_path_cache_capacity(mut self, capacity: usize) -> Self {
+        self.set_path_cache_capacity(capacity);
+        self
+    }
+
+    /// Sets how sources are queried for assets.
+    ///
+    /// Default is [`SourceStrategy::Sequential`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use futures::future::BoxFuture;
+    /// # use std::time::Duration;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// /// Source that answers after a latency.
+    /// struct Delayed(MemorySource, Duration);
+    ///
+    /// impl Source for Delayed {
+    ///     fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+    ///         Box::pin(async move {
+    ///             tokio::time::sleep(self.1).await;
+    ///             self.0.find(path, asset).await
+    ///         })
+    ///     }
+    ///
+    ///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         Box::pin(async move {
+    ///             tokio::time::sleep(self.1).await;
+    ///             self.0.load(id).await
+    ///         })
+    ///     }
+    ///
+    ///     fn update<'a>(&'a self, id: AssetId, version: u64) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         self.0.update(id, version)
+    ///     }
+    /// }
+    ///
+    /// let network = MemorySource::new();
+    /// network.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 1 }"#[..]);
+    ///
+    /// let local = MemorySource::new();
+    /// local.insert_with_path("number", AssetId::new(2).unwrap(), &br#"{ "value": 2 }"#[..]);
+    ///
+    /// let loader = Loader::builder()
+    ///     .with(Delayed(network, Duration::from_secs(60)))
+    ///     .with(Delayed(local, Duration::from_millis(1)))
+    ///     .with_source_strategy(SourceStrategy::Staggered {
+    ///         delay: Duration::from_millis(10),
+    ///     })
+    ///     .build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .enable_time()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         // Local source answers while network source is still busy.
+    ///         let lookup = loader.load::<Number, _>("number");
+    ///         let mut number = tokio::time::timeout(Duration::from_secs(1), lookup)
+    ///             .await
+    ///             .expect("Slow source must not be awaited")?;
+    ///         assert_eq!(number.build(&mut ())?.value, 2);
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn set_source_strategy(&mut self, strategy: SourceStrategy) -> &mut Self {
+        self.source_strategy = strategy;
+        self
+    }
+
+    /// Sets how sources are queried for assets.
+    ///
+    /// See [`LoaderBuilder::set_source_strategy`].
+    pub fn with_source_strategy(mut self, strategy: SourceStrategy) -> Self {
+        self.set_source_strategy(strategy);
+        self
+    }
+
+    /// Sets limits of the prefetch scheduler.
+    ///
+    /// At most `max_running` prefetches are loaded at once,
+    /// and no prefetch starts while prefetched assets that are not built yet
+    /// hold `max_bytes` of raw data or more.
+    ///
+    /// See [`Loader::prefetch`].
+    pub fn set_prefetch_limits(&mut self, max_running: usize, max_bytes: usize) -> &mut Self {
+        self.max_prefetches = max_running;
+        self.max_prefetch_bytes = max_bytes;
+        self
+    }
+
+    /// Sets limits of the prefetch scheduler.
+    ///
+    /// See [`LoaderBuilder::set_prefetch_limits`].
+    pub fn with_prefetch_limits(mut self, max_running: usize, max_bytes: usize) -> Self {
+        self.set_prefetch_limits(max_running, max_bytes);
+        self
+    }
+
+    /// Sets limits of update sweeps.
+    ///
+    /// Each [`Loader::update_tick`] calls [`Source::update`] at most `max_per_tick` times.
+    /// Asset that is found unchanged is checked again after 2, 4, 8... ticks,
+    /// but at most after `max_interval` ticks.
+    pub fn set_update_limits(&mut self, max_per_tick: usize, max_interval: u64) -> &mut Self {
+        self.max_updates_per_tick = max_per_tick;
+        self.max_update_interval = max_interval;
+        self
+    }
+
+    /// Sets limits of update sweeps.
+    ///
+    /// See [`LoaderBuilder::set_update_limits`].
+    pub fn with_update_limits(mut self, max_per_tick: usize, max_interval: u64) -> Self {
+        self.set_update_limits(max_per_tick, max_interval);
+        self
+    }
+
+    /// Sets maximum number of failures kept until [`Loader::take_failures`].
+    /// Oldest failures are dropped first. Zero disables recording.
+    ///
+    /// Default is 64.
+    pub fn set_max_failures(&mut self, max: usize) -> &mut Self {
+        self.max_failures = max;
+        self
+    }
+
+    /// Sets maximum number of failures kept until [`Loader::take_failures`].
+    ///
+    /// See [`LoaderBuilder::set_max_failures`].
+    pub fn with_max_failures(mut self, max: usize) -> Self {
+        self.set_max_failures(max);
+        self
+    }
+
+    /// Sets policy for an asset requested as different types.
+    ///
+    /// Default is [`TypeConflictPolicy::Warn`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Texture {
+    ///     value: u32,
+    /// }
+    ///
+    /// #[derive(Clone, Asset)]
+    /// struct Mesh {
+    ///     value: u32,
+    /// }
+    ///
+    /// let id = AssetId::new(1).unwrap();
+    ///
+    /// for policy in [TypeConflictPolicy::Allow, TypeConflictPolicy::Warn, TypeConflictPolicy::Deny] {
+    ///     let source = MemorySource::new();
+    ///     source.insert_with_path("tile", id, &br#"{ "value": 3 }"#[..]);
+    ///     let loader = Loader::builder()
+    ///         .with(source)
+    ///         .with_type_conflicts(policy)
+    ///         .build();
+    ///
+    ///     tokio::runtime::Builder::new_current_thread()
+    ///         .build()
+    ///         .unwrap()
+    ///         .block_on(async move {
+    ///             let mut texture = loader.load::<Texture, _>(id).await?;
+    ///             assert_eq!(texture.build(&mut ())?.value, 3);
+    ///
+    ///             // Requesting the same type again is not a conflict.
+    ///             loader.load::<Texture, _>(id).await?;
+    ///
+    ///             let mesh = loader.load::<Mesh, _>("tile").await;
+    ///             match policy {
+    ///                 TypeConflictPolicy::Deny => {
+    ///                     let error = mesh.err().unwrap();
+    ///                     let conflict = error.downcast_ref::<TypeConflict>().unwrap();
+    ///                     assert_eq!(conflict.id, id);
+    ///                     assert_eq!(&*conflict.existing_type, Texture::asset_name());
+    ///                     assert_eq!(&*conflict.requested_type, Mesh::asset_name());
+    ///
+    ///                     let error = loader.load::<Mesh, _>(id).await.err().unwrap();
+    ///                     assert!(error.is::<TypeConflict>());
+    ///                 }
+    ///                 _ => assert_eq!(mesh?.build(&mut ())?.value, 3),
+    ///             }
+    ///             Ok::<_, Error>(())
+    ///         })?;
+    /// }
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn set_type_conflicts(&mut self, policy: TypeConflictPolicy) -> &mut Self {
+        self.type_conflicts = policy;
+        self
+    }
+
+    /// Sets policy for an asset requested as different types.
+    ///
+    /// See [`LoaderBuilder::set_type_conflicts`].
+    pub fn with_type_conflicts(mut self, policy: TypeConflictPolicy) -> Self {
+        self.set_type_conflicts(policy);
+        self
+    }
+
+    /// Limits raw bytes of decoded assets that are not built yet.
+    ///
+    /// When decoding outpaces building, loads that would exceed `max_bytes`
+    /// wait before decoding, holding only raw data,
+    /// until builds bring unbuilt bytes down to `resume_bytes`.
+    /// Asset larger than `max_bytes` is decoded when nothing else is unbuilt.
+    /// Waiting loads are still in flight for [`Loader::wait_idle`].
+    ///
+    /// While loads wait, [`LoaderStats::build_urgent`] is set
+    /// and a warning is emitted once, so the application knows building is the bottleneck.
+    /// Loads with [`LoadOptions::priority`] of at least `critical_priority` never wait.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// async fn settle() {
+    ///     for _ in 0..20 {
+    ///         tokio::task::yield_now().await;
+    ///     }
+    /// }
+    ///
+    /// let id = |value| AssetId::new(value).unwrap();
+    ///
+    /// let source = MemorySource::new();
+    /// for value in 1..=4 {
+    ///     source.insert(id(value), format!(r#"{{ "value": {value} }}"#).into_bytes());
+    /// }
+    /// let loader = Loader::builder()
+    ///     .with(source)
+    ///     .with_unbuilt_limit(20, 0, 100)
+    ///     .build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let one = loader.load::<Number, _>(id(1));
+    ///         let mut two = loader.load::<Number, _>(id(2));
+    ///         let mut three = loader.load::<Number, _>(id(3));
+    ///         settle().await;
+    ///
+    ///         // Nothing builds, only one asset fits the limit.
+    ///         let stats = loader.stats();
+    ///         assert_eq!(stats.unbuilt_bytes, 14);
+    ///         assert_eq!(stats.parked_decodes, 2);
+    ///         assert!(stats.build_urgent);
+    ///         assert!(two.poll_loaded().is_none());
+    ///         assert!(three.poll_loaded().is_none());
+    ///
+    ///         // Critical asset is not held.
+    ///         let options = LoadOptions::new().with_priority(100);
+    ///         let mut four = loader.load_with_options::<Number, _>(id(4), options).await?;
+    ///         assert_eq!(four.build(&mut ())?.value, 4);
+    ///
+    ///         // Building lets waiting loads proceed.
+    ///         assert_eq!(one.await?.build(&mut ())?.value, 1);
+    ///         settle().await;
+    ///         assert_eq!(loader.stats().parked_decodes, 1);
+    ///         assert!(three.poll_loaded().is_none());
+    ///
+    ///         assert_eq!(two.await?.build(&mut ())?.value, 2);
+    ///         assert_eq!(three.await?.build(&mut ())?.value, 3);
+    ///
+    ///         let stats = loader.stats();
+    ///         assert_eq!(stats.unbuilt_bytes, 0);
+    ///         assert!(!stats.build_urgent);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn set_unbuilt_limit(
+        &mut self,
+        max_bytes: usize,
+        resume_bytes: usize,
+        critical_priority: i32,
+    ) -> &mut Self {
+        self.unbuilt_limit = Some((max_bytes, resume_bytes, critical_priority));
+        self
+    }
+
+    /// Limits raw bytes of decoded assets that are not built yet.
+    ///
+    /// See [`LoaderBuilder::set_unbuilt_limit`].
+    pub fn with_unbuilt_limit(
+        mut self,
+        max_bytes: usize,
+        resume_bytes: usize,
+        critical_priority: i32,
+    ) -> Self {
+        self.set_unbuilt_limit(max_bytes, resume_bytes, critical_priority);
+        self
+    }
+
+    /// Sets whether unknown fields in JSON asset infos are errors.
+    ///
+    /// Strict mode catches typos in descriptors during development.
+    /// Lenient mode, which is the default, ignores unknown fields,
+    /// so that content produced by newer tools can be loaded.
+    /// Bincode infos are always strict.
+    ///
+    /// Applies to assets with derived implementation of [`Asset`].
+    /// Manual implementations can follow it with [`Loader::strict_descriptors`].
+    /// Only top-level fields of asset info are checked.
+    /// Infos of assets that use `#[serde(flatten)]` on fields or
+    /// `#[serde(rename_all)]` are not checked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Door {
+    ///     #[serde(rename = "locked", alias = "closed")]
+    ///     is_locked: bool,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("door", AssetId::new(1).unwrap(), &br#"{ "locked": true, "colour": "red" }"#[..]);
+    /// source.insert_with_path("gate", AssetId::new(2).unwrap(), &br#"{ "closed": false }"#[..]);
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    ///
+    /// // Unknown field is ignored by default.
+    /// let lenient = Loader::builder().with(source.clone()).build();
+    /// runtime.block_on(async {
+    ///     let mut door = lenient.load::<Door, _>("door").await?;
+    ///     assert!(door.build(&mut ())?.is_locked);
+    ///     Ok::<_, Error>(())
+    /// })?;
+    ///
+    /// let strict = Loader::builder()
+    ///     .with(source)
+    ///     .with_strict_descriptors(true)
+    ///     .build();
+    /// runtime.block_on(async {
+    ///     let err = strict.load::<Door, _>("door").await.err().unwrap();
+    ///     assert!(format!("{err:#}").contains("Unknown field 'colour'"));
+    ///
+    ///     // Aliases are known fields.
+    ///     let mut gate = strict.load::<Door, _>("gate").await?;
+    ///     assert!(!gate.build(&mut ())?.is_locked);
+    ///     Ok::<_, Error>(())
+    /// })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn set_strict_descriptors(&mut self, strict: bool) -> &mut Self {
+        self.strict_descriptors = strict;
+        self
+    }
+
+    /// Sets whether unknown fields in JSON asset infos are errors.
+    ///
+    /// See [`LoaderBuilder::set_strict_descriptors`].
+    pub fn with_strict_descriptors(mut self, strict: bool) -> Self {
+        self.set_strict_descriptors(strict);
+        self
+    }
+
+    /// Enables warning about assets that are awaited with [`AssetFuture`]
+    /// but stay decoded and not built for longer than `threshold`.
+    ///
+    /// Such futures resolve only when something builds the asset,
+    /// awaiting them on the task that is supposed to build it never completes.
+    /// Warning is emitted once per load with `tracing`.
+    /// Use [`AssetFuture::ready_or_build`] to build asset inline instead.
+    ///
+    /// Requires `tokio` feature.
+    /// Runtime must have time driver enabled.
+    ///
+    /// [`AssetFuture`]: crate::AssetFuture
+    /// [`AssetFuture::ready_or_build`]: crate::AssetFuture::ready_or_build
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use std::{sync::{Arc, Mutex}, time::Duration};
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// /// Collects messages of warning events.
+    /// #[derive(Clone, Default)]
+    /// struct Warnings(Arc<Mutex<Vec<String>>>);
+    ///
+    /// impl tracing::Subscriber for Warnings {
+    ///     fn enabled(&self, meta: &tracing::Metadata<'_>) -> bool {
+    ///         *meta.level() <= tracing::Level::WARN
+    ///     }
+    ///     fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+    ///         tracing::span::Id::from_u64(1)
+    ///     }
+    ///     fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+    ///     fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+    ///     fn event(&self, event: &tracing::Event<'_>) {
+    ///         struct Message<'a>(&'a mut String);
+    ///         impl tracing::field::Visit for Message<'_> {
+    ///             fn record_debug(&mut self, _: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+    ///                 self.0.push_str(&format!("{value:?}"));
+    ///             }
+    ///         }
+    ///         let mut message = String::new();
+    ///         event.record(&mut Message(&mut message));
+    ///         self.0.lock().unwrap().push(message);
+    ///     }
+    ///     fn enter(&self, _: &tracing::span::Id) {}
+    ///     fn exit(&self, _: &tracing::span::Id) {}
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 3 }"#[..]);
+    /// let loader = Loader::builder()
+    ///     .with(source)
+    ///     .with_build_wait_warning(Duration::from_millis(10))
+    ///     .build();
+    ///
+    /// let warnings = Warnings::default();
+    /// tracing::subscriber::with_default(warnings.clone(), || {
+    ///     tokio::runtime::Builder::new_current_thread()
+    ///         .enable_time()
+    ///         .build()
+    ///         .unwrap()
+    ///         .block_on(async {
+    ///             // Nothing builds the number, so this would wait forever.
+    ///             let ready = loader.load::<Number, _>("number").ready();
+    ///             let result = tokio::time::timeout(Duration::from_millis(100), ready).await;
+    ///             assert!(result.is_err());
+    ///         })
+    /// });
+    ///
+    /// let warnings = warnings.0.lock().unwrap();
+    /// assert_eq!(warnings.len(), 1);
+    /// assert!(warnings[0].contains("Number"));
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn set_build_wait_warning(&mut self, threshold: Duration) -> &mut Self {
+        self.build_wait_warning = Some(threshold);
+        self
+    }
+
+    /// Enables warning about assets that are awaited but not built.
+    ///
+    /// See [`LoaderBuilder::set_build_wait_warning`].
+    #[cfg(feature = "tokio")]
+    pub fn with_build_wait_warning(mut self, threshold: Duration) -> Self {
+        self.set_build_wait_warning(threshold);
+        self
+    }
+
+    /// Sets clock that measures retry intervals, deadlines, stagger delays,
+    /// build wait warnings and auto-unload grace periods.
+    ///
+    /// Default is [`SystemClock`].
+    /// Tests may set `VirtualClock` and advance it manually.
+    pub fn set_clock(&mut self, clock: impl Clock) -> &mut Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Sets clock of time-dependent policies.
+    ///
+    /// See [`LoaderBuilder::set_clock`].
+    pub fn with_clock(mut self, clock: impl Clock) -> Self {
+        self.set_clock(clock);
+        self
+    }
+
+    /// Sets time for which data fetched by one tenant is reused by other tenants.
+    ///
+    /// Default is one second.
+    /// Zero disables reuse of fetched data.
+    /// See [`Loader::tenant`].
+    pub fn set_tenant_fetch_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.tenant_fetch_ttl = ttl;
+        self
+    }
+
+    /// Sets time for which fetched data is reused by tenants.
+    ///
+    /// See [`LoaderBuilder::set_tenant_fetch_ttl`].
+    pub fn with_tenant_fetch_ttl(mut self, ttl: Duration) -> Self {
+        self.set_tenant_fetch_ttl(ttl);
+        self
+    }
+
+    /// Registers name of the asset type.
+    ///
+    /// [`Asset::asset_name`] is the only thing sources see to tell asset types apart,
+    /// so two types with the same name would find each other's assets.
+    /// Registered names are checked for uniqueness when the loader is built
+    /// and can be resolved to types with [`Loader::resolve_type_name`].
+    ///
+    /// Registration is optional, unregistered types are loaded as usual.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// mod ui {
+    ///     #[derive(Clone, argosy::Asset)]
+    ///     #[asset(name = "config")]
+    ///     pub struct Config {
+    ///         pub scale: f32,
+    ///     }
+    /// }
+    ///
+    /// mod audio {
+    ///     #[derive(Clone, argosy::Asset)]
+    ///     #[asset(name = "config")]
+    ///     pub struct Config {
+    ///         pub volume: f32,
+    ///     }
+    /// }
+    ///
+    /// let err = Loader::builder()
+    ///     .with_registered_asset::<ui::Config>()
+    ///     .with_registered_asset::<audio::Config>()
+    ///     .try_build()
+    ///     .err()
+    ///     .unwrap();
+    /// assert_eq!(err.name, "config");
+    ///
+    /// // Without strict checking duplicates are only reported with a warning
+    /// // and the first registered type keeps the name.
+    /// let loader = Loader::builder()
+    ///     .with_registered_asset::<ui::Config>()
+    ///     .with_registered_asset::<audio::Config>()
+    ///     .with_strict_asset_names(false)
+    ///     .build();
+    ///
+    /// let ui_config = std::any::TypeId::of::<ui::Config>();
+    /// assert_eq!(loader.resolve_type_name("config"), Some(ui_config));
+    /// assert_eq!(loader.resolve_type_name("unknown"), None);
+    /// ```
+    pub fn register_asset<A: Asset>(&mut self) -> &mut Self {
+        self.asset_names.register::<A>();
+        self
+    }
+
+    /// Registers name of the asset type.
+    ///
+    /// See [`LoaderBuilder::register_asset`].
+    pub fn with_registered_asset<A: Asset>(mut self) -> Self {
+        self.register_asset::<A>();
+        self
+    }
+
+    /// Sets whether duplicate names of registered asset types
+    /// fail the build or are only reported with a warning.
+    ///
+    /// Enabled by default.
+    pub fn set_strict_asset_names(&mut self, strict: bool) -> &mut Self {
+        self.strict_asset_names = strict;
+        self
+    }
+
+    /// Sets whether duplicate names of registered asset types
+    /// fail the build or are only reported with a warning.
+    ///
+    /// See [`LoaderBuilder::set_strict_asset_names`].
+    pub fn with_strict_asset_names(mut self, strict: bool) -> Self {
+        self.set_strict_asset_names(strict);
+        self
+    }
+
+    /// Sets whether missing external assets are replaced with placeholders.
+    ///
+    /// In development mode asset field that refers to missing asset
+    /// gets placeholder registered with [`LoaderBuilder::register_placeholder`]
+    /// instead of failing the asset that has the field.
+    /// Each substitution is recorded in [`Loader::dev_warnings`].
+    /// Missing assets of types without placeholder still fail.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Texture {
+    ///     size: u32,
+    /// }
+    ///
+    /// #[derive(Clone, Asset)]
+    /// struct Material {
+    ///     #[asset(external)]
+    ///     albedo: Texture,
+    /// }
+    ///
+    /// let material = AssetId::new(1).unwrap();
+    /// let texture = AssetId::new(2).unwrap();
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert(material, &br#"{ "albedo": 2 }"#[..]);
+    ///
+    /// let strict = Loader::builder().with(source.clone()).build();
+    /// let dev = Loader::builder()
+    ///     .with(source)
+    ///     .with_dev_placeholders(true)
+    ///     .with_registered_placeholder(Texture { size: 1 })
+    ///     .build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         assert!(strict.load::<Material, _>(material).await.is_err());
+    ///
+    ///         let material = dev.load::<Material, _>(material).await?.build(&mut ())?;
+    ///         assert_eq!(material.albedo.size, 1);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    ///
+    /// let warnings = dev.dev_warnings();
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].parent, Some(material));
+    /// assert_eq!(warnings[0].field, "Texture");
+    /// assert_eq!(warnings[0].missing, texture);
+    /// assert!(strict.dev_warnings().is_empty());
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn set_dev_placeholders(&mut self, enabled: bool) -> &mut Self {
+        self.dev_placeholders = enabled;
+        self
+    }
+
+    /// Sets whether missing external assets are replaced with placeholders.
+    ///
+    /// See [`LoaderBuilder::set_dev_placeholders`].
+    pub fn with_dev_placeholders(mut self, enabled: bool) -> Self {
+        self.set_dev_placeholders(enabled);
+        self
+    }
+
+    /// Registers placeholder for missing assets of type `A`.
+    /// Placeholders are used only in development mode.
+    ///
+    /// See [`LoaderBuilder::set_dev_placeholders`].
+    pub fn register_placeholder<A: Asset>(&mut self, asset: A) -> &mut Self {
+        self.placeholders.register(asset);
+        self
+    }
+
+    /// Registers placeholder for missing assets of type `A`.
+    ///
+    /// See [`LoaderBuilder::register_placeholder`].
+    pub fn with_registered_placeholder<A: Asset>(mut self, asset: A) -> Self {
+        self.register_placeholder(asset);
+        self
+    }
+
+    /// Registers hook called when published value of type `A` replaces ready one,
+    /// e.g. when asset is hot-reloaded.
+    /// Hook gets old and new values and decides which value is stored.
+    ///
+    /// Hook is called before cache entries are locked,
+    /// so it may use the loader, but must not publish assets.
+    /// If old value is replaced meanwhile, new value is stored as is.
+    ///
+    /// See [`Loader::begin_publish`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use std::sync::{Arc, Mutex};
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Debug, PartialEq, Asset)]
+    /// struct Emitter {
+    ///     rate: u32,
+    ///     seed: u32,
+    /// }
+    ///
+    /// #[derive(Clone, Default)]
+    /// struct Events(Arc<Mutex<Vec<ReloadEvent>>>);
+    ///
+    /// impl ReloadSink for Events {
+    ///     fn reloaded(&self, event: &ReloadEvent) {
+    ///         self.0.lock().unwrap().push(event.clone());
+    ///     }
+    /// }
+    ///
+    /// let id = AssetId::new(1).unwrap();
+    /// let source = MemorySource::new();
+    /// source.insert(id, &br#"{ "rate": 10, "seed": 1 }"#[..]);
+    ///
+    /// let events = Events::default();
+    /// let loader = Loader::builder()
+    ///     .with(source.clone())
+    ///     .with_registered_reload_hook(|old: &Emitter, new: &Emitter| match new.rate {
+    ///         // Broken data, keep running emitter.
+    ///         0 => ReloadAction::KeepOld,
+    ///         _ if new.seed == old.seed => ReloadAction::Replace,
+    ///         // Keep random sequence of running particles.
+    ///         rate => ReloadAction::Custom(Emitter { rate, seed: old.seed }),
+    ///     })
+    ///     .with_reload_events(events.clone())
+    ///     .build();
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// runtime.block_on(async { loader.load::<Emitter, _>(id).await?.build(&mut ()) })?;
+    ///
+    /// let reload = |data: &'static [u8]| {
+    ///     source.insert(id, data);
+    ///
+    ///     runtime.block_on(async {
+    ///         assert_eq!(loader.update_tick().await, [id]);
+    ///
+    ///         // Decode new version apart from the cache and publish it.
+    ///         let fresh = Loader::builder().with(source.clone()).build();
+    ///         let mut loaded = fresh.load::<Emitter, _>(id).await?;
+    ///         let version = loaded.metadata().version;
+    ///         let emitter = loaded.build(&mut ())?;
+    ///         let mut tx = loader.begin_publish();
+    ///         tx.stage_with_version(id, emitter, version);
+    ///         tx.commit();
+    ///
+    ///         Ok::<_, Error>(loader.load::<Emitter, _>(id).poll_ready().unwrap()?)
+    ///     })
+    /// };
+    ///
+    /// assert_eq!(reload(br#"{ "rate": 20, "seed": 1 }"#)?, Emitter { rate: 20, seed: 1 });
+    /// assert_eq!(reload(br#"{ "rate": 0, "seed": 1 }"#)?, Emitter { rate: 20, seed: 1 });
+    /// assert_eq!(reload(br#"{ "rate": 30, "seed": 2 }"#)?, Emitter { rate: 30, seed: 1 });
+    ///
+    /// let events = events.0.lock().unwrap();
+    /// let outcomes: Vec<_> = events.iter().map(|e| (e.old_version, e.new_version, e.outcome)).collect();
+    /// assert_eq!(
+    ///     outcomes,
+    ///     [
+    ///         (0, 1, ReloadOutcome::Replaced),
+    ///         (1, 1, ReloadOutcome::KeptOld),
+    ///         (1, 3, ReloadOutcome::Custom),
+    ///     ]
+    /// );
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn register_reload_hook<A, F>(&mut self, hook: F) -> &mut Self
+    where
+        A: Asset,
+        F: Fn(&A, &A) -> ReloadAction<A> + Send + Sync + 'static,
+    {
+        self.reload.register(hook);
+        self
+    }
+
+    /// Registers hook called when published value of type `A` replaces ready one.
+    ///
+    /// See [`LoaderBuilder::register_reload_hook`].
+    pub fn with_registered_reload_hook<A, F>(mut self, hook: F) -> Self
+    where
+        A: Asset,
+        F: Fn(&A, &A) -> ReloadAction<A> + Send + Sync + 'static,
+    {
+        self.register_reload_hook(hook);
+        self
+    }
+
+    /// Sets sink for events of assets replaced with reload hooks.
+    ///
+    /// See [`LoaderBuilder::register_reload_hook`].
+    pub fn set_reload_events(&mut self, sink: impl ReloadSink) -> &mut Self {
+        self.reload.set_sink(Box::new(sink));
+        self
+    }
+
+    /// Sets sink for events of assets replaced with reload hooks.
+    ///
+    /// See [`LoaderBuilder::register_reload_hook`].
+    pub fn with_reload_events(mut self, sink: impl ReloadSink) -> Self {
+        self.set_reload_events(sink);
+        self
+    }
+
+    /// Sets path aliases of the loader.
+    /// Each alias remaps asset path to another path before it is looked up.
+    ///
+    /// See [`Loader::set_path_alias`].
+    pub fn set_path_aliases<K, V>(&mut self, aliases: impl IntoIterator<Item = (K, V)>) -> &mut Self
+    where
+        K: Into<Arc<str>>,
+        V: Into<Arc<str>>,
+    {
+        self.path_aliases = aliases
+            .into_iter()
+            .map(|(from, to)| (from.into(), to.into()))
+            .collect();
+        self
+    }
+
+    /// Sets path aliases of the loader.
+    ///
+    /// See [`Loader::set_path_alias`].
+    pub fn with_path_aliases<K, V>(mut self, aliases: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<Arc<str>>,
+        V: Into<Arc<str>>,
+    {
+        self.set_path_aliases(aliases);
+        self
+    }
+
+    /// Builds and returns new [`Loader`] instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if registered asset types have duplicate names
+    /// and strict checking is enabled.
+    /// See [`LoaderBuilder::try_build`].
+    pub fn build(self) -> Loader {
+        match self.try_build() {
+            Ok(loader) => loader,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Builds and returns new [`Loader`] instance.
+    ///
+    /// Returns error if registered asset types have duplicate names
+    /// and strict checking is enabled.
+    /// See [`LoaderBuilder::register_asset`].
+    pub fn try_build(self) -> Result<Loader, DuplicateAssetName> {
+        for duplicate in self.asset_names.duplicates() {
+            if self.strict_asset_names {
+                return Err(duplicate.clone());
+            }
+            tracing::warn!("{duplicate}");
+        }
+
+        let random_state = RandomState::new();
+        let next_serial = AtomicU64::new(1);
+        let (slots, sources): (Vec<_>, Vec<Arc<dyn Source>>) = self
+            .sources
+            .into_iter()
+            .map(|(label, source)| {
+                let slot =
+                    SourceSlot::new(label, None, next_serial.fetch_add(1, Ordering::Relaxed));
+                (slot, Arc::from(source))
+            })
+            .unzip();
+        let (changed, _) = watch::channel(());
+
+        let path_aliases = self.path_aliases.into_iter().map(|(from, to)| {
+            match sources
+                .iter()
+                .find_map(|source| source.canonical_path(&from))
+            {
+                None => (from, to),
+                Some(canonical) => (canonical.into(), to),
+            }
+        });
+        let path_aliases = Arc::new(PathAliases::new(path_aliases));
+
+        let tenancy = Tenancy {
+            cache_backend: self.cache_backend,
+            num_shards: self.num_shards,
+            path_cache_capacity: self.path_cache_capacity,
+            auto_unload: self.auto_unload,
+            decode_stats: self.decode_stats,
+            max_prefetches: self.max_prefetches,
+            max_prefetch_bytes: self.max_prefetch_bytes,
+            max_failures: self.max_failures,
+            type_conflicts: self.type_conflicts,
+            fetches: FetchCache::new(self.tenant_fetch_ttl),
+        };
+
+        Ok(Loader {
+            sources: Arc::new(Sources {
+                array: RwLock::new(SourceArray {
+                    sources: sources.into(),
+                    slots: slots.into(),
+                    strategy: self.source_strategy,
+                }),
+                wait: AtomicBool::new(false),
+                changed,
+                next_serial,
+                replaced: AtomicU64::new(0),
+                recorder: self
+                    .recording
+                    .map(|sink| Recorder::new(sink, self.clock.clone())),
+                clock: self.clock.clone(),
+            }),
+            dependencies: Arc::new(Mutex::new(HashMap::with_hasher(random_state.clone()))),
+            shared_data: Arc::new(Mutex::new(VecDeque::new())),
+            in_flight: Arc::new(InFlight {
+                count: AtomicUsize::new(0),
+                idle: Notify::new(),
+            }),
+            auto_unload: self.auto_unload.map(|grace| {
+                Arc::new(AutoUnload::new(
+                    grace,
+                    random_state.clone(),
+                    self.clock.clone(),
+                ))
+            }),
+            sequence: Arc::new(AtomicU64::new(0)),
+            decode_cache: self.decode_cache,
+            decode_stats: self.decode_stats.then(|| Arc::new(DecodeStats::new())),
+            publish: Arc::new(RwLock::new(())),
+            usage: self.usage.map(|sink| Arc::new(UsageRecorder::new(sink))),
+            reload: Arc::new(self.reload),
+            cascades: Arc::new(Cascades::default()),
+            asset_names: Arc::new(self.asset_names),
+            strict_descriptors: self.strict_descriptors,
+            #[cfg(feature = "tokio")]
+            build_wait_warning: self.build_wait_warning,
+            prefetch: Arc::new(Prefetcher::new(
+                self.max_prefetches,
+                self.max_prefetch_bytes,
+            )),
+            failures: Arc::new(Failures::new(self.max_failures)),
+            asset_kinds: Arc::new(AssetKinds::new(self.type_conflicts)),
+            clock: self.clock.clone(),
+            backpressure: self
+                .unbuilt_limit
+                .map(|(max_bytes, resume_bytes, critical)| {
+                    Arc::new(Backpressure::new(max_bytes, resume_bytes, critical))
+                }),
+            dev: self
+                .dev_placeholders
+                .then(|| Arc::new(DevMode::new(self.placeholders))),
+            path_lookups: Arc::new(PathLookups::new()),
+            path_aliases,
+            updates: Arc::new(UpdateScheduler::new(
+                self.max_updates_per_tick,
+                self.max_update_interval,
+            )),
+            reloaders: Arc::new(Reloaders::default()),
+            decoding: None,
+            decoding_path: None,
+            decoding_properties: AssetProperties::new(),
+            decoding_format: None,
+            abort: None,
+            #[cfg(not(feature = "tokio"))]
+            tasks: Arc::new(Tasks::new()),
+            #[cfg(feature = "tokio")]
+            runtime: Arc::new(std::sync::OnceLock::new()),
+            random_state,
+            asset_cache: tenancy.asset_shards(),
+            path_cache: tenancy.path_shards(),
+            tenancy: Arc::new(tenancy),
+            tenant: None,
+        })
+    }
+}
+
+pub(crate) type AssetShard = Arc<Mutex<Box<dyn CacheBackend<TypeKey, AssetState>>>>;
+pub(crate) type PathShard = Arc<Mutex<Box<dyn CacheBackend<PathKey, PathState>>>>;
+
+/// Statistics of the loader caches.
+///
+/// See [`Loader::stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LoaderStats {
+    /// Number of cached path lookups.
+    pub path_cache_len: usize,
+
+    /// Number of cached assets loaded from sources
+    /// that were removed or replaced since.
+    pub orphaned_entries: usize,
+
+    /// Number of decoded assets that were never built
+    /// and have no handles left.
+    ///
+    /// See [`Loader::drop_orphaned_decoded`].
+    pub orphaned_decoded: usize,
+
+    /// Number of [`Source::update`] calls made by [`Loader::update_tick`].
+    pub update_calls: u64,
+
+    /// Number of update calls that found newer asset data.
+    pub updates_found: u64,
+
+    /// Number of times asset was skipped by update sweep
+    /// because it was found unchanged recently.
+    pub updates_backed_off: u64,
+
+    /// Raw bytes of decoded assets that are not built yet.
+    ///
+    /// Counted only with [`LoaderBuilder::set_unbuilt_limit`].
+    pub unbuilt_bytes: usize,
+
+    /// Number of loads waiting for builds before decoding.
+    pub parked_decodes: usize,
+
+    /// Whether building is the bottleneck.
+    /// Set when limit of unbuilt assets is exceeded
+    /// and cleared when builds bring them below resume mark.
+    pub build_urgent: bool,
+
+    /// Number of loads of the tenant that reused data fetched by other tenants.
+    /// See [`Loader::tenant`].
+    pub shared_fetches: usize,
+}
+
+/// State of the cache entry reported by [`Loader::entries`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EntryStatus {
+    /// Asset is being searched for or loaded.
+    Pending,
+
+    /// Path is resolved to an id or asset is decoded but not built yet.
+    Loaded,
+
+    /// Asset is built.
+    Ready,
+
+    /// Asset is missing.
+    Missing,
+
+    /// Asset failed to load.
+    Error,
+}
+
+/// Summary of the loader cache entry.
+///
+/// See [`Loader::entries`] and [`Loader::entries_since`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct EntrySummary {
+    /// Sequence number assigned to the entry when it was inserted.
+    /// Entries inserted later have greater numbers.
+    pub sequence: u64,
+
+    /// Path of the asset for path lookup entries.
+    pub path: Option<Arc<str>>,
+
+    /// Id of the asset.
+    /// `None` for path lookup entries that are not resolved.
+    pub id: Option<AssetId>,
+
+    /// State of the entry.
+    pub status: EntryStatus,
+
+    /// Whether asset was loaded from source that was removed or replaced since.
+    pub orphaned: bool,
+}
+
+/// Virtual storage for all available assets.
+#[derive(Clone)]
+pub struct Loader {
+    /// Available asset sources.
+    sources: Arc<Sources>,
+
+    /// Hasher to pick a shard.
+    random_state: RandomState,
+
+    /// Cache with asset states.
+    asset_cache: Arc<[AssetShard]>,
+
+    /// Cache with path states.
+    path_cache: Arc<[PathShard]>,
+
+    /// Dependencies of assets recorded during decoding.
+    dependencies: Arc<Mutex<HashMap<AssetId, Vec<AssetId>, RandomState>>>,
+
+    /// Raw data of recently loaded artifacts with sub-assets.
+    shared_data: Arc<SharedData>,
+
+    /// Counter of spawned find and load tasks.
+    in_flight: Arc<InFlight>,
+
+    /// Unloads assets without handles, if enabled.
+    auto_unload: Option<Arc<AutoUnload>>,
+
+    /// Sequence number for the next cache entry.
+    sequence: Arc<AtomicU64>,
+
+    /// Persistent cache of decoded assets, if configured.
+    decode_cache: Option<Arc<dyn DecodeCache>>,
+
+    /// Decode statistics per asset type, if enabled.
+    decode_stats: Option<Arc<DecodeStats>>,
+
+    /// Held exclusively while published assets are swapped in.
+    publish: Arc<RwLock<()>>,
+
+    /// Records used assets, if enabled.
+    usage: Option<Arc<UsageRecorder>>,
+
+    /// Hooks called when published assets replace ready ones.
+    reload: Arc<ReloadHooks>,
+
+    /// Asset types rebuilt when their dependencies reload.
+    cascades: Arc<Cascades>,
+
+    /// Names of registered asset types.
+    asset_names: Arc<AssetNames>,
+
+    /// Whether unknown fields in asset infos are errors.
+    strict_descriptors: bool,
+
+    /// Time after which awaited but not built asset is reported.
+    #[cfg(feature = "tokio")]
+    build_wait_warning: Option<Duration>,
+
+    /// Schedules prefetches.
+    pub(crate) prefetch: Arc<Prefetcher>,
+
+    /// Failed loads that no handle observed.
+    pub(crate) failures: Arc<Failures>,
+
+    /// Types each asset was requested as.
+    asset_kinds: Arc<AssetKinds>,
+
+    /// Limit of decoded but not built bytes, if enabled.
+    backpressure: Option<Arc<Backpressure>>,
+
+    /// Source of time for time-dependent policies.
+    clock: Arc<dyn Clock>,
+
+    /// Development mode state, if enabled.
+    dev: Option<Arc<DevMode>>,
+
+    /// Path lookups not bound to asset types.
+    path_lookups: Arc<PathLookups>,
+
+    /// Path remapping table.
+    path_aliases: Arc<PathAliases>,
+
+    /// Paces update calls to sources.
+    updates: Arc<UpdateScheduler>,
+
+    /// Decoders of loaded asset kinds for [`Loader::poll_updates`].
+    reloaders: Arc<Reloaders>,
+
+    /// Configuration of tenant caches and data fetched by tenants.
+    tenancy: Arc<Tenancy>,
+
+    /// Tenant this loader belongs to, if any.
+    tenant: Option<Arc<Tenant>>,
+
+    /// Asset that is decoded using this loader instance.
+    /// Assets requested with this instance are recorded as its dependencies.
+    decoding: Option<AssetId>,
+
+    /// Path with which decoded asset was requested.
+    /// Used to resolve relative paths.
+    decoding_path: Option<Arc<str>>,
+
+    /// Properties of decoded asset reported by the source.
+    decoding_properties: AssetProperties,
+
+    /// Format requested for decoded asset with [`Loader::load_as`].
+    decoding_format: Option<AssetFormat>,
+
+    /// Signal to abort decoding of the asset.
+    abort: Option<AbortSignal>,
+
+    /// Loading tasks driven by [`Loader::pump`].
+    #[cfg(not(feature = "tokio"))]
+    tasks: Arc<Tasks>,
+
+    /// Runtime of the first spawned loading task.
+    /// Tasks spawned outside of tokio runtime run on it.
+    #[cfg(feature = "tokio")]
+    runtime: Arc<std::sync::OnceLock<tokio::runtime::Handle>>,
+}
+
+/// Asset sources shared by all clones of the [`Loader`].
+///
+/// Sources are appended, so index of a source does not change
+/// until sources are removed or reordered.
+struct Sources {
+    /// Array of available asset sources.
+    /// Replaced with updated copy when sources change.
+    array: RwLock<SourceArray>,
+
+    /// If set, assets that are not found wait for new sources.
+    wait: AtomicBool,
+
+    /// Notifies waiting tasks about new sources and mode changes.
+    changed: watch::Sender<()>,
+
+    /// Serial number for the next added source.
+    next_serial: AtomicU64,
+
+    /// Incremented when indices of sources change.
+    replaced: AtomicU64,
+
+    /// Records requests to sources if enabled.
+    recorder: Option<Recorder>,
+
+    /// Clock for retries, deadlines and staggered queries.
+    clock: Arc<dyn Clock>,
+}
+
+/// Snapshot of asset sources.
+#[derive(Clone)]
+struct SourceArray {
+    sources: Arc<[Arc<dyn Source>]>,
+
+    /// Description of each source, in the same order.
+    slots: Arc<[SourceSlot]>,
+
+    /// How sources are queried.
+    strategy: SourceStrategy,
+}
+
+/// Description of the source in the loader.
+#[derive(Clone)]
+struct SourceSlot {
+    label: Option<Arc<str>>,
+    fingerprint: Option<Arc<str>>,
+
+    /// Unique among all sources ever added to the loader.
+    /// Zero is reserved for assets that do not come from sources.
+    serial: u64,
+
+    /// Inline assets of the source, fetched before the first load from the source.
+    inline: Arc<tokio::sync::OnceCell<Option<InlineAssets>>>,
+
+    /// Latest versions of assets reported by [`Source::update`].
+    /// Older inline data is not served.
+    updated: Arc<Mutex<HashMap<AssetId, u64>>>,
+}
+
+impl SourceSlot {
+    fn new(label: Option<Arc<str>>, fingerprint: Option<Arc<str>>, serial: u64) -> Self {
+        SourceSlot {
+            label,
+            fingerprint,
+            serial,
+            inline: Arc::new(tokio::sync::OnceCell::new()),
+            updated: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns inline assets of the source, fetching them on first call.
+    async fn inline(&self, source: &dyn Source) -> Option<&InlineAssets> {
+        self.inline
+            .get_or_init(|| async {
+                match source.bulk_hint().await {
+                    Ok(inline) => inline,
+                    Err(err) => {
+                        tracing::warn!("Failed to fetch inline assets of the source. {err:#}");
+                        None
+                    }
+                }
+            })
+            .await
+            .as_ref()
+    }
+
+    /// Returns inline data of the asset unless source reported newer version.
+    async fn inline_data(&self, source: &dyn Source, id: AssetId) -> Option<AssetData> {
+        let data = self.inline(source).await?.get(id)?;
+        match self.updated.lock().get(&id) {
+            Some(&version) if version > data.version => None,
+            _ => Some(data),
+        }
+    }
+
+    /// Records version of the asset reported by [`Source::update`].
+    fn updated(&self, id: AssetId, version: u64) {
+        let mut updated = self.updated.lock();
+        let latest = updated.entry(id).or_insert(version);
+        *latest = (*latest).max(version);
+    }
+}
+
+/// Counter of find and load tasks that are not finished yet.
+struct InFlight {
+    count: AtomicUsize,
+
+    /// Notified when counter drops to zero.
+    idle: Notify,
+}
+
+/// Counts a task as in flight until dropped.
+/// Owned by the task, so it is dropped even if the task panics.
+struct InFlightGuard {
+    in_flight: Arc<InFlight>,
+}
+
+impl InFlightGuard {
+    fn new(in_flight: &Arc<InFlight>) -> Self {
+        in_flight.count.fetch_add(1, Ordering::AcqRel);
+        InFlightGuard {
+            in_flight: in_flight.clone(),
+        }
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.in_flight.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.in_flight.idle.notify_waiters();
+        }
+    }
+}
+
+impl Sources {
+    fn snapshot(&self) -> SourceArray {
+        self.array.read().clone()
+    }
+
+    fn slot(&self, label: Option<Arc<str>>, fingerprint: Option<Arc<str>>) -> SourceSlot {
+        SourceSlot::new(
+            label,
+            fingerprint,
+            self.next_serial.fetch_add(1, Ordering::Relaxed),
+        )
+    }
+
+    /// Replaces sources array and notifies waiting tasks.
+    fn swap(&self, array: &mut SourceArray, new: SourceArray) {
+        let shifted = array.slots.len() > new.slots.len()
+            || array
+                .slots
+                .iter()
+                .zip(new.slots.iter())
+                .any(|(old, new)| old.serial != new.serial);
+        if shifted {
+            self.replaced.fetch_add(1, Ordering::AcqRel);
+        }
+        *array = new;
+        self.changed.send_replace(());
+    }
+
+    fn push(&self, source: Arc<dyn Source>) {
+        let slot = self.slot(None, None);
+        let mut array = self.array.write();
+        let new = SourceArray {
+            sources: array.sources.iter().cloned().chain(Some(source)).collect(),
+            slots: array.slots.iter().cloned().chain(Some(slot)).collect(),
+            strategy: array.strategy,
+        };
+        self.swap(&mut array, new);
+    }
+
+    /// Replaces source with the same label or appends it.
+    /// Returns `true` if source was replaced.
+    fn mount(&self, label: Arc<str>, source: Arc<dyn Source>) -> bool {
+        let slot = self.slot(Some(label.clone()), None);
+        let mut array = self.array.write();
+
+        let mut sources = array.sources.to_vec();
+        let mut slots = array.slots.to_vec();
+        let replaced = match slots.iter().position(|s| s.label == Some(label.clone())) {
+            Some(index) => {
+                sources[index] = source;
+                slots[index] = slot;
+                true
+            }
+            None => {
+                sources.push(source);
+                slots.push(slot);
+                false
+            }
+        };
+
+        let new = SourceArray {
+            sources: sources.into(),
+            slots: slots.into(),
+            strategy: array.strategy,
+        };
+        self.swap(&mut array, new);
+        replaced
+    }
+
+    /// Removes source with the label.
+    /// Returns `false` if there is no such source.
+    fn unmount(&self, label: &str) -> bool {
+        let mut array = self.array.write();
+        let Some(index) = array
+            .slots
+            .iter()
+            .position(|slot| slot.label.as_deref() == Some(label))
+        else {
+            return false;
+        };
+
+        let mut sources = array.sources.to_vec();
+        let mut slots = array.slots.to_vec();
+        sources.remove(index);
+        slots.remove(index);
+
+        let new = SourceArray {
+            sources: sources.into(),
+            slots: slots.into(),
+            strategy: array.strategy,
+        };
+        self.swap(&mut array, new);
+        true
+    }
+
+    /// Replaces labeled sources and settings with ones from config.
+    fn apply(&self, config: &LoaderConfig) -> Result<ConfigDiff, Error> {
+        let mut labels = HashSet::new();
+        for entry in &config.sources {
+            if !labels.insert(&*entry.label) {
+                return Err(Error::new(DuplicateSourceLabel {
+                    label: entry.label.clone(),
+                }));
+            }
+        }
+
+        let mut diff = ConfigDiff::default();
+        let mut array = self.array.write();
+
+        let mut sources = Vec::new();
+        let mut slots = Vec::new();
+        for entry in &config.sources {
+            let existing = array
+                .slots
+                .iter()
+                .position(|slot| slot.label.as_deref() == Some(&*entry.label));
+
+            match existing {
+                Some(index)
+                    if array.slots[index].fingerprint.as_deref() == Some(&*entry.fingerprint) =>
+                {
+                    sources.push(array.sources[index].clone());
+                    slots.push(array.slots[index].clone());
+                }
+                existing => {
+                    match existing {
+                        None => diff.mounted.push(entry.label.clone()),
+                        Some(_) => diff.replaced.push(entry.label.clone()),
+                    }
+                    sources.push(entry.source.clone());
+                    slots.push(
+                        self.slot(Some(entry.label.clone()), Some(entry.fingerprint.clone())),
+                    );
+                }
+            }
+        }
+
+        // Unlabeled sources are not managed by config and keep lower priority.
+        for (slot, source) in array.slots.iter().zip(array.sources.iter()) {
+            match &slot.label {
+                None => {
+                    sources.push(source.clone());
+                    slots.push(slot.clone());
+                }
+                Some(label) if !labels.contains(&**label) => {
+                    diff.unmounted.push(label.clone());
+                }
+                Some(_) => {}
+            }
+        }
+
+        let kept = |serials: &mut dyn Iterator<Item = u64>| -> Vec<u64> {
+            serials
+                .filter(|serial| {
+                    array.slots.iter().any(|slot| slot.serial == *serial)
+                        && slots.iter().any(|slot| slot.serial == *serial)
+                })
+                .collect()
+        };
+        diff.reordered = kept(&mut array.slots.iter().map(|slot| slot.serial))
+            != kept(&mut slots.iter().map(|slot| slot.serial));
+
+        let strategy = config.strategy.unwrap_or(array.strategy);
+        diff.strategy_changed = strategy != array.strategy;
+
+        if let Some(wait) = config.wait_for_sources {
+            diff.wait_for_sources_changed = self.wait.swap(wait, Ordering::AcqRel) != wait;
+        }
+
+        let new = SourceArray {
+            sources: sources.into(),
+            slots: slots.into(),
+            strategy,
+        };
+        self.swap(&mut array, new);
+        Ok(diff)
+    }
+
+    /// Returns `true` if source with specified serial was removed.
+    fn is_removed(&self, serial: u64) -> bool {
+        serial != 0
+            && !self
+                .array
+                .read()
+                .slots
+                .iter()
+                .any(|slot| slot.serial == serial)
+    }
+
+    fn set_wait(&self, wait: bool) {
+        self.wait.store(wait, Ordering::Release);
+
+        // Let waiting tasks re-check the mode.
+        self.changed.send_replace(());
+    }
+
+    /// Loads asset data from sources.
+    ///
+    /// If no source has the asset, waits as specified by `missing`
+    /// and tries sources again.
+    async fn load(
+        &self,
+        id: AssetId,
+        missing: &MissingWait,
+        abort: &AbortSignal,
+    ) -> Result<Option<Data>, Error> {
+        let mut start = 0;
+        loop {
+            // Subscribe before taking snapshot to not miss new sources.
+            let mut changed = self.changed.subscribe();
+            let replaced = self.replaced.load(Ordering::Acquire);
+            let sources = self.snapshot();
+
+            let recording = self.record(|| ReplayRequest::Load { id });
+            let result = load_asset(&sources, start, id, &*self.clock).await;
+            if let Some(recording) = recording {
+                recording.data(&result);
+            }
+
+            if let Some(data) = result? {
+                return Ok(Some(data));
+            }
+
+            match missing
+                .wait(self, &mut changed, sources.sources.len(), Some(abort))
+                .await
+            {
+                None => return Ok(None),
+                Some(next) => start = self.next_start(replaced, next),
+            }
+        }
+    }
+
+    /// Returns canonical form of the path reported by the first source that has one.
+    fn canonical_path<'a>(&self, path: &'a str) -> Option<Cow<'a, str>> {
+        self.array
+            .read()
+            .sources
+            .iter()
+            .find_map(|source| source.canonical_path(path))
+    }
+
+    /// Finds asset id in sources.
+    ///
+    /// If no source knows the path, waits as specified by `missing`
+    /// and tries sources again.
+    async fn find(&self, name: &str, path: &str, missing: &MissingWait) -> Option<AssetId> {
+        let mut start = 0;
+        loop {
+            // Subscribe before taking snapshot to not miss new sources.
+            let mut changed = self.changed.subscribe();
+            let replaced = self.replaced.load(Ordering::Acquire);
+            let sources = self.snapshot();
+
+            let recording = self.record(|| ReplayRequest::Find {
+                path: path.to_owned(),
+                asset: name.to_owned(),
+            });
+            let found = find_asset(
+                &sources.sources[start..],
+                name,
+                path,
+                sources.strategy,
+                &*self.clock,
+            )
+            .await;
+            if let Some(recording) = recording {
+                recording.found(found);
+            }
+
+            if let Some(id) = found {
+                return Some(id);
+            }
+
+            match missing
+                .wait(self, &mut changed, sources.sources.len(), None)
+                .await
+            {
+                None => return None,
+                Some(next) => start = self.next_start(replaced, next),
+            }
+        }
+    }
+
+    /// Starts recording of the request if recording is enabled.
+    fn record(&self, request: impl FnOnce() -> ReplayRequest) -> Option<Recording<'_>> {
+        self.recorder
+            .as_ref()
+            .map(|recorder| recorder.begin(request()))
+    }
+
+    /// Returns index of the first source to try next.
+    /// All sources are tried if indices changed since `replaced` was read.
+    fn next_start(&self, replaced: u64, next: usize) -> usize {
+        if self.replaced.load(Ordering::Acquire) == replaced {
+            next
+        } else {
+            0
+        }
+    }
+}
+
+/// Policy for assets that no source can provide.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingPolicy {
+    /// Asset is reported missing.
+    ///
+    /// If waiting for sources is enabled with [`Loader::set_wait_for_sources`],
+    /// waits for new sources first.
+    #[default]
+    Fail,
+
+    /// All sources are tried again after specified interval
+    /// or when [`Loader::notify_sources_changed`] is called.
+    ///
+    /// Requires `tokio` feature.
+    #[cfg(feature = "tokio")]
+    RetryAfter(Duration),
+
+    /// All sources are tried again each time
+    /// [`Loader::notify_sources_changed`] is called or a source is added.
+    WaitUntilFound,
+}
+
+/// Strategy of querying sources for assets.
+///
+/// Sources are ordered by priority in order they were added to the loader.
+/// With concurrent strategies higher priority source wins
+/// only if answers are ready simultaneously,
+/// otherwise first source that has the asset wins
+/// and queries to other sources are cancelled.
+///
+/// Error of a source is reported only when all sources before it
+/// don't have the asset and no source provided the asset yet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SourceStrategy {
+    /// Sources are queried one after another.
+    #[default]
+    Sequential,
+
+    /// All sources are queried concurrently.
+    RaceAll,
+
+    /// Sources are queried one after another,
+    /// but next source is queried without waiting for the previous one
+    /// if it didn't answer within `delay`.
+    ///
+    /// Requires `tokio` feature.
+    /// Runtime must have time driver enabled.
+    #[cfg(feature = "tokio")]
+    Staggered {
+        /// Time to wait for a source before querying the next one.
+        delay: Duration,
+    },
+}
+
+/// Options for [`Loader::load_with_options`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// What to do when no source has the asset.
+    pub missing: MissingPolicy,
+
+    /// Maximum time to wait for missing asset.
+    /// After it passes asset is reported missing.
+    ///
+    /// Requires `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub deadline: Option<Duration>,
+
+    /// Priority of the load.
+    /// Loads with priority at or above critical threshold
+    /// are not held by limit of unbuilt assets.
+    ///
+    /// See [`LoaderBuilder::set_unbuilt_limit`].
+    pub priority: i32,
+}
+
+impl LoadOptions {
+    /// Returns default [`LoadOptions`].
+    pub fn new() -> Self {
+        LoadOptions::default()
+    }
+
+    /// Sets policy for missing assets.
+    pub fn set_missing(&mut self, missing: MissingPolicy) -> &mut Self {
+        self.missing = missing;
+        self
+    }
+
+    /// Sets policy for missing assets.
+    pub fn with_missing(mut self, missing: MissingPolicy) -> Self {
+        self.set_missing(missing);
+        self
+    }
+
+    /// Sets maximum time to wait for missing asset.
+    #[cfg(feature = "tokio")]
+    pub fn set_deadline(&mut self, deadline: Duration) -> &mut Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets maximum time to wait for missing asset.
+    #[cfg(feature = "tokio")]
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.set_deadline(deadline);
+        self
+    }
+
+    /// Sets priority of the load.
+    pub fn set_priority(&mut self, priority: i32) -> &mut Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets priority of the load.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.set_priority(priority);
+        self
+    }
+
+    /// Returns `true` if missing assets should be looked up again.
+    fn retries_missing(&self) -> bool {
+        self.missing != MissingPolicy::Fail
+    }
+}
+
+/// Missing policy of a started load.
+#[derive(Clone, Copy)]
+struct MissingWait {
+    policy: MissingPolicy,
+    #[cfg(feature = "tokio")]
+    deadline: Option<std::time::Instant>,
+}
+
+impl MissingWait {
+    fn new(options: &LoadOptions, clock: &dyn Clock) -> Self {
+        #[cfg(not(feature = "tokio"))]
+        let _ = clock;
+
+        MissingWait {
+            policy: options.missing,
+            #[cfg(feature = "tokio")]
+            deadline: options.deadline.map(|deadline| clock.now() + deadline),
+        }
+    }
+
+    /// Waits before sources are tried again.
+    ///
+    /// Returns index of the first source to try
+    /// or `None` if asset should be reported missing or load is aborted.
+    async fn wait(
+        &self,
+        sources: &Sources,
+        changed: &mut watch::Receiver<()>,
+        checked: usize,
+        abort: Option<&AbortSignal>,
+    ) -> Option<usize> {
+        let wait = async {
+            match self.policy {
+                MissingPolicy::Fail => {
+                    // Only new sources may have the asset.
+                    if !sources.wait.load(Ordering::Acquire) {
+                        return None;
+                    }
+                    changed.changed().await.ok()?;
+                    Some(checked)
+                }
+                #[cfg(feature = "tokio")]
+                MissingPolicy::RetryAfter(interval) => {
+                    let _ = select(pin!(changed.changed()), sources.clock.sleep(interval)).await;
+                    Some(0)
+                }
+                MissingPolicy::WaitUntilFound => {
+                    changed.changed().await.ok()?;
+                    Some(0)
+                }
+            }
+        };
+
+        #[cfg(feature = "tokio")]
+        let wait = async {
+            match self.deadline {
+                None => wait.await,
+                Some(deadline) => {
+                    match select(pin!(wait), sources.clock.sleep_until(deadline)).await {
+                        Either::Left((next, _)) => next,
+                        Either::Right(_) => None,
+                    }
+                }
+            }
+        };
+
+        match abort {
+            None => wait.await,
+            Some(abort) => match select(pin!(wait), pin!(abort.aborted())).await {
+                Either::Left((next, _)) => next,
+                Either::Right(_) => None,
+            },
+        }
+    }
+}
+
+pub(crate) type DecodedState<A> = Option<<A as Asset>::Decoded>;
+
+/// Decoded asset state with erased type.
+/// Contains `DecodedState<A>`.
+pub(crate) type ErasedDecodedState = Arc<spin::Mutex<dyn Any + Send + Sync>>;
+
+/// Describes how assets of one kind are found and decoded.
+///
+/// Implemented for asset types via [`Typed`]
+/// and for dynamic asset descriptors.
+pub(crate) trait AssetKind: Send + Sync + 'static {
+    /// Future that will resolve into decoded asset state.
+    type Fut: Future<Output = Result<ErasedDecodedState, Error>> + Send + 'static;
+
+    /// Key of this kind in the loader caches.
+    fn key(&self) -> KindKey;
+
+    /// Asset name used to find assets in sources.
+    fn name(&self) -> &str;
+
+    /// Decode asset from bytes loaded from asset source.
+    fn decode(&self, bytes: Box<[u8]>, loader: &Loader) -> Self::Fut;
+
+    /// Returns `true` if raw data is shared
+    /// with other kinds loaded from the same artifact.
+    fn shares_data(&self) -> bool {
+        false
+    }
+
+    /// Decode asset from bytes shared with other kinds.
+    fn decode_shared(&self, bytes: &[u8], loader: &Loader) -> Self::Fut {
+        self.decode(bytes.into(), loader)
+    }
+
+    /// Returns `true` if decoded asset is stored in [`DecodeCache`].
+    fn caches_decoded(&self) -> bool {
+        false
+    }
+
+    /// Encodes decoded asset to store in [`DecodeCache`].
+    fn encode_decoded(&self, _decoded: &ErasedDecodedState) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Decodes asset from bytes stored in [`DecodeCache`].
+    fn decode_cached(&self, _bytes: &[u8]) -> Option<ErasedDecodedState> {
+        None
+    }
+
+    /// Returns decoder for reloading assets of this kind.
+    /// Kinds without one are not reloaded by [`Loader::poll_updates`].
+    fn reload_decode(&self) -> Option<ReloadDecode> {
+        None
+    }
+}
+
+/// Erases type of the decoded asset.
+fn erase_decoded<A: Asset>(
+    result: Result<A::Decoded, A::DecodeError>,
+) -> Result<ErasedDecodedState, Error> {
+    match result {
+        Ok(decoded) => Ok(Arc::new(spin::Mutex::new(Some(decoded))) as ErasedDecodedState),
+        Err(err) => Err(Error::new(err)),
+    }
+}
+
+type ErasedFut<A> = Map<
+    <A as Asset>::Fut,
+    fn(
+        Result<<A as Asset>::Decoded, <A as Asset>::DecodeError>,
+    ) -> Result<ErasedDecodedState, Error>,
+>;
+
+/// Asset kind of the asset type `A`.
+pub(crate) struct Typed<A> {
+    /// Format to decode asset from, overriding detection.
+    format: Option<AssetFormat>,
+    marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Typed<A> {
+    pub fn new(format: Option<AssetFormat>) -> Self {
+        Typed {
+            format,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A> AssetKind for Typed<A>
+where
+    A: Asset,
+{
+    type Fut = ErasedFut<A>;
+
+    #[inline]
+    fn key(&self) -> KindKey {
+        match self.format {
+            None => KindKey::of::<A>(),
+            Some(format) => {
+                // Assets decoded from requested format are cached separately.
+                let mut hasher = DefaultHasher::new();
+                format.hash(&mut hasher);
+                KindKey::dynamic(TypeId::of::<A>(), hasher.finish())
+            }
+        }
+    }
+
+    #[inline]
+    fn name(&self) -> &str {
+        A::asset_name().as_str()
+    }
+
+    #[inline]
+    fn decode(&self, bytes: Box<[u8]>, loader: &Loader) -> Self::Fut {
+        if self.format == loader.decoding_format {
+            return A::decode(bytes, loader).map(erase_decoded::<A>);
+        }
+
+        let loader = Loader {
+            decoding_format: self.format,
+            ..loader.clone()
+        };
+        A::decode(bytes, &loader).map(erase_decoded::<A>)
+    }
+
+    fn reload_decode(&self) -> Option<ReloadDecode> {
+        let format = self.format;
+        Some(Arc::new(move |bytes, loader| {
+            Box::pin(Typed::<A>::new(format).decode(bytes, loader))
+        }))
+    }
+}
+
+/// Asset kind of the asset type `A` that uses [`DecodeCache`].
+/// Shares cache entries with [`Typed`].
+pub(crate) struct Cached<A> {
+    marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Cached<A> {
+    pub fn new() -> Self {
+        Cached {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A> AssetKind for Cached<A>
+where
+    A: CacheableDecode,
+{
+    type Fut = ErasedFut<A>;
+
+    #[inline]
+    fn key(&self) -> KindKey {
+        KindKey::of::<A>()
+    }
+
+    #[inline]
+    fn name(&self) -> &str {
+        A::asset_name().as_str()
+    }
+
+    #[inline]
+    fn decode(&self, bytes: Box<[u8]>, loader: &Loader) -> Self::Fut {
+        A::decode(bytes, loader).map(erase_decoded::<A>)
+    }
+
+    #[inline]
+    fn caches_decoded(&self) -> bool {
+        true
+    }
+
+    fn encode_decoded(&self, decoded: &ErasedDecodedState) -> Option<Vec<u8>> {
+        let decoded = decoded.lock();
+        let decoded = decoded.downcast_ref::<DecodedState<A>>()?;
+        Some(A::encode_decoded(decoded.as_ref()?))
+    }
+
+    fn decode_cached(&self, bytes: &[u8]) -> Option<ErasedDecodedState> {
+        let decoded = A::decode_cached(bytes)?;
+        Some(Arc::new(spin::Mutex::new(Some(decoded))) as ErasedDecodedState)
+    }
+
+    fn reload_decode(&self) -> Option<ReloadDecode> {
+        Some(Arc::new(|bytes, loader| {
+            Box::pin(Cached::<A>::new().decode(bytes, loader))
+        }))
+    }
+}
+
+/// Asset kind of the named sub-asset of type `A`.
+pub(crate) struct Sub<A> {
+    sub: Arc<str>,
+    sub_hash: u64,
+    marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Sub<A> {
+    pub fn new(sub: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        sub.hash(&mut hasher);
+
+        Sub {
+            sub: sub.into(),
+            sub_hash: hasher.finish(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A> AssetKind for Sub<A>
+where
+    A: SubAsset,
+{
+    type Fut = ErasedFut<A>;
+
+    #[inline]
+    fn key(&self) -> KindKey {
+        KindKey::dynamic(TypeId::of::<A>(), self.sub_hash)
+    }
+
+    #[inline]
+    fn name(&self) -> &str {
+        A::asset_name().as_str()
+    }
+
+    #[inline]
+    fn decode(&self, bytes: Box<[u8]>, loader: &Loader) -> Self::Fut {
+        self.decode_shared(&bytes, loader)
+    }
+
+    #[inline]
+    fn shares_data(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn decode_shared(&self, bytes: &[u8], loader: &Loader) -> Self::Fut {
+        A::decode_sub(bytes, &self.sub, loader).map(erase_decoded::<A>)
+    }
+}
+
+pub(crate) enum AssetState {
+    /// Not yet loaded asset.
+    Unloaded {
+        wakers: WakeOnDrop,
+
+        /// Signal to abort the load.
+        abort: AbortSignal,
+    },
+    Loaded {
+        decoded: ErasedDecodedState,
+        metadata: AssetMetadata,
+        wakers: WakeOnDrop,
+
+        /// Signal of the load, tracks interest of handles in decoded asset.
+        abort: AbortSignal,
+
+        /// When a future started waiting for the asset to be built.
+        /// Tracked only with build wait warning.
+        #[cfg(feature = "tokio")]
+        build_wait: Option<BuildWait>,
+
+        /// Bytes counted against limit of unbuilt assets.
+        _reserved: Option<Reservation>,
+    },
+    Ready {
+        // Contains `A`
+        asset: Arc<dyn Any + Send + Sync>,
+        metadata: AssetMetadata,
+    },
+    /// All sources reported that asset is missing.
+    Missing,
+    Error {
+        error: Error,
+    },
+}
+
+pub(crate) enum PathState {
+    /// Not yet loaded asset.
+    Unloaded {
+        wakers: PathWakers,
+
+        /// Assets being decoded that requested this path.
+        dependents: SmallVec<[AssetId; 1]>,
+    },
+
+    /// Asset is loaded. Lookup main entry by this id.
+    Loaded { id: AssetId },
+
+    /// All sources reported that asset is missing.
+    Missing,
+}
+
+impl AssetState {
+    pub(crate) fn status(&self) -> EntryStatus {
+        match self {
+            AssetState::Unloaded { .. } => EntryStatus::Pending,
+            AssetState::Loaded { .. } => EntryStatus::Loaded,
+            AssetState::Ready { .. } => EntryStatus::Ready,
+            AssetState::Missing => EntryStatus::Missing,
+            AssetState::Error { .. } => EntryStatus::Error,
+        }
+    }
+
+    /// Returns `true` if asset is decoded but all handles were dropped without building it.
+    pub(crate) fn is_orphaned_decoded(&self) -> bool {
+        match self {
+            AssetState::Loaded { abort, .. } => abort.is_aborted() && !abort.has_interest(),
+            _ => false,
+        }
+    }
+
+    /// Returns number of tasks waiting on the entry.
+    #[cfg(feature = "debug-statemachine")]
+    fn waiters(&self) -> usize {
+        match self {
+            AssetState::Unloaded { wakers, .. } | AssetState::Loaded { wakers, .. } => {
+                wakers.vec.len()
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl PathState {
+    pub(crate) fn status(&self) -> EntryStatus {
+        match self {
+            PathState::Unloaded { .. } => EntryStatus::Pending,
+            PathState::Loaded { .. } => EntryStatus::Loaded,
+            PathState::Missing => EntryStatus::Missing,
+        }
+    }
+
+    /// Returns number of tasks waiting on the entry.
+    #[cfg(feature = "debug-statemachine")]
+    fn waiters(&self) -> usize {
+        match self {
+            PathState::Unloaded { wakers, .. } => wakers.vec.len(),
+            _ => 0,
+        }
+    }
+}
+
+impl Loader {
+    /// Returns [`LoaderBuilder`] instance
+    pub fn builder() -> LoaderBuilder {
+        LoaderBuilder::new()
+    }
+
+    /// Returns type of the asset registered with specified [`Asset::asset_name`].
+    ///
+    /// Returns `None` if no type with the name is registered.
+    /// See [`LoaderBuilder::register_asset`].
+    pub fn resolve_type_name(&self, name: &str) -> Option<TypeId> {
+        self.asset_names.resolve(name)
+    }
+
+    /// Returns `true` if unknown fields in asset infos are errors.
+    ///
+    /// Manual [`Asset::decode`] implementations that decode infos
+    /// should read it before returning the future.
+    ///
+    /// See [`LoaderBuilder::set_strict_descriptors`].
+    pub fn strict_descriptors(&self) -> bool {
+        self.strict_descriptors
+    }
+
+    /// Adds provided source to the loader.
+    ///
+    /// Loads waiting for sources are retried with the new source.
+    /// See [`Loader::set_wait_for_sources`].
+    pub fn push_source(&self, source: impl Source) {
+        self.sources.push(Arc::new(source));
+    }
+
+    /// Adds provided source to the loader.
+    ///
+    /// Loads waiting for sources are retried with the new source.
+    /// See [`Loader::set_wait_for_sources`].
+    pub fn push_source_dyn(&self, source: Box<dyn Source>) {
+        self.sources.push(Arc::from(source));
+    }
+
+    /// Sets whether loads of assets that no source can provide
+    /// should wait for new sources instead of failing as missing.
+    ///
+    /// When enabled, such loads stay pending until a source added with
+    /// [`Loader::push_source`] resolves them.
+    /// This allows requesting assets before all sources are registered.
+    ///
+    /// Disabling it makes all waiting loads complete as missing.
+    /// Disabled by default.
+    pub fn set_wait_for_sources(&self, wait: bool) {
+        self.sources.set_wait(wait);
+    }
+
+    /// Notifies loads of missing assets that sources may have new assets.
+    ///
+    /// Loads started with [`MissingPolicy::RetryAfter`] or
+    /// [`MissingPolicy::WaitUntilFound`] try all sources again.
+    pub fn notify_sources_changed(&self) {
+        self.sources.changed.send_replace(());
+    }
+
+    /// Adds provided source to the loader with a label.
+    /// Source with the same label is replaced in place, keeping its priority.
+    ///
+    /// Returns `true` if source was replaced.
+    /// Assets already loaded from replaced source stay cached,
+    /// see [`LoaderStats::orphaned_entries`].
+    pub fn mount(&self, label: impl Into<Arc<str>>, source: impl Source) -> bool {
+        self.sources.mount(label.into(), Arc::new(source))
+    }
+
+    /// Removes source with specified label.
+    ///
+    /// Returns `false` if there is no such source.
+    /// Assets already loaded from removed source stay cached,
+    /// see [`LoaderStats::orphaned_entries`].
+    pub fn unmount(&self, label: &str) -> bool {
+        self.sources.unmount(label)
+    }
+
+    /// Returns labels of sources in order of priority.
+    /// `None` for sources added without label.
+    pub fn source_labels(&self) -> Vec<Option<Arc<str>>> {
+        let array = self.sources.array.read();
+        array.slots.iter().map(|slot| slot.label.clone()).collect()
+    }
+
+    /// Applies new configuration of sources and settings.
+    ///
+    /// Labeled sources are mounted, replaced and unmounted to match the config
+    /// in one swap, so each load sees either old or new set of sources.
+    /// Sources added without label are kept after configured ones.
+    /// Settings not specified in the config are left unchanged.
+    ///
+    /// Cached assets are kept.
+    /// Assets loaded from removed or replaced sources are counted in
+    /// [`LoaderStats::orphaned_entries`] and flagged in [`EntrySummary::orphaned`].
+    ///
+    /// Returns error if config has duplicate labels, nothing is changed then.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let id = |value| AssetId::new(value).unwrap();
+    ///
+    /// let base = MemorySource::new();
+    /// base.insert_with_path("number", id(1), &br#"{ "value": 1 }"#[..]);
+    /// base.insert_with_path("other", id(2), &br#"{ "value": 2 }"#[..]);
+    ///
+    /// let v1 = MemorySource::new();
+    /// v1.insert_with_path("number", id(11), &br#"{ "value": 11 }"#[..]);
+    ///
+    /// let v2 = MemorySource::new();
+    /// v2.insert_with_path("number", id(21), &br#"{ "value": 21 }"#[..]);
+    /// v2.insert_with_path("other", id(22), &br#"{ "value": 22 }"#[..]);
+    ///
+    /// let loader = Loader::builder().with(base).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let value = |path: &'static str| {
+    ///             let loader = loader.clone();
+    ///             async move { loader.load::<Number, _>(path).await?.build(&mut ()).map(|n| n.value) }
+    ///         };
+    ///
+    ///         let diff = loader.apply_config(&LoaderConfig::new().with_source("cdn", "v1", v1.clone()))?;
+    ///         assert_eq!(diff.mounted, [std::sync::Arc::from("cdn")]);
+    ///         assert_eq!(value("number").await?, 11);
+    ///
+    ///         // Same fingerprint keeps mounted source.
+    ///         let diff = loader.apply_config(&LoaderConfig::new().with_source("cdn", "v1", v1))?;
+    ///         assert!(diff.is_empty());
+    ///
+    ///         let diff = loader.apply_config(&LoaderConfig::new().with_source("cdn", "v2", v2))?;
+    ///         assert_eq!(diff.replaced, [std::sync::Arc::from("cdn")]);
+    ///
+    ///         // Cached asset survives, new loads go to the new source.
+    ///         assert_eq!(value("number").await?, 11);
+    ///         assert_eq!(value("other").await?, 22);
+    ///         assert_eq!(loader.stats().orphaned_entries, 1);
+    ///
+    ///         let diff = loader.apply_config(&LoaderConfig::new())?;
+    ///         assert_eq!(diff.unmounted, [std::sync::Arc::from("cdn")]);
+    ///         assert_eq!(loader.source_labels(), [None]);
+    ///         assert_eq!(loader.stats().orphaned_entries, 2);
+    ///
+    ///         // Duplicate labels are rejected.
+    ///         let config = LoaderConfig::new()
+    ///             .with_source("cdn", "v1", MemorySource::new())
+    ///             .with_source("cdn", "v2", MemorySource::new());
+    ///         assert!(loader.apply_config(&config).err().unwrap().is::<DuplicateSourceLabel>());
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn apply_config(&self, config: &LoaderConfig) -> Result<ConfigDiff, Error> {
+        self.sources.apply(config)
+    }
+
+    /// Returns loader of new tenant with specified label.
+    ///
+    /// Tenant shares sources with this loader, but has its own caches.
+    /// See [`TenantLoader`].
+    pub fn tenant(&self, label: &str) -> TenantLoader {
+        let tenancy = &self.tenancy;
+        let root = match &self.tenant {
+            None => Loader {
+                decoding: None,
+                decoding_path: None,
+                decoding_properties: AssetProperties::new(),
+                decoding_format: None,
+                abort: None,
+                ..self.clone()
+            },
+            Some(tenant) => tenant.root.clone(),
+        };
+
+        TenantLoader::new(Loader {
+            asset_cache: tenancy.asset_shards(),
+            path_cache: tenancy.path_shards(),
+            dependencies: Arc::new(Mutex::new(HashMap::with_hasher(self.random_state.clone()))),
+            in_flight: Arc::new(InFlight {
+                count: AtomicUsize::new(0),
+                idle: Notify::new(),
+            }),
+            auto_unload: tenancy.auto_unload.map(|grace| {
+                Arc::new(AutoUnload::new(
+                    grace,
+                    self.random_state.clone(),
+                    self.clock.clone(),
+                ))
+            }),
+            sequence: Arc::new(AtomicU64::new(0)),
+            decode_stats: tenancy.decode_stats.then(|| Arc::new(DecodeStats::new())),
+            publish: Arc::new(RwLock::new(())),
+            prefetch: Arc::new(Prefetcher::new(
+                tenancy.max_prefetches,
+                tenancy.max_prefetch_bytes,
+            )),
+            failures: Arc::new(Failures::new(tenancy.max_failures)),
+            asset_kinds: Arc::new(AssetKinds::new(tenancy.type_conflicts)),
+            path_lookups: Arc::new(PathLookups::new()),
+            tenant: Some(Arc::new(Tenant {
+                label: label.into(),
+                root: root.clone(),
+                shared_fetches: AtomicUsize::new(0),
+            })),
+            ..root
+        })
+    }
+
+    /// Returns state of the tenant this loader belongs to.
+    pub(crate) fn tenant_state(&self) -> Option<&Tenant> {
+        self.tenant.as_deref()
+    }
+
+    /// Load asset that is shared by all tenants with specified key (path or id).
+    ///
+    /// Tenants load it with the loader that created them,
+    /// so it is loaded and built once.
+    /// Same as [`Loader::load`] for loader that is not a tenant.
+    pub fn load_shared<'a, A, K>(&self, key: K) -> AssetHandle<A>
+    where
+        A: TenantShared,
+        K: Into<Key<'a>>,
+    {
+        match &self.tenant {
+            None => self.load(key),
+            Some(tenant) => tenant.root.load(key),
+        }
+    }
+
+    /// Load asset with specified id and returns handle
+    /// that can be used to access assets once it is loaded.
+    ///
+    /// If asset was previously requested it will not be re-loaded,
+    /// but handle to shared state will be returned instead.
+    pub fn load_with_id<A: Asset>(&self, id: AssetId) -> AssetHandle<A> {
+        AssetHandle::new(self.load_kind_with_id(Typed::<A>::new(None), id, LoadOptions::default()))
+    }
+
+    /// Load asset with specified key (path or id) and returns handle
+    /// that can be used to access assets once it is loaded.
+    ///
+    /// If asset was previously requested it will not be re-loaded,
+    /// but handle to shared state will be returned instead,
+    /// even if first load was not successful or different format was used.
+    pub fn load<'a, A, K>(&self, key: K) -> AssetHandle<A>
+    where
+        A: Asset,
+        K: Into<Key<'a>>,
+    {
+        AssetHandle::new(self.load_kind(Typed::<A>::new(None), key.into(), LoadOptions::default()))
+    }
+
+    /// Load asset with specified key (path or id)
+    /// and blocks current thread until it is loaded and built with `()` builder.
+    ///
+    /// See [`Loader::load_build_blocking`].
+    pub fn load_blocking<'a, A, K>(&self, key: K) -> Result<A, Error>
+    where
+        A: AssetBuild<()>,
+        K: Into<Key<'a>>,
+    {
+        self.load_build_blocking(key, &mut ())
+    }
+
+    /// Load asset with specified key (path or id)
+    /// and blocks current thread until it is loaded and built with `builder`.
+    ///
+    /// Intended for threads without async runtime, like game loop or tools.
+    /// Caller does not need to set up a runtime.
+    /// Loading tasks run on the runtime this loader spawned its first task on,
+    /// even if that runtime runs on another thread,
+    /// or on a background runtime when loader was not used inside a runtime yet.
+    /// Without `tokio` feature loading tasks are driven by this method, as with [`Loader::pump`].
+    ///
+    /// # Deadlocks
+    ///
+    /// Do not call this method inside async context, `.await` the handle from [`Loader::load`] instead.
+    /// Loading tasks are spawned on the current runtime there
+    /// and blocking the only thread of single-threaded runtime deadlocks.
+    /// Same happens when loader runs tasks on single-threaded runtime
+    /// of the thread that is blocked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone)]
+    /// struct Texture {
+    ///     upload: usize,
+    /// }
+    ///
+    /// impl LeafAsset for Texture {
+    ///     type Decoded = ();
+    ///     type DecodeError = std::convert::Infallible;
+    ///     type BuildError = std::convert::Infallible;
+    ///
+    ///     fn asset_name() -> AssetName {
+    ///         AssetName::new("Texture")
+    ///     }
+    ///
+    ///     fn decode(_: Box<[u8]>) -> Result<(), std::convert::Infallible> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// struct Gpu {
+    ///     uploads: usize,
+    /// }
+    ///
+    /// impl AssetBuild<Gpu> for Texture {
+    ///     fn build(gpu: &mut Gpu, _: ()) -> Result<Texture, std::convert::Infallible> {
+    ///         gpu.uploads += 1;
+    ///         Ok(Texture { upload: gpu.uploads })
+    ///     }
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("grass", AssetId::new(1).unwrap(), &b"pixels"[..]);
+    /// let loader = Loader::builder().with(source.clone()).build();
+    ///
+    /// // No runtime on this thread.
+    /// let mut gpu = Gpu { uploads: 0 };
+    /// let grass = loader.load_build_blocking::<Texture, _, _>("grass", &mut gpu)?;
+    /// assert_eq!(grass.upload, 1);
+    ///
+    /// // Built asset is shared.
+    /// let grass = loader.load_build_blocking::<Texture, _, _>("grass", &mut gpu)?;
+    /// assert_eq!(grass.upload, 1);
+    ///
+    /// let err = loader.load_build_blocking::<Texture, _, _>("sand", &mut gpu).err().unwrap();
+    /// assert!(err.is_not_found());
+    ///
+    /// // Loader first used on a runtime of another thread keeps spawning tasks there.
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// let handle = runtime.handle().clone();
+    /// std::thread::spawn(move || runtime.block_on(std::future::pending::<()>()));
+    ///
+    /// let loader = Loader::builder().with(source).build();
+    /// let _pending = {
+    ///     let _runtime = handle.enter();
+    ///     loader.load::<Texture, _>("grass")
+    /// };
+    ///
+    /// let grass = loader.load_build_blocking::<Texture, _, _>("grass", &mut gpu)?;
+    /// assert_eq!(grass.upload, 2);
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn load_build_blocking<'a, A, B, K>(&self, key: K, builder: &mut B) -> Result<A, Error>
+    where
+        A: AssetBuild<B>,
+        K: Into<Key<'a>>,
+    {
+        let handle = self.load::<A, K>(key);
+
+        #[cfg(feature = "tokio")]
+        let loaded = crate::blocking::block_on(handle, |_| {});
+
+        #[cfg(not(feature = "tokio"))]
+        let loaded = crate::blocking::block_on(handle, |waker| {
+            self.tasks.wake_on_activity(waker);
+            self.pump();
+        });
+
+        loaded?.build(builder)
+    }
+
+    /// Load asset with specified key (path or id) and returns handle
+    /// that can be used to access assets once it is loaded.
+    ///
+    /// If no source has the asset, it is handled according to `options`.
+    /// With [`MissingPolicy::RetryAfter`] and [`MissingPolicy::WaitUntilFound`]
+    /// handle stays pending until asset is found or deadline passes,
+    /// and asset that was previously reported missing is looked up again.
+    ///
+    /// Same as [`Loader::load`] otherwise.
+    /// Options take effect only when loading starts,
+    /// if asset is already being loaded handle to shared state is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// # #[derive(Clone, Asset)]
+    /// # #[asset(name = "Number")]
+    /// # struct Number { value: u32 }
+    /// let source = MemorySource::new();
+    /// let loader = Loader::builder().with(source.clone()).build();
+    /// let options = LoadOptions::new().with_missing(MissingPolicy::WaitUntilFound);
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .enable_time()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let handle = loader.load_with_options::<Number, _>("number", options);
+    ///
+    ///         // Asset appears later, e.g. when importer finishes.
+    ///         tokio::task::yield_now().await;
+    ///         let id = AssetId::new(1).unwrap();
+    ///         source.insert_with_path("number", id, &br#"{ "value": 42 }"#[..]);
+    ///         loader.notify_sources_changed();
+    ///
+    ///         let mut number = handle.await?;
+    ///         assert_eq!(number.build(&mut ())?.value, 42);
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn load_with_options<'a, A, K>(&self, key: K, options: LoadOptions) -> AssetHandle<A>
+    where
+        A: Asset,
+        K: Into<Key<'a>>,
+    {
+        AssetHandle::new(self.load_kind(Typed::<A>::new(None), key.into(), options))
+    }
+
+    /// Loads first asset found with keys (paths or ids) in specified order.
+    /// Returns handle that resolves once asset is loaded.
+    ///
+    /// If asset with a key is missing, next key is tried.
+    /// Any other error is reported without trying remaining keys.
+    /// If all assets are missing, not found error of the last key is reported.
+    ///
+    /// Each key is loaded as with [`Loader::load`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Texture {
+    ///     name: String,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("logo", AssetId::new(1).unwrap(), &br#"{ "name": "default" }"#[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         // No localized textures, default is used.
+    ///         let mut logo = loader.load_first::<Texture, _>(["de/logo", "en/logo", "logo"]);
+    ///         let mut texture = (&mut logo).await?;
+    ///         assert_eq!(texture.build(&mut ())?.name, "default");
+    ///         assert_eq!(logo.resolved_key().unwrap().to_string(), "logo");
+    ///
+    ///         // All keys are missing.
+    ///         let result = loader.load_first::<Texture, _>(["de/logo", "en/logo"]).await;
+    ///         assert!(result.is_err_and(|err| err.is_not_found()));
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn load_first<'a, A, K>(&self, keys: impl IntoIterator<Item = K>) -> AssetFallback<A>
+    where
+        A: Asset,
+        K: Into<Key<'a>>,
+    {
+        AssetFallback::new(self, keys)
+    }
+
+    /// Load asset with specified key (path or id) and returns handle
+    /// that can be used to access assets once it is loaded.
+    ///
+    /// Decoded asset is looked up in [`DecodeCache`] configured with
+    /// [`LoaderBuilder::with_decode_cache`] before decoding,
+    /// keyed by asset name, id and version reported by the source.
+    /// Successfully decoded asset is stored in the cache.
+    /// Without decode cache it is the same as [`Loader::load`].
+    ///
+    /// Shares loaded assets with [`Loader::load`],
+    /// cache is used only if asset is not requested yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # use std::future::{ready, Ready};
+    /// # #[cfg(all(feature = "tokio", feature = "fs"))] {
+    /// static DECODED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// /// Asset that is expensive to decode.
+    /// #[derive(Clone)]
+    /// struct NavMesh {
+    ///     cells: u32,
+    /// }
+    ///
+    /// impl LeafAsset for NavMesh {
+    ///     type Decoded = u32;
+    ///     type DecodeError = std::num::ParseIntError;
+    ///     type BuildError = std::convert::Infallible;
+    ///
+    ///     fn asset_name() -> AssetName {
+    ///         AssetName::new("NavMesh")
+    ///     }
+    ///
+    ///     fn decode(bytes: Box<[u8]>) -> Result<u32, std::num::ParseIntError> {
+    ///         DECODED.fetch_add(1, Ordering::SeqCst);
+    ///         std::str::from_utf8(&bytes).unwrap().parse()
+    ///     }
+    /// }
+    ///
+    /// impl<B> AssetBuild<B> for NavMesh {
+    ///     fn build(_: &mut B, cells: u32) -> Result<NavMesh, std::convert::Infallible> {
+    ///         Ok(NavMesh { cells })
+    ///     }
+    /// }
+    ///
+    /// impl CacheableDecode for NavMesh {
+    ///     fn encode_decoded(cells: &u32) -> Vec<u8> {
+    ///         cells.to_le_bytes().to_vec()
+    ///     }
+    ///
+    ///     fn decode_cached(bytes: &[u8]) -> Option<u32> {
+    ///         Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    ///     }
+    /// }
+    ///
+    /// let cache_dir = std::env::temp_dir().join("argosy-decode-cache-example");
+    /// # let _ = std::fs::remove_dir_all(&cache_dir);
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("level", AssetId::new(1).unwrap(), &b"42"[..]);
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    ///
+    /// // Each loader simulates a run of the game.
+    /// let run = |expected: u32| {
+    ///     let loader = Loader::builder()
+    ///         .with(source.clone())
+    ///         .with_decode_cache(DirDecodeCache::new(&cache_dir))
+    ///         .build();
+    ///     runtime.block_on(async move {
+    ///         let mut navmesh = loader.load_cached::<NavMesh, _>("level").await?;
+    ///         assert_eq!(navmesh.build(&mut ())?.cells, expected);
+    ///         Ok::<_, Error>(())
+    ///     })
+    /// };
+    ///
+    /// run(42).unwrap();
+    /// assert_eq!(DECODED.load(Ordering::SeqCst), 1);
+    ///
+    /// // Second run skips decoding.
+    /// run(42).unwrap();
+    /// assert_eq!(DECODED.load(Ordering::SeqCst), 1);
+    ///
+    /// // New version of the asset is decoded again.
+    /// source.insert_with_path("level", AssetId::new(1).unwrap(), &b"43"[..]);
+    /// run(43).unwrap();
+    /// run(43).unwrap();
+    /// assert_eq!(DECODED.load(Ordering::SeqCst), 2);
+    /// # std::fs::remove_dir_all(&cache_dir).unwrap();
+    /// # }
+    /// ```
+    pub fn load_cached<'a, A, K>(&self, key: K) -> AssetHandle<A>
+    where
+        A: CacheableDecode,
+        K: Into<Key<'a>>,
+    {
+        AssetHandle::new(self.load_kind(Cached::<A>::new(), key.into(), LoadOptions::default()))
+    }
+
+    /// Load asset with path relative to the path of the asset being decoded
+    /// and returns handle that can be used to access assets once it is loaded.
+    ///
+    /// Intended to be called from [`Asset::decode`] with the loader it receives.
+    /// Relative path is joined to the directory of the path
+    /// with which decoded asset was requested.
+    /// `.` and `..` segments are resolved, path starting with `/` is resolved from the root.
+    /// Resolved path is loaded as with [`Loader::load`].
+    ///
+    /// `..` segments that go above the root are kept in the resolved path.
+    /// Whether such path is found depends on the source.
+    ///
+    /// If decoded asset was requested by id only, or loader is not decoding any asset,
+    /// returned handle resolves to [`NoParentPath`] error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// use futures::future::BoxFuture;
+    /// # #[cfg(feature = "tokio")] {
+    ///
+    /// #[derive(Clone, Asset)]
+    /// struct Texture {
+    ///     name: String,
+    /// }
+    ///
+    /// /// Material stores relative path to its texture.
+    /// #[derive(Clone)]
+    /// struct Material {
+    ///     albedo: String,
+    /// }
+    ///
+    /// impl Asset for Material {
+    ///     type Decoded = Material;
+    ///     type DecodeError = Error;
+    ///     type BuildError = std::convert::Infallible;
+    ///     type Fut = BoxFuture<'static, Result<Material, Error>>;
+    ///
+    ///     fn asset_name() -> AssetName {
+    ///         AssetName::new("Material")
+    ///     }
+    ///
+    ///     fn decode(bytes: Box<[u8]>, loader: &Loader) -> Self::Fut {
+    ///         let path = std::str::from_utf8(&bytes).unwrap();
+    ///         let texture = loader.load_relative::<Texture>(path);
+    ///         Box::pin(async move {
+    ///             let mut texture = texture.await?;
+    ///             let albedo = texture.build(&mut ())?.name.clone();
+    ///             Ok(Material { albedo })
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// impl<B> AssetBuild<B> for Material {
+    ///     fn build(_: &mut B, decoded: Material) -> Result<Material, std::convert::Infallible> {
+    ///         Ok(decoded)
+    ///     }
+    /// }
+    ///
+    /// let id = |id| AssetId::new(id).unwrap();
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("ship/albedo", id(1), &br#"{ "name": "ship" }"#[..]);
+    /// source.insert_with_path("shared/metal", id(2), &br#"{ "name": "metal" }"#[..]);
+    /// source.insert_with_path("ship/hull", id(3), &b"./albedo"[..]);
+    /// source.insert_with_path("ship/deck", id(4), &b"../shared/metal"[..]);
+    /// source.insert_with_path("ship/sail", id(5), &b"./albedo"[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let mut hull = loader.load::<Material, _>("ship/hull").await?;
+    ///         assert_eq!(hull.build(&mut ())?.albedo, "ship");
+    ///
+    ///         let mut deck = loader.load::<Material, _>("ship/deck").await?;
+    ///         assert_eq!(deck.build(&mut ())?.albedo, "metal");
+    ///
+    ///         // Requested by id, there is no path to resolve relative path against.
+    ///         let result = loader.load::<Material, _>(id(5)).await;
+    ///         assert!(result.is_err_and(|err| err.downcast_ref::<NoParentPath>().is_some()));
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn load_relative<A: Asset>(&self, relative: &str) -> AssetHandle<A> {
+        match &self.decoding_path {
+            None => {
+                let error = NoParentPath {
+                    path: relative.into(),
+                    parent: self.decoding,
+                };
+                AssetHandle::new(Handle {
+                    kind: Typed::<A>::new(None).key(),
+                    path: Some(relative.into()),
+                    id: None,
+                    retain: None,
+                    state: State::Error {
+                        error: Error::new(error),
+                    },
+                })
+            }
+            Some(parent) => {
+                let path = join_relative(parent, relative);
+                self.load(path.as_str())
+            }
+        }
+    }
+
+    /// Load asset with specified key (path or id) and returns handle
+    /// that can be used to access assets once it is loaded.
+    ///
+    /// Asset data is decoded from specified format
+    /// instead of detecting it from the data.
+    ///
+    /// Assets loaded with a format are cached separately
+    /// from assets loaded with [`Loader::load`] and with other formats,
+    /// so the same key can be decoded from different formats.
+    /// Format is passed to [`Asset::decode`] with [`Loader::decoding_format`].
+    ///
+    /// Same as [`Loader::load`] otherwise.
+    pub fn load_as<'a, A, K>(&self, key: K, format: AssetFormat) -> AssetHandle<A>
+    where
+        A: Asset,
+        K: Into<Key<'a>>,
+    {
+        AssetHandle::new(self.load_kind(
+            Typed::<A>::new(Some(format)),
+            key.into(),
+            LoadOptions::default(),
+        ))
+    }
+
+    /// Load named sub-asset of the artifact with specified key (path or id)
+    /// and returns handle that can be used to access assets once it is loaded.
+    ///
+    /// Each sub-asset is cached separately.
+    /// Raw data of the artifact is loaded once and shared
+    /// by sub-assets of the same artifact loaded shortly after each other.
+    ///
+    /// Same as [`Loader::load`] otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+    /// use futures::future::BoxFuture;
+    /// # #[cfg(feature = "tokio")] {
+    ///
+    /// /// Frame of a sprite sheet.
+    /// /// Sheet is stored as lines of `name=x` pairs.
+    /// #[derive(Clone)]
+    /// struct Frame {
+    ///     x: u32,
+    /// }
+    ///
+    /// impl Asset for Frame {
+    ///     type Decoded = Frame;
+    ///     type DecodeError = std::io::Error;
+    ///     type BuildError = std::convert::Infallible;
+    ///     type Fut = BoxFuture<'static, Result<Frame, std::io::Error>>;
+    ///
+    ///     fn asset_name() -> AssetName {
+    ///         AssetName::new("Frame")
+    ///     }
+    ///
+    ///     fn decode(bytes: Box<[u8]>, loader: &Loader) -> Self::Fut {
+    ///         // Whole sheet is the first frame.
+    ///         Frame::decode_sub(&bytes, "", loader)
+    ///     }
+    /// }
+    ///
+    /// impl SubAsset for Frame {
+    ///     fn decode_sub(bytes: &[u8], sub: &str, _loader: &Loader) -> Self::Fut {
+    ///         let frame = std::str::from_utf8(bytes)
+    ///             .unwrap()
+    ///             .lines()
+    ///             .filter_map(|line| line.split_once('='))
+    ///             .find(|(name, _)| sub.is_empty() || *name == sub)
+    ///             .map(|(_, x)| Frame { x: x.parse().unwrap() })
+    ///             .ok_or_else(|| std::io::ErrorKind::NotFound.into());
+    ///         Box::pin(async move { frame })
+    ///     }
+    /// }
+    ///
+    /// impl<B> AssetBuild<B> for Frame {
+    ///     fn build(_: &mut B, decoded: Frame) -> Result<Frame, std::convert::Infallible> {
+    ///         Ok(decoded)
+    ///     }
+    /// }
+    ///
+    /// /// Source that counts loads.
+    /// struct Counting(MemorySource, Arc<AtomicUsize>);
+    ///
+    /// impl Source for Counting {
+    ///     fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+    ///         self.0.find(path, asset)
+    ///     }
+    ///
+    ///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         self.1.fetch_add(1, Ordering::SeqCst);
+    ///         self.0.load(id)
+    ///     }
+    ///
+    ///     fn update<'a>(&'a self, id: AssetId, version: u64) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         self.0.update(id, version)
+    ///     }
+    /// }
+    ///
+    /// let loads = Arc::new(AtomicUsize::new(0));
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("sheet", AssetId::new(1).unwrap(), &b"idle=0\nwalk=16"[..]);
+    /// let loader = Loader::builder().with(Counting(source, loads.clone())).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let mut idle = loader.load_sub::<Frame, _>("sheet", "idle").await?;
+    ///         let mut walk = loader.load_sub::<Frame, _>("sheet", "walk").await?;
+    ///         assert_eq!(idle.build(&mut ())?.x, 0);
+    ///         assert_eq!(walk.build(&mut ())?.x, 16);
+    ///
+    ///         // Artifact is loaded from the source once.
+    ///         assert_eq!(loads.load(Ordering::SeqCst), 1);
+    ///
+    ///         // Each sub-asset is cached separately.
+    ///         let mut walk = loader.load_sub::<Frame, _>("sheet", "walk").poll_loaded().unwrap()?;
+    ///         assert_eq!(walk.build(&mut ())?.x, 16);
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn load_sub<'a, A, K>(&self, key: K, sub: &str) -> AssetHandle<A>
+    where
+        A: SubAsset,
+        K: Into<Key<'a>>,
+    {
+        AssetHandle::new(self.load_kind(Sub::<A>::new(sub), key.into(), LoadOptions::default()))
+    }
+
+    /// Load dynamic asset of the kind described by `descriptor`
+    /// with specified key (path or id) and returns handle
+    /// that can be used to access assets once it is loaded.
+    ///
+    /// Same as [`Loader::load`] except that decoding and building
+    /// is performed by closures in `descriptor`.
+    pub fn load_dyn<'a, K>(&self, descriptor: &DynAssetDescriptor, key: K) -> AssetHandle<DynValue>
+    where
+        K: Into<Key<'a>>,
+    {
+        AssetHandle::new(self.load_kind(descriptor.clone(), key.into(), LoadOptions::default()))
+    }
+
+    /// Cancels loading of asset with specified id.
+    ///
+    /// Raises [`AbortSignal`] of the load.
+    /// If decoding then fails, handles waiting for the asset
+    /// resolve with [`Cancelled`] error and next request for the asset
+    /// starts loading anew.
+    ///
+    /// Returns `false` if asset is not being loaded.
+    ///
+    /// [`Cancelled`]: crate::Cancelled
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # #[cfg(feature = "tokio")] {
+    /// static STARTED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// #[derive(Clone)]
+    /// struct Cutscene;
+    ///
+    /// impl Asset for Cutscene {
+    ///     type Decoded = Cutscene;
+    ///     type DecodeError = std::io::Error;
+    ///     type BuildError = std::convert::Infallible;
+    ///     type Fut = futures::future::BoxFuture<'static, Result<Cutscene, std::io::Error>>;
+    ///
+    ///     fn asset_name() -> AssetName {
+    ///         AssetName::new("Cutscene")
+    ///     }
+    ///
+    ///     fn decode(_: Box<[u8]>, loader: &Loader) -> Self::Fut {
+    ///         let abort = loader.abort_signal().unwrap();
+    ///         Box::pin(async move {
+    ///             STARTED.fetch_add(1, Ordering::Relaxed);
+    ///
+    ///             // Long decoding that checks the signal.
+    ///             while !abort.is_aborted() {
+    ///                 tokio::task::yield_now().await;
+    ///             }
+    ///             Err(std::io::ErrorKind::Interrupted.into())
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// impl<B> AssetBuild<B> for Cutscene {
+    ///     fn build(_: &mut B, decoded: Cutscene) -> Result<Cutscene, std::convert::Infallible> {
+    ///         Ok(decoded)
+    ///     }
+    /// }
+    ///
+    /// let id = AssetId::new(1).unwrap();
+    /// let source = MemorySource::new();
+    /// source.insert(id, &b"frames"[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let handle = loader.load::<Cutscene, _>(id);
+    ///         tokio::task::yield_now().await;
+    ///
+    ///         // Player skipped the cutscene.
+    ///         assert!(loader.cancel::<Cutscene>(id));
+    ///         assert!(handle.await.err().unwrap().is_cancelled());
+    ///
+    ///         // Next request starts loading anew.
+    ///         let _handle = loader.load::<Cutscene, _>(id);
+    ///         tokio::task::yield_now().await;
+    ///         assert_eq!(STARTED.load(Ordering::Relaxed), 2);
+    ///     });
+    /// # }
+    /// ```
+    pub fn cancel<A: Asset>(&self, id: AssetId) -> bool {
+        let kind_key = KindKey::of::<A>();
+        let key_hash = hash_id_key(kind_key, id, &self.random_state);
+
+        let shards_len = self.asset_cache.len();
+        let shard = &self.asset_cache[key_hash as usize % shards_len];
+
+        let locked_shard = shard.lock();
+        match locked_shard.get(key_hash, &mut |k| k.eq_key(kind_key, id)) {
+            Some((_, AssetState::Unloaded { abort, .. })) => {
+                abort.abort();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Records that asset with specified key (path or id) is likely to be needed,
+    /// with `score` telling how likely.
+    ///
+    /// Prefetch does not start right away if limits of the scheduler are reached,
+    /// see [`LoaderBuilder::set_prefetch_limits`].
+    /// Pending prefetches with highest scores are started as prefetches finish.
+    /// Prefetched assets are decoded but not built,
+    /// they are held in the cache until requested and built.
+    ///
+    /// Prefetching asset that is already pending changes its score.
+    /// Non-positive score cancels pending prefetch.
+    ///
+    /// Sources are hinted with [`Advice::WillNeed`] for assets prefetched by id,
+    /// see [`Source::advise`].
+    ///
+    /// [`Advice::WillNeed`]: crate::Advice::WillNeed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Tile {
+    ///     value: u32,
+    /// }
+    ///
+    /// let tile = |n| AssetId::new(n).unwrap();
+    /// let source = MemorySource::new();
+    /// source.insert(tile(1), &br#"{ "value": 1 }"#[..]);
+    /// source.insert(tile(2), &br#"{ "value": 2 }"#[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         // Tiles around the player, nearest scores highest.
+    ///         loader.prefetch::<Tile, _>(tile(1), 1.0);
+    ///         loader.prefetch::<Tile, _>(tile(2), 5.0);
+    ///
+    ///         let queue = loader.prefetch_queue();
+    ///         let ids: Vec<_> = queue.iter().map(|e| e.id).collect();
+    ///         assert_eq!(ids, [Some(tile(2)), Some(tile(1))]);
+    ///
+    ///         // Player reached the tile.
+    ///         let mut tile_2 = loader.load::<Tile, _>(tile(2)).await?;
+    ///         assert_eq!(tile_2.build(&mut ())?.value, 2);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn prefetch<'a, A, K>(&self, key: K, score: f32)
+    where
+        A: Asset,
+        K: Into<Key<'a>>,
+    {
+        let key = key.into();
+        if score > 0.0 {
+            if let Key::Id(id) = key {
+                for source in self.sources.snapshot().sources.iter() {
+                    source.advise(id, Advice::WillNeed);
+                }
+            }
+        }
+
+        self.prefetch.prefetch::<A>(key, score);
+        self.prefetch.schedule(self);
+    }
+
+    /// Changes score of the pending prefetch.
+    /// Non-positive score cancels it.
+    ///
+    /// Returns `false` if asset is not prefetched.
+    /// Prefetches that are started already are not affected.
+    ///
+    /// See [`Loader::prefetch`].
+    pub fn reprioritize<'a, A, K>(&self, key: K, score: f32) -> bool
+    where
+        A: Asset,
+        K: Into<Key<'a>>,
+    {
+        let found = self.prefetch.reprioritize::<A>(key.into(), score);
+        self.prefetch.schedule(self);
+        found
+    }
+
+    /// Returns prefetches that are pending, running
+    /// or loaded and not built yet, ordered by score.
+    ///
+    /// See [`Loader::prefetch`].
+    pub fn prefetch_queue(&self) -> Vec<PrefetchEntry> {
+        self.prefetch.queue()
+    }
+
+    /// Starts loading of the asset without a handle, e.g. to warm the cache.
+    ///
+    /// Preload is a prefetch with a score lower than any other,
+    /// it does nothing if asset is prefetched already.
+    /// See [`Loader::prefetch`].
+    /// Failure is recorded for [`Loader::take_failures`].
+    pub fn preload<'a, A, K>(&self, key: K)
+    where
+        A: Asset,
+        K: Into<Key<'a>>,
+    {
+        self.prefetch.preload::<A>(key.into());
+        self.prefetch.schedule(self);
+    }
+
+    /// Loads assets with specified keys (paths or ids) to warm the cache,
+    /// resolving with outcome of each key once all are loaded or `cancel` is cancelled.
+    ///
+    /// Unlike [`Loader::preload`] loads start right away, bypassing the prefetch scheduler.
+    /// Loaded assets are decoded but not built, they are held in the cache until requested.
+    ///
+    /// When `cancel` is cancelled, batch resolves promptly
+    /// and keys that are not loaded yet are reported as [`BatchItem::Cancelled`].
+    /// Their loads are aborted as with [`Loader::cancel`],
+    /// unless other handles wait for them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let id = |value| AssetId::new(value).unwrap();
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert(id(1), &br#"{ "value": 1 }"#[..]);
+    /// source.insert(id(2), &br#"{ "value": 2 }"#[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let outcome = loader
+    ///             .prewarm::<Number, _>([id(1), id(2), id(3)], &CancelToken::new())
+    ///             .await;
+    ///         assert_eq!(outcome.succeeded(), 2);
+    ///         assert!(matches!(&outcome.items[2], BatchItem::Failed(err) if err.is_not_found()));
+    ///
+    ///         // Cancelled batch does not start loading.
+    ///         let cancel = CancelToken::new();
+    ///         cancel.cancel();
+    ///         let outcome = loader.prewarm::<Number, _>([id(4)], &cancel).await;
+    ///         assert_eq!(outcome.cancelled(), 1);
+    ///     });
+    /// # }
+    /// ```
+    pub async fn prewarm<'a, A, K>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+        cancel: &CancelToken,
+    ) -> BatchOutcome
+    where
+        A: Asset,
+        K: Into<Key<'a>>,
+    {
+        let keys: Vec<Key<'a>> = keys.into_iter().map(Into::into).collect();
+        let mut items = vec![BatchItem::Cancelled; keys.len()];
+        if cancel.is_cancelled() {
+            return BatchOutcome { items };
+        }
+
+        let mut pending: futures::stream::FuturesUnordered<_> = keys
+            .into_iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let handle = self.load::<A, _>(key);
+                handle.map(move |result| (index, result.map(drop)))
+            })
+            .collect();
+
+        let mut cancelled = pin!(cancel.cancelled());
+        loop {
+            match select(pending.next(), cancelled.as_mut()).await {
+                Either::Left((Some((index, result)), _)) => {
+                    items[index] = match result {
+                        Ok(()) => BatchItem::Done,
+                        Err(err) => BatchItem::Failed(err),
+                    };
+                }
+                Either::Left((None, _)) => break,
+                Either::Right(((), next)) => {
+                    drop(next);
+
+                    // Collect loads that are finished already.
+                    while let Some(Some((index, result))) = pending.next().now_or_never() {
+                        items[index] = match result {
+                            Ok(()) => BatchItem::Done,
+                            Err(err) => BatchItem::Failed(err),
+                        };
+                    }
+                    break;
+                }
+            }
+        }
+
+        // Dropping handles aborts loads no one else waits for.
+        drop(pending);
+        BatchOutcome { items }
+    }
+
+    /// Takes failures of loads that no handle observed, oldest first.
+    ///
+    /// Failures of preloads and prefetches are recorded,
+    /// as well as failures of loads whose handles did not wait for them.
+    /// Number of kept failures is limited with [`LoaderBuilder::set_max_failures`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let id = |value| AssetId::new(value).unwrap();
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert(id(1), &br#"{ "value": 1 }"#[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         loader.preload::<Number, _>(id(1));
+    ///         loader.preload::<Number, _>(id(2));
+    ///         loader.preload::<Number, _>(id(2));
+    ///         loader.wait_idle().await;
+    ///
+    ///         let failures = loader.take_failures();
+    ///         assert_eq!(failures.len(), 1);
+    ///         assert_eq!(failures[0].name, "Number");
+    ///         assert_eq!(failures[0].id, Some(id(2)));
+    ///         assert!(failures[0].error.is::<NotFound>());
+    ///         assert!(loader.take_failures().is_empty());
+    ///
+    ///         // Failure is observed by the handle.
+    ///         assert!(loader.load::<Number, _>(id(3)).await.is_err());
+    ///         assert!(loader.load::<Number, _>("missing").await.is_err());
+    ///         loader.wait_idle().await;
+    ///         assert!(loader.take_failures().is_empty());
+    ///
+    ///         // Handle is dropped without waiting.
+    ///         drop(loader.load::<Number, _>("unknown"));
+    ///         loader.wait_idle().await;
+    ///
+    ///         let failures = loader.take_failures();
+    ///         assert_eq!(failures.len(), 1);
+    ///         assert_eq!(failures[0].path.as_deref(), Some("unknown"));
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn take_failures(&self) -> Vec<FailureRecord> {
+        self.failures.take()
+    }
+
+    /// Removes all assets and paths cached as missing or failed to load,
+    /// so that next request for them loads them anew.
+    ///
+    /// Handles that did not observe the failure yet
+    /// resolve with [`Cancelled`] error.
+    ///
+    /// [`Cancelled`]: crate::Cancelled
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// # #[derive(Clone, Asset)]
+    /// # struct Number { value: u32 }
+    /// let source = MemorySource::new();
+    /// let loader = Loader::builder().with(source.clone()).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         assert!(loader.load::<Number, _>("number").await.is_err());
+    ///
+    ///         // Missing asset is cached.
+    ///         source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 7 }"#[..]);
+    ///         assert!(loader.load::<Number, _>("number").await.is_err());
+    ///
+    ///         loader.forget_failures();
+    ///         let mut number = loader.load::<Number, _>("number").await?;
+    ///         assert_eq!(number.build(&mut ())?.value, 7);
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn forget_failures(&self) {
+        for shard in self.asset_cache.iter() {
+            shard.lock().retain(&mut |_, state| {
+                !matches!(state, AssetState::Missing | AssetState::Error { .. })
+            });
+        }
+
+        for shard in self.path_cache.iter() {
+            shard
+                .lock()
+                .retain(&mut |_, state| !matches!(state, PathState::Missing));
+        }
+
+        self.path_lookups.forget_missing();
+    }
+
+    /// Drops decoded assets that were never built
+    /// and have no handles, drivers or [`LoadedAsset`]s left.
+    /// Next request for them loads them anew.
+    ///
+    /// Returns number of dropped assets.
+    ///
+    /// [`LoadedAsset`]: crate::LoadedAsset
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # #[cfg(feature = "tokio")] {
+    ///
+    /// static DROPPED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// struct Mesh(Vec<u8>);
+    ///
+    /// impl Drop for Mesh {
+    ///     fn drop(&mut self) {
+    ///         DROPPED.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// #[derive(Clone)]
+    /// struct Model;
+    ///
+    /// impl LeafAsset for Model {
+    ///     type Decoded = Mesh;
+    ///     type DecodeError = std::convert::Infallible;
+    ///     type BuildError = std::convert::Infallible;
+    ///
+    ///     fn asset_name() -> AssetName {
+    ///         AssetName::new("Model")
+    ///     }
+    ///
+    ///     fn decode(bytes: Box<[u8]>) -> Result<Mesh, std::convert::Infallible> {
+    ///         Ok(Mesh(bytes.into()))
+    ///     }
+    /// }
+    ///
+    /// impl AssetBuild<()> for Model {
+    ///     fn build(_: &mut (), _: Mesh) -> Result<Model, std::convert::Infallible> {
+    ///         Ok(Model)
+    ///     }
+    /// }
+    ///
+    /// let id = AssetId::new(1).unwrap();
+    /// let source = MemorySource::new();
+    /// source.insert(id, &b"vertices"[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let driver = loader.load::<Model, _>(id).driver::<SimpleDrive<()>>();
+    ///         loader.wait_idle().await;
+    ///
+    ///         // The system that would build the model is gone.
+    ///         drop(driver);
+    ///         assert_eq!(loader.stats().orphaned_decoded, 1);
+    ///
+    ///         assert_eq!(loader.drop_orphaned_decoded(), 1);
+    ///         assert_eq!(DROPPED.load(Ordering::Relaxed), 1);
+    ///         assert_eq!(loader.stats().orphaned_decoded, 0);
+    ///
+    ///         // Asset is loaded anew.
+    ///         let mut model = loader.load::<Model, _>(id).await?;
+    ///         model.build(&mut ())?;
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn drop_orphaned_decoded(&self) -> usize {
+        // Decoded assets may hold handles to their dependencies.
+        // Drop them after locks are released.
+        let mut removed = Vec::new();
+        for shard in self.asset_cache.iter() {
+            shard.lock().retain(&mut |_, state| {
+                if !state.is_orphaned_decoded() {
+                    return true;
+                }
+                removed.push(std::mem::replace(state, AssetState::Missing));
+                false
+            });
+        }
+        removed.len()
+    }
+
+    /// Removes asset with specified id of any type
+    /// if it is cached as missing or failed to load,
+    /// so that next request for it loads it anew.
+    ///
+    /// Handles that did not observe the failure yet
+    /// resolve with [`Cancelled`] error.
+    ///
+    /// Returns `false` if asset is not cached as missing or failed.
+    ///
+    /// [`Cancelled`]: crate::Cancelled
+    pub fn retry(&self, id: AssetId) -> bool {
+        let mut removed = false;
+        for shard in self.asset_cache.iter() {
+            shard.lock().retain(&mut |key, state| {
+                let failed =
+                    key.id == id && matches!(state, AssetState::Missing | AssetState::Error { .. });
+                removed |= failed;
+                !failed
+            });
+        }
+        removed
+    }
+
+    /// Starts publication of built assets that must become visible at once.
+    ///
+    /// Use it to swap related assets, e.g. material and its shader,
+    /// so that readers never observe new version of one with old version of another.
+    /// Readers that access several assets should do so within [`Loader::read_consistent`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// #[derive(Clone, Asset)]
+    /// struct Material {
+    ///     version: u32,
+    /// }
+    ///
+    /// #[derive(Clone, Asset)]
+    /// struct Shader {
+    ///     version: u32,
+    /// }
+    ///
+    /// let material = AssetId::new(1).unwrap();
+    /// let shader = AssetId::new(2).unwrap();
+    ///
+    /// let loader = Loader::builder().with(MemorySource::new()).build();
+    ///
+    /// let publish = |version| {
+    ///     let mut tx = loader.begin_publish();
+    ///     tx.stage(material, Material { version });
+    ///     tx.stage(shader, Shader { version });
+    ///     tx.commit();
+    /// };
+    /// publish(0);
+    ///
+    /// // Handle that observed old value keeps it.
+    /// let mut old = loader.load::<Material, _>(material);
+    /// assert_eq!(old.poll_ready().unwrap().unwrap().version, 0);
+    ///
+    /// publish(1);
+    /// let versions = loader.read_consistent(|| {
+    ///     let material = loader.load::<Material, _>(material).poll_ready().unwrap().unwrap();
+    ///     let shader = loader.load::<Shader, _>(shader).poll_ready().unwrap().unwrap();
+    ///     (material.version, shader.version)
+    /// });
+    /// assert_eq!(versions, (1, 1));
+    /// assert_eq!(old.poll_ready().unwrap().unwrap().version, 0);
+    /// ```
+    pub fn begin_publish(&self) -> Publish<'_> {
+        Publish::new(self)
+    }
+
+    /// Calls `f` so that assets published with [`Loader::begin_publish`]
+    /// are either all observed or none of them are.
+    ///
+    /// Publication waits until `f` returns,
+    /// so it should not wait for assets to load.
+    /// Committing publication within `f` deadlocks.
+    pub fn read_consistent<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.publish.read_recursive();
+        f()
+    }
+
+    /// Enables cascade rebuilding of assets of type `A` with builder `B`.
+    ///
+    /// When assets reload, loaded assets that depend on them,
+    /// as recorded by [`Loader::dependencies`], are marked stale.
+    /// Stale assets of type `A` are then decoded from fresh data
+    /// and built with `builder` in a spawned task.
+    /// Rebuilt value replaces the cached one.
+    /// Handles that already resolved to the old value keep it
+    /// and report [`AssetHandle::is_stale`].
+    ///
+    /// Each asset is rebuilt once per reload, after its stale dependencies.
+    /// Assets that depend on each other in a cycle are rebuilt in arbitrary order
+    /// and ones rebuilt before their dependencies stay stale.
+    ///
+    /// Assets reload when published with [`Loader::begin_publish`]
+    /// or when [`Loader::update_tick`] finds new data of an asset of a type with cascade enabled.
+    /// Rebuilt assets are reported by [`Loader::wait_idle`] only after the cascade completes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use std::sync::{Arc, Mutex, atomic::{AtomicUsize, Ordering}};
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Foo {
+    ///     value: u32,
+    /// }
+    ///
+    /// #[derive(Clone, Asset)]
+    /// struct WithFoo {
+    ///     #[asset(external)]
+    ///     foo: Foo,
+    /// }
+    ///
+    /// // Depends on `Foo` through both fields.
+    /// #[derive(Clone, Asset)]
+    /// struct Pair {
+    ///     #[asset(external)]
+    ///     left: WithFoo,
+    ///
+    ///     #[asset(external)]
+    ///     right: WithFoo,
+    /// }
+    ///
+    /// let id = |value| AssetId::new(value).unwrap();
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert(id(1), &br#"{ "value": 1 }"#[..]);
+    /// source.insert(id(2), &br#"{ "foo": 1 }"#[..]);
+    /// source.insert(id(3), &br#"{ "foo": 1 }"#[..]);
+    /// source.insert(id(4), &br#"{ "left": 2, "right": 3 }"#[..]);
+    ///
+    /// // Counts rebuilt pairs.
+    /// let rebuilt = Arc::new(AtomicUsize::new(0));
+    /// let counter = rebuilt.clone();
+    /// let loader = Loader::builder()
+    ///     .with(source.clone())
+    ///     .with_registered_reload_hook::<Pair, _>(move |_, _| {
+    ///         counter.fetch_add(1, Ordering::SeqCst);
+    ///         ReloadAction::Replace
+    ///     })
+    ///     .build();
+    ///
+    /// let builder = Arc::new(Mutex::new(()));
+    /// loader.enable_cascade::<Foo, ()>(builder.clone());
+    /// loader.enable_cascade::<WithFoo, ()>(builder.clone());
+    /// loader.enable_cascade::<Pair, ()>(builder);
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let mut pair = loader.load::<Pair, _>(id(4));
+    ///         let old = pair.clone().await?.build(&mut ())?;
+    ///         assert_eq!((old.left.foo.value, old.right.foo.value), (1, 1));
+    ///         assert_eq!(pair.poll_ready().unwrap()?.left.foo.value, 1);
+    ///         assert!(!pair.is_stale());
+    ///
+    ///         source.insert(id(1), &br#"{ "value": 2 }"#[..]);
+    ///         assert_eq!(loader.update_tick().await, [id(1)]);
+    ///         loader.wait_idle().await;
+    ///
+    ///         // Handle keeps the old value.
+    ///         assert!(pair.is_stale());
+    ///         assert_eq!(pair.poll_ready().unwrap()?.left.foo.value, 1);
+    ///
+    ///         let mut pair = loader.load::<Pair, _>(id(4));
+    ///         let new = pair.poll_ready().unwrap()?;
+    ///         assert_eq!((new.left.foo.value, new.right.foo.value), (2, 2));
+    ///         assert!(!pair.is_stale());
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    ///
+    /// // Pair is rebuilt once, after both of its dependencies.
+    /// assert_eq!(rebuilt.load(Ordering::SeqCst), 1);
+    ///
+    /// // Without cascade, dependent assets only become stale.
+    /// let loader = Loader::builder().with(source.clone()).build();
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let mut with_foo = loader.load::<WithFoo, _>(id(2)).await?;
+    ///         assert_eq!(with_foo.build(&mut ())?.foo.value, 2);
+    ///
+    ///         let mut tx = loader.begin_publish();
+    ///         tx.stage(id(1), Foo { value: 3 });
+    ///         tx.commit();
+    ///
+    ///         assert!(with_foo.is_stale());
+    ///         let mut with_foo = loader.load::<WithFoo, _>(id(2)).await?;
+    ///         assert!(with_foo.is_stale());
+    ///         assert_eq!(with_foo.build(&mut ())?.foo.value, 2);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn enable_cascade<A, B>(&self, builder: Arc<std::sync::Mutex<B>>)
+    where
+        A: AssetBuild<B>,
+        B: Send + 'static,
+    {
+        self.cascades.enable::<A, B>(builder);
+    }
+
+    pub(crate) fn random_state(&self) -> &RandomState {
+        &self.random_state
+    }
+
+    /// Replaces cache entries with published assets.
+    ///
+    /// Involved shards are locked in order of their indices
+    /// and released only after all entries are replaced.
+    /// If `cascade` is set, assets that depend on replaced ones are marked stale
+    /// and rebuilt if enabled with [`Loader::enable_cascade`].
+    pub(crate) fn commit_published(&self, staged: Vec<Staged>, cascade: bool) {
+        // Hooks are called before shards are locked.
+        let reloads = self.call_reload_hooks(&staged);
+
+        let shards_len = self.asset_cache.len();
+
+        let mut indices: Vec<usize> = staged
+            .iter()
+            .map(|staged| staged.key_hash as usize % shards_len)
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+
+        let mut events = Vec::new();
+        let mut reloaded = Vec::new();
+
+        let _guard = self.publish.write();
+        let mut locked_shards: Vec<_> = indices
+            .iter()
+            .map(|&index| self.asset_cache[index].lock())
+            .collect();
+
+        for (staged, reload) in staged.into_iter().zip(reloads) {
+            let index = indices
+                .binary_search(&(staged.key_hash as usize % shards_len))
+                .unwrap();
+            let locked_shard = &mut locked_shards[index];
+
+            let Staged {
+                kind,
+                id,
+                key_hash,
+                mut asset,
+                version,
+            } = staged;
+
+            match locked_shard.entry(key_hash, |k| k.eq_key(kind, id)) {
+                Entry::Occupied(mut entry) => {
+                    let mut metadata = match entry.get() {
+                        AssetState::Loaded { metadata, .. }
+                        | AssetState::Ready { metadata, .. } => {
+                            reloaded.push(id);
+                            AssetMetadata {
+                                stale: StaleFlag::default(),
+                                ..metadata.clone()
+                            }
+                        }
+                        AssetState::Unloaded { abort, .. } => {
+                            abort.abort();
+                            published_metadata()
+                        }
+                        AssetState::Missing | AssetState::Error { .. } => published_metadata(),
+                    };
+
+                    // Decision of the hook is stale if old value was replaced meanwhile.
+                    let reload = reload.filter(|reload| {
+                        matches!(entry.get(), AssetState::Ready { asset, .. } if Arc::ptr_eq(asset, &reload.old))
+                    });
+
+                    if let Some(reload) = reload {
+                        let outcome = match reload.action {
+                            ErasedAction::Replace => ReloadOutcome::Replaced,
+                            ErasedAction::KeepOld => ReloadOutcome::KeptOld,
+                            ErasedAction::Custom(custom) => {
+                                asset = custom;
+                                ReloadOutcome::Custom
+                            }
+                        };
+
+                        let old_version = metadata.version;
+                        if outcome != ReloadOutcome::KeptOld {
+                            metadata.version = version.unwrap_or(old_version);
+                        }
+
+                        events.push(ReloadEvent {
+                            id,
+                            name: reload.name,
+                            old_version,
+                            new_version: metadata.version,
+                            outcome,
+                        });
+
+                        if outcome == ReloadOutcome::KeptOld {
+                            reloaded.retain(|reloaded| *reloaded != id);
+                            continue;
+                        }
+                    } else if let Some(version) = version {
+                        metadata.version = version;
+                    }
+
+                    debug_check(
+                        EntryKind::Asset,
+                        Some(entry.get().status()),
+                        Event::Publish,
+                        Some(EntryStatus::Ready),
+                    );
+                    *entry.get_mut() = AssetState::Ready { asset, metadata };
+                }
+                Entry::Vacant(entry) => {
+                    debug_check(
+                        EntryKind::Asset,
+                        None,
+                        Event::Publish,
+                        Some(EntryStatus::Ready),
+                    );
+                    let asset_key = TypeKey::new(kind, id).with_sequence(self.next_sequence());
+                    let mut metadata = published_metadata();
+                    metadata.version = version.unwrap_or(0);
+                    entry.insert(asset_key, AssetState::Ready { asset, metadata });
+                }
+            }
+        }
+
+        drop(locked_shards);
+        drop(_guard);
+
+        for event in &events {
+            self.reload.report(event);
+        }
+
+        if cascade && !reloaded.is_empty() {
+            self.cascade_reloaded(&reloaded);
+        }
+    }
+
+    /// Marks assets that depend on reloaded ones stale
+    /// and spawns task that rebuilds ones with cascade enabled.
+    fn cascade_reloaded(&self, reloaded: &[AssetId]) {
+        let stale = dependents(&self.dependencies.lock(), reloaded);
+        if stale.is_empty() {
+            return;
+        }
+
+        let targets = self.mark_stale(&stale);
+        if targets.is_empty() {
+            return;
+        }
+
+        let loader = self.detached();
+        self.spawn_in_flight(async move {
+            loader.rebuild_stale(&stale, &targets).await;
+        });
+    }
+
+    /// Marks loaded assets with specified ids stale.
+    /// Returns ones with cascade enabled.
+    fn mark_stale(&self, ids: &HashSet<AssetId>) -> Vec<(KindKey, AssetId)> {
+        self.cascade_targets(ids, |metadata| metadata.stale.set())
+    }
+
+    /// Calls `f` with metadata of loaded assets with specified ids.
+    /// Returns ones with cascade enabled.
+    fn cascade_targets(
+        &self,
+        ids: &HashSet<AssetId>,
+        mut f: impl FnMut(&AssetMetadata),
+    ) -> Vec<(KindKey, AssetId)> {
+        let mut targets = Vec::new();
+        for shard in self.asset_cache.iter() {
+            shard.lock().retain(&mut |key, state| {
+                if !ids.contains(&key.id) {
+                    return true;
+                }
+                if let AssetState::Loaded { metadata, .. } | AssetState::Ready { metadata, .. } =
+                    state
+                {
+                    f(metadata);
+                    if self.cascades.get(key.kind).is_some() {
+                        targets.push((key.kind, key.id));
+                    }
+                }
+                true
+            });
+        }
+        targets
+    }
+
+    /// Spawns task that rebuilds changed assets with cascade enabled
+    /// and then assets that depend on them.
+    fn reload_changed(&self, changed: &[AssetId]) {
+        let roots = self.cascade_targets(&changed.iter().copied().collect(), |_| {});
+        if roots.is_empty() {
+            return;
+        }
+
+        let loader = self.detached();
+        self.spawn_in_flight(async move {
+            let mut reloaded = Vec::new();
+            for (kind, id) in roots {
+                if loader.rebuild(kind, id).await && !reloaded.contains(&id) {
+                    reloaded.push(id);
+                }
+            }
+
+            if !reloaded.is_empty() {
+                loader.cascade_reloaded(&reloaded);
+            }
+        });
+    }
+
+    /// Rebuilds stale assets once each, after their stale dependencies.
+    async fn rebuild_stale(&self, stale: &HashSet<AssetId>, targets: &[(KindKey, AssetId)]) {
+        let mut ids: Vec<AssetId> = Vec::new();
+        for &(_, id) in targets {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+
+        let order = rebuild_order(&self.dependencies.lock(), stale, &ids);
+
+        // Assets rebuilt before their dependencies in a cycle stay stale.
+        let mut early = HashSet::new();
+        for (id, rebuilt_early) in order {
+            for &(kind, _) in targets.iter().filter(|(_, target)| *target == id) {
+                self.rebuild(kind, id).await;
+            }
+            if rebuilt_early {
+                early.insert(id);
+            }
+        }
+
+        if !early.is_empty() {
+            self.mark_stale(&early);
+        }
+    }
+
+    /// Decodes fresh data of the asset, builds and publishes it without cascading.
+    /// Returns `true` if asset is replaced.
+    async fn rebuild(&self, kind: KindKey, id: AssetId) -> bool {
+        let Some(rebuild) = self.cascades.get(kind) else {
+            return false;
+        };
+
+        match rebuild(self.clone(), id).await {
+            Ok(Some(staged)) => {
+                self.commit_published(vec![staged], false);
+                true
+            }
+            Ok(None) => {
+                tracing::warn!("Asset {id} to rebuild is missing");
+                false
+            }
+            Err(error) => {
+                tracing::warn!("Failed to rebuild asset {id}: {error:#}");
+                false
+            }
+        }
+    }
+
+    /// Loads fresh data of the asset and decodes it.
+    /// Returns decoded asset with version of its data or `None` if asset is missing.
+    pub(crate) async fn redecode<A: Asset>(
+        &self,
+        id: AssetId,
+    ) -> Result<Option<(A::Decoded, u64)>, Error> {
+        let abort = AbortSignal::new();
+        let missing = MissingWait::new(&LoadOptions::default(), &*self.clock);
+        let data = match self.sources.load(id, &missing, &abort).await {
+            Ok(Some(data)) => data,
+            Ok(None) => return Ok(None),
+            Err(error) => {
+                return Err(error
+                    .or_code(ErrorCode::SourceIo)
+                    .with_stage(ErrorStage::SourceLoad))
+            }
+        };
+
+        let decoder = Loader {
+            decoding: Some(id),
+            decoding_path: self.path_of(KindKey::of::<A>(), id),
+            decoding_properties: data.properties.clone(),
+            decoding_format: None,
+            abort: Some(abort),
+            ..self.clone()
+        };
+
+        let decoded = A::decode(data.bytes.into_boxed(), &decoder)
+            .await
+            .map_err(|err| {
+                let error = Error::new(err);
+                let (stage, code) = decode_stage(&error);
+                error.or_code(code).with_stage(stage)
+            })?;
+
+        Ok(Some((decoded, data.version)))
+    }
+
+    /// Returns path the asset was loaded with, if any.
+    fn path_of(&self, kind: KindKey, id: AssetId) -> Option<Arc<str>> {
+        let mut found = None;
+        for shard in self.path_cache.iter() {
+            shard.lock().retain(&mut |key, state| {
+                if found.is_none()
+                    && key.kind == kind
+                    && matches!(state, PathState::Loaded { id: loaded } if *loaded == id)
+                {
+                    found = Some(key.path.clone());
+                }
+                true
+            });
+        }
+        found
+    }
+
+    /// Calls reload hooks for staged assets that replace ready values.
+    fn call_reload_hooks(&self, staged: &[Staged]) -> Vec<Option<Reload>> {
+        if self.reload.is_empty() {
+            return staged.iter().map(|_| None).collect();
+        }
+
+        staged
+            .iter()
+            .map(|staged| {
+                if !self.reload.has_hook(staged.kind.type_id) {
+                    return None;
+                }
+
+                let shard = &self.asset_cache[staged.key_hash as usize % self.asset_cache.len()];
+                let old = match shard
+                    .lock()
+                    .entry(staged.key_hash, |k| k.eq_key(staged.kind, staged.id))
+                {
+                    Entry::Occupied(mut entry) => match entry.get() {
+                        AssetState::Ready { asset, .. } => asset.clone(),
+                        _ => return None,
+                    },
+                    Entry::Vacant(_) => return None,
+                };
+
+                let (name, action) =
+                    self.reload
+                        .call(staged.kind.type_id, &*old, &*staged.asset)?;
+                Some(Reload { old, name, action })
+            })
+            .collect()
+    }
+
+    /// Returns statistics of the loader caches.
+    ///
+    /// Walks all cache entries, so it should not be called every frame.
+    pub fn stats(&self) -> LoaderStats {
+        let mut path_cache_len = 0;
+        for shard in self.path_cache.iter() {
+            shard.lock().retain(&mut |_, _| {
+                path_cache_len += 1;
+                true
+            });
+        }
+
+        let mut orphaned_entries = 0;
+        let mut orphaned_decoded = 0;
+        for shard in self.asset_cache.iter() {
+            shard.lock().retain(&mut |_, state| {
+                orphaned_entries += usize::from(self.is_orphaned(state));
+                orphaned_decoded += usize::from(state.is_orphaned_decoded());
+                true
+            });
+        }
+
+        let (update_calls, updates_found, updates_backed_off) = self.updates.counters();
+
+        LoaderStats {
+            path_cache_len,
+            orphaned_entries,
+            orphaned_decoded,
+            update_calls,
+            updates_found,
+            updates_backed_off,
+            unbuilt_bytes: self.backpressure.as_ref().map_or(0, |b| b.unbuilt()),
+            parked_decodes: self.backpressure.as_ref().map_or(0, |b| b.parked()),
+            build_urgent: self.backpressure.as_ref().is_some_and(|b| b.is_throttled()),
+            shared_fetches: self
+                .tenant
+                .as_ref()
+                .map_or(0, |t| t.shared_fetches.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Returns assets used by the loader so far, sorted by id.
+    ///
+    /// Returns empty vector unless enabled with [`LoaderBuilder::set_usage_recording`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("one", AssetId::new(1).unwrap(), &br#"{ "value": 1 }"#[..]);
+    /// source.insert(AssetId::new(2).unwrap(), &br#"{ "value": 2 }"#[..]);
+    ///
+    /// let loader = Loader::builder().with(source).with_usage_recording(()).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         for _ in 0..2 {
+    ///             loader.load::<Number, _>("one").await.unwrap();
+    ///             loader.load::<Number, _>(AssetId::new(2).unwrap()).await.unwrap();
+    ///         }
+    ///
+    ///         // Missing assets are not recorded.
+    ///         assert!(loader.load::<Number, _>("missing").await.is_err());
+    ///     });
+    ///
+    /// let usage: Vec<_> = loader
+    ///     .usage_snapshot()
+    ///     .into_iter()
+    ///     .map(|usage| (usage.id.value().get(), usage.name, usage.path))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     usage,
+    ///     [
+    ///         (1, "Number".to_owned(), Some("one".into())),
+    ///         (2, "Number".to_owned(), None),
+    ///     ]
+    /// );
+    /// # }
+    /// ```
+    pub fn usage_snapshot(&self) -> Vec<AssetUsage> {
+        match &self.usage {
+            None => Vec::new(),
+            Some(usage) => usage.snapshot(),
+        }
+    }
+
+    /// Returns decode statistics of asset types loaded so far, sorted by name.
+    ///
+    /// Returns empty vector unless enabled with [`LoaderBuilder::set_decode_stats`].
+    /// Durations are measured from start to end of decoding,
+    /// including time spent waiting inside [`Asset::decode`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use std::time::Duration;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert(AssetId::new(1).unwrap(), &br#"{ "value": 1 }"#[..]);
+    ///
+    /// let loader = Loader::builder().with(source).with_decode_stats(true).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         loader.load::<Number, _>(AssetId::new(1).unwrap()).await.unwrap();
+    ///     });
+    ///
+    /// let stats = loader.type_stats();
+    /// assert_eq!(stats.len(), 1);
+    /// assert_eq!(stats[0].name, "Number");
+    /// assert_eq!(stats[0].count, 1);
+    /// assert_eq!(stats[0].bytes_total, 14);
+    ///
+    /// // Synthetic samples.
+    /// loader.reset_stats();
+    /// for _ in 0..19 {
+    ///     loader.record_decode_sample("Number", Duration::from_micros(1), 0);
+    /// }
+    /// loader.record_decode_sample("Number", Duration::from_millis(1), 0);
+    ///
+    /// let stats = loader.type_stats();
+    /// assert_eq!(stats[0].count, 20);
+    /// assert_eq!(stats[0].decode_p50, Duration::from_nanos(1024));
+    /// assert_eq!(stats[0].decode_p95, Duration::from_nanos(1024));
+    /// assert_eq!(stats[0].bytes_total, 0);
+    ///
+    /// loader.record_decode_sample("Number", Duration::from_millis(1), 0);
+    /// assert_eq!(loader.type_stats()[0].decode_p95, Duration::from_nanos(1 << 20));
+    /// # }
+    /// ```
+    pub fn type_stats(&self) -> Vec<TypeStats> {
+        match &self.decode_stats {
+            None => Vec::new(),
+            Some(stats) => stats.type_stats(),
+        }
+    }
+
+    /// Clears decode statistics of all asset types.
+    pub fn reset_stats(&self) {
+        if let Some(stats) = &self.decode_stats {
+            stats.reset();
+        }
+    }
+
+    /// Records decode sample as if asset type was decoded.
+    /// Used to test statistics with known durations.
+    #[doc(hidden)]
+    pub fn record_decode_sample(&self, name: &str, duration: Duration, bytes: usize) {
+        if let Some(stats) = &self.decode_stats {
+            stats.record(name, duration, bytes);
+        }
+    }
+
+    /// Returns sequence number that will be assigned to the next cache entry.
+    ///
+    /// Pass it to [`Loader::entries_since`] later to get entries inserted in between.
+    pub fn sequence(&self) -> u64 {
+        self.sequence.load(Ordering::Relaxed)
+    }
+
+    /// Renders cache entries as a graph in DOT format.
+    ///
+    /// Path entries are boxes linked to assets they resolved to,
+    /// dashed edges link assets to their dependencies.
+    /// Nodes are colored by state and show number of waiting tasks.
+    /// See [`docs`] for the transitions between states.
+    ///
+    /// Walks all cache entries, so it should not be called every frame.
+    ///
+    /// [`docs`]: crate::docs
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 1 }"#[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         loader.load::<Number, _>("number").await?;
+    ///         assert!(loader.load::<Number, _>("missing").await.is_err());
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    ///
+    /// let mut out = Vec::new();
+    /// loader.dump_entry_graph(&mut out).unwrap();
+    /// let dot = String::from_utf8(out).unwrap();
+    ///
+    /// assert!(dot.starts_with("digraph"));
+    /// assert!(dot.contains(r#"label="number\nLoaded""#));
+    /// assert!(dot.contains(r#"label="missing\nMissing""#));
+    /// let id = AssetId::new(1).unwrap();
+    /// assert!(dot.contains(&format!(r#"label="{id}\nLoaded""#)));
+    /// assert_eq!(dot.matches(" -> ").count(), 1);
+    /// # Ok::<_, Error>(())
+    /// ```
+    #[cfg(feature = "debug-statemachine")]
+    pub fn dump_entry_graph(&self, out: &mut impl std::io::Write) -> std::io::Result<()> {
+        use crate::docs::state_color;
+
+        fn escape(label: &str) -> String {
+            label.replace('\\', "\\\\").replace('"', "\\\"")
+        }
+
+        fn label(name: &str, status: EntryStatus, waiters: usize) -> String {
+            match waiters {
+                0 => format!("{}\\n{:?}", escape(name), status),
+                _ => format!("{}\\n{:?} ({} waiting)", escape(name), status, waiters),
+            }
+        }
+
+        let mut paths = Vec::new();
+        for shard in self.path_cache.iter() {
+            shard.lock().retain(&mut |key, state| {
+                let id = match state {
+                    PathState::Loaded { id } => Some(*id),
+                    _ => None,
+                };
+                paths.push((key.clone(), state.status(), state.waiters(), id));
+                true
+            });
+        }
+
+        let mut assets = Vec::new();
+        for shard in self.asset_cache.iter() {
+            shard.lock().retain(&mut |key, state| {
+                assets.push((key.clone(), state.status(), state.waiters()));
+                true
+            });
+        }
+
+        paths.sort_unstable_by_key(|(key, ..)| key.sequence);
+        assets.sort_unstable_by_key(|(key, ..)| key.sequence);
+
+        writeln!(out, "digraph entries {{")?;
+        writeln!(out, "  node [style=filled];")?;
+
+        for (key, status, waiters, _) in &paths {
+            writeln!(
+                out,
+                "  p{} [shape=box, label=\"{}\", fillcolor={}];",
+                key.sequence,
+                label(&key.path, *status, *waiters),
+                state_color(*status)
+            )?;
+        }
+
+        for (key, status, waiters) in &assets {
+            writeln!(
+                out,
+                "  a{} [shape=ellipse, label=\"{}\", fillcolor={}];",
+                key.sequence,
+                label(&key.id.to_string(), *status, *waiters),
+                state_color(*status)
+            )?;
+        }
+
+        for (path_key, _, _, id) in &paths {
+            let Some(id) = id else { continue };
+            for (key, ..) in &assets {
+                if key.kind == path_key.kind && key.id == *id {
+                    writeln!(out, "  p{} -> a{};", path_key.sequence, key.sequence)?;
+                }
+            }
+        }
+
+        let dependencies = self.dependencies.lock().clone();
+        for (parent, ..) in &assets {
+            for child in dependencies.get(&parent.id).into_iter().flatten() {
+                for (key, ..) in &assets {
+                    if key.id == *child {
+                        writeln!(
+                            out,
+                            "  a{} -> a{} [style=dashed];",
+                            parent.sequence, key.sequence
+                        )?;
+                    }
+                }
+            }
+        }
+
+        writeln!(out, "}}")
+    }
+
+    /// Returns summaries of all cache entries ordered by sequence number.
+    ///
+    /// Walks all cache entries, so it should not be called every frame.
+    pub fn entries(&self) -> Vec<EntrySummary> {
+        self.entries_since(0)
+    }
+
+    /// Returns summaries of cache entries with sequence number not less than `sequence`
+    /// ordered by sequence number.
+    ///
+    /// Entries that were evicted or unloaded are not reported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// # #[derive(Clone, Asset)]
+    /// # struct Number { value: u32 }
+    /// let id = |id| AssetId::new(id).unwrap();
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("one", id(1), &br#"{ "value": 1 }"#[..]);
+    /// source.insert_with_path("two", id(2), &br#"{ "value": 2 }"#[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         loader.load::<Number, _>("one").await?;
+    ///         let first = loader.entries();
+    ///         assert!(first.windows(2).all(|w| w[0].sequence < w[1].sequence));
+    ///
+    ///         let mark = loader.sequence();
+    ///         loader.load::<Number, _>("two").await?;
+    ///         assert!(loader.load::<Number, _>("missing").await.is_err());
+    ///
+    ///         // Path entries of "two" and "missing" and asset entry of "two".
+    ///         let new = loader.entries_since(mark);
+    ///         assert_eq!(new.len(), 3);
+    ///         assert!(new.iter().all(|entry| entry.sequence >= mark));
+    ///         assert_eq!(new[0].path.as_deref(), Some("two"));
+    ///         assert_eq!(new[1].id, Some(id(2)));
+    ///         assert_eq!(new[2].status, EntryStatus::Missing);
+    ///
+    ///         // Old entries are reported before new ones.
+    ///         let all = loader.entries();
+    ///         assert_eq!(all.len(), first.len() + new.len());
+    ///         assert_eq!(all[first.len()].sequence, new[0].sequence);
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn entries_since(&self, sequence: u64) -> Vec<EntrySummary> {
+        let mut entries = Vec::new();
+
+        for shard in self.path_cache.iter() {
+            shard.lock().retain(&mut |key, state| {
+                if key.sequence >= sequence {
+                    let id = match state {
+                        PathState::Loaded { id } => Some(*id),
+                        _ => None,
+                    };
+                    entries.push(EntrySummary {
+                        sequence: key.sequence,
+                        path: Some(key.path.clone()),
+                        id,
+                        status: state.status(),
+                        orphaned: false,
+                    });
+                }
+                true
+            });
+        }
+
+        for shard in self.asset_cache.iter() {
+            shard.lock().retain(&mut |key, state| {
+                if key.sequence >= sequence {
+                    entries.push(EntrySummary {
+                        sequence: key.sequence,
+                        path: None,
+                        id: Some(key.id),
+                        status: state.status(),
+                        orphaned: self.is_orphaned(state),
+                    });
+                }
+                true
+            });
+        }
+
+        entries.sort_unstable_by_key(|entry| entry.sequence);
+        entries
+    }
+
+    /// Returns number of find and load tasks that are not finished yet.
+    ///
+    /// Assets that are loaded but not built yet are not counted,
+    /// building is up to the caller.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.count.load(Ordering::Acquire)
+    }
+
+    /// Waits until loader has no find and load tasks in flight.
+    ///
+    /// Tasks spawned by decoders to load dependencies are waited for as well,
+    /// so are started prefetches.
+    /// Resolves immediately if loader is idle.
+    /// Loads started while waiting delay completion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use futures::future::BoxFuture;
+    /// # use std::time::Duration;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// /// Source that answers after a delay.
+    /// struct Delayed(MemorySource);
+    ///
+    /// impl Source for Delayed {
+    ///     fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+    ///         Box::pin(async move {
+    ///             tokio::time::sleep(Duration::from_millis(10)).await;
+    ///             self.0.find(path, asset).await
+    ///         })
+    ///     }
+    ///
+    ///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         Box::pin(async move {
+    ///             tokio::time::sleep(Duration::from_millis(10)).await;
+    ///             self.0.load(id).await
+    ///         })
+    ///     }
+    ///
+    ///     fn update<'a>(&'a self, id: AssetId, version: u64) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         self.0.update(id, version)
+    ///     }
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// for i in 1..=4 {
+    ///     let bytes = format!(r#"{{ "value": {} }}"#, i);
+    ///     source.insert_with_path(format!("number{}", i), AssetId::new(i).unwrap(), bytes.as_bytes());
+    /// }
+    /// let loader = Loader::builder().with(Delayed(source)).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .enable_time()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let handles: Vec<_> = (1..=4)
+    ///             .map(|i| loader.load::<Number, _>(format!("number{}", i).as_str()))
+    ///             .collect();
+    ///         assert_eq!(loader.in_flight(), 4);
+    ///
+    ///         loader.wait_idle().await;
+    ///         assert_eq!(loader.in_flight(), 0);
+    ///
+    ///         for (i, mut handle) in (1..=4).zip(handles) {
+    ///             let mut number = handle.poll_loaded().unwrap()?;
+    ///             assert_eq!(number.build(&mut ())?.value, i as u32);
+    ///         }
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn wait_idle(&self) {
+        loop {
+            // Future receives notifications since creation.
+            let notified = self.in_flight.idle.notified();
+            if self.in_flight() == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Waits until loader has no find and load tasks in flight or `cancel` is cancelled.
+    ///
+    /// Returns `true` if loader is idle and `false` if waiting was cancelled,
+    /// e.g. on shutdown while sources hang.
+    /// See [`Loader::wait_idle`].
+    pub async fn wait_idle_or_cancel(&self, cancel: &CancelToken) -> bool {
+        let idle = pin!(self.wait_idle());
+        let cancelled = pin!(cancel.cancelled());
+        if self.in_flight() == 0 {
+            return true;
+        }
+        matches!(select(idle, cancelled).await, Either::Left(_))
+    }
+
+    /// Returns signal to abort decoding of the asset.
+    ///
+    /// Returns `Some` only for loader passed to [`Asset::decode`].
+    /// Decoders that may take long time should clone the signal
+    /// and check it periodically.
+    pub fn abort_signal(&self) -> Option<AbortSignal> {
+        self.abort.clone()
+    }
+
+    /// Reports progress of decoding the asset.
+    /// Handles read it with [`AssetHandle::progress`].
+    ///
+    /// Does nothing for loader not passed to [`Asset::decode`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// use futures::future::BoxFuture;
+    /// # #[cfg(feature = "tokio")] {
+    ///
+    /// #[derive(Clone)]
+    /// struct Video {
+    ///     frames: u64,
+    /// }
+    ///
+    /// impl Asset for Video {
+    ///     type Decoded = u64;
+    ///     type DecodeError = std::convert::Infallible;
+    ///     type BuildError = std::convert::Infallible;
+    ///     type Fut = BoxFuture<'static, Result<u64, std::convert::Infallible>>;
+    ///
+    ///     fn asset_name() -> AssetName {
+    ///         AssetName::new("Video")
+    ///     }
+    ///
+    ///     fn decode(bytes: Box<[u8]>, loader: &Loader) -> Self::Fut {
+    ///         let loader = loader.clone();
+    ///         Box::pin(async move {
+    ///             let frames = bytes.len() as u64;
+    ///             for frame in 0..frames {
+    ///                 loader.report_progress(frame, Some(frames));
+    ///                 // Decoding a frame takes a while.
+    ///                 tokio::task::yield_now().await;
+    ///             }
+    ///             Ok(frames)
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// impl AssetBuild<()> for Video {
+    ///     fn build(_: &mut (), frames: u64) -> Result<Video, std::convert::Infallible> {
+    ///         Ok(Video { frames })
+    ///     }
+    /// }
+    ///
+    /// let id = AssetId::new(1).unwrap();
+    /// let source = MemorySource::new();
+    /// source.insert(id, &[0u8; 8][..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let mut handle = loader.load::<Video, _>(id);
+    ///         assert_eq!(handle.progress(), None);
+    ///
+    ///         let mut observed = Vec::new();
+    ///         let video = loop {
+    ///             if let Some(video) = handle.poll_build(&mut ()) {
+    ///                 break video?;
+    ///             }
+    ///             if let Some(progress) = handle.progress() {
+    ///                 assert_eq!(progress.total, Some(8));
+    ///                 observed.push(progress.done);
+    ///             }
+    ///             tokio::task::yield_now().await;
+    ///         };
+    ///
+    ///         assert_eq!(video.frames, 8);
+    ///         assert!(!observed.is_empty());
+    ///         assert!(observed.windows(2).all(|w| w[0] <= w[1]));
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn report_progress(&self, done: u64, total: Option<u64>) {
+        if let Some(abort) = &self.abort {
+            abort.report_progress(done, total);
+        }
+    }
+
+    /// Returns properties of the asset being decoded reported by the source.
+    ///
+    /// Returns empty properties for loader not passed to [`Asset::decode`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// use futures::future::BoxFuture;
+    /// # #[cfg(feature = "tokio")] {
+    ///
+    /// /// Text that keeps its license.
+    /// #[derive(Clone)]
+    /// struct Text {
+    ///     license: Option<String>,
+    /// }
+    ///
+    /// impl Asset for Text {
+    ///     type Decoded = Text;
+    ///     type DecodeError = std::convert::Infallible;
+    ///     type BuildError = std::convert::Infallible;
+    ///     type Fut = BoxFuture<'static, Result<Text, std::convert::Infallible>>;
+    ///
+    ///     fn asset_name() -> AssetName {
+    ///         AssetName::new("Text")
+    ///     }
+    ///
+    ///     fn decode(_bytes: Box<[u8]>, loader: &Loader) -> Self::Fut {
+    ///         let license = loader.decoding_properties().get("license").map(|l| l.to_string());
+    ///         Box::pin(async move { Ok(Text { license }) })
+    ///     }
+    /// }
+    ///
+    /// impl<B> AssetBuild<B> for Text {
+    ///     fn build(_: &mut B, decoded: Text) -> Result<Text, std::convert::Infallible> {
+    ///         Ok(decoded)
+    ///     }
+    /// }
+    ///
+    /// let id = AssetId::new(1).unwrap();
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("readme", id, &b"Hello"[..]);
+    /// source.set_properties(id, AssetProperties::new().with("license", "MIT").with("lang", "en"));
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let mut text = loader.load::<Text, _>("readme").await?;
+    ///         assert_eq!(text.property("lang").as_deref(), Some("en"));
+    ///         assert_eq!(text.metadata().properties.len(), 2);
+    ///         assert_eq!(text.build(&mut ())?.license.as_deref(), Some("MIT"));
+    ///
+    ///         // Handle reports properties once asset is loaded.
+    ///         let mut handle = loader.load::<Text, _>("readme");
+    ///         assert!(handle.poll_loaded().is_some());
+    ///         assert_eq!(handle.property("license").as_deref(), Some("MIT"));
+    ///         assert_eq!(handle.property("missing"), None);
+    ///         Ok::<_, Error>(())
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn decoding_properties(&self) -> &AssetProperties {
+        &self.decoding_properties
+    }
+
+    /// Returns format requested with [`Loader::load_as`]
+    /// for the asset being decoded with this loader instance.
+    ///
+    /// Returns `None` if format should be detected from the data.
+    pub fn decoding_format(&self) -> Option<AssetFormat> {
+        self.decoding_format
+    }
+
+    /// Writes asset with specified id and all its transitive dependencies
+    /// into a bundle that can be read by [`ArchiveSource`] and [`EmbeddedSource`].
+    ///
+    /// Raw asset data is taken from the sources as is.
+    /// Dependencies are known only for assets that were decoded by this loader,
+    /// so assets should be loaded before bundling.
+    ///
+    /// Returns number of assets written.
+    ///
+    /// [`ArchiveSource`]: crate::ArchiveSource
+    /// [`EmbeddedSource`]: crate::EmbeddedSource
+    pub async fn bundle(&self, id: AssetId, writer: &mut impl Write) -> Result<usize, Error> {
+        let sources = self.sources.snapshot();
+
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        visited.insert(id);
+        queue.push_back(id);
+
+        write_archive_header(writer).map_err(Error::new)?;
+
+        while let Some(id) = queue.pop_front() {
+            let recording = self.sources.record(|| ReplayRequest::Load { id });
+            let result = load_asset(&sources, 0, id, &*self.clock).await;
+            if let Some(recording) = recording {
+                recording.data(&result);
+            }
+
+            let Some(data) = result? else {
+                return Err(NotFound {
+                    path: None,
+                    id: Some(id),
+                }
+                .into_error());
+            };
+
+            write_archive_entry(writer, id, &data.bytes).map_err(Error::new)?;
+
+            for dep in self.dependencies(id) {
+                if visited.insert(dep) {
+                    queue.push_back(dep);
+                }
+            }
+        }
+
+        Ok(visited.len())
+    }
+
+    /// Lists assets of type `A` with paths that start with `prefix`.
+    ///
+    /// Returns paths and ids sorted by path.
+    /// If several sources have asset with the same path,
+    /// id from the source added first is returned, same as with [`Loader::load`].
+    /// Sources that do not support path-based lookup list nothing.
+    ///
+    /// Assets are not loaded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("numbers/one", AssetId::new(1).unwrap(), &br#"{ "value": 1 }"#[..]);
+    /// source.insert_with_path("numbers/two", AssetId::new(2).unwrap(), &br#"{ "value": 2 }"#[..]);
+    /// source.insert_with_path("other", AssetId::new(3).unwrap(), &br#"{ "value": 3 }"#[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// let found = tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(loader.find_under::<Number>("numbers/"));
+    ///
+    /// assert_eq!(
+    ///     found,
+    ///     [
+    ///         ("numbers/one".to_owned(), AssetId::new(1).unwrap()),
+    ///         ("numbers/two".to_owned(), AssetId::new(2).unwrap()),
+    ///     ]
+    /// );
+    /// # }
+    /// ```
+    pub async fn find_under<A: Asset>(&self, prefix: &str) -> Vec<(String, AssetId)> {
+        let sources = self.sources.snapshot();
+
+        let recording = self.sources.record(|| ReplayRequest::FindPrefix {
+            prefix: prefix.to_owned(),
+            asset: A::asset_name().to_string(),
+        });
+        let found = find_assets_under(&sources.sources, &A::asset_name(), prefix).await;
+        if let Some(recording) = recording {
+            recording.listed(&found);
+        }
+        found
+    }
+
+    /// Looks up id of the asset with specified path and [`Asset::asset_name`],
+    /// without asset type and without loading the asset.
+    ///
+    /// Lookups are cached separately from paths of loaded assets,
+    /// except that paths already resolved for type registered with
+    /// [`LoaderBuilder::register_asset`] under `target` name resolve immediately.
+    /// Lookup runs while returned future is polled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// #[asset(name = "number")]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("one", AssetId::new(1).unwrap(), &br#"{ "value": 1 }"#[..]);
+    /// source.insert_with_path("two", AssetId::new(2).unwrap(), &br#"{ "value": 2 }"#[..]);
+    /// let loader = Loader::builder().with(source).with_registered_asset::<Number>().build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         // Lookup before typed load.
+    ///         assert_eq!(loader.peek_path("one", "number"), None);
+    ///         assert_eq!(loader.lookup_path("one", "number").await?, AssetId::new(1).unwrap());
+    ///         assert_eq!(loader.peek_path("one", "number"), AssetId::new(1));
+    ///         assert!(loader.lookup_path("three", "number").await.is_err());
+    ///
+    ///         // Typed load does not depend on lookups.
+    ///         let mut one = loader.load::<Number, _>("one").await?;
+    ///         assert_eq!(one.build(&mut ())?.value, 1);
+    ///
+    ///         // Path resolved by typed load is shared with lookups.
+    ///         loader.load::<Number, _>("two").id().await?;
+    ///         assert_eq!(loader.peek_path("two", "number"), AssetId::new(2));
+    ///         assert_eq!(loader.lookup_path("two", "number").await?, AssetId::new(2).unwrap());
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn lookup_path(&self, path: &str, target: &str) -> PathLookup {
+        let resolved = self.resolve_path(path);
+        let path = &*resolved;
+
+        if let Some(id) = self.peek_typed_path(path, target) {
+            return PathLookup::ready(Ok(id));
+        }
+
+        let sources = self.sources.clone();
+        self.path_lookups.lookup(target, path, move |target, path| {
+            Box::pin(async move {
+                let missing = MissingWait::new(&LoadOptions::default(), &*sources.clock);
+                sources.find(&target, &path, &missing).await
+            })
+        })
+    }
+
+    /// Remaps asset path `from` to path `to`.
+    /// Following loads of `from` path load asset found by `to` path instead.
+    /// Aliases of `to` path are followed too, up to a limited depth.
+    ///
+    /// Cached lookups of `from` path are forgotten.
+    /// Assets already loaded with `from` path are not affected.
+    ///
+    /// Paths are canonicalized by sources before aliases are applied.
+    ///
+    /// Returns previous target of `from` path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Material {
+    ///     version: u32,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("characters/hero.mat", AssetId::new(1).unwrap(), &br#"{ "version": 1 }"#[..]);
+    /// source.insert_with_path("characters/hero_v2.mat", AssetId::new(2).unwrap(), &br#"{ "version": 2 }"#[..]);
+    /// source.insert_with_path("characters/hero_v3.mat", AssetId::new(3).unwrap(), &br#"{ "version": 3 }"#[..]);
+    /// let loader = Loader::builder()
+    ///     .with(source)
+    ///     .with_path_aliases([("characters/./hero_v2.mat", "characters/hero_v3.mat")])
+    ///     .build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let mut v1 = loader.load::<Material, _>("characters/hero.mat").await?;
+    ///         assert_eq!(v1.build(&mut ())?.version, 1);
+    ///
+    ///         // Live patch redirects the path, chained to the alias set on build.
+    ///         assert_eq!(loader.set_path_alias("characters//hero.mat", "characters/hero_v2.mat"), None);
+    ///         let mut hero = loader.load::<Material, _>("characters/hero.mat").await?;
+    ///         assert_eq!(hero.build(&mut ())?.version, 3);
+    ///         assert_eq!(loader.lookup_path("characters/hero.mat", "Material").await?, AssetId::new(3).unwrap());
+    ///
+    ///         // Asset loaded before the redirect is untouched.
+    ///         assert_eq!(v1.build(&mut ())?.version, 1);
+    ///
+    ///         assert_eq!(
+    ///             loader.path_aliases(),
+    ///             [
+    ///                 ("characters/hero.mat".into(), "characters/hero_v2.mat".into()),
+    ///                 ("characters/hero_v2.mat".into(), "characters/hero_v3.mat".into()),
+    ///             ]
+    ///         );
+    ///
+    ///         assert_eq!(loader.remove_path_alias("characters/hero_v2.mat").as_deref(), Some("characters/hero_v3.mat"));
+    ///         let mut hero = loader.load::<Material, _>("characters/hero.mat").await?;
+    ///         assert_eq!(hero.build(&mut ())?.version, 2);
+    ///
+    ///         // Cyclic aliases stop at depth limit.
+    ///         loader.set_path_alias("characters/hero_v2.mat", "characters/hero.mat");
+    ///         assert!(loader.load::<Material, _>("characters/hero.mat").await.is_ok());
+    ///
+    ///         loader.remove_path_alias("characters/hero.mat");
+    ///         loader.remove_path_alias("characters/hero_v2.mat");
+    ///         assert!(loader.path_aliases().is_empty());
+    ///         let mut hero = loader.load::<Material, _>("characters/hero.mat").await?;
+    ///         assert_eq!(hero.build(&mut ())?.version, 1);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn set_path_alias(&self, from: &str, to: &str) -> Option<Arc<str>> {
+        let from: Arc<str> = match self.sources.canonical_path(from) {
+            None => from.into(),
+            Some(canonical) => canonical.into(),
+        };
+
+        let previous = self.path_aliases.set(from.clone(), to.into());
+        self.forget_path(&from);
+        previous
+    }
+
+    /// Removes alias of asset path `from`.
+    /// Returns its target.
+    ///
+    /// See [`Loader::set_path_alias`].
+    pub fn remove_path_alias(&self, from: &str) -> Option<Arc<str>> {
+        let canonical = self.sources.canonical_path(from);
+        let from = canonical.as_deref().unwrap_or(from);
+
+        let removed = self.path_aliases.remove(from);
+        if removed.is_some() {
+            self.forget_path(from);
+        }
+        removed
+    }
+
+    /// Returns path aliases as pairs of remapped path and its target,
+    /// sorted by remapped path.
+    ///
+    /// See [`Loader::set_path_alias`].
+    pub fn path_aliases(&self) -> Vec<(Arc<str>, Arc<str>)> {
+        self.path_aliases.list()
+    }
+
+    /// Returns canonical path with aliases applied.
+    fn resolve_path<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        let mut resolved = match self.sources.canonical_path(path) {
+            None => Cow::Borrowed(path),
+            Some(canonical) => canonical,
+        };
+
+        for _ in 0..MAX_PATH_ALIAS_DEPTH {
+            let Some(to) = self.path_aliases.get(&resolved) else {
+                return resolved;
+            };
+            resolved = match self.sources.canonical_path(&to) {
+                None => Cow::Owned(to.to_string()),
+                Some(canonical) => Cow::Owned(canonical.into_owned()),
+            };
+        }
+
+        tracing::warn!(
+            "Aliases of path '{}' are chained deeper than {}, possibly cyclic. Using '{}'",
+            path,
+            MAX_PATH_ALIAS_DEPTH,
+            resolved
+        );
+        resolved
+    }
+
+    /// Forgets cached lookups of the path.
+    /// Lookups in progress are kept.
+    fn forget_path(&self, path: &str) {
+        for shard in self.path_cache.iter() {
+            shard.lock().retain(&mut |key, state| {
+                &*key.path != path || matches!(state, PathState::Unloaded { .. })
+            });
+        }
+
+        self.path_lookups.forget_path(path);
+    }
+
+    /// Returns warnings recorded in development mode, oldest first.
+    ///
+    /// Only recent warnings are kept.
+    /// Empty unless enabled with [`LoaderBuilder::set_dev_placeholders`].
+    pub fn dev_warnings(&self) -> Vec<DevWarning> {
+        self.dev
+            .as_ref()
+            .map_or_else(Vec::new, |dev| dev.warnings())
+    }
+
+    /// Returns placeholder for asset `missing` referenced by decoded asset,
+    /// if development mode is enabled.
+    pub(crate) fn placeholder<A: Asset>(&self, missing: AssetId) -> Option<Placeholder<A>> {
+        self.dev.as_ref()?.placeholder(self.decoding, missing)
+    }
+
+    /// Checks sources of cached assets for newer data.
+    /// Returns ids of assets which sources reported newer data.
+    ///
+    /// Call it periodically, e.g. once per frame, to drive hot-reloading.
+    /// Each call continues sweep of cached assets where previous call stopped,
+    /// making at most as many [`Source::update`] calls as configured with [`LoaderBuilder::set_update_limits`].
+    /// Assets that are found unchanged are checked less often,
+    /// and assets of sources that do not [support updates](Source::supports_update) are never checked.
+    ///
+    /// Loader does not reload changed assets by itself, see [`Loader::poll_updates`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use futures::future::BoxFuture;
+    /// # use std::{collections::HashSet, sync::{Arc, Mutex}};
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// /// Source that records update calls.
+    /// #[derive(Clone, Default)]
+    /// struct Counting(MemorySource, Arc<Mutex<Vec<AssetId>>>);
+    ///
+    /// impl Source for Counting {
+    ///     fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+    ///         self.0.find(path, asset)
+    ///     }
+    ///
+    ///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         self.0.load(id)
+    ///     }
+    ///
+    ///     fn update<'a>(&'a self, id: AssetId, version: u64) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         self.1.lock().unwrap().push(id);
+    ///         self.0.update(id, version)
+    ///     }
+    /// }
+    ///
+    /// let source = Counting::default();
+    /// let ids: Vec<_> = (1..=5).map(|n| AssetId::new(n).unwrap()).collect();
+    /// for &id in &ids {
+    ///     source.0.insert(id, &br#"{ "value": 1 }"#[..]);
+    /// }
+    ///
+    /// let loader = Loader::builder()
+    ///     .with(source.clone())
+    ///     .with_update_limits(2, 4)
+    ///     .build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         for &id in &ids {
+    ///             loader.load::<Number, _>(id).await?;
+    ///         }
+    ///
+    ///         // Sweep continues where previous tick stopped.
+    ///         let mut checked = HashSet::new();
+    ///         for _ in 0..3 {
+    ///             loader.update_tick().await;
+    ///             let calls = std::mem::take(&mut *source.1.lock().unwrap());
+    ///             assert!(calls.len() <= 2);
+    ///             checked.extend(calls);
+    ///         }
+    ///         assert_eq!(checked.len(), 5);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    ///
+    /// // Unchanged asset is checked after 2, 4 and then every 4 ticks.
+    /// let source = Counting::default();
+    /// let id = AssetId::new(1).unwrap();
+    /// source.0.insert(id, &br#"{ "value": 1 }"#[..]);
+    /// let loader = Loader::builder()
+    ///     .with(source.clone())
+    ///     .with_update_limits(2, 4)
+    ///     .build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         loader.load::<Number, _>(id).await?;
+    ///
+    ///         let mut checked_on = Vec::new();
+    ///         for tick in 1..=12 {
+    ///             assert!(loader.update_tick().await.is_empty());
+    ///             if !std::mem::take(&mut *source.1.lock().unwrap()).is_empty() {
+    ///                 checked_on.push(tick);
+    ///             }
+    ///         }
+    ///         assert_eq!(checked_on, [1, 3, 7, 11]);
+    ///
+    ///         // Change is found on next check.
+    ///         source.0.insert(id, &br#"{ "value": 2 }"#[..]);
+    ///         let mut ticks = 1;
+    ///         while loader.update_tick().await.is_empty() {
+    ///             ticks += 1;
+    ///         }
+    ///         assert_eq!(ticks, 3);
+    ///
+    ///         let stats = loader.stats();
+    ///         assert_eq!(stats.update_calls, 5);
+    ///         assert_eq!(stats.updates_found, 1);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub async fn update_tick(&self) -> Vec<AssetId> {
+        let array = self.sources.snapshot();
+        let (tick, candidates) = self.updates.next_tick(&self.asset_cache, |serial| {
+            let index = array.slots.iter().position(|slot| slot.serial == serial)?;
+            let source = &array.sources[index];
+            source.supports_update().then(|| source.clone())
+        });
+
+        let results = futures::future::join_all(candidates.iter().map(|candidate| async {
+            let recording = self.sources.record(|| ReplayRequest::Update {
+                id: candidate.id,
+                version: candidate.version,
+            });
+            let result = candidate
+                .source
+                .update(candidate.id, candidate.version)
+                .await;
+            if let Some(recording) = recording {
+                recording.data(&result);
+            }
+            result
+        }))
+        .await;
+
+        let mut changed = Vec::new();
+        for (candidate, result) in candidates.iter().zip(results) {
+            let version = match result {
+                Ok(Some(data)) => {
+                    changed.push(candidate.id);
+                    Some(data.version)
+                }
+                Ok(None) => None,
+                Err(error) => {
+                    tracing::warn!("Failed to check asset {} for update: {error}", candidate.id);
+                    None
+                }
+            };
+            self.updates.finish(tick, candidate.id, version);
+        }
+
+        if !changed.is_empty() && !self.cascades.is_empty() {
+            self.reload_changed(&changed);
+        }
+        changed
+    }
+
+    /// Checks sources of all cached assets for newer data and reloads changed assets.
+    /// Returns ids of reloaded assets.
+    ///
+    /// Unlike [`Loader::update_tick`], calls [`Source::update`] for every loaded asset
+    /// of source that [supports updates](Source::supports_update) at once,
+    /// without limit per tick.
+    /// Assets that keep reporting no change are backed off the same way,
+    /// see [`LoaderBuilder::with_update_limits`].
+    ///
+    /// Assets with [cascade enabled](Loader::enable_cascade) are rebuilt and published,
+    /// calling reload hooks and reporting reload events,
+    /// see [`LoaderBuilder::register_reload_hook`].
+    /// Newer data of other assets is decoded and the asset becomes loaded again,
+    /// [`AssetHandle::poll_build`] builds it anew next time it is called.
+    /// Until the new data is decoded the old asset stays in the cache,
+    /// asset that fails to decode keeps the old value.
+    /// Handles that already got the asset with [`AssetHandle::poll_ready`] keep it.
+    /// Assets that depend on reloaded ones become stale.
+    ///
+    /// Sub-assets are not reloaded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::{Arc, Mutex};
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// #[derive(Clone, Asset)]
+    /// struct Pair {
+    ///     #[asset(external)]
+    ///     left: Number,
+    ///     #[asset(external)]
+    ///     right: Number,
+    /// }
+    ///
+    /// #[derive(Clone, Default)]
+    /// struct Events(Arc<Mutex<Vec<ReloadEvent>>>);
+    ///
+    /// impl ReloadSink for Events {
+    ///     fn reloaded(&self, event: &ReloadEvent) {
+    ///         self.0.lock().unwrap().push(event.clone());
+    ///     }
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// let id = AssetId::new(1).unwrap();
+    /// let pair = AssetId::new(2).unwrap();
+    /// source.insert(id, &br#"{ "value": 1 }"#[..]);
+    /// source.insert(pair, &br#"{ "left": 1, "right": 1 }"#[..]);
+    /// let loader = Loader::builder().with(source.clone()).build();
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// runtime.block_on(async {
+    ///     let mut handle = loader.load::<Number, _>(id);
+    ///     (&mut handle).await?;
+    ///     assert_eq!(handle.poll_build(&mut ()).unwrap()?.value, 1);
+    ///     let mut pair = loader.load::<Pair, _>(pair).await?;
+    ///     pair.build(&mut ())?;
+    ///     assert!(loader.poll_updates().await.is_empty());
+    ///
+    ///     // Unchanged asset is checked again after backoff.
+    ///     source.insert(id, &br#"{ "value": 2 }"#[..]);
+    ///     assert!(loader.poll_updates().await.is_empty());
+    ///     assert_eq!(loader.poll_updates().await, [id]);
+    ///     assert_eq!(handle.poll_build(&mut ()).unwrap()?.value, 2);
+    ///     assert!(pair.is_stale());
+    ///
+    ///     // Data that fails to decode keeps the old value.
+    ///     source.insert(id, &br#"{ "value": "three" }"#[..]);
+    ///     assert!(loader.poll_updates().await.is_empty());
+    ///     assert_eq!(handle.poll_build(&mut ()).unwrap()?.value, 2);
+    ///     Ok::<_, Error>(())
+    /// })?;
+    ///
+    /// // Assets with cascade enabled are published with reload hooks.
+    /// let events = Events::default();
+    /// source.insert(id, &br#"{ "value": 1 }"#[..]);
+    /// let loader = Loader::builder()
+    ///     .with(source.clone())
+    ///     .with_registered_reload_hook(|_: &Number, _: &Number| ReloadAction::Replace)
+    ///     .with_reload_events(events.clone())
+    ///     .build();
+    /// loader.enable_cascade::<Number, ()>(Arc::new(Mutex::new(())));
+    ///
+    /// runtime.block_on(async {
+    ///     loader.load::<Number, _>(id).await?.build(&mut ())?;
+    ///     source.insert(id, &br#"{ "value": 4 }"#[..]);
+    ///     assert_eq!(loader.poll_updates().await, [id]);
+    ///     assert_eq!(loader.load::<Number, _>(id).poll_ready().unwrap()?.value, 4);
+    ///     Ok::<_, Error>(())
+    /// })?;
+    ///
+    /// let events = events.0.lock().unwrap();
+    /// assert_eq!(events.len(), 1);
+    /// assert_eq!((events[0].id, events[0].outcome), (id, ReloadOutcome::Replaced));
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub async fn poll_updates(&self) -> Vec<AssetId> {
+        struct Check {
+            kind: KindKey,
+            id: AssetId,
+            version: u64,
+            source_serial: u64,
+        }
+
+        let array = self.sources.snapshot();
+        let (tick, candidates) = self.updates.full_tick(&self.asset_cache, |serial| {
+            let index = array.slots.iter().position(|slot| slot.serial == serial)?;
+            let source = &array.sources[index];
+            source.supports_update().then(|| source.clone())
+        });
+        if candidates.is_empty() {
+            return Vec::new();
+        }
+
+        // Shards are locked only while entries are collected.
+        let mut checks = Vec::new();
+        for shard in self.asset_cache.iter() {
+            shard.lock().retain(&mut |key, state| {
+                if let AssetState::Loaded { metadata, .. } | AssetState::Ready { metadata, .. } =
+                    state
+                {
+                    if candidates.iter().any(|c| c.id == key.id) {
+                        checks.push(Check {
+                            kind: key.kind,
+                            id: key.id,
+                            version: metadata.version,
+                            source_serial: metadata.source_serial,
+                        });
+                    }
+                }
+                true
+            });
+        }
+
+        // Same asset may be cached for several types, source is asked once.
+        let mut calls: Vec<(AssetId, u64, u64)> = Vec::new();
+        for check in &checks {
+            let key = (check.id, check.version, check.source_serial);
+            if !calls.contains(&key) {
+                calls.push(key);
+            }
+        }
+
+        let array = &array;
+        let results =
+            futures::future::join_all(calls.iter().map(|&(id, version, serial)| async move {
+                let index = array.slots.iter().position(|slot| slot.serial == serial)?;
+                let source = &array.sources[index];
+                if !source.supports_update() {
+                    return None;
+                }
+
+                let recording = self
+                    .sources
+                    .record(|| ReplayRequest::Update { id, version });
+                let result = source.update(id, version).await;
+                if let Some(recording) = recording {
+                    recording.data(&result);
+                }
+
+                match result {
+                    Ok(data) => {
+                        let data = data?;
+                        array.slots[index].updated(id, data.version);
+                        Some((index, data))
+                    }
+                    Err(error) => {
+                        tracing::warn!("Failed to check asset {id} for update: {error}");
+                        None
+                    }
+                }
+            }))
+            .await;
+
+        for candidate in &candidates {
+            let found = calls
+                .iter()
+                .zip(&results)
+                .find_map(|(&(id, ..), result)| match result {
+                    Some((_, data)) if id == candidate.id => Some(data.version),
+                    _ => None,
+                });
+            self.updates.finish(tick, candidate.id, found);
+        }
+
+        let mut reloaded = Vec::new();
+        let mut rebuilds = Vec::new();
+        for (&(id, version, serial), result) in calls.iter().zip(results) {
+            let Some((index, data)) = result else {
+                continue;
+            };
+
+            for check in checks
+                .iter()
+                .filter(|c| c.id == id && c.version == version && c.source_serial == serial)
+            {
+                // Assets with cascade enabled are built and published with reload hooks.
+                if self.cascades.get(check.kind).is_some() {
+                    rebuilds.push((check.kind, id));
+                    continue;
+                }
+
+                let Some(decode) = self.reloaders.get(check.kind) else {
+                    continue;
+                };
+
+                let metadata = AssetMetadata {
+                    version: data.version,
+                    source_index: index,
+                    source_label: array.slots[index].label.clone(),
+                    source_serial: serial,
+                    bytes_len: data.bytes.len(),
+                    properties: data.properties.clone(),
+                    stale: StaleFlag::default(),
+                    superseded: StaleFlag::default(),
+                };
+
+                // New data is held until built, as for regular loads.
+                let reserved = match &self.backpressure {
+                    None => None,
+                    Some(backpressure) => Some(
+                        backpressure
+                            .reserve(data.bytes.len(), LoadOptions::default().priority)
+                            .await,
+                    ),
+                };
+
+                let decoder = Loader {
+                    decoding: Some(id),
+                    decoding_properties: data.properties.clone(),
+                    ..self.detached()
+                };
+                let result = decode(data.bytes.clone().into_boxed(), &decoder).await;
+
+                let decoded = match result {
+                    Ok(decoded) => decoded,
+                    Err(error) => {
+                        tracing::warn!("Failed to decode reloaded asset {id}: {error:#}");
+                        continue;
+                    }
+                };
+
+                if self.replace_decoded(check.kind, id, version, decoded, metadata, reserved)
+                    && !reloaded.contains(&id)
+                {
+                    reloaded.push(id);
+                }
+            }
+        }
+
+        for (kind, id) in rebuilds {
+            if self.rebuild(kind, id).await && !reloaded.contains(&id) {
+                reloaded.push(id);
+            }
+        }
+
+        if !reloaded.is_empty() {
+            self.cascade_reloaded(&reloaded);
+        }
+        reloaded
+    }
+
+    /// Replaces loaded or ready asset of `version` with newly decoded one.
+    /// Returns `false` if the entry changed meanwhile.
+    fn replace_decoded(
+        &self,
+        kind: KindKey,
+        id: AssetId,
+        version: u64,
+        decoded: ErasedDecodedState,
+        metadata: AssetMetadata,
+        reserved: Option<Reservation>,
+    ) -> bool {
+        let key_hash = hash_id_key(kind, id, &self.random_state);
+        let shard = &self.asset_cache[key_hash as usize % self.asset_cache.len()];
+        let mut locked_shard = shard.lock();
+
+        let Entry::Occupied(mut entry) = locked_shard.entry(key_hash, |k| k.eq_key(kind, id))
+        else {
+            return false;
+        };
+
+        match entry.get_mut() {
+            AssetState::Loaded {
+                decoded: old_decoded,
+                metadata: old_metadata,
+                _reserved: old_reserved,
+                ..
+            } if old_metadata.version == version => {
+                debug_check(
+                    EntryKind::Asset,
+                    Some(EntryStatus::Loaded),
+                    Event::Reload,
+                    Some(EntryStatus::Loaded),
+                );
+                old_metadata.superseded.set();
+                *old_decoded = decoded;
+                *old_metadata = metadata;
+                *old_reserved = reserved;
+                true
+            }
+            AssetState::Ready {
+                metadata: old_metadata,
+                ..
+            } if old_metadata.version == version => {
+                debug_check(
+                    EntryKind::Asset,
+                    Some(EntryStatus::Ready),
+                    Event::Reload,
+                    Some(EntryStatus::Loaded),
+                );
+                old_metadata.superseded.set();
+                *entry.get_mut() = AssetState::Loaded {
+                    decoded,
+                    metadata,
+                    wakers: WakeOnDrop::new(),
+                    abort: AbortSignal::new(),
+                    #[cfg(feature = "tokio")]
+                    build_wait: self
+                        .build_wait_warning
+                        .map(|_| BuildWait::new(self.clock.clone())),
+                    _reserved: reserved,
+                };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns id of the asset with specified path and [`Asset::asset_name`]
+    /// if it is already resolved by [`Loader::lookup_path`]
+    /// or by loading asset of type registered under `target` name.
+    pub fn peek_path(&self, path: &str, target: &str) -> Option<AssetId> {
+        let resolved = self.resolve_path(path);
+        let path = &*resolved;
+
+        self.path_lookups
+            .peek(target, path)
+            .or_else(|| self.peek_typed_path(path, target))
+    }
+
+    /// Returns id resolved for the path by loading asset of type registered under `target` name.
+    fn peek_typed_path(&self, path: &str, target: &str) -> Option<AssetId> {
+        let kind_key = KindKey::dynamic(self.asset_names.resolve(target)?, 0);
+        let key_hash = hash_path_key(kind_key, path, &self.random_state);
+        let path_shard = &self.path_cache[key_hash as usize % self.path_cache.len()];
+
+        let mut locked_shard = path_shard.lock();
+        match locked_shard.entry(key_hash, |k| k.eq_key(kind_key, path)) {
+            Entry::Occupied(mut entry) => match entry.get() {
+                PathState::Loaded { id } => Some(*id),
+                _ => None,
+            },
+            Entry::Vacant(_) => None,
+        }
+    }
+
+    /// Returns ids of assets requested while decoding asset with specified id.
+    ///
+    /// Dependencies are recorded only for assets decoded by this loader.
+    pub fn dependencies(&self, id: AssetId) -> Vec<AssetId> {
+        match self.dependencies.lock().get(&id) {
+            None => Vec::new(),
+            Some(deps) => deps.clone(),
+        }
+    }
+
+    /// Records asset with specified id as dependency of currently decoded asset.
+    fn add_dependency(&self, id: AssetId) {
+        if let Some(parent) = self.decoding {
+            add_dependency(&self.dependencies, parent, id);
+        }
+    }
+
+    /// Loads raw asset data shared by sub-assets of the same artifact.
+    ///
+    /// Concurrent loads of the same artifact wait for the first one.
+    /// Data is kept for few recently loaded artifacts.
+    async fn load_artifact(
+        &self,
+        id: AssetId,
+        missing: &MissingWait,
+        abort: &AbortSignal,
+    ) -> Result<Option<Arc<Data>>, Error> {
+        let cell = {
+            let mut shared_data = self.shared_data.lock();
+            match shared_data.iter().find(|(cached, _)| *cached == id) {
+                Some((_, cell)) => cell.clone(),
+                None => {
+                    if shared_data.len() >= SHARED_DATA_CAPACITY {
+                        shared_data.pop_front();
+                    }
+                    let cell = Arc::new(OnceCell::new());
+                    shared_data.push_back((id, cell.clone()));
+                    cell
+                }
+            }
+        };
+
+        let data = self.load_once(&cell, id, missing, abort).await?;
+        Ok(data.map(|(data, _)| data))
+    }
+
+    /// Loads raw asset data fetched by any tenant recently.
+    ///
+    /// Concurrent loads of the same asset by tenants wait for the first one.
+    async fn load_fetched(
+        &self,
+        tenant: &Tenant,
+        id: AssetId,
+        missing: &MissingWait,
+        abort: &AbortSignal,
+    ) -> Result<Option<Arc<Data>>, Error> {
+        let cell = self.tenancy.fetches.cell(id, self.clock.now());
+        let data = self.load_once(&cell, id, missing, abort).await?;
+        Ok(data.map(|(data, reused)| {
+            if reused {
+                tenant.shared_fetches.fetch_add(1, Ordering::Relaxed);
+            }
+            data
+        }))
+    }
+
+    /// Loads raw asset data into the cell unless it is loaded already.
+    /// Returns data and whether it was loaded by another call.
+    async fn load_once(
+        &self,
+        cell: &OnceCell<Arc<Data>>,
+        id: AssetId,
+        missing: &MissingWait,
+        abort: &AbortSignal,
+    ) -> Result<Option<(Arc<Data>, bool)>, Error> {
+        let mut reused = true;
+
+        // Missing asset and errors are not cached.
+        let result = cell
+            .get_or_try_init(|| {
+                reused = false;
+                async {
+                    match self.sources.load(id, missing, abort).await {
+                        Ok(Some(data)) => Ok(Arc::new(data)),
+                        Ok(None) => Err(None),
+                        Err(error) => Err(Some(error)),
+                    }
+                }
+            })
+            .await;
+
+        match result {
+            Ok(data) => Ok(Some((data.clone(), reused))),
+            Err(None) => Ok(None),
+            Err(Some(error)) => Err(error),
+        }
+    }
+
+    /// Returns `true` if asset was loaded from source that was removed since.
+    fn is_orphaned(&self, state: &AssetState) -> bool {
+        match state {
+            AssetState::Loaded { metadata, .. } | AssetState::Ready { metadata, .. } => {
+                self.sources.is_removed(metadata.source_serial)
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks that asset is not requested as another type.
+    /// Sub-assets of the same artifact are not checked.
+    fn check_kind<K: AssetKind>(&self, kind: &K, id: AssetId) -> Result<(), Error> {
+        if kind.shares_data() {
+            return Ok(());
+        }
+        self.asset_kinds.check(kind.key(), kind.name(), id)
+    }
 
-pub(crate) enum PathState {
-    /// Not yet loaded asset.
-    Unloaded {
-        asset_wakers: WakeOnDrop,
-        id_wakers: WakeOnDrop,
-    },
+    /// Returns sequence number for new cache entry.
+    fn next_sequence(&self) -> u64 {
+        self.sequence.fetch_add(1, Ordering::Relaxed)
+    }
 
-    /// Asset is loaded. Lookup main entry by this id.
-    Loaded { id: AssetId },
+    /// Returns loader instance for tasks that are not decoding any asset.
+    fn detached(&self) -> Loader {
+        Loader {
+            decoding: None,
+            decoding_path: None,
+            decoding_properties: AssetProperties::new(),
+            decoding_format: None,
+            abort: None,
+            ..self.clone()
+        }
+    }
 
-    /// All sources reported that asset is missing.
-    Missing,
-}
+    /// Spawns task that [`Loader::wait_idle`] waits for.
+    pub(crate) fn spawn_in_flight(&self, task: impl Future<Output = ()> + Send + 'static) {
+        let guard = InFlightGuard::new(&self.in_flight);
+        self.spawn(async move {
+            let _guard = guard;
+            task.await;
+        });
+    }
 
-impl Loader {
-    /// Returns [`LoaderBuilder`] instance
-    pub fn builder() -> LoaderBuilder {
-        LoaderBuilder::new()
+    /// Spawns loading task on tokio runtime
+    /// or queues it for [`Loader::pump`] if `tokio` feature is disabled.
+    ///
+    /// Outside of tokio runtime task is spawned on the runtime of the first spawned task,
+    /// or on background runtime if there was none.
+    pub(crate) fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        #[cfg(feature = "tokio")]
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                self.runtime.get_or_init(|| runtime.clone());
+                runtime.spawn(task);
+            }
+            Err(_) => {
+                self.runtime
+                    .get_or_init(crate::blocking::background_runtime)
+                    .spawn(task);
+            }
+        }
+
+        #[cfg(not(feature = "tokio"))]
+        self.tasks.spawn(Box::pin(task));
     }
 
-    pub fn load_with_id<A: Asset>(&self, id: AssetId) -> AssetHandle<A> {
+    /// Drives loading tasks of this loader.
+    ///
+    /// Available only when `tokio` feature is disabled.
+    /// Loading tasks are not spawned then and make progress only inside this method.
+    /// Polls tasks that are ready until all of them wait for sources,
+    /// and unloads released assets if automatic unloading is enabled.
+    /// Call it periodically, for example once per frame.
+    ///
+    /// Returns number of unfinished loading tasks.
+    ///
+    /// Handles are resolved as tasks complete,
+    /// they can be polled with any executor or checked without blocking.
+    #[cfg(not(feature = "tokio"))]
+    pub fn pump(&self) -> usize {
+        let pending = self.tasks.pump();
+        if let Some(auto_unload) = &self.auto_unload {
+            auto_unload.sweep();
+        }
+        pending
+    }
+
+    /// Returns share in the asset entry for new handle
+    /// if automatic unloading is enabled.
+    fn retain(
+        &self,
+        kind: KindKey,
+        id: AssetId,
+        shard: &AssetShard,
+        key_hash: u64,
+    ) -> Option<Retain> {
+        let auto_unload = self.auto_unload.as_ref()?;
+        #[cfg(feature = "tokio")]
+        auto_unload.start_sweeping();
+        Some(auto_unload.retain(TypeKey::new(kind, id), key_hash, shard))
+    }
+
+    pub(crate) fn load_kind_with_id<K: AssetKind>(
+        &self,
+        kind: K,
+        id: AssetId,
+        options: LoadOptions,
+    ) -> Handle {
+        let kind_key = kind.key();
+        self.add_dependency(id);
+
         // Hash asset key.
-        let key_hash = hash_id_key::<A>(id, &self.random_state);
+        let key_hash = hash_id_key(kind_key, id, &self.random_state);
 
         // Use asset key hash to pick a shard.
         // It will always pick same shard for same key.
         let shards_len = self.asset_cache.len();
         let shard = &self.asset_cache[key_hash as usize % shards_len];
 
+        // Retain before lookup, so that entry can't be unloaded in between.
+        let retain = self.retain(kind_key, id, shard, key_hash);
+
         // Lock picked shard.
         let mut locked_shard = shard.lock();
 
         // Find an entry into sharded hashmap.
-        let asset_entry = locked_shard
-            .raw_entry_mut()
-            .from_hash(key_hash, |k| k.eq_key::<A>(id));
+        let asset_entry = locked_shard.entry(key_hash, |k| k.eq_key(kind_key, id));
 
         match asset_entry {
-            RawEntryMut::Occupied(entry) => {
+            Entry::Occupied(mut entry) => {
+                let restart = match entry.get() {
+                    // Asset may be available now. Look it up again.
+                    AssetState::Missing => options.retries_missing(),
+                    // Cancelled load is not yet reverted. Start new one.
+                    AssetState::Unloaded { abort, .. } => abort.is_aborted(),
+                    _ => false,
+                };
+
+                if restart {
+                    let from = entry.get().status();
+                    let event = match from {
+                        EntryStatus::Missing => Event::Retry,
+                        _ => Event::Restart,
+                    };
+                    debug_check(
+                        EntryKind::Asset,
+                        Some(from),
+                        event,
+                        Some(EntryStatus::Pending),
+                    );
+
+                    let abort = AbortSignal::new();
+                    *entry.get_mut() = AssetState::Unloaded {
+                        wakers: WakeOnDrop::new(),
+                        abort: abort.clone(),
+                    };
+                    drop(locked_shard);
+
+                    return Handle {
+                        retain,
+                        ..self.spawn_load(kind, shard, key_hash, id, options, abort)
+                    };
+                }
+
                 // Already queried. See status.
                 match entry.get() {
-                    AssetState::Unloaded { .. } => AssetHandle::new(Handle {
-                        type_id: TypeId::of::<A>(),
+                    AssetState::Unloaded { abort, .. } => Handle {
+                        kind: kind_key,
                         path: None,
                         id: Some(id),
+                        retain,
                         state: State::Loading {
                             key_hash,
                             shard: shard.clone(),
+                            interest: Some(abort.interest()),
                         },
-                    }),
-                    AssetState::Error { error } => AssetHandle::new(Handle {
-                        type_id: TypeId::of::<A>(),
+                    },
+                    AssetState::Error { error } => Handle {
+                        kind: kind_key,
                         path: None,
                         id: Some(id),
+                        retain,
                         state: State::Error {
                             error: error.clone(),
                         },
-                    }),
-                    AssetState::Missing => AssetHandle::new(Handle {
-                        type_id: TypeId::of::<A>(),
+                    },
+                    AssetState::Missing => Handle {
+                        kind: kind_key,
                         path: None,
                         id: Some(id),
-                        state: State::Missing,
-                    }),
-                    AssetState::Loaded { .. } => AssetHandle::new(Handle {
-                        type_id: TypeId::of::<A>(),
+                        retain,
+                        state: State::missing(Some(id), None),
+                    },
+                    AssetState::Loaded {
+                        metadata, abort, ..
+                    } => Handle {
+                        kind: kind_key,
                         path: None,
                         id: Some(id),
+                        retain,
                         state: State::Loaded {
                             key_hash,
                             shard: shard.clone(),
+                            metadata: metadata.clone(),
+                            interest: Some(abort.interest()),
                         },
-                    }),
-                    AssetState::Ready { asset, .. } => AssetHandle::new(Handle {
-                        type_id: TypeId::of::<A>(),
+                    },
+                    AssetState::Ready { asset, metadata } => Handle {
+                        kind: kind_key,
                         path: None,
                         id: Some(id),
+                        retain,
                         state: State::Ready {
+                            key_hash,
+                            shard: shard.clone(),
                             asset: asset.clone(),
+                            metadata: metadata.clone(),
                         },
-                    }),
+                    },
                 }
             }
-            RawEntryMut::Vacant(entry) => {
-                let asset_key = TypeKey::new::<A>(id);
+            Entry::Vacant(entry) => {
+                let asset_key = TypeKey::new(kind_key, id).with_sequence(self.next_sequence());
+
+                if let Err(error) = self.check_kind(&kind, id) {
+                    debug_check(
+                        EntryKind::Asset,
+                        None,
+                        Event::Deny,
+                        Some(EntryStatus::Error),
+                    );
+                    entry.insert(
+                        asset_key,
+                        AssetState::Error {
+                            error: error.clone(),
+                        },
+                    );
+                    drop(locked_shard);
+
+                    return Handle {
+                        kind: kind_key,
+                        path: None,
+                        id: Some(id),
+                        retain,
+                        state: State::Error { error },
+                    };
+                }
 
                 // Register query
-                let _ = entry.insert_hashed_nocheck(
-                    key_hash,
+                debug_check(
+                    EntryKind::Asset,
+                    None,
+                    Event::Request,
+                    Some(EntryStatus::Pending),
+                );
+                let abort = AbortSignal::new();
+                entry.insert(
                     asset_key,
                     AssetState::Unloaded {
                         wakers: WakeOnDrop::new(),
+                        abort: abort.clone(),
                     },
                 );
                 drop(locked_shard);
 
-                let shard = shard.clone();
+                Handle {
+                    retain,
+                    ..self.spawn_load(kind, shard, key_hash, id, options, abort)
+                }
+            }
+        }
+    }
 
-                let handle = AssetHandle::new(Handle {
-                    type_id: TypeId::of::<A>(),
-                    path: None,
-                    id: Some(id),
-                    state: State::Loading {
-                        key_hash,
-                        shard: shard.clone(),
-                    },
-                });
+    /// Spawns task to load asset with unloaded state.
+    fn spawn_load<K: AssetKind>(
+        &self,
+        kind: K,
+        shard: &AssetShard,
+        key_hash: u64,
+        id: AssetId,
+        options: LoadOptions,
+        abort: AbortSignal,
+    ) -> Handle {
+        let shard = shard.clone();
 
-                let loader = self.clone();
-                tokio::spawn(
-                    async move {
-                        load_asset_task::<A>(&loader, shard, key_hash, id).await;
-                    }
-                    .in_current_span(),
-                );
+        let handle = Handle {
+            kind: kind.key(),
+            path: None,
+            id: Some(id),
+            retain: None,
+            state: State::Loading {
+                key_hash,
+                shard: shard.clone(),
+                interest: Some(abort.interest()),
+            },
+        };
 
-                handle
+        let loader = self.detached();
+        let missing = MissingWait::new(&options, &*self.clock);
+        let priority = options.priority;
+        let guard = InFlightGuard::new(&self.in_flight);
+        self.spawn(
+            async move {
+                let _guard = guard;
+                load_asset_task(
+                    &loader, kind, shard, key_hash, id, None, missing, priority, abort,
+                )
+                .await;
             }
-        }
+            .in_current_span(),
+        );
+
+        handle
     }
 
-    /// Load asset with specified key (path or id) and returns handle
-    /// that can be used to access assets once it is loaded.
-    ///
-    /// If asset was previously requested it will not be re-loaded,
-    /// but handle to shared state will be returned instead,
-    /// even if first load was not successful or different format was used.
-    pub fn load<'a, A, K>(&self, key: K) -> AssetHandle<A>
-    where
-        A: Asset,
-        K: Into<Key<'a>>,
-    {
-        match key.into() {
+    pub(crate) fn load_kind<K: AssetKind>(
+        &self,
+        kind: K,
+        key: Key<'_>,
+        options: LoadOptions,
+    ) -> Handle {
+        let kind_key = kind.key();
+
+        match key {
             Key::Path(path) => {
+                // Collapse aliases into single path entry.
+                let resolved = self.resolve_path(path);
+                let path = &*resolved;
+
                 // Hash asset path key.
-                let mut hasher = self.random_state.build_hasher();
-                hash_path_key::<A, _>(path, &mut hasher);
-                let key_hash = hasher.finish();
+                let key_hash = hash_path_key(kind_key, path, &self.random_state);
 
                 // Use asset key hash to pick a shard.
                 // It will always pick same shard for same key.
@@ -322,163 +6612,505 @@ impl Loader {
                 let mut locked_shard = path_shard.lock();
 
                 // Find an entry into sharded hashmap.
-                let raw_entry = locked_shard
-                    .raw_entry_mut()
-                    .from_hash(key_hash, |k| k.eq_key::<A>(path));
+                let raw_entry = locked_shard.entry(key_hash, |k| k.eq_key(kind_key, path));
 
                 match raw_entry {
-                    RawEntryMut::Occupied(entry) => {
-                        // Already queried. See status.
-
+                    Entry::Occupied(mut entry) => {
                         let path_key = entry.key().clone();
-                        match entry.get() {
-                            PathState::Unloaded { .. } => {
+
+                        if options.retries_missing() && matches!(entry.get(), PathState::Missing) {
+                            // Asset may be available now. Look it up again.
+                            debug_check(
+                                EntryKind::Path,
+                                Some(EntryStatus::Missing),
+                                Event::Retry,
+                                Some(EntryStatus::Pending),
+                            );
+                            *entry.get_mut() = PathState::Unloaded {
+                                wakers: PathWakers::new(),
+                                dependents: self.decoding.into_iter().collect(),
+                            };
+                            drop(locked_shard);
+
+                            return self.spawn_find(kind, path_shard, key_hash, path_key, options);
+                        }
+
+                        // Already queried. See status.
+                        match entry.into_mut() {
+                            PathState::Unloaded { dependents, .. } => {
+                                dependents.extend(self.decoding);
                                 drop(locked_shard);
 
-                                AssetHandle::new(Handle {
-                                    type_id: TypeId::of::<A>(),
+                                Handle {
+                                    kind: kind_key,
                                     path: Some(path_key.path),
                                     id: None,
+                                    retain: None,
                                     state: State::Searching {
                                         key_hash,
                                         path_shard: path_shard.clone(),
                                         asset_shards: self.asset_cache.clone(),
                                         random_state: self.random_state.clone(),
+                                        auto_unload: self.auto_unload.clone(),
                                     },
-                                })
+                                }
                             }
                             PathState::Loaded { id } => {
                                 let id = *id;
                                 drop(locked_shard);
 
-                                self.load_with_id(id)
+                                self.load_kind_with_id(kind, id, options)
                             }
-                            PathState::Missing => AssetHandle::new(Handle {
-                                type_id: TypeId::of::<A>(),
+                            PathState::Missing => Handle {
+                                kind: kind_key,
                                 path: Some(path_key.path.clone()),
                                 id: None,
-                                state: State::Missing,
-                            }),
+                                retain: None,
+                                state: State::missing(None, Some(path_key.path.clone())),
+                            },
                         }
                     }
-                    RawEntryMut::Vacant(entry) => {
-                        let path_key = PathKey::new::<A>(path.into());
-                        let path = path_key.path.clone();
+                    Entry::Vacant(entry) => {
+                        let path_key = PathKey::new(kind_key, path.into(), self.next_sequence());
 
                         // Register query
-                        let _ = entry.insert_hashed_nocheck(
-                            key_hash,
+                        debug_check(
+                            EntryKind::Path,
+                            None,
+                            Event::Request,
+                            Some(EntryStatus::Pending),
+                        );
+                        entry.insert(
                             path_key.clone(),
                             PathState::Unloaded {
-                                asset_wakers: WakeOnDrop::new(),
-                                id_wakers: WakeOnDrop::new(),
+                                wakers: PathWakers::new(),
+                                dependents: self.decoding.into_iter().collect(),
                             },
                         );
                         drop(locked_shard);
 
-                        let path_shard = path_shard.clone();
-
-                        let handle = AssetHandle::new(Handle {
-                            type_id: TypeId::of::<A>(),
-                            path: Some(path_key.path),
-                            id: None,
-                            state: State::Searching {
-                                key_hash,
-                                path_shard: path_shard.clone(),
-                                asset_shards: self.asset_cache.clone(),
-                                random_state: self.random_state.clone(),
-                            },
-                        });
-
-                        let loader = self.clone();
-                        tokio::spawn(
-                            async move {
-                                find_asset_task::<A>(&loader, path_shard, key_hash, &path).await;
-                            }
-                            .in_current_span(),
-                        );
-
-                        handle
+                        self.spawn_find(kind, path_shard, key_hash, path_key, options)
                     }
                 }
             }
-            Key::Id(id) => self.load_with_id(id),
+            Key::Id(id) => self.load_kind_with_id(kind, id, options),
         }
     }
+
+    /// Spawns task to find asset with unloaded path state.
+    fn spawn_find<K: AssetKind>(
+        &self,
+        kind: K,
+        path_shard: &PathShard,
+        key_hash: u64,
+        path_key: PathKey,
+        options: LoadOptions,
+    ) -> Handle {
+        let path_shard = path_shard.clone();
+        let path = path_key.path.clone();
+
+        let handle = Handle {
+            kind: kind.key(),
+            path: Some(path_key.path),
+            id: None,
+            retain: None,
+            state: State::Searching {
+                key_hash,
+                path_shard: path_shard.clone(),
+                asset_shards: self.asset_cache.clone(),
+                random_state: self.random_state.clone(),
+                auto_unload: self.auto_unload.clone(),
+            },
+        };
+
+        let loader = self.detached();
+        let missing = MissingWait::new(&options, &*self.clock);
+        let priority = options.priority;
+        let guard = InFlightGuard::new(&self.in_flight);
+        self.spawn(
+            async move {
+                let _guard = guard;
+                find_asset_task(&loader, kind, path_shard, key_hash, path, missing, priority).await;
+            }
+            .in_current_span(),
+        );
+
+        handle
+    }
 }
 
-async fn load_asset_task<A: Asset>(loader: &Loader, shard: AssetShard, key_hash: u64, id: AssetId) {
-    let new_state = match load_asset(&loader.sources, id).await {
-        Err(error) => AssetState::Error { error },
+#[allow(clippy::too_many_arguments)]
+async fn load_asset_task<K: AssetKind>(
+    loader: &Loader,
+    kind: K,
+    shard: AssetShard,
+    key_hash: u64,
+    id: AssetId,
+    path: Option<Arc<str>>,
+    missing: MissingWait,
+    priority: i32,
+    abort: AbortSignal,
+) {
+    let kind_key = kind.key();
+    loader.reloaders.register(kind_key, || kind.reload_decode());
+
+    let raw = async {
+        if kind.shares_data() {
+            let result = loader.load_artifact(id, &missing, &abort).await;
+            result.map(|data| data.map(RawData::Shared))
+        } else if let Some(tenant) = &loader.tenant {
+            let result = loader.load_fetched(tenant, id, &missing, &abort).await;
+            result.map(|data| data.map(RawData::Shared))
+        } else {
+            let result = loader.sources.load(id, &missing, &abort).await;
+            result.map(|data| data.map(RawData::Owned))
+        }
+    };
+
+    // Aborted load does not wait for sources that hang.
+    let raw = match select(pin!(raw), pin!(abort.aborted())).await {
+        Either::Left((raw, _)) => raw,
+        Either::Right(_) => Err(Error::new(Cancelled { id }).with_code(ErrorCode::Cancelled)),
+    };
+
+    let new_state = match raw {
+        Err(error) => AssetState::Error {
+            error: error
+                .or_code(ErrorCode::SourceIo)
+                .with_stage(ErrorStage::SourceLoad),
+        },
         Ok(None) => AssetState::Missing,
-        Ok(Some(data)) => {
-            let result = A::decode(data.bytes, loader).await;
+        Ok(Some(raw)) => 'decode: {
+            // Only raw bytes are held while builds catch up.
+            let reserved = match &loader.backpressure {
+                None => None,
+                Some(backpressure) => {
+                    let reserve = backpressure.reserve(raw.data().bytes.len(), priority);
+                    match select(pin!(reserve), pin!(abort.aborted())).await {
+                        Either::Left((reserved, _)) => Some(reserved),
+                        Either::Right(_) => {
+                            break 'decode AssetState::Error {
+                                error: Error::new(Cancelled { id }).with_code(ErrorCode::Cancelled),
+                            }
+                        }
+                    }
+                }
+            };
+
+            let data = raw.data();
+            let metadata = AssetMetadata {
+                version: data.version,
+                source_index: data.source,
+                source_label: data.slot.label.clone(),
+                source_serial: data.slot.serial,
+                bytes_len: data.bytes.len(),
+                properties: data.properties.clone(),
+                stale: StaleFlag::default(),
+                superseded: StaleFlag::default(),
+            };
+            let decoder = Loader {
+                decoding: Some(id),
+                decoding_path: path.clone(),
+                decoding_properties: data.properties.clone(),
+                decoding_format: None,
+                abort: Some(abort.clone()),
+                ..loader.clone()
+            };
+            let decode_cache = loader
+                .decode_cache
+                .as_ref()
+                .filter(|_| kind.caches_decoded());
+            let version = data.version;
+            let cached = decode_cache
+                .and_then(|cache| cache.get(kind.name(), id, version))
+                .and_then(|bytes| kind.decode_cached(&bytes));
+
+            let bytes_len = metadata.bytes_len;
+            let start = loader.decode_stats.as_ref().map(|_| loader.clock.now());
+
+            let result = match (cached, raw) {
+                (Some(decoded), _) => Ok(decoded),
+                (None, RawData::Owned(data)) => {
+                    kind.decode(data.bytes.into_boxed(), &decoder).await
+                }
+                (None, RawData::Shared(data)) => kind.decode_shared(&data.bytes, &decoder).await,
+            };
+
+            if let (Some(stats), Some(start)) = (&loader.decode_stats, start) {
+                stats.record(kind.name(), loader.clock.now() - start, bytes_len);
+            }
+
+            if let (Some(cache), Ok(decoded)) = (decode_cache, &result) {
+                if let Some(bytes) = kind.encode_decoded(decoded) {
+                    cache.put(kind.name(), id, version, &bytes);
+                }
+            }
 
             match result {
-                Err(err) => AssetState::Error {
-                    error: Error::new(err),
-                },
-                Ok(decoded) => AssetState::Loaded {
-                    decoded: Arc::new(spin::Mutex::new(Some(decoded))),
-                    version: data.version,
-                    source: data.source,
-                    wakers: WakeOnDrop::new(),
-                },
+                Err(error) => {
+                    let (stage, code) = decode_stage(&error);
+                    AssetState::Error {
+                        error: error.or_code(code).with_stage(stage),
+                    }
+                }
+                Ok(decoded) => {
+                    if let Some(usage) = &loader.usage {
+                        usage.record(kind.name(), id, decoder.decoding_path.as_ref());
+                    }
+
+                    AssetState::Loaded {
+                        decoded,
+                        metadata,
+                        wakers: WakeOnDrop::new(),
+                        abort: abort.clone(),
+                        #[cfg(feature = "tokio")]
+                        build_wait: loader
+                            .build_wait_warning
+                            .map(|_| BuildWait::new(loader.clock.clone())),
+                        _reserved: reserved,
+                    }
+                }
             }
         }
     };
 
+    // Load that failed after abort is cancelled.
+    let cancelled = abort.is_aborted() && !matches!(new_state, AssetState::Loaded { .. });
+
+    // Failure that no handle waits for is recorded.
+    if !cancelled && !abort.has_interest() {
+        let error = match &new_state {
+            AssetState::Error { error } => Some(error.clone()),
+            AssetState::Missing => Some(
+                NotFound {
+                    id: Some(id),
+                    path: path.clone(),
+                }
+                .into_error(),
+            ),
+            _ => None,
+        };
+        if let Some(error) = error {
+            loader
+                .failures
+                .record(kind.name(), path.clone(), Some(id), error);
+        }
+    }
+
     // Change state and notify waters.
     let mut locked_shard = shard.lock();
 
-    let entry = locked_shard
-        .raw_entry_mut()
-        .from_hash(key_hash, |k| k.eq_key::<A>(id));
+    let entry = locked_shard.entry(key_hash, |k| k.eq_key(kind_key, id));
 
     match entry {
-        RawEntryMut::Vacant(_) => {
-            unreachable!("No other code could change the state")
+        Entry::Vacant(_) => {
+            // Entry was evicted by cache backend.
+            // Waiting handles were woken and resolve with cancelled error.
         }
-        RawEntryMut::Occupied(mut entry) => {
-            let entry = entry.get_mut();
-            match entry {
-                AssetState::Unloaded { .. } => {
-                    *entry = new_state;
+        Entry::Occupied(mut entry) => match entry.get_mut() {
+            AssetState::Unloaded { abort: current, .. } if current.ptr_eq(&abort) => {
+                if cancelled {
+                    // Revert to vacant so that next request starts loading anew.
+                    debug_check(
+                        EntryKind::Asset,
+                        Some(EntryStatus::Pending),
+                        Event::Cancel,
+                        None,
+                    );
+                    entry.remove();
+                } else {
+                    let event = match &new_state {
+                        AssetState::Loaded { .. } => Event::Decoded,
+                        AssetState::Missing => Event::NotFound,
+                        _ => Event::Failed,
+                    };
+                    debug_check(
+                        EntryKind::Asset,
+                        Some(EntryStatus::Pending),
+                        event,
+                        Some(new_state.status()),
+                    );
+
+                    #[cfg(feature = "tokio")]
+                    if let (AssetState::Loaded { .. }, Some(threshold)) =
+                        (&new_state, loader.build_wait_warning)
+                    {
+                        let shard = shard.clone();
+                        let name = kind.name().to_owned();
+                        let clock = loader.clock.clone();
+                        loader.spawn(async move {
+                            warn_unbuilt(shard, key_hash, kind_key, id, name, threshold, clock)
+                                .await;
+                        });
+                    }
+                    *entry.get_mut() = new_state;
+                }
+            }
+            _ => {
+                // Load was restarted after cancellation or eviction.
+            }
+        },
+    }
+}
+
+/// Time a future started waiting for the asset to be built.
+#[cfg(feature = "tokio")]
+pub(crate) struct BuildWait {
+    clock: Arc<dyn Clock>,
+    since: Option<std::time::Instant>,
+}
+
+#[cfg(feature = "tokio")]
+impl BuildWait {
+    fn new(clock: Arc<dyn Clock>) -> Self {
+        BuildWait { clock, since: None }
+    }
+
+    /// Records that a future waits for the asset, unless one already does.
+    pub(crate) fn start(&mut self) {
+        self.since.get_or_insert_with(|| self.clock.now());
+    }
+}
+
+/// Warns once if asset stays awaited but not built for longer than `threshold`.
+#[cfg(feature = "tokio")]
+async fn warn_unbuilt(
+    shard: AssetShard,
+    key_hash: u64,
+    kind: KindKey,
+    id: AssetId,
+    name: String,
+    threshold: Duration,
+    clock: Arc<dyn Clock>,
+) {
+    let mut wait = threshold;
+    loop {
+        clock.sleep(wait).await;
+
+        let mut locked_shard = shard.lock();
+        let Entry::Occupied(mut entry) = locked_shard.entry(key_hash, |k| k.eq_key(kind, id))
+        else {
+            return;
+        };
+
+        let AssetState::Loaded { build_wait, .. } = entry.get() else {
+            return;
+        };
+
+        match build_wait.as_ref().and_then(|wait| wait.since) {
+            None => wait = threshold,
+            Some(since) => {
+                let waited = clock.now() - since;
+                if waited < threshold {
+                    wait = threshold - waited;
+                    continue;
                 }
-                _ => unreachable!("No other code could change the state"),
+
+                drop(locked_shard);
+                tracing::warn!(
+                    "Asset '{name}' with id '{id}' is awaited for {waited:?} but is not built. \
+                     Something must build it with `AssetHandle::poll_build`, a driver or \
+                     `AssetFuture::ready_or_build`, otherwise the future never resolves"
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// Returns metadata of published asset that replaces no loaded asset.
+/// Decision of the reload hook about staged asset.
+struct Reload {
+    /// Value the hook was called with.
+    old: Arc<dyn Any + Send + Sync>,
+    name: AssetName,
+    action: ErasedAction,
+}
+
+fn published_metadata() -> AssetMetadata {
+    AssetMetadata {
+        version: 0,
+        source_index: 0,
+        source_label: None,
+        source_serial: 0,
+        bytes_len: 0,
+        properties: AssetProperties::new(),
+        stale: StaleFlag::default(),
+        superseded: StaleFlag::default(),
+    }
+}
+
+/// Returns stage and code of the decoding error.
+/// Schema mismatch may be wrapped into decoding error of the asset.
+fn decode_stage(error: &Error) -> (ErrorStage, ErrorCode) {
+    let mut decode_error = error.downcast_ref::<DecodeError>();
+    let mut source = std::error::Error::source(error);
+    loop {
+        if let Some(DecodeError::SchemaMismatch { .. }) = decode_error {
+            return (ErrorStage::Validate, ErrorCode::SchemaMismatch);
+        }
+        match source {
+            None => return (ErrorStage::Decode, ErrorCode::DecodeFailed),
+            Some(error) => {
+                decode_error = error.downcast_ref();
+                source = error.source();
             }
         }
     }
 }
 
 // Task to find asset using path.
-async fn find_asset_task<A: Asset>(
+async fn find_asset_task<K: AssetKind>(
     loader: &Loader,
+    kind: K,
     path_shard: PathShard,
     key_hash: u64,
-    path: &str,
+    path: Arc<str>,
+    missing: MissingWait,
+    priority: i32,
 ) {
-    let opt = find_asset::<A>(&loader.sources, path).await;
+    let kind_key = kind.key();
+    let opt = loader.sources.find(kind.name(), &path, &missing).await;
     match opt {
         None => {
             // Asset not found. Change state and notify waters.
             let mut locked_shard = path_shard.lock();
 
-            let entry = locked_shard
-                .raw_entry_mut()
-                .from_hash(key_hash, |k| k.eq_key::<A>(path));
+            let entry = locked_shard.entry(key_hash, |k| k.eq_key(kind_key, &path));
 
             match entry {
-                RawEntryMut::Vacant(_) => {
-                    unreachable!("No other code could change the state")
+                Entry::Vacant(_) => {
+                    // Entry was evicted by cache backend.
                 }
-                RawEntryMut::Occupied(mut entry) => {
+                Entry::Occupied(mut entry) => {
                     let entry = entry.get_mut();
                     match entry {
-                        PathState::Unloaded { .. } => {
+                        PathState::Unloaded { wakers, .. } => {
+                            // Failure that no handle waits for is recorded.
+                            if wakers.is_empty() {
+                                let error = NotFound {
+                                    id: None,
+                                    path: Some(path.clone()),
+                                }
+                                .into_error();
+                                loader.failures.record(
+                                    kind.name(),
+                                    Some(path.clone()),
+                                    None,
+                                    error,
+                                );
+                            }
+                            debug_check(
+                                EntryKind::Path,
+                                Some(EntryStatus::Pending),
+                                Event::NotFound,
+                                Some(EntryStatus::Missing),
+                            );
                             *entry = PathState::Missing;
                         }
-                        _ => unreachable!("No other code could change the state"),
+                        _ => {
+                            // Search was restarted after eviction and finished first.
+                        }
                     }
                 }
             }
@@ -488,37 +7120,54 @@ async fn find_asset_task<A: Asset>(
 
             let asset_shard;
             let asset_key_hash;
+            let abort = AbortSignal::new();
             {
-                // Taking wakers from path state
-                // and either moving them to asset state
-                // or waking them.
-                let mut moving_wakers = WakeOnDrop::new();
+                // Taking wakers from path state.
+                // Wakers waiting for id are woken,
+                // others are moved to asset state or woken
+                // if asset state would not change anymore.
+                let mut load_wakers = WakeOnDrop::new();
+                let mut ready_wakers = WakeOnDrop::new();
 
                 let mut locked_shard = path_shard.lock();
 
-                let entry = locked_shard
-                    .raw_entry_mut()
-                    .from_hash(key_hash, |k| k.eq_key::<A>(path));
+                let entry = locked_shard.entry(key_hash, |k| k.eq_key(kind_key, &path));
 
                 match entry {
-                    RawEntryMut::Vacant(_) => {
-                        unreachable!("No other code could change the state")
+                    Entry::Vacant(_) => {
+                        // Entry was evicted by cache backend.
+                        // Waiting handles were woken and resolve with cancelled error.
+                        return;
                     }
-                    RawEntryMut::Occupied(mut entry) => {
+                    Entry::Occupied(mut entry) => {
                         let state = entry.get_mut();
                         match state {
-                            PathState::Unloaded { asset_wakers, .. } => {
-                                // Decide what to do with asset wakers later.
-                                moving_wakers.append(&mut asset_wakers.vec);
+                            PathState::Unloaded {
+                                wakers, dependents, ..
+                            } => {
+                                wakers.split_asset_wakers(&mut load_wakers, &mut ready_wakers);
+
+                                for parent in dependents.drain(..) {
+                                    add_dependency(&loader.dependencies, parent, id);
+                                }
+                                debug_check(
+                                    EntryKind::Path,
+                                    Some(EntryStatus::Pending),
+                                    Event::Found,
+                                    Some(EntryStatus::Loaded),
+                                );
                                 *state = PathState::Loaded { id };
                             }
-                            _ => unreachable!("No other code could change the state"),
+                            _ => {
+                                // Search was restarted after eviction and finished first.
+                                return;
+                            }
                         }
                     }
                 }
 
                 // Hash asset key.
-                asset_key_hash = hash_id_key::<A>(id, &loader.random_state);
+                asset_key_hash = hash_id_key(kind_key, id, &loader.random_state);
 
                 // Check ID entry.
                 let shard_idx = asset_key_hash as usize % loader.asset_cache.len();
@@ -526,70 +7175,349 @@ async fn find_asset_task<A: Asset>(
 
                 let mut locked_shard = asset_shard.lock();
 
-                let entry = locked_shard
-                    .raw_entry_mut()
-                    .from_hash(asset_key_hash, |k| k.eq_key::<A>(id));
+                let entry = locked_shard.entry(asset_key_hash, |k| k.eq_key(kind_key, id));
 
                 match entry {
-                    RawEntryMut::Vacant(entry) => {
+                    Entry::Vacant(entry) => {
                         // Asset was not requested by ID yet.
-                        let asset_key = TypeKey::new::<A>(id);
+                        let asset_key =
+                            TypeKey::new(kind_key, id).with_sequence(loader.next_sequence());
+
+                        if let Err(error) = loader.check_kind(&kind, id) {
+                            debug_check(
+                                EntryKind::Asset,
+                                None,
+                                Event::Deny,
+                                Some(EntryStatus::Error),
+                            );
+                            // Wakers are woken on return.
+                            entry.insert(asset_key, AssetState::Error { error });
+                            return;
+                        }
 
                         // Register query
-                        let _ = entry.insert_hashed_nocheck(
-                            asset_key_hash,
+                        debug_check(
+                            EntryKind::Asset,
+                            None,
+                            Event::Request,
+                            Some(EntryStatus::Pending),
+                        );
+                        load_wakers.append(&mut ready_wakers.vec);
+                        entry.insert(
                             asset_key,
                             AssetState::Unloaded {
-                                wakers: moving_wakers,
+                                wakers: load_wakers,
+                                abort: abort.clone(),
                             }, // Put wakers here.
                         );
                     }
-                    RawEntryMut::Occupied(mut entry) => {
+                    Entry::Occupied(mut entry) => {
+                        // Asset is requested by ID or found by another path already.
+                        // Wakers taken from this path entry are either moved to ID entry
+                        // or woken when dropped, but never both.
                         match entry.get_mut() {
-                            AssetState::Unloaded { wakers } => {
+                            AssetState::Unloaded { wakers, .. } => {
                                 // Move wakers to ID entry.
-                                wakers.append(&mut moving_wakers.vec);
+                                wakers.append(&mut load_wakers.vec);
+                                wakers.append(&mut ready_wakers.vec);
+                                return;
+                            }
+                            AssetState::Loaded { wakers, .. } => {
+                                // Asset is loaded, but not ready yet.
+                                // Move wakers waiting for ready asset to ID entry.
+                                // Wake wakers waiting for load.
+                                wakers.append(&mut ready_wakers.vec);
+                                return;
+                            }
+                            state @ AssetState::Missing
+                                if missing.policy != MissingPolicy::Fail =>
+                            {
+                                // Asset may be available now. Load it again.
+                                debug_check(
+                                    EntryKind::Asset,
+                                    Some(EntryStatus::Missing),
+                                    Event::Retry,
+                                    Some(EntryStatus::Pending),
+                                );
+                                load_wakers.append(&mut ready_wakers.vec);
+                                *state = AssetState::Unloaded {
+                                    wakers: load_wakers,
+                                    abort: abort.clone(),
+                                };
                             }
                             _ => {
                                 // Loading is complete one way or another.
                                 // Wake wakers from path entry.
+                                return;
                             }
                         }
-                        return;
                     }
                 }
             }
 
             // Proceed loading by ID.
-            load_asset_task::<A>(loader, asset_shard, asset_key_hash, id).await;
+            load_asset_task(
+                loader,
+                kind,
+                asset_shard,
+                asset_key_hash,
+                id,
+                Some(path),
+                missing,
+                priority,
+                abort,
+            )
+            .await;
+        }
+    }
+}
+
+async fn load_asset(
+    sources: &SourceArray,
+    start: usize,
+    id: AssetId,
+    clock: &dyn Clock,
+) -> Result<Option<Data>, Error> {
+    let found = query_sources(
+        &sources.sources[start..],
+        sources.strategy,
+        clock,
+        |index, source| {
+            let slot = &sources.slots[start + index];
+            Box::pin(async move {
+                // Inline data is fetched when the source is queried and served without loading.
+                if let Some(data) = slot.inline_data(source, id).await {
+                    return Ok(Some(data));
+                }
+                source.load(id).await
+            })
+        },
+    )
+    .await?;
+
+    Ok(found.map(|(index, asset)| Data {
+        bytes: asset.bytes,
+        version: asset.version,
+        source: start + index,
+        slot: sources.slots[start + index].clone(),
+        properties: asset.properties,
+    }))
+}
+
+async fn find_assets_under(
+    sources: &[Arc<dyn Source>],
+    name: &str,
+    prefix: &str,
+) -> Vec<(String, AssetId)> {
+    let mut found = HashMap::new();
+    for source in sources {
+        let mut stream = source.find_prefix(prefix, name);
+        while let Some((path, id)) = stream.next().await {
+            // Same as with `find`, first source wins.
+            found.entry(path).or_insert(id);
         }
     }
+    let mut found: Vec<_> = found.into_iter().collect();
+    found.sort_unstable();
+    found
+}
+
+async fn find_asset(
+    sources: &[Arc<dyn Source>],
+    name: &str,
+    path: &str,
+    strategy: SourceStrategy,
+    clock: &dyn Clock,
+) -> Option<AssetId> {
+    let found = query_sources(sources, strategy, clock, |_, source| {
+        source.find(path, name).map(Ok::<_, Infallible>)
+    })
+    .await;
+
+    match found {
+        Ok(found) => found.map(|(_, id)| id),
+        Err(never) => match never {},
+    }
+}
+
+/// State of the query to a single source.
+enum Query<F, E> {
+    Pending(F),
+    Missing,
+    Failed(E),
 }
 
-async fn load_asset(sources: &[Box<dyn Source>], id: AssetId) -> Result<Option<Data>, Error> {
-    for (index, source) in sources.iter().enumerate() {
-        if let Some(asset) = source.load(id).await? {
-            return Ok(Some(Data {
-                bytes: asset.bytes,
-                version: asset.version,
-                source: index,
-            }));
+/// Queries sources according to the strategy.
+/// Returns index of the source that has the asset and its answer.
+async fn query_sources<'a, F, T, E>(
+    sources: &'a [Arc<dyn Source>],
+    strategy: SourceStrategy,
+    clock: &dyn Clock,
+    mut query: impl FnMut(usize, &'a dyn Source) -> F,
+) -> Result<Option<(usize, T)>, E>
+where
+    F: Future<Output = Result<Option<T>, E>> + Unpin,
+{
+    let mut queries: Vec<Query<F, E>> = Vec::with_capacity(sources.len());
+    #[cfg(feature = "tokio")]
+    let mut stagger: Option<futures::future::BoxFuture<'static, ()>> = None;
+    #[cfg(not(feature = "tokio"))]
+    let _ = clock;
+
+    poll_fn(|cx| loop {
+        let mut progress = false;
+
+        if queries.len() < sources.len() {
+            let answered = queries.iter().all(|q| !matches!(q, Query::Pending(_)));
+
+            let start_next = match strategy {
+                SourceStrategy::Sequential => answered,
+                SourceStrategy::RaceAll => true,
+                #[cfg(feature = "tokio")]
+                SourceStrategy::Staggered { .. } => {
+                    answered
+                        || stagger
+                            .as_mut()
+                            .is_none_or(|s| s.as_mut().poll(cx).is_ready())
+                }
+            };
+
+            if start_next {
+                let index = queries.len();
+                queries.push(Query::Pending(query(index, &*sources[index])));
+
+                #[cfg(feature = "tokio")]
+                if let SourceStrategy::Staggered { delay } = strategy {
+                    stagger = Some(clock.sleep(delay));
+                }
+                continue;
+            }
+        }
+
+        // Sources are polled in priority order,
+        // so simultaneous answers are resolved by priority.
+        for (index, q) in queries.iter_mut().enumerate() {
+            if let Query::Pending(future) = q {
+                match Pin::new(future).poll(cx) {
+                    Poll::Pending => {}
+                    Poll::Ready(Ok(Some(value))) => return Poll::Ready(Ok(Some((index, value)))),
+                    Poll::Ready(Ok(None)) => {
+                        *q = Query::Missing;
+                        progress = true;
+                    }
+                    Poll::Ready(Err(err)) => {
+                        *q = Query::Failed(err);
+                        progress = true;
+                    }
+                }
+            }
+        }
+
+        match queries.iter().position(|q| !matches!(q, Query::Missing)) {
+            None if queries.len() == sources.len() => return Poll::Ready(Ok(None)),
+            Some(index) if matches!(queries[index], Query::Failed(_)) => {
+                let Query::Failed(err) = queries.swap_remove(index) else {
+                    unreachable!()
+                };
+                return Poll::Ready(Err(err));
+            }
+            _ => {}
+        }
+
+        if !progress {
+            return Poll::Pending;
         }
+    })
+    .await
+}
+
+fn add_dependency(
+    dependencies: &Mutex<HashMap<AssetId, Vec<AssetId>, RandomState>>,
+    parent: AssetId,
+    id: AssetId,
+) {
+    let mut dependencies = dependencies.lock();
+    let deps = dependencies.entry(parent).or_default();
+    if !deps.contains(&id) {
+        deps.push(id);
     }
-    Ok(None)
 }
 
-async fn find_asset<A: Asset>(sources: &[Box<dyn Source>], path: &str) -> Option<AssetId> {
-    for source in sources {
-        if let Some(id) = source.find(path, A::name()).await {
-            return Some(id);
+/// Joins relative path to the directory of the parent path.
+/// `.` segments are removed and `..` segments remove preceding segment.
+/// `..` segments that go above the root are kept as is.
+/// Path that starts with `/` is resolved from the root.
+fn join_relative(parent: &str, relative: &str) -> String {
+    let mut segments = Vec::new();
+    if !relative.starts_with('/') {
+        // Last segment of the parent path is the parent asset itself.
+        segments.extend(parent.split('/').filter(|s| !s.is_empty()));
+        segments.pop();
+    }
+
+    for segment in relative.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => match segments.last() {
+                Some(&last) if last != ".." => {
+                    segments.pop();
+                }
+                _ => segments.push(".."),
+            },
+            segment => segments.push(segment),
         }
     }
-    None
+
+    segments.join("/")
 }
 
 type WakersVec = SmallVec<[Waker; 4]>;
 
+/// Wakers of handles that request asset by path.
+/// Each waker is tagged with what handle waits for.
+/// Wakes all wakers on drop.
+pub(crate) struct PathWakers {
+    vec: SmallVec<[(PollFor, Waker); 4]>,
+}
+
+impl PathWakers {
+    pub fn new() -> Self {
+        PathWakers {
+            vec: SmallVec::new(),
+        }
+    }
+
+    pub fn push(&mut self, poll_for: PollFor, waker: Waker) {
+        self.vec.push((poll_for, waker));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Moves wakers that wait for asset to be loaded or ready
+    /// into separate lists.
+    /// Wakers that wait for asset id are left to be woken on drop.
+    pub fn split_asset_wakers(&mut self, load: &mut WakeOnDrop, ready: &mut WakeOnDrop) {
+        let mut index = 0;
+        while index < self.vec.len() {
+            match self.vec[index].0 {
+                PollFor::Id => index += 1,
+                PollFor::Load => load.push(self.vec.swap_remove(index).1),
+                PollFor::Ready => ready.push(self.vec.swap_remove(index).1),
+            }
+        }
+    }
+}
+
+impl Drop for PathWakers {
+    fn drop(&mut self) {
+        for (_, waker) in self.vec.drain(..) {
+            waker.wake()
+        }
+    }
+}
+
 // Convenient type to wake wakers on scope exit.
 pub(crate) struct WakeOnDrop {
     vec: WakersVec,
@@ -0,0 +1,167 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use argosy_id::AssetId;
+use futures::future::{BoxFuture, FusedFuture, FutureExt, Shared};
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+
+use crate::error::{Error, NotFound};
+
+type FindFuture = Shared<BoxFuture<'static, Option<AssetId>>>;
+
+/// Target name and path.
+type LookupKey = (Arc<str>, Arc<str>);
+
+enum LookupState {
+    Pending(FindFuture),
+    Found(AssetId),
+    Missing,
+}
+
+/// Path lookups by target name, independent of asset types.
+pub(crate) struct PathLookups {
+    table: Mutex<HashMap<LookupKey, LookupState>>,
+}
+
+impl PathLookups {
+    pub fn new() -> Self {
+        PathLookups {
+            table: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns id found for the path.
+    pub fn peek(&self, target: &str, path: &str) -> Option<AssetId> {
+        let table = self.table.lock();
+        match table.get(&(Arc::from(target), Arc::from(path)))? {
+            LookupState::Found(id) => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// Returns lookup of the path.
+    /// Calls `find` to start new lookup if path was not looked up yet.
+    pub fn lookup(
+        self: &Arc<Self>,
+        target: &str,
+        path: &str,
+        find: impl FnOnce(Arc<str>, Arc<str>) -> BoxFuture<'static, Option<AssetId>>,
+    ) -> PathLookup {
+        let key = (Arc::<str>::from(target), Arc::<str>::from(path));
+
+        let mut table = self.table.lock();
+        let fut = match table.get(&key) {
+            Some(LookupState::Found(id)) => return PathLookup::ready(Ok(*id)),
+            Some(LookupState::Missing) => return PathLookup::ready(Err(not_found(&key.1))),
+            Some(LookupState::Pending(fut)) => fut.clone(),
+            None => {
+                let fut = find(key.0.clone(), key.1.clone()).shared();
+                table.insert(key.clone(), LookupState::Pending(fut.clone()));
+                fut
+            }
+        };
+
+        PathLookup {
+            result: None,
+            pending: Some(Pending {
+                fut,
+                key,
+                lookups: self.clone(),
+            }),
+            done: false,
+        }
+    }
+
+    /// Forgets paths that were not found.
+    pub fn forget_missing(&self) {
+        self.table
+            .lock()
+            .retain(|_, state| !matches!(state, LookupState::Missing));
+    }
+
+    /// Forgets finished lookups of the path.
+    pub fn forget_path(&self, path: &str) {
+        self.table.lock().retain(|(_, key_path), state| {
+            &**key_path != path || matches!(state, LookupState::Pending(_))
+        });
+    }
+
+    fn finish(&self, key: &LookupKey, found: Option<AssetId>) {
+        let mut table = self.table.lock();
+        if let Some(state @ LookupState::Pending(_)) = table.get_mut(key) {
+            *state = match found {
+                Some(id) => LookupState::Found(id),
+                None => LookupState::Missing,
+            };
+        }
+    }
+}
+
+fn not_found(path: &Arc<str>) -> Error {
+    NotFound {
+        id: None,
+        path: Some(path.clone()),
+    }
+    .into_error()
+}
+
+struct Pending {
+    fut: FindFuture,
+    key: LookupKey,
+    lookups: Arc<PathLookups>,
+}
+
+/// Future that resolves to id of the asset found by path and target name.
+///
+/// See [`Loader::lookup_path`].
+///
+/// [`Loader::lookup_path`]: crate::Loader::lookup_path
+pub struct PathLookup {
+    result: Option<Result<AssetId, Error>>,
+    pending: Option<Pending>,
+    done: bool,
+}
+
+impl PathLookup {
+    pub(crate) fn ready(result: Result<AssetId, Error>) -> Self {
+        PathLookup {
+            result: Some(result),
+            pending: None,
+            done: false,
+        }
+    }
+}
+
+impl Future for PathLookup {
+    type Output = Result<AssetId, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<AssetId, Error>> {
+        let me = self.get_mut();
+
+        if let Some(pending) = &mut me.pending {
+            let found = match pending.fut.poll_unpin(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(found) => found,
+            };
+
+            pending.lookups.finish(&pending.key, found);
+            me.result = Some(found.ok_or_else(|| not_found(&pending.key.1)));
+            me.pending = None;
+        }
+
+        me.done = true;
+        Poll::Ready(me.result.clone().expect("Lookup is either pending or done"))
+    }
+}
+
+impl FusedFuture for PathLookup {
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
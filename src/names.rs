@@ -0,0 +1,232 @@
+use std::{
+    any::TypeId,
+    borrow::Borrow,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::OnceLock,
+};
+
+use hashbrown::{HashMap, HashSet};
+use parking_lot::Mutex;
+
+use crate::{asset::Asset, error::DuplicateAssetName};
+
+/// Name of the asset type.
+///
+/// Names of asset types defined in code are static strings.
+/// Names computed at runtime, like names of plugin-provided types,
+/// are interned with [`AssetName::intern`], so they are leaked once per unique name.
+///
+/// Names compare by content.
+/// Same name interned twice is the same pointer, so comparing it is cheap.
+///
+/// Implementations of deprecated [`Asset::name`] that return `&'static str`
+/// keep working, [`Asset::asset_name`] converts their names with [`AssetName::new`].
+///
+/// # Example
+///
+/// ```
+/// # use argosy::AssetName;
+/// # use std::collections::HashSet;
+/// let plugin = String::from("Texture");
+/// let interned = AssetName::intern(&plugin);
+///
+/// assert_eq!(interned, AssetName::new("Texture"));
+/// assert_eq!(interned, "Texture");
+/// assert_ne!(interned, AssetName::intern("Mesh"));
+/// assert!(AssetName::intern("Texture").ptr_eq(interned));
+///
+/// // Interning from many threads yields single name.
+/// let names: Vec<AssetName> = std::thread::scope(|scope| {
+///     let threads: Vec<_> = (0..8)
+///         .map(|_| scope.spawn(|| AssetName::intern(&format!("Plugin{}", 42))))
+///         .collect();
+///     threads.into_iter().map(|t| t.join().unwrap()).collect()
+/// });
+/// assert!(names.iter().all(|name| name.ptr_eq(names[0])));
+///
+/// // Hashes match regardless of origin.
+/// let set: HashSet<AssetName> = [AssetName::new("Plugin42"), names[0]].into_iter().collect();
+/// assert_eq!(set.len(), 1);
+/// assert!(set.contains("Plugin42"));
+/// ```
+#[derive(Clone, Copy)]
+pub struct AssetName {
+    name: &'static str,
+}
+
+impl AssetName {
+    /// Returns asset name from static string.
+    #[inline]
+    pub const fn new(name: &'static str) -> Self {
+        AssetName { name }
+    }
+
+    /// Returns interned asset name.
+    /// Each unique name is leaked once and returned for every following call.
+    ///
+    /// Interned names are never freed, so memory grows with number of unique names.
+    /// Intern names from a bounded set, like names of types provided by loaded plugins,
+    /// and not names taken from untrusted data.
+    pub fn intern(name: &str) -> Self {
+        static INTERNED: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+        let mut interned = INTERNED.get_or_init(Default::default).lock();
+        match interned.get(name) {
+            Some(name) => AssetName { name },
+            None => {
+                let name: &'static str = Box::leak(name.into());
+                interned.insert(name);
+                AssetName { name }
+            }
+        }
+    }
+
+    /// Returns name as string.
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns `true` if both names point to the same string.
+    #[inline]
+    pub fn ptr_eq(&self, other: AssetName) -> bool {
+        std::ptr::eq(self.name, other.name)
+    }
+}
+
+impl From<&'static str> for AssetName {
+    #[inline]
+    fn from(name: &'static str) -> Self {
+        AssetName::new(name)
+    }
+}
+
+impl Deref for AssetName {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.name
+    }
+}
+
+impl AsRef<str> for AssetName {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+impl Borrow<str> for AssetName {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.name
+    }
+}
+
+impl PartialEq for AssetName {
+    #[inline]
+    fn eq(&self, other: &AssetName) -> bool {
+        self.ptr_eq(*other) || self.name == other.name
+    }
+}
+
+impl Eq for AssetName {}
+
+impl PartialEq<str> for AssetName {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.name == other
+    }
+}
+
+impl PartialEq<&str> for AssetName {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.name == *other
+    }
+}
+
+impl PartialEq<AssetName> for &str {
+    #[inline]
+    fn eq(&self, other: &AssetName) -> bool {
+        *self == other.name
+    }
+}
+
+impl PartialOrd for AssetName {
+    #[inline]
+    fn partial_cmp(&self, other: &AssetName) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AssetName {
+    #[inline]
+    fn cmp(&self, other: &AssetName) -> std::cmp::Ordering {
+        self.name.cmp(other.name)
+    }
+}
+
+impl Hash for AssetName {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl fmt::Debug for AssetName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.name, f)
+    }
+}
+
+impl fmt::Display for AssetName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.name, f)
+    }
+}
+
+/// Asset names registered with [`LoaderBuilder::register_asset`].
+///
+/// [`LoaderBuilder::register_asset`]: crate::LoaderBuilder::register_asset
+#[derive(Default)]
+pub(crate) struct AssetNames {
+    types: HashMap<AssetName, (TypeId, &'static str)>,
+    duplicates: Vec<DuplicateAssetName>,
+}
+
+impl AssetNames {
+    /// Registers name of the asset type.
+    /// First type registered with a name keeps it,
+    /// other types with the same name are recorded as duplicates.
+    pub fn register<A: Asset>(&mut self) {
+        let name = A::asset_name();
+        let type_id = TypeId::of::<A>();
+        let type_name = std::any::type_name::<A>();
+
+        match self.types.get(&name) {
+            None => {
+                self.types.insert(name, (type_id, type_name));
+            }
+            Some((registered, _)) if *registered == type_id => {}
+            Some((_, first)) => self.duplicates.push(DuplicateAssetName {
+                name,
+                first,
+                second: type_name,
+            }),
+        }
+    }
+
+    /// Returns duplicates found so far.
+    pub fn duplicates(&self) -> &[DuplicateAssetName] {
+        &self.duplicates
+    }
+
+    /// Returns type registered with the name.
+    pub fn resolve(&self, name: &str) -> Option<TypeId> {
+        self.types.get(name).map(|(type_id, _)| *type_id)
+    }
+}
@@ -0,0 +1,218 @@
+use std::{borrow::Cow, fmt, marker::PhantomData};
+
+use argosy_id::AssetId;
+
+use crate::{
+    asset::Asset, cache::Entry, handle::Handle, handle::State, key::Key, loader::Loader,
+    loader::PathState, typed_id::TypedAssetId,
+};
+
+/// Serialized form of asset handles.
+///
+/// Id is preferred. Path is stored only when asset is not identified yet.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+enum AssetRef<'a> {
+    Id(AssetId),
+    Path(Cow<'a, str>),
+}
+
+impl Handle {
+    /// Returns asset id if it is known, without polling the handle.
+    fn known_id(&self) -> Option<AssetId> {
+        if let Some(id) = self.id {
+            return Some(id);
+        }
+
+        match &self.state {
+            State::Searching {
+                key_hash,
+                path_shard,
+                ..
+            } => {
+                let path = self.path.as_deref()?;
+                let mut locked_shard = path_shard.lock();
+                match locked_shard.entry(*key_hash, |k| k.eq_key(self.kind, path)) {
+                    Entry::Occupied(mut entry) => match entry.get() {
+                        PathState::Loaded { id } => Some(*id),
+                        _ => None,
+                    },
+                    Entry::Vacant(_) => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let asset_ref = match (self.known_id(), self.path.as_deref()) {
+            (Some(id), _) => AssetRef::Id(id),
+            (None, Some(path)) => AssetRef::Path(Cow::Borrowed(path)),
+            (None, None) => unreachable!("Handle is created with either id or path"),
+        };
+        serde::Serialize::serialize(&asset_ref, serializer)
+    }
+}
+
+/// Asset handle deserialized from a save.
+///
+/// Handles can't exist without loader cache,
+/// so deserialized handle must be attached to a loader with [`PendingHandle::attach`].
+///
+/// [`AssetHandle`] and [`LoadedAsset`] are serialized as asset id when it is known.
+/// Handle that was requested with path and not identified yet
+/// is serialized as that path instead.
+/// Attaching such handle looks the path up again,
+/// which may resolve to a different asset if sources changed since the save.
+/// Await [`AssetHandle::id`] before saving to store stable id.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let source = MemorySource::new();
+/// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 7 }"#[..]);
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         // Not identified yet, saved as path.
+///         let handle = loader.load::<Number, _>("number");
+///         let json = serde_json::to_string(&handle).unwrap();
+///         assert_eq!(json, r#"{"Path":"number"}"#);
+///
+///         let loaded = handle.await?;
+///
+///         // Identified, saved as id.
+///         let json = serde_json::to_string(&loaded).unwrap();
+///         let bytes = bincode::serialize(&loaded).unwrap();
+///
+///         let pending: PendingHandle<Number> = serde_json::from_str(&json).unwrap();
+///         assert_eq!(pending.id(), AssetId::new(1));
+///         let mut number = pending.attach(&loader).await?;
+///         assert_eq!(number.build(&mut ())?.value, 7);
+///
+///         let pending: PendingHandle<Number> = bincode::deserialize(&bytes).unwrap();
+///         let typed: TypedAssetId<Number> = pending.typed_id().unwrap();
+///         assert_eq!(typed.id, AssetId::new(1).unwrap());
+///         let mut number = pending.attach(&loader).await?;
+///         assert_eq!(number.build(&mut ())?.value, 7);
+///         Ok::<_, Error>(())
+///     })
+///     .unwrap();
+/// ```
+///
+/// [`AssetHandle`]: crate::AssetHandle
+/// [`AssetHandle::id`]: crate::AssetHandle::id
+/// [`LoadedAsset`]: crate::LoadedAsset
+pub struct PendingHandle<A> {
+    asset_ref: AssetRef<'static>,
+    marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Clone for PendingHandle<A> {
+    fn clone(&self) -> Self {
+        PendingHandle {
+            asset_ref: self.asset_ref.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A> fmt::Debug for PendingHandle<A>
+where
+    A: Asset,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.asset_ref {
+            AssetRef::Id(id) => write!(f, "{}({})", A::asset_name(), id),
+            AssetRef::Path(path) => write!(f, "{}({})", A::asset_name(), path),
+        }
+    }
+}
+
+impl<A> PendingHandle<A> {
+    /// Returns id of the referenced asset.
+    /// Returns none if asset was saved as path.
+    pub fn id(&self) -> Option<AssetId> {
+        match &self.asset_ref {
+            AssetRef::Id(id) => Some(*id),
+            AssetRef::Path(_) => None,
+        }
+    }
+
+    /// Returns typed id of the referenced asset.
+    /// Returns none if asset was saved as path.
+    pub fn typed_id(&self) -> Option<TypedAssetId<A>> {
+        self.id().map(|id| TypedAssetId {
+            id,
+            marker: PhantomData,
+        })
+    }
+
+    /// Returns path of the referenced asset.
+    /// Returns none if asset was saved as id.
+    pub fn path(&self) -> Option<&str> {
+        match &self.asset_ref {
+            AssetRef::Id(_) => None,
+            AssetRef::Path(path) => Some(path),
+        }
+    }
+
+    /// Attaches handle to the loader.
+    /// Asset is loaded the same way as with [`Loader::load`].
+    pub fn attach(&self, loader: &Loader) -> crate::AssetHandle<A>
+    where
+        A: Asset,
+    {
+        match &self.asset_ref {
+            AssetRef::Id(id) => loader.load(Key::Id(*id)),
+            AssetRef::Path(path) => loader.load(Key::Path(path.as_ref())),
+        }
+    }
+}
+
+impl<A> From<AssetId> for PendingHandle<A> {
+    fn from(id: AssetId) -> Self {
+        PendingHandle {
+            asset_ref: AssetRef::Id(id),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A> From<TypedAssetId<A>> for PendingHandle<A> {
+    fn from(id: TypedAssetId<A>) -> Self {
+        PendingHandle::from(id.id)
+    }
+}
+
+impl<A> serde::Serialize for PendingHandle<A> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&self.asset_ref, serializer)
+    }
+}
+
+impl<'de, A> serde::Deserialize<'de> for PendingHandle<A> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(PendingHandle {
+            asset_ref: AssetRef::deserialize(deserializer)?,
+            marker: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,357 @@
+use std::sync::Arc;
+
+use argosy_id::AssetId;
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
+
+use crate::{
+    asset::Asset,
+    error::Cancelled,
+    key::{Key, KindKey},
+    loader::{EntryStatus, Loader},
+    names::AssetName,
+};
+
+/// Default maximum number of prefetches running at once.
+pub(crate) const DEFAULT_MAX_PREFETCHES: usize = 4;
+
+/// Score of preloads, lower than any score of prefetches.
+const PRELOAD_SCORE: f32 = f32::MIN_POSITIVE;
+
+/// Status of the prefetch reported by [`Loader::prefetch_queue`].
+///
+/// [`Loader::prefetch_queue`]: crate::Loader::prefetch_queue
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrefetchStatus {
+    /// Waiting for capacity.
+    Pending,
+
+    /// Asset is being loaded.
+    Running,
+
+    /// Asset is decoded and waits to be built.
+    Loaded {
+        /// Length of raw asset data.
+        bytes: usize,
+    },
+}
+
+/// Prefetch in the queue of the loader.
+///
+/// See [`Loader::prefetch_queue`].
+///
+/// [`Loader::prefetch_queue`]: crate::Loader::prefetch_queue
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct PrefetchEntry {
+    /// Name of the asset type.
+    pub name: AssetName,
+
+    /// Path of the asset if it was prefetched by path.
+    pub path: Option<Arc<str>>,
+
+    /// Id of the asset if it was prefetched by id or is loaded already.
+    pub id: Option<AssetId>,
+
+    /// Current score.
+    pub score: f32,
+
+    /// Status of the prefetch.
+    pub status: PrefetchStatus,
+}
+
+/// Starts loading of the prefetched asset.
+type StartFn = Box<dyn FnOnce(&Loader) -> BoxFuture<'static, Option<Loaded>> + Send>;
+
+/// Prefetched asset.
+struct Loaded {
+    id: AssetId,
+    bytes: usize,
+
+    /// Returns state of the asset in the loader cache.
+    /// Holds the asset handle, keeping it from being unloaded.
+    status: Box<dyn Fn() -> Option<EntryStatus> + Send>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+enum PrefetchKey {
+    Path(Arc<str>),
+    Id(AssetId),
+}
+
+impl PrefetchKey {
+    fn new(key: Key<'_>) -> Self {
+        match key {
+            Key::Path(path) => PrefetchKey::Path(path.into()),
+            Key::Id(id) => PrefetchKey::Id(id),
+        }
+    }
+
+    fn key(&self) -> Key<'_> {
+        match self {
+            PrefetchKey::Path(path) => Key::Path(path),
+            PrefetchKey::Id(id) => Key::Id(*id),
+        }
+    }
+
+    fn parts(&self) -> (Option<Arc<str>>, Option<AssetId>) {
+        match self {
+            PrefetchKey::Path(path) => (Some(path.clone()), None),
+            PrefetchKey::Id(id) => (None, Some(*id)),
+        }
+    }
+}
+
+enum Stage {
+    Pending { start: StartFn },
+    Running,
+    Loaded(Loaded),
+}
+
+struct Prefetch {
+    kind: KindKey,
+    name: AssetName,
+    key: PrefetchKey,
+    score: f32,
+
+    /// Insertion order, earlier prefetch wins among equal scores.
+    order: u64,
+    stage: Stage,
+}
+
+struct Queue {
+    prefetches: Vec<Prefetch>,
+    running: usize,
+    next_order: u64,
+}
+
+/// Starts prefetches with highest scores within limits.
+pub(crate) struct Prefetcher {
+    max_running: usize,
+    max_bytes: usize,
+    queue: Mutex<Queue>,
+}
+
+impl Prefetcher {
+    pub fn new(max_running: usize, max_bytes: usize) -> Self {
+        Prefetcher {
+            max_running,
+            max_bytes,
+            queue: Mutex::new(Queue {
+                prefetches: Vec::new(),
+                running: 0,
+                next_order: 0,
+            }),
+        }
+    }
+
+    /// Adds prefetch or changes score of pending one.
+    pub fn prefetch<A: Asset>(&self, key: Key<'_>, score: f32) {
+        let kind = KindKey::of::<A>();
+        let key = PrefetchKey::new(key);
+
+        if self.set_score(kind, &key, score) || score.is_nan() || score <= 0.0 {
+            return;
+        }
+
+        self.insert::<A>(&mut self.queue.lock(), key, score);
+    }
+
+    /// Adds prefetch with the lowest score unless asset is prefetched already.
+    pub fn preload<A: Asset>(&self, key: Key<'_>) {
+        let kind = KindKey::of::<A>();
+        let key = PrefetchKey::new(key);
+
+        let mut queue = self.queue.lock();
+        if !queue
+            .prefetches
+            .iter()
+            .any(|p| p.kind == kind && p.key == key)
+        {
+            self.insert::<A>(&mut queue, key, PRELOAD_SCORE);
+        }
+    }
+
+    /// Adds pending prefetch.
+    /// Failed prefetch is recorded, see [`Loader::take_failures`].
+    fn insert<A: Asset>(&self, queue: &mut Queue, key: PrefetchKey, score: f32) {
+        let start_key = key.clone();
+        let start: StartFn = Box::new(move |loader: &Loader| {
+            let handle = loader.load::<A, _>(start_key.key());
+            let failures = loader.failures.clone();
+            Box::pin(async move {
+                let asset = match handle.await {
+                    Ok(asset) => asset,
+                    Err(error) => {
+                        if !error.is::<Cancelled>() {
+                            let (path, id) = start_key.parts();
+                            failures.record(&A::asset_name(), path, id, error);
+                        }
+                        return None;
+                    }
+                };
+                Some(Loaded {
+                    id: asset.id()?,
+                    bytes: asset.metadata().bytes_len,
+                    status: Box::new(move || asset.loader_state()),
+                })
+            })
+        });
+
+        let order = queue.next_order;
+        queue.next_order += 1;
+        queue.prefetches.push(Prefetch {
+            kind: KindKey::of::<A>(),
+            name: A::asset_name(),
+            key,
+            score,
+            order,
+            stage: Stage::Pending { start },
+        });
+    }
+
+    /// Changes score of the pending prefetch.
+    /// Non-positive score removes it.
+    ///
+    /// Returns `false` if there is no such prefetch.
+    /// Prefetches that are started already are not affected.
+    pub fn reprioritize<A: Asset>(&self, key: Key<'_>, score: f32) -> bool {
+        self.set_score(KindKey::of::<A>(), &PrefetchKey::new(key), score)
+    }
+
+    fn set_score(&self, kind: KindKey, key: &PrefetchKey, score: f32) -> bool {
+        let mut queue = self.queue.lock();
+        let Some(index) = queue
+            .prefetches
+            .iter()
+            .position(|p| p.kind == kind && p.key == *key)
+        else {
+            return false;
+        };
+
+        let prefetch = &mut queue.prefetches[index];
+        if let Stage::Pending { .. } = prefetch.stage {
+            if score > 0.0 {
+                prefetch.score = score;
+            } else {
+                queue.prefetches.swap_remove(index);
+            }
+        }
+        true
+    }
+
+    /// Starts pending prefetches with highest scores while limits allow.
+    /// Forgets loaded prefetches that were built or unloaded.
+    pub fn schedule(&self, loader: &Loader) {
+        let mut started = Vec::new();
+        {
+            let mut queue = self.queue.lock();
+
+            queue.prefetches.retain(|prefetch| match &prefetch.stage {
+                Stage::Loaded(loaded) => (loaded.status)() == Some(EntryStatus::Loaded),
+                _ => true,
+            });
+
+            let bytes: usize = queue
+                .prefetches
+                .iter()
+                .map(|prefetch| match &prefetch.stage {
+                    Stage::Loaded(loaded) => loaded.bytes,
+                    _ => 0,
+                })
+                .sum();
+
+            while queue.running < self.max_running && bytes < self.max_bytes {
+                let best = queue
+                    .prefetches
+                    .iter_mut()
+                    .filter(|prefetch| matches!(prefetch.stage, Stage::Pending { .. }))
+                    .max_by(|a, b| {
+                        a.score
+                            .total_cmp(&b.score)
+                            .then_with(|| b.order.cmp(&a.order))
+                    });
+
+                let Some(best) = best else {
+                    break;
+                };
+
+                let Stage::Pending { start } = std::mem::replace(&mut best.stage, Stage::Running)
+                else {
+                    unreachable!()
+                };
+
+                started.push((best.kind, best.key.clone(), start));
+                queue.running += 1;
+            }
+        }
+
+        for (kind, key, start) in started {
+            let loaded = start(loader);
+            let task_loader = loader.clone();
+            loader.spawn_in_flight(async move {
+                let loaded = loaded.await;
+                task_loader.prefetch.finish(kind, &key, loaded);
+                task_loader.prefetch.schedule(&task_loader);
+            });
+        }
+    }
+
+    fn finish(&self, kind: KindKey, key: &PrefetchKey, loaded: Option<Loaded>) {
+        let mut queue = self.queue.lock();
+        queue.running -= 1;
+
+        let Some(index) = queue.prefetches.iter().position(|prefetch| {
+            prefetch.kind == kind
+                && prefetch.key == *key
+                && matches!(prefetch.stage, Stage::Running)
+        }) else {
+            return;
+        };
+
+        match loaded {
+            Some(loaded) => queue.prefetches[index].stage = Stage::Loaded(loaded),
+            None => {
+                queue.prefetches.swap_remove(index);
+            }
+        }
+    }
+
+    /// Returns prefetches ordered by score.
+    pub fn queue(&self) -> Vec<PrefetchEntry> {
+        let queue = self.queue.lock();
+
+        let mut prefetches: Vec<&Prefetch> = queue.prefetches.iter().collect();
+        prefetches.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.order.cmp(&b.order))
+        });
+
+        prefetches
+            .into_iter()
+            .map(|prefetch| {
+                let (path, mut id) = prefetch.key.parts();
+
+                let status = match &prefetch.stage {
+                    Stage::Pending { .. } => PrefetchStatus::Pending,
+                    Stage::Running => PrefetchStatus::Running,
+                    Stage::Loaded(loaded) => {
+                        id = Some(loaded.id);
+                        PrefetchStatus::Loaded {
+                            bytes: loaded.bytes,
+                        }
+                    }
+                };
+
+                PrefetchEntry {
+                    name: prefetch.name,
+                    path,
+                    id,
+                    score: prefetch.score,
+                    status,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    iter::Sum,
+    ops::Add,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Value of `done` before decoder reports progress.
+const NOT_REPORTED: u64 = u64::MAX;
+
+/// Value of `total` when decoder does not know it.
+const UNKNOWN_TOTAL: u64 = u64::MAX;
+
+/// Decoding progress reported with [`Loader::report_progress`].
+///
+/// Progress of several assets can be summed to show single progress bar,
+/// only known totals are added up.
+///
+/// [`Loader::report_progress`]: crate::Loader::report_progress
+///
+/// # Example
+///
+/// ```
+/// # use argosy::Progress;
+/// let progress: Progress = [
+///     Progress { done: 3, total: Some(4) },
+///     Progress { done: 1, total: None },
+///     Progress { done: 2, total: Some(4) },
+/// ]
+/// .into_iter()
+/// .sum();
+///
+/// assert_eq!(progress, Progress { done: 6, total: Some(8) });
+/// assert_eq!(progress.fraction(), Some(0.75));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Progress {
+    /// Amount of work done.
+    pub done: u64,
+
+    /// Total amount of work if known.
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    /// Returns fraction of work done, clamped to `[0, 1]`.
+    /// Returns `None` if total is unknown.
+    pub fn fraction(&self) -> Option<f32> {
+        match self.total? {
+            0 => Some(1.0),
+            total => Some((self.done as f64 / total as f64).min(1.0) as f32),
+        }
+    }
+}
+
+impl Add for Progress {
+    type Output = Progress;
+
+    fn add(self, rhs: Progress) -> Progress {
+        let total = match (self.total, rhs.total) {
+            (None, None) => None,
+            (lhs, rhs) => Some(lhs.unwrap_or(0) + rhs.unwrap_or(0)),
+        };
+
+        Progress {
+            done: self.done + rhs.done,
+            total,
+        }
+    }
+}
+
+impl Sum for Progress {
+    fn sum<I: Iterator<Item = Progress>>(iter: I) -> Progress {
+        iter.fold(Progress::default(), Add::add)
+    }
+}
+
+/// Progress of the load shared between decoder and handles.
+pub(crate) struct ProgressCell {
+    done: AtomicU64,
+    total: AtomicU64,
+}
+
+impl ProgressCell {
+    pub fn new() -> Self {
+        ProgressCell {
+            done: AtomicU64::new(NOT_REPORTED),
+            total: AtomicU64::new(UNKNOWN_TOTAL),
+        }
+    }
+
+    pub fn report(&self, done: u64, total: Option<u64>) {
+        self.total
+            .store(total.unwrap_or(UNKNOWN_TOTAL), Ordering::Relaxed);
+        self.done
+            .store(done.min(NOT_REPORTED - 1), Ordering::Release);
+    }
+
+    /// Returns last reported progress.
+    pub fn get(&self) -> Option<Progress> {
+        let done = self.done.load(Ordering::Acquire);
+        if done == NOT_REPORTED {
+            return None;
+        }
+
+        let total = self.total.load(Ordering::Relaxed);
+        Some(Progress {
+            done,
+            total: (total != UNKNOWN_TOTAL).then_some(total),
+        })
+    }
+}
@@ -0,0 +1,116 @@
+use std::{any::Any, sync::Arc};
+
+use argosy_id::AssetId;
+
+use crate::{
+    asset::Asset,
+    key::{hash_id_key, KindKey},
+    loader::Loader,
+};
+
+/// Asset value staged for publication.
+pub(crate) struct Staged {
+    pub kind: KindKey,
+    pub id: AssetId,
+    pub key_hash: u64,
+
+    // Contains `A`
+    pub asset: Arc<dyn Any + Send + Sync>,
+
+    /// Version of the asset after publication.
+    /// Previous version is kept if not set.
+    pub version: Option<u64>,
+}
+
+impl Staged {
+    pub fn new<A: Asset>(loader: &Loader, id: AssetId, asset: A, version: Option<u64>) -> Self {
+        let kind = KindKey::of::<A>();
+        Staged {
+            kind,
+            id,
+            key_hash: hash_id_key(kind, id, loader.random_state()),
+            asset: Arc::new(asset),
+            version,
+        }
+    }
+}
+
+/// Set of built assets published to the loader at once.
+///
+/// Created with [`Loader::begin_publish`].
+/// Staged assets are not visible until [`Publish::commit`].
+/// Dropping without commit discards staged assets.
+#[must_use = "Staged assets are published only on commit"]
+pub struct Publish<'a> {
+    loader: &'a Loader,
+    staged: Vec<Staged>,
+}
+
+impl<'a> Publish<'a> {
+    pub(crate) fn new(loader: &'a Loader) -> Self {
+        Publish {
+            loader,
+            staged: Vec::new(),
+        }
+    }
+
+    /// Stages new value of the asset.
+    /// Replaces value staged earlier for the same asset.
+    pub fn stage<A: Asset>(&mut self, id: AssetId, asset: A) -> &mut Self {
+        self.stage_impl(id, asset, None)
+    }
+
+    /// Stages new value of the asset with its version,
+    /// e.g. version of the data it was reloaded from.
+    /// Replaces value staged earlier for the same asset.
+    pub fn stage_with_version<A: Asset>(
+        &mut self,
+        id: AssetId,
+        asset: A,
+        version: u64,
+    ) -> &mut Self {
+        self.stage_impl(id, asset, Some(version))
+    }
+
+    fn stage_impl<A: Asset>(&mut self, id: AssetId, asset: A, version: Option<u64>) -> &mut Self {
+        let kind = KindKey::of::<A>();
+        let staged = Staged::new(self.loader, id, asset, version);
+
+        match self
+            .staged
+            .iter_mut()
+            .find(|s| s.kind == kind && s.id == id)
+        {
+            Some(existing) => *existing = staged,
+            None => self.staged.push(staged),
+        }
+        self
+    }
+
+    /// Returns number of staged assets.
+    pub fn len(&self) -> usize {
+        self.staged.len()
+    }
+
+    /// Returns `true` if no assets are staged.
+    pub fn is_empty(&self) -> bool {
+        self.staged.is_empty()
+    }
+
+    /// Makes all staged assets visible at once.
+    ///
+    /// Staged values replace cache entries in any state.
+    /// Loads in progress for staged assets are aborted.
+    /// Handles that already got previous values keep them.
+    ///
+    /// Reload hooks are called for staged assets that replace ready values.
+    /// See [`LoaderBuilder::register_reload_hook`].
+    ///
+    /// Assets that depend on replaced ones become stale,
+    /// see [`Loader::enable_cascade`].
+    ///
+    /// [`LoaderBuilder::register_reload_hook`]: crate::LoaderBuilder::register_reload_hook
+    pub fn commit(self) {
+        self.loader.commit_published(self.staged, true);
+    }
+}
@@ -0,0 +1,92 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Waker},
+};
+
+use futures::{
+    future::BoxFuture,
+    stream::FuturesUnordered,
+    task::{waker, ArcWake, AtomicWaker},
+    StreamExt,
+};
+use parking_lot::Mutex;
+
+/// Loading tasks driven manually with [`Loader::pump`].
+///
+/// Used instead of tokio runtime when `tokio` feature is disabled.
+///
+/// [`Loader::pump`]: crate::Loader::pump
+pub(crate) struct Tasks {
+    /// Tasks spawned since last pump.
+    /// Kept separately so that tasks may spawn new tasks while being polled.
+    spawned: Mutex<Vec<BoxFuture<'static, ()>>>,
+
+    /// Tasks that are not finished yet.
+    running: Mutex<FuturesUnordered<BoxFuture<'static, ()>>>,
+
+    /// Set when any running task is woken.
+    woken: Arc<Woken>,
+}
+
+struct Woken {
+    flag: AtomicBool,
+
+    /// Woken with running tasks, used by blocking loads.
+    waiter: AtomicWaker,
+}
+
+impl ArcWake for Woken {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.flag.store(true, Ordering::Release);
+        arc_self.waiter.wake();
+    }
+}
+
+impl Tasks {
+    pub(crate) fn new() -> Self {
+        Tasks {
+            spawned: Mutex::new(Vec::new()),
+            running: Mutex::new(FuturesUnordered::new()),
+            woken: Arc::new(Woken {
+                flag: AtomicBool::new(false),
+                waiter: AtomicWaker::new(),
+            }),
+        }
+    }
+
+    pub(crate) fn spawn(&self, task: BoxFuture<'static, ()>) {
+        self.spawned.lock().push(task);
+        ArcWake::wake_by_ref(&self.woken);
+    }
+
+    /// Registers `waker` to be woken once when any task is woken or spawned.
+    pub(crate) fn wake_on_activity(&self, waker: &Waker) {
+        self.woken.waiter.register(waker);
+    }
+
+    /// Polls tasks until all of them wait for something.
+    /// Returns number of tasks that are not finished.
+    pub(crate) fn pump(&self) -> usize {
+        let mut running = self.running.lock();
+
+        let waker = waker(self.woken.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        loop {
+            running.extend(self.spawned.lock().drain(..));
+            self.woken.flag.store(false, Ordering::Release);
+
+            match running.poll_next_unpin(&mut cx) {
+                Poll::Ready(Some(())) => continue,
+                Poll::Ready(None) | Poll::Pending => {
+                    if !self.woken.flag.load(Ordering::Acquire) && self.spawned.lock().is_empty() {
+                        return running.len();
+                    }
+                }
+            }
+        }
+    }
+}
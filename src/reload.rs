@@ -0,0 +1,139 @@
+use std::{
+    any::{Any, TypeId},
+    sync::Arc,
+};
+
+use argosy_id::AssetId;
+use hashbrown::HashMap;
+
+use crate::{asset::Asset, names::AssetName};
+
+/// Decision of the reload hook about new value of the asset.
+///
+/// See [`LoaderBuilder::register_reload_hook`].
+///
+/// [`LoaderBuilder::register_reload_hook`]: crate::LoaderBuilder::register_reload_hook
+pub enum ReloadAction<A> {
+    /// New value replaces the old one.
+    Replace,
+
+    /// Old value is kept, new value is dropped.
+    KeepOld,
+
+    /// Provided value, e.g. old value merged with new one, replaces the old one.
+    Custom(A),
+}
+
+/// Action taken on asset reload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReloadOutcome {
+    /// New value replaced the old one.
+    Replaced,
+
+    /// Old value was kept.
+    KeptOld,
+
+    /// Value provided by the hook replaced the old one.
+    Custom,
+}
+
+/// Asset value replaced by publication with reload hook registered for its type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReloadEvent {
+    /// Id of the asset.
+    pub id: AssetId,
+
+    /// Name of the asset type.
+    pub name: AssetName,
+
+    /// Version of the old value.
+    pub old_version: u64,
+
+    /// Version of the value after reload.
+    pub new_version: u64,
+
+    /// Action chosen by the hook.
+    pub outcome: ReloadOutcome,
+}
+
+/// Receives reload events.
+///
+/// See [`LoaderBuilder::set_reload_events`].
+///
+/// [`LoaderBuilder::set_reload_events`]: crate::LoaderBuilder::set_reload_events
+pub trait ReloadSink: Send + Sync + 'static {
+    /// Records asset reload.
+    fn reloaded(&self, event: &ReloadEvent);
+}
+
+impl ReloadSink for () {
+    fn reloaded(&self, _event: &ReloadEvent) {}
+}
+
+/// Type-erased [`ReloadAction`].
+pub(crate) enum ErasedAction {
+    Replace,
+    KeepOld,
+    Custom(Arc<dyn Any + Send + Sync>),
+}
+
+type ErasedHook =
+    Box<dyn Fn(&(dyn Any + Send + Sync), &(dyn Any + Send + Sync)) -> ErasedAction + Send + Sync>;
+
+/// Reload hooks by asset type.
+#[derive(Default)]
+pub(crate) struct ReloadHooks {
+    hooks: HashMap<TypeId, (AssetName, ErasedHook)>,
+    sink: Option<Box<dyn ReloadSink>>,
+}
+
+impl ReloadHooks {
+    pub fn register<A, F>(&mut self, hook: F)
+    where
+        A: Asset,
+        F: Fn(&A, &A) -> ReloadAction<A> + Send + Sync + 'static,
+    {
+        let hook: ErasedHook = Box::new(move |old, new| {
+            let old = old.downcast_ref::<A>().unwrap();
+            let new = new.downcast_ref::<A>().unwrap();
+            match hook(old, new) {
+                ReloadAction::Replace => ErasedAction::Replace,
+                ReloadAction::KeepOld => ErasedAction::KeepOld,
+                ReloadAction::Custom(asset) => ErasedAction::Custom(Arc::new(asset)),
+            }
+        });
+        self.hooks
+            .insert(TypeId::of::<A>(), (A::asset_name(), hook));
+    }
+
+    pub fn set_sink(&mut self, sink: Box<dyn ReloadSink>) {
+        self.sink = Some(sink);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Calls hook registered for the asset type, if any.
+    /// Returns name of the asset type with hook's decision.
+    pub fn call(
+        &self,
+        type_id: TypeId,
+        old: &(dyn Any + Send + Sync),
+        new: &(dyn Any + Send + Sync),
+    ) -> Option<(AssetName, ErasedAction)> {
+        let (name, hook) = self.hooks.get(&type_id)?;
+        Some((*name, hook(old, new)))
+    }
+
+    pub fn has_hook(&self, type_id: TypeId) -> bool {
+        self.hooks.contains_key(&type_id)
+    }
+
+    pub fn report(&self, event: &ReloadEvent) {
+        if let Some(sink) = &self.sink {
+            sink.reloaded(event);
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use argosy_id::AssetId;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+
+use crate::{
+    clock::Clock,
+    error::Error,
+    source::{AssetData, AssetProperties},
+};
+
+/// Bytes at the start of encoded [`ReplayLog`].
+const REPLAY_LOG_MAGIC: [u8; 8] = *b"ARGOSYRL";
+
+/// Version of the [`ReplayLog`] encoding.
+/// Logs of other versions are rejected.
+pub const REPLAY_LOG_VERSION: u32 = 1;
+
+/// Request made by the loader to its sources.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum ReplayRequest {
+    /// Asset id lookup by path, see [`Source::find`].
+    ///
+    /// [`Source::find`]: crate::Source::find
+    Find {
+        /// Requested path.
+        path: String,
+
+        /// Name of the asset type.
+        asset: String,
+    },
+
+    /// Listing of assets under the prefix, see [`Source::find_prefix`].
+    ///
+    /// [`Source::find_prefix`]: crate::Source::find_prefix
+    FindPrefix {
+        /// Requested path prefix.
+        prefix: String,
+
+        /// Name of the asset type.
+        asset: String,
+    },
+
+    /// Asset data loading, see [`Source::load`].
+    ///
+    /// [`Source::load`]: crate::Source::load
+    Load {
+        /// Requested asset.
+        id: AssetId,
+    },
+
+    /// Check for newer asset data, see [`Source::update`].
+    ///
+    /// [`Source::update`]: crate::Source::update
+    Update {
+        /// Requested asset.
+        id: AssetId,
+
+        /// Version known to the loader.
+        version: u64,
+    },
+}
+
+/// Answer of sources to the [`ReplayRequest`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ReplayResponse {
+    /// Id found for the path, if any.
+    Found(Option<AssetId>),
+
+    /// Paths and ids listed under the prefix.
+    Listed(Vec<(String, AssetId)>),
+
+    /// Asset data, if any.
+    Data(Option<ReplayData>),
+
+    /// Error with its message.
+    Error(String),
+}
+
+/// Asset data recorded in [`ReplayLog`].
+///
+/// Bytes are stored once per distinct payload, see [`ReplayLog::bytes`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ReplayData {
+    /// Hash of the bytes.
+    pub hash: u64,
+
+    /// Length of the bytes.
+    pub len: usize,
+
+    /// Version reported by the source.
+    pub version: u64,
+
+    /// Properties reported by the source.
+    pub properties: Vec<(String, String)>,
+}
+
+/// Single request to the sources with its answer.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ReplayEntry {
+    /// Time since recording start when request was made.
+    pub started: Duration,
+
+    /// Time since recording start when answer was received.
+    pub finished: Duration,
+
+    /// Request to the sources.
+    pub request: ReplayRequest,
+
+    /// Answer of the sources.
+    pub response: ReplayResponse,
+}
+
+/// Error decoding [`ReplayLog`].
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayLogError {
+    /// Data is not a replay log.
+    #[error("Data is not a replay log")]
+    NotReplayLog,
+
+    /// Log is written in unsupported version of the format.
+    #[error("Replay log version {found} is not supported, expected {REPLAY_LOG_VERSION}")]
+    UnsupportedVersion {
+        /// Version of the log.
+        found: u32,
+    },
+
+    /// Log is corrupted.
+    #[error("Replay log is corrupted")]
+    Corrupted(#[source] bincode::Error),
+}
+
+/// Encoded form of the [`ReplayLog`] after header.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LogBody {
+    entries: Vec<ReplayEntry>,
+    blobs: Vec<(u64, Box<[u8]>)>,
+}
+
+/// Log of the loader interactions with sources.
+///
+/// Recorded with [`ReplayRecorder`] and served back by [`ReplaySource`].
+///
+/// [`ReplaySource`]: crate::ReplaySource
+#[derive(Clone, Default)]
+pub struct ReplayLog {
+    entries: Vec<ReplayEntry>,
+    blobs: HashMap<u64, Arc<[u8]>>,
+}
+
+impl ReplayLog {
+    /// Returns new empty log.
+    pub fn new() -> Self {
+        ReplayLog::default()
+    }
+
+    /// Returns recorded entries in order they were finished.
+    pub fn entries(&self) -> &[ReplayEntry] {
+        &self.entries
+    }
+
+    /// Returns bytes with specified hash, see [`ReplayData::hash`].
+    pub fn bytes(&self, hash: u64) -> Option<&[u8]> {
+        self.blobs.get(&hash).map(|bytes| &**bytes)
+    }
+
+    /// Appends entry to the log.
+    /// `bytes` are asset data bytes of the [`ReplayResponse::Data`] response.
+    pub fn push(&mut self, entry: ReplayEntry, bytes: Option<&[u8]>) {
+        if let ReplayResponse::Data(Some(data)) = &entry.response {
+            if let Some(bytes) = bytes {
+                self.blobs.entry(data.hash).or_insert_with(|| bytes.into());
+            }
+        }
+        self.entries.push(entry);
+    }
+
+    /// Encodes the log into compact binary form.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut blobs: Vec<_> = self
+            .blobs
+            .iter()
+            .map(|(hash, bytes)| (*hash, Box::from(&**bytes)))
+            .collect();
+        blobs.sort_unstable_by_key(|(hash, _)| *hash);
+
+        let body = LogBody {
+            entries: self.entries.clone(),
+            blobs,
+        };
+
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(&REPLAY_LOG_MAGIC);
+        encoded.extend_from_slice(&REPLAY_LOG_VERSION.to_le_bytes());
+        bincode::serialize_into(&mut encoded, &body).expect("Writing to vector cannot fail");
+        encoded
+    }
+
+    /// Decodes the log encoded with [`ReplayLog::encode`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// let mut encoded = ReplayLog::new().encode();
+    /// assert!(ReplayLog::decode(&encoded).unwrap().entries().is_empty());
+    ///
+    /// assert!(matches!(
+    ///     ReplayLog::decode(b"{}").err().unwrap(),
+    ///     ReplayLogError::NotReplayLog
+    /// ));
+    ///
+    /// encoded[8..12].copy_from_slice(&(REPLAY_LOG_VERSION + 1).to_le_bytes());
+    /// assert!(matches!(
+    ///     ReplayLog::decode(&encoded).err().unwrap(),
+    ///     ReplayLogError::UnsupportedVersion { .. }
+    /// ));
+    /// ```
+    pub fn decode(encoded: &[u8]) -> Result<Self, ReplayLogError> {
+        let Some(rest) = encoded.strip_prefix(&REPLAY_LOG_MAGIC) else {
+            return Err(ReplayLogError::NotReplayLog);
+        };
+        let Some((version, rest)) = rest.split_first_chunk::<4>() else {
+            return Err(ReplayLogError::NotReplayLog);
+        };
+
+        let version = u32::from_le_bytes(*version);
+        if version != REPLAY_LOG_VERSION {
+            return Err(ReplayLogError::UnsupportedVersion { found: version });
+        }
+
+        let body: LogBody = bincode::deserialize(rest).map_err(ReplayLogError::Corrupted)?;
+        Ok(ReplayLog {
+            entries: body.entries,
+            blobs: body
+                .blobs
+                .into_iter()
+                .map(|(hash, bytes)| (hash, bytes.into()))
+                .collect(),
+        })
+    }
+}
+
+/// Receives interactions of the loader with sources.
+///
+/// See [`LoaderBuilder::set_recording`].
+///
+/// [`LoaderBuilder::set_recording`]: crate::LoaderBuilder::set_recording
+pub trait RecordingSink: Send + Sync + 'static {
+    /// Records request with its answer.
+    /// `bytes` are asset data bytes of the [`ReplayResponse::Data`] response.
+    fn record(&self, entry: &ReplayEntry, bytes: Option<&[u8]>);
+}
+
+/// Records interactions into [`ReplayLog`].
+///
+/// Clones share the same log.
+#[derive(Clone, Default)]
+pub struct ReplayRecorder {
+    log: Arc<Mutex<ReplayLog>>,
+}
+
+impl ReplayRecorder {
+    /// Returns new recorder with empty log.
+    pub fn new() -> Self {
+        ReplayRecorder::default()
+    }
+
+    /// Returns copy of the log recorded so far.
+    pub fn log(&self) -> ReplayLog {
+        self.log.lock().clone()
+    }
+}
+
+impl RecordingSink for ReplayRecorder {
+    fn record(&self, entry: &ReplayEntry, bytes: Option<&[u8]>) {
+        self.log.lock().push(entry.clone(), bytes);
+    }
+}
+
+/// Hashes asset data bytes with FNV-1a, which is stable across runs and platforms.
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Asset data answered by sources.
+pub(crate) trait RecordedData {
+    fn parts(&self) -> (&[u8], u64, &AssetProperties);
+}
+
+impl RecordedData for AssetData {
+    fn parts(&self) -> (&[u8], u64, &AssetProperties) {
+        (&self.bytes, self.version, &self.properties)
+    }
+}
+
+/// Forwards interactions with sources to the sink.
+pub(crate) struct Recorder {
+    start: Instant,
+    clock: Arc<dyn Clock>,
+    sink: Box<dyn RecordingSink>,
+}
+
+impl Recorder {
+    pub fn new(sink: Box<dyn RecordingSink>, clock: Arc<dyn Clock>) -> Self {
+        Recorder {
+            start: clock.now(),
+            clock,
+            sink,
+        }
+    }
+
+    /// Returns time since recording start.
+    fn elapsed(&self) -> Duration {
+        self.clock.now() - self.start
+    }
+
+    /// Starts recording of the request.
+    pub fn begin(&self, request: ReplayRequest) -> Recording<'_> {
+        Recording {
+            recorder: self,
+            started: self.elapsed(),
+            request,
+        }
+    }
+}
+
+/// Request waiting for the answer.
+pub(crate) struct Recording<'a> {
+    recorder: &'a Recorder,
+    started: Duration,
+    request: ReplayRequest,
+}
+
+impl Recording<'_> {
+    fn finish(self, response: ReplayResponse, bytes: Option<&[u8]>) {
+        let entry = ReplayEntry {
+            started: self.started,
+            finished: self.recorder.elapsed(),
+            request: self.request,
+            response,
+        };
+        self.recorder.sink.record(&entry, bytes);
+    }
+
+    pub fn found(self, id: Option<AssetId>) {
+        self.finish(ReplayResponse::Found(id), None);
+    }
+
+    pub fn listed(self, found: &[(String, AssetId)]) {
+        self.finish(ReplayResponse::Listed(found.to_vec()), None);
+    }
+
+    pub fn data<D: RecordedData>(self, result: &Result<Option<D>, Error>) {
+        match result {
+            Err(err) => self.finish(ReplayResponse::Error(format!("{err:#}")), None),
+            Ok(None) => self.finish(ReplayResponse::Data(None), None),
+            Ok(Some(data)) => {
+                let (bytes, version, properties) = data.parts();
+                let data = ReplayData {
+                    hash: hash_bytes(bytes),
+                    len: bytes.len(),
+                    version,
+                    properties: properties
+                        .iter()
+                        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                        .collect(),
+                };
+                self.finish(ReplayResponse::Data(Some(data)), Some(bytes));
+            }
+        }
+    }
+}
@@ -0,0 +1,421 @@
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::{io::Write, ops::Range, path::PathBuf, sync::Arc};
+
+use argosy_id::AssetId;
+use futures::future::BoxFuture;
+use hashbrown::HashMap;
+
+use crate::error::Error;
+
+#[cfg(all(feature = "fs", unix))]
+use super::mmap::{Madvise, Mapping};
+use super::{Advice, AssetBytes, AssetData, AssetProperties, Source};
+
+/// Magic bytes at the start of the archive.
+const MAGIC: [u8; 8] = *b"ARGOSYA1";
+
+/// Error that may occur when opening an archive.
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("Failed to read archive '{path}'. {error}")]
+    Io {
+        error: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[error("Archive header is invalid")]
+    InvalidHeader,
+
+    #[error("Archive is truncated")]
+    Truncated,
+
+    #[error("Archive contains zero asset id")]
+    ZeroId,
+
+    #[error("Failed to read archive. {0}")]
+    Read(#[source] std::io::Error),
+
+    #[error("Path index embedded into archive is invalid. {0}")]
+    InvalidIndex(#[source] serde_json::Error),
+
+    /// Requires `zip` feature.
+    #[cfg(feature = "zip")]
+    #[error("Zip archive is invalid. {0}")]
+    Zip(#[source] zip::result::ZipError),
+}
+
+/// Writes archive header.
+/// Must be called once before writing entries.
+pub(crate) fn write_archive_header(writer: &mut impl Write) -> std::io::Result<()> {
+    writer.write_all(&MAGIC)
+}
+
+/// Writes archive entry with asset data.
+pub(crate) fn write_archive_entry(
+    writer: &mut impl Write,
+    id: AssetId,
+    bytes: &[u8],
+) -> std::io::Result<()> {
+    writer.write_all(&id.value().get().to_le_bytes())?;
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(bytes)
+}
+
+/// Builds index of the archive entries.
+fn read_archive_index(data: &[u8]) -> Result<HashMap<AssetId, Range<usize>>, ArchiveError> {
+    if data.len() < MAGIC.len() || data[..MAGIC.len()] != MAGIC {
+        return Err(ArchiveError::InvalidHeader);
+    }
+
+    let mut index = HashMap::new();
+    let mut offset = MAGIC.len();
+
+    while offset < data.len() {
+        let Some(header) = data.get(offset..offset + 16) else {
+            return Err(ArchiveError::Truncated);
+        };
+        let id = u64::from_le_bytes(header[..8].try_into().unwrap());
+        let len = u64::from_le_bytes(header[8..].try_into().unwrap());
+        offset += 16;
+
+        let id = AssetId::new(id).ok_or(ArchiveError::ZeroId)?;
+
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| offset.checked_add(len))
+            .filter(|&end| end <= data.len())
+            .ok_or(ArchiveError::Truncated)?;
+
+        index.insert(id, offset..end);
+        offset = end;
+    }
+
+    Ok(index)
+}
+
+/// Applies residency hints to byte ranges of an archive.
+///
+/// Archives opened with [`ArchiveSource::map`] on unix pass hints to `madvise`.
+/// Custom advisor can be set with [`ArchiveSource::set_advisor`].
+pub trait ResidencyAdvisor: Send + Sync + 'static {
+    /// Applies advice to the `range` of archive `data`.
+    fn advise(&self, data: &[u8], range: Range<usize>, advice: Advice);
+}
+
+/// Bytes of the archive.
+enum ArchiveData {
+    Owned(Box<[u8]>),
+    #[cfg(all(feature = "fs", unix))]
+    Mapped(Mapping),
+}
+
+impl AsRef<[u8]> for ArchiveData {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            ArchiveData::Owned(data) => data,
+            #[cfg(all(feature = "fs", unix))]
+            ArchiveData::Mapped(mapping) => mapping.bytes(),
+        }
+    }
+}
+
+/// Source that serves assets from an archive.
+/// Archive can be produced with [`Loader::bundle`].
+///
+/// Asset data is served as [`AssetBytes::Shared`] ranges of the archive without copying.
+/// Archive stays in memory while any of them is alive.
+///
+/// # Example
+///
+/// ```
+/// # use std::{ops::Range, sync::{Arc, Mutex}};
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// # #[derive(Clone)]
+/// # struct Blob(Box<[u8]>);
+/// # impl TrivialAsset for Blob {
+/// #     type Error = std::convert::Infallible;
+/// #     fn asset_name() -> AssetName { AssetName::new("Blob") }
+/// #     fn decode(bytes: Box<[u8]>) -> Result<Self, Self::Error> { Ok(Blob(bytes)) }
+/// # }
+/// /// Advisor that records hints.
+/// #[derive(Clone, Default)]
+/// struct Recording(Arc<Mutex<Vec<(Range<usize>, Advice)>>>);
+///
+/// impl ResidencyAdvisor for Recording {
+///     fn advise(&self, _data: &[u8], range: Range<usize>, advice: Advice) {
+///         self.0.lock().unwrap().push((range, advice));
+///     }
+/// }
+///
+/// let id = AssetId::new(1).unwrap();
+/// let source = MemorySource::new();
+/// source.insert(id, &b"Hello"[..]);
+/// let loader = Loader::builder().with(source).build();
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let mut packed = Vec::new();
+/// runtime.block_on(loader.bundle(id, &mut packed))?;
+///
+/// let advisor = Recording::default();
+/// let archive = ArchiveSource::new(packed)?.with_advisor(advisor.clone());
+/// assert!(archive.advise(id, Advice::WillNeed));
+/// assert!(!archive.advise(AssetId::new(2).unwrap(), Advice::WillNeed));
+///
+/// // Loader passes hints for prefetched assets to its sources.
+/// let loader = Loader::builder().with(archive).build();
+/// runtime.block_on(async {
+///     loader.prefetch::<Blob, _>(id, 1.0);
+///     loader.wait_idle().await;
+/// });
+///
+/// // Hints cover asset data after the archive and entry headers.
+/// assert_eq!(*advisor.0.lock().unwrap(), [(24..29, Advice::WillNeed), (24..29, Advice::WillNeed)]);
+/// # }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`Loader::bundle`]: crate::Loader::bundle
+pub struct ArchiveSource {
+    data: Arc<ArchiveData>,
+    index: HashMap<AssetId, Range<usize>>,
+    advisor: Option<Box<dyn ResidencyAdvisor>>,
+}
+
+impl ArchiveSource {
+    /// Returns new [`ArchiveSource`] that serves assets from archive data.
+    pub fn new(data: impl Into<Box<[u8]>>) -> Result<Self, ArchiveError> {
+        let data = data.into();
+        let index = read_archive_index(&data)?;
+        Ok(ArchiveSource {
+            data: Arc::new(ArchiveData::Owned(data)),
+            index,
+            advisor: None,
+        })
+    }
+
+    /// Returns new [`ArchiveSource`] that serves assets from archive file.
+    ///
+    /// Requires `fs` feature.
+    #[cfg(feature = "fs")]
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ArchiveError> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|error| ArchiveError::Io {
+            error,
+            path: path.to_owned(),
+        })?;
+        ArchiveSource::new(data)
+    }
+
+    /// Returns new [`ArchiveSource`] that serves assets from memory-mapped archive file.
+    ///
+    /// Pages of the archive are read on demand
+    /// and residency of asset data can be hinted with [`ArchiveSource::advise`].
+    /// Served asset data borrows the mapping, which is unmapped
+    /// when the source and all served [`AssetBytes`] are dropped.
+    ///
+    /// On platforms other than unix archive is read into memory as with [`ArchiveSource::open`].
+    ///
+    /// Requires `fs` feature.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or other process,
+    /// while the mapping is alive.
+    /// Modification changes bytes already served as asset data
+    /// and truncation makes reading them fault,
+    /// both are undefined behavior.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[derive(Clone)]
+    /// # struct Blob(Box<[u8]>);
+    /// # impl TrivialAsset for Blob {
+    /// #     type Error = std::convert::Infallible;
+    /// #     fn asset_name() -> AssetName { AssetName::new("Blob") }
+    /// #     fn decode(bytes: Box<[u8]>) -> Result<Self, Self::Error> { Ok(Blob(bytes)) }
+    /// # }
+    /// let id = AssetId::new(1).unwrap();
+    /// let source = MemorySource::new();
+    /// source.insert(id, &b"Hello"[..]);
+    /// let loader = Loader::builder().with(source).build();
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// let mut packed = Vec::new();
+    /// runtime.block_on(loader.bundle(id, &mut packed))?;
+    ///
+    /// let path = std::env::temp_dir().join(format!("argosy-map-{}.bin", std::process::id()));
+    /// std::fs::write(&path, &packed)?;
+    ///
+    /// // SAFETY: File is not modified until the loader is dropped.
+    /// let archive = unsafe { ArchiveSource::map(&path)? };
+    /// assert!(archive.advise(id, Advice::WillNeed));
+    ///
+    /// // Asset data is served from the mapping without copying.
+    /// let data = runtime.block_on(archive.load(id))?.unwrap();
+    /// assert!(matches!(data.bytes, AssetBytes::Shared { .. }));
+    /// assert_eq!(&*data.bytes, b"Hello");
+    ///
+    /// let loader = Loader::builder().with(archive).build();
+    ///
+    /// let served = runtime.block_on(async {
+    ///     let mut loaded = loader.load::<Blob, _>(id).await?;
+    ///     loaded.build(&mut ())
+    /// })?;
+    /// assert_eq!(*served.0, *b"Hello");
+    /// assert!(packed.ends_with(&served.0));
+    ///
+    /// drop(loader);
+    /// std::fs::remove_file(&path)?;
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "fs")]
+    pub unsafe fn map(path: impl AsRef<Path>) -> Result<Self, ArchiveError> {
+        #[cfg(unix)]
+        {
+            let path = path.as_ref();
+            let io_error = |error| ArchiveError::Io {
+                error,
+                path: path.to_owned(),
+            };
+
+            let file = std::fs::File::open(path).map_err(io_error)?;
+            // SAFETY: Caller guarantees that the file is not modified while it is mapped.
+            let Some(mapping) = (unsafe { Mapping::new(&file) }).map_err(io_error)? else {
+                return ArchiveSource::new(Vec::new());
+            };
+
+            let index = read_archive_index(mapping.bytes())?;
+            Ok(ArchiveSource {
+                data: Arc::new(ArchiveData::Mapped(mapping)),
+                index,
+                advisor: Some(Box::new(Madvise)),
+            })
+        }
+
+        #[cfg(not(unix))]
+        ArchiveSource::open(path)
+    }
+
+    /// Sets advisor that applies residency hints.
+    pub fn set_advisor(&mut self, advisor: impl ResidencyAdvisor) -> &mut Self {
+        self.advisor = Some(Box::new(advisor));
+        self
+    }
+
+    /// Sets advisor that applies residency hints.
+    pub fn with_advisor(mut self, advisor: impl ResidencyAdvisor) -> Self {
+        self.set_advisor(advisor);
+        self
+    }
+
+    /// Hints whether asset data will be needed soon,
+    /// so that streaming systems can read ahead upcoming assets
+    /// and release memory of passed ones.
+    ///
+    /// Hints are ignored if archive has no advisor,
+    /// e.g. if it is not memory-mapped.
+    ///
+    /// Returns `false` if asset is not in the archive.
+    pub fn advise(&self, id: AssetId, advice: Advice) -> bool {
+        let Some(range) = self.index.get(&id) else {
+            return false;
+        };
+        if let Some(advisor) = &self.advisor {
+            advisor.advise((*self.data).as_ref(), range.clone(), advice);
+        }
+        true
+    }
+}
+
+impl Source for ArchiveSource {
+    fn find<'a>(&'a self, _path: &'a str, _asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        // Archives contain only asset ids.
+        Box::pin(async move { None })
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move {
+            let range = self.index.get(&id);
+            Ok(range.map(|range| {
+                let buffer: Arc<dyn AsRef<[u8]> + Send + Sync> = self.data.clone();
+                archive_data(AssetBytes::shared_arc(buffer, range.clone()))
+            }))
+        })
+    }
+
+    fn update<'a>(
+        &'a self,
+        _id: AssetId,
+        _version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        // Archive never changes.
+        Box::pin(async move { Ok(None) })
+    }
+
+    fn supports_update(&self) -> bool {
+        false
+    }
+
+    fn advise(&self, id: AssetId, advice: Advice) {
+        ArchiveSource::advise(self, id, advice);
+    }
+}
+
+/// Source that serves assets from an archive embedded into the binary.
+/// Archive can be produced with [`Loader::bundle`]
+/// and embedded with [`include_bytes!`].
+///
+/// Asset data is served as [`AssetBytes::Static`] slices of the archive without copying.
+///
+/// [`Loader::bundle`]: crate::Loader::bundle
+pub struct EmbeddedSource {
+    data: &'static [u8],
+    index: HashMap<AssetId, Range<usize>>,
+}
+
+impl EmbeddedSource {
+    /// Returns new [`EmbeddedSource`] that serves assets from archive data.
+    pub fn new(data: &'static [u8]) -> Result<Self, ArchiveError> {
+        let index = read_archive_index(data)?;
+        Ok(EmbeddedSource { data, index })
+    }
+}
+
+impl Source for EmbeddedSource {
+    fn find<'a>(&'a self, _path: &'a str, _asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        // Archives contain only asset ids.
+        Box::pin(async move { None })
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move {
+            let range = self.index.get(&id);
+            Ok(range.map(|range| archive_data(AssetBytes::Static(&self.data[range.clone()]))))
+        })
+    }
+
+    fn update<'a>(
+        &'a self,
+        _id: AssetId,
+        _version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        // Archive never changes.
+        Box::pin(async move { Ok(None) })
+    }
+
+    fn supports_update(&self) -> bool {
+        false
+    }
+}
+
+fn archive_data(bytes: AssetBytes) -> AssetData {
+    AssetData {
+        bytes,
+        version: 0,
+        properties: AssetProperties::new(),
+    }
+}
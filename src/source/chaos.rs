@@ -0,0 +1,630 @@
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+
+use argosy_id::AssetId;
+use futures::{future::BoxFuture, stream::BoxStream};
+use parking_lot::Mutex;
+
+use crate::error::Error;
+
+use super::{Advice, AssetData, Source};
+
+/// Failure injected by [`ChaosSource`].
+#[derive(Debug, thiserror::Error)]
+#[error("Chaos source injected failure for asset '{id}'")]
+pub struct InjectedFault {
+    /// Asset identifier.
+    pub id: AssetId,
+}
+
+/// Numbers of faults injected by [`ChaosSource`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChaosStats {
+    /// Errors returned from [`Source::load`].
+    pub load_errors: u64,
+
+    /// Errors returned from [`Source::update`].
+    pub update_errors: u64,
+
+    /// Payloads cut short.
+    pub truncations: u64,
+
+    /// Updates reported with version older than requested.
+    pub version_regressions: u64,
+
+    /// Found ids that were then reported missing.
+    pub phantom_finds: u64,
+
+    /// Calls delayed with artificial latency.
+    pub delays: u64,
+}
+
+impl ChaosStats {
+    /// Returns total number of injected faults.
+    /// Delays are not counted as faults.
+    pub fn faults(&self) -> u64 {
+        self.load_errors
+            + self.update_errors
+            + self.truncations
+            + self.version_regressions
+            + self.phantom_finds
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    load_errors: AtomicU64,
+    update_errors: AtomicU64,
+    truncations: AtomicU64,
+    version_regressions: AtomicU64,
+    phantom_finds: AtomicU64,
+    delays: AtomicU64,
+}
+
+/// Probabilities of injected faults.
+#[derive(Clone, Copy, Default)]
+struct ChaosConfig {
+    errors: f64,
+    truncations: f64,
+    version_regressions: f64,
+    phantom_finds: f64,
+    fault_limit: Option<u64>,
+
+    #[cfg(feature = "tokio")]
+    latency: Option<(Duration, Duration)>,
+}
+
+/// Source wrapper that injects failures into the inner source.
+///
+/// Meant for testing how code built on the loader copes with flaky IO.
+/// Each fault happens with configured probability,
+/// decided by a random generator seeded with [`ChaosSourceBuilder::with_seed`].
+/// With the same seed the same sequence of calls gets the same faults.
+///
+/// Requires `test-util` feature.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let id = AssetId::new(1).unwrap();
+///
+/// let source = MemorySource::new();
+/// source.insert(id, &br#"{ "value": 7 }"#[..]);
+///
+/// let chaos = ChaosSource::builder(source)
+///     .with_seed(42)
+///     .with_errors(1.0)
+///     .with_fault_limit(1)
+///     .build();
+/// let stats = chaos.stats_handle();
+///
+/// let loader = Loader::builder().with(chaos).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let err = loader.load::<Number, _>(id).await.err().unwrap();
+///         assert!(err.is::<InjectedFault>());
+///         assert_eq!(err.stage(), ErrorStage::SourceLoad);
+///
+///         // Error is cached until retried.
+///         assert!(loader.load::<Number, _>(id).await.is_err());
+///         assert!(loader.retry(id));
+///
+///         let mut number = loader.load::<Number, _>(id).await?;
+///         assert_eq!(number.build(&mut ())?.value, 7);
+///         Ok::<_, Error>(())
+///     })?;
+///
+/// assert_eq!(stats.get().load_errors, 1);
+/// # Ok::<_, Error>(())
+/// ```
+pub struct ChaosSource<S> {
+    inner: S,
+    config: ChaosConfig,
+    rng: Mutex<u64>,
+    phantoms: Mutex<HashSet<AssetId>>,
+    counters: Arc<Counters>,
+}
+
+/// Shared view of [`ChaosSource`] counters.
+///
+/// Stays valid after the source is moved into the loader.
+#[derive(Clone)]
+pub struct ChaosStatsHandle {
+    counters: Arc<Counters>,
+}
+
+impl ChaosStatsHandle {
+    /// Returns numbers of faults injected so far.
+    pub fn get(&self) -> ChaosStats {
+        self.counters.snapshot()
+    }
+}
+
+impl Counters {
+    fn snapshot(&self) -> ChaosStats {
+        ChaosStats {
+            load_errors: self.load_errors.load(Ordering::Relaxed),
+            update_errors: self.update_errors.load(Ordering::Relaxed),
+            truncations: self.truncations.load(Ordering::Relaxed),
+            version_regressions: self.version_regressions.load(Ordering::Relaxed),
+            phantom_finds: self.phantom_finds.load(Ordering::Relaxed),
+            delays: self.delays.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Builder for [`ChaosSource`].
+///
+/// All faults are disabled by default.
+/// Probabilities are clamped to `0.0..=1.0`.
+pub struct ChaosSourceBuilder<S> {
+    inner: S,
+    config: ChaosConfig,
+    seed: u64,
+}
+
+impl<S> ChaosSourceBuilder<S> {
+    /// Sets seed of the random generator that decides which calls fail.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # use futures::executor::block_on;
+    /// let id = AssetId::new(1).unwrap();
+    ///
+    /// let run = |seed| {
+    ///     let source = MemorySource::new();
+    ///     source.insert(id, &b"0123456789"[..]);
+    ///
+    ///     let chaos = ChaosSource::builder(source)
+    ///         .with_seed(seed)
+    ///         .with_errors(0.3)
+    ///         .with_truncation(0.3)
+    ///         .build();
+    ///
+    ///     (0..100)
+    ///         .map(|_| match block_on(chaos.load(id)) {
+    ///             Err(_) => None,
+    ///             Ok(data) => Some(data.unwrap().bytes.len()),
+    ///         })
+    ///         .collect::<Vec<_>>()
+    /// };
+    ///
+    /// assert_eq!(run(7), run(7));
+    /// assert_ne!(run(7), run(8));
+    /// ```
+    pub fn set_seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets seed of the random generator that decides which calls fail.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.set_seed(seed);
+        self
+    }
+
+    /// Sets probability of [`Source::load`] and [`Source::update`]
+    /// returning [`InjectedFault`] error instead of calling inner source.
+    pub fn set_errors(&mut self, probability: f64) -> &mut Self {
+        self.config.errors = clamp(probability);
+        self
+    }
+
+    /// Sets probability of [`Source::load`] and [`Source::update`]
+    /// returning [`InjectedFault`] error instead of calling inner source.
+    pub fn with_errors(mut self, probability: f64) -> Self {
+        self.set_errors(probability);
+        self
+    }
+
+    /// Sets probability of loaded payload being cut short.
+    ///
+    /// Truncated payload fails to decode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let id = AssetId::new(1).unwrap();
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("number", id, &br#"{ "value": 7 }"#[..]);
+    ///
+    /// let chaos = ChaosSource::builder(source).with_truncation(1.0).build();
+    /// let stats = chaos.stats_handle();
+    ///
+    /// let loader = Loader::builder().with(chaos).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let err = loader.load::<Number, _>("number").await.err().unwrap();
+    ///         assert_eq!(err.stage(), ErrorStage::Decode);
+    ///     });
+    ///
+    /// assert_eq!(stats.get().truncations, 1);
+    /// ```
+    pub fn set_truncation(&mut self, probability: f64) -> &mut Self {
+        self.config.truncations = clamp(probability);
+        self
+    }
+
+    /// Sets probability of loaded payload being cut short.
+    pub fn with_truncation(mut self, probability: f64) -> Self {
+        self.set_truncation(probability);
+        self
+    }
+
+    /// Sets probability of [`Source::update`] returning data
+    /// with version older than requested one.
+    pub fn set_version_regressions(&mut self, probability: f64) -> &mut Self {
+        self.config.version_regressions = clamp(probability);
+        self
+    }
+
+    /// Sets probability of [`Source::update`] returning data
+    /// with version older than requested one.
+    pub fn with_version_regressions(mut self, probability: f64) -> Self {
+        self.set_version_regressions(probability);
+        self
+    }
+
+    /// Sets probability of [`Source::find`] returning id
+    /// that the next [`Source::load`] reports missing.
+    ///
+    /// # Example
+    ///
+    /// Missing asset is found after retry.
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use argosy::*;
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 7 }"#[..]);
+    ///
+    /// let chaos = ChaosSource::builder(source).with_phantom_finds(1.0).build();
+    /// let stats = chaos.stats_handle();
+    ///
+    /// let loader = Loader::builder().with(chaos).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .enable_time()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let options = LoadOptions::new()
+    ///             .with_missing(MissingPolicy::RetryAfter(Duration::from_millis(1)));
+    ///         let mut number = loader.load_with_options::<Number, _>("number", options).await?;
+    ///         assert_eq!(number.build(&mut ())?.value, 7);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    ///
+    /// assert_eq!(stats.get().phantom_finds, 1);
+    /// # Ok::<_, Error>(())
+    /// ```
+    ///
+    /// Lower priority source provides the asset.
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let id = AssetId::new(1).unwrap();
+    ///
+    /// let primary = MemorySource::new();
+    /// primary.insert_with_path("number", id, &br#"{ "value": 1 }"#[..]);
+    ///
+    /// let backup = MemorySource::new();
+    /// backup.insert(id, &br#"{ "value": 2 }"#[..]);
+    ///
+    /// let primary = ChaosSource::builder(primary).with_phantom_finds(1.0).build();
+    /// let loader = Loader::builder().with(primary).with(backup).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let mut number = loader.load::<Number, _>("number").await?;
+    ///         assert_eq!(number.metadata().source_index, 1);
+    ///         assert_eq!(number.build(&mut ())?.value, 2);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # Ok::<_, Error>(())
+    /// ```
+    pub fn set_phantom_finds(&mut self, probability: f64) -> &mut Self {
+        self.config.phantom_finds = clamp(probability);
+        self
+    }
+
+    /// Sets probability of [`Source::find`] returning id
+    /// that the next [`Source::load`] reports missing.
+    pub fn with_phantom_finds(mut self, probability: f64) -> Self {
+        self.set_phantom_finds(probability);
+        self
+    }
+
+    /// Sets maximum number of faults to inject.
+    /// After that calls are passed to the inner source as is.
+    pub fn set_fault_limit(&mut self, limit: u64) -> &mut Self {
+        self.config.fault_limit = Some(limit);
+        self
+    }
+
+    /// Sets maximum number of faults to inject.
+    /// After that calls are passed to the inner source as is.
+    pub fn with_fault_limit(mut self, limit: u64) -> Self {
+        self.set_fault_limit(limit);
+        self
+    }
+
+    /// Delays each call by duration uniformly distributed between `min` and `max`.
+    ///
+    /// Requires `tokio` feature.
+    /// Runtime must have time driver enabled.
+    ///
+    /// # Example
+    ///
+    /// Slow source is overtaken by the next one.
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use argosy::*;
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let id = AssetId::new(1).unwrap();
+    ///
+    /// let slow = MemorySource::new();
+    /// slow.insert(id, &br#"{ "value": 1 }"#[..]);
+    ///
+    /// let fast = MemorySource::new();
+    /// fast.insert(id, &br#"{ "value": 2 }"#[..]);
+    ///
+    /// let slow = ChaosSource::builder(slow)
+    ///     .with_latency(Duration::from_secs(5), Duration::from_secs(10))
+    ///     .build();
+    ///
+    /// let loader = Loader::builder()
+    ///     .with_source_strategy(SourceStrategy::Staggered { delay: Duration::from_millis(10) })
+    ///     .with(slow)
+    ///     .with(fast)
+    ///     .build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .enable_time()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let mut number = loader.load::<Number, _>(id).await?;
+    ///         assert_eq!(number.build(&mut ())?.value, 2);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    /// # Ok::<_, Error>(())
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn set_latency(&mut self, min: Duration, max: Duration) -> &mut Self {
+        self.config.latency = Some((min, max.max(min)));
+        self
+    }
+
+    /// Delays each call by duration uniformly distributed between `min` and `max`.
+    ///
+    /// Requires `tokio` feature.
+    /// Runtime must have time driver enabled.
+    #[cfg(feature = "tokio")]
+    pub fn with_latency(mut self, min: Duration, max: Duration) -> Self {
+        self.set_latency(min, max);
+        self
+    }
+
+    /// Builds [`ChaosSource`].
+    pub fn build(self) -> ChaosSource<S> {
+        ChaosSource {
+            inner: self.inner,
+            config: self.config,
+            rng: Mutex::new(self.seed),
+            phantoms: Mutex::new(HashSet::new()),
+            counters: Default::default(),
+        }
+    }
+}
+
+fn clamp(probability: f64) -> f64 {
+    if probability.is_nan() {
+        0.0
+    } else {
+        probability.clamp(0.0, 1.0)
+    }
+}
+
+impl<S> ChaosSource<S> {
+    /// Returns builder for [`ChaosSource`] that wraps `inner` source.
+    pub fn builder(inner: S) -> ChaosSourceBuilder<S> {
+        ChaosSourceBuilder {
+            inner,
+            config: ChaosConfig::default(),
+            seed: 0,
+        }
+    }
+
+    /// Returns inner source.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns numbers of faults injected so far.
+    pub fn stats(&self) -> ChaosStats {
+        self.counters.snapshot()
+    }
+
+    /// Returns handle to read numbers of injected faults
+    /// after the source is added to the loader.
+    pub fn stats_handle(&self) -> ChaosStatsHandle {
+        ChaosStatsHandle {
+            counters: self.counters.clone(),
+        }
+    }
+
+    /// Returns next random value with splitmix64.
+    fn next_u64(&self) -> u64 {
+        let mut state = self.rng.lock();
+        *state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns random value in `0.0..1.0`.
+    fn next_f64(&self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Decides whether to inject a fault and counts it.
+    ///
+    /// Random generator is not advanced for disabled faults,
+    /// so enabling one fault does not change decisions of others
+    /// as long as it never fires.
+    fn roll(&self, probability: f64, counter: &AtomicU64) -> bool {
+        if probability <= 0.0 {
+            return false;
+        }
+        if let Some(limit) = self.config.fault_limit {
+            if self.stats().faults() >= limit {
+                return false;
+            }
+        }
+        if self.next_f64() >= probability {
+            return false;
+        }
+        counter.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    async fn delay(&self) {
+        #[cfg(feature = "tokio")]
+        if let Some((min, max)) = self.config.latency {
+            let delay = min + (max - min).mul_f64(self.next_f64());
+            self.counters.delays.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn truncate(&self, mut data: AssetData) -> AssetData {
+        if !data.bytes.is_empty() && self.roll(self.config.truncations, &self.counters.truncations)
+        {
+            let len = (self.next_u64() % data.bytes.len() as u64) as usize;
+            data.bytes = data.bytes[..len].into();
+        }
+        data
+    }
+}
+
+impl<S> Source for ChaosSource<S>
+where
+    S: Source,
+{
+    fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        Box::pin(async move {
+            self.delay().await;
+            let id = self.inner.find(path, asset).await?;
+            if self.roll(self.config.phantom_finds, &self.counters.phantom_finds) {
+                self.phantoms.lock().insert(id);
+            }
+            Some(id)
+        })
+    }
+
+    fn canonical_path<'a>(&self, path: &'a str) -> Option<Cow<'a, str>> {
+        self.inner.canonical_path(path)
+    }
+
+    fn find_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+        asset: &'a str,
+    ) -> BoxStream<'a, (String, AssetId)> {
+        self.inner.find_prefix(prefix, asset)
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move {
+            self.delay().await;
+            if self.phantoms.lock().remove(&id) {
+                return Ok(None);
+            }
+            if self.roll(self.config.errors, &self.counters.load_errors) {
+                return Err(Error::new(InjectedFault { id }));
+            }
+            let data = self.inner.load(id).await?;
+            Ok(data.map(|data| self.truncate(data)))
+        })
+    }
+
+    fn update<'a>(
+        &'a self,
+        id: AssetId,
+        version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move {
+            self.delay().await;
+            if self.roll(self.config.errors, &self.counters.update_errors) {
+                return Err(Error::new(InjectedFault { id }));
+            }
+            let Some(mut data) = self.inner.update(id, version).await? else {
+                return Ok(None);
+            };
+            if self.roll(
+                self.config.version_regressions,
+                &self.counters.version_regressions,
+            ) {
+                data.version = version.saturating_sub(1);
+            }
+            Ok(Some(self.truncate(data)))
+        })
+    }
+
+    fn supports_update(&self) -> bool {
+        self.inner.supports_update()
+    }
+
+    fn advise(&self, id: AssetId, advice: Advice) {
+        self.inner.advise(id, advice);
+    }
+}
@@ -0,0 +1,146 @@
+use std::{borrow::Cow, sync::Arc, time::Duration};
+
+use argosy_id::AssetId;
+use futures::{future::BoxFuture, stream::BoxStream};
+
+use crate::{clock::Clock, error::Error};
+
+use super::{Advice, AssetData, Source};
+
+/// Source that answers after a delay measured by a [`Clock`].
+///
+/// With `VirtualClock` shared with the loader,
+/// slow sources are simulated without real waiting.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use std::time::Duration;
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// async fn settle() {
+///     for _ in 0..20 {
+///         tokio::task::yield_now().await;
+///     }
+/// }
+///
+/// let id = AssetId::new(1).unwrap();
+/// let clock = VirtualClock::new();
+///
+/// let slow = MemorySource::new();
+/// slow.insert_with_path("number", id, &br#"{ "value": 1 }"#[..]);
+/// let fast = MemorySource::new();
+/// fast.insert_with_path("number", id, &br#"{ "value": 2 }"#[..]);
+///
+/// let loader = Loader::builder()
+///     .with(DelaySource::new(slow, clock.clone(), Duration::from_secs(10)))
+///     .with(DelaySource::new(fast, clock.clone(), Duration::from_secs(1)))
+///     .with_source_strategy(SourceStrategy::Staggered {
+///         delay: Duration::from_secs(2),
+///     })
+///     .with_clock(clock.clone())
+///     .build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let start = clock.now();
+///         let mut number = loader.load::<Number, _>("number");
+///         settle().await;
+///
+///         // Second source is asked when first is slower than stagger delay.
+///         assert_eq!(clock.advance_to_next(), Some(Duration::from_secs(2)));
+///         settle().await;
+///         assert!(number.poll_loaded().is_none());
+///
+///         // Second source answers first.
+///         assert_eq!(clock.advance_to_next(), Some(Duration::from_secs(1)));
+///         settle().await;
+///         while clock.advance_to_next().is_some() {
+///             settle().await;
+///         }
+///
+///         assert_eq!(number.await?.build(&mut ())?.value, 2);
+///         // Load by id is staggered the same way.
+///         assert_eq!(clock.now() - start, Duration::from_secs(6));
+///         Ok::<_, Error>(())
+///     })?;
+/// # Ok::<_, Error>(())
+/// ```
+pub struct DelaySource<S> {
+    inner: S,
+    clock: Arc<dyn Clock>,
+    delay: Duration,
+}
+
+impl<S> DelaySource<S> {
+    /// Returns new [`DelaySource`] that answers calls to `inner` source
+    /// after `delay` passes on `clock`.
+    pub fn new(inner: S, clock: impl Clock, delay: Duration) -> Self {
+        DelaySource {
+            inner,
+            clock: Arc::new(clock),
+            delay,
+        }
+    }
+
+    /// Returns delay of each call.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+impl<S> Source for DelaySource<S>
+where
+    S: Source,
+{
+    fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        Box::pin(async move {
+            self.clock.sleep(self.delay).await;
+            self.inner.find(path, asset).await
+        })
+    }
+
+    fn canonical_path<'a>(&self, path: &'a str) -> Option<Cow<'a, str>> {
+        self.inner.canonical_path(path)
+    }
+
+    fn find_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+        asset: &'a str,
+    ) -> BoxStream<'a, (String, AssetId)> {
+        self.inner.find_prefix(prefix, asset)
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move {
+            self.clock.sleep(self.delay).await;
+            self.inner.load(id).await
+        })
+    }
+
+    fn update<'a>(
+        &'a self,
+        id: AssetId,
+        version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move {
+            self.clock.sleep(self.delay).await;
+            self.inner.update(id, version).await
+        })
+    }
+
+    fn supports_update(&self) -> bool {
+        self.inner.supports_update()
+    }
+
+    fn advise(&self, id: AssetId, advice: Advice) {
+        self.inner.advise(id, advice);
+    }
+}
@@ -1,30 +1,150 @@
 use std::{
     fs::File,
     io::{Read, Seek, SeekFrom},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::OnceLock,
     time::SystemTime,
 };
 
 use argosy_id::AssetId;
 use futures::future::BoxFuture;
 
-use crate::error::Error;
-
-use super::{AssetData, Source};
-
+use crate::error::{Error, ErrorCode};
+
+use super::{path_index::PathIndex, AssetData, AssetProperties, Source};
+
+/// Source that loads assets from files in a directory.
+/// Each asset is stored in a file named after its [`AssetId`]
+/// in zero-padded 16-digit hex form.
+///
+/// Assets are found by path using index file, a JSON object that maps paths
+/// either to ids of assets of any type or to objects that map asset names to ids.
+/// Index is read on first lookup and kept for the lifetime of the source.
+/// Without index file assets can be loaded only by id.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// #[derive(Clone, Asset)]
+/// struct Texture {
+///     size: u32,
+/// }
+///
+/// #[derive(Clone, Asset)]
+/// struct Sound {
+///     volume: u32,
+/// }
+///
+/// let root = std::env::temp_dir().join(format!("argosy-files-{}", std::process::id()));
+/// std::fs::create_dir_all(&root).unwrap();
+/// std::fs::write(root.join("0000000000000001"), r#"{ "size": 16 }"#).unwrap();
+/// std::fs::write(root.join("0000000000000002"), r#"{ "volume": 3 }"#).unwrap();
+/// std::fs::write(
+///     root.join("index.json"),
+///     r#"{ "ui/icon": "1", "ui/click": { "Sound": "2" } }"#,
+/// )
+/// .unwrap();
+///
+/// let loader = Loader::builder().with(FileSource::new(&root)).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let icon = loader.load::<Texture, _>("./ui/icon").await?.build(&mut ())?;
+///         assert_eq!(icon.size, 16);
+///
+///         let click = loader.load::<Sound, _>("ui/click").await?.build(&mut ())?;
+///         assert_eq!(click.volume, 3);
+///
+///         // Path is indexed for sounds only.
+///         let err = loader.load::<Texture, _>("ui/click").await.err().unwrap();
+///         assert!(err.is_not_found());
+///         Ok::<_, Error>(())
+///     })?;
+///
+/// // Index at custom path.
+/// std::fs::rename(root.join("index.json"), root.join("paths.json")).unwrap();
+/// let source = FileSource::with_index(&root, "paths.json");
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let icon = loader.load::<Texture, _>("ui/icon").await?.build(&mut ())?;
+///         assert_eq!(icon.size, 16);
+///         Ok::<_, Error>(())
+///     })?;
+///
+/// std::fs::remove_dir_all(&root).unwrap();
+/// # Ok::<_, Error>(())
+/// ```
 pub struct FileSource {
     root: PathBuf,
+    index_path: PathBuf,
+    index: OnceLock<PathIndex>,
 }
 
-impl Source for FileSource {
+impl FileSource {
+    /// Name of the index file that [`FileSource::new`] looks for in the root directory.
+    pub const INDEX_FILE: &'static str = "index.json";
+
+    /// Returns new [`FileSource`] that loads assets from `root` directory.
+    ///
+    /// Finds assets by path using [`FileSource::INDEX_FILE`] in `root` directory if it exists.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FileSource::with_index(root, Self::INDEX_FILE)
+    }
 
-    fn find<'a>(&'a self, _path: &'a str, _asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
-        // Somewhat counter-intuitively, FileSource does not support path-based asset lookup.
-        Box::pin(async move { None })
+    /// Returns new [`FileSource`] that loads assets from `root` directory
+    /// and finds them by path using index file at `index_path`.
+    ///
+    /// Relative `index_path` is resolved against `root`.
+    pub fn with_index(root: impl Into<PathBuf>, index_path: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let index_path = root.join(index_path.into());
+        FileSource {
+            root,
+            index_path,
+            index: OnceLock::new(),
+        }
+    }
+
+    fn index(&self) -> &PathIndex {
+        self.index.get_or_init(|| read_index(&self.index_path))
+    }
+}
+
+/// Reads index file.
+/// Missing or malformed index is reported and treated as empty.
+fn read_index(path: &Path) -> PathIndex {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return PathIndex::default(),
+        Err(err) => {
+            tracing::error!("Failed to read asset index '{}': {}", path.display(), err);
+            return PathIndex::default();
+        }
+    };
+
+    match PathIndex::from_json(&bytes) {
+        Ok(index) => index,
+        Err(err) => {
+            tracing::error!("Failed to parse asset index '{}': {}", path.display(), err);
+            PathIndex::default()
+        }
+    }
+}
+
+impl Source for FileSource {
+    fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        Box::pin(async move { self.index().find(path, asset) })
     }
 
     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
-        let path = self.root.join(id.to_string());
+        let path = self.root.join(id.to_padded_hex());
 
         Box::pin(async move {
             let mut file = match File::open(&path) {
@@ -43,7 +163,8 @@ impl Source for FileSource {
                 return Err(Error::new(std::io::Error::new(
                     std::io::ErrorKind::InvalidData,
                     "Asset is too large",
-                )));
+                ))
+                .with_code(ErrorCode::TooLarge));
             };
 
             file.rewind().map_err(Error::new)?;
@@ -52,8 +173,9 @@ impl Source for FileSource {
             file.read_to_end(&mut data).map_err(Error::new)?;
 
             Ok(Some(AssetData {
-                bytes: data.into_boxed_slice(),
-                version: version,
+                bytes: data.into(),
+                version,
+                properties: file_properties(id, modified.map(|_| version)),
             }))
         })
     }
@@ -63,7 +185,7 @@ impl Source for FileSource {
         id: AssetId,
         version: u64,
     ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
-        let path = self.root.join(id.to_string());
+        let path = self.root.join(id.to_padded_hex());
 
         Box::pin(async move {
             let mut file = match File::open(&path) {
@@ -86,7 +208,8 @@ impl Source for FileSource {
                 return Err(Error::new(std::io::Error::new(
                     std::io::ErrorKind::InvalidData,
                     "Asset is too large",
-                )));
+                ))
+                .with_code(ErrorCode::TooLarge));
             };
 
             file.rewind().map_err(Error::new)?;
@@ -95,9 +218,20 @@ impl Source for FileSource {
             file.read_to_end(&mut data).map_err(Error::new)?;
 
             Ok(Some(AssetData {
-                bytes: data.into_boxed_slice(),
+                bytes: data.into(),
                 version: new_version,
+                properties: file_properties(id, modified.map(|_| new_version)),
             }))
         })
     }
 }
+
+/// Returns properties of the asset file.
+/// Reports file name and modification time in seconds since UNIX epoch if known.
+fn file_properties(id: AssetId, mtime: Option<u64>) -> AssetProperties {
+    let properties = AssetProperties::new().with("filename", id.to_padded_hex());
+    match mtime {
+        None => properties,
+        Some(mtime) => properties.with("mtime", mtime.to_string()),
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    collections::VecDeque,
+    io::{Read, Seek},
+};
+
+use argosy_id::AssetId;
+use parking_lot::Mutex;
+
+use crate::error::Error;
+
+use super::{archive::ArchiveError, path_index::PathIndex, AssetData, AssetProperties};
+
+/// Name of the entry with path index embedded into archive files.
+const INDEX_ENTRY: &str = "index.json";
+
+/// Reader of archive file.
+pub(crate) trait ReadSeek: Read + Seek + Send + 'static {}
+
+impl<T> ReadSeek for T where T: Read + Seek + Send + 'static {}
+
+/// Entry of archive file that holds asset data.
+pub(crate) struct IndexedEntry {
+    /// Name of the entry in the archive.
+    pub name: String,
+
+    /// Version reported for the asset data.
+    pub version: u64,
+}
+
+/// Archive file format that can read entries by asset id.
+pub(crate) trait ArchiveIndex: Send + 'static {
+    /// Reads data of the entry with specified name.
+    /// Used to read embedded path index.
+    fn read_named(&mut self, name: &str) -> std::io::Result<Option<Vec<u8>>>;
+
+    /// Returns entry with data of the asset.
+    fn entry(&self, id: AssetId) -> Option<&IndexedEntry>;
+
+    /// Reads data of the asset.
+    fn read(&mut self, id: AssetId) -> std::io::Result<Option<Vec<u8>>>;
+}
+
+/// Returns id of the asset stored in entry with specified name.
+///
+/// Assets are stored in entries named after their ids in zero-padded 16-digit hex form,
+/// possibly in a directory.
+pub(crate) fn entry_asset_id(name: &str) -> Option<AssetId> {
+    let file_name = name.rsplit('/').next()?;
+    if file_name.len() != 16 {
+        return None;
+    }
+    file_name.parse().ok()
+}
+
+/// Archive file shared by archive format sources.
+///
+/// Serializes reads of the archive and keeps recently read entries.
+pub(crate) struct IndexedArchive<I> {
+    index: Mutex<I>,
+    paths: PathIndex,
+    recent: Mutex<VecDeque<(AssetId, Box<[u8]>)>>,
+    capacity: usize,
+}
+
+impl<I> IndexedArchive<I>
+where
+    I: ArchiveIndex,
+{
+    /// Returns new archive, reading embedded path index if there is one.
+    pub fn new(mut index: I, capacity: usize) -> Result<Self, ArchiveError> {
+        let paths = match index.read_named(INDEX_ENTRY).map_err(ArchiveError::Read)? {
+            None => PathIndex::default(),
+            Some(bytes) => PathIndex::from_json(&bytes).map_err(ArchiveError::InvalidIndex)?,
+        };
+
+        Ok(IndexedArchive {
+            index: Mutex::new(index),
+            paths,
+            recent: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        })
+    }
+
+    /// Sets number of recently read entries to keep.
+    #[cfg(feature = "zip")]
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.recent.get_mut().truncate(capacity);
+    }
+
+    pub fn find(&self, path: &str, asset: &str) -> Option<AssetId> {
+        self.paths.find(path, asset)
+    }
+
+    pub fn load(&self, id: AssetId) -> Result<Option<AssetData>, Error> {
+        let mut index = self.index.lock();
+        let Some(entry) = index.entry(id) else {
+            return Ok(None);
+        };
+        let version = entry.version;
+        let properties = AssetProperties::new().with("entry", entry.name.as_str());
+
+        let mut recent = self.recent.lock();
+        if let Some(position) = recent.iter().position(|(recent, _)| *recent == id) {
+            let (id, bytes) = recent.remove(position).unwrap();
+            let data = bytes.clone();
+            recent.push_back((id, bytes));
+            return Ok(Some(AssetData {
+                bytes: data.into(),
+                version,
+                properties,
+            }));
+        }
+        drop(recent);
+
+        let Some(bytes) = index.read(id).map_err(Error::new)? else {
+            return Ok(None);
+        };
+        drop(index);
+
+        let bytes = bytes.into_boxed_slice();
+        if self.capacity > 0 {
+            let mut recent = self.recent.lock();
+            if recent.len() >= self.capacity {
+                recent.pop_front();
+            }
+            recent.push_back((id, bytes.clone()));
+        }
+
+        Ok(Some(AssetData {
+            bytes: bytes.into(),
+            version,
+            properties,
+        }))
+    }
+}
@@ -0,0 +1,206 @@
+use std::{io::Write, sync::Arc};
+
+use argosy_id::AssetId;
+use hashbrown::HashMap;
+
+use super::{archive::ArchiveError, AssetBytes, AssetData, AssetProperties};
+
+/// Magic bytes at the start of serialized inline assets.
+const MAGIC: [u8; 8] = *b"ARGOSYI1";
+
+/// Data of the asset kept in [`InlineAssets`].
+struct InlineAsset {
+    bytes: AssetBytes,
+    version: u64,
+    properties: AssetProperties,
+}
+
+/// Index of small assets with their data, fetched from a source at once.
+///
+/// Returned by [`Source::bulk_hint`].
+/// Loader serves assets found in the index without calling [`Source::load`].
+///
+/// Can be stored with [`InlineAssets::write`] and read back with [`InlineAssets::read`].
+///
+/// # Example
+///
+/// ```
+/// # use argosy::{*, source::prelude::*};
+/// let mut inline = InlineAssets::new();
+/// inline.insert(
+///     AssetId::new(1).unwrap(),
+///     AssetData {
+///         bytes: (*b"Hello").into(),
+///         version: 3,
+///         properties: AssetProperties::new().with("source", "hello.txt"),
+///     },
+/// );
+///
+/// let mut blob = Vec::new();
+/// inline.write(&mut blob).unwrap();
+/// let inline = InlineAssets::read(&blob).unwrap();
+///
+/// let data = inline.get(AssetId::new(1).unwrap()).unwrap();
+/// assert_eq!(&*data.bytes, b"Hello");
+/// assert!(matches!(data.bytes, AssetBytes::Shared { .. }));
+/// assert_eq!(data.version, 3);
+/// assert_eq!(data.properties.get("source").map(|s| &**s), Some("hello.txt"));
+/// assert!(inline.get(AssetId::new(2).unwrap()).is_none());
+/// ```
+///
+/// [`Source::bulk_hint`]: super::Source::bulk_hint
+/// [`Source::load`]: super::Source::load
+#[derive(Default)]
+pub struct InlineAssets {
+    assets: HashMap<AssetId, InlineAsset>,
+}
+
+impl InlineAssets {
+    /// Returns empty index.
+    pub fn new() -> Self {
+        InlineAssets::default()
+    }
+
+    /// Returns number of assets in the index.
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    /// Returns `true` if there are no assets in the index.
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Adds asset data to the index, replacing previous data of the asset.
+    pub fn insert(&mut self, id: AssetId, data: AssetData) {
+        self.assets.insert(
+            id,
+            InlineAsset {
+                bytes: share(data.bytes),
+                version: data.version,
+                properties: data.properties,
+            },
+        );
+    }
+
+    /// Returns `true` if index has data of the asset.
+    pub fn contains(&self, id: AssetId) -> bool {
+        self.assets.contains_key(&id)
+    }
+
+    /// Returns the asset data.
+    /// Bytes are shared with the index, not copied.
+    pub fn get(&self, id: AssetId) -> Option<AssetData> {
+        let asset = self.assets.get(&id)?;
+        Some(AssetData {
+            bytes: asset.bytes.clone(),
+            version: asset.version,
+            properties: asset.properties.clone(),
+        })
+    }
+
+    /// Replaces ids of assets, dropping assets for which `f` returns `None`.
+    pub(crate) fn filter_map_ids(self, mut f: impl FnMut(AssetId) -> Option<AssetId>) -> Self {
+        let assets = self
+            .assets
+            .into_iter()
+            .filter_map(|(id, asset)| Some((f(id)?, asset)))
+            .collect();
+        InlineAssets { assets }
+    }
+
+    /// Writes the index with asset data.
+    /// Assets are written in order of their ids.
+    pub fn write(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(&MAGIC)?;
+
+        let mut ids: Vec<_> = self.assets.keys().copied().collect();
+        ids.sort_unstable();
+
+        for id in ids {
+            let asset = &self.assets[&id];
+            writer.write_all(&id.value().get().to_le_bytes())?;
+            writer.write_all(&asset.version.to_le_bytes())?;
+            writer.write_all(&(asset.properties.len() as u64).to_le_bytes())?;
+            for (key, value) in asset.properties.iter() {
+                write_chunk(writer, key.as_bytes())?;
+                write_chunk(writer, value.as_bytes())?;
+            }
+            write_chunk(writer, &asset.bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Reads index written with [`InlineAssets::write`].
+    pub fn read(data: &[u8]) -> Result<Self, ArchiveError> {
+        let Some(mut data) = data.strip_prefix(&MAGIC) else {
+            return Err(ArchiveError::InvalidHeader);
+        };
+
+        let mut assets = HashMap::new();
+        while !data.is_empty() {
+            let id = read_u64(&mut data)?;
+            let id = AssetId::new(id).ok_or(ArchiveError::ZeroId)?;
+            let version = read_u64(&mut data)?;
+
+            let mut properties = AssetProperties::new();
+            for _ in 0..read_u64(&mut data)? {
+                let key = read_str(&mut data)?;
+                let value = read_str(&mut data)?;
+                properties.insert(key, value);
+            }
+
+            let bytes = Arc::<[u8]>::from(read_chunk(&mut data)?).into();
+            assets.insert(
+                id,
+                InlineAsset {
+                    bytes,
+                    version,
+                    properties,
+                },
+            );
+        }
+
+        Ok(InlineAssets { assets })
+    }
+}
+
+/// Moves owned bytes to shared buffer, so they are not copied when served.
+fn share(bytes: AssetBytes) -> AssetBytes {
+    match bytes {
+        AssetBytes::Owned(bytes) => Arc::<[u8]>::from(bytes).into(),
+        bytes => bytes,
+    }
+}
+
+/// Writes length-prefixed bytes.
+fn write_chunk(writer: &mut impl Write, bytes: &[u8]) -> std::io::Result<()> {
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(bytes)
+}
+
+fn read_u64(data: &mut &[u8]) -> Result<u64, ArchiveError> {
+    let Some((value, rest)) = data.split_first_chunk::<8>() else {
+        return Err(ArchiveError::Truncated);
+    };
+    *data = rest;
+    Ok(u64::from_le_bytes(*value))
+}
+
+/// Reads length-prefixed bytes.
+fn read_chunk<'a>(data: &mut &'a [u8]) -> Result<&'a [u8], ArchiveError> {
+    let len = read_u64(data)?;
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|&len| len <= data.len())
+        .ok_or(ArchiveError::Truncated)?;
+    let (chunk, rest) = data.split_at(len);
+    *data = rest;
+    Ok(chunk)
+}
+
+fn read_str(data: &mut &[u8]) -> Result<Arc<str>, ArchiveError> {
+    let chunk = read_chunk(data)?;
+    let string = std::str::from_utf8(chunk).map_err(|_| ArchiveError::InvalidHeader)?;
+    Ok(string.into())
+}
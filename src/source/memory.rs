@@ -0,0 +1,140 @@
+use std::{borrow::Cow, sync::Arc};
+
+use argosy_id::AssetId;
+use futures::{future::BoxFuture, stream::BoxStream, StreamExt};
+use hashbrown::HashMap;
+use parking_lot::RwLock;
+
+use crate::error::Error;
+
+use super::{normalize_path, AssetData, AssetProperties, Source};
+
+#[derive(Default)]
+struct Inner {
+    assets: HashMap<AssetId, (Arc<[u8]>, u64)>,
+    paths: HashMap<String, AssetId>,
+    properties: HashMap<AssetId, AssetProperties>,
+}
+
+impl Inner {
+    fn properties(&self, id: AssetId) -> AssetProperties {
+        self.properties.get(&id).cloned().unwrap_or_default()
+    }
+}
+
+/// Source that serves assets from memory.
+///
+/// Clones share the same storage, so assets can be added
+/// after the source is added to the loader.
+///
+/// Paths are normalized with [`normalize_path`],
+/// so aliases like `ui//icon.png` and `./ui/icon.png` find the same asset.
+#[derive(Clone, Default)]
+pub struct MemorySource {
+    inner: Arc<RwLock<Inner>>,
+}
+
+impl MemorySource {
+    /// Returns new empty [`MemorySource`].
+    pub fn new() -> Self {
+        MemorySource::default()
+    }
+
+    /// Inserts asset data with specified id.
+    /// Replaces previous data and bumps its version.
+    pub fn insert(&self, id: AssetId, bytes: impl Into<Arc<[u8]>>) {
+        let mut inner = self.inner.write();
+        let version = inner.assets.get(&id).map_or(0, |(_, version)| version + 1);
+        inner.assets.insert(id, (bytes.into(), version));
+    }
+
+    /// Inserts asset data with specified id and makes it findable by path.
+    pub fn insert_with_path(
+        &self,
+        path: impl Into<String>,
+        id: AssetId,
+        bytes: impl Into<Arc<[u8]>>,
+    ) {
+        let path = path.into();
+        let path = match normalize_path(&path) {
+            Cow::Borrowed(_) => path,
+            Cow::Owned(normalized) => normalized,
+        };
+
+        self.insert(id, bytes);
+        self.inner.write().paths.insert(path, id);
+    }
+
+    /// Sets properties reported with asset data with specified id.
+    /// Properties are kept when asset data is replaced.
+    pub fn set_properties(&self, id: AssetId, properties: AssetProperties) {
+        self.inner.write().properties.insert(id, properties);
+    }
+
+    /// Removes asset data with specified id.
+    /// Paths to the asset are kept.
+    pub fn remove(&self, id: AssetId) {
+        self.inner.write().assets.remove(&id);
+    }
+}
+
+impl Source for MemorySource {
+    fn find<'a>(&'a self, path: &'a str, _asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        Box::pin(async move {
+            let path = normalize_path(path);
+            self.inner.read().paths.get(&*path).copied()
+        })
+    }
+
+    fn canonical_path<'a>(&self, path: &'a str) -> Option<Cow<'a, str>> {
+        match normalize_path(path) {
+            Cow::Borrowed(_) => None,
+            normalized => Some(normalized),
+        }
+    }
+
+    fn find_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+        _asset: &'a str,
+    ) -> BoxStream<'a, (String, AssetId)> {
+        let found: Vec<_> = self
+            .inner
+            .read()
+            .paths
+            .iter()
+            .filter(|(path, _)| path.starts_with(prefix))
+            .map(|(path, id)| (path.clone(), *id))
+            .collect();
+        futures::stream::iter(found).boxed()
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move {
+            let inner = self.inner.read();
+            Ok(inner.assets.get(&id).map(|(bytes, version)| AssetData {
+                bytes: (**bytes).into(),
+                version: *version,
+                properties: inner.properties(id),
+            }))
+        })
+    }
+
+    fn update<'a>(
+        &'a self,
+        id: AssetId,
+        version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move {
+            let inner = self.inner.read();
+            match inner.assets.get(&id) {
+                Some((bytes, new_version)) if *new_version > version => Ok(Some(AssetData {
+                    bytes: (**bytes).into(),
+                    version: *new_version,
+                    properties: inner.properties(id),
+                })),
+                _ => Ok(None),
+            }
+        })
+    }
+}
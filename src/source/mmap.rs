@@ -0,0 +1,109 @@
+use std::{fs::File, io, ops::Range, os::unix::io::AsRawFd, ptr::NonNull};
+
+use super::{archive::ResidencyAdvisor, Advice};
+
+/// Read-only private mapping of a file.
+pub(crate) struct Mapping {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// SAFETY: Mapping is read-only and never mutated through the pointer.
+unsafe impl Send for Mapping {}
+
+// SAFETY: Mapping is read-only and never mutated through the pointer.
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    /// Maps whole file into memory.
+    /// Returns `None` for empty file, which cannot be mapped.
+    ///
+    /// # Safety
+    ///
+    /// File must not be modified or truncated while the mapping is alive.
+    pub unsafe fn new(file: &File) -> io::Result<Option<Self>> {
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::OutOfMemory, "File is too large to map"))?;
+
+        if len == 0 {
+            return Ok(None);
+        }
+
+        // SAFETY: Mapping a valid file descriptor with valid length.
+        // Mapping is private and read-only.
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Some(Mapping {
+            ptr: NonNull::new(ptr.cast()).unwrap(),
+            len,
+        }))
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        // SAFETY: Pointer and length describe live read-only mapping.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // SAFETY: Unmapping the mapping created in `Mapping::new`.
+        // No references to the bytes outlive `self`.
+        unsafe {
+            libc::munmap(self.ptr.as_ptr().cast(), self.len);
+        }
+    }
+}
+
+/// Advisor that passes hints to the kernel with `madvise`.
+///
+/// Must be used only with mapped archives,
+/// `MADV_DONTNEED` discards contents of anonymous memory.
+pub(crate) struct Madvise;
+
+impl ResidencyAdvisor for Madvise {
+    fn advise(&self, data: &[u8], range: Range<usize>, advice: Advice) {
+        if range.is_empty() {
+            return;
+        }
+
+        // SAFETY: `sysconf` has no preconditions.
+        let page = match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+            page if page > 0 => page as usize,
+            _ => 4096,
+        };
+
+        // Address passed to `madvise` must be page-aligned.
+        let start = data[range.start..].as_ptr() as usize;
+        let aligned = start & !(page - 1);
+        let len = range.len() + (start - aligned);
+
+        let advice = match advice {
+            Advice::WillNeed => libc::MADV_WILLNEED,
+            Advice::DontNeed => libc::MADV_DONTNEED,
+        };
+
+        // SAFETY: Range lies within the mapping, aligned start is the page of its first byte.
+        // Private read-only file pages are reloaded from the file after `MADV_DONTNEED`.
+        let result = unsafe { libc::madvise(aligned as *mut libc::c_void, len, advice) };
+        if result != 0 {
+            tracing::debug!(
+                "Failed to advise archive pages. {:#}",
+                io::Error::last_os_error()
+            );
+        }
+    }
+}
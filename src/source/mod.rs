@@ -1,37 +1,631 @@
-pub mod fs;
+//! Asset sources.
+//!
+//! Sources are implemented with futures from the [`futures`](crate::futures) crate
+//! re-exported by argosy, so that version used by the source always matches.
+//! Everything required to implement [`Source`] is in the [`prelude`].
+
+pub(crate) mod archive;
+#[cfg(feature = "test-util")]
+pub(crate) mod chaos;
+#[cfg(feature = "test-util")]
+pub(crate) mod delay;
+#[cfg(feature = "fs")]
+pub(crate) mod fs;
+mod indexed;
+pub(crate) mod inline;
+pub(crate) mod memory;
+#[cfg(all(feature = "fs", unix))]
+mod mmap;
+pub(crate) mod namespaced;
+mod path_index;
+pub(crate) mod replay;
+pub(crate) mod tar;
+#[cfg(feature = "zip")]
+pub(crate) mod zip;
+
+use std::{borrow::Cow, fmt, ops::Range, sync::Arc};
 
 use argosy_id::AssetId;
-use futures::{future::BoxFuture, TryFutureExt};
+use futures::{future::BoxFuture, stream::BoxStream, StreamExt};
 
 use crate::error::Error;
 
+use self::inline::InlineAssets;
+
 /// Asset data loaded from [`Source`].
 pub struct AssetData {
     /// Serialized asset data.
-    pub bytes: Box<[u8]>,
+    pub bytes: AssetBytes,
 
     /// Opaque version for asset.
     /// It can only by interpreted by [`Source`]
     /// that returned this [`AssetData`] instance.
     pub version: u64,
+
+    /// Properties of the asset known to the source.
+    pub properties: AssetProperties,
+}
+
+/// Bytes of asset data.
+///
+/// Sources that keep data in memory serve it without copying,
+/// as static slices or as ranges of shared buffers.
+/// Bytes are copied only when asset is decoded from owned bytes.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use argosy::source::prelude::*;
+/// let archive: Arc<[u8]> = (*b"headerHello").into();
+/// let bytes = AssetBytes::shared(archive, 6..11);
+/// assert_eq!(&*bytes, b"Hello");
+/// assert_eq!(&*bytes.into_boxed(), b"Hello");
+///
+/// // Borrowed slices are copied.
+/// let bytes = AssetBytes::from(&b"Hello"[..]);
+/// assert!(matches!(bytes, AssetBytes::Owned(_)));
+/// ```
+#[derive(Clone)]
+pub enum AssetBytes {
+    /// Bytes owned by the asset data.
+    Owned(Box<[u8]>),
+
+    /// Bytes that live for the whole program, e.g. embedded into the binary.
+    Static(&'static [u8]),
+
+    /// Range of a buffer shared with the source.
+    Shared {
+        buffer: Arc<dyn AsRef<[u8]> + Send + Sync>,
+        range: Range<usize>,
+    },
+}
+
+impl AssetBytes {
+    /// Returns bytes in the `range` of shared `buffer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds of the `buffer`.
+    pub fn shared(buffer: impl AsRef<[u8]> + Send + Sync + 'static, range: Range<usize>) -> Self {
+        AssetBytes::shared_arc(Arc::new(buffer), range)
+    }
+
+    /// Returns bytes in the `range` of shared `buffer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds of the `buffer`.
+    pub fn shared_arc(buffer: Arc<dyn AsRef<[u8]> + Send + Sync>, range: Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= (*buffer).as_ref().len(),
+            "Range {range:?} is out of bounds of shared buffer"
+        );
+        AssetBytes::Shared { buffer, range }
+    }
+
+    /// Returns owned bytes, copying them unless they are already owned.
+    pub fn into_boxed(self) -> Box<[u8]> {
+        match self {
+            AssetBytes::Owned(bytes) => bytes,
+            bytes => (*bytes).into(),
+        }
+    }
+}
+
+impl std::ops::Deref for AssetBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            AssetBytes::Owned(bytes) => bytes,
+            AssetBytes::Static(bytes) => bytes,
+            AssetBytes::Shared { buffer, range } => &(**buffer).as_ref()[range.clone()],
+        }
+    }
+}
+
+impl AsRef<[u8]> for AssetBytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl fmt::Debug for AssetBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl PartialEq for AssetBytes {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for AssetBytes {}
+
+impl Default for AssetBytes {
+    fn default() -> Self {
+        AssetBytes::Static(&[])
+    }
+}
+
+impl From<Box<[u8]>> for AssetBytes {
+    fn from(bytes: Box<[u8]>) -> Self {
+        AssetBytes::Owned(bytes)
+    }
+}
+
+impl From<Vec<u8>> for AssetBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        AssetBytes::Owned(bytes.into())
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for AssetBytes {
+    fn from(bytes: [u8; N]) -> Self {
+        AssetBytes::Owned(bytes.into())
+    }
+}
+
+impl From<&[u8]> for AssetBytes {
+    fn from(bytes: &[u8]) -> Self {
+        AssetBytes::Owned(bytes.into())
+    }
+}
+
+impl From<Arc<[u8]>> for AssetBytes {
+    fn from(bytes: Arc<[u8]>) -> Self {
+        let len = bytes.len();
+        AssetBytes::shared(bytes, 0..len)
+    }
+}
+
+/// Residency hint for asset data, see [`Source::advise`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Advice {
+    /// Data will be needed soon and may be read ahead.
+    WillNeed,
+
+    /// Data is not needed anymore and its memory may be released.
+    DontNeed,
+}
+
+/// Key-value properties of the asset reported by the source.
+///
+/// Sources may know things about assets that are not part of asset data,
+/// like original file name or import settings.
+/// Properties are available to decoders with [`Loader::decoding_properties`]
+/// and with [`AssetMetadata`] of the loaded asset.
+///
+/// Empty properties do not allocate.
+///
+/// [`Loader::decoding_properties`]: crate::Loader::decoding_properties
+/// [`AssetMetadata`]: crate::AssetMetadata
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct AssetProperties {
+    entries: Option<Arc<[Property]>>,
+}
+
+/// Key and value of a property.
+type Property = (Arc<str>, Arc<str>);
+
+impl fmt::Debug for AssetProperties {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl AssetProperties {
+    /// Returns empty properties.
+    pub const fn new() -> Self {
+        AssetProperties { entries: None }
+    }
+
+    /// Returns `true` if there are no properties.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_none()
+    }
+
+    /// Returns number of properties.
+    pub fn len(&self) -> usize {
+        self.entries.as_ref().map_or(0, |entries| entries.len())
+    }
+
+    /// Returns value of the property.
+    pub fn get(&self, key: &str) -> Option<&Arc<str>> {
+        let entries = self.entries.as_ref()?;
+        entries.iter().find(|(k, _)| **k == *key).map(|(_, v)| v)
+    }
+
+    /// Returns iterator over properties in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.entries
+            .iter()
+            .flat_map(|entries| entries.iter())
+            .map(|(k, v)| (&**k, &**v))
+    }
+
+    /// Sets value of the property.
+    pub fn insert(&mut self, key: impl Into<Arc<str>>, value: impl Into<Arc<str>>) {
+        let key = key.into();
+        let value = value.into();
+
+        let mut entries = self.entries.as_deref().unwrap_or(&[]).to_vec();
+        match entries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => entries.push((key, value)),
+        }
+        self.entries = Some(entries.into());
+    }
+
+    /// Sets value of the property.
+    pub fn with(mut self, key: impl Into<Arc<str>>, value: impl Into<Arc<str>>) -> Self {
+        self.insert(key, value);
+        self
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for AssetProperties
+where
+    K: Into<Arc<str>>,
+    V: Into<Arc<str>>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut properties = AssetProperties::new();
+        for (key, value) in iter {
+            properties.insert(key, value);
+        }
+        properties
+    }
 }
 
 /// Abstract source for asset raw data.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// use argosy::source::prelude::*;
+/// # #[cfg(feature = "tokio")] {
+///
+/// /// Source with single asset that never changes.
+/// struct ConstSource;
+///
+/// impl Source for ConstSource {
+///     fn find<'a>(&'a self, path: &'a str, _asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+///         Box::pin(async move { (path == "number").then(|| AssetId::new(1).unwrap()) })
+///     }
+///
+///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+///         Box::pin(async move {
+///             if id.value().get() != 1 {
+///                 return Ok(None);
+///             }
+///             Ok(Some(AssetData {
+///                 bytes: (*br#"{ "value": 7 }"#).into(),
+///                 version: 0,
+///                 properties: AssetProperties::new(),
+///             }))
+///         })
+///     }
+///
+///     fn update<'a>(
+///         &'a self,
+///         _id: AssetId,
+///         _version: u64,
+///     ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+///         // Data never changes, so there is never a newer version.
+///         Box::pin(async { Ok(None) })
+///     }
+/// }
+///
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let loader = Loader::builder().with(ConstSource).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let mut number = loader.load::<Number, _>("number").await?;
+///         assert_eq!(number.build(&mut ())?.value, 7);
+///         Ok::<_, Error>(())
+///     })
+///     .unwrap();
+/// # }
+/// ```
 pub trait Source: Send + Sync + 'static {
     /// Searches for the asset by given path.
     /// Returns `Ok(Some(asset_data))` if asset is found and loaded successfully.
     /// Returns `Ok(None)` if asset is not found.
     fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>>;
 
+    /// Returns canonical form of the `path` if it differs from `path`.
+    ///
+    /// Loader consults sources in order before looking up path cache,
+    /// first returned path is used instead of requested one.
+    /// This way aliases of the same path share single lookup.
+    ///
+    /// Default implementation returns `None`, keeping paths as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::{borrow::Cow, sync::atomic::{AtomicUsize, Ordering}};
+    /// # use argosy::{*, source::{normalize_path, prelude::*}};
+    /// # #[cfg(feature = "tokio")] {
+    /// static FINDS: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// /// Source with file-like paths that counts lookups.
+    /// struct Counting;
+    ///
+    /// impl Source for Counting {
+    ///     fn find<'a>(&'a self, path: &'a str, _asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+    ///         FINDS.fetch_add(1, Ordering::Relaxed);
+    ///         Box::pin(async move { (path == "ui/number").then(|| AssetId::new(1).unwrap()) })
+    ///     }
+    ///
+    ///     fn canonical_path<'a>(&self, path: &'a str) -> Option<Cow<'a, str>> {
+    ///         match normalize_path(path) {
+    ///             Cow::Borrowed(_) => None,
+    ///             normalized => Some(normalized),
+    ///         }
+    ///     }
+    ///
+    ///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         Box::pin(async move {
+    ///             Ok((id.value().get() == 1).then(|| AssetData {
+    ///                 bytes: (*br#"{ "value": 7 }"#).into(),
+    ///                 version: 0,
+    ///                 properties: AssetProperties::new(),
+    ///             }))
+    ///         })
+    ///     }
+    ///
+    ///     fn update<'a>(&'a self, _: AssetId, _: u64) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         Box::pin(async { Ok(None) })
+    ///     }
+    /// }
+    ///
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let loader = Loader::builder().with(Counting).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let handles = ["ui/number", "ui//number", "./ui/./number", "ui\\number"]
+    ///             .map(|path| loader.load::<Number, _>(path));
+    ///
+    ///         for handle in handles {
+    ///             assert_eq!(handle.await?.build(&mut ())?.value, 7);
+    ///         }
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    ///
+    /// // Aliases share single lookup.
+    /// assert_eq!(FINDS.load(Ordering::Relaxed), 1);
+    /// assert_eq!(loader.stats().path_cache_len, 1);
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    fn canonical_path<'a>(&self, path: &'a str) -> Option<Cow<'a, str>> {
+        let _ = path;
+        None
+    }
+
+    /// Lists assets with paths that start with `prefix`.
+    /// Yields path and id of each asset.
+    ///
+    /// Default implementation yields nothing.
+    /// Sources that do not support path-based lookup should keep it.
+    fn find_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+        asset: &'a str,
+    ) -> BoxStream<'a, (String, AssetId)> {
+        let _ = (prefix, asset);
+        futures::stream::empty().boxed()
+    }
+
     /// Load asset data from this source.
     /// Returns `Ok(Some(asset_data))` if asset is loaded successfully.
     /// Returns `Ok(None)` if asset is not found, allowing checking other sources.
     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>>;
 
     /// Update asset data if newer is available.
+    /// Returns `Ok(Some(asset_data))` only if data is newer than `version`.
+    /// Returns `Ok(None)` if asset is up-to-date or not found.
     fn update<'a>(
         &'a self,
         id: AssetId,
         version: u64,
     ) -> BoxFuture<'a, Result<Option<AssetData>, Error>>;
+
+    /// Returns `true` if assets of this source may change.
+    /// Loader never calls [`Source::update`] for assets of sources that return `false`.
+    ///
+    /// Default implementation returns `true`.
+    fn supports_update(&self) -> bool {
+        true
+    }
+
+    /// Hints whether asset data will be needed soon.
+    ///
+    /// Loader calls it with [`Advice::WillNeed`] for assets prefetched by id.
+    /// Sources that keep data in memory, like memory-mapped archives,
+    /// may read it ahead or release it.
+    ///
+    /// Default implementation ignores hints.
+    fn advise(&self, id: AssetId, advice: Advice) {
+        let _ = (id, advice);
+    }
+
+    /// Returns index of small assets with their data, fetched at once.
+    ///
+    /// Loader calls it once per source when the source is queried for an asset the first time,
+    /// including sources added after the loader is built.
+    /// Sources that are not queried, e.g. because a source with higher priority has the asset,
+    /// are not asked for the index.
+    /// Assets found in the index are served from it without calling [`Source::load`],
+    /// other assets are loaded individually.
+    /// Index is kept as long as the source stays in the loader,
+    /// changed assets are picked up with [`Source::update`].
+    /// Once update reports newer version of an asset,
+    /// its older data in the index is not served anymore.
+    /// Errors are logged and treated as absence of the index.
+    ///
+    /// Default implementation returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # use argosy::{*, source::prelude::*};
+    /// # #[cfg(feature = "tokio")] {
+    /// static BULK: AtomicUsize = AtomicUsize::new(0);
+    /// static LOADS: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// /// Source with many small assets packed together and one large asset.
+    /// struct Packed;
+    ///
+    /// fn number(id: AssetId) -> AssetData {
+    ///     AssetData {
+    ///         bytes: format!(r#"{{ "value": {} }}"#, id.value()).into_bytes().into(),
+    ///         version: 0,
+    ///         properties: AssetProperties::new(),
+    ///     }
+    /// }
+    ///
+    /// impl Source for Packed {
+    ///     fn find<'a>(&'a self, _: &'a str, _: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+    ///         Box::pin(async { None })
+    ///     }
+    ///
+    ///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         LOADS.fetch_add(1, Ordering::Relaxed);
+    ///         Box::pin(async move { Ok((id.value().get() <= 1001).then(|| number(id))) })
+    ///     }
+    ///
+    ///     fn update<'a>(&'a self, _: AssetId, _: u64) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+    ///         Box::pin(async { Ok(None) })
+    ///     }
+    ///
+    ///     fn bulk_hint<'a>(&'a self) -> BoxFuture<'a, Result<Option<InlineAssets>, Error>> {
+    ///         BULK.fetch_add(1, Ordering::Relaxed);
+    ///         Box::pin(async {
+    ///             // Asset 1001 is too large to be packed.
+    ///             let mut inline = InlineAssets::new();
+    ///             for id in (1..=1000).map(|id| AssetId::new(id).unwrap()) {
+    ///                 inline.insert(id, number(id));
+    ///             }
+    ///             Ok(Some(inline))
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u64,
+    /// }
+    ///
+    /// let loader = Loader::builder().with(Packed).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         let handles: Vec<_> = (1..=1000)
+    ///             .map(|id| loader.load::<Number, _>(AssetId::new(id).unwrap()))
+    ///             .collect();
+    ///
+    ///         for (value, handle) in (1..=1000).zip(handles) {
+    ///             assert_eq!(handle.await?.build(&mut ())?.value, value);
+    ///         }
+    ///         assert_eq!(BULK.load(Ordering::Relaxed), 1);
+    ///         assert_eq!(LOADS.load(Ordering::Relaxed), 0);
+    ///
+    ///         let mut large = loader.load::<Number, _>(AssetId::new(1001).unwrap()).await?;
+    ///         assert_eq!(large.build(&mut ())?.value, 1001);
+    ///         assert_eq!(LOADS.load(Ordering::Relaxed), 1);
+    ///         Ok::<_, Error>(())
+    ///     })?;
+    ///
+    /// assert_eq!(BULK.load(Ordering::Relaxed), 1);
+    /// # }
+    /// # Ok::<_, Error>(())
+    /// ```
+    fn bulk_hint<'a>(&'a self) -> BoxFuture<'a, Result<Option<InlineAssets>, Error>> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// Normalizes path the way file systems resolve it.
+///
+/// Backslashes are replaced with slashes, repeated separators
+/// and `.` segments are removed and `..` segments remove preceding segment.
+/// Leading `..` segments are kept.
+///
+/// ```
+/// # use argosy::source::normalize_path;
+/// assert_eq!(normalize_path("ui/icon.png"), "ui/icon.png");
+/// assert_eq!(normalize_path("ui//icon.png"), "ui/icon.png");
+/// assert_eq!(normalize_path("./ui/./icon.png"), "ui/icon.png");
+/// assert_eq!(normalize_path("ui\\icons/../icon.png"), "ui/icon.png");
+/// assert_eq!(normalize_path("../icon.png"), "../icon.png");
+/// assert_eq!(normalize_path("/ui/icon.png"), "/ui/icon.png");
+/// ```
+pub fn normalize_path(path: &str) -> Cow<'_, str> {
+    let absolute = path.starts_with(['/', '\\']);
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split(['/', '\\']) {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            ".." if absolute => {}
+            segment => segments.push(segment),
+        }
+    }
+
+    let mut normalized = String::with_capacity(path.len());
+    if absolute {
+        normalized.push('/');
+    }
+    for (index, segment) in segments.iter().enumerate() {
+        if index > 0 {
+            normalized.push('/');
+        }
+        normalized.push_str(segment);
+    }
+
+    if normalized == path {
+        Cow::Borrowed(path)
+    } else {
+        Cow::Owned(normalized)
+    }
+}
+
+/// Items required to implement [`Source`].
+///
+/// ```
+/// use argosy::source::prelude::*;
+/// ```
+pub mod prelude {
+    pub use argosy_id::AssetId;
+    pub use futures::{future::BoxFuture, stream::BoxStream};
+
+    pub use super::{inline::InlineAssets, Advice, AssetBytes, AssetData, AssetProperties, Source};
+    pub use crate::error::Error;
 }
@@ -0,0 +1,176 @@
+use std::borrow::Cow;
+
+use argosy_id::AssetId;
+use futures::{future::BoxFuture, stream::BoxStream, StreamExt};
+
+use crate::error::Error;
+
+use super::{inline::InlineAssets, Advice, AssetData, Source};
+
+/// Number of low bits of asset id available to namespaced source.
+const ID_BITS: u32 = 48;
+
+/// Mask of the low bits of asset id available to namespaced source.
+const ID_MASK: u64 = (1 << ID_BITS) - 1;
+
+/// Source that moves ids of the inner source into separate namespace.
+///
+/// Namespace is stored in the high 16 bits of asset id.
+/// Inner source may use only low 48 bits of asset ids,
+/// assets with larger ids are not visible through this source.
+/// This way sources with independent id spaces, like separately authored asset packs,
+/// can be added to the same loader without collisions.
+///
+/// Ids referenced inside asset data are not remapped.
+/// Assets should refer to their dependencies by path
+/// or be authored with namespaced ids.
+///
+/// Namespace `0` leaves ids unchanged.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let id = AssetId::new(1).unwrap();
+///
+/// let first = MemorySource::new();
+/// first.insert_with_path("first", id, &br#"{ "value": 1 }"#[..]);
+///
+/// let second = MemorySource::new();
+/// second.insert_with_path("second", id, &br#"{ "value": 2 }"#[..]);
+///
+/// let first = NamespacedSource::new(1, first);
+/// let second = NamespacedSource::new(2, second);
+/// let second_id = second.namespaced_id(id).unwrap();
+///
+/// let loader = Loader::builder().with(first).with(second).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let mut number = loader.load::<Number, _>("first").await?;
+///         assert_eq!(number.build(&mut ())?.value, 1);
+///
+///         let mut number = loader.load::<Number, _>("second").await?;
+///         assert_eq!(number.build(&mut ())?.value, 2);
+///
+///         let mut number = loader.load_with_id::<Number>(second_id).await?;
+///         assert_eq!(number.build(&mut ())?.value, 2);
+///         Ok::<_, Error>(())
+///     })
+///     .unwrap();
+/// # }
+/// ```
+pub struct NamespacedSource<S> {
+    namespace: u16,
+    inner: S,
+}
+
+impl<S> NamespacedSource<S> {
+    /// Returns new [`NamespacedSource`] that moves ids of `inner` source into `namespace`.
+    pub fn new(namespace: u16, inner: S) -> Self {
+        NamespacedSource { namespace, inner }
+    }
+
+    /// Returns namespace of this source.
+    pub fn namespace(&self) -> u16 {
+        self.namespace
+    }
+
+    /// Returns inner source.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Maps id of the inner source to id visible to the loader.
+    /// Returns `None` if id does not fit into low 48 bits.
+    pub fn namespaced_id(&self, id: AssetId) -> Option<AssetId> {
+        let value = id.value().get();
+        if value & !ID_MASK != 0 {
+            return None;
+        }
+        AssetId::new(value | (u64::from(self.namespace) << ID_BITS))
+    }
+
+    /// Maps id visible to the loader back to id of the inner source.
+    /// Returns `None` if id belongs to another namespace.
+    pub fn inner_id(&self, id: AssetId) -> Option<AssetId> {
+        let value = id.value().get();
+        if value >> ID_BITS != u64::from(self.namespace) {
+            return None;
+        }
+        AssetId::new(value & ID_MASK)
+    }
+}
+
+impl<S> Source for NamespacedSource<S>
+where
+    S: Source,
+{
+    fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        Box::pin(async move {
+            let id = self.inner.find(path, asset).await?;
+            self.namespaced_id(id)
+        })
+    }
+
+    fn canonical_path<'a>(&self, path: &'a str) -> Option<Cow<'a, str>> {
+        self.inner.canonical_path(path)
+    }
+
+    fn find_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+        asset: &'a str,
+    ) -> BoxStream<'a, (String, AssetId)> {
+        self.inner
+            .find_prefix(prefix, asset)
+            .filter_map(move |(path, id)| {
+                let id = self.namespaced_id(id);
+                async move { Some((path, id?)) }
+            })
+            .boxed()
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        match self.inner_id(id) {
+            None => Box::pin(async { Ok(None) }),
+            Some(id) => self.inner.load(id),
+        }
+    }
+
+    fn update<'a>(
+        &'a self,
+        id: AssetId,
+        version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        match self.inner_id(id) {
+            None => Box::pin(async { Ok(None) }),
+            Some(id) => self.inner.update(id, version),
+        }
+    }
+
+    fn supports_update(&self) -> bool {
+        self.inner.supports_update()
+    }
+
+    fn advise(&self, id: AssetId, advice: Advice) {
+        if let Some(id) = self.inner_id(id) {
+            self.inner.advise(id, advice);
+        }
+    }
+
+    fn bulk_hint<'a>(&'a self) -> BoxFuture<'a, Result<Option<InlineAssets>, Error>> {
+        Box::pin(async move {
+            let inline = self.inner.bulk_hint().await?;
+            Ok(inline.map(|inline| inline.filter_map_ids(|id| self.namespaced_id(id))))
+        })
+    }
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use argosy_id::AssetId;
+
+use super::normalize_path;
+
+/// Entry of the index file.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum IndexEntry {
+    /// Asset of any type.
+    Any(AssetId),
+
+    /// Assets by names of their types.
+    Typed(HashMap<String, AssetId>),
+}
+
+/// Paths of assets read from index file.
+///
+/// Index file is a JSON object that maps paths either to ids of assets of any type
+/// or to objects that map asset names to ids.
+#[derive(Default)]
+pub(crate) struct PathIndex {
+    entries: HashMap<String, IndexEntry>,
+}
+
+impl PathIndex {
+    /// Parses index file.
+    pub fn from_json(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        let entries = serde_json::from_slice::<HashMap<String, IndexEntry>>(bytes)?;
+        Ok(PathIndex {
+            entries: entries
+                .into_iter()
+                .map(|(path, entry)| (normalize_path(&path).into_owned(), entry))
+                .collect(),
+        })
+    }
+
+    /// Returns id of the asset of type `asset` at `path`.
+    pub fn find(&self, path: &str, asset: &str) -> Option<AssetId> {
+        match self.entries.get(&*normalize_path(path))? {
+            IndexEntry::Any(id) => Some(*id),
+            IndexEntry::Typed(ids) => ids.get(asset).copied(),
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use argosy_id::AssetId;
+use futures::{future::BoxFuture, stream::BoxStream, StreamExt};
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+
+#[cfg(feature = "tokio")]
+use std::{sync::OnceLock, time::Duration};
+
+#[cfg(feature = "tokio")]
+use tokio::time::Instant;
+
+use crate::{
+    error::Error,
+    replay::{ReplayEntry, ReplayLog, ReplayRequest, ReplayResponse},
+};
+
+use super::{AssetData, Source};
+
+/// Request to [`ReplaySource`] that is not in the replay log.
+///
+/// Points at nondeterminism in the application,
+/// e.g. assets requested in different order or with different paths.
+#[derive(Debug, thiserror::Error)]
+#[error("Request {request:?} is not in the replay log")]
+pub struct UnexpectedRequest {
+    /// Request made by the loader.
+    pub request: ReplayRequest,
+}
+
+/// Error returned by the source when the log was recorded.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct ReplayedError {
+    /// Message of the recorded error.
+    pub message: String,
+}
+
+/// Pacing of [`ReplaySource`] answers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplayTiming {
+    /// Answers are given as soon as requested.
+    #[default]
+    AsFastAsPossible,
+
+    /// Answers are delayed to match time they were received
+    /// relative to the first request of the recording.
+    ///
+    /// Requires `tokio` feature.
+    #[cfg(feature = "tokio")]
+    Recorded,
+}
+
+struct ReplayState {
+    /// Indices of not yet replayed entries for each request, in recorded order.
+    pending: Mutex<HashMap<ReplayRequest, VecDeque<usize>>>,
+
+    /// Requests that are not in the log.
+    unexpected: Mutex<Vec<ReplayRequest>>,
+}
+
+/// Source that serves answers recorded in [`ReplayLog`].
+///
+/// Requests are matched by their inputs, so assets loaded concurrently
+/// may be requested in any order. Each recorded answer is served once,
+/// repeated requests get subsequent answers.
+/// Requests not present in the log fail with [`UnexpectedRequest`]
+/// and are listed by [`ReplayHandle::unexpected`].
+///
+/// Log is recorded for the whole set of loader sources,
+/// so replaying loader should have this source only.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// struct Number {
+///     value: u32,
+/// }
+///
+/// let one = AssetId::new(1).unwrap();
+/// let two = AssetId::new(2).unwrap();
+///
+/// let source = MemorySource::new();
+/// source.insert_with_path("one", one, &br#"{ "value": 1 }"#[..]);
+/// source.insert(two, &br#"{ "value": 2 }"#[..]);
+///
+/// // Loads the same assets and returns summary of the cache.
+/// let session = |loader: &Loader, changes: &dyn Fn()| {
+///     tokio::runtime::Builder::new_current_thread()
+///         .build()
+///         .unwrap()
+///         .block_on(async {
+///             let mut first = loader.load::<Number, _>("one").await?;
+///             assert_eq!(first.build(&mut ())?.value, 1);
+///             loader.load::<Number, _>(two).await?;
+///             assert!(loader.load::<Number, _>("three").await.is_err());
+///
+///             changes();
+///             assert_eq!(loader.update_tick().await, [one]);
+///             Ok::<_, Error>(())
+///         })?;
+///
+///     let entries: Vec<_> = loader
+///         .entries()
+///         .into_iter()
+///         .map(|e| (e.sequence, e.path, e.id, e.status))
+///         .collect();
+///     Ok::<_, Error>((entries, loader.stats()))
+/// };
+///
+/// let recorder = ReplayRecorder::new();
+/// let recording = Loader::builder()
+///     .with(source.clone())
+///     .with_recording(recorder.clone())
+///     .build();
+/// let recorded = session(&recording, &|| source.insert(one, &br#"{ "value": 10 }"#[..]))?;
+///
+/// let encoded = recorder.log().encode();
+/// let log = ReplayLog::decode(&encoded).unwrap();
+/// assert!(log
+///     .entries()
+///     .iter()
+///     .any(|entry| entry.request == ReplayRequest::Update { id: one, version: 0 }));
+///
+/// let replay = ReplaySource::from_log(log);
+/// let handle = replay.handle();
+/// let replaying = Loader::builder().with(replay).build();
+/// assert_eq!(session(&replaying, &|| {})?, recorded);
+///
+/// assert!(handle.unexpected().is_empty());
+/// assert_eq!(handle.remaining(), 0);
+/// # }
+/// # Ok::<_, Error>(())
+/// ```
+pub struct ReplaySource {
+    log: ReplayLog,
+    timing: ReplayTiming,
+    state: Arc<ReplayState>,
+
+    /// Earliest request time in the log.
+    #[cfg(feature = "tokio")]
+    origin: Duration,
+
+    /// Time of the first replayed request.
+    #[cfg(feature = "tokio")]
+    start: OnceLock<Instant>,
+}
+
+/// Shared view of [`ReplaySource`] progress.
+///
+/// Stays valid after the source is moved into the loader.
+#[derive(Clone)]
+pub struct ReplayHandle {
+    state: Arc<ReplayState>,
+}
+
+impl ReplayHandle {
+    /// Returns requests that were not found in the log.
+    pub fn unexpected(&self) -> Vec<ReplayRequest> {
+        self.state.unexpected.lock().clone()
+    }
+
+    /// Returns number of recorded entries not replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.state.pending.lock().values().map(VecDeque::len).sum()
+    }
+}
+
+impl ReplaySource {
+    /// Returns source that serves answers from the log.
+    pub fn from_log(log: ReplayLog) -> Self {
+        let mut pending: HashMap<ReplayRequest, VecDeque<usize>> = HashMap::new();
+        for (index, entry) in log.entries().iter().enumerate() {
+            pending
+                .entry(entry.request.clone())
+                .or_default()
+                .push_back(index);
+        }
+
+        ReplaySource {
+            #[cfg(feature = "tokio")]
+            origin: log
+                .entries()
+                .iter()
+                .map(|entry| entry.started)
+                .min()
+                .unwrap_or_default(),
+            #[cfg(feature = "tokio")]
+            start: OnceLock::new(),
+            log,
+            timing: ReplayTiming::default(),
+            state: Arc::new(ReplayState {
+                pending: Mutex::new(pending),
+                unexpected: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Sets pacing of the answers.
+    pub fn set_timing(&mut self, timing: ReplayTiming) -> &mut Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Sets pacing of the answers.
+    pub fn with_timing(mut self, timing: ReplayTiming) -> Self {
+        self.set_timing(timing);
+        self
+    }
+
+    /// Returns handle to check replay progress.
+    pub fn handle(&self) -> ReplayHandle {
+        ReplayHandle {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Takes next recorded answer to the request.
+    async fn answer(&self, request: ReplayRequest) -> Result<&ReplayEntry, Error> {
+        #[cfg(feature = "tokio")]
+        let start = *self.start.get_or_init(Instant::now);
+
+        let index = self
+            .state
+            .pending
+            .lock()
+            .get_mut(&request)
+            .and_then(VecDeque::pop_front);
+
+        let Some(index) = index else {
+            self.state.unexpected.lock().push(request.clone());
+            return Err(Error::new(UnexpectedRequest { request }));
+        };
+
+        let entry = &self.log.entries()[index];
+
+        #[cfg(feature = "tokio")]
+        if self.timing == ReplayTiming::Recorded {
+            tokio::time::sleep_until(start + entry.finished.saturating_sub(self.origin)).await;
+        }
+
+        Ok(entry)
+    }
+
+    async fn data(&self, request: ReplayRequest) -> Result<Option<AssetData>, Error> {
+        let entry = self.answer(request).await?;
+        match &entry.response {
+            ReplayResponse::Error(message) => Err(Error::new(ReplayedError {
+                message: message.clone(),
+            })),
+            ReplayResponse::Data(Some(data)) => {
+                let bytes = self.log.bytes(data.hash).unwrap_or_default();
+                Ok(Some(AssetData {
+                    bytes: bytes.into(),
+                    version: data.version,
+                    properties: data.properties.iter().cloned().collect(),
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+impl Source for ReplaySource {
+    fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        Box::pin(async move {
+            let request = ReplayRequest::Find {
+                path: path.to_owned(),
+                asset: asset.to_owned(),
+            };
+            match self.answer(request).await {
+                Err(err) => {
+                    tracing::error!("{err}");
+                    None
+                }
+                Ok(entry) => match entry.response {
+                    ReplayResponse::Found(id) => id,
+                    _ => None,
+                },
+            }
+        })
+    }
+
+    fn find_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+        asset: &'a str,
+    ) -> BoxStream<'a, (String, AssetId)> {
+        let listed = async move {
+            let request = ReplayRequest::FindPrefix {
+                prefix: prefix.to_owned(),
+                asset: asset.to_owned(),
+            };
+            match self.answer(request).await {
+                Err(err) => {
+                    tracing::error!("{err}");
+                    Vec::new()
+                }
+                Ok(entry) => match &entry.response {
+                    ReplayResponse::Listed(found) => found.clone(),
+                    _ => Vec::new(),
+                },
+            }
+        };
+        futures::stream::once(listed)
+            .flat_map(futures::stream::iter)
+            .boxed()
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(self.data(ReplayRequest::Load { id }))
+    }
+
+    fn update<'a>(
+        &'a self,
+        id: AssetId,
+        version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(self.data(ReplayRequest::Update { id, version }))
+    }
+}
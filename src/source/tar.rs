@@ -0,0 +1,280 @@
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::{
+    collections::HashMap,
+    io::{Read, Seek, SeekFrom},
+};
+
+use argosy_id::AssetId;
+use futures::future::BoxFuture;
+
+use crate::error::Error;
+
+use super::{
+    archive::ArchiveError,
+    indexed::{entry_asset_id, ArchiveIndex, IndexedArchive, IndexedEntry, ReadSeek},
+    AssetData, Source,
+};
+
+/// Size of tar header and data blocks.
+const BLOCK: u64 = 512;
+
+/// Location of the entry data in tar file.
+struct TarEntry {
+    entry: IndexedEntry,
+    offset: u64,
+    len: u64,
+}
+
+/// Offsets of entries of uncompressed tar file.
+struct TarIndex {
+    reader: Box<dyn ReadSeek>,
+    assets: HashMap<AssetId, TarEntry>,
+    named: HashMap<String, (u64, u64)>,
+}
+
+/// Parses octal number field of tar header.
+fn parse_octal(field: &[u8]) -> Option<u64> {
+    let digits = field
+        .iter()
+        .skip_while(|&&byte| byte == b' ')
+        .take_while(|&&byte| byte != 0 && byte != b' ');
+
+    let mut value = 0u64;
+    let mut empty = true;
+    for &digit in digits {
+        if !(b'0'..=b'7').contains(&digit) {
+            return None;
+        }
+        value = value.checked_mul(8)?.checked_add(u64::from(digit - b'0'))?;
+        empty = false;
+    }
+    (!empty).then_some(value)
+}
+
+/// Parses string field of tar header.
+fn parse_str(field: &[u8]) -> Option<&str> {
+    let len = field
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(field.len());
+    std::str::from_utf8(&field[..len]).ok()
+}
+
+impl TarIndex {
+    fn new(mut reader: Box<dyn ReadSeek>) -> Result<Self, ArchiveError> {
+        let mut assets = HashMap::new();
+        let mut named = HashMap::new();
+
+        let mut offset = reader.rewind().map(|()| 0).map_err(ArchiveError::Read)?;
+        let mut header = [0; BLOCK as usize];
+
+        loop {
+            match reader.read_exact(&mut header) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    return Err(ArchiveError::Truncated)
+                }
+                Err(err) => return Err(ArchiveError::Read(err)),
+            }
+            offset += BLOCK;
+
+            // Archive ends with zero blocks.
+            if header.iter().all(|&byte| byte == 0) {
+                break;
+            }
+
+            let checksum = parse_octal(&header[148..156]).ok_or(ArchiveError::InvalidHeader)?;
+            let sum = header
+                .iter()
+                .enumerate()
+                .map(|(index, &byte)| match index {
+                    148..156 => u64::from(b' '),
+                    _ => u64::from(byte),
+                })
+                .sum::<u64>();
+            if sum != checksum {
+                return Err(ArchiveError::InvalidHeader);
+            }
+
+            let len = parse_octal(&header[124..136]).ok_or(ArchiveError::InvalidHeader)?;
+            let mtime = parse_octal(&header[136..148]).unwrap_or(0);
+
+            let name = parse_str(&header[..100]).ok_or(ArchiveError::InvalidHeader)?;
+            let name = match &header[257..262] == b"ustar" {
+                false => name.to_owned(),
+                true => match parse_str(&header[345..500]) {
+                    Some("") => name.to_owned(),
+                    Some(prefix) => format!("{prefix}/{name}"),
+                    None => return Err(ArchiveError::InvalidHeader),
+                },
+            };
+
+            // Regular files only.
+            if matches!(header[156], b'0' | 0) {
+                match entry_asset_id(&name) {
+                    Some(id) => {
+                        let entry = IndexedEntry {
+                            name,
+                            version: mtime,
+                        };
+                        assets.insert(id, TarEntry { entry, offset, len });
+                    }
+                    None => {
+                        named.insert(name, (offset, len));
+                    }
+                }
+            }
+
+            offset = len
+                .div_ceil(BLOCK)
+                .checked_mul(BLOCK)
+                .and_then(|len| offset.checked_add(len))
+                .ok_or(ArchiveError::Truncated)?;
+            reader
+                .seek(SeekFrom::Start(offset))
+                .map_err(ArchiveError::Read)?;
+        }
+
+        Ok(TarIndex {
+            reader,
+            assets,
+            named,
+        })
+    }
+
+    fn read_at(&mut self, offset: u64, len: u64) -> std::io::Result<Vec<u8>> {
+        let len = usize::try_from(len).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "Asset is too large")
+        })?;
+
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let mut data = vec![0; len];
+        self.reader.read_exact(&mut data)?;
+        Ok(data)
+    }
+}
+
+impl ArchiveIndex for TarIndex {
+    fn read_named(&mut self, name: &str) -> std::io::Result<Option<Vec<u8>>> {
+        match self.named.get(name) {
+            None => Ok(None),
+            Some(&(offset, len)) => self.read_at(offset, len).map(Some),
+        }
+    }
+
+    fn entry(&self, id: AssetId) -> Option<&IndexedEntry> {
+        self.assets.get(&id).map(|tar| &tar.entry)
+    }
+
+    fn read(&mut self, id: AssetId) -> std::io::Result<Option<Vec<u8>>> {
+        match self.assets.get(&id) {
+            None => Ok(None),
+            Some(&TarEntry { offset, len, .. }) => self.read_at(offset, len).map(Some),
+        }
+    }
+}
+
+/// Source that serves assets from uncompressed tar archive.
+///
+/// Assets are stored in regular files named after their [`AssetId`]
+/// in zero-padded 16-digit hex form, as with [`FileSource`].
+/// Optional `index.json` file in the archive root maps paths to ids
+/// in the same format as index file of [`FileSource`].
+///
+/// Entries are indexed when source is created and read on demand.
+/// Asset version is modification time of the entry.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # #[cfg(feature = "tokio")] {
+/// #[derive(Clone, Asset)]
+/// struct Texture {
+///     size: u32,
+/// }
+///
+/// let mut tar = tar::Builder::new(Vec::new());
+/// for (path, data) in [
+///     ("textures/0000000000000001", &br#"{ "size": 16 }"#[..]),
+///     ("index.json", &br#"{ "ui/icon": "1" }"#[..]),
+/// ] {
+///     let mut header = tar::Header::new_ustar();
+///     header.set_size(data.len() as u64);
+///     header.set_mtime(7);
+///     header.set_cksum();
+///     tar.append_data(&mut header, path, data)?;
+/// }
+/// let tar = tar.into_inner()?;
+///
+/// let source = TarSource::new(std::io::Cursor::new(tar))?;
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let mut icon = loader.load::<Texture, _>("ui/icon").await?;
+///         assert_eq!(icon.metadata().version, 7);
+///         assert_eq!(icon.build(&mut ())?.size, 16);
+///
+///         let err = loader.load::<Texture, _>(AssetId::new(2).unwrap()).await.err().unwrap();
+///         assert!(err.is_not_found());
+///         Ok::<_, Error>(())
+///     })?;
+/// # }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`FileSource`]: crate::FileSource
+pub struct TarSource {
+    archive: IndexedArchive<TarIndex>,
+}
+
+impl TarSource {
+    /// Returns new [`TarSource`] that serves assets from tar read by `reader`.
+    pub fn new(reader: impl Read + Seek + Send + 'static) -> Result<Self, ArchiveError> {
+        let index = TarIndex::new(Box::new(reader))?;
+
+        // Entries are not compressed, so there is nothing to save by keeping them.
+        let archive = IndexedArchive::new(index, 0)?;
+        Ok(TarSource { archive })
+    }
+
+    /// Returns new [`TarSource`] that serves assets from tar file.
+    ///
+    /// Requires `fs` feature.
+    #[cfg(feature = "fs")]
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ArchiveError> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).map_err(|error| ArchiveError::Io {
+            error,
+            path: path.to_owned(),
+        })?;
+        TarSource::new(file)
+    }
+}
+
+impl Source for TarSource {
+    fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        Box::pin(async move { self.archive.find(path, asset) })
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move { self.archive.load(id) })
+    }
+
+    fn update<'a>(
+        &'a self,
+        _id: AssetId,
+        _version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        // Archive never changes.
+        Box::pin(async move { Ok(None) })
+    }
+
+    fn supports_update(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,216 @@
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::{
+    collections::HashMap,
+    io::{Read, Seek},
+};
+
+use argosy_id::AssetId;
+use futures::future::BoxFuture;
+use zip::ZipArchive;
+
+use crate::error::Error;
+
+use super::{
+    archive::ArchiveError,
+    indexed::{entry_asset_id, ArchiveIndex, IndexedArchive, IndexedEntry, ReadSeek},
+    AssetData, Source,
+};
+
+/// Number of recently decompressed entries kept by default.
+const DEFAULT_RECENT: usize = 16;
+
+/// Entries of zip archive.
+struct ZipIndex {
+    archive: ZipArchive<Box<dyn ReadSeek>>,
+    assets: HashMap<AssetId, (usize, IndexedEntry)>,
+}
+
+impl ZipIndex {
+    fn new(reader: Box<dyn ReadSeek>) -> Result<Self, ArchiveError> {
+        let mut archive = ZipArchive::new(reader).map_err(ArchiveError::Zip)?;
+        let mut assets = HashMap::new();
+
+        for file_number in 0..archive.len() {
+            let file = archive
+                .by_index_raw(file_number)
+                .map_err(ArchiveError::Zip)?;
+
+            if !file.is_file() {
+                continue;
+            }
+            let Some(id) = entry_asset_id(file.name()) else {
+                continue;
+            };
+
+            let entry = IndexedEntry {
+                name: file.name().to_owned(),
+                version: u64::from(file.crc32()),
+            };
+            assets.insert(id, (file_number, entry));
+        }
+
+        Ok(ZipIndex { archive, assets })
+    }
+
+    fn read_file(&mut self, file_number: usize) -> std::io::Result<Vec<u8>> {
+        let mut file = self.archive.by_index(file_number)?;
+        let mut data = Vec::with_capacity(usize::try_from(file.size()).unwrap_or(0));
+        file.read_to_end(&mut data)?;
+        Ok(data)
+    }
+}
+
+impl ArchiveIndex for ZipIndex {
+    fn read_named(&mut self, name: &str) -> std::io::Result<Option<Vec<u8>>> {
+        match self.archive.index_for_name(name) {
+            None => Ok(None),
+            Some(file_number) => self.read_file(file_number).map(Some),
+        }
+    }
+
+    fn entry(&self, id: AssetId) -> Option<&IndexedEntry> {
+        self.assets.get(&id).map(|(_, entry)| entry)
+    }
+
+    fn read(&mut self, id: AssetId) -> std::io::Result<Option<Vec<u8>>> {
+        match self.assets.get(&id) {
+            None => Ok(None),
+            Some(&(file_number, _)) => self.read_file(file_number).map(Some),
+        }
+    }
+}
+
+/// Source that serves assets from zip archive.
+///
+/// Assets are stored in files named after their [`AssetId`]
+/// in zero-padded 16-digit hex form, as with [`FileSource`].
+/// Optional `index.json` file in the archive root maps paths to ids
+/// in the same format as index file of [`FileSource`].
+///
+/// Central directory is read when source is created,
+/// entries are decompressed on demand
+/// and several recently decompressed entries are kept,
+/// see [`ZipSource::set_recent_capacity`].
+/// Asset version is CRC-32 of the entry.
+///
+/// Requires `zip` feature.
+///
+/// # Example
+///
+/// ```
+/// # use std::io::Write;
+/// # use argosy::*;
+/// #[derive(Clone, Asset)]
+/// struct Texture {
+///     size: u32,
+/// }
+///
+/// let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+/// let options = zip::write::SimpleFileOptions::default();
+/// zip.start_file("textures/0000000000000001", options)?;
+/// zip.write_all(br#"{ "size": 16 }"#)?;
+/// zip.start_file("0000000000000002", options)?;
+/// zip.write_all(br#"{ "size": 32 }"#)?;
+/// zip.start_file("index.json", options)?;
+/// zip.write_all(br#"{ "ui/icon": { "Texture": "1" } }"#)?;
+/// let zip = zip.finish()?;
+///
+/// let source = ZipSource::new(zip)?;
+/// let loader = Loader::builder().with(source).build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .build()
+///     .unwrap()
+///     .block_on(async {
+///         let mut icon = loader.load::<Texture, _>("ui/icon").await?;
+///         let metadata = icon.metadata();
+///         assert_eq!(metadata.version, u64::from(crc32(br#"{ "size": 16 }"#)));
+///         assert_eq!(metadata.properties.get("entry").map(|entry| &**entry), Some("textures/0000000000000001"));
+///         assert_eq!(icon.build(&mut ())?.size, 16);
+///
+///         let mut loaded = loader.load::<Texture, _>(AssetId::new(2).unwrap()).await?;
+///         assert_eq!(loaded.build(&mut ())?.size, 32);
+///
+///         let err = loader.load::<Texture, _>(AssetId::new(3).unwrap()).await.err().unwrap();
+///         assert!(err.is_not_found());
+///         Ok::<_, Error>(())
+///     })?;
+///
+/// /// CRC-32 as computed by zip.
+/// fn crc32(data: &[u8]) -> u32 {
+///     let mut crc = !0u32;
+///     for &byte in data {
+///         crc ^= u32::from(byte);
+///         for _ in 0..8 {
+///             crc = (crc >> 1) ^ (0xEDB88320 & (crc & 1).wrapping_neg());
+///         }
+///     }
+///     !crc
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`FileSource`]: crate::FileSource
+pub struct ZipSource {
+    archive: IndexedArchive<ZipIndex>,
+}
+
+impl ZipSource {
+    /// Returns new [`ZipSource`] that serves assets from zip read by `reader`.
+    pub fn new(reader: impl Read + Seek + Send + 'static) -> Result<Self, ArchiveError> {
+        let index = ZipIndex::new(Box::new(reader))?;
+        let archive = IndexedArchive::new(index, DEFAULT_RECENT)?;
+        Ok(ZipSource { archive })
+    }
+
+    /// Returns new [`ZipSource`] that serves assets from zip file.
+    ///
+    /// Requires `fs` feature.
+    #[cfg(feature = "fs")]
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ArchiveError> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).map_err(|error| ArchiveError::Io {
+            error,
+            path: path.to_owned(),
+        })?;
+        ZipSource::new(file)
+    }
+
+    /// Sets number of recently decompressed entries to keep.
+    /// Zero disables keeping entries.
+    pub fn set_recent_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.archive.set_capacity(capacity);
+        self
+    }
+
+    /// Sets number of recently decompressed entries to keep.
+    /// Zero disables keeping entries.
+    pub fn with_recent_capacity(mut self, capacity: usize) -> Self {
+        self.set_recent_capacity(capacity);
+        self
+    }
+}
+
+impl Source for ZipSource {
+    fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        Box::pin(async move { self.archive.find(path, asset) })
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move { self.archive.load(id) })
+    }
+
+    fn update<'a>(
+        &'a self,
+        _id: AssetId,
+        _version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        // Archive never changes.
+        Box::pin(async move { Ok(None) })
+    }
+
+    fn supports_update(&self) -> bool {
+        false
+    }
+}
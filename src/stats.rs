@@ -0,0 +1,295 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use hashbrown::HashMap;
+use parking_lot::RwLock;
+
+/// Number of buckets in [`DecodeHistogram`].
+pub const DECODE_BUCKETS: usize = 40;
+
+/// Histogram of decode durations with fixed log-scale buckets.
+///
+/// Bucket `0` counts zero durations,
+/// bucket `i` counts durations in `[2^(i-1), 2^i)` nanoseconds.
+/// Last bucket also counts all longer durations.
+/// Recording a sample is two atomic additions and never allocates.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use std::time::Duration;
+/// let histogram = DecodeHistogram::new();
+/// histogram.record(Duration::from_nanos(3), 10);
+/// histogram.record(Duration::from_nanos(3), 10);
+/// histogram.record(Duration::from_micros(1), 20);
+///
+/// let counts = histogram.bucket_counts();
+/// assert_eq!(counts[DecodeHistogram::bucket_of(Duration::from_nanos(3))], 2);
+/// assert_eq!(counts[2], 2);
+/// assert_eq!(counts[10], 1);
+/// assert_eq!(histogram.count(), 3);
+/// assert_eq!(histogram.bytes_total(), 40);
+///
+/// // Quantiles are upper bounds of the buckets.
+/// assert_eq!(histogram.quantile(0.5), Duration::from_nanos(4));
+/// assert_eq!(histogram.quantile(0.95), Duration::from_nanos(1024));
+/// ```
+pub struct DecodeHistogram {
+    buckets: [AtomicU64; DECODE_BUCKETS],
+    bytes: AtomicU64,
+}
+
+impl Default for DecodeHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DecodeHistogram {
+    /// Returns empty histogram.
+    pub const fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const ZERO: AtomicU64 = AtomicU64::new(0);
+
+        DecodeHistogram {
+            buckets: [ZERO; DECODE_BUCKETS],
+            bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns index of the bucket that counts `duration`.
+    pub fn bucket_of(duration: Duration) -> usize {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        let bucket = (u64::BITS - nanos.leading_zeros()) as usize;
+        bucket.min(DECODE_BUCKETS - 1)
+    }
+
+    /// Returns exclusive upper bound of durations counted by the bucket.
+    /// Returns [`Duration::MAX`] for the last bucket.
+    pub fn bucket_bound(bucket: usize) -> Duration {
+        if bucket + 1 >= DECODE_BUCKETS {
+            Duration::MAX
+        } else {
+            Duration::from_nanos(1 << bucket)
+        }
+    }
+
+    /// Records decoding that took `duration` for `bytes` of asset data.
+    pub fn record(&self, duration: Duration, bytes: usize) {
+        self.buckets[Self::bucket_of(duration)].fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Returns number of samples in each bucket.
+    pub fn bucket_counts(&self) -> [u64; DECODE_BUCKETS] {
+        std::array::from_fn(|bucket| self.buckets[bucket].load(Ordering::Relaxed))
+    }
+
+    /// Returns number of recorded samples.
+    pub fn count(&self) -> u64 {
+        self.bucket_counts().iter().sum()
+    }
+
+    /// Returns total size of decoded asset data.
+    pub fn bytes_total(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Returns estimate of the `q` quantile of recorded durations,
+    /// i.e. upper bound of the bucket where it falls.
+    /// Returns zero duration if there are no samples.
+    pub fn quantile(&self, q: f64) -> Duration {
+        quantile(&self.bucket_counts(), q)
+    }
+
+    /// Removes all samples.
+    pub fn reset(&self) {
+        for bucket in &self.buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.bytes.store(0, Ordering::Relaxed);
+    }
+}
+
+fn quantile(counts: &[u64; DECODE_BUCKETS], q: f64) -> Duration {
+    let total: u64 = counts.iter().sum();
+    if total == 0 {
+        return Duration::ZERO;
+    }
+
+    let rank = ((total as f64 * q).ceil() as u64).clamp(1, total);
+    let mut seen = 0;
+    for (bucket, count) in counts.iter().enumerate() {
+        seen += count;
+        if seen >= rank {
+            return DecodeHistogram::bucket_bound(bucket);
+        }
+    }
+    Duration::MAX
+}
+
+/// Decoding statistics of one asset type.
+///
+/// See [`Loader::type_stats`].
+///
+/// [`Loader::type_stats`]: crate::Loader::type_stats
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
+pub struct TypeStats {
+    /// Name of the asset type.
+    pub name: String,
+
+    /// Number of decoded assets.
+    pub count: u64,
+
+    /// Median decode duration.
+    pub decode_p50: Duration,
+
+    /// 95th percentile of decode duration.
+    pub decode_p95: Duration,
+
+    /// Total size of decoded asset data.
+    pub bytes_total: u64,
+}
+
+impl TypeStats {
+    /// Writes statistics as flat JSON array of benchmark entries.
+    ///
+    /// Each entry has `name`, `unit` and `value` fields,
+    /// with names like `"Texture/decode_p95"`.
+    /// Durations are written in nanoseconds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// # #[cfg(feature = "tokio")] {
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert(AssetId::new(1).unwrap(), &br#"{ "value": 1 }"#[..]);
+    ///
+    /// let loader = Loader::builder().with(source).with_decode_stats(true).build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async {
+    ///         loader.load::<Number, _>(AssetId::new(1).unwrap()).await.unwrap();
+    ///     });
+    ///
+    /// let mut json = Vec::new();
+    /// TypeStats::write_benchmark_json(&loader.type_stats(), &mut json).unwrap();
+    ///
+    /// let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    /// assert_eq!(json[0]["name"], "Number/count");
+    /// assert_eq!(json[0]["value"], 1);
+    /// assert_eq!(json[3]["name"], "Number/bytes_total");
+    /// assert_eq!(json[3]["unit"], "bytes");
+    /// # }
+    /// ```
+    pub fn write_benchmark_json(
+        stats: &[TypeStats],
+        writer: impl std::io::Write,
+    ) -> serde_json::Result<()> {
+        #[derive(serde::Serialize)]
+        struct Entry {
+            name: String,
+            unit: &'static str,
+            value: u64,
+        }
+
+        let nanos = |duration: Duration| u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+
+        let entries: Vec<Entry> = stats
+            .iter()
+            .flat_map(|stats| {
+                [
+                    ("count", "count", stats.count),
+                    ("decode_p50", "ns", nanos(stats.decode_p50)),
+                    ("decode_p95", "ns", nanos(stats.decode_p95)),
+                    ("bytes_total", "bytes", stats.bytes_total),
+                ]
+                .map(|(field, unit, value)| Entry {
+                    name: format!("{}/{}", stats.name, field),
+                    unit,
+                    value,
+                })
+            })
+            .collect();
+
+        serde_json::to_writer_pretty(writer, &entries)
+    }
+}
+
+/// Decode histograms of all asset types.
+pub(crate) struct DecodeStats {
+    types: RwLock<HashMap<String, Arc<DecodeHistogram>>>,
+}
+
+impl DecodeStats {
+    pub fn new() -> Self {
+        DecodeStats {
+            types: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Returns histogram of the asset type.
+    pub fn histogram(&self, name: &str) -> Arc<DecodeHistogram> {
+        if let Some(histogram) = self.types.read().get(name) {
+            return histogram.clone();
+        }
+
+        self.types
+            .write()
+            .entry(name.to_owned())
+            .or_default()
+            .clone()
+    }
+
+    /// Records decoding of asset type.
+    pub fn record(&self, name: &str, duration: Duration, bytes: usize) {
+        if let Some(histogram) = self.types.read().get(name) {
+            histogram.record(duration, bytes);
+            return;
+        }
+
+        self.histogram(name).record(duration, bytes);
+    }
+
+    pub fn type_stats(&self) -> Vec<TypeStats> {
+        let mut stats: Vec<_> = self
+            .types
+            .read()
+            .iter()
+            .map(|(name, histogram)| {
+                let counts = histogram.bucket_counts();
+                TypeStats {
+                    name: name.clone(),
+                    count: counts.iter().sum(),
+                    decode_p50: quantile(&counts, 0.5),
+                    decode_p95: quantile(&counts, 0.95),
+                    bytes_total: histogram.bytes_total(),
+                }
+            })
+            .collect();
+
+        stats.sort_by(|a, b| a.name.cmp(&b.name));
+        stats
+    }
+
+    pub fn reset(&self) {
+        for histogram in self.types.read().values() {
+            histogram.reset();
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    ops::Deref,
+    sync::{atomic::AtomicUsize, Arc},
+    time::{Duration, Instant},
+};
+
+use argosy_id::AssetId;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+use tokio::sync::OnceCell;
+
+use crate::{
+    asset::Asset,
+    cache::{BoundedPathCache, CacheBackend, LoaderCacheFactory},
+    conflict::TypeConflictPolicy,
+    key::PathKey,
+    loader::{AssetShard, Data, Loader, PathShard, PathState},
+};
+
+/// Default time data fetched by one tenant is reused by others.
+pub(crate) const DEFAULT_TENANT_FETCH_TTL: Duration = Duration::from_secs(1);
+
+/// Marker for asset types that are loaded and built once for all tenants.
+///
+/// Load them with [`Loader::load_shared`].
+pub trait TenantShared: Asset {}
+
+/// Loader of one tenant.
+///
+/// Tenant has its own caches, so assets loaded, built, reloaded
+/// and unloaded by one tenant are never seen by others.
+/// Sources are shared by all tenants and data fetched by one of them
+/// is reused by others for a short time.
+/// See [`LoaderBuilder::set_tenant_fetch_ttl`].
+///
+/// Tenant dereferences to [`Loader`] and has the same API.
+/// Statistics, failures and entries are reported per tenant.
+/// Clones share the tenant.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::*;
+/// # use futures::future::BoxFuture;
+/// # use std::{sync::atomic::{AtomicUsize, Ordering}, time::Duration};
+/// # #[cfg(feature = "tokio")] {
+/// /// Level is built with modifier of the session.
+/// #[derive(Clone)]
+/// struct Level {
+///     value: u32,
+/// }
+///
+/// impl Asset for Level {
+///     type Decoded = Box<[u8]>;
+///     type DecodeError = std::convert::Infallible;
+///     type BuildError = std::convert::Infallible;
+///     type Fut = std::future::Ready<Result<Box<[u8]>, std::convert::Infallible>>;
+///
+///     fn asset_name() -> AssetName {
+///         AssetName::new("Level")
+///     }
+///
+///     fn decode(bytes: Box<[u8]>, _: &Loader) -> Self::Fut {
+///         std::future::ready(Ok(bytes))
+///     }
+/// }
+///
+/// impl AssetBuild<u32> for Level {
+///     fn build(modifier: &mut u32, bytes: Box<[u8]>) -> Result<Level, std::convert::Infallible> {
+///         *modifier += 1;
+///         Ok(Level { value: bytes.len() as u32 + *modifier })
+///     }
+/// }
+///
+/// #[derive(Clone, Asset)]
+/// struct Palette {
+///     colors: u32,
+/// }
+///
+/// impl TenantShared for Palette {}
+///
+/// static LOADS: AtomicUsize = AtomicUsize::new(0);
+///
+/// /// Source that counts loads.
+/// struct Counting(MemorySource);
+///
+/// impl Source for Counting {
+///     fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+///         self.0.find(path, asset)
+///     }
+///
+///     fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+///         LOADS.fetch_add(1, Ordering::Relaxed);
+///         self.0.load(id)
+///     }
+///
+///     fn update<'a>(&'a self, id: AssetId, version: u64) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+///         self.0.update(id, version)
+///     }
+/// }
+///
+/// let level = AssetId::new(1).unwrap();
+/// let palette = AssetId::new(2).unwrap();
+///
+/// let source = MemorySource::new();
+/// source.insert(level, &b"0123456789"[..]);
+/// source.insert(palette, &br#"{ "colors": 16 }"#[..]);
+///
+/// let loader = Loader::builder()
+///     .with(Counting(source))
+///     .with_auto_unload(Duration::from_millis(10))
+///     .with_tenant_fetch_ttl(Duration::from_millis(20))
+///     .build();
+///
+/// tokio::runtime::Builder::new_current_thread()
+///     .enable_time()
+///     .build()
+///     .unwrap()
+///     .block_on(async move {
+///         let red = loader.tenant("red");
+///         let blue = loader.tenant("blue");
+///         assert_eq!(red.label(), "red");
+///
+///         // Each tenant builds its own value.
+///         let mut red_level = red.load::<Level, _>(level).await?;
+///         let mut blue_level = blue.load::<Level, _>(level).await?;
+///         let (mut red_modifier, mut blue_modifier) = (100, 200);
+///         assert_eq!(red_level.build(&mut red_modifier)?.value, 111);
+///         assert_eq!(blue_level.build(&mut blue_modifier)?.value, 211);
+///         assert_eq!(red.load::<Level, _>(level).await?.build(&mut red_modifier)?.value, 111);
+///
+///         // But data is fetched once.
+///         assert_eq!(LOADS.load(Ordering::Relaxed), 1);
+///         assert_eq!(red.stats().shared_fetches + blue.stats().shared_fetches, 1);
+///
+///         // Shared assets are loaded and built once for all tenants.
+///         let mut red_palette = red.load_shared::<Palette, _>(palette).await?;
+///         let mut blue_palette = blue.load_shared::<Palette, _>(palette).await?;
+///         assert_eq!(red_palette.build(&mut ())?.colors, 16);
+///         assert_eq!(blue_palette.build(&mut ())?.colors, 16);
+///         assert!(red.entries().iter().all(|entry| entry.id != Some(palette)));
+///         assert_eq!(loader.entries().len(), 1);
+///         assert_eq!(LOADS.load(Ordering::Relaxed), 2);
+///
+///         // Assets are unloaded per tenant.
+///         drop(red_level);
+///         tokio::time::sleep(Duration::from_millis(50)).await;
+///         assert!(red.entries().is_empty());
+///         assert_eq!(blue.entries().len(), 1);
+///
+///         // Fetched data expires, so next load fetches it again.
+///         red.load::<Level, _>(level).await?;
+///         assert_eq!(LOADS.load(Ordering::Relaxed), 3);
+///         drop(blue_level);
+///         Ok::<_, Error>(())
+///     })?;
+/// # }
+/// # Ok::<_, Error>(())
+/// ```
+///
+/// [`LoaderBuilder::set_tenant_fetch_ttl`]: crate::LoaderBuilder::set_tenant_fetch_ttl
+#[derive(Clone)]
+pub struct TenantLoader {
+    loader: Loader,
+}
+
+impl TenantLoader {
+    pub(crate) fn new(loader: Loader) -> Self {
+        TenantLoader { loader }
+    }
+
+    /// Returns label of the tenant.
+    pub fn label(&self) -> &str {
+        &self.loader.tenant_state().unwrap().label
+    }
+
+    /// Returns loader of the tenant.
+    pub fn loader(&self) -> &Loader {
+        &self.loader
+    }
+}
+
+impl Deref for TenantLoader {
+    type Target = Loader;
+
+    #[inline]
+    fn deref(&self) -> &Loader {
+        &self.loader
+    }
+}
+
+/// State of the tenant kept in its loader.
+pub(crate) struct Tenant {
+    pub label: Arc<str>,
+
+    /// Loader that created the tenant.
+    /// Assets shared by tenants are loaded with it.
+    pub root: Loader,
+
+    /// Number of loads that reused data fetched by other tenants.
+    pub shared_fetches: AtomicUsize,
+}
+
+/// Configuration of caches made for each tenant
+/// and data fetched by tenants.
+pub(crate) struct Tenancy {
+    pub cache_backend: Box<dyn LoaderCacheFactory>,
+    pub num_shards: usize,
+    pub path_cache_capacity: Option<usize>,
+    pub auto_unload: Option<Duration>,
+    pub decode_stats: bool,
+    pub max_prefetches: usize,
+    pub max_prefetch_bytes: usize,
+    pub max_failures: usize,
+    pub type_conflicts: TypeConflictPolicy,
+    pub fetches: FetchCache,
+}
+
+impl Tenancy {
+    /// Returns new empty asset cache.
+    pub fn asset_shards(&self) -> Arc<[AssetShard]> {
+        (0..self.num_shards)
+            .map(|_| Arc::new(Mutex::new(self.cache_backend.asset_backend())))
+            .collect()
+    }
+
+    /// Returns new empty path cache.
+    pub fn path_shards(&self) -> Arc<[PathShard]> {
+        (0..self.num_shards)
+            .map(|_| {
+                let backend = self.cache_backend.path_backend();
+                let backend: Box<dyn CacheBackend<PathKey, PathState>> =
+                    match self.path_cache_capacity {
+                        None => backend,
+                        Some(capacity) => Box::new(BoundedPathCache::new(
+                            backend,
+                            capacity.div_ceil(self.num_shards),
+                        )),
+                    };
+                Arc::new(Mutex::new(backend))
+            })
+            .collect()
+    }
+}
+
+/// Data fetch started at the instant.
+type Fetch = (Instant, Arc<OnceCell<Arc<Data>>>);
+
+/// Data fetched by tenants, kept for a short time.
+pub(crate) struct FetchCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<AssetId, Fetch>>,
+}
+
+impl FetchCache {
+    pub fn new(ttl: Duration) -> Self {
+        FetchCache {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns cell with data of the asset.
+    /// Expired cells are replaced with empty ones.
+    pub fn cell(&self, id: AssetId, now: Instant) -> Arc<OnceCell<Arc<Data>>> {
+        let mut entries = self.entries.lock();
+        entries.retain(|_, (at, _)| *at + self.ttl > now);
+        entries
+            .entry(id)
+            .or_insert_with(|| (now, Arc::new(OnceCell::new())))
+            .1
+            .clone()
+    }
+}
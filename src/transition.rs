@@ -0,0 +1,164 @@
+use crate::loader::EntryStatus;
+
+/// Kind of the loader cache entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EntryKind {
+    /// Lookup of the asset id by path.
+    Path,
+
+    /// Asset loaded by id.
+    Asset,
+}
+
+/// Event that changes state of the loader cache entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// Entry is requested for the first time.
+    Request,
+
+    /// Request is denied before it starts, see [`TypeConflictPolicy::Deny`].
+    ///
+    /// [`TypeConflictPolicy::Deny`]: crate::TypeConflictPolicy::Deny
+    Deny,
+
+    /// Path is resolved to an id.
+    Found,
+
+    /// Asset data is decoded.
+    Decoded,
+
+    /// No source has the asset.
+    NotFound,
+
+    /// Loading, decoding or building failed.
+    Failed,
+
+    /// Missing asset is looked up again.
+    Retry,
+
+    /// Cancelled load is started anew before it is reverted.
+    Restart,
+
+    /// Load is cancelled and entry is reverted.
+    Cancel,
+
+    /// Decoded asset is built.
+    Built,
+
+    /// Newer data of the asset is decoded, see [`Loader::poll_updates`].
+    ///
+    /// [`Loader::poll_updates`]: crate::Loader::poll_updates
+    Reload,
+
+    /// Asset value is published.
+    Publish,
+
+    /// Entry is unloaded, forgotten or evicted.
+    Remove,
+}
+
+/// Allowed transition of the loader cache entry.
+/// `None` state is an absent entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transition {
+    /// Kind of the entry.
+    pub entry: EntryKind,
+
+    /// State before the event.
+    pub from: Option<EntryStatus>,
+
+    /// Event that changes the state.
+    pub event: Event,
+
+    /// State after the event.
+    pub to: Option<EntryStatus>,
+}
+
+const fn path(from: Option<EntryStatus>, event: Event, to: Option<EntryStatus>) -> Transition {
+    Transition {
+        entry: EntryKind::Path,
+        from,
+        event,
+        to,
+    }
+}
+
+const fn asset(from: Option<EntryStatus>, event: Event, to: Option<EntryStatus>) -> Transition {
+    Transition {
+        entry: EntryKind::Asset,
+        from,
+        event,
+        to,
+    }
+}
+
+const PENDING: Option<EntryStatus> = Some(EntryStatus::Pending);
+const LOADED: Option<EntryStatus> = Some(EntryStatus::Loaded);
+const READY: Option<EntryStatus> = Some(EntryStatus::Ready);
+const MISSING: Option<EntryStatus> = Some(EntryStatus::Missing);
+const ERROR: Option<EntryStatus> = Some(EntryStatus::Error);
+
+/// All transitions the loader makes.
+///
+/// States carry wakers and values, so the loader changes them in place,
+/// but every change must be listed here.
+/// Debug builds check changes against this table.
+pub const TRANSITIONS: &[Transition] = &[
+    path(None, Event::Request, PENDING),
+    path(PENDING, Event::Found, LOADED),
+    path(PENDING, Event::NotFound, MISSING),
+    path(MISSING, Event::Retry, PENDING),
+    path(LOADED, Event::Remove, None),
+    path(MISSING, Event::Remove, None),
+    asset(None, Event::Request, PENDING),
+    asset(None, Event::Deny, ERROR),
+    asset(None, Event::Publish, READY),
+    asset(PENDING, Event::Decoded, LOADED),
+    asset(PENDING, Event::NotFound, MISSING),
+    asset(PENDING, Event::Failed, ERROR),
+    asset(PENDING, Event::Restart, PENDING),
+    asset(PENDING, Event::Cancel, None),
+    asset(PENDING, Event::Publish, READY),
+    asset(LOADED, Event::Built, READY),
+    asset(LOADED, Event::Failed, ERROR),
+    asset(LOADED, Event::Publish, READY),
+    asset(LOADED, Event::Reload, LOADED),
+    asset(LOADED, Event::Remove, None),
+    asset(READY, Event::Publish, READY),
+    asset(READY, Event::Reload, LOADED),
+    asset(READY, Event::Remove, None),
+    asset(MISSING, Event::Retry, PENDING),
+    asset(MISSING, Event::Publish, READY),
+    asset(MISSING, Event::Remove, None),
+    asset(ERROR, Event::Publish, READY),
+    asset(ERROR, Event::Remove, None),
+];
+
+/// Returns state the entry gets after the event.
+/// Returns `None` if the event is not allowed in the state.
+pub fn transition(
+    entry: EntryKind,
+    from: Option<EntryStatus>,
+    event: Event,
+) -> Option<Option<EntryStatus>> {
+    TRANSITIONS
+        .iter()
+        .find(|t| t.entry == entry && t.from == from && t.event == event)
+        .map(|t| t.to)
+}
+
+/// Checks in debug builds that the change of the entry state is allowed.
+#[inline]
+#[track_caller]
+pub(crate) fn debug_check(
+    entry: EntryKind,
+    from: Option<EntryStatus>,
+    event: Event,
+    to: Option<EntryStatus>,
+) {
+    debug_assert_eq!(
+        transition(entry, from, event),
+        Some(to),
+        "{entry:?} entry can't change from {from:?} to {to:?} on {event:?}",
+    );
+}
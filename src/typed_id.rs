@@ -6,7 +6,7 @@ use std::{
 
 use argosy_id::AssetId;
 
-use crate::Asset;
+use crate::{Asset, Key};
 
 /// `AssetId` augmented with type information, specifying which asset type is referenced.
 #[derive(
@@ -46,9 +46,9 @@ where
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if f.alternate() {
-            write!(f, "{}({:#?})", A::name(), self.id)
+            write!(f, "{}({:#?})", A::asset_name(), self.id)
         } else {
-            write!(f, "{}({:?})", A::name(), self.id)
+            write!(f, "{}({:?})", A::asset_name(), self.id)
         }
     }
 }
@@ -60,9 +60,9 @@ where
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if f.alternate() {
-            write!(f, "{}({:#})", A::name(), self.id)
+            write!(f, "{}({:#})", A::asset_name(), self.id)
         } else {
-            write!(f, "{}({:})", A::name(), self.id)
+            write!(f, "{}({:})", A::asset_name(), self.id)
         }
     }
 }
@@ -74,9 +74,9 @@ where
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if f.alternate() {
-            write!(f, "{}({:#x})", A::name(), self.id)
+            write!(f, "{}({:#x})", A::asset_name(), self.id)
         } else {
-            write!(f, "{}({:x})", A::name(), self.id)
+            write!(f, "{}({:x})", A::asset_name(), self.id)
         }
     }
 }
@@ -88,9 +88,16 @@ where
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if f.alternate() {
-            write!(f, "{}({:#X})", A::name(), self.id)
+            write!(f, "{}({:#X})", A::asset_name(), self.id)
         } else {
-            write!(f, "{}({:X})", A::name(), self.id)
+            write!(f, "{}({:X})", A::asset_name(), self.id)
         }
     }
 }
+
+impl<A> From<TypedAssetId<A>> for Key<'_> {
+    #[inline(always)]
+    fn from(id: TypedAssetId<A>) -> Self {
+        Key::Id(id.id)
+    }
+}
@@ -0,0 +1,218 @@
+#[cfg(feature = "tokio")]
+use std::sync::atomic::AtomicBool;
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+use ahash::RandomState;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+
+use crate::{
+    cache::Entry,
+    clock::Clock,
+    key::TypeKey,
+    loader::{AssetShard, AssetState},
+    transition::{debug_check, EntryKind, Event},
+};
+
+/// Minimal period between sweeps of released assets.
+#[cfg(feature = "tokio")]
+const MIN_SWEEP_PERIOD: Duration = Duration::from_millis(10);
+
+/// Automatic unloading of assets that have no handles left.
+///
+/// Handles keep shared counter of the asset entry.
+/// When last handle is dropped, asset is queued for unloading
+/// and background task removes it from the cache after grace period,
+/// unless new handle is requested in between.
+pub(crate) struct AutoUnload {
+    /// Time asset stays in cache after last handle is dropped.
+    grace: Duration,
+
+    /// Clock that measures grace period.
+    clock: Arc<dyn Clock>,
+
+    /// Counters of handles of cached assets.
+    refs: Mutex<HashMap<TypeKey, (u64, Weak<Refs>), RandomState>>,
+
+    /// Assets which last handle was dropped, in order of release.
+    released: Mutex<VecDeque<Released>>,
+
+    /// Generation of the next counter.
+    /// Distinguishes releases of the same asset.
+    generation: AtomicU64,
+
+    /// Set when sweeping task is spawned.
+    #[cfg(feature = "tokio")]
+    sweeping: AtomicBool,
+}
+
+struct Released {
+    key: TypeKey,
+    key_hash: u64,
+    shard: AssetShard,
+    generation: u64,
+    at: Instant,
+}
+
+/// Shared counter of asset handles.
+/// Asset is released when last clone is dropped.
+struct Refs {
+    key: TypeKey,
+    key_hash: u64,
+    shard: AssetShard,
+    generation: u64,
+    unload: Weak<AutoUnload>,
+}
+
+impl Drop for Refs {
+    fn drop(&mut self) {
+        if let Some(unload) = self.unload.upgrade() {
+            unload.released.lock().push_back(Released {
+                key: self.key.clone(),
+                key_hash: self.key_hash,
+                shard: self.shard.clone(),
+                generation: self.generation,
+                at: unload.clock.now(),
+            });
+        }
+    }
+}
+
+/// Handle's share in the asset entry.
+/// Keeps asset from being unloaded automatically.
+#[derive(Clone)]
+pub(crate) struct Retain {
+    _refs: Arc<Refs>,
+}
+
+impl AutoUnload {
+    pub(crate) fn new(grace: Duration, random_state: RandomState, clock: Arc<dyn Clock>) -> Self {
+        AutoUnload {
+            grace,
+            clock,
+            refs: Mutex::new(HashMap::with_hasher(random_state)),
+            released: Mutex::new(VecDeque::new()),
+            generation: AtomicU64::new(0),
+            #[cfg(feature = "tokio")]
+            sweeping: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns share in the asset entry for new handle.
+    ///
+    /// Must be called before handle looks up the entry,
+    /// so that sweep won't remove it in between.
+    pub(crate) fn retain(
+        self: &Arc<Self>,
+        key: TypeKey,
+        key_hash: u64,
+        shard: &AssetShard,
+    ) -> Retain {
+        let mut refs = self.refs.lock();
+
+        if let Some((_, weak)) = refs.get(&key) {
+            if let Some(refs) = weak.upgrade() {
+                return Retain { _refs: refs };
+            }
+        }
+
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed);
+        let new_refs = Arc::new(Refs {
+            key: key.clone(),
+            key_hash,
+            shard: shard.clone(),
+            generation,
+            unload: Arc::downgrade(self),
+        });
+        refs.insert(key, (generation, Arc::downgrade(&new_refs)));
+
+        Retain { _refs: new_refs }
+    }
+
+    /// Spawns task that sweeps released assets periodically.
+    /// Does nothing if task is already spawned or called outside of tokio runtime.
+    ///
+    /// Task stops when loader is dropped.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn start_sweeping(self: &Arc<Self>) {
+        if self.sweeping.load(Ordering::Acquire) {
+            return;
+        }
+
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        if self.sweeping.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let period = self.grace.max(MIN_SWEEP_PERIOD);
+        let unload = Arc::downgrade(self);
+
+        let clock = self.clock.clone();
+
+        runtime.spawn(async move {
+            loop {
+                clock.sleep(period).await;
+                match unload.upgrade() {
+                    None => return,
+                    Some(unload) => unload.sweep(),
+                }
+            }
+        });
+    }
+
+    /// Removes built and orphaned decoded assets which grace period is over
+    /// and that did not get new handles since release.
+    pub(crate) fn sweep(&self) {
+        let now = self.clock.now();
+
+        // Removed assets may hold handles to their dependencies.
+        // Drop them after all locks are released.
+        let mut removed = Vec::new();
+
+        loop {
+            let mut released = self.released.lock();
+            match released.front() {
+                Some(front) if front.at + self.grace <= now => {}
+                _ => break,
+            }
+            let item = released.pop_front().unwrap();
+            drop(released);
+
+            let mut refs = self.refs.lock();
+            match refs.get(&item.key) {
+                Some((generation, _)) if *generation == item.generation => {
+                    refs.remove(&item.key);
+                }
+                // Asset got new handles since release.
+                _ => continue,
+            }
+
+            // Refs are locked until the entry is removed,
+            // so no new handle can find it in between.
+            let mut locked_shard = item.shard.lock();
+            if let Entry::Occupied(mut entry) =
+                locked_shard.entry(item.key_hash, |k| k.eq_key(item.key.kind, item.key.id))
+            {
+                let state = entry.get();
+                if matches!(state, AssetState::Ready { .. }) || state.is_orphaned_decoded() {
+                    debug_check(EntryKind::Asset, Some(state.status()), Event::Remove, None);
+                    removed.push(entry.remove());
+                }
+            }
+            drop(locked_shard);
+            drop(refs);
+        }
+
+        drop(removed);
+    }
+}
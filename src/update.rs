@@ -0,0 +1,285 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use argosy_id::AssetId;
+use futures::future::BoxFuture;
+use hashbrown::HashMap;
+use parking_lot::{Mutex, RwLock};
+
+use crate::{
+    error::Error,
+    key::KindKey,
+    loader::{AssetShard, AssetState, ErasedDecodedState, Loader},
+    source::Source,
+};
+
+/// Default maximum number of update calls per tick.
+pub(crate) const DEFAULT_MAX_UPDATES_PER_TICK: usize = 64;
+
+/// Default maximum interval in ticks between update calls for unchanged asset.
+pub(crate) const DEFAULT_MAX_UPDATE_INTERVAL: u64 = 32;
+
+/// Asset picked for update call.
+pub(crate) struct UpdateCandidate {
+    pub id: AssetId,
+    pub version: u64,
+    pub source: Arc<dyn Source>,
+}
+
+/// Update schedule of the asset.
+#[derive(Default)]
+struct Backoff {
+    /// Number of consecutive update calls that found no change.
+    unchanged: u32,
+
+    /// First tick asset is checked again.
+    next_tick: u64,
+
+    /// Latest version reported by update call.
+    version: Option<u64>,
+
+    /// Last tick asset was found in the cache.
+    seen: u64,
+}
+
+struct Sweep {
+    tick: u64,
+
+    /// Shard and position in the shard where next tick continues.
+    shard: usize,
+    offset: usize,
+
+    /// Tick current round over all shards started on.
+    round: u64,
+
+    /// Schedules of cached assets.
+    /// Assets not found in the cache during whole round are dropped.
+    assets: HashMap<AssetId, Backoff>,
+}
+
+/// Paces update calls to sources.
+///
+/// Each tick sweeps cached assets incrementally, starting where previous tick stopped,
+/// and backs off assets that keep reporting no change.
+pub(crate) struct UpdateScheduler {
+    max_per_tick: usize,
+    max_interval: u64,
+    sweep: Mutex<Sweep>,
+
+    calls: AtomicU64,
+    changed: AtomicU64,
+    backed_off: AtomicU64,
+}
+
+impl UpdateScheduler {
+    pub fn new(max_per_tick: usize, max_interval: u64) -> Self {
+        UpdateScheduler {
+            max_per_tick,
+            max_interval: max_interval.max(1),
+            sweep: Mutex::new(Sweep {
+                tick: 0,
+                shard: 0,
+                offset: 0,
+                round: 0,
+                assets: HashMap::new(),
+            }),
+            calls: AtomicU64::new(0),
+            changed: AtomicU64::new(0),
+            backed_off: AtomicU64::new(0),
+        }
+    }
+
+    /// Starts next tick.
+    /// Returns the tick and assets to call update for.
+    ///
+    /// `source` returns updatable source by serial.
+    pub fn next_tick(
+        &self,
+        shards: &[AssetShard],
+        source: impl Fn(u64) -> Option<Arc<dyn Source>>,
+    ) -> (u64, Vec<UpdateCandidate>) {
+        self.sweep(shards, self.max_per_tick, source)
+    }
+
+    /// Starts next tick that picks all assets that are not backed off.
+    pub fn full_tick(
+        &self,
+        shards: &[AssetShard],
+        source: impl Fn(u64) -> Option<Arc<dyn Source>>,
+    ) -> (u64, Vec<UpdateCandidate>) {
+        self.sweep(shards, usize::MAX, source)
+    }
+
+    fn sweep(
+        &self,
+        shards: &[AssetShard],
+        max_picked: usize,
+        source: impl Fn(u64) -> Option<Arc<dyn Source>>,
+    ) -> (u64, Vec<UpdateCandidate>) {
+        let mut sweep = self.sweep.lock();
+        sweep.tick += 1;
+
+        let Sweep {
+            tick,
+            shard,
+            offset,
+            round,
+            assets,
+        } = &mut *sweep;
+        let tick = *tick;
+
+        let mut picked = Vec::new();
+        let start = *shard % shards.len();
+        let start_offset = *offset;
+
+        // Visit starting shard again after full round to check entries before the cursor.
+        for step in 0..=shards.len() {
+            let index = (start + step) % shards.len();
+            let (from, to) = match step {
+                0 => (start_offset, usize::MAX),
+                step if step == shards.len() => (0, start_offset),
+                _ => (0, usize::MAX),
+            };
+
+            let mut position = 0;
+            let mut stopped = None;
+            shards[index].lock().retain(&mut |key, state| {
+                let current = position;
+                position += 1;
+
+                if current < from || current >= to || stopped.is_some() {
+                    return true;
+                }
+
+                if picked.len() >= max_picked {
+                    stopped = Some(current);
+                    return true;
+                }
+
+                let metadata = match state {
+                    AssetState::Loaded { metadata, .. } | AssetState::Ready { metadata, .. } => {
+                        metadata
+                    }
+                    _ => return true,
+                };
+
+                if let Some(backoff) = assets.get_mut(&key.id) {
+                    backoff.seen = tick;
+                }
+
+                // Same asset may be cached for several types.
+                if picked.iter().any(|c: &UpdateCandidate| c.id == key.id) {
+                    return true;
+                }
+
+                let Some(source) = source(metadata.source_serial) else {
+                    return true;
+                };
+
+                let version = match assets.get(&key.id) {
+                    Some(backoff) if backoff.next_tick > tick => {
+                        self.backed_off.fetch_add(1, Ordering::Relaxed);
+                        return true;
+                    }
+                    Some(backoff) => backoff.version.unwrap_or(metadata.version),
+                    None => metadata.version,
+                };
+
+                picked.push(UpdateCandidate {
+                    id: key.id,
+                    version,
+                    source,
+                });
+                true
+            });
+
+            // Reaching end of the last shard completes the round.
+            if stopped.is_none() && to == usize::MAX && index == shards.len() - 1 {
+                let started = *round;
+                assets.retain(|_, backoff| backoff.seen >= started);
+                *round = tick;
+            }
+
+            if let Some(stopped) = stopped {
+                *shard = index;
+                *offset = stopped;
+                return (tick, picked);
+            }
+
+            if picked.len() >= max_picked {
+                *shard = index + 1;
+                *offset = 0;
+                return (tick, picked);
+            }
+        }
+
+        (tick, picked)
+    }
+
+    /// Records result of the update call made on the tick.
+    /// `version` is set if source returned newer data.
+    pub fn finish(&self, tick: u64, id: AssetId, version: Option<u64>) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+
+        let mut sweep = self.sweep.lock();
+        let backoff = sweep.assets.entry(id).or_default();
+        backoff.seen = tick;
+        match version {
+            Some(version) => {
+                self.changed.fetch_add(1, Ordering::Relaxed);
+                backoff.unchanged = 0;
+                backoff.next_tick = tick + 1;
+                backoff.version = Some(version);
+            }
+            None => {
+                backoff.unchanged = backoff.unchanged.saturating_add(1);
+                let interval = 1u64
+                    .checked_shl(backoff.unchanged)
+                    .unwrap_or(u64::MAX)
+                    .min(self.max_interval);
+                backoff.next_tick = tick + interval;
+            }
+        }
+    }
+
+    /// Returns number of update calls made, calls that found newer data
+    /// and assets skipped because of backoff.
+    pub fn counters(&self) -> (u64, u64, u64) {
+        (
+            self.calls.load(Ordering::Relaxed),
+            self.changed.load(Ordering::Relaxed),
+            self.backed_off.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// Decodes fresh data of assets of one kind.
+pub(crate) type ReloadDecode = Arc<
+    dyn Fn(Box<[u8]>, &Loader) -> BoxFuture<'static, Result<ErasedDecodedState, Error>>
+        + Send
+        + Sync,
+>;
+
+/// Decoders of asset kinds that were loaded, used to reload changed assets.
+#[derive(Default)]
+pub(crate) struct Reloaders {
+    decoders: RwLock<HashMap<KindKey, ReloadDecode>>,
+}
+
+impl Reloaders {
+    /// Registers decoder of the kind if kind has one and it is not registered yet.
+    pub fn register(&self, kind: KindKey, decode: impl FnOnce() -> Option<ReloadDecode>) {
+        if self.decoders.read().contains_key(&kind) {
+            return;
+        }
+        if let Some(decode) = decode() {
+            self.decoders.write().entry(kind).or_insert(decode);
+        }
+    }
+
+    pub fn get(&self, kind: KindKey) -> Option<ReloadDecode> {
+        self.decoders.read().get(&kind).cloned()
+    }
+}
@@ -0,0 +1,222 @@
+use std::{collections::HashSet, sync::Arc};
+
+use argosy_id::AssetId;
+use parking_lot::Mutex;
+
+/// Asset resolved by the loader.
+///
+/// Recorded when usage recording is enabled with
+/// [`LoaderBuilder::set_usage_recording`].
+///
+/// [`LoaderBuilder::set_usage_recording`]: crate::LoaderBuilder::set_usage_recording
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssetUsage {
+    /// Id of the asset.
+    pub id: AssetId,
+
+    /// Name of the asset type.
+    pub name: String,
+
+    /// Path with which asset was requested, if any.
+    pub path: Option<Arc<str>>,
+}
+
+/// Receives assets used by the loader.
+///
+/// Each asset usage is reported once per loader.
+/// `()` discards usages, leaving them only in [`Loader::usage_snapshot`].
+///
+/// [`Loader::usage_snapshot`]: crate::Loader::usage_snapshot
+pub trait UsageSink: Send + Sync + 'static {
+    /// Records asset usage.
+    fn record(&self, usage: &AssetUsage);
+}
+
+impl UsageSink for () {
+    fn record(&self, _usage: &AssetUsage) {}
+}
+
+/// Deduplicates usages and forwards new ones to the sink.
+pub(crate) struct UsageRecorder {
+    seen: Mutex<HashSet<AssetUsage>>,
+    sink: Box<dyn UsageSink>,
+}
+
+impl UsageRecorder {
+    pub fn new(sink: Box<dyn UsageSink>) -> Self {
+        UsageRecorder {
+            seen: Mutex::new(HashSet::new()),
+            sink,
+        }
+    }
+
+    pub fn record(&self, name: &str, id: AssetId, path: Option<&Arc<str>>) {
+        let usage = AssetUsage {
+            id,
+            name: name.to_owned(),
+            path: path.cloned(),
+        };
+
+        if self.seen.lock().insert(usage.clone()) {
+            self.sink.record(&usage);
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<AssetUsage> {
+        let mut usages: Vec<_> = self.seen.lock().iter().cloned().collect();
+        usages.sort();
+        usages
+    }
+}
+
+#[cfg(feature = "fs")]
+pub use self::log::UsageLog;
+
+#[cfg(feature = "fs")]
+mod log {
+    use std::{
+        io::{BufRead, BufWriter, Write},
+        path::Path,
+        sync::{mpsc, Arc},
+        thread::JoinHandle,
+    };
+
+    use super::{AssetUsage, UsageSink};
+
+    /// [`UsageSink`] that appends usages to a file.
+    ///
+    /// Each usage is written as a line with tab-separated id, type name and path.
+    /// Writing happens on a background thread
+    /// that is joined when the log is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::*;
+    /// #[derive(Clone, Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// let path = std::env::temp_dir().join(format!("argosy-usage-{}.log", std::process::id()));
+    ///
+    /// let source = MemorySource::new();
+    /// source.insert_with_path("number", AssetId::new(1).unwrap(), &br#"{ "value": 1 }"#[..]);
+    ///
+    /// let loader = Loader::builder()
+    ///     .with(source)
+    ///     .with_usage_recording(UsageLog::append(&path).unwrap())
+    ///     .build();
+    ///
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         loader.load::<Number, _>("number").await.unwrap();
+    ///         loader.load::<Number, _>(AssetId::new(1).unwrap()).await.unwrap();
+    ///     });
+    ///
+    /// let usages = UsageLog::read(&path).unwrap();
+    /// assert_eq!(usages.len(), 1);
+    /// assert_eq!(usages[0].id, AssetId::new(1).unwrap());
+    /// assert_eq!(usages[0].name, "Number");
+    /// assert_eq!(usages[0].path.as_deref(), Some("number"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub struct UsageLog {
+        sender: Option<mpsc::Sender<AssetUsage>>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl UsageLog {
+        /// Returns sink that appends usages to the file at `path`.
+        /// File is created if it does not exist.
+        pub fn append(path: impl AsRef<Path>) -> std::io::Result<Self> {
+            let path = path.as_ref();
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+
+            let (sender, receiver) = mpsc::channel::<AssetUsage>();
+            let log_path = path.display().to_string();
+
+            let thread = std::thread::spawn(move || {
+                let mut writer = BufWriter::new(file);
+
+                while let Ok(usage) = receiver.recv() {
+                    // Flush once all pending usages are written.
+                    let result = std::iter::once(usage)
+                        .chain(receiver.try_iter())
+                        .try_for_each(|usage| write_usage(&mut writer, &usage))
+                        .and_then(|()| writer.flush());
+
+                    if let Err(err) = result {
+                        tracing::warn!("Failed to write asset usage log '{log_path}'. {err}");
+                        break;
+                    }
+                }
+            });
+
+            Ok(UsageLog {
+                sender: Some(sender),
+                thread: Some(thread),
+            })
+        }
+
+        /// Reads usages written to the file at `path`.
+        /// Usages written by different runs are all returned.
+        pub fn read(path: impl AsRef<Path>) -> std::io::Result<Vec<AssetUsage>> {
+            let file = std::io::BufReader::new(std::fs::File::open(path)?);
+
+            let mut usages = Vec::new();
+            for line in file.lines() {
+                let line = line?;
+                let mut parts = line.split('\t');
+
+                let (Some(id), Some(name), path) = (parts.next(), parts.next(), parts.next())
+                else {
+                    continue;
+                };
+                let Ok(id) = id.parse() else {
+                    continue;
+                };
+
+                usages.push(AssetUsage {
+                    id,
+                    name: name.to_owned(),
+                    path: path.filter(|path| !path.is_empty()).map(Arc::from),
+                });
+            }
+            Ok(usages)
+        }
+    }
+
+    fn write_usage(writer: &mut impl Write, usage: &AssetUsage) -> std::io::Result<()> {
+        writeln!(
+            writer,
+            "{}\t{}\t{}",
+            usage.id,
+            usage.name,
+            usage.path.as_deref().unwrap_or("")
+        )
+    }
+
+    impl UsageSink for UsageLog {
+        fn record(&self, usage: &AssetUsage) {
+            if let Some(sender) = &self.sender {
+                let _ = sender.send(usage.clone());
+            }
+        }
+    }
+
+    impl Drop for UsageLog {
+        fn drop(&mut self) {
+            // Closing the channel stops the thread after pending usages are written.
+            self.sender.take();
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
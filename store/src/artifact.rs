@@ -0,0 +1,157 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use argosy::{ArtifactEnvelope, AssetFormat};
+use argosy_import::DescriptorFormat;
+use sha2::{Digest, Sha256};
+
+/// Description of the stored artifact.
+///
+/// See [`Store::artifact_info`].
+///
+/// [`Store::artifact_info`]: crate::Store::artifact_info
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ArtifactInfo {
+    /// Length of the artifact data in bytes.
+    pub len: u64,
+
+    /// SHA-256 hash of the artifact data.
+    pub sha256: [u8; 32],
+
+    /// Whether artifact file is compressed.
+    /// Store writes artifacts uncompressed, so this is always `false` for now.
+    pub compressed: bool,
+}
+
+/// Artifact data does not match its metadata.
+///
+/// Returned from [`ArtifactReader`] as source of [`std::io::Error`]
+/// with [`std::io::ErrorKind::InvalidData`] kind.
+#[derive(Debug, thiserror::Error)]
+pub enum ArtifactError {
+    #[error("Artifact is truncated. Expected {expected} bytes, found {found}")]
+    Truncated { expected: u64, found: u64 },
+
+    #[error("Artifact is longer than expected {expected} bytes")]
+    TooLong { expected: u64 },
+
+    #[error("Artifact hash mismatch. Expected {expected}, found {found}")]
+    HashMismatch { expected: String, found: String },
+}
+
+/// Reader of the stored artifact.
+///
+/// Validates length and hash of the artifact data incrementally as it is read.
+/// Mismatch is reported as error from the read call that reaches
+/// expected length or end of the file.
+///
+/// See [`Store::open_artifact`].
+///
+/// [`Store::open_artifact`]: crate::Store::open_artifact
+pub struct ArtifactReader {
+    file: BufReader<File>,
+    info: ArtifactInfo,
+    hasher: Sha256,
+    read: u64,
+    checked: bool,
+}
+
+impl ArtifactReader {
+    pub(crate) fn new(file: File, info: ArtifactInfo) -> Self {
+        ArtifactReader {
+            file: BufReader::new(file),
+            info,
+            hasher: Sha256::new(),
+            read: 0,
+            checked: false,
+        }
+    }
+
+    /// Returns description of the artifact being read.
+    pub fn info(&self) -> &ArtifactInfo {
+        &self.info
+    }
+
+    /// Reads whole artifact into a vector preallocated to expected length.
+    pub fn read_all(mut self) -> std::io::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(self.info.len as usize);
+        self.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn check_hash(&mut self) -> Result<(), ArtifactError> {
+        let hash = std::mem::take(&mut self.hasher).finalize();
+        if hash[..] == self.info.sha256[..] {
+            return Ok(());
+        }
+
+        let mut found = [0; 32];
+        found.copy_from_slice(&hash);
+        Err(ArtifactError::HashMismatch {
+            expected: hex(self.info.sha256),
+            found: hex(found),
+        })
+    }
+}
+
+/// Formats hash as lowercase hex string.
+pub(crate) fn hex(bytes: [u8; 32]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+impl Read for ArtifactReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let invalid = |err| std::io::Error::new(std::io::ErrorKind::InvalidData, err);
+
+        let n = self.file.read(buf)?;
+        self.read += n as u64;
+
+        if self.read > self.info.len {
+            return Err(invalid(ArtifactError::TooLong {
+                expected: self.info.len,
+            }));
+        }
+
+        self.hasher.update(&buf[..n]);
+
+        if n == 0 && !buf.is_empty() && self.read < self.info.len {
+            return Err(invalid(ArtifactError::Truncated {
+                expected: self.info.len,
+                found: self.read,
+            }));
+        }
+
+        if self.read == self.info.len && !self.checked {
+            self.checked = true;
+            self.check_hash().map_err(invalid)?;
+        }
+
+        Ok(n)
+    }
+}
+
+/// Wraps descriptor artifact at `path` into [`ArtifactEnvelope`] and writes it to `output`.
+///
+/// Returns `false` without writing if artifact is enveloped already.
+pub(crate) fn envelope_artifact(
+    path: &Path,
+    output: &Path,
+    format: DescriptorFormat,
+) -> std::io::Result<bool> {
+    let bytes = std::fs::read(path)?;
+    if bytes.starts_with(&ArtifactEnvelope::MAGIC) {
+        return Ok(false);
+    }
+
+    let format = match format {
+        DescriptorFormat::Json => AssetFormat::Json,
+        DescriptorFormat::Bincode => AssetFormat::Bincode,
+    };
+
+    std::fs::write(output, ArtifactEnvelope::new(format).wrap(&bytes))?;
+    Ok(true)
+}
@@ -7,21 +7,42 @@ use std::{
 
 pub(crate) const PREFIX_STARTING_LEN: usize = 8;
 
+/// Number of suffixes tried after all prefixes are occupied.
+pub(crate) const MAX_PATH_SUFFIXES: usize = 1024;
+
+/// All path candidates for the hash are occupied.
+#[derive(Debug, thiserror::Error)]
+#[error("All path candidates for '{hex}' in '{}' are occupied", base.display())]
+pub struct PathCandidatesExhausted {
+    pub hex: String,
+    pub base: PathBuf,
+}
+
+impl From<PathCandidatesExhausted> for std::io::Error {
+    fn from(err: PathCandidatesExhausted) -> Self {
+        std::io::Error::other(err)
+    }
+}
+
 /// Tries to find non-occupied path in given directory
 /// using hex-string representation of file hash.
 ///
 /// This function iterates over all possible prefixes of given hex-string
-/// and then over all possible suffixes.
+/// and then over [`MAX_PATH_SUFFIXES`] suffixes.
 ///
 /// Calls provided closure with each path candidate.
 /// When closure returns `Ok(None)` then next candidate is tried.
 /// When closure returns `Ok(Some(ok))` then this value is returned from this function.
 /// When closure returns `Err(err)` then this error is returned from this function.
+/// When all candidates are rejected [`PathCandidatesExhausted`] error is returned.
 pub(crate) fn with_path_candidates<T, E>(
     hex: &str,
     base: &Path,
     mut f: impl FnMut(PathBuf, u64) -> Result<Option<T>, E>,
-) -> Result<T, E> {
+) -> Result<T, E>
+where
+    E: From<PathCandidatesExhausted>,
+{
     use std::fmt::Write;
 
     for len in PREFIX_STARTING_LEN..=hex.len() {
@@ -37,7 +58,7 @@ pub(crate) fn with_path_candidates<T, E>(
     // Rarely needed.
     let mut name = hex.to_owned();
 
-    for suffix in 0usize.. {
+    for suffix in 0..MAX_PATH_SUFFIXES {
         name.truncate(hex.len());
         write!(name, ":{}", suffix).unwrap();
 
@@ -50,7 +71,16 @@ pub(crate) fn with_path_candidates<T, E>(
         }
     }
 
-    unreachable!()
+    tracing::error!(
+        "All path candidates for '{}' in '{}' are occupied",
+        hex,
+        base.display()
+    );
+    Err(PathCandidatesExhausted {
+        hex: hex.to_owned(),
+        base: base.to_owned(),
+    }
+    .into())
 }
 
 /// Stores copy of the content in the base directory.
@@ -59,7 +89,7 @@ pub(crate) fn with_path_candidates<T, E>(
 /// This function uses hex-string representation of content hash
 /// for the new file name.
 /// It walks over prefix length starting with [`PREFIX_STARTING_LEN`]
-/// and then over suffixes from 0 to i64::MAX.
+/// and then over suffixes from 0 to [`MAX_PATH_SUFFIXES`].
 /// For each name candidate it checks if file with this name exists
 /// and if it has the same content.
 /// If identical file is found then its path is returned.
@@ -107,7 +137,7 @@ pub(crate) fn store_data_with_content_address(
 /// This function uses hex-string representation of content hash
 /// for the new file name.
 /// It walks over prefix length starting with [`PREFIX_STARTING_LEN`]
-/// and then over suffixes from 0 to i64::MAX.
+/// and then over suffixes from 0 to [`MAX_PATH_SUFFIXES`].
 /// For each name candidate it checks if file with this name exists
 /// and if it has the same content.
 /// If identical file is found then its path is returned.
@@ -122,7 +152,7 @@ pub(crate) fn move_file_with_content_address(
 
     with_path_candidates(hex, base, move |path, len| match path.metadata() {
         Err(_) => {
-            std::fs::rename(&file, &path)?;
+            std::fs::rename(file, &path)?;
             Ok(Some((path, len)))
         }
         Ok(metadata) => {
@@ -155,10 +185,8 @@ fn files_eq(path1: &Path, path2: &Path) -> std::io::Result<bool> {
     loop {
         if len1 == 0 {
             let n = file1.read(&mut buf1[off1 + len1..])?;
-            if n == 0 {
-                if len1 < len2 {
-                    return Ok(false);
-                }
+            if n == 0 && len1 < len2 {
+                return Ok(false);
             }
             len1 += n;
         }
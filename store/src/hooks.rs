@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use argosy_id::AssetId;
+use url::Url;
+
+/// Import that is about to be performed.
+///
+/// Passed to pre-import hooks, see [`Store::add_pre_import_hook`].
+///
+/// [`Store::add_pre_import_hook`]: crate::Store::add_pre_import_hook
+#[derive(Clone, Debug)]
+pub struct ImportRequest {
+    /// Source URL of the asset.
+    pub source: Url,
+
+    /// Source format of the asset if specified.
+    pub format: Option<String>,
+
+    /// Target format of the asset.
+    pub target: String,
+
+    /// Name of the importer that will import the asset.
+    pub importer: String,
+
+    /// Path to the fetched source file.
+    pub source_path: PathBuf,
+}
+
+/// Asset that was just imported.
+///
+/// Passed to post-import hooks, see [`Store::add_post_import_hook`].
+///
+/// [`Store::add_post_import_hook`]: crate::Store::add_post_import_hook
+#[derive(Clone, Debug)]
+pub struct ImportResultInfo {
+    /// Id of the imported asset.
+    pub id: AssetId,
+
+    /// Source URL of the asset.
+    pub source: Url,
+
+    /// Target format of the asset.
+    pub target: String,
+
+    /// Path to the artifact with imported asset data.
+    pub artifact_path: PathBuf,
+
+    /// Whether asset was imported before and its artifact is replaced.
+    pub reimport: bool,
+}
+
+pub(crate) type PreImportHook = Box<dyn Fn(&ImportRequest) -> Result<(), String> + Send + Sync>;
+pub(crate) type PostImportHook = Box<dyn Fn(&ImportResultInfo) -> Result<(), String> + Send + Sync>;
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use argosy_import::{loading::LoadingError, Importer};
 use hashbrown::{hash_map::RawEntryMut, HashMap};
@@ -10,6 +10,74 @@ pub struct CannotDecideOnImporter {
     pub target: String,
 }
 
+/// Stage of an import pipeline.
+/// Imports from `format` to `target` with registered importer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StageSpec {
+    /// Format stage imports from.
+    pub format: String,
+
+    /// Format stage produces.
+    pub target: String,
+}
+
+/// Error returned when pipeline stages do not form a chain to the target.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidPipeline {
+    #[error("Pipeline to '{target}' has no stages")]
+    Empty { target: String },
+
+    #[error(
+        "Stage {index} of pipeline to '{target}' imports from '{found}' instead of '{expected}'"
+    )]
+    Disconnected {
+        target: String,
+        index: usize,
+        expected: String,
+        found: String,
+    },
+}
+
+/// Information about registered importer.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ImporterInfo {
+    /// Name of the importer.
+    pub name: String,
+
+    /// Source formats the importer can import from.
+    pub formats: Vec<String>,
+
+    /// Target format the importer produces.
+    pub target: String,
+
+    /// Source file extensions the importer is selected by.
+    pub extensions: Vec<String>,
+
+    /// Human-readable description of the importer.
+    /// Empty if importer does not provide one.
+    pub description: String,
+
+    /// Version of the importer.
+    pub version: u32,
+}
+
+impl ImporterInfo {
+    fn new(importer: &dyn Importer) -> Self {
+        ImporterInfo {
+            name: importer.name().to_owned(),
+            formats: importer.formats().iter().map(|&f| f.to_owned()).collect(),
+            target: importer.target().to_owned(),
+            extensions: importer
+                .extensions()
+                .iter()
+                .map(|&e| e.to_owned())
+                .collect(),
+            description: importer.description().to_owned(),
+            version: importer.version(),
+        }
+    }
+}
+
 struct ToTarget {
     importers: Vec<Box<dyn Importer>>,
     formats: HashMap<String, usize>,
@@ -18,12 +86,18 @@ struct ToTarget {
 
 pub struct Importers {
     targets: HashMap<String, ToTarget>,
+    pipelines: HashMap<String, Vec<Vec<StageSpec>>>,
+
+    /// Target and name of importers loaded from each dylib.
+    libs: HashMap<PathBuf, Vec<(String, String)>>,
 }
 
 impl Importers {
     pub fn new() -> Self {
         Importers {
             targets: HashMap::new(),
+            pipelines: HashMap::new(),
+            libs: HashMap::new(),
         }
     }
 
@@ -37,13 +111,53 @@ impl Importers {
     pub unsafe fn load_dylib_importers(&mut self, lib_path: &Path) -> Result<(), LoadingError> {
         let iter = argosy_import::loading::load_importers(lib_path)?;
 
+        let mut loaded = Vec::new();
         for importer in iter {
+            loaded.push((importer.target().to_owned(), importer.name().to_owned()));
             self.add_importer(Box::new(importer));
         }
 
+        self.libs
+            .entry(lib_path.to_owned())
+            .or_default()
+            .extend(loaded);
+
         Ok(())
     }
 
+    /// Replaces importers loaded from dylib with ones loaded from it anew.
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`Importers::load_dylib_importers`].
+    pub unsafe fn reload_dylib_importers(&mut self, lib_path: &Path) -> Result<(), LoadingError> {
+        if let Some(loaded) = self.libs.remove(lib_path) {
+            // Old importers must release the library before it is opened again.
+            self.retain_importers(|importer| {
+                !loaded
+                    .iter()
+                    .any(|(target, name)| importer.target() == target && importer.name() == name)
+            });
+        }
+
+        self.load_dylib_importers(lib_path)
+    }
+
+    /// Removes importers for which `f` returns `false`.
+    fn retain_importers(&mut self, mut f: impl FnMut(&dyn Importer) -> bool) {
+        let importers: Vec<_> = self
+            .targets
+            .drain()
+            .flat_map(|(_, to_target)| to_target.importers)
+            .collect();
+
+        for importer in importers {
+            if f(&*importer) {
+                self.add_importer(importer);
+            }
+        }
+    }
+
     /// Try to guess importer by optionally provided format and extension or by target alone.
     pub fn guess(
         &self,
@@ -88,6 +202,152 @@ impl Importers {
         }
     }
 
+    /// Registers pipeline of stages that imports to `target`.
+    ///
+    /// Each stage must import from the target of the previous one,
+    /// last stage must produce `target`.
+    /// Stage importers are looked up when pipeline is used,
+    /// so they may be registered later.
+    pub fn register_pipeline(
+        &mut self,
+        target: &str,
+        stages: Vec<StageSpec>,
+    ) -> Result<(), InvalidPipeline> {
+        let Some(last) = stages.last() else {
+            return Err(InvalidPipeline::Empty {
+                target: target.to_owned(),
+            });
+        };
+
+        if last.target != target {
+            return Err(InvalidPipeline::Disconnected {
+                target: target.to_owned(),
+                index: stages.len(),
+                expected: last.target.clone(),
+                found: target.to_owned(),
+            });
+        }
+
+        for (index, pair) in stages.windows(2).enumerate() {
+            if pair[0].target != pair[1].format {
+                return Err(InvalidPipeline::Disconnected {
+                    target: target.to_owned(),
+                    index: index + 1,
+                    expected: pair[0].target.clone(),
+                    found: pair[1].format.clone(),
+                });
+            }
+        }
+
+        tracing::info!(
+            "Registering pipeline to '{}'. {:?}",
+            target,
+            stages
+                .iter()
+                .map(|stage| format!("'{}' -> '{}'", stage.format, stage.target))
+                .collect::<Vec<_>>(),
+        );
+
+        self.pipelines
+            .entry(target.to_owned())
+            .or_default()
+            .push(stages);
+        Ok(())
+    }
+
+    /// Finds importers to run in sequence to import to `target`.
+    ///
+    /// Importer found with [`Importers::guess`] is used alone.
+    /// Otherwise registered pipelines to `target` are tried in registration order.
+    /// At last chains of two registered importers through any intermediate format are searched.
+    /// Chains are searched only if format or extension is known.
+    pub fn guess_chain(
+        &self,
+        format: Option<&str>,
+        extension: Option<&str>,
+        target: &str,
+    ) -> Result<Option<Vec<&dyn Importer>>, CannotDecideOnImporter> {
+        if let Some(importer) = self.guess(format, extension, target)? {
+            return Ok(Some(vec![importer]));
+        }
+
+        if format.is_none() && extension.is_none() {
+            return Ok(None);
+        }
+
+        for stages in self.pipelines.get(target).into_iter().flatten() {
+            if let Some(chain) = self.pipeline_chain(format, extension, stages) {
+                return Ok(Some(chain));
+            }
+        }
+
+        let mut found = Vec::new();
+        for intermediate in self.targets.keys() {
+            if intermediate == target {
+                continue;
+            }
+
+            // Ambiguous stages are not considered.
+            let Ok(Some(first)) = self.guess(format, extension, intermediate) else {
+                continue;
+            };
+            let Ok(Some(second)) = self.guess(Some(intermediate), None, target) else {
+                continue;
+            };
+            found.push((intermediate, [first, second]));
+        }
+
+        match found.len() {
+            0 => {
+                tracing::debug!("No import chains to '{}' found", target);
+                Ok(None)
+            }
+            1 => Ok(Some(found.pop().unwrap().1.to_vec())),
+            _ => {
+                tracing::debug!("Multiple import chains to '{}' found", target);
+                let mut formats: Vec<_> = found.iter().map(|(f, _)| (*f).clone()).collect();
+                formats.sort_unstable();
+                Err(CannotDecideOnImporter {
+                    target: target.to_owned(),
+                    formats,
+                })
+            }
+        }
+    }
+
+    /// Returns importers for pipeline stages
+    /// if pipeline imports from specified format or extension.
+    fn pipeline_chain(
+        &self,
+        format: Option<&str>,
+        extension: Option<&str>,
+        stages: &[StageSpec],
+    ) -> Option<Vec<&dyn Importer>> {
+        let chain = stages
+            .iter()
+            .map(
+                |stage| match self.guess(Some(&stage.format), None, &stage.target) {
+                    Ok(Some(importer)) => Some(importer),
+                    _ => {
+                        tracing::debug!(
+                            "No importer for pipeline stage '{}' -> '{}'",
+                            stage.format,
+                            stage.target
+                        );
+                        None
+                    }
+                },
+            )
+            .collect::<Option<Vec<_>>>()?;
+
+        let matches = match format {
+            Some(format) => stages[0].format == format,
+            None => extension.is_some_and(|ext| chain[0].extensions().contains(&ext)),
+        };
+
+        matches.then_some(chain)
+    }
+
     /// Adds importer to the list of importers.
     pub fn add_importer(&mut self, importer: Box<dyn Importer>) {
         let name = importer.name();
@@ -116,11 +376,11 @@ impl Importers {
                     )
                     .1;
 
-                for &format in &*formats {
+                for &format in formats {
                     to_target.formats.insert(format.to_owned(), 0);
                 }
 
-                for &extension in &*extensions {
+                for &extension in extensions {
                     to_target.extensions.insert(extension.to_owned(), 0);
                 }
                 to_target.importers.push(importer);
@@ -129,7 +389,7 @@ impl Importers {
                 let to_target = entry.into_mut();
                 let idx = to_target.importers.len();
 
-                for &format in &*formats {
+                for &format in formats {
                     match to_target.formats.raw_entry_mut().from_key(format) {
                         RawEntryMut::Vacant(entry) => {
                             entry.insert(format.to_owned(), idx);
@@ -145,7 +405,7 @@ impl Importers {
                     }
                 }
 
-                for &extension in &*extensions {
+                for &extension in extensions {
                     match to_target.extensions.raw_entry_mut().from_key(extension) {
                         RawEntryMut::Vacant(entry) => {
                             entry.insert(extension.to_owned(), idx);
@@ -165,4 +425,93 @@ impl Importers {
             }
         }
     }
+
+    /// Returns information about all registered importers.
+    /// Sorted by target and then by name.
+    pub fn infos(&self) -> Vec<ImporterInfo> {
+        let mut infos: Vec<_> = self
+            .targets
+            .values()
+            .flat_map(|to_target| &to_target.importers)
+            .map(|importer| ImporterInfo::new(&**importer))
+            .collect();
+
+        infos.sort_by(|a, b| a.target.cmp(&b.target).then_with(|| a.name.cmp(&b.name)));
+        infos
+    }
+
+    /// Returns all targets importers are registered for.
+    /// Sorted alphabetically.
+    pub fn targets(&self) -> Vec<&str> {
+        let mut targets: Vec<_> = self.targets.keys().map(String::as_str).collect();
+        targets.sort_unstable();
+        targets
+    }
+
+    /// Returns information about importers to specified target.
+    /// Sorted by name.
+    pub fn describe(&self, target: &str) -> Vec<ImporterInfo> {
+        let Some(to_target) = self.targets.get(target) else {
+            return Vec::new();
+        };
+
+        let mut infos: Vec<_> = to_target
+            .importers
+            .iter()
+            .map(|importer| ImporterInfo::new(&**importer))
+            .collect();
+
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
+    }
+
+    /// Returns names of importers in the chain joined with arrows.
+    pub fn chain_name(chain: &[&dyn Importer]) -> String {
+        let names: Vec<_> = chain.iter().map(|importer| importer.name()).collect();
+        names.join(" -> ")
+    }
+
+    /// Suggests registered target or format close to the requested one
+    /// when no importer is found.
+    ///
+    /// Unknown target is matched against registered targets.
+    /// Unknown format is matched against formats of importers to the target.
+    pub fn suggest(&self, format: Option<&str>, target: &str) -> Option<String> {
+        match self.targets.get(target) {
+            None => closest(target, self.targets.keys()),
+            Some(to_target) => closest(format?, to_target.formats.keys()),
+        }
+    }
+}
+
+/// Returns candidate closest to `name` by edit distance
+/// if it is close enough to be a typo.
+fn closest<'a>(name: &str, candidates: impl Iterator<Item = &'a String>) -> Option<String> {
+    // Allow one typo per few characters.
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.clone())
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
 }
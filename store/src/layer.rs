@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use url::Url;
+
+use crate::meta::SourceMeta;
+
+/// Read-only base store which artifacts are reused by the store.
+pub(crate) struct Layer {
+    pub base: PathBuf,
+    pub base_url: Url,
+    pub artifacts: PathBuf,
+    pub external: PathBuf,
+}
+
+impl Layer {
+    /// Returns URL of the local source in this layer.
+    ///
+    /// Sources under local base directory are mapped to the same relative path
+    /// under base directory of the layer. Other sources are kept as is.
+    pub fn layer_url(&self, source: &Url, local_base: &Url) -> Url {
+        map_url(source, local_base, &self.base_url)
+    }
+
+    /// Returns URL of the source of this layer in the local store.
+    pub fn local_url(&self, source: &Url, local_base: &Url) -> Url {
+        map_url(source, &self.base_url, local_base)
+    }
+
+    /// Returns metadata of the source in this layer, if any.
+    pub fn source_meta(&self, source: &Url, local_base: &Url) -> Option<SourceMeta> {
+        let url = self.layer_url(source, local_base);
+        match SourceMeta::find_existing(&url, &self.base, &self.external) {
+            Ok(meta) => meta,
+            Err(err) => {
+                tracing::error!(
+                    "Failed to read metadata of '{}' in base store '{}'. {:#}",
+                    url,
+                    self.base.display(),
+                    err
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Returns first layer that has asset imported from the source to the target.
+/// Returns metadata of the source in that layer with the layer.
+pub(crate) fn find_in_layers<'a>(
+    layers: &'a [Layer],
+    source: &Url,
+    local_base: &Url,
+    target: &str,
+    profile: Option<&str>,
+) -> Option<(SourceMeta, &'a Layer)> {
+    layers.iter().find_map(|layer| {
+        let meta = layer.source_meta(source, local_base)?;
+        meta.get_asset(target, profile)?;
+        Some((meta, layer))
+    })
+}
+
+fn map_url(source: &Url, from: &Url, to: &Url) -> Url {
+    match from.make_relative(source) {
+        Some(relative) if !relative.starts_with("../") => {
+            to.join(&relative).unwrap_or_else(|_| source.clone())
+        }
+        _ => source.clone(),
+    }
+}
@@ -1,11 +1,29 @@
+mod artifact;
 mod content_address;
 mod gen;
+mod hooks;
 mod importer;
+mod layer;
 mod meta;
+mod outcome;
+mod scan;
+mod schema;
 mod scheme;
 mod sha256;
 mod sources;
 mod store;
 mod temp;
 
-pub use self::store::{OpenStoreError, SaveStoreError, Store, StoreError, StoreInfo};
+#[cfg(feature = "test-util")]
+#[doc(hidden)]
+pub mod test_util;
+
+pub use self::{
+    artifact::{ArtifactError, ArtifactInfo, ArtifactReader},
+    hooks::{ImportRequest, ImportResultInfo},
+    importer::{ImporterInfo, InvalidPipeline, StageSpec},
+    outcome::{DependencyOutcome, FetchOutcome, FindOutcome, StoreOutcome},
+    scan::ScanStats,
+    schema::stamp_schema,
+    store::{OpenStoreError, ProfileInfo, SaveStoreError, Store, StoreError, StoreInfo},
+};
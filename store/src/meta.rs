@@ -1,20 +1,31 @@
 use std::{
+    borrow::Cow,
     path::{Path, PathBuf},
     time::SystemTime,
 };
 
 use argosy_id::AssetId;
+use argosy_import::{DescriptorFormat, Importer};
 use hashbrown::HashMap;
 use url::Url;
 
 use crate::{
-    content_address::{move_file_with_content_address, with_path_candidates, PREFIX_STARTING_LEN},
+    content_address::{
+        move_file_with_content_address, with_path_candidates, PathCandidatesExhausted,
+        PREFIX_STARTING_LEN,
+    },
     scheme::Scheme,
     sha256::Sha256Hash,
 };
 
-const EXTENSION: &'static str = "argosy";
-const DOT_EXTENSION: &'static str = ".argosy";
+const EXTENSION: &str = "argosy";
+const DOT_EXTENSION: &str = ".argosy";
+
+/// Maximum length of file name in bytes on common file systems.
+const MAX_FILE_NAME_LEN: usize = 255;
+
+/// Separates target from profile name in keys of source metadata.
+const PROFILE_SEPARATOR: char = '@';
 
 /// Metadata for single asset.
 ///
@@ -27,10 +38,19 @@ pub struct AssetMeta {
     /// Imported asset file hash.
     sha256: Sha256Hash,
 
+    /// Imported asset file length in bytes.
+    /// Missing in metadata written by older versions.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    len: Option<u64>,
+
     /// Asset format if specified.
     #[serde(skip_serializing_if = "Option::is_none", default)]
     format: Option<String>,
 
+    /// Format of the artifact if importer marked it as serialized descriptor.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    descriptor: Option<DescriptorFormat>,
+
     /// Minimal length of the hash prefix required to avoid collisions between files with same hash prefixes.
     #[serde(skip_serializing_if = "prefix_is_default", default = "default_prefix")]
     path_len: u64,
@@ -42,6 +62,29 @@ pub struct AssetMeta {
     // Maps source URL to last modified time.
     #[serde(skip_serializing_if = "HashMap::is_empty", default)]
     sources: HashMap<String, SystemTime>,
+
+    // Importers that produced the artifact, in order they were run.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    stages: Vec<StageMeta>,
+
+    // Hash of the content of in-memory source.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    source_hash: Option<Sha256Hash>,
+}
+
+/// Stage of the import that produced an asset.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StageMeta {
+    /// Name of the importer.
+    pub importer: String,
+
+    /// Version of the importer.
+    pub version: u32,
+
+    /// Hash of the intermediate output.
+    /// `None` for the last stage.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub output: Option<Sha256Hash>,
 }
 
 fn prefix_is_default(prefix: &u64) -> bool {
@@ -122,6 +165,12 @@ pub enum MetaError {
         error: std::io::Error,
         path: PathBuf,
     },
+
+    #[error("Source file '{path}' has extension of meta files '{DOT_EXTENSION}'")]
+    SourceIsMeta { path: PathBuf },
+
+    #[error(transparent)]
+    PathCandidatesExhausted(#[from] PathCandidatesExhausted),
 }
 
 impl AssetMeta {
@@ -139,39 +188,90 @@ impl AssetMeta {
     ///
     /// If artifact with the same hash already exists in the `artifacts` directory,
     /// it will be shared between assets.
+    ///
+    /// `descriptor` is set when importer marked output as serialized descriptor.
     pub fn new(
         id: AssetId,
         format: Option<String>,
+        descriptor: Option<DescriptorFormat>,
         sources: Vec<(String, SystemTime)>,
         dependencies: Vec<AssetId>,
         output: &Path,
         artifacts: &Path,
     ) -> Result<Self, MetaError> {
-        let sha256 = Sha256Hash::file_hash(output).map_err(|error| MetaError::HashError {
-            error,
-            path: output.to_owned(),
-        })?;
-
-        let hex = format!("{:x}", sha256);
-
-        let (_, path_len) =
-            move_file_with_content_address(&hex, output, artifacts).map_err(|error| {
-                MetaError::SaveArtifactError {
-                    path: output.to_owned(),
-                    error,
-                }
-            })?;
+        let (sha256, len, path_len) = save_artifact(output, artifacts)?;
 
         Ok(AssetMeta {
             id,
             format,
+            descriptor,
             sha256,
+            len: Some(len),
             path_len,
             sources: sources.into_iter().collect(),
             dependencies,
+            stages: Vec::new(),
+            source_hash: None,
         })
     }
 
+    /// Returns copy of this metadata with artifact replaced by `output`.
+    /// Sources, dependencies and stages are kept.
+    pub fn with_artifact(&self, output: &Path, artifacts: &Path) -> Result<Self, MetaError> {
+        let (sha256, len, path_len) = save_artifact(output, artifacts)?;
+
+        Ok(AssetMeta {
+            id: self.id,
+            format: self.format.clone(),
+            descriptor: self.descriptor,
+            sha256,
+            len: Some(len),
+            path_len,
+            sources: self.sources.clone(),
+            dependencies: self.dependencies.clone(),
+            stages: self.stages.clone(),
+            source_hash: self.source_hash,
+        })
+    }
+
+    /// Returns this metadata with importer stages that produced the artifact.
+    pub fn with_stages(mut self, stages: Vec<StageMeta>) -> Self {
+        self.stages = stages;
+        self
+    }
+
+    /// Returns this metadata with hash of the in-memory source content.
+    pub fn with_source_hash(mut self, hash: Option<Sha256Hash>) -> Self {
+        self.source_hash = hash;
+        self
+    }
+
+    /// Returns hash of the content of in-memory source
+    /// if asset was imported from one.
+    pub fn source_hash(&self) -> Option<Sha256Hash> {
+        self.source_hash
+    }
+
+    /// Returns importer stages that produced the artifact.
+    pub fn stages(&self) -> &[StageMeta] {
+        &self.stages
+    }
+
+    /// Checks if importers differ from ones that produced the artifact
+    /// or any of them has changed version.
+    ///
+    /// Always returns `false` if stages were not recorded.
+    pub fn importers_changed(&self, chain: &[&dyn Importer]) -> bool {
+        if self.stages.is_empty() {
+            return false;
+        }
+
+        self.stages.len() != chain.len()
+            || self.stages.iter().zip(chain).any(|(stage, importer)| {
+                stage.importer != importer.name() || stage.version != importer.version()
+            })
+    }
+
     pub fn id(&self) -> AssetId {
         self.id
     }
@@ -180,6 +280,28 @@ impl AssetMeta {
         self.format.as_deref()
     }
 
+    /// Returns SHA-256 hash of the artifact.
+    pub fn sha256(&self) -> [u8; 32] {
+        *self.sha256
+    }
+
+    /// Returns length of the artifact in bytes.
+    /// Returns `None` if metadata was written without it.
+    pub fn len(&self) -> Option<u64> {
+        self.len
+    }
+
+    /// Returns ids of assets this asset depends on.
+    pub fn dependencies(&self) -> &[AssetId] {
+        &self.dependencies
+    }
+
+    /// Returns format of the artifact if it is serialized descriptor.
+    /// Returns `None` for opaque artifacts.
+    pub fn descriptor(&self) -> Option<DescriptorFormat> {
+        self.descriptor
+    }
+
     pub fn needs_reimport(&self, base: &Url) -> bool {
         for (url, last_modified) in &self.sources {
             let url = match base.join(url) {
@@ -226,7 +348,8 @@ impl AssetMeta {
                         return true;
                     }
                 }
-                Ok(Scheme::Data) => continue,
+                // Content of in-memory sources is compared when they are stored.
+                Ok(Scheme::Data | Scheme::Mem) => continue,
                 Err(_) => tracing::error!("Unsupported scheme: '{}'", url.scheme()),
             }
         }
@@ -259,6 +382,52 @@ impl AssetMeta {
 /// This metadata is stored in sibling file with `.argosy` extension.
 /// Or in 'external' directory if source is not in the base directory or
 /// one of its subdirectories. Or if source is not a file.
+/// Moves artifact into artifacts directory.
+/// Returns its hash, length and length of the path prefix.
+fn save_artifact(output: &Path, artifacts: &Path) -> Result<(Sha256Hash, u64, u64), MetaError> {
+    let sha256 = Sha256Hash::file_hash(output).map_err(|error| MetaError::HashError {
+        error,
+        path: output.to_owned(),
+    })?;
+
+    let len = std::fs::metadata(output)
+        .map_err(|error| MetaError::HashError {
+            error,
+            path: output.to_owned(),
+        })?
+        .len();
+
+    let hex = format!("{:x}", sha256);
+
+    let (_, path_len) =
+        move_file_with_content_address(&hex, output, artifacts).map_err(|error| {
+            MetaError::SaveArtifactError {
+                path: output.to_owned(),
+                error,
+            }
+        })?;
+
+    Ok((sha256, len, path_len))
+}
+
+/// Returns key of the asset imported to `target` within `profile`.
+fn asset_key<'a>(target: &'a str, profile: Option<&str>) -> Cow<'a, str> {
+    match profile {
+        None => Cow::Borrowed(target),
+        Some(profile) => Cow::Owned(format!("{target}{PROFILE_SEPARATOR}{profile}")),
+    }
+}
+
+/// Splits asset key into target and profile.
+fn split_asset_key(key: &str) -> (&str, Option<&str>) {
+    match key.split_once(PROFILE_SEPARATOR) {
+        None => (key, None),
+        Some((target, profile)) => (target, Some(profile)),
+    }
+}
+
+/// Assets imported from the source, keyed by target.
+/// Assets imported within a profile are keyed by `target@profile`.
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct SourceMeta {
     url: Url,
@@ -278,12 +447,54 @@ impl SourceMeta {
         }
     }
 
+    /// Finds and returns existing meta for the source URL.
+    /// Unlike [`SourceMeta::new`] never creates files or directories
+    /// and does not require source file to exist.
+    pub fn find_existing(
+        source: &Url,
+        base: &Path,
+        external: &Path,
+    ) -> Result<Option<SourceMeta>, MetaError> {
+        if source.scheme() == "file" {
+            if let Ok(path) = source.to_file_path() {
+                if path.starts_with(base) {
+                    if let Some(meta_path) = local_meta_path(&path)? {
+                        if !meta_path.is_file() {
+                            return Ok(None);
+                        }
+                        return SourceMeta::open_local(&meta_path).map(Some);
+                    }
+                }
+            }
+        }
+
+        if !external.is_dir() {
+            return Ok(None);
+        }
+
+        let hash = Sha256Hash::hash(source.as_str());
+        let hex = format!("{:x}", hash);
+
+        with_path_candidates(&hex, external, |path, _| {
+            match path.metadata() {
+                Err(_) => return Ok(Some(None)),
+                Ok(md) if !md.is_file() => return Ok(None),
+                Ok(_) => {}
+            }
+            let meta = SourceMeta::open_external(&path)?;
+            match meta.url == *source {
+                true => Ok(Some(Some(meta))),
+                false => Ok(None),
+            }
+        })
+    }
+
     pub fn url(&self) -> &Url {
         &self.url
     }
 
     pub fn is_local_meta_path(meta_path: &Path) -> bool {
-        meta_path.extension().map_or(false, |e| e == EXTENSION)
+        meta_path.extension().is_some_and(|e| e == EXTENSION)
     }
 
     pub fn new_local(meta_path: &Path) -> Result<SourceMeta, MetaError> {
@@ -332,14 +543,15 @@ impl SourceMeta {
                 path: meta_path.to_owned(),
             }),
             Ok(data) => {
-                let assets =
+                // External meta files keep URL of the source with its assets.
+                let meta: SourceMeta =
                     toml::from_str(&data).map_err(|error| MetaError::DeserializeError {
                         error,
                         path: meta_path.to_owned(),
                     })?;
                 Ok(SourceMeta {
                     url: source.clone(),
-                    assets,
+                    assets: meta.assets,
                 })
             }
         }
@@ -361,22 +573,31 @@ impl SourceMeta {
         }
     }
 
-    pub fn get_asset(&self, target: &str) -> Option<&AssetMeta> {
-        self.assets.get(target)
+    pub fn get_asset(&self, target: &str, profile: Option<&str>) -> Option<&AssetMeta> {
+        self.assets.get(&*asset_key(target, profile))
     }
 
-    pub fn assets(&self) -> impl Iterator<Item = (&str, &AssetMeta)> + '_ {
-        self.assets.iter().map(|(target, meta)| (&**target, meta))
+    /// Returns assets imported within `profile` with their targets.
+    pub fn assets<'a>(
+        &'a self,
+        profile: Option<&'a str>,
+    ) -> impl Iterator<Item = (&'a str, &'a AssetMeta)> + 'a {
+        self.assets.iter().filter_map(move |(key, meta)| {
+            let (target, key_profile) = split_asset_key(key);
+            (key_profile == profile).then_some((target, meta))
+        })
     }
 
     pub fn add_asset(
         &mut self,
-        target: String,
+        target: &str,
+        profile: Option<&str>,
         asset: AssetMeta,
         base: &Path,
         external: &Path,
     ) -> Result<(), MetaError> {
-        self.assets.insert(target, asset);
+        self.assets
+            .insert(asset_key(target, profile).into_owned(), asset);
 
         let (meta_path, is_external) = get_meta_path(&self.url, base, external)?;
         if is_external {
@@ -413,26 +634,50 @@ impl SourceMeta {
     }
 }
 
+/// Returns path of the local meta file for the source file.
+///
+/// Returns `None` if name of the meta file would exceed [`MAX_FILE_NAME_LEN`].
+/// Such sources have external meta file instead, which name is a hash of the URL.
+/// Sources with meta file extension are rejected,
+/// otherwise they would be mistaken for meta files when store is scanned.
+fn local_meta_path(path: &Path) -> Result<Option<PathBuf>, MetaError> {
+    if SourceMeta::is_local_meta_path(path) {
+        return Err(MetaError::SourceIsMeta {
+            path: path.to_owned(),
+        });
+    }
+
+    let mut filename = path.file_name().unwrap_or("".as_ref()).to_owned();
+    filename.push(DOT_EXTENSION);
+
+    if filename.len() > MAX_FILE_NAME_LEN {
+        return Ok(None);
+    }
+    Ok(Some(path.with_file_name(filename)))
+}
+
 /// Finds and returns meta for the source URL.
 /// Creates new file if needed.
 fn get_meta_path(source: &Url, base: &Path, external: &Path) -> Result<(PathBuf, bool), MetaError> {
     if source.scheme() == "file" {
-        match source.to_file_path() {
-            Ok(path) => {
-                let path = dunce::canonicalize(&path)
-                    .map_err(|err| MetaError::CanonError { error: err, path })?;
-
-                if path.starts_with(base) {
-                    // Files inside `base` directory has meta attached to them as sibling file with `.argosy` extension added.
-
-                    let mut filename = path.file_name().unwrap_or("".as_ref()).to_owned();
-                    filename.push(DOT_EXTENSION);
-
-                    let path = path.with_file_name(filename);
-                    return Ok((path, false));
+        if let Ok(path) = source.to_file_path() {
+            let path = dunce::canonicalize(&path)
+                .map_err(|err| MetaError::CanonError { error: err, path })?;
+
+            if path.starts_with(base) {
+                // Files inside `base` directory has meta attached to them as sibling file with `.argosy` extension added.
+                if let Some(meta_path) = local_meta_path(&path)? {
+                    if meta_path.is_dir() {
+                        return Err(MetaError::PathOccupiedByDirectory { path: meta_path });
+                    }
+                    return Ok((meta_path, false));
                 }
+
+                tracing::debug!(
+                    "Name of the meta file for '{}' is too long, using external meta file",
+                    path.display()
+                );
             }
-            Err(()) => {}
         }
     }
 
@@ -444,7 +689,7 @@ fn get_meta_path(source: &Url, base: &Path, external: &Path) -> Result<(PathBuf,
     let hash = Sha256Hash::hash(source.as_str());
     let hex = format!("{:x}", hash);
 
-    let (path, _) = with_path_candidates(&hex, external, |path, _| {
+    let (path, _) = with_path_candidates::<_, MetaError>(&hex, external, |path, _| {
         match path.metadata() {
             Err(_) => {
                 // Not exists. Let's try to occupy.
@@ -0,0 +1,124 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use argosy_id::AssetId;
+use url::Url;
+
+/// Result of [`Store::store_detailed`] and [`Store::store_url_detailed`].
+///
+/// Serializable to provide machine-readable output.
+///
+/// # Example
+///
+/// ```
+/// # use argosy_store::{Store, StoreInfo};
+/// # use argosy_store::test_util::CopyImporter;
+/// # let store_dir = argosy_store::test_util::TempStore::new("outcome");
+/// # let base = store_dir.path();
+/// std::fs::write(base.join("hello.txt"), "Hello").unwrap();
+///
+/// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+/// store.register_importer(Box::new(CopyImporter));
+///
+/// let outcome = futures::executor::block_on(store.store_detailed("hello.txt", None, "text")).unwrap();
+/// let json = serde_json::to_value(&outcome).unwrap();
+///
+/// assert_eq!(json["id"], outcome.id.to_padded_hex());
+/// assert_eq!(json["artifact_path"], outcome.artifact_path.to_str().unwrap());
+/// assert_eq!(json["reimported"], true);
+/// assert_eq!(json["importer"], "Copy");
+/// assert!(json["elapsed"]["secs"].is_u64());
+/// assert!(json["modified"]["secs_since_epoch"].is_u64());
+/// assert_eq!(json["dependencies"], serde_json::json!([]));
+///
+/// // Second time asset is up-to-date.
+/// let outcome = futures::executor::block_on(store.store_detailed("hello.txt", None, "text")).unwrap();
+/// let json = serde_json::to_value(&outcome).unwrap();
+/// assert_eq!(json["reimported"], false);
+/// assert_eq!(json["importer"], serde_json::Value::Null);
+/// ```
+///
+/// [`Store::store_detailed`]: crate::Store::store_detailed
+/// [`Store::store_url_detailed`]: crate::Store::store_url_detailed
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct StoreOutcome {
+    /// Id of the stored asset.
+    pub id: AssetId,
+
+    /// Path to the artifact with imported asset data.
+    pub artifact_path: PathBuf,
+
+    /// Latest modification time of the asset sources.
+    pub modified: SystemTime,
+
+    /// Whether asset was imported by this operation.
+    /// `false` if up-to-date artifact already existed.
+    pub reimported: bool,
+
+    /// Name of the importer used.
+    /// `None` if asset was not reimported.
+    pub importer: Option<String>,
+
+    /// Time spent on the operation.
+    pub elapsed: Duration,
+
+    /// Dependencies imported transitively by this operation.
+    pub dependencies: Vec<DependencyOutcome>,
+}
+
+/// Dependency imported while storing an asset.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct DependencyOutcome {
+    /// Id of the dependency.
+    pub id: AssetId,
+
+    /// Source URL of the dependency.
+    pub source: Url,
+
+    /// Target format of the dependency.
+    pub target: String,
+
+    /// Whether dependency was imported by this operation.
+    pub reimported: bool,
+
+    /// Name of the importer used.
+    /// `None` if dependency was not reimported.
+    pub importer: Option<String>,
+}
+
+/// Result of [`Store::find_asset_detailed`].
+///
+/// [`Store::find_asset_detailed`]: crate::Store::find_asset_detailed
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct FindOutcome {
+    /// Id of the found asset.
+    /// `None` if asset was not found and failed to store.
+    pub id: Option<AssetId>,
+
+    /// Outcome of storing the asset.
+    /// `None` if asset was already stored.
+    pub stored: Option<StoreOutcome>,
+
+    /// Reason why asset was not stored on lookup.
+    pub error: Option<String>,
+}
+
+/// Result of [`Store::fetch_detailed`].
+///
+/// [`Store::fetch_detailed`]: crate::Store::fetch_detailed
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct FetchOutcome {
+    /// Source URL of the asset.
+    pub source: Url,
+
+    /// Source format of the asset.
+    pub format: Option<String>,
+
+    /// Target format of the asset.
+    pub target: String,
+
+    /// Outcome of ensuring asset artifact is up-to-date.
+    pub store: StoreOutcome,
+}
@@ -0,0 +1,364 @@
+use std::{
+    collections::VecDeque,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use argosy_id::AssetId;
+use hashbrown::HashMap;
+use url::Url;
+
+use crate::meta::SourceMeta;
+
+/// Name of the scan index file in the artifacts directory.
+pub(crate) const SCAN_INDEX_NAME: &str = "scan-index.json";
+
+/// Version of the scan index format.
+/// Index of other version is ignored.
+const SCAN_INDEX_VERSION: u32 = 1;
+
+/// Statistics of the metadata scan.
+///
+/// See [`Store::rescan`].
+///
+/// [`Store::rescan`]: crate::Store::rescan
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ScanStats {
+    /// Number of directories which entries were listed.
+    pub dirs_read: usize,
+
+    /// Number of meta files that were parsed.
+    pub metas_read: usize,
+
+    /// Number of meta files reused from the scan index.
+    pub metas_reused: usize,
+}
+
+/// Asset listed in the meta file.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ScannedAsset {
+    pub id: AssetId,
+    pub source: Url,
+    pub format: Option<String>,
+    pub target: String,
+    pub dependencies: Vec<AssetId>,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct IndexedDir {
+    modified: SystemTime,
+    dirs: Vec<PathBuf>,
+    metas: Vec<PathBuf>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct IndexedMeta {
+    modified: SystemTime,
+    assets: Vec<ScannedAsset>,
+}
+
+/// Scan results of previous run.
+///
+/// Listing of a directory is reused if its modification time is unchanged.
+/// Assets of a meta file are reused if its modification time is unchanged.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct ScanIndex {
+    version: u32,
+    profile: Option<String>,
+
+    /// Directories walked for local meta files.
+    local: HashMap<PathBuf, IndexedDir>,
+
+    /// Directories with external meta files.
+    external: HashMap<PathBuf, IndexedDir>,
+
+    metas: HashMap<PathBuf, IndexedMeta>,
+}
+
+/// Directories of one store scanned for meta files.
+pub(crate) struct ScanRoot {
+    pub base: PathBuf,
+    pub external: PathBuf,
+
+    /// Directories under base that contain no local meta files.
+    pub skip: Vec<PathBuf>,
+}
+
+/// Scan of meta files of the store and its base stores.
+pub(crate) struct ScanJob {
+    pub index: PathBuf,
+    pub profile: Option<String>,
+    pub roots: Vec<ScanRoot>,
+
+    /// Ignores existing index.
+    pub force: bool,
+}
+
+impl ScanJob {
+    /// Scans meta files of all roots and writes updated index.
+    /// Returns assets found for each root.
+    pub fn run(&self) -> (Vec<Vec<ScannedAsset>>, ScanStats) {
+        let old = match self.force {
+            true => ScanIndex::default(),
+            false => read_index(&self.index, self.profile.as_deref()),
+        };
+
+        let mut scanner = Scanner {
+            old,
+            new: ScanIndex {
+                version: SCAN_INDEX_VERSION,
+                profile: self.profile.clone(),
+                ..ScanIndex::default()
+            },
+            profile: self.profile.as_deref(),
+            stats: ScanStats::default(),
+        };
+
+        let found = self
+            .roots
+            .iter()
+            .map(|root| {
+                let mut assets = Vec::new();
+                scanner.scan_local(root, &mut assets);
+                scanner.scan_external(&root.external, &mut assets);
+                assets
+            })
+            .collect();
+
+        write_index(&self.index, &scanner.new);
+        (found, scanner.stats)
+    }
+}
+
+fn read_index(path: &Path, profile: Option<&str>) -> ScanIndex {
+    let data = match std::fs::read(path) {
+        Err(err) if err.kind() == ErrorKind::NotFound => return ScanIndex::default(),
+        Err(err) => {
+            tracing::warn!("Failed to read scan index '{}'. {:#}", path.display(), err);
+            return ScanIndex::default();
+        }
+        Ok(data) => data,
+    };
+
+    match serde_json::from_slice::<ScanIndex>(&data) {
+        Err(err) => {
+            tracing::warn!("Failed to parse scan index '{}'. {:#}", path.display(), err);
+            ScanIndex::default()
+        }
+        Ok(index) if index.version != SCAN_INDEX_VERSION || index.profile.as_deref() != profile => {
+            ScanIndex::default()
+        }
+        Ok(index) => index,
+    }
+}
+
+fn write_index(path: &Path, index: &ScanIndex) {
+    let data = match serde_json::to_vec(index) {
+        Err(err) => {
+            tracing::warn!("Failed to serialize scan index. {:#}", err);
+            return;
+        }
+        Ok(data) => data,
+    };
+
+    let Some(dir) = path.parent() else {
+        return;
+    };
+
+    let tmp = path.with_extension("tmp");
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|()| std::fs::write(&tmp, data))
+        .and_then(|()| std::fs::rename(&tmp, path));
+
+    if let Err(err) = result {
+        tracing::warn!("Failed to write scan index '{}'. {:#}", path.display(), err);
+    }
+}
+
+struct Scanner<'a> {
+    old: ScanIndex,
+    new: ScanIndex,
+    profile: Option<&'a str>,
+    stats: ScanStats,
+}
+
+impl Scanner<'_> {
+    fn scan_local(&mut self, root: &ScanRoot, assets: &mut Vec<ScannedAsset>) {
+        debug_assert!(root.base.is_absolute());
+
+        if !root.base.exists() {
+            tracing::info!("Local artifacts directory does not exists");
+            return;
+        }
+
+        let mut queue = VecDeque::new();
+        queue.push_back(root.base.clone());
+
+        while let Some(dir_path) = queue.pop_front() {
+            let Some(dir) = self.list_dir(&dir_path, true) else {
+                continue;
+            };
+
+            queue.extend(
+                dir.dirs
+                    .into_iter()
+                    .filter(|path| !root.skip.contains(path)),
+            );
+
+            for path in &dir.metas {
+                self.meta(path, true, assets);
+            }
+        }
+    }
+
+    fn scan_external(&mut self, external: &Path, assets: &mut Vec<ScannedAsset>) {
+        let Some(dir) = self.list_dir(external, false) else {
+            return;
+        };
+
+        for path in &dir.metas {
+            self.meta(path, false, assets);
+        }
+    }
+
+    /// Returns subdirectories and meta files in the directory.
+    /// Reuses listing from the index if directory is not modified.
+    fn list_dir(&mut self, dir_path: &Path, local: bool) -> Option<IndexedDir> {
+        let modified = match std::fs::metadata(dir_path).and_then(|m| m.modified()) {
+            Err(err) if err.kind() == ErrorKind::NotFound && !local => {
+                tracing::info!("External directory does not exists");
+                return None;
+            }
+            Err(err) => {
+                tracing::error!(
+                    "Failed to scan directory '{}'. {:#}",
+                    dir_path.display(),
+                    err
+                );
+                return None;
+            }
+            Ok(modified) => modified,
+        };
+
+        let (old, new) = match local {
+            true => (&mut self.old.local, &mut self.new.local),
+            false => (&mut self.old.external, &mut self.new.external),
+        };
+
+        if let Some(dir) = old.remove(dir_path) {
+            if dir.modified == modified {
+                new.insert(dir_path.to_owned(), dir.clone());
+                return Some(dir);
+            }
+        }
+
+        self.stats.dirs_read += 1;
+
+        let entries = match std::fs::read_dir(dir_path) {
+            Err(err) => {
+                tracing::error!(
+                    "Failed to scan directory '{}'. {:#}",
+                    dir_path.display(),
+                    err
+                );
+                return None;
+            }
+            Ok(entries) => entries,
+        };
+
+        let mut dir = IndexedDir {
+            modified,
+            dirs: Vec::new(),
+            metas: Vec::new(),
+        };
+
+        for e in entries {
+            let e = match e {
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to read entry in directory '{}'. {:#}",
+                        dir_path.display(),
+                        err,
+                    );
+                    continue;
+                }
+                Ok(e) => e,
+            };
+            let path = dir_path.join(e.file_name());
+            let ft = match e.file_type() {
+                Err(err) => {
+                    tracing::error!("Failed to check '{}'. {:#}", path.display(), err);
+                    continue;
+                }
+                Ok(ft) => ft,
+            };
+            if ft.is_dir() && local {
+                dir.dirs.push(path);
+            } else if ft.is_file() && SourceMeta::is_local_meta_path(&path) == local {
+                dir.metas.push(path);
+            }
+        }
+
+        new.insert(dir_path.to_owned(), dir.clone());
+        Some(dir)
+    }
+
+    /// Adds assets listed in the meta file.
+    /// Reuses assets from the index if meta file is not modified.
+    fn meta(&mut self, path: &Path, local: bool, assets: &mut Vec<ScannedAsset>) {
+        let modified = match std::fs::metadata(path).and_then(|m| m.modified()) {
+            Err(err) => {
+                tracing::error!("Failed to scan meta file '{}'. {:#}", path.display(), err);
+                return;
+            }
+            Ok(modified) => modified,
+        };
+
+        if let Some(meta) = self.old.metas.remove(path) {
+            if meta.modified == modified {
+                self.stats.metas_reused += 1;
+                assets.extend_from_slice(&meta.assets);
+                self.new.metas.insert(path.to_owned(), meta);
+                return;
+            }
+        }
+
+        self.stats.metas_read += 1;
+
+        let meta = match local {
+            true => SourceMeta::open_local(path),
+            false => SourceMeta::open_external(path),
+        };
+
+        let meta = match meta {
+            Err(err) => {
+                tracing::error!("Failed to scan meta file '{}'. {:#}", path.display(), err);
+                return;
+            }
+            Ok(meta) => meta,
+        };
+
+        let source = meta.url();
+        let found: Vec<_> = meta
+            .assets(self.profile)
+            .map(|(target, asset)| ScannedAsset {
+                id: asset.id(),
+                source: source.clone(),
+                format: asset.format().map(ToOwned::to_owned),
+                target: target.to_owned(),
+                dependencies: asset.dependencies().to_vec(),
+            })
+            .collect();
+
+        assets.extend_from_slice(&found);
+        self.new.metas.insert(
+            path.to_owned(),
+            IndexedMeta {
+                modified,
+                assets: found,
+            },
+        );
+    }
+}
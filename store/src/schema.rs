@@ -0,0 +1,27 @@
+use argosy::CheckedAsset;
+
+/// Stamps schema hash of asset type `A` into JSON asset info.
+///
+/// Importers that write info for assets with `#[asset(checked)]` attribute
+/// should stamp it, so loader can verify that artifact matches the asset type.
+/// Does nothing if `info` is not a JSON object.
+///
+/// # Example
+///
+/// ```
+/// # use argosy::{Asset, CheckedAsset};
+/// #[derive(Clone, Asset)]
+/// #[asset(checked)]
+/// struct Speed {
+///     value: f32,
+/// }
+///
+/// let mut info = serde_json::json!({ "value": 1.0 });
+/// argosy_store::stamp_schema::<Speed>(&mut info);
+/// assert_eq!(info[Speed::SCHEMA_FIELD], Speed::SCHEMA);
+/// ```
+pub fn stamp_schema<A: CheckedAsset>(info: &mut serde_json::Value) {
+    if let Some(object) = info.as_object_mut() {
+        object.insert(A::SCHEMA_FIELD.to_owned(), A::SCHEMA.into());
+    }
+}
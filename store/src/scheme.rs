@@ -6,6 +6,7 @@ use std::str::FromStr;
 pub(crate) enum Scheme {
     File,
     Data,
+    Mem,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -19,6 +20,7 @@ impl FromStr for Scheme {
         match s {
             "file" => Ok(Scheme::File),
             "data" => Ok(Scheme::Data),
+            "mem" => Ok(Scheme::Mem),
             _ => Err(UnsupportedScheme),
         }
     }
@@ -137,7 +137,7 @@ impl Serialize for Sha256Hash {
 
         if serializer.is_human_readable() {
             let mut hex = [0u8; 64];
-            write!(std::io::Cursor::new(&mut hex[..]), "{:#x}", self).expect("Must fit");
+            write!(std::io::Cursor::new(&mut hex[..]), "{:x}", self).expect("Must fit");
             let hex = std::str::from_utf8(&hex).expect("Must be UTF-8");
             serializer.serialize_str(hex)
         } else {
@@ -29,6 +29,9 @@ pub enum SourcesError {
         path: PathBuf,
     },
 
+    #[error("In-memory source '{url}' is available only while it is stored")]
+    MissingMemory { url: Url },
+
     #[error("Unsupported scheme '{}' in '{url}'", url.scheme())]
     UnsupportedScheme { url: Url },
 }
@@ -52,7 +55,7 @@ pub(crate) fn source_modified(url: &Url, path: &Path) -> Result<SystemTime, Sour
                 }),
             }
         }
-        "data" => Ok(SystemTime::UNIX_EPOCH),
+        "data" | "mem" => Ok(SystemTime::UNIX_EPOCH),
         _ => unreachable!(),
     }
 }
@@ -64,6 +67,12 @@ impl Sources {
         }
     }
 
+    /// Adds in-memory source with content saved at `path`.
+    pub fn insert_memory(&mut self, source: Url, path: PathBuf) {
+        debug_assert_eq!(source.scheme(), "mem");
+        self.fetched.insert(source, path);
+    }
+
     pub fn get(&self, source: &Url) -> Option<(&Path, SystemTime)> {
         let path = self.fetched.get(source)?;
         let modified = source_modified(source, path).ok()?;
@@ -130,6 +139,10 @@ impl Sources {
                     let (_, path) = entry.insert(source.clone(), path);
                     Ok((path, SystemTime::UNIX_EPOCH))
                 }
+                // In-memory sources are inserted before they are stored.
+                "mem" => Err(SourcesError::MissingMemory {
+                    url: source.clone(),
+                }),
                 _ => Err(SourcesError::UnsupportedScheme {
                     url: source.clone(),
                 }),
@@ -1,30 +1,42 @@
 use std::{
-    collections::VecDeque,
     path::{Path, PathBuf},
-    time::SystemTime,
+    time::{Instant, SystemTime},
 };
 
+use argosy::{BatchItem, BatchOutcome, CancelToken};
 use argosy_id::AssetId;
-use argosy_import::{loading::LoadingError, ImportError, Importer};
-use futures::future::BoxFuture;
+use argosy_import::{
+    loading::LoadingError, Dependency, DescriptorFormat, ImportError, Importer, OutputSink,
+};
+use futures::{future::BoxFuture, stream::BoxStream, StreamExt};
 use hashbrown::{HashMap, HashSet};
 use parking_lot::RwLock;
 use url::Url;
 
 use crate::{
+    artifact::{envelope_artifact, hex, ArtifactInfo, ArtifactReader},
     gen::Generator,
-    importer::Importers,
-    meta::{AssetMeta, MetaError, SourceMeta},
+    hooks::{ImportRequest, ImportResultInfo, PostImportHook, PreImportHook},
+    importer::{ImporterInfo, Importers, InvalidPipeline, StageSpec},
+    layer::{find_in_layers, Layer},
+    meta::{AssetMeta, MetaError, SourceMeta, StageMeta},
+    outcome::{DependencyOutcome, FetchOutcome, FindOutcome, StoreOutcome},
+    scan::{ScanJob, ScanRoot, ScanStats, ScannedAsset, SCAN_INDEX_NAME},
+    scheme::Scheme,
+    sha256::Sha256Hash,
     sources::{Sources, SourcesError},
-    temp::make_temporary,
+    temp::{make_temporary, Temporaries},
 };
 
-pub const ARGOSY_META_NAME: &'static str = "argosy.toml";
+pub const ARGOSY_META_NAME: &str = "argosy.toml";
+
+const DEFAULT_AUX: &str = "argosy";
+const DEFAULT_ARTIFACTS: &str = "artifacts";
+const DEFAULT_EXTERNAL: &str = "external";
+const DEFAULT_MAX_ATTEMPTS: u32 = 1024;
 
-const DEFAULT_AUX: &'static str = "argosy";
-const DEFAULT_ARTIFACTS: &'static str = "artifacts";
-const DEFAULT_EXTERNAL: &'static str = "external";
-const MAX_ITEM_ATTEMPTS: u32 = 1024;
+/// Name of the file in artifacts directory with packed small artifacts.
+const SMALL_PACK_NAME: &str = "small-assets.pack";
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct StoreInfo {
@@ -36,6 +48,65 @@ pub struct StoreInfo {
     pub temp: Option<PathBuf>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub importers: Vec<PathBuf>,
+
+    /// Maximum number of import attempts for single asset.
+    /// Importer makes another attempt each time it requires more sources or dependencies.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_attempts: Option<u32>,
+
+    /// Format in which importers write descriptor artifacts.
+    /// JSON is used if not specified.
+    ///
+    /// Profiles may use separate store metadata files
+    /// to import JSON descriptors for development and bincode for shipping.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub descriptor_format: Option<DescriptorFormat>,
+
+    /// Whether descriptor artifacts are wrapped into [`ArtifactEnvelope`],
+    /// so that loader reports stale artifacts clearly.
+    /// Disabled if not specified.
+    ///
+    /// [`ArtifactEnvelope`]: argosy::ArtifactEnvelope
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub artifact_envelopes: Option<bool>,
+
+    /// Named profiles that import the same sources with different settings,
+    /// e.g. for different platforms.
+    ///
+    /// See [`Store::open_with_profile`].
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub profiles: HashMap<String, ProfileInfo>,
+
+    /// Directories of read-only base stores, in order of precedence.
+    ///
+    /// Assets not imported into this store are looked up in base stores
+    /// and their artifacts are used in place, as long as they are up to date
+    /// with sources of this store.
+    /// New imports are always written into this store.
+    /// Base stores of base stores are not consulted.
+    ///
+    /// See [`Store::promote`].
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub base_stores: Vec<PathBuf>,
+}
+
+/// Settings of the store profile.
+///
+/// Assets imported within a profile have their own ids and artifacts.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ProfileInfo {
+    /// Artifacts directory of the profile.
+    /// Profile name under the store artifacts directory if not specified.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub artifacts: Option<PathBuf>,
+
+    /// Configuration passed to importers by importer name.
+    ///
+    /// See [`OutputSink::config`].
+    ///
+    /// [`OutputSink::config`]: argosy_import::OutputSink::config
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub importers: HashMap<String, toml::Table>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -64,6 +135,15 @@ pub enum OpenStoreError {
         error: toml::de::Error,
         path: PathBuf,
     },
+
+    #[error("Profile '{profile}' is not defined in store metadata")]
+    UnknownProfile { profile: String },
+
+    #[error("Failed to serialize configuration of importer '{importer}'. {error}")]
+    ImporterConfigError {
+        error: toml::ser::Error,
+        importer: String,
+    },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -90,14 +170,100 @@ pub enum StoreError {
         url: String,
     },
 
+    /// Failed to find or update metadata of the source.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreError, StoreInfo};
+    /// # use sha2::Digest;
+    /// # use argosy_store::test_util::CopyImporter;
+    /// # let store_dir = argosy_store::test_util::TempStore::new("meta-paths");
+    /// # let base = store_dir.path();
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(CopyImporter));
+    /// let store_file = |source: &str| futures::executor::block_on(store.store(source, None, "text"));
+    ///
+    /// // Source with extension of meta files would be mistaken for one.
+    /// std::fs::write(base.join("notes.argosy"), "Hello").unwrap();
+    /// let err = store_file("notes.argosy").unwrap_err();
+    /// assert!(matches!(err, StoreError::MetaError(_)));
+    /// assert!(err.to_string().contains("has extension of meta files"));
+    ///
+    /// // Name of the sibling meta file would exceed file name length limit,
+    /// // so metadata is kept in external directory.
+    /// let long = format!("{}.txt", "a".repeat(250));
+    /// std::fs::write(base.join(&long), "Hello").unwrap();
+    /// let (id, ..) = store_file(&long).unwrap();
+    /// assert!(!base.join(format!("{long}.argosy")).exists());
+    /// assert_eq!(store_file(&long).unwrap().0, id);
+    ///
+    /// // All candidate names of external meta file are occupied.
+    /// let outside = base.with_extension("txt");
+    /// std::fs::write(&outside, "Hello").unwrap();
+    /// let url = url::Url::from_file_path(&outside).unwrap();
+    /// let hex: String = sha2::Sha256::digest(url.as_str())
+    ///     .iter()
+    ///     .map(|byte| format!("{byte:02x}"))
+    ///     .collect();
+    /// let external = base.join("argosy").join("external");
+    /// for len in 8..=hex.len() {
+    ///     std::fs::create_dir_all(external.join(&hex[..len])).unwrap();
+    /// }
+    /// for suffix in 0..1024 {
+    ///     std::fs::create_dir_all(external.join(format!("{hex}:{suffix}"))).unwrap();
+    /// }
+    /// let err = futures::executor::block_on(store.store_url(url, None, "text")).unwrap_err();
+    /// assert!(err.to_string().contains("are occupied"));
+    /// # std::fs::remove_file(&outside).unwrap();
+    /// ```
     #[error(transparent)]
     MetaError(MetaError),
 
-    #[error("Failed to find importer '{url}':'{format:?}->{target}'")]
+    /// No importer is registered for the target and format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreError, StoreInfo};
+    /// # use argosy_store::test_util::CopyImporter;
+    /// # let store_dir = argosy_store::test_util::TempStore::new("no-importers");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("hello.txt"), "Hello").unwrap();
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(CopyImporter));
+    ///
+    /// // Typo in target.
+    /// let err = futures::executor::block_on(store.store("hello.txt", None, "test")).unwrap_err();
+    /// match &err {
+    ///     StoreError::NoImporters { suggestion, .. } => {
+    ///         assert_eq!(suggestion.as_deref(), Some("text"));
+    ///     }
+    ///     err => panic!("Unexpected error {err}"),
+    /// }
+    /// assert!(err.to_string().ends_with("Did you mean 'text'?"));
+    ///
+    /// // Typo in format.
+    /// let err = futures::executor::block_on(store.store("hello.txt", Some("txt"), "text")).unwrap_err();
+    /// match &err {
+    ///     StoreError::NoImporters { suggestion, .. } => {
+    ///         assert_eq!(suggestion.as_deref(), Some("text"));
+    ///     }
+    ///     err => panic!("Unexpected error {err}"),
+    /// }
+    /// ```
+    #[error(
+        "Failed to find importer '{url}':'{format:?}->{target}'{}",
+        did_you_mean(suggestion)
+    )]
     NoImporters {
         format: Option<String>,
         target: String,
         url: Url,
+
+        /// Registered target or format close to the requested one.
+        suggestion: Option<String>,
     },
 
     #[error(
@@ -120,11 +286,81 @@ pub enum StoreError {
         reason: String,
     },
 
-    #[error("Too many attempts to import asset '{url}':'{format:?}->{target}'")]
+    #[error(
+        "Import of asset '{url}':'{format:?}->{target}' was rejected by pre-import hook. {reason}"
+    )]
+    ImportRejected {
+        format: Option<String>,
+        target: String,
+        url: Url,
+        reason: String,
+    },
+
+    #[error(
+        "Importer '{importer}' made too many attempts ({attempts}) to import asset '{url}':'{format:?}->{target}'"
+    )]
     TooManyAttempts {
         format: Option<String>,
         target: String,
         url: Url,
+        importer: String,
+        attempts: u32,
+    },
+
+    /// Importer keeps requiring the same sources and dependencies.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreError, StoreInfo};
+    /// # struct NeedsImporter;
+    /// # impl argosy_import::Importer for NeedsImporter {
+    /// #     fn name(&self) -> &str { "Needs" }
+    /// #     fn formats(&self) -> &[&str] { &["text"] }
+    /// #     fn extensions(&self) -> &[&str] { &["txt"] }
+    /// #     fn target(&self) -> &str { "text" }
+    /// #     fn import(
+    /// #         &self,
+    /// #         _: &std::path::Path,
+    /// #         _: &std::path::Path,
+    /// #         sources: &mut dyn argosy_import::Sources,
+    /// #         _: &mut dyn argosy_import::Dependencies,
+    /// #         _: &mut argosy_import::OutputSink,
+    /// #     ) -> Result<(), argosy_import::ImportError> {
+    /// #         match sources.get("missing.txt") {
+    /// #             Some(_) => Ok(()),
+    /// #             None => argosy_import::ensure(vec!["missing.txt".to_owned()], vec![]),
+    /// #         }
+    /// #     }
+    /// # }
+    /// # let store_dir = argosy_store::test_util::TempStore::new("unsatisfied");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("hello.txt"), "Hello").unwrap();
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(NeedsImporter));
+    ///
+    /// let err = futures::executor::block_on(store.store("hello.txt", None, "text")).unwrap_err();
+    /// match err {
+    ///     StoreError::RequirementsNotSatisfied { importer, attempts, sources, .. } => {
+    ///         assert_eq!(importer, "Needs");
+    ///         assert_eq!(attempts, 2);
+    ///         assert_eq!(sources, ["missing.txt"]);
+    ///     }
+    ///     err => panic!("Unexpected error {err}"),
+    /// }
+    /// ```
+    #[error(
+        "Importer '{importer}' failed to import asset '{url}':'{format:?}->{target}' after {attempts} attempts. Sources {sources:?} and dependencies {dependencies:?} could not be satisfied"
+    )]
+    RequirementsNotSatisfied {
+        format: Option<String>,
+        target: String,
+        url: Url,
+        importer: String,
+        attempts: u32,
+        sources: Vec<String>,
+        dependencies: Vec<Dependency>,
     },
 
     #[error("Failed to create directory '{path}' to store import artifacts. {error}")]
@@ -132,6 +368,12 @@ pub enum StoreError {
         error: std::io::Error,
         path: PathBuf,
     },
+
+    #[error("Failed to pack small artifacts into '{path}'. {error}")]
+    FailedToPackArtifacts {
+        error: std::io::Error,
+        path: PathBuf,
+    },
 }
 
 impl Default for StoreInfo {
@@ -184,6 +426,11 @@ impl StoreInfo {
             external,
             temp,
             importers,
+            max_attempts: None,
+            descriptor_format: None,
+            artifact_envelopes: None,
+            profiles: HashMap::new(),
+            base_stores: Vec::new(),
         }
     }
 }
@@ -193,6 +440,7 @@ struct AssetItem {
     source: Url,
     format: Option<String>,
     target: String,
+    dependencies: Vec<AssetId>,
 }
 
 pub struct Store {
@@ -202,9 +450,24 @@ pub struct Store {
     external: PathBuf,
     temp: PathBuf,
     importers: Importers,
+    max_attempts: u32,
+    descriptor_format: DescriptorFormat,
+    artifact_envelopes: bool,
+    profile: Option<String>,
+
+    /// Read-only base stores.
+    layers: Vec<Layer>,
+
+    /// Serialized configuration of importers in the active profile.
+    importer_configs: HashMap<String, String>,
+    pre_import_hooks: Vec<PreImportHook>,
+    post_import_hooks: Vec<PostImportHook>,
 
     artifacts: RwLock<HashMap<AssetId, AssetItem>>,
     scanned: RwLock<bool>,
+
+    /// Serializes scans of meta files.
+    scan_lock: futures::lock::Mutex<()>,
     id_gen: Generator,
 }
 
@@ -217,7 +480,7 @@ impl Store {
             path: path.to_owned(),
         })?;
 
-        let meta_path = find_argosy_info(&path).ok_or_else(|| OpenStoreError::NotFound { path })?;
+        let meta_path = find_argosy_info(&path).ok_or(OpenStoreError::NotFound { path })?;
 
         Store::open(&meta_path)
     }
@@ -238,7 +501,104 @@ impl Store {
         Self::new(&base, meta)
     }
 
+    /// Open store database at specified path with named profile active.
+    ///
+    /// Assets are imported, fetched and checked for reimport within the profile.
+    /// They get ids distinct from ids of the same sources in other profiles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{ProfileInfo, Store, StoreInfo};
+    /// struct Quality;
+    ///
+    /// impl argosy_import::Importer for Quality {
+    ///     fn name(&self) -> &str { "Quality" }
+    ///     fn formats(&self) -> &[&str] { &["text"] }
+    ///     fn extensions(&self) -> &[&str] { &["txt"] }
+    ///     fn target(&self) -> &str { "text" }
+    ///     fn import(
+    ///         &self,
+    ///         source: &std::path::Path,
+    ///         output: &std::path::Path,
+    ///         _: &mut dyn argosy_import::Sources,
+    ///         _: &mut dyn argosy_import::Dependencies,
+    ///         sink: &mut argosy_import::OutputSink,
+    ///     ) -> Result<(), argosy_import::ImportError> {
+    ///         let data = std::fs::read_to_string(source).unwrap();
+    ///         let config = sink.config().unwrap_or_default().trim();
+    ///         std::fs::write(output, format!("{data}|{config}")).unwrap();
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// # let store_dir = argosy_store::test_util::TempStore::new("profiles");
+    /// # let base = store_dir.path();
+    /// let mut info = StoreInfo::new(None, None, Some(&base.join("temp")), &[]);
+    /// for (name, quality) in [("pc", "high"), ("mobile", "low")] {
+    ///     let mut profile = ProfileInfo::default();
+    ///     profile.artifacts = Some(format!("artifacts/{name}").into());
+    ///
+    ///     let mut config = toml::Table::new();
+    ///     config.insert("quality".to_owned(), quality.into());
+    ///     profile.importers.insert("Quality".to_owned(), config);
+    ///     info.profiles.insert(name.to_owned(), profile);
+    /// }
+    /// info.write(&base.join("argosy.toml")).unwrap();
+    /// std::fs::write(base.join("icon.txt"), "icon").unwrap();
+    ///
+    /// let open = |profile| {
+    ///     let mut store = Store::open_with_profile(&base.join("argosy.toml"), profile).unwrap();
+    ///     store.register_importer(Box::new(Quality));
+    ///     store
+    /// };
+    /// let import = |store: &Store| {
+    ///     futures::executor::block_on(store.store_detailed("icon.txt", None, "text")).unwrap()
+    /// };
+    ///
+    /// let pc = open("pc");
+    /// let mobile = open("mobile");
+    ///
+    /// let pc_icon = import(&pc);
+    /// let mobile_icon = import(&mobile);
+    /// assert!(pc_icon.reimported && mobile_icon.reimported);
+    /// assert_ne!(pc_icon.id, mobile_icon.id);
+    /// assert!(pc_icon.artifact_path.starts_with(base.join("artifacts/pc")));
+    /// assert!(mobile_icon.artifact_path.starts_with(base.join("artifacts/mobile")));
+    /// assert_eq!(std::fs::read_to_string(&pc_icon.artifact_path).unwrap(), "icon|quality = \"high\"");
+    /// assert_eq!(std::fs::read_to_string(&mobile_icon.artifact_path).unwrap(), "icon|quality = \"low\"");
+    ///
+    /// // Each profile keeps its own import up to date.
+    /// assert!(!import(&pc).reimported);
+    /// assert!(!import(&mobile).reimported);
+    ///
+    /// // Fetch finds only assets of the active profile.
+    /// let fetch = |store: &Store, id| futures::executor::block_on(store.fetch(id));
+    /// assert!(fetch(&open("pc"), pc_icon.id).is_some());
+    /// assert!(fetch(&open("pc"), mobile_icon.id).is_none());
+    ///
+    /// assert!(Store::open_with_profile(&base.join("argosy.toml"), "console").is_err());
+    /// ```
+    #[tracing::instrument]
+    pub fn open_with_profile(path: &Path, profile: &str) -> Result<Self, OpenStoreError> {
+        let meta = StoreInfo::read(path)?;
+        let base = path.parent().unwrap().to_owned();
+
+        Self::new_with_profile(&base, meta, Some(profile))
+    }
+
     pub fn new(base: &Path, meta: StoreInfo) -> Result<Self, OpenStoreError> {
+        Self::new_with_profile(base, meta, None)
+    }
+
+    /// Creates store with named profile active.
+    ///
+    /// See [`Store::open_with_profile`].
+    pub fn new_with_profile(
+        base: &Path,
+        mut meta: StoreInfo,
+        profile: Option<&str>,
+    ) -> Result<Self, OpenStoreError> {
         let base = dunce::canonicalize(base).map_err(|error| OpenStoreError::CanonError {
             error,
             path: base.to_owned(),
@@ -246,15 +606,37 @@ impl Store {
         let base_url =
             Url::from_directory_path(&base).expect("Canonical path must be convertible to URL");
 
-        let artifacts = base.join(
-            meta.artifacts
-                .unwrap_or_else(|| Path::new(DEFAULT_AUX).join(DEFAULT_ARTIFACTS)),
-        );
+        let mut importer_configs = HashMap::new();
+        let (artifacts, external) = match profile {
+            None => store_dirs(&base, &meta, None),
+            Some(name) => {
+                let profile =
+                    meta.profiles
+                        .remove(name)
+                        .ok_or_else(|| OpenStoreError::UnknownProfile {
+                            profile: name.to_owned(),
+                        })?;
+
+                let dirs = store_dirs(&base, &meta, Some((name, &profile)));
+
+                for (importer, config) in profile.importers {
+                    let config = toml::to_string(&config).map_err(|error| {
+                        OpenStoreError::ImporterConfigError {
+                            error,
+                            importer: importer.clone(),
+                        }
+                    })?;
+                    importer_configs.insert(importer, config);
+                }
 
-        let external = base.join(
-            meta.external
-                .unwrap_or_else(|| Path::new(DEFAULT_AUX).join(DEFAULT_EXTERNAL)),
-        );
+                dirs
+            }
+        };
+
+        let mut layers = Vec::with_capacity(meta.base_stores.len());
+        for path in &meta.base_stores {
+            layers.push(open_layer(&base.join(path), profile)?);
+        }
 
         let temp = meta
             .temp
@@ -286,8 +668,17 @@ impl Store {
             external,
             temp,
             importers,
+            max_attempts: meta.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS),
+            descriptor_format: meta.descriptor_format.unwrap_or_default(),
+            artifact_envelopes: meta.artifact_envelopes.unwrap_or(false),
+            profile: profile.map(str::to_owned),
+            layers,
+            importer_configs,
+            pre_import_hooks: Vec::new(),
+            post_import_hooks: Vec::new(),
             artifacts: RwLock::new(HashMap::new()),
             scanned: RwLock::new(false),
+            scan_lock: futures::lock::Mutex::new(()),
             id_gen: Generator::new(),
         })
     }
@@ -298,7 +689,263 @@ impl Store {
         self.importers.add_importer(importer);
     }
 
+    /// Registers pipeline of importers that imports to `target`.
+    ///
+    /// When no single importer can import an asset,
+    /// registered pipelines are tried before chains of two importers are searched automatically.
+    /// Each stage imports output of the previous one,
+    /// intermediate outputs are stored in temporary files.
+    /// Asset is reimported when any stage importer changes version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::atomic::{AtomicU32, Ordering};
+    /// # use argosy_store::{StageSpec, Store, StoreError, StoreInfo};
+    /// /// Importer that appends its target to the data.
+    /// struct Append {
+    ///     name: &'static str,
+    ///     formats: &'static [&'static str],
+    ///     target: &'static str,
+    /// }
+    ///
+    /// static VERSION: AtomicU32 = AtomicU32::new(1);
+    ///
+    /// impl argosy_import::Importer for Append {
+    ///     fn name(&self) -> &str { self.name }
+    ///     fn formats(&self) -> &[&str] { self.formats }
+    ///     fn extensions(&self) -> &[&str] { self.formats }
+    ///     fn target(&self) -> &str { self.target }
+    ///     fn version(&self) -> u32 { VERSION.load(Ordering::Relaxed) }
+    ///     fn import(
+    ///         &self,
+    ///         source: &std::path::Path,
+    ///         output: &std::path::Path,
+    ///         _: &mut dyn argosy_import::Sources,
+    ///         _: &mut dyn argosy_import::Dependencies,
+    ///         _: &mut argosy_import::OutputSink,
+    ///     ) -> Result<(), argosy_import::ImportError> {
+    ///         let data = std::fs::read_to_string(source).unwrap();
+    ///         std::fs::write(output, format!("{data}|{}", self.target)).unwrap();
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// # let store_dir = argosy_store::test_util::TempStore::new("pipeline");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("icon.svg"), "svg").unwrap();
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(Append { name: "Rasterize", formats: &["svg"], target: "png" }));
+    /// store.register_importer(Box::new(Append { name: "Compress", formats: &["png"], target: "texture" }));
+    /// store
+    ///     .register_pipeline(
+    ///         "texture",
+    ///         vec![
+    ///             StageSpec { format: "svg".to_owned(), target: "png".to_owned() },
+    ///             StageSpec { format: "png".to_owned(), target: "texture".to_owned() },
+    ///         ],
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let outcome = futures::executor::block_on(store.store_detailed("icon.svg", None, "texture")).unwrap();
+    /// assert_eq!(outcome.importer.as_deref(), Some("Rasterize -> Compress"));
+    /// assert_eq!(std::fs::read_to_string(&outcome.artifact_path).unwrap(), "svg|png|texture");
+    ///
+    /// // Up to date.
+    /// let outcome = futures::executor::block_on(store.store_detailed("icon.svg", None, "texture")).unwrap();
+    /// assert!(!outcome.reimported);
+    ///
+    /// // New version of stage importer.
+    /// VERSION.store(2, Ordering::Relaxed);
+    /// let outcome = futures::executor::block_on(store.store_detailed("icon.svg", None, "texture")).unwrap();
+    /// assert!(outcome.reimported);
+    ///
+    /// // No chain leads to the target.
+    /// match futures::executor::block_on(store.store_detailed("icon.svg", None, "mesh")) {
+    ///     Err(StoreError::NoImporters { .. }) => {}
+    ///     _ => panic!("Import must fail"),
+    /// }
+    ///
+    /// // Stages must form a chain.
+    /// assert!(store
+    ///     .register_pipeline(
+    ///         "texture",
+    ///         vec![StageSpec { format: "svg".to_owned(), target: "png".to_owned() }],
+    ///     )
+    ///     .is_err());
+    /// ```
+    #[tracing::instrument(skip(self, stages))]
+    pub fn register_pipeline(
+        &mut self,
+        target: &str,
+        stages: Vec<StageSpec>,
+    ) -> Result<(), InvalidPipeline> {
+        self.importers.register_pipeline(target, stages)
+    }
+
+    /// Adds hook that is called before asset is imported.
+    ///
+    /// Hook receives source, target and importer of the asset
+    /// and may reject the import by returning an error,
+    /// in which case [`StoreError::ImportRejected`] is returned.
+    /// Hooks are called for dependencies imported transitively too,
+    /// in order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreError, StoreInfo};
+    /// # use argosy_store::test_util::CopyImporter;
+    /// # let store_dir = argosy_store::test_util::TempStore::new("pre-hook");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("hello.txt"), "Hello").unwrap();
+    /// std::fs::write(base.join("Bad Name.txt"), "Hello").unwrap();
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(CopyImporter));
+    ///
+    /// // Enforce naming convention.
+    /// store.add_pre_import_hook(|request| {
+    ///     match request.source.path().contains("%20") {
+    ///         true => Err("Spaces are not allowed in asset names".to_owned()),
+    ///         false => Ok(()),
+    ///     }
+    /// });
+    ///
+    /// futures::executor::block_on(store.store("hello.txt", None, "text")).unwrap();
+    ///
+    /// let err = futures::executor::block_on(store.store("Bad Name.txt", None, "text")).unwrap_err();
+    /// match err {
+    ///     StoreError::ImportRejected { reason, .. } => {
+    ///         assert_eq!(reason, "Spaces are not allowed in asset names");
+    ///     }
+    ///     err => panic!("Unexpected error {err}"),
+    /// }
+    /// ```
+    pub fn add_pre_import_hook(
+        &mut self,
+        hook: impl Fn(&ImportRequest) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.pre_import_hooks.push(Box::new(hook));
+    }
+
+    /// Adds hook that is called after asset is imported and its metadata is written.
+    ///
+    /// Hooks are called for dependencies imported transitively too,
+    /// each dependency before the asset that requires it.
+    /// Assets that are up-to-date are not reported.
+    /// Errors returned by hooks are only logged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreInfo};
+    /// # use std::sync::{Arc, Mutex};
+    /// /// Importer that requires asset named in the source as dependency.
+    /// struct RefImporter;
+    ///
+    /// impl argosy_import::Importer for RefImporter {
+    ///     fn name(&self) -> &str { "Ref" }
+    ///     fn formats(&self) -> &[&str] { &["text"] }
+    ///     fn extensions(&self) -> &[&str] { &["txt"] }
+    ///     fn target(&self) -> &str { "text" }
+    ///     fn import(
+    ///         &self,
+    ///         source: &std::path::Path,
+    ///         output: &std::path::Path,
+    ///         _: &mut dyn argosy_import::Sources,
+    ///         dependencies: &mut dyn argosy_import::Dependencies,
+    ///         _: &mut argosy_import::OutputSink,
+    ///     ) -> Result<(), argosy_import::ImportError> {
+    ///         let dependency = std::fs::read_to_string(source).unwrap();
+    ///         if !dependency.is_empty() && dependencies.get(&dependency, "text").is_none() {
+    ///             return argosy_import::ensure(vec![], vec![argosy_import::Dependency {
+    ///                 source: dependency,
+    ///                 target: "text".to_owned(),
+    ///             }]);
+    ///         }
+    ///         std::fs::write(output, "").unwrap();
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// # let store_dir = argosy_store::test_util::TempStore::new("post-hook");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("a.txt"), "b.txt").unwrap();
+    /// std::fs::write(base.join("b.txt"), "c.txt").unwrap();
+    /// std::fs::write(base.join("c.txt"), "").unwrap();
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(RefImporter));
+    ///
+    /// let imported = Arc::new(Mutex::new(Vec::new()));
+    /// let log = imported.clone();
+    /// store.add_post_import_hook(move |info| {
+    ///     let name = info.source.path().rsplit('/').next().unwrap().to_owned();
+    ///     log.lock().unwrap().push((name, info.reimport));
+    ///     Ok(())
+    /// });
+    ///
+    /// futures::executor::block_on(store.store("a.txt", None, "text")).unwrap();
+    /// assert_eq!(
+    ///     *imported.lock().unwrap(),
+    ///     [("c.txt".to_owned(), false), ("b.txt".to_owned(), false), ("a.txt".to_owned(), false)],
+    /// );
+    /// ```
+    pub fn add_post_import_hook(
+        &mut self,
+        hook: impl Fn(&ImportResultInfo) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.post_import_hooks.push(Box::new(hook));
+    }
+
+    /// Returns information about registered importers.
+    /// Sorted by target and then by name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreInfo};
+    /// # use argosy_store::test_util::CopyImporter;
+    /// # let store_dir = argosy_store::test_util::TempStore::new("importers");
+    /// # let base = store_dir.path();
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(CopyImporter));
+    ///
+    /// let importers = store.importers();
+    /// assert_eq!(importers.len(), 1);
+    /// assert_eq!(importers[0].name, "Copy");
+    /// assert_eq!(importers[0].formats, ["text"]);
+    /// assert_eq!(importers[0].target, "text");
+    /// assert_eq!(importers[0].extensions, ["txt"]);
+    /// assert_eq!(importers[0].description, "");
+    ///
+    /// assert_eq!(store.importer_targets(), ["text"]);
+    /// assert_eq!(store.describe_target("text")[0].name, "Copy");
+    /// assert!(store.describe_target("texture").is_empty());
+    /// ```
+    pub fn importers(&self) -> Vec<ImporterInfo> {
+        self.importers.infos()
+    }
+
+    /// Returns all targets importers are registered for.
+    /// Sorted alphabetically.
+    pub fn importer_targets(&self) -> Vec<&str> {
+        self.importers.targets()
+    }
+
+    /// Returns information about importers to specified target.
+    /// Sorted by name.
+    /// Returns empty vector if no importer is registered for the target.
+    pub fn describe_target(&self, target: &str) -> Vec<ImporterInfo> {
+        self.importers.describe(target)
+    }
+
     /// Loads importers from dylib.
+    ///
+    /// # Safety
+    ///
     /// There is no possible way to guarantee that dylib does not break safety contracts.
     /// Some measures to ensure safety are taken.
     /// Providing dylib from which importers will be successfully loaded and then cause an UB should only be possible on purpose.
@@ -307,6 +954,22 @@ impl Store {
         self.importers.load_dylib_importers(lib_path)
     }
 
+    /// Replaces importers loaded from dylib with ones loaded from it anew.
+    ///
+    /// Use it after the library is rebuilt.
+    /// Assets produced by importers that changed version
+    /// are reimported on the next store.
+    /// Platforms that keep library loaded after it is closed
+    /// may provide the same importers again.
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`Store::register_importers_lib`].
+    #[tracing::instrument(skip(self))]
+    pub unsafe fn reload_importers_lib(&mut self, lib_path: &Path) -> Result<(), LoadingError> {
+        self.importers.reload_dylib_importers(lib_path)
+    }
+
     /// Import an asset.
     #[tracing::instrument(skip(self))]
     pub async fn store(
@@ -315,6 +978,19 @@ impl Store {
         format: Option<&str>,
         target: &str,
     ) -> Result<(AssetId, PathBuf, SystemTime), StoreError> {
+        let outcome = self.store_detailed(source, format, target).await?;
+        Ok((outcome.id, outcome.artifact_path, outcome.modified))
+    }
+
+    /// Import an asset.
+    /// Returns detailed outcome of the operation.
+    #[tracing::instrument(skip(self))]
+    pub async fn store_detailed(
+        &self,
+        source: &str,
+        format: Option<&str>,
+        target: &str,
+    ) -> Result<StoreOutcome, StoreError> {
         let source = self
             .base_url
             .join(source)
@@ -324,7 +1000,7 @@ impl Store {
                 url: source.to_owned(),
             })?;
 
-        self.store_url(source, format, target).await
+        self.store_url_detailed(source, format, target).await
     }
 
     /// Import an asset.
@@ -335,12 +1011,139 @@ impl Store {
         format: Option<&str>,
         target: &str,
     ) -> Result<(AssetId, PathBuf, SystemTime), StoreError> {
+        let outcome = self.store_url_detailed(source, format, target).await?;
+        Ok((outcome.id, outcome.artifact_path, outcome.modified))
+    }
+
+    /// Import an asset.
+    /// Returns detailed outcome of the operation.
+    #[tracing::instrument(skip(self))]
+    pub async fn store_url_detailed(
+        &self,
+        source: Url,
+        format: Option<&str>,
+        target: &str,
+    ) -> Result<StoreOutcome, StoreError> {
+        self.store_url_impl(source, format, target, false, Sources::new())
+            .await
+    }
+
+    /// Import an asset from bytes in memory.
+    ///
+    /// Source gets `mem:` URL made from `synthetic_name`,
+    /// its extension is used to guess importer if `format` is not specified.
+    /// Storing the same bytes under the same name again does not reimport the asset.
+    /// Different bytes reimport it and the asset keeps its id.
+    ///
+    /// In-memory sources are not kept by the store,
+    /// importers may not require other sources relative to them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreInfo};
+    /// # use argosy_store::test_util::CopyImporter;
+    /// # let store_dir = argosy_store::test_util::TempStore::new("store-bytes");
+    /// # let base = store_dir.path();
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(CopyImporter));
+    ///
+    /// let (id, path) = futures::executor::block_on(store.store_bytes(b"rock", "procedural/rock.txt", None, "text")).unwrap();
+    /// assert_eq!(std::fs::read(&path).unwrap(), b"rock");
+    ///
+    /// // Same bytes are not reimported.
+    /// let outcome = futures::executor::block_on(store.store_bytes_detailed(b"rock", "procedural/rock.txt", None, "text")).unwrap();
+    /// assert!(!outcome.reimported);
+    /// assert_eq!(outcome.id, id);
+    ///
+    /// // Changed bytes are reimported under the same id.
+    /// let outcome = futures::executor::block_on(store.store_bytes_detailed(b"stone", "procedural/rock.txt", None, "text")).unwrap();
+    /// assert!(outcome.reimported);
+    /// assert_eq!(outcome.id, id);
+    /// assert_ne!(outcome.artifact_path, path);
+    /// assert_eq!(std::fs::read(&outcome.artifact_path).unwrap(), b"stone");
+    /// ```
+    #[tracing::instrument(skip(self, bytes))]
+    pub async fn store_bytes(
+        &self,
+        bytes: &[u8],
+        synthetic_name: &str,
+        format: Option<&str>,
+        target: &str,
+    ) -> Result<(AssetId, PathBuf), StoreError> {
+        let outcome = self
+            .store_bytes_detailed(bytes, synthetic_name, format, target)
+            .await?;
+        Ok((outcome.id, outcome.artifact_path))
+    }
+
+    /// Import an asset from bytes in memory.
+    /// Returns detailed outcome of the operation.
+    ///
+    /// See [`Store::store_bytes`].
+    #[tracing::instrument(skip(self, bytes))]
+    pub async fn store_bytes_detailed(
+        &self,
+        bytes: &[u8],
+        synthetic_name: &str,
+        format: Option<&str>,
+        target: &str,
+    ) -> Result<StoreOutcome, StoreError> {
+        let mem_base = Url::parse("mem:/").unwrap();
+        let source =
+            mem_base
+                .join(synthetic_name)
+                .map_err(|error| StoreError::InvalidSourceUrl {
+                    error,
+                    base: mem_base.clone(),
+                    url: synthetic_name.to_owned(),
+                })?;
+
+        let meta =
+            SourceMeta::new(&source, &self.base, &self.external).map_err(StoreError::MetaError)?;
+
+        // Stored bytes differ from the submitted ones.
+        let changed = meta
+            .get_asset(target, self.profile.as_deref())
+            .and_then(AssetMeta::source_hash)
+            .is_some_and(|hash| hash != Sha256Hash::hash(bytes));
+
+        let mut temporaries = Temporaries::new(&self.temp);
+        let path = temporaries.make();
+        std::fs::write(&path, bytes).map_err(|error| {
+            StoreError::SourcesError(SourcesError::FileError {
+                error,
+                url: source.clone(),
+                path: path.clone(),
+            })
+        })?;
+
         let mut sources = Sources::new();
+        sources.insert_memory(source.clone(), path);
+
+        self.store_url_impl(source, format, target, changed, sources)
+            .await
+    }
+
+    /// Imports an asset, reimporting it if `forced` even if it is up to date.
+    async fn store_url_impl(
+        &self,
+        source: Url,
+        format: Option<&str>,
+        target: &str,
+        forced: bool,
+        mut sources: Sources,
+    ) -> Result<StoreOutcome, StoreError> {
+        let start = Instant::now();
+        let mut dependencies = Vec::new();
 
         let base = &self.base;
         let artifacts_base = &self.artifacts_base;
         let external = &self.external;
         let importers = &self.importers;
+        let profile = self.profile.as_deref();
+        let layers = &self.layers;
+        let base_url = &self.base_url;
 
         struct StackItem {
             /// Source URL.
@@ -361,6 +1164,18 @@ impl Store {
 
             /// Dependencies requested by importer.
             dependencies: HashSet<AssetId>,
+
+            /// Sources and dependencies importer required on previous attempt.
+            /// Used to detect that importer makes no progress.
+            required: Option<(Vec<String>, Vec<Dependency>)>,
+
+            /// Id of the asset that is reimported without changes in sources,
+            /// e.g. to change descriptor format.
+            /// Kept so that references to the asset remain valid.
+            preserved_id: Option<AssetId>,
+
+            /// Whether asset is reimported even if it is up to date.
+            forced: bool,
         }
 
         let mut stack = Vec::new();
@@ -371,6 +1186,9 @@ impl Store {
             attempt: 0,
             sources: HashMap::new(),
             dependencies: HashSet::new(),
+            required: None,
+            preserved_id: None,
+            forced,
         });
 
         loop {
@@ -380,7 +1198,28 @@ impl Store {
             let mut meta = SourceMeta::new(&item.source, &self.base, &self.external)
                 .map_err(StoreError::MetaError)?;
 
-            if let Some(asset) = meta.get_asset(&item.target) {
+            // Local asset takes precedence over assets of base stores.
+            let layered;
+            let (found, found_artifacts) = match meta.get_asset(&item.target, profile) {
+                Some(asset) => (Some(asset), &self.artifacts_base),
+                None => {
+                    layered = find_in_layers(
+                        &self.layers,
+                        &item.source,
+                        &self.base_url,
+                        &item.target,
+                        profile,
+                    );
+                    match &layered {
+                        None => (None, &self.artifacts_base),
+                        Some((meta, layer)) => {
+                            (meta.get_asset(&item.target, profile), &layer.artifacts)
+                        }
+                    }
+                }
+            };
+
+            if let Some(asset) = found {
                 if asset.needs_reimport(&self.base_url) {
                     tracing::debug!(
                         "'{}' '{:?}' '{}' reimporting",
@@ -388,6 +1227,39 @@ impl Store {
                         item.format,
                         item.target
                     );
+                } else if !asset.stages().is_empty()
+                    && importers
+                        .guess_chain(item.format.as_deref(), url_ext(&item.source), &item.target)
+                        .ok()
+                        .flatten()
+                        .is_some_and(|chain| asset.importers_changed(&chain))
+                {
+                    tracing::debug!(
+                        "'{}' '{:?}' '{}' reimporting with updated importers",
+                        item.source,
+                        item.format,
+                        item.target
+                    );
+                } else if asset
+                    .descriptor()
+                    .is_some_and(|format| format != self.descriptor_format)
+                {
+                    tracing::debug!(
+                        "'{}' '{:?}' '{}' reimporting as {:?}",
+                        item.source,
+                        item.format,
+                        item.target,
+                        self.descriptor_format
+                    );
+                    item.preserved_id = Some(asset.id());
+                } else if item.forced {
+                    tracing::debug!(
+                        "'{}' '{:?}' '{}' reimporting on request",
+                        item.source,
+                        item.format,
+                        item.target
+                    );
+                    item.preserved_id = Some(asset.id());
                 } else {
                     match &item.format {
                         None => tracing::debug!("{} @ '{}'", item.target, item.source),
@@ -396,28 +1268,44 @@ impl Store {
                         }
                     }
 
-                    stack.pop().unwrap();
+                    let item = stack.pop().unwrap();
                     if stack.is_empty() {
-                        let path = asset.artifact_path(&self.artifacts_base);
-                        return Ok((asset.id(), path, asset.latest_modified()));
+                        return Ok(StoreOutcome {
+                            id: asset.id(),
+                            artifact_path: asset.artifact_path(found_artifacts),
+                            modified: asset.latest_modified(),
+                            reimported: false,
+                            importer: None,
+                            elapsed: start.elapsed(),
+                            dependencies,
+                        });
                     }
+                    dependencies.push(DependencyOutcome {
+                        id: asset.id(),
+                        source: item.source,
+                        target: item.target,
+                        reimported: false,
+                        importer: None,
+                    });
                     continue;
                 }
             }
 
-            let importer = importers
-                .guess(item.format.as_deref(), url_ext(&item.source), &item.target)
+            let chain = importers
+                .guess_chain(item.format.as_deref(), url_ext(&item.source), &item.target)
                 .map_err(|err| StoreError::AmbiguousImporters {
                     formats: err.formats,
                     target: err.target,
                     url: item.source.clone(),
                 })?;
 
-            let importer = importer.ok_or_else(|| StoreError::NoImporters {
+            let chain = chain.ok_or_else(|| StoreError::NoImporters {
                 format: item.format.clone(),
                 target: item.target.clone(),
                 url: item.source.clone(),
+                suggestion: importers.suggest(item.format.as_deref(), &item.target),
             })?;
+            let importer_name = Importers::chain_name(&chain);
 
             // Fetch source file.
             let (source_path, source_modified) = sources
@@ -426,7 +1314,42 @@ impl Store {
                 .map_err(StoreError::SourcesError)?;
 
             let source_path = source_path.to_owned();
+
+            let source_hash = match item.source.scheme().parse() {
+                Ok(Scheme::Mem) => Some(Sha256Hash::file_hash(&source_path).map_err(|error| {
+                    StoreError::MetaError(MetaError::HashError {
+                        error,
+                        path: source_path.clone(),
+                    })
+                })?),
+                _ => None,
+            };
+
+            // Importer may be invoked several times for the same asset,
+            // hooks are asked only once.
+            if item.attempt == 1 && !self.pre_import_hooks.is_empty() {
+                let request = ImportRequest {
+                    source: item.source.clone(),
+                    format: item.format.clone(),
+                    target: item.target.clone(),
+                    importer: importer_name.clone(),
+                    source_path: source_path.clone(),
+                };
+
+                for hook in &self.pre_import_hooks {
+                    if let Err(reason) = hook(&request) {
+                        return Err(StoreError::ImportRejected {
+                            format: item.format.clone(),
+                            target: item.target.clone(),
+                            url: item.source.clone(),
+                            reason,
+                        });
+                    }
+                }
+            }
+
             let output_path = make_temporary(&self.temp);
+            let mut sink = OutputSink::new(self.descriptor_format);
 
             struct Fn<F>(F);
 
@@ -448,31 +1371,83 @@ impl Store {
                 }
             }
 
-            let result = importer.import(
-                &source_path,
-                &output_path,
-                &mut Fn(|src: &str| {
-                    let src = item.source.join(src).ok()?; // If parsing fails - source will be listed in `ImportResult::RequireSources`.
-                    let (path, modified) = sources.get(&src)?;
-                    item.sources.insert(src, modified);
-                    Some(path.to_owned())
-                }),
-                &mut Fn(|src: &str, target: &str| {
-                    let src = item.source.join(src).ok()?;
-
-                    match SourceMeta::new(&src, base, external) {
-                        Ok(meta) => {
-                            let asset = meta.get_asset(target)?;
-                            item.dependencies.insert(asset.id());
-                            Some(asset.id())
-                        }
-                        Err(err) => {
-                            tracing::error!("Fetching dependency failed. {:#}", err);
-                            None
+            // Stages run in sequence, each importing output of the previous one.
+            // Intermediate outputs are removed when import is done.
+            let mut temporaries = Temporaries::new(&self.temp);
+            let mut stages = Vec::with_capacity(chain.len());
+            let mut input = source_path.clone();
+            let mut result = Ok(());
+
+            for (index, importer) in chain.iter().enumerate() {
+                let last = index + 1 == chain.len();
+                let output = match last {
+                    true => output_path.clone(),
+                    false => temporaries.make(),
+                };
+
+                // Only the last stage decides if artifact is a descriptor.
+                let mut stage_sink = OutputSink::new(self.descriptor_format);
+                let stage_sink = if last { &mut sink } else { &mut stage_sink };
+                if let Some(config) = self.importer_configs.get(importer.name()) {
+                    stage_sink.set_config(config);
+                }
+
+                result = importer.import(
+                    &input,
+                    &output,
+                    &mut Fn(|src: &str| {
+                        let src = item.source.join(src).ok()?; // If parsing fails - source will be listed in `ImportResult::RequireSources`.
+                        let (path, modified) = sources.get(&src)?;
+                        item.sources.insert(src, modified);
+                        Some(path.to_owned())
+                    }),
+                    &mut Fn(|src: &str, target: &str| {
+                        let src = item.source.join(src).ok()?;
+
+                        match SourceMeta::new(&src, base, external) {
+                            Ok(meta) => {
+                                let id = match meta.get_asset(target, profile) {
+                                    Some(asset) => asset.id(),
+                                    None => {
+                                        let (meta, _) = find_in_layers(
+                                            layers, &src, base_url, target, profile,
+                                        )?;
+                                        meta.get_asset(target, profile)?.id()
+                                    }
+                                };
+                                item.dependencies.insert(id);
+                                Some(id)
+                            }
+                            Err(err) => {
+                                tracing::error!("Fetching dependency failed. {:#}", err);
+                                None
+                            }
                         }
-                    }
-                }),
-            );
+                    }),
+                    stage_sink,
+                );
+
+                if result.is_err() {
+                    break;
+                }
+
+                let output_hash = match last {
+                    true => None,
+                    false => Some(Sha256Hash::file_hash(&output).map_err(|error| {
+                        StoreError::MetaError(MetaError::HashError {
+                            error,
+                            path: output.clone(),
+                        })
+                    })?),
+                };
+
+                stages.push(StageMeta {
+                    importer: importer.name().to_owned(),
+                    version: importer.version(),
+                    output: output_hash,
+                });
+                input = output;
+            }
 
             match result {
                 Ok(()) => {}
@@ -485,16 +1460,36 @@ impl Store {
                     });
                 }
                 Err(ImportError::Requires {
-                    sources: srcs,
-                    dependencies: deps,
+                    sources: mut srcs,
+                    dependencies: mut deps,
                 }) => {
-                    if item.attempt >= MAX_ITEM_ATTEMPTS {
+                    srcs.sort();
+                    deps.sort();
+
+                    if item.required.as_ref() == Some(&(srcs.clone(), deps.clone())) {
+                        // Nothing changed since previous attempt.
+                        return Err(StoreError::RequirementsNotSatisfied {
+                            format: item.format.clone(),
+                            target: item.target.clone(),
+                            url: item.source.clone(),
+                            importer: importer_name.clone(),
+                            attempts: item.attempt,
+                            sources: srcs,
+                            dependencies: deps,
+                        });
+                    }
+
+                    if item.attempt >= self.max_attempts {
                         return Err(StoreError::TooManyAttempts {
                             format: item.format.clone(),
                             target: item.target.clone(),
                             url: item.source.clone(),
+                            importer: importer_name.clone(),
+                            attempts: item.attempt,
                         });
                     }
+
+                    item.required = Some((srcs.clone(), deps.clone()));
                     let item_source = item.source.clone();
 
                     for src in srcs {
@@ -506,10 +1501,13 @@ impl Store {
                                     url: src.clone(),
                                 });
                             }
-                            Ok(url) => sources
-                                .fetch(&self.temp, &url)
-                                .await
-                                .map_err(StoreError::SourcesError)?,
+                            Ok(url) => {
+                                // Source that can't be fetched is reported
+                                // if importer requires it again.
+                                if let Err(err) = sources.fetch(&self.temp, &url).await {
+                                    tracing::warn!("Failed to fetch required source. {:#}", err);
+                                }
+                            }
                         };
                     }
 
@@ -530,6 +1528,9 @@ impl Store {
                                     attempt: 0,
                                     sources: HashMap::new(),
                                     dependencies: HashSet::new(),
+                                    required: None,
+                                    preserved_id: None,
+                                    forced: false,
                                 });
                             }
                         };
@@ -538,26 +1539,31 @@ impl Store {
                 }
             }
 
-            if !artifacts_base.exists() {
-                std::fs::create_dir_all(artifacts_base).map_err(|error| {
-                    StoreError::FailedToCreateArtifactsDirectory {
-                        error,
-                        path: artifacts_base.to_owned(),
-                    }
-                })?;
-
-                if let Err(err) = std::fs::write(artifacts_base.join(".gitignore"), "*") {
-                    tracing::error!(
-                        "Failed to place .gitignore into artifacts directory. {:#}",
-                        err
-                    );
+            create_artifacts_dir(artifacts_base).map_err(|error| {
+                StoreError::FailedToCreateArtifactsDirectory {
+                    error,
+                    path: artifacts_base.to_owned(),
                 }
-            }
+            })?;
 
-            let new_id = AssetId(self.id_gen.generate());
+            if self.artifact_envelopes && sink.is_descriptor() {
+                envelope_artifact(&output_path, &output_path, self.descriptor_format).map_err(
+                    |error| {
+                        StoreError::MetaError(MetaError::SaveArtifactError {
+                            error,
+                            path: output_path.clone(),
+                        })
+                    },
+                )?;
+            }
 
             let item = stack.pop().unwrap();
 
+            let new_id = match item.preserved_id {
+                Some(id) => id,
+                None => AssetId(self.id_gen.generate()),
+            };
+
             let make_relative_source = |source| match self.base_url.make_relative(source) {
                 None => source.to_string(),
                 Some(source) => source,
@@ -576,76 +1582,1193 @@ impl Store {
             let asset = AssetMeta::new(
                 new_id,
                 item.format.clone(),
+                sink.is_descriptor().then_some(self.descriptor_format),
                 sources,
                 item.dependencies.into_iter().collect(),
                 &output_path,
                 artifacts_base,
             )
-            .map_err(StoreError::MetaError)?;
+            .map_err(StoreError::MetaError)?
+            .with_stages(stages)
+            .with_source_hash(source_hash);
 
             let artifact_path = asset.artifact_path(artifacts_base);
 
             let latest_modified = asset.latest_modified();
-            meta.add_asset(item.target.clone(), asset, base, external)
+            let asset_dependencies = asset.dependencies().to_vec();
+            let reimport = meta
+                .get_asset(&item.target, self.profile.as_deref())
+                .is_some();
+            meta.add_asset(&item.target, profile, asset, base, external)
                 .map_err(StoreError::MetaError)?;
 
+            if !self.post_import_hooks.is_empty() {
+                let info = ImportResultInfo {
+                    id: new_id,
+                    source: item.source.clone(),
+                    target: item.target.clone(),
+                    artifact_path: artifact_path.clone(),
+                    reimport,
+                };
+
+                for hook in &self.post_import_hooks {
+                    if let Err(err) = hook(&info) {
+                        tracing::error!(
+                            "Post-import hook failed for '{}':'{}'. {}",
+                            item.source,
+                            item.target,
+                            err
+                        );
+                    }
+                }
+            }
+
             self.artifacts.write().insert(
                 new_id,
                 AssetItem {
-                    source: item.source,
+                    source: item.source.clone(),
                     format: item.format,
-                    target: item.target,
+                    target: item.target.clone(),
+                    dependencies: asset_dependencies,
                 },
             );
 
             if stack.is_empty() {
-                return Ok((new_id, artifact_path, latest_modified));
+                return Ok(StoreOutcome {
+                    id: new_id,
+                    artifact_path,
+                    modified: latest_modified,
+                    reimported: true,
+                    importer: Some(importer_name.clone()),
+                    elapsed: start.elapsed(),
+                    dependencies,
+                });
             }
+
+            dependencies.push(DependencyOutcome {
+                id: new_id,
+                source: item.source,
+                target: item.target,
+                reimported: true,
+                importer: Some(importer_name),
+            });
         }
     }
 
     /// Fetch asset data path.
     pub async fn fetch(&self, id: AssetId) -> Option<(PathBuf, SystemTime)> {
-        let scanned = *self.scanned.read();
+        let outcome = self.fetch_detailed(id).await?;
+        Some((outcome.store.artifact_path, outcome.store.modified))
+    }
+
+    /// Fetch asset data path.
+    /// Returns detailed outcome of the operation.
+    pub async fn fetch_detailed(&self, id: AssetId) -> Option<FetchOutcome> {
+        self.scan_artifacts().await;
+
+        let item = self.artifacts.read().get(&id).cloned()?;
+
+        let store = self
+            .store_url_detailed(item.source.clone(), item.format.as_deref(), &item.target)
+            .await
+            .ok()?;
+
+        Some(FetchOutcome {
+            source: item.source,
+            format: item.format,
+            target: item.target,
+            store,
+        })
+    }
+
+    /// Returns length and hash of the up-to-date artifact of the asset.
+    /// Reimports the asset if needed.
+    pub async fn artifact_info(&self, id: AssetId) -> Option<ArtifactInfo> {
+        let outcome = self.fetch_detailed(id).await?;
+        self.artifact_info_of(&outcome)
+    }
+
+    /// Opens up-to-date artifact of the asset for reading.
+    /// Reimports the asset if needed.
+    ///
+    /// Returned reader validates artifact length and hash
+    /// against the metadata as data is read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::io::Read;
+    /// # use argosy_store::{ArtifactError, Store, StoreInfo};
+    /// # use argosy_store::test_util::CopyImporter;
+    /// # let store_dir = argosy_store::test_util::TempStore::new("open-artifact");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("hello.txt"), "Hello, world!").unwrap();
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(CopyImporter));
+    ///
+    /// let (id, path, _) = futures::executor::block_on(store.store("hello.txt", None, "text")).unwrap();
+    ///
+    /// let info = futures::executor::block_on(store.artifact_info(id)).unwrap();
+    /// assert_eq!(info.len, 13);
+    /// assert!(!info.compressed);
+    ///
+    /// let mut reader = futures::executor::block_on(store.open_artifact(id)).unwrap().unwrap();
+    /// let mut copy = Vec::new();
+    /// std::io::copy(&mut reader, &mut copy).unwrap();
+    /// assert_eq!(copy, b"Hello, world!");
+    ///
+    /// // Damage the artifact behind the store's back.
+    /// std::fs::write(&path, "Hello").unwrap();
+    ///
+    /// let reader = futures::executor::block_on(store.open_artifact(id)).unwrap().unwrap();
+    /// let err = reader.read_all().unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    /// assert!(matches!(
+    ///     err.get_ref().unwrap().downcast_ref::<ArtifactError>(),
+    ///     Some(ArtifactError::Truncated { expected: 13, found: 5 })
+    /// ));
+    /// ```
+    pub async fn open_artifact(&self, id: AssetId) -> std::io::Result<Option<ArtifactReader>> {
+        Ok(self
+            .open_artifact_detailed(id)
+            .await?
+            .map(|(_, reader)| reader))
+    }
+
+    /// Opens up-to-date artifact of the asset for reading.
+    /// Returns detailed outcome of the fetch as well.
+    async fn open_artifact_detailed(
+        &self,
+        id: AssetId,
+    ) -> std::io::Result<Option<(FetchOutcome, ArtifactReader)>> {
+        let Some(outcome) = self.fetch_detailed(id).await else {
+            return Ok(None);
+        };
+        let Some(info) = self.artifact_info_of(&outcome) else {
+            return Ok(None);
+        };
+        let file = std::fs::File::open(&outcome.store.artifact_path)?;
+        Ok(Some((outcome, ArtifactReader::new(file, info))))
+    }
+
+    /// Reads artifact description from the metadata of the fetched asset.
+    fn artifact_info_of(&self, outcome: &FetchOutcome) -> Option<ArtifactInfo> {
+        let meta = match SourceMeta::new(&outcome.source, &self.base, &self.external) {
+            Ok(meta) => meta,
+            Err(err) => {
+                tracing::error!("Failed to read metadata of '{}': {err}", outcome.source);
+                return None;
+            }
+        };
+        let profile = self.profile.as_deref();
+        let layered;
+        let asset = match meta.get_asset(&outcome.target, profile) {
+            Some(asset) => asset,
+            None => {
+                layered = find_in_layers(
+                    &self.layers,
+                    &outcome.source,
+                    &self.base_url,
+                    &outcome.target,
+                    profile,
+                )?;
+                layered.0.get_asset(&outcome.target, profile)?
+            }
+        };
 
-        if !scanned {
-            let existing_artifacts: HashSet<_> = self.artifacts.read().keys().copied().collect();
+        // Metadata written by older versions lacks artifact length.
+        let len = match asset.len() {
+            Some(len) => len,
+            None => std::fs::metadata(&outcome.store.artifact_path).ok()?.len(),
+        };
 
-            let mut new_artifacts = Vec::new();
-            let mut scanned = self.scanned.write();
+        Some(ArtifactInfo {
+            len,
+            sha256: asset.sha256(),
+            compressed: false,
+        })
+    }
 
-            if !*scanned {
-                scan_local(&self.base, &existing_artifacts, &mut new_artifacts);
-                scan_external(&self.external, &existing_artifacts, &mut new_artifacts);
+    /// Lists stored assets with specified target
+    /// which sources start with `prefix`.
+    /// Returns source and id of each asset.
+    ///
+    /// Unlike [`Store::find_asset`] this does not import assets.
+    pub fn find_stored_prefix(&self, prefix: &str, target: &str) -> Vec<(String, AssetId)> {
+        self.scan_artifacts_blocking();
+
+        self.artifacts
+            .read()
+            .iter()
+            .filter(|(_, item)| item.target == target)
+            .filter_map(|(id, item)| {
+                let source = match self.base_url.make_relative(&item.source) {
+                    None => item.source.to_string(),
+                    Some(source) => source,
+                };
+                source.starts_with(prefix).then_some((source, *id))
+            })
+            .collect()
+    }
 
-                let mut artifacts = self.artifacts.write();
-                for (id, item) in new_artifacts {
-                    artifacts.insert(id, item);
+    /// Returns stored assets that are not used.
+    ///
+    /// `usage` lists ids of assets loaded at runtime,
+    /// e.g. recorded with usage recording of the loader over many runs.
+    /// Dependencies of used assets are used too, transitively.
+    /// Returns id, source and target of each unused asset, sorted by source and target.
+    ///
+    /// Metadata is scanned anew, so assets reimported with new ids are not reported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreInfo};
+    /// /// Importer that requires assets listed in the source as dependencies.
+    /// struct RefImporter;
+    ///
+    /// impl argosy_import::Importer for RefImporter {
+    ///     fn name(&self) -> &str { "Ref" }
+    ///     fn formats(&self) -> &[&str] { &["text"] }
+    ///     fn extensions(&self) -> &[&str] { &["txt"] }
+    ///     fn target(&self) -> &str { "text" }
+    ///     fn import(
+    ///         &self,
+    ///         source: &std::path::Path,
+    ///         output: &std::path::Path,
+    ///         _: &mut dyn argosy_import::Sources,
+    ///         dependencies: &mut dyn argosy_import::Dependencies,
+    ///         _: &mut argosy_import::OutputSink,
+    ///     ) -> Result<(), argosy_import::ImportError> {
+    ///         let mut missing = Vec::new();
+    ///         for dependency in std::fs::read_to_string(source).unwrap().split_whitespace() {
+    ///             dependencies.get_or_append(dependency, "text", &mut missing);
+    ///         }
+    ///         if !missing.is_empty() {
+    ///             return argosy_import::ensure(vec![], missing);
+    ///         }
+    ///         std::fs::write(output, "").unwrap();
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// # let store_dir = argosy_store::test_util::TempStore::new("unreferenced");
+    /// # let base = store_dir.path();
+    /// // level -> hero -> sword, hero -> shield, chest -> sword, unused.
+    /// std::fs::write(base.join("level.txt"), "hero.txt").unwrap();
+    /// std::fs::write(base.join("hero.txt"), "sword.txt shield.txt").unwrap();
+    /// std::fs::write(base.join("sword.txt"), "").unwrap();
+    /// std::fs::write(base.join("shield.txt"), "").unwrap();
+    /// std::fs::write(base.join("chest.txt"), "sword.txt").unwrap();
+    /// std::fs::write(base.join("unused.txt"), "").unwrap();
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(RefImporter));
+    ///
+    /// let mut ids = std::collections::HashMap::new();
+    /// for name in ["level", "hero", "sword", "shield", "chest", "unused"] {
+    ///     let source = format!("{name}.txt");
+    ///     let (id, _, _) = futures::executor::block_on(store.store(&source, None, "text")).unwrap();
+    ///     ids.insert(name, id);
+    /// }
+    ///
+    /// let unreferenced = |used: &[&str]| {
+    ///     let used: Vec<_> = used.iter().map(|name| ids[name]).collect();
+    ///     let mut names: Vec<_> = store
+    ///         .unreferenced_assets(&used)
+    ///         .into_iter()
+    ///         .map(|(id, source, target)| {
+    ///             assert_eq!(target, "text");
+    ///             let name = source.path().rsplit('/').next().unwrap().trim_end_matches(".txt").to_owned();
+    ///             assert_eq!(ids[name.as_str()], id);
+    ///             name
+    ///         })
+    ///         .collect();
+    ///     names.sort();
+    ///     names
+    /// };
+    ///
+    /// assert_eq!(unreferenced(&["level"]), ["chest", "unused"]);
+    /// assert_eq!(unreferenced(&["chest"]), ["hero", "level", "shield", "unused"]);
+    /// assert_eq!(unreferenced(&["hero", "chest", "unused"]), ["level"]);
+    /// assert_eq!(unreferenced(&[]).len(), 6);
+    /// ```
+    pub fn unreferenced_assets(&self, usage: &[AssetId]) -> Vec<(AssetId, Url, String)> {
+        let (found, _) = self.scan_job(false).run();
+        let artifacts: Vec<_> = found
+            .into_iter()
+            .next()
+            .into_iter()
+            .flatten()
+            .map(asset_item)
+            .collect();
+
+        let dependencies: HashMap<AssetId, &[AssetId]> = artifacts
+            .iter()
+            .map(|(id, item)| (*id, &item.dependencies[..]))
+            .collect();
+
+        let mut used = HashSet::new();
+        let mut queue: Vec<AssetId> = usage.to_vec();
+        while let Some(id) = queue.pop() {
+            if used.insert(id) {
+                if let Some(deps) = dependencies.get(&id) {
+                    queue.extend_from_slice(deps);
                 }
+            }
+        }
+        drop(dependencies);
+
+        let mut unreferenced: Vec<_> = artifacts
+            .into_iter()
+            .filter(|(id, _)| !used.contains(id))
+            .map(|(id, item)| (id, item.source, item.target))
+            .collect();
+
+        unreferenced.sort_by(|a, b| (&a.1, &a.2).cmp(&(&b.1, &b.2)));
+        unreferenced
+    }
+
+    /// Copies asset provided by a base store into this store,
+    /// so it can be reimported and edited locally.
+    /// Asset id is preserved.
+    ///
+    /// Returns `false` if asset is not provided by a base store,
+    /// e.g. if it is already imported into this store.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreInfo};
+    /// # use argosy_store::test_util::CopyImporter;
+    /// # let store_dir = argosy_store::test_util::TempStore::new("layers");
+    /// # let root = store_dir.path();
+    /// # let (ci, dev) = (root.join("ci"), root.join("dev"));
+    /// # for dir in [&ci, &dev] { std::fs::create_dir_all(dir.join("temp")).unwrap(); }
+    /// # StoreInfo::new(None, None, Some(&ci.join("temp")), &[]).write(&ci.join("argosy.toml")).unwrap();
+    /// // Canonical store imports everything.
+    /// for name in ["hello.txt", "bye.txt"] {
+    ///     std::fs::write(ci.join(name), name).unwrap();
+    /// }
+    /// let mut base = Store::open(&ci.join("argosy.toml")).unwrap();
+    /// base.register_importer(Box::new(CopyImporter));
+    /// let (hello, hello_path, _) = futures::executor::block_on(base.store("hello.txt", None, "text")).unwrap();
+    /// let (bye, _, _) = futures::executor::block_on(base.store("bye.txt", None, "text")).unwrap();
+    ///
+    /// // Checkout of the same sources refers to the canonical store.
+    /// let mut info = StoreInfo::new(None, None, Some(&dev.join("temp")), &[]);
+    /// info.base_stores.push("../ci".into());
+    /// info.write(&dev.join("argosy.toml")).unwrap();
+    /// for name in ["hello.txt", "bye.txt"] {
+    ///     let modified = std::fs::metadata(ci.join(name)).unwrap().modified().unwrap();
+    ///     std::fs::write(dev.join(name), name).unwrap();
+    ///     std::fs::File::options().write(true).open(dev.join(name)).unwrap().set_modified(modified).unwrap();
+    /// }
+    /// let mut store = Store::open(&dev.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(CopyImporter));
+    ///
+    /// // Artifacts of the base store are used in place.
+    /// let (id, path, _) = futures::executor::block_on(store.store("hello.txt", None, "text")).unwrap();
+    /// assert_eq!((id, &path), (hello, &hello_path));
+    /// let (path, _) = futures::executor::block_on(store.fetch(bye)).unwrap();
+    /// assert_eq!(std::fs::read_to_string(path).unwrap(), "bye.txt");
+    ///
+    /// // Local changes are imported into this store only.
+    /// std::fs::write(dev.join("hello.txt"), "Hello, branch!").unwrap();
+    /// let (id, path, _) = futures::executor::block_on(store.store("hello.txt", None, "text")).unwrap();
+    /// assert_ne!(id, hello);
+    /// assert!(path.starts_with(&dev));
+    /// assert_eq!(std::fs::read_to_string(&hello_path).unwrap(), "hello.txt");
+    /// assert_eq!(futures::executor::block_on(base.store("hello.txt", None, "text")).unwrap().0, hello);
+    ///
+    /// // Promoted asset keeps its id and lives in this store.
+    /// assert!(futures::executor::block_on(store.promote(bye)).unwrap());
+    /// assert!(!futures::executor::block_on(store.promote(bye)).unwrap());
+    /// assert!(!futures::executor::block_on(store.promote(id)).unwrap());
+    /// let (path, _) = futures::executor::block_on(store.fetch(bye)).unwrap();
+    /// assert!(path.starts_with(&dev));
+    /// assert_eq!(std::fs::read_to_string(path).unwrap(), "bye.txt");
+    /// ```
+    pub async fn promote(&self, id: AssetId) -> Result<bool, StoreError> {
+        self.scan_artifacts().await;
+
+        let Some(item) = self.artifacts.read().get(&id).cloned() else {
+            return Ok(false);
+        };
+
+        let profile = self.profile.as_deref();
+        let mut meta = SourceMeta::new(&item.source, &self.base, &self.external)
+            .map_err(StoreError::MetaError)?;
 
-                *scanned = true;
+        if meta.get_asset(&item.target, profile).is_some() {
+            return Ok(false);
+        }
+
+        let Some((layer_meta, layer)) = find_in_layers(
+            &self.layers,
+            &item.source,
+            &self.base_url,
+            &item.target,
+            profile,
+        ) else {
+            return Ok(false);
+        };
+
+        let asset = layer_meta
+            .get_asset(&item.target, profile)
+            .expect("Layer is found by this asset");
 
-                drop(artifacts);
-                drop(scanned);
+        if asset.id() != id {
+            return Ok(false);
+        }
+
+        create_artifacts_dir(&self.artifacts_base).map_err(|error| {
+            StoreError::FailedToCreateArtifactsDirectory {
+                error,
+                path: self.artifacts_base.clone(),
+            }
+        })?;
+
+        let artifact_path = asset.artifact_path(&layer.artifacts);
+        let output = make_temporary(&self.temp);
+        std::fs::copy(&artifact_path, &output).map_err(|error| {
+            StoreError::MetaError(MetaError::SaveArtifactError {
+                error,
+                path: artifact_path,
+            })
+        })?;
+
+        let asset = asset
+            .with_artifact(&output, &self.artifacts_base)
+            .map_err(StoreError::MetaError)?;
+        meta.add_asset(&item.target, profile, asset, &self.base, &self.external)
+            .map_err(StoreError::MetaError)?;
+
+        Ok(true)
+    }
+
+    /// Returns format in which importers write descriptor artifacts.
+    pub fn descriptor_format(&self) -> DescriptorFormat {
+        self.descriptor_format
+    }
+
+    /// Returns name of the active profile.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Rewrites descriptor artifacts into specified format.
+    /// Following imports write descriptors in this format too.
+    ///
+    /// Descriptors are reimported with their original importers,
+    /// artifacts not marked as descriptors are left untouched.
+    /// Asset ids are preserved, unless sources were modified since last import.
+    ///
+    /// Returns number of transcoded assets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreInfo};
+    /// use argosy_import::DescriptorFormat;
+    ///
+    /// #[derive(Clone, PartialEq, Debug, argosy::Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// struct NumberImporter;
+    ///
+    /// impl argosy_import::Importer for NumberImporter {
+    ///     fn name(&self) -> &str { "Number" }
+    ///     fn formats(&self) -> &[&str] { &["text"] }
+    ///     fn extensions(&self) -> &[&str] { &["txt"] }
+    ///     fn target(&self) -> &str { "Number" }
+    ///     fn import(
+    ///         &self,
+    ///         source: &std::path::Path,
+    ///         output: &std::path::Path,
+    ///         _: &mut dyn argosy_import::Sources,
+    ///         _: &mut dyn argosy_import::Dependencies,
+    ///         sink: &mut argosy_import::OutputSink,
+    ///     ) -> Result<(), argosy_import::ImportError> {
+    ///         #[derive(serde::Serialize)]
+    ///         struct NumberInfo {
+    ///             value: u32,
+    ///         }
+    ///
+    ///         let text = std::fs::read_to_string(source).unwrap();
+    ///         let value = text.trim().parse().unwrap();
+    ///         sink.write_descriptor(output, &NumberInfo { value })
+    ///     }
+    /// }
+    ///
+    /// # let store_dir = argosy_store::test_util::TempStore::new("transcode");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("number.txt"), "42").unwrap();
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    ///
+    /// let load = |store: Store| {
+    ///     let loader = argosy::Loader::builder().with(store).build();
+    ///     runtime.block_on(async move {
+    ///         let mut number = loader.load::<Number, _>("number.txt").await.unwrap();
+    ///         number.build(&mut ()).unwrap().clone()
+    ///     })
+    /// };
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(NumberImporter));
+    /// let (id, path, _) = futures::executor::block_on(store.store("number.txt", None, "Number")).unwrap();
+    /// assert_eq!(std::fs::read(&path).unwrap()[0], b'{');
+    /// let from_json = load(store);
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(NumberImporter));
+    /// let count = futures::executor::block_on(store.transcode_artifacts(DescriptorFormat::Bincode)).unwrap();
+    /// assert_eq!(count, 1);
+    ///
+    /// let (bincode_id, path, _) = futures::executor::block_on(store.store("number.txt", None, "Number")).unwrap();
+    /// assert_eq!(bincode_id, id);
+    /// assert_eq!(std::fs::read(&path).unwrap(), 42u32.to_le_bytes());
+    /// let from_bincode = load(store);
+    ///
+    /// assert_eq!(from_json, Number { value: 42 });
+    /// assert_eq!(from_json, from_bincode);
+    /// ```
+    pub async fn transcode_artifacts(
+        &mut self,
+        format: DescriptorFormat,
+    ) -> Result<usize, StoreError> {
+        self.descriptor_format = format;
+        self.scan_artifacts().await;
+
+        let items: Vec<AssetItem> = self.artifacts.read().values().cloned().collect();
+
+        let mut count = 0;
+        for item in items {
+            let meta = SourceMeta::new(&item.source, &self.base, &self.external)
+                .map_err(StoreError::MetaError)?;
+
+            let transcode = match meta.get_asset(&item.target, self.profile.as_deref()) {
+                None => false,
+                Some(asset) => asset.descriptor().is_some_and(|f| f != format),
+            };
+
+            if transcode {
+                self.store_url_detailed(item.source, item.format.as_deref(), &item.target)
+                    .await?;
+                count += 1;
             }
         }
 
-        let item = self.artifacts.read().get(&id).cloned()?;
+        Ok(count)
+    }
 
-        let (_, path, modified) = self
-            .store_url(item.source, item.format.as_deref(), &item.target)
-            .await
-            .ok()?;
+    /// Wraps existing descriptor artifacts into [`ArtifactEnvelope`].
+    /// Following imports write enveloped descriptors too.
+    ///
+    /// Artifacts are rewritten without reimport, asset ids are preserved.
+    /// Opaque and already enveloped artifacts are left untouched.
+    ///
+    /// Returns number of wrapped artifacts.
+    ///
+    /// See [`StoreInfo::artifact_envelopes`].
+    ///
+    /// [`ArtifactEnvelope`]: argosy::ArtifactEnvelope
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreInfo};
+    /// use argosy::ArtifactEnvelope;
+    ///
+    /// #[derive(Clone, PartialEq, Debug, argosy::Asset)]
+    /// struct Number {
+    ///     value: u32,
+    /// }
+    ///
+    /// struct NumberImporter;
+    ///
+    /// impl argosy_import::Importer for NumberImporter {
+    ///     fn name(&self) -> &str { "Number" }
+    ///     fn formats(&self) -> &[&str] { &["text"] }
+    ///     fn extensions(&self) -> &[&str] { &["txt"] }
+    ///     fn target(&self) -> &str { "Number" }
+    ///     fn import(
+    ///         &self,
+    ///         source: &std::path::Path,
+    ///         output: &std::path::Path,
+    ///         _: &mut dyn argosy_import::Sources,
+    ///         _: &mut dyn argosy_import::Dependencies,
+    ///         sink: &mut argosy_import::OutputSink,
+    ///     ) -> Result<(), argosy_import::ImportError> {
+    ///         #[derive(serde::Serialize)]
+    ///         struct NumberInfo {
+    ///             value: u32,
+    ///         }
+    ///
+    ///         let text = std::fs::read_to_string(source).unwrap();
+    ///         let value = text.trim().parse().unwrap();
+    ///         sink.write_descriptor(output, &NumberInfo { value })
+    ///     }
+    /// }
+    ///
+    /// # let store_dir = argosy_store::test_util::TempStore::new("envelope");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("number.txt"), "42").unwrap();
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(NumberImporter));
+    /// let (id, path, _) = futures::executor::block_on(store.store("number.txt", None, "Number")).unwrap();
+    /// assert_eq!(std::fs::read(&path).unwrap()[0], b'{');
+    ///
+    /// assert_eq!(futures::executor::block_on(store.envelope_artifacts()).unwrap(), 1);
+    /// assert_eq!(futures::executor::block_on(store.envelope_artifacts()).unwrap(), 0);
+    ///
+    /// let (wrapped_id, path, _) = futures::executor::block_on(store.store("number.txt", None, "Number")).unwrap();
+    /// assert_eq!(wrapped_id, id);
+    /// assert!(std::fs::read(&path).unwrap().starts_with(&ArtifactEnvelope::MAGIC));
+    ///
+    /// // Reimported descriptors are enveloped as well.
+    /// std::fs::write(base.join("number.txt"), "7").unwrap();
+    /// let (_, path, _) = futures::executor::block_on(store.store("number.txt", None, "Number")).unwrap();
+    /// assert!(std::fs::read(&path).unwrap().starts_with(&ArtifactEnvelope::MAGIC));
+    ///
+    /// let loader = argosy::Loader::builder().with(store).build();
+    /// let number = tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let mut number = loader.load::<Number, _>("number.txt").await.unwrap();
+    ///         number.build(&mut ()).unwrap().clone()
+    ///     });
+    /// assert_eq!(number, Number { value: 7 });
+    /// ```
+    pub async fn envelope_artifacts(&mut self) -> Result<usize, StoreError> {
+        self.artifact_envelopes = true;
+        self.scan_artifacts().await;
+
+        let items: Vec<AssetItem> = self.artifacts.read().values().cloned().collect();
+
+        let mut count = 0;
+        for item in items {
+            let mut meta = SourceMeta::new(&item.source, &self.base, &self.external)
+                .map_err(StoreError::MetaError)?;
+
+            let Some(asset) = meta.get_asset(&item.target, self.profile.as_deref()) else {
+                continue;
+            };
+
+            let Some(format) = asset.descriptor() else {
+                continue;
+            };
+
+            let artifact_path = asset.artifact_path(&self.artifacts_base);
+            let output = make_temporary(&self.temp);
 
-        Some((path, modified))
+            let wrapped = envelope_artifact(&artifact_path, &output, format).map_err(|error| {
+                StoreError::MetaError(MetaError::SaveArtifactError {
+                    error,
+                    path: artifact_path.clone(),
+                })
+            })?;
+
+            if wrapped {
+                let asset = asset
+                    .with_artifact(&output, &self.artifacts_base)
+                    .map_err(StoreError::MetaError)?;
+                meta.add_asset(
+                    &item.target,
+                    self.profile.as_deref(),
+                    asset,
+                    &self.base,
+                    &self.external,
+                )
+                .map_err(StoreError::MetaError)?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
     }
 
-    /// Fetch asset data path.
+    /// Reimports all assets produced by importer with specified name,
+    /// including assets where it was one of the pipeline stages.
+    /// Assets are reimported even if they are up to date.
+    /// Asset ids are preserved, unless sources were modified since last import.
+    ///
+    /// Returns number of reimported assets.
+    ///
+    /// Assets imported with older version of registered importer
+    /// are reimported on the next store without calling this method.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::atomic::{AtomicU32, Ordering};
+    /// # use argosy_store::{Store, StoreInfo};
+    /// /// Importer that copies the source.
+    /// struct Copy {
+    ///     name: &'static str,
+    ///     target: &'static str,
+    ///     version: &'static AtomicU32,
+    /// }
+    ///
+    /// impl argosy_import::Importer for Copy {
+    ///     fn name(&self) -> &str { self.name }
+    ///     fn formats(&self) -> &[&str] { &["text"] }
+    ///     fn extensions(&self) -> &[&str] { &["txt"] }
+    ///     fn target(&self) -> &str { self.target }
+    ///     fn version(&self) -> u32 { self.version.load(Ordering::Relaxed) }
+    ///     fn import(
+    ///         &self,
+    ///         source: &std::path::Path,
+    ///         output: &std::path::Path,
+    ///         _: &mut dyn argosy_import::Sources,
+    ///         _: &mut dyn argosy_import::Dependencies,
+    ///         _: &mut argosy_import::OutputSink,
+    ///     ) -> Result<(), argosy_import::ImportError> {
+    ///         std::fs::copy(source, output).unwrap();
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// static UPPER: AtomicU32 = AtomicU32::new(1);
+    /// static LOWER: AtomicU32 = AtomicU32::new(1);
+    ///
+    /// # let store_dir = argosy_store::test_util::TempStore::new("reimport");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("a.txt"), "a").unwrap();
+    /// std::fs::write(base.join("b.txt"), "b").unwrap();
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(Copy { name: "Upper", target: "upper", version: &UPPER }));
+    /// store.register_importer(Box::new(Copy { name: "Lower", target: "lower", version: &LOWER }));
+    ///
+    /// let store_all = |store: &Store| {
+    ///     let mut reimported = Vec::new();
+    ///     for source in ["a.txt", "b.txt"] {
+    ///         for target in ["upper", "lower"] {
+    ///             let outcome = futures::executor::block_on(store.store_detailed(source, None, target)).unwrap();
+    ///             if outcome.reimported {
+    ///                 reimported.push(format!("{source}:{target}"));
+    ///             }
+    ///         }
+    ///     }
+    ///     reimported
+    /// };
+    ///
+    /// assert_eq!(store_all(&store).len(), 4);
+    /// assert!(store_all(&store).is_empty());
+    ///
+    /// // Only assets of the updated importer are reimported.
+    /// UPPER.store(2, Ordering::Relaxed);
+    /// assert_eq!(store_all(&store), ["a.txt:upper", "b.txt:upper"]);
+    ///
+    /// let (id, _, _) = futures::executor::block_on(store.store("a.txt", None, "lower")).unwrap();
+    /// let count = futures::executor::block_on(store.reimport_by_importer("Lower")).unwrap();
+    /// assert_eq!(count, 2);
+    ///
+    /// let (reimported_id, _, _) = futures::executor::block_on(store.store("a.txt", None, "lower")).unwrap();
+    /// assert_eq!(reimported_id, id);
+    /// ```
+    pub async fn reimport_by_importer(&self, name: &str) -> Result<usize, StoreError> {
+        let outcome = self
+            .reimport_by_importer_detailed(name, &CancelToken::new())
+            .await?;
+
+        let mut count = 0;
+        for item in outcome.items {
+            match item {
+                BatchItem::Failed(err) => return Err(err),
+                BatchItem::Done => count += 1,
+                BatchItem::Cancelled => {}
+            }
+        }
+        Ok(count)
+    }
+
+    /// Reimports all assets produced by importer with specified name,
+    /// stopping when `cancel` is cancelled.
+    ///
+    /// Returns outcome of each asset, ordered by source URL and target.
+    /// Failure of one asset does not stop reimporting others.
+    /// Reimport that is running when `cancel` is cancelled finishes,
+    /// assets not reimported yet are reported as [`BatchItem::Cancelled`].
+    ///
+    /// See [`Store::reimport_by_importer`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy::{BatchItem, CancelToken};
+    /// # use argosy_store::{test_util::CopyImporter, Store};
+    /// # let store_dir = argosy_store::test_util::TempStore::new("reimport-detailed");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("a.txt"), "a").unwrap();
+    /// std::fs::write(base.join("b.txt"), "b").unwrap();
+    ///
+    /// let mut store = Store::open(&store_dir.meta()).unwrap();
+    /// store.register_importer(Box::new(CopyImporter));
+    /// for source in ["a.txt", "b.txt"] {
+    ///     futures::executor::block_on(store.store(source, None, "text")).unwrap();
+    /// }
+    ///
+    /// let cancel = CancelToken::new();
+    /// let outcome = futures::executor::block_on(store.reimport_by_importer_detailed("Copy", &cancel)).unwrap();
+    /// assert_eq!(outcome.succeeded(), 2);
+    ///
+    /// // Shutting down.
+    /// cancel.cancel();
+    /// let outcome = futures::executor::block_on(store.reimport_by_importer_detailed("Copy", &cancel)).unwrap();
+    /// assert!(outcome.items.iter().all(BatchItem::is_cancelled));
+    /// assert_eq!(outcome.cancelled(), 2);
+    /// ```
+    pub async fn reimport_by_importer_detailed(
+        &self,
+        name: &str,
+        cancel: &CancelToken,
+    ) -> Result<BatchOutcome<StoreError>, StoreError> {
+        self.scan_artifacts().await;
+
+        let mut items: Vec<AssetItem> = self.artifacts.read().values().cloned().collect();
+        items.sort_by(|a, b| (&a.source, &a.target).cmp(&(&b.source, &b.target)));
+
+        // Assets reimported with new ids are listed under old ids too.
+        items.dedup_by(|a, b| a.source == b.source && a.target == b.target);
+
+        let mut produced_items = Vec::new();
+        for item in items {
+            let meta = SourceMeta::new(&item.source, &self.base, &self.external)
+                .map_err(StoreError::MetaError)?;
+
+            let produced = match meta.get_asset(&item.target, self.profile.as_deref()) {
+                None => false,
+                Some(asset) => asset.stages().iter().any(|stage| stage.importer == name),
+            };
+
+            // In-memory sources are not kept.
+            let memory = matches!(item.source.scheme().parse(), Ok(Scheme::Mem));
+
+            if produced && !memory {
+                produced_items.push(item);
+            }
+        }
+
+        let mut outcome = BatchOutcome {
+            items: Vec::with_capacity(produced_items.len()),
+        };
+        for item in produced_items {
+            if cancel.is_cancelled() {
+                outcome.items.push(BatchItem::Cancelled);
+                continue;
+            }
+
+            let result = self
+                .store_url_impl(
+                    item.source,
+                    item.format.as_deref(),
+                    &item.target,
+                    true,
+                    Sources::new(),
+                )
+                .await;
+            outcome.items.push(match result {
+                Ok(_) => BatchItem::Done,
+                Err(err) => BatchItem::Failed(err),
+            });
+        }
+
+        Ok(outcome)
+    }
+
+    /// Packs artifacts not larger than `threshold` bytes into a single file
+    /// in the artifacts directory, replacing previous pack.
+    /// Assets are reimported if needed before packing.
+    ///
+    /// The loader fetches the pack with [`Source::bulk_hint`]
+    /// and serves packed assets without opening their artifacts,
+    /// larger assets are loaded individually.
+    /// Pack is a snapshot, assets reimported after packing are served stale
+    /// until the pack is rebuilt or the loader picks them up with [`Source::update`].
+    ///
+    /// Returns number of packed assets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreInfo};
+    /// # #[derive(Clone)]
+    /// # struct Text(Box<[u8]>);
+    /// # impl argosy::TrivialAsset for Text {
+    /// #     type Error = std::convert::Infallible;
+    /// #     fn asset_name() -> argosy::AssetName { argosy::AssetName::new("Text") }
+    /// #     fn decode(bytes: Box<[u8]>) -> Result<Self, Self::Error> { Ok(Text(bytes)) }
+    /// # }
+    /// # use argosy_store::test_util::CopyImporter;
+    /// # let store_dir = argosy_store::test_util::TempStore::new("pack-small");
+    /// # let base = store_dir.path();
+    /// std::fs::write(base.join("small.txt"), "Hi").unwrap();
+    /// std::fs::write(base.join("large.txt"), "Hello, world!").unwrap();
+    ///
+    /// let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    /// store.register_importer(Box::new(CopyImporter));
+    ///
+    /// let (small, small_path, _) = futures::executor::block_on(store.store("small.txt", None, "text")).unwrap();
+    /// let (large, _, _) = futures::executor::block_on(store.store("large.txt", None, "text")).unwrap();
+    ///
+    /// assert_eq!(futures::executor::block_on(store.pack_small_assets(8)).unwrap(), 1);
+    ///
+    /// // Packed asset does not need its artifact anymore.
+    /// std::fs::remove_file(&small_path).unwrap();
+    ///
+    /// let loader = argosy::Loader::builder().with(store).build();
+    /// tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap()
+    ///     .block_on(async move {
+    ///         let mut text = loader.load::<Text, _>(small).await.unwrap();
+    ///         assert_eq!(&*text.build(&mut ()).unwrap().0, b"Hi");
+    ///         assert_eq!(text.metadata().properties.get("target").map(|t| &**t), Some("text"));
+    ///
+    ///         let mut text = loader.load::<Text, _>(large).await.unwrap();
+    ///         assert_eq!(&*text.build(&mut ()).unwrap().0, b"Hello, world!");
+    ///     });
+    /// ```
+    ///
+    /// [`Source::bulk_hint`]: argosy::Source::bulk_hint
+    /// [`Source::update`]: argosy::Source::update
+    pub async fn pack_small_assets(&self, threshold: u64) -> Result<usize, StoreError> {
+        self.scan_artifacts().await;
+
+        let ids: Vec<AssetId> = self.artifacts.read().keys().copied().collect();
+        let pack_path = self.artifacts_base.join(SMALL_PACK_NAME);
+        let pack_error = |error| StoreError::FailedToPackArtifacts {
+            error,
+            path: pack_path.clone(),
+        };
+
+        let mut inline = argosy::InlineAssets::new();
+        for id in ids {
+            let Some((outcome, reader)) =
+                self.open_artifact_detailed(id).await.map_err(pack_error)?
+            else {
+                continue;
+            };
+            if reader.info().len > threshold {
+                continue;
+            }
+
+            let properties = artifact_properties(&outcome, reader.info());
+            let bytes = reader.read_all().map_err(pack_error)?;
+            inline.insert(
+                id,
+                argosy::AssetData {
+                    bytes: bytes.into(),
+                    version: modified_to_version(outcome.store.modified),
+                    properties,
+                },
+            );
+        }
+
+        create_artifacts_dir(&self.artifacts_base).map_err(|error| {
+            StoreError::FailedToCreateArtifactsDirectory {
+                error,
+                path: self.artifacts_base.clone(),
+            }
+        })?;
+
+        // Pack is replaced at once, so the loader never reads partially written one.
+        let output = make_temporary(&self.temp);
+        let mut data = Vec::new();
+        inline.write(&mut data).map_err(pack_error)?;
+        std::fs::write(&output, data).map_err(pack_error)?;
+        std::fs::rename(&output, &pack_path).map_err(pack_error)?;
+
+        Ok(inline.len())
+    }
+
+    /// Adds artifacts from meta files to the known artifacts.
+    /// Scans only once, on a separate thread.
+    async fn scan_artifacts(&self) {
+        if *self.scanned.read() {
+            return;
+        }
+
+        let _guard = self.scan_lock.lock().await;
+        if *self.scanned.read() {
+            return;
+        }
+
+        let (found, _) = run_scan(self.scan_job(false)).await;
+        self.add_scanned(found, false);
+    }
+
+    /// Same as [`Store::scan_artifacts`], but scans on the current thread.
+    fn scan_artifacts_blocking(&self) {
+        if *self.scanned.read() {
+            return;
+        }
+
+        let (found, _) = self.scan_job(false).run();
+        self.add_scanned(found, false);
+    }
+
+    /// Rescans meta files and replaces known artifacts with found ones.
+    ///
+    /// Scan results are kept in the index file in the artifacts directory.
+    /// Unless `force` is set, directories and meta files that were not modified
+    /// since they were indexed are not read again.
+    /// Initial scan on first use is incremental as well.
+    ///
+    /// Returns statistics of the scan.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argosy_store::{Store, StoreInfo};
+    /// # use argosy_store::test_util::CopyImporter;
+    /// # let store_dir = argosy_store::test_util::TempStore::new("rescan");
+    /// # let base = store_dir.path();
+    /// # std::fs::create_dir_all(base.join("textures")).unwrap();
+    /// let open = || {
+    ///     let mut store = Store::open(&base.join("argosy.toml")).unwrap();
+    ///     store.register_importer(Box::new(CopyImporter));
+    ///     store
+    /// };
+    /// let import = |store: &Store, source: &str| {
+    ///     futures::executor::block_on(store.store(source, None, "text")).unwrap()
+    /// };
+    ///
+    /// std::fs::write(base.join("textures/wall.txt"), "wall").unwrap();
+    /// let (wall, _, _) = import(&open(), "textures/wall.txt");
+    ///
+    /// // First scan walks the whole tree.
+    /// let store = open();
+    /// let stats = futures::executor::block_on(store.rescan(false));
+    /// assert!(stats.dirs_read > 0);
+    /// assert_eq!(stats.metas_read, 1);
+    /// assert!(futures::executor::block_on(store.fetch(wall)).is_some());
+    ///
+    /// // Nothing changed, index is used.
+    /// let store = open();
+    /// let stats = futures::executor::block_on(store.rescan(false));
+    /// assert_eq!((stats.dirs_read, stats.metas_read, stats.metas_reused), (0, 0, 1));
+    ///
+    /// // New meta file is picked up by reading only its directory.
+    /// std::fs::write(base.join("textures/floor.txt"), "floor").unwrap();
+    /// let (floor, _, _) = import(&store, "textures/floor.txt");
+    ///
+    /// let store = open();
+    /// let stats = futures::executor::block_on(store.rescan(false));
+    /// assert_eq!((stats.dirs_read, stats.metas_read, stats.metas_reused), (1, 1, 1));
+    /// assert!(futures::executor::block_on(store.fetch(floor)).is_some());
+    ///
+    /// // Forced scan ignores the index.
+    /// let stats = futures::executor::block_on(store.rescan(true));
+    /// assert_eq!(stats.metas_read, 2);
+    /// ```
+    pub async fn rescan(&self, force: bool) -> ScanStats {
+        let _guard = self.scan_lock.lock().await;
+
+        let (found, stats) = run_scan(self.scan_job(force)).await;
+        self.add_scanned(found, true);
+        stats
+    }
+
+    /// Returns scan of meta files of this store and base stores.
+    fn scan_job(&self, force: bool) -> ScanJob {
+        let mut roots = vec![ScanRoot {
+            base: self.base.clone(),
+            external: self.external.clone(),
+            skip: vec![
+                self.artifacts_base.clone(),
+                self.external.clone(),
+                self.temp.clone(),
+            ],
+        }];
+
+        roots.extend(self.layers.iter().map(|layer| ScanRoot {
+            base: layer.base.clone(),
+            external: layer.external.clone(),
+            skip: vec![layer.artifacts.clone(), layer.external.clone()],
+        }));
+
+        ScanJob {
+            index: self.artifacts_base.join(SCAN_INDEX_NAME),
+            profile: self.profile.clone(),
+            roots,
+            force,
+        }
+    }
+
+    /// Adds scanned assets to the known artifacts.
+    /// Known artifacts are replaced if `replace` is set.
+    ///
+    /// First list contains assets of this store, others are assets of base stores.
+    fn add_scanned(&self, found: Vec<Vec<ScannedAsset>>, replace: bool) {
+        let mut found = found.into_iter();
+        let mut artifacts = self.artifacts.write();
+        if replace {
+            artifacts.clear();
+        }
+
+        for asset in found.next().into_iter().flatten() {
+            let (id, item) = asset_item(asset);
+            artifacts.entry(id).or_insert(item);
+        }
+
+        for (layer, layer_artifacts) in self.layers.iter().zip(found) {
+            for asset in layer_artifacts {
+                let (id, mut item) = asset_item(asset);
+                item.source = layer.local_url(&item.source, &self.base_url);
+
+                match artifacts.get(&id) {
+                    None => {
+                        artifacts.insert(id, item);
+                    }
+                    Some(existing)
+                        if existing.source != item.source || existing.target != item.target =>
+                    {
+                        tracing::warn!(
+                            "Asset {} of base store '{}' ('{}' as '{}') collides with '{}' as '{}'. Asset of the store with higher precedence is used",
+                            id,
+                            layer.base.display(),
+                            item.source,
+                            item.target,
+                            existing.source,
+                            existing.target,
+                        );
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        *self.scanned.write() = true;
+    }
+
+    /// Find asset id by source and target.
+    /// Stores asset if it is not stored yet.
     pub async fn find_asset(
         &self,
         source: &str,
         target: &str,
     ) -> Result<Option<AssetId>, StoreError> {
+        let outcome = self.find_asset_detailed(source, target).await?;
+        Ok(outcome.id)
+    }
+
+    /// Find asset id by source and target.
+    /// Stores asset if it is not stored yet.
+    /// Returns detailed outcome of the operation.
+    pub async fn find_asset_detailed(
+        &self,
+        source: &str,
+        target: &str,
+    ) -> Result<FindOutcome, StoreError> {
         let source_url =
             self.base_url
                 .join(source)
@@ -658,10 +2781,17 @@ impl Store {
         let meta = SourceMeta::new(&source_url, &self.base, &self.external)
             .map_err(StoreError::MetaError)?;
 
-        match meta.get_asset(target) {
+        let profile = self.profile.as_deref();
+        let id = match meta.get_asset(target, profile) {
+            Some(asset) => Some(asset.id()),
+            None => find_in_layers(&self.layers, &source_url, &self.base_url, target, profile)
+                .and_then(|(meta, _)| meta.get_asset(target, profile).map(AssetMeta::id)),
+        };
+
+        match id {
             None => {
                 drop(meta);
-                match self.store(source, None, target).await {
+                match self.store_detailed(source, None, target).await {
                     Err(err) => {
                         tracing::warn!(
                             "Failed to store '{}' as '{}' on lookup. {:#}",
@@ -669,16 +2799,103 @@ impl Store {
                             target,
                             err
                         );
-                        Ok(None)
+                        Ok(FindOutcome {
+                            id: None,
+                            stored: None,
+                            error: Some(err.to_string()),
+                        })
                     }
-                    Ok((id, _, _)) => Ok(Some(id)),
+                    Ok(outcome) => Ok(FindOutcome {
+                        id: Some(outcome.id),
+                        stored: Some(outcome),
+                        error: None,
+                    }),
                 }
             }
-            Some(asset) => Ok(Some(asset.id())),
+            Some(id) => Ok(FindOutcome {
+                id: Some(id),
+                stored: None,
+                error: None,
+            }),
         }
     }
 }
 
+/// Returns artifacts and external directories of the store
+/// with optional active profile.
+fn store_dirs(
+    base: &Path,
+    meta: &StoreInfo,
+    profile: Option<(&str, &ProfileInfo)>,
+) -> (PathBuf, PathBuf) {
+    let artifacts = base.join(
+        meta.artifacts
+            .clone()
+            .unwrap_or_else(|| Path::new(DEFAULT_AUX).join(DEFAULT_ARTIFACTS)),
+    );
+
+    let external = base.join(
+        meta.external
+            .clone()
+            .unwrap_or_else(|| Path::new(DEFAULT_AUX).join(DEFAULT_EXTERNAL)),
+    );
+
+    let artifacts = match profile {
+        None => artifacts,
+        Some((name, profile)) => match &profile.artifacts {
+            None => artifacts.join(name),
+            Some(path) => base.join(path),
+        },
+    };
+
+    (artifacts, external)
+}
+
+/// Opens base store in the directory as read-only layer.
+fn open_layer(path: &Path, profile: Option<&str>) -> Result<Layer, OpenStoreError> {
+    let meta = StoreInfo::read(&path.join(ARGOSY_META_NAME))?;
+    let base = dunce::canonicalize(path).map_err(|error| OpenStoreError::CanonError {
+        error,
+        path: path.to_owned(),
+    })?;
+    let base_url =
+        Url::from_directory_path(&base).expect("Canonical path must be convertible to URL");
+
+    // Base store may lack the profile, then default directory of the profile is used.
+    let default_profile = ProfileInfo::default();
+    let profile = profile.map(|name| (name, meta.profiles.get(name).unwrap_or(&default_profile)));
+    let (artifacts, external) = store_dirs(&base, &meta, profile);
+
+    Ok(Layer {
+        base,
+        base_url,
+        artifacts,
+        external,
+    })
+}
+
+/// Creates artifacts directory if it does not exist.
+fn create_artifacts_dir(artifacts_base: &Path) -> std::io::Result<()> {
+    if !artifacts_base.exists() {
+        std::fs::create_dir_all(artifacts_base)?;
+
+        if let Err(err) = std::fs::write(artifacts_base.join(".gitignore"), "*") {
+            tracing::error!(
+                "Failed to place .gitignore into artifacts directory. {:#}",
+                err
+            );
+        }
+    }
+    Ok(())
+}
+
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    match suggestion {
+        None => String::new(),
+        Some(suggestion) => format!(". Did you mean '{suggestion}'?"),
+    }
+}
+
 pub fn find_argosy_info(path: &Path) -> Option<PathBuf> {
     for path in path.ancestors() {
         let candidate = path.join(ARGOSY_META_NAME);
@@ -701,156 +2918,32 @@ fn url_ext(url: &Url) -> Option<&str> {
     }
 }
 
-fn scan_external(
-    external: &Path,
-    existing_artifacts: &HashSet<AssetId>,
-    artifacts: &mut Vec<(AssetId, AssetItem)>,
-) {
-    let dir = match std::fs::read_dir(&external) {
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
-            tracing::info!("External directory does not exists");
-            return;
-        }
-        Err(err) => {
-            tracing::error!(
-                "Failed to scan directory '{}'. {:#}",
-                external.display(),
-                err
-            );
-            return;
-        }
-        Ok(dir) => dir,
-    };
-    for e in dir {
-        let e = match e {
-            Err(err) => {
-                tracing::error!(
-                    "Failed to read entry in directory '{}'. {:#}",
-                    external.display(),
-                    err,
-                );
-                return;
-            }
-            Ok(e) => e,
-        };
-        let name = e.file_name();
-        let path = external.join(&name);
-        let ft = match e.file_type() {
-            Err(err) => {
-                tracing::error!("Failed to check '{}'. {:#}", path.display(), err);
-                continue;
-            }
-            Ok(ft) => ft,
-        };
-        if ft.is_file() && !SourceMeta::is_local_meta_path(&path) {
-            let meta = match SourceMeta::open_external(&path) {
-                Err(err) => {
-                    tracing::error!("Failed to scan meta file '{}'. {:#}", path.display(), err);
-                    continue;
-                }
-                Ok(meta) => meta,
-            };
-
-            let source = meta.url();
-
-            for (target, asset) in meta.assets() {
-                if !existing_artifacts.contains(&asset.id()) {
-                    artifacts.push((
-                        asset.id(),
-                        AssetItem {
-                            source: source.clone(),
-                            format: asset.format().map(ToOwned::to_owned),
-                            target: target.to_owned(),
-                        },
-                    ));
-                }
-            }
-        }
-    }
+fn asset_item(asset: ScannedAsset) -> (AssetId, AssetItem) {
+    (
+        asset.id,
+        AssetItem {
+            source: asset.source,
+            format: asset.format,
+            target: asset.target,
+            dependencies: asset.dependencies,
+        },
+    )
 }
 
-fn scan_local(
-    base: &Path,
-    existing_artifacts: &HashSet<AssetId>,
-    artifacts: &mut Vec<(AssetId, AssetItem)>,
-) {
-    debug_assert!(base.is_absolute());
-
-    if !base.exists() {
-        tracing::info!("Local artifacts directory does not exists");
-        return;
-    }
-
-    let mut queue = VecDeque::new();
-    queue.push_back(base.to_owned());
-
-    while let Some(dir_path) = queue.pop_front() {
-        let dir = match std::fs::read_dir(&dir_path) {
-            Err(err) => {
-                tracing::error!(
-                    "Failed to scan directory '{}'. {:#}",
-                    dir_path.display(),
-                    err
-                );
-                continue;
-            }
-            Ok(dir) => dir,
-        };
-        for e in dir {
-            let e = match e {
-                Err(err) => {
-                    tracing::error!(
-                        "Failed to read entry in directory '{}'. {:#}",
-                        dir_path.display(),
-                        err,
-                    );
-                    continue;
-                }
-                Ok(e) => e,
-            };
-            let name = e.file_name();
-            let path = dir_path.join(&name);
-            let ft = match e.file_type() {
-                Err(err) => {
-                    tracing::error!("Failed to check '{}'. {:#}", path.display(), err);
-                    continue;
-                }
-                Ok(ft) => ft,
-            };
-            if ft.is_dir() {
-                queue.push_back(path);
-            } else if ft.is_file() && SourceMeta::is_local_meta_path(&path) {
-                let meta = match SourceMeta::open_local(&path) {
-                    Err(err) => {
-                        tracing::error!("Failed to scan meta file '{}'. {:#}", path.display(), err);
-                        continue;
-                    }
-                    Ok(meta) => meta,
-                };
-
-                let source = meta.url();
-                for (target, asset) in meta.assets() {
-                    if !existing_artifacts.contains(&asset.id()) {
-                        artifacts.push((
-                            asset.id(),
-                            AssetItem {
-                                source: source.clone(),
-                                format: asset.format().map(ToOwned::to_owned),
-                                target: target.to_owned(),
-                            },
-                        ));
-                    }
-                }
-            }
-        }
-    }
+/// Runs scan on a separate thread to not block the executor.
+async fn run_scan(job: ScanJob) -> (Vec<Vec<ScannedAsset>>, ScanStats) {
+    let (tx, rx) = futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(job.run());
+    });
+    rx.await.expect("Scan thread panicked")
 }
 
 impl argosy::Source for Store {
     #[inline]
     fn find<'a>(&'a self, key: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
         Box::pin(async move {
-            match self.find_asset(&key, &asset).await {
+            match self.find_asset(key, asset).await {
                 Err(err) => {
                     tracing::error!("Error while searching for asset '{asset} @ {key}': {err}");
                     None
@@ -861,25 +2954,55 @@ impl argosy::Source for Store {
         })
     }
 
+    #[inline]
+    fn find_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+        asset: &'a str,
+    ) -> BoxStream<'a, (String, AssetId)> {
+        futures::stream::iter(self.find_stored_prefix(prefix, asset)).boxed()
+    }
+
     #[inline]
     fn load<'a>(
         &'a self,
         id: AssetId,
     ) -> BoxFuture<'a, Result<Option<argosy::AssetData>, argosy::Error>> {
         Box::pin(async move {
-            match self.fetch(id).await {
+            match self
+                .open_artifact_detailed(id)
+                .await
+                .map_err(argosy::Error::new)?
+            {
                 None => Ok(None),
-                Some((path, modified)) => {
-                    let bytes = std::fs::read(&path).map_err(argosy::Error::new)?;
+                Some((outcome, reader)) => {
+                    let properties = artifact_properties(&outcome, reader.info());
+                    let bytes = reader.read_all().map_err(argosy::Error::new)?;
                     Ok(Some(argosy::AssetData {
-                        bytes: bytes.into_boxed_slice(),
-                        version: modified_to_version(modified),
+                        properties,
+                        bytes: bytes.into(),
+                        version: modified_to_version(outcome.store.modified),
                     }))
                 }
             }
         })
     }
 
+    /// Serves small assets packed with [`Store::pack_small_assets`].
+    fn bulk_hint<'a>(
+        &'a self,
+    ) -> BoxFuture<'a, Result<Option<argosy::InlineAssets>, argosy::Error>> {
+        Box::pin(async move {
+            let data = match std::fs::read(self.artifacts_base.join(SMALL_PACK_NAME)) {
+                Ok(data) => data,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                Err(err) => return Err(argosy::Error::new(err)),
+            };
+            let inline = argosy::InlineAssets::read(&data).map_err(argosy::Error::new)?;
+            Ok(Some(inline))
+        })
+    }
+
     #[inline]
     fn update<'a>(
         &'a self,
@@ -887,16 +3010,22 @@ impl argosy::Source for Store {
         version: u64,
     ) -> BoxFuture<'a, Result<Option<argosy::AssetData>, argosy::Error>> {
         Box::pin(async move {
-            match self.fetch(id).await {
+            match self
+                .open_artifact_detailed(id)
+                .await
+                .map_err(argosy::Error::new)?
+            {
                 None => Ok(None),
-                Some((path, modified)) => {
-                    if modified_to_version(modified) <= version {
+                Some((outcome, reader)) => {
+                    if modified_to_version(outcome.store.modified) <= version {
                         return Ok(None);
                     }
-                    let bytes = std::fs::read(&path).map_err(argosy::Error::new)?;
+                    let properties = artifact_properties(&outcome, reader.info());
+                    let bytes = reader.read_all().map_err(argosy::Error::new)?;
                     Ok(Some(argosy::AssetData {
-                        bytes: bytes.into_boxed_slice(),
-                        version: modified_to_version(modified),
+                        properties,
+                        bytes: bytes.into(),
+                        version: modified_to_version(outcome.store.modified),
                     }))
                 }
             }
@@ -904,6 +3033,18 @@ impl argosy::Source for Store {
     }
 }
 
+/// Returns properties of the fetched artifact reported to the loader.
+fn artifact_properties(outcome: &FetchOutcome, info: &ArtifactInfo) -> argosy::AssetProperties {
+    let mut properties = argosy::AssetProperties::new()
+        .with("source", outcome.source.as_str())
+        .with("target", &*outcome.target)
+        .with("sha256", hex(info.sha256));
+    if let Some(importer) = &outcome.store.importer {
+        properties.insert("importer", &**importer);
+    }
+    properties
+}
+
 #[inline]
 fn modified_to_version(modified: SystemTime) -> u64 {
     modified
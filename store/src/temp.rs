@@ -13,7 +13,7 @@ pub fn make_temporary(base: &Path) -> PathBuf {
         let key_bytes = key.to_le_bytes();
         let mut filename = [0; 22];
         let len = GeneralPurpose::new(&URL_SAFE, NO_PAD)
-            .encode_slice(&key_bytes, &mut filename)
+            .encode_slice(key_bytes, &mut filename)
             .unwrap();
         debug_assert_eq!(len, 22);
         let path = base.join(std::str::from_utf8(&filename).unwrap());
@@ -22,3 +22,41 @@ pub fn make_temporary(base: &Path) -> PathBuf {
         }
     }
 }
+
+/// Temporary files that are removed when dropped.
+pub struct Temporaries {
+    base: PathBuf,
+    paths: Vec<PathBuf>,
+}
+
+impl Temporaries {
+    pub fn new(base: &Path) -> Self {
+        Temporaries {
+            base: base.to_owned(),
+            paths: Vec::new(),
+        }
+    }
+
+    /// Returns new temporary path that is removed with this value.
+    pub fn make(&mut self) -> PathBuf {
+        let path = make_temporary(&self.base);
+        self.paths.push(path.clone());
+        path
+    }
+}
+
+impl Drop for Temporaries {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            if let Err(err) = std::fs::remove_file(path) {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!(
+                        "Failed to remove temporary file '{}'. {:#}",
+                        path.display(),
+                        err
+                    );
+                }
+            }
+        }
+    }
+}
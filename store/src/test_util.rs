@@ -0,0 +1,83 @@
+//! Fixtures shared by examples and tests of the store.
+//! Available with `test-util` feature.
+
+use std::path::{Path, PathBuf};
+
+use argosy_import::{Dependencies, ImportError, Importer, OutputSink, Sources};
+
+use crate::{store::ARGOSY_META_NAME, StoreInfo};
+
+/// Importer that copies source file to the artifact.
+///
+/// Imports `text` format from `.txt` files to `text` target.
+pub struct CopyImporter;
+
+impl Importer for CopyImporter {
+    fn name(&self) -> &str {
+        "Copy"
+    }
+
+    fn formats(&self) -> &[&str] {
+        &["text"]
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["txt"]
+    }
+
+    fn target(&self) -> &str {
+        "text"
+    }
+
+    fn import(
+        &self,
+        source: &Path,
+        output: &Path,
+        _sources: &mut dyn Sources,
+        _dependencies: &mut dyn Dependencies,
+        _sink: &mut OutputSink,
+    ) -> Result<(), ImportError> {
+        std::fs::copy(source, output)
+            .map(|_| ())
+            .map_err(|err| ImportError::Other {
+                reason: err.to_string(),
+            })
+    }
+}
+
+/// Directory with empty store in system temporary directory.
+/// Removed when dropped.
+pub struct TempStore {
+    base: PathBuf,
+}
+
+impl TempStore {
+    /// Creates store directory unique for the `name` and current process.
+    /// Leftovers of previous runs are removed.
+    pub fn new(name: &str) -> Self {
+        let base = std::env::temp_dir().join(format!("argosy-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(base.join("temp")).unwrap();
+        let store = TempStore { base };
+        StoreInfo::new(None, None, Some(&store.base.join("temp")), &[])
+            .write(&store.meta())
+            .unwrap();
+        store
+    }
+
+    /// Returns base directory of the store.
+    pub fn path(&self) -> &Path {
+        &self.base
+    }
+
+    /// Returns path to the store metadata file.
+    pub fn meta(&self) -> PathBuf {
+        self.base.join(ARGOSY_META_NAME)
+    }
+}
+
+impl Drop for TempStore {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.base);
+    }
+}
@@ -0,0 +1,46 @@
+//! Names of asset types implemented before `Asset::asset_name`.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use std::convert::Infallible;
+
+use argosy::*;
+use common::*;
+
+/// Asset that names itself with deprecated `&'static str` method.
+#[derive(Clone, Debug)]
+struct Legacy;
+
+impl TrivialAsset for Legacy {
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "Legacy"
+    }
+
+    fn decode(_: Box<[u8]>) -> Result<Self, Infallible> {
+        Ok(Legacy)
+    }
+}
+
+#[test]
+fn static_str_name() {
+    assert_eq!(<Legacy as Asset>::asset_name(), "Legacy");
+    assert_eq!(Number::asset_name(), "Number");
+
+    let source = MemorySource::new();
+    source.insert_with_path("legacy", id(1), &b"null"[..]);
+    let loader = Loader::builder()
+        .with(source)
+        .with_registered_asset::<Legacy>()
+        .build();
+    assert!(loader.resolve_type_name("Legacy").is_some());
+
+    block_on(async {
+        loader.load::<Legacy, _>("legacy").await?;
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,91 @@
+//! Shuts down batch prewarm while sources are loaded.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use argosy::*;
+use common::*;
+
+#[test]
+fn cancel_prewarm_under_load() {
+    let ids: Vec<_> = (1..=100).map(id).collect();
+    let gate = Gate::new();
+    let loader = Loader::builder()
+        .with(gate.source(numbers(1..=100)))
+        .build();
+
+    block_on(async {
+        let cancel = CancelToken::new();
+        let prewarm = loader.prewarm::<Number, _>(ids.iter().copied(), &cancel);
+
+        let shutdown = async {
+            gate.open(10);
+
+            // Wait for 10 loads to finish while the rest hang.
+            while gate.served() < 10 || loader.in_flight() > 90 {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+            cancel.cancel();
+            Instant::now()
+        };
+
+        let (outcome, cancelled_at) = tokio::time::timeout(
+            Duration::from_secs(10),
+            futures::future::join(prewarm, shutdown),
+        )
+        .await
+        .expect("prewarm hangs");
+
+        assert!(cancelled_at.elapsed() < Duration::from_secs(1));
+        assert_eq!(outcome.items.len(), 100);
+        assert_eq!(outcome.succeeded(), 10);
+        assert_eq!(outcome.cancelled(), 90);
+        assert_eq!(outcome.failed(), 0);
+
+        // Prewarmed assets are served from the cache.
+        let index = outcome.items.iter().position(BatchItem::is_done).unwrap();
+        let mut number = loader.load::<Number, _>(ids[index]).await.unwrap();
+        assert_eq!(number.build(&mut ()).unwrap().value, index as u32 + 1);
+
+        // Abandoned loads are aborted.
+        assert!(
+            loader
+                .wait_idle_or_cancel(&cancel_after(Duration::from_secs(5)))
+                .await
+        );
+        assert_eq!(gate.served(), 10);
+    });
+}
+
+#[test]
+fn cancel_wait_idle() {
+    let loader = Loader::builder()
+        .with(Gate::new().source(MemorySource::new()))
+        .build();
+
+    block_on(async {
+        let _handle = loader.load::<Number, _>(id(1));
+        assert_eq!(loader.in_flight(), 1);
+
+        let idle = loader
+            .wait_idle_or_cancel(&cancel_after(Duration::from_millis(10)))
+            .await;
+        assert!(!idle);
+    });
+}
+
+/// Returns token cancelled after `delay`.
+fn cancel_after(delay: Duration) -> CancelToken {
+    let cancel = CancelToken::new();
+    tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            tokio::time::sleep(delay).await;
+            cancel.cancel();
+        }
+    });
+    cancel
+}
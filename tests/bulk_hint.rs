@@ -0,0 +1,129 @@
+//! Serves inline assets from bulk hints of the sources.
+
+#![cfg(feature = "tokio")]
+
+use std::sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc,
+};
+
+use argosy::{futures::future::BoxFuture, source::prelude::*, *};
+
+#[derive(Clone, Asset)]
+struct Number {
+    value: u64,
+}
+
+/// Same data as [`Number`], cached separately.
+#[derive(Clone, Asset)]
+struct Other {
+    value: u64,
+}
+
+/// Source with packed assets whose value is `version * 100 + id`.
+#[derive(Clone, Default)]
+struct Packed {
+    version: Arc<AtomicU64>,
+    bulk: Arc<AtomicUsize>,
+    loads: Arc<AtomicUsize>,
+}
+
+fn number(id: AssetId, version: u64) -> AssetData {
+    AssetData {
+        bytes: format!(r#"{{ "value": {} }}"#, version * 100 + id.value().get())
+            .into_bytes()
+            .into(),
+        version,
+        properties: AssetProperties::new(),
+    }
+}
+
+impl Source for Packed {
+    fn find<'a>(&'a self, _: &'a str, _: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        Box::pin(async { None })
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        self.loads.fetch_add(1, Ordering::Relaxed);
+        let version = self.version.load(Ordering::Relaxed);
+        Box::pin(async move { Ok(Some(number(id, version))) })
+    }
+
+    fn update<'a>(
+        &'a self,
+        id: AssetId,
+        version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        let latest = self.version.load(Ordering::Relaxed);
+        Box::pin(async move { Ok((latest > version).then(|| number(id, latest))) })
+    }
+
+    fn bulk_hint<'a>(&'a self) -> BoxFuture<'a, Result<Option<InlineAssets>, Error>> {
+        self.bulk.fetch_add(1, Ordering::Relaxed);
+        let version = self.version.load(Ordering::Relaxed);
+        Box::pin(async move {
+            let mut inline = InlineAssets::new();
+            for id in (1..=10).map(|id| AssetId::new(id).unwrap()) {
+                inline.insert(id, number(id, version));
+            }
+            Ok(Some(inline))
+        })
+    }
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+fn hint_is_fetched_when_source_is_queried() {
+    let id = |value| AssetId::new(value).unwrap();
+
+    let primary = MemorySource::new();
+    primary.insert(id(1), &br#"{ "value": 0 }"#[..]);
+    let packed = Packed::default();
+    let loader = Loader::builder().with(primary).with(packed.clone()).build();
+
+    block_on(async {
+        // Higher priority source has the asset, hint of the other one is not needed.
+        let mut number = loader.load::<Number, _>(id(1)).await?;
+        assert_eq!(number.build(&mut ())?.value, 0);
+        assert_eq!(packed.bulk.load(Ordering::Relaxed), 0);
+
+        let mut number = loader.load::<Number, _>(id(2)).await?;
+        assert_eq!(number.build(&mut ())?.value, 2);
+        assert_eq!(packed.bulk.load(Ordering::Relaxed), 1);
+        assert_eq!(packed.loads.load(Ordering::Relaxed), 0);
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn updated_asset_is_not_served_inline() {
+    let id = AssetId::new(1).unwrap();
+
+    let packed = Packed::default();
+    let loader = Loader::builder().with(packed.clone()).build();
+
+    block_on(async {
+        let mut number = loader.load::<Number, _>(id).await?;
+        assert_eq!(number.build(&mut ())?.value, 1);
+
+        packed.version.store(1, Ordering::Relaxed);
+        assert_eq!(loader.poll_updates().await, [id]);
+        let mut number = loader.load::<Number, _>(id).await?;
+        assert_eq!(number.build(&mut ())?.value, 101);
+
+        // Inline data is older than reported by update, asset is loaded instead.
+        let mut other = loader.load::<Other, _>(id).await?;
+        assert_eq!(other.build(&mut ())?.value, 101);
+        assert_eq!(packed.loads.load(Ordering::Relaxed), 1);
+        assert_eq!(packed.bulk.load(Ordering::Relaxed), 1);
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,78 @@
+//! Bundles assets with their dependencies.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use argosy::*;
+use common::*;
+
+#[derive(Clone, Asset)]
+struct Pair {
+    #[asset(external)]
+    left: Number,
+
+    #[asset(external)]
+    right: Number,
+}
+
+fn loader(source: &MemorySource) -> Loader {
+    source.insert(id(10), &br#"{ "left": 1, "right": 2 }"#[..]);
+    Loader::builder().with(source.clone()).build()
+}
+
+#[test]
+fn bundle_with_dependencies() {
+    let source = numbers([1, 2, 3]);
+    let loader = loader(&source);
+
+    let bundle = block_on(async {
+        loader.load::<Pair, _>(id(10)).await?.build(&mut ())?;
+
+        let mut bundle = Vec::new();
+        assert_eq!(loader.bundle(id(10), &mut bundle).await?, 3);
+        Ok::<_, Error>(bundle)
+    })
+    .unwrap();
+
+    // Bundle has the pair and its dependencies only.
+    let loader = Loader::builder()
+        .with(ArchiveSource::new(bundle).unwrap())
+        .build();
+    block_on(async {
+        let pair = loader.load::<Pair, _>(id(10)).await?.build(&mut ())?;
+        assert_eq!((pair.left.value, pair.right.value), (1, 2));
+        assert!(loader
+            .load::<Number, _>(id(3))
+            .await
+            .err()
+            .unwrap()
+            .is_not_found());
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn bundle_with_missing_dependency() {
+    let source = numbers([1, 2]);
+    let loader = loader(&source);
+
+    block_on(async {
+        loader.load::<Pair, _>(id(10)).await?.build(&mut ())?;
+
+        // Dependency is gone from the source after loading.
+        source.remove(id(2));
+
+        let mut bundle = Vec::new();
+        let err = loader.bundle(id(10), &mut bundle).await.err().unwrap();
+        assert!(err.is_not_found());
+
+        // Assets before the missing one are written.
+        let mut complete = Vec::new();
+        loader.bundle(id(1), &mut complete).await?;
+        assert!(bundle.len() > complete.len());
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
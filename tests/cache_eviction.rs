@@ -0,0 +1,105 @@
+//! Loads with cache backend that evicts entries.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use std::collections::VecDeque;
+
+use argosy::*;
+use common::*;
+
+/// Backend that keeps only the latest entry.
+struct LatestCache<K, V>(VecDeque<(u64, K, V)>);
+
+impl<K: Send + 'static, V: Send + 'static> CacheBackend<K, V> for LatestCache<K, V> {
+    fn get(&self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(&K, &V)> {
+        let (_, k, v) = self.0.iter().find(|(h, k, _)| *h == hash && eq(k))?;
+        Some((k, v))
+    }
+
+    fn get_mut(&mut self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(&K, &mut V)> {
+        let (_, k, v) = self.0.iter_mut().find(|(h, k, _)| *h == hash && eq(k))?;
+        Some((k, v))
+    }
+
+    fn insert(&mut self, hash: u64, key: K, value: V) {
+        self.0.clear();
+        self.0.push_back((hash, key, value));
+    }
+
+    fn remove(&mut self, hash: u64, eq: &mut dyn FnMut(&K) -> bool) -> Option<(K, V)> {
+        let index = self.0.iter().position(|(h, k, _)| *h == hash && eq(k))?;
+        let (_, k, v) = self.0.remove(index)?;
+        Some((k, v))
+    }
+
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &mut V) -> bool) {
+        self.0.retain_mut(|(_, k, v)| f(k, v));
+    }
+}
+
+struct LatestCacheFactory;
+
+impl CacheBackendFactory for LatestCacheFactory {
+    fn new_backend<K: Send + 'static, V: Send + 'static>(&self) -> Box<dyn CacheBackend<K, V>> {
+        Box::new(LatestCache(VecDeque::new()))
+    }
+}
+
+fn loader() -> Loader {
+    Loader::builder()
+        .with(numbers(1..=4))
+        .with_num_shards(1)
+        .with_cache_backend(LatestCacheFactory)
+        .build()
+}
+
+#[test]
+fn evicted_loads_are_cancelled() {
+    let loader = loader();
+
+    block_on(async {
+        let by_id: Vec<_> = (1..=4)
+            .map(|value| loader.load::<Number, _>(id(value)))
+            .collect();
+        let by_path: Vec<_> = (1..=4)
+            .map(|value| loader.load::<Number, _>(format!("number{value}").as_str()))
+            .collect();
+
+        for (value, handle) in (1..=4).chain(1..=4).zip(by_id.into_iter().chain(by_path)) {
+            match handle.await {
+                Ok(mut number) => match number.build(&mut ()) {
+                    Ok(number) => assert_eq!(number.value, value),
+                    Err(err) => assert!(err.is_cancelled(), "{err}"),
+                },
+                Err(err) => assert!(err.is_cancelled(), "{err}"),
+            }
+        }
+
+        // Evicted assets are loaded anew.
+        for value in 1..=4 {
+            let mut number = loader.load::<Number, _>(id(value.into())).await?;
+            assert_eq!(number.build(&mut ())?.value, value);
+        }
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn evicted_before_build() {
+    let loader = loader();
+
+    block_on(async {
+        let mut first = loader.load::<Number, _>(id(1)).await?;
+        loader.load::<Number, _>(id(2)).await?;
+
+        assert!(first.build(&mut ()).err().unwrap().is_cancelled());
+
+        let mut first = loader.load::<Number, _>(id(1)).await?;
+        assert_eq!(first.build(&mut ())?.value, 1);
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
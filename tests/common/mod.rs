@@ -0,0 +1,129 @@
+//! Fixtures shared by integration tests.
+
+#![allow(dead_code)]
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use argosy::{futures::future::BoxFuture, *};
+use tokio::sync::Semaphore;
+
+/// Asset decoded from `{ "value": N }`.
+#[derive(Clone, Debug, PartialEq, Asset)]
+pub struct Number {
+    pub value: u32,
+}
+
+/// Returns asset id with specified value.
+pub fn id(value: u64) -> AssetId {
+    AssetId::new(value).unwrap()
+}
+
+/// Returns JSON data of [`Number`] with specified value.
+pub fn number(value: u32) -> Vec<u8> {
+    format!(r#"{{ "value": {value} }}"#).into_bytes()
+}
+
+/// Returns source with [`Number`]s of specified values.
+/// Each number has id equal to its value and path `numberN`.
+pub fn numbers(values: impl IntoIterator<Item = u32>) -> MemorySource {
+    let source = MemorySource::new();
+    for value in values {
+        source.insert_with_path(format!("number{value}"), id(value.into()), number(value));
+    }
+    source
+}
+
+/// Runs future to completion on a single-threaded runtime with time enabled.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// Lets spawned tasks run until they are blocked.
+pub async fn settle() {
+    for _ in 0..16 {
+        tokio::task::yield_now().await;
+    }
+}
+
+/// Controls loads of [`Gated`] sources.
+/// Each load waits for a permit.
+#[derive(Clone)]
+pub struct Gate {
+    started: Arc<Mutex<Vec<u64>>>,
+    permits: Arc<Semaphore>,
+    served: Arc<AtomicUsize>,
+}
+
+impl Gate {
+    /// Returns closed gate.
+    pub fn new() -> Self {
+        Gate {
+            started: Arc::new(Mutex::new(Vec::new())),
+            permits: Arc::new(Semaphore::new(0)),
+            served: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns source that loads assets from `inner` through this gate.
+    pub fn source(&self, inner: MemorySource) -> Gated {
+        Gated {
+            inner,
+            gate: self.clone(),
+        }
+    }
+
+    /// Lets specified number of loads through.
+    pub fn open(&self, loads: usize) {
+        self.permits.add_permits(loads);
+    }
+
+    /// Returns ids of started loads in order.
+    pub fn started(&self) -> Vec<u64> {
+        self.started.lock().unwrap().clone()
+    }
+
+    /// Returns number of served loads.
+    pub fn served(&self) -> usize {
+        self.served.load(Ordering::Relaxed)
+    }
+}
+
+/// Source that records loads and serves them when [`Gate`] permits.
+pub struct Gated {
+    inner: MemorySource,
+    gate: Gate,
+}
+
+impl Source for Gated {
+    fn find<'a>(&'a self, path: &'a str, asset: &'a str) -> BoxFuture<'a, Option<AssetId>> {
+        self.inner.find(path, asset)
+    }
+
+    fn load<'a>(&'a self, id: AssetId) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        Box::pin(async move {
+            self.gate.started.lock().unwrap().push(id.value().get());
+            self.gate.permits.acquire().await.unwrap().forget();
+            let data = self.inner.load(id).await;
+            self.gate.served.fetch_add(1, Ordering::Relaxed);
+            data
+        })
+    }
+
+    fn update<'a>(
+        &'a self,
+        id: AssetId,
+        version: u64,
+    ) -> BoxFuture<'a, Result<Option<AssetData>, Error>> {
+        self.inner.update(id, version)
+    }
+}
@@ -0,0 +1,50 @@
+//! Derives assets with fields gated by `#[cfg(...)]`.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use argosy::*;
+use common::*;
+
+#[derive(Clone, Asset)]
+struct Sprite {
+    /// Number of frames.
+    frames: u32,
+
+    /// Only in builds that never happen.
+    #[cfg(any())]
+    debug_name: String,
+
+    #[cfg(any())]
+    #[asset(external)]
+    debug_overlay: Number,
+
+    #[cfg(not(any()))]
+    #[asset(external)]
+    palette: Number,
+}
+
+#[test]
+fn cfg_gated_fields() {
+    let source = numbers([1]);
+    source.insert_with_path(
+        "sprite",
+        id(10),
+        // Data for disabled fields is ignored.
+        &br#"{ "frames": 4, "debug_name": "hero", "debug_overlay": 2, "palette": 1 }"#[..],
+    );
+    let loader = Loader::builder().with(source).build();
+
+    block_on(async {
+        let mut sprite = loader.load::<Sprite, _>("sprite").await?;
+        let sprite = sprite.build(&mut ())?;
+        assert_eq!(sprite.frames, 4);
+        assert_eq!(sprite.palette, Number { value: 1 });
+
+        // Disabled external field is not loaded.
+        assert_eq!(loader.dependencies(id(10)), [id(1)]);
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,121 @@
+//! Loads derived assets with their dependencies from memory source.
+
+#![cfg(feature = "tokio")]
+
+use argosy::*;
+
+#[derive(Clone, serde::Deserialize)]
+struct Foo;
+
+#[derive(Clone, Asset)]
+struct Bar;
+
+#[derive(Clone, AssetField)]
+struct Baz;
+
+#[derive(Clone, Asset)]
+#[asset(name = "MyAssetStruct")]
+struct AssetStruct {
+    foo: Foo,
+
+    #[asset(external)]
+    bar: Bar,
+
+    baz: Baz,
+}
+
+const BAR: AssetId = AssetId::new(1).unwrap();
+const TREE: AssetId = AssetId::new(2).unwrap();
+const MISSING: AssetId = AssetId::new(9).unwrap();
+
+fn loader() -> Loader {
+    let source = MemorySource::new();
+    source.insert(BAR, &b"null"[..]);
+    source.insert_with_path(
+        "tree",
+        TREE,
+        &br#"{ "foo": null, "bar": 1, "baz": null }"#[..],
+    );
+    source.insert_with_path(
+        "malformed",
+        AssetId::new(3).unwrap(),
+        &br#"{ "foo": null, "#[..],
+    );
+    source.insert_with_path(
+        "orphan",
+        AssetId::new(4).unwrap(),
+        &br#"{ "foo": null, "bar": 9, "baz": null }"#[..],
+    );
+    Loader::builder().with(source).build()
+}
+
+fn block_on<T>(f: impl std::future::Future<Output = T>) -> T {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(f)
+}
+
+#[test]
+fn load_by_path_and_id() {
+    let loader = loader();
+    block_on(async {
+        // Awaited by path.
+        let mut loaded = loader.load::<AssetStruct, _>("tree").await.unwrap();
+        assert_eq!((loaded.id(), loaded.path()), (Some(TREE), Some("tree")));
+        let AssetStruct {
+            foo: Foo,
+            bar: Bar,
+            baz: Baz,
+        } = loaded.build(&mut ()).unwrap();
+
+        // Polled by id, the asset is already built.
+        let mut handle = loader.load::<AssetStruct, _>(TREE);
+        assert!(handle.poll_ready().unwrap().is_ok());
+    });
+}
+
+#[test]
+fn drive_erased() {
+    let loader = loader();
+    block_on(async {
+        let mut driver = loader.load::<Bar, _>(BAR).driver::<NoBuilderDrive>();
+        loader.wait_idle().await;
+        assert!(driver.poll_build(&mut ()));
+        assert!(loader.load::<Bar, _>(BAR).poll_ready().unwrap().is_ok());
+    });
+}
+
+#[test]
+fn missing_asset() {
+    let loader = loader();
+    let err = block_on(loader.load::<AssetStruct, _>(MISSING))
+        .err()
+        .unwrap();
+    assert_eq!(err.get_not_found().unwrap().id, Some(MISSING));
+}
+
+#[test]
+fn malformed_descriptor() {
+    let loader = loader();
+    let err = block_on(loader.load::<AssetStruct, _>("malformed"))
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err.get_decode_error::<AssetStruct>(),
+        Some(AssetStructDecodeError::Info(DecodeError::Json(_)))
+    ));
+}
+
+#[test]
+fn missing_dependency() {
+    let loader = loader();
+    let err = block_on(loader.load::<AssetStruct, _>("orphan"))
+        .err()
+        .unwrap();
+    let Some(AssetStructDecodeError::BarError(dependency)) = err.get_decode_error::<AssetStruct>()
+    else {
+        panic!("Missing dependency must fail decoding of the field");
+    };
+    assert_eq!(dependency.get_not_found().unwrap().id, Some(MISSING));
+}
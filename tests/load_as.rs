@@ -0,0 +1,76 @@
+//! Loads assets decoded from requested formats.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use argosy::*;
+use common::*;
+
+/// Asset with dependency, decoded asynchronously.
+#[derive(Clone, Debug, Asset)]
+struct Labeled {
+    label: u32,
+
+    #[asset(external)]
+    number: Number,
+}
+
+/// Returns bincode data of [`Labeled`].
+fn labeled(label: u32, number: u64) -> Vec<u8> {
+    let mut bytes = label.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&number.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn same_payload_in_each_format() {
+    let loader = Loader::builder().with(numbers([7])).build();
+
+    // JSON text read as bincode is the number of its first bytes.
+    let bincode_value = u32::from_le_bytes(number(7)[..4].try_into().unwrap());
+
+    block_on(async {
+        // Both loads are in flight at once and don't share state.
+        let json = loader.load_as::<Number, _>(id(7), AssetFormat::Json);
+        let bincode = loader.load_as::<Number, _>("number7", AssetFormat::Bincode);
+
+        assert_eq!(json.await?.build(&mut ())?.value, 7);
+        assert_eq!(bincode.await?.build(&mut ())?.value, bincode_value);
+
+        // Format is detected without override.
+        let mut detected = loader.load::<Number, _>(id(7)).await?;
+        assert_eq!(detected.build(&mut ())?.value, 7);
+
+        // Repeated load with a format shares its state.
+        let mut handle = loader.load_as::<Number, _>(id(7), AssetFormat::Bincode);
+        assert_eq!(handle.poll_ready().unwrap()?.value, bincode_value);
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn format_of_asset_with_dependencies() {
+    let source = numbers([1]);
+    source.insert_with_path("labeled", id(10), labeled(5, 1));
+    let loader = Loader::builder().with(source).build();
+
+    block_on(async {
+        let err = loader
+            .load_as::<Labeled, _>("labeled", AssetFormat::Json)
+            .await
+            .err()
+            .unwrap();
+        assert!(format!("{err:#}").contains("from json"), "{err:#}");
+
+        let mut labeled = loader
+            .load_as::<Labeled, _>("labeled", AssetFormat::Bincode)
+            .await?;
+        let labeled = labeled.build(&mut ())?;
+        assert_eq!(labeled.label, 5);
+        assert_eq!(labeled.number, Number { value: 1 });
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
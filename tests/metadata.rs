@@ -0,0 +1,52 @@
+//! Metadata of loaded assets.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use argosy::*;
+use common::*;
+
+#[test]
+fn metadata_of_loaded_asset() {
+    let first = MemorySource::new();
+    let second = numbers([1, 2]);
+    second.set_properties(id(2), AssetProperties::new().with("origin", "disk"));
+    let loader = Loader::builder()
+        .with(first)
+        .with_labeled("second", second)
+        .build();
+
+    block_on(async {
+        let loaded = loader.load::<Number, _>(id(2)).await?;
+        let metadata = loaded.metadata();
+        assert_eq!(metadata.source_index, 1);
+        assert_eq!(metadata.source_label.as_deref(), Some("second"));
+        assert_eq!(metadata.bytes_len, number(2).len());
+        assert_eq!(
+            metadata.properties.get("origin").map(|s| &**s),
+            Some("disk")
+        );
+
+        // Metadata is kept after asset is built.
+        let mut loaded = loader.load::<Number, _>(id(1)).await?;
+        loaded.build(&mut ())?;
+        let metadata = loader.load::<Number, _>(id(1)).await?.metadata();
+        assert_eq!(metadata.bytes_len, number(1).len());
+        assert!(metadata.properties.is_empty());
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn no_metadata_for_missing_asset() {
+    let loader = Loader::builder().with(numbers([1])).build();
+
+    block_on(async {
+        let mut handle = loader.load::<Number, _>(id(9));
+        let err = (&mut handle).await.err().unwrap();
+        assert!(err.is_not_found());
+        assert!(handle.poll_loaded().unwrap().is_err());
+    });
+}
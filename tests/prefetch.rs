@@ -0,0 +1,54 @@
+//! Scheduling of prefetches by score.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use argosy::*;
+use common::*;
+
+#[test]
+fn prefetches_start_by_score() {
+    let gate = Gate::new();
+    let loader = Loader::builder()
+        .with(gate.source(numbers(1..=5)))
+        .with_prefetch_limits(2, usize::MAX)
+        .build();
+
+    block_on(async {
+        for (n, score) in [(1, 1.0), (2, 5.0), (3, 3.0), (4, 4.0), (5, 2.0)] {
+            loader.prefetch::<Number, _>(id(n), score);
+        }
+        settle().await;
+        assert_eq!(gate.started(), [1, 2]);
+
+        let queue = loader.prefetch_queue();
+        let ids: Vec<_> = queue.iter().map(|e| e.id.unwrap().value().get()).collect();
+        assert_eq!(ids, [2, 4, 3, 5, 1]);
+        assert_eq!(queue[1].status, PrefetchStatus::Pending);
+
+        // Scores change while prefetches wait.
+        assert!(loader.reprioritize::<Number, _>(id(3), 0.0));
+        assert!(loader.reprioritize::<Number, _>(id(5), 6.0));
+
+        gate.open(1);
+        settle().await;
+        assert_eq!(gate.started(), [1, 2, 5]);
+
+        gate.open(4);
+        settle().await;
+        assert_eq!(gate.started(), [1, 2, 5, 4]);
+
+        // Prefetched assets are decoded, but not built.
+        let queue = loader.prefetch_queue();
+        assert_eq!(queue.len(), 4);
+        assert!(queue
+            .iter()
+            .all(|e| matches!(e.status, PrefetchStatus::Loaded { .. })));
+
+        let mut number = loader.load::<Number, _>(id(2)).await?;
+        assert_eq!(number.build(&mut ())?.value, 2);
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,73 @@
+//! Publication of related assets observed by concurrent readers.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use argosy::*;
+use common::*;
+
+#[derive(Clone, Asset)]
+struct Material {
+    version: u32,
+}
+
+#[derive(Clone, Asset)]
+struct Shader {
+    version: u32,
+}
+
+#[test]
+fn readers_never_observe_mixed_versions() {
+    const VERSIONS: u32 = 1000;
+
+    // Assets are likely in different shards.
+    let loader = Loader::builder()
+        .with(MemorySource::new())
+        .with_num_shards(8)
+        .build();
+
+    let publish = |version| {
+        let mut tx = loader.begin_publish();
+        tx.stage(id(1), Material { version });
+        tx.stage(id(2), Shader { version });
+        tx.commit();
+    };
+    publish(0);
+
+    let read = || {
+        loader.read_consistent(|| {
+            let material = loader
+                .load::<Material, _>(id(1))
+                .poll_ready()
+                .unwrap()
+                .unwrap();
+            let shader = loader
+                .load::<Shader, _>(id(2))
+                .poll_ready()
+                .unwrap()
+                .unwrap();
+            (material.version, shader.version)
+        })
+    };
+
+    std::thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                let mut last = 0;
+                while last < VERSIONS {
+                    let (material, shader) = read();
+                    assert_eq!(material, shader, "Mixed versions observed");
+                    assert!(material >= last);
+                    last = material;
+                }
+            });
+        }
+
+        for version in 1..=VERSIONS {
+            publish(version);
+        }
+    });
+
+    assert_eq!(read(), (VERSIONS, VERSIONS));
+}
@@ -0,0 +1,86 @@
+//! Forgets failures while handles still point to them.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use argosy::*;
+use common::*;
+
+/// Asset that fails to build.
+#[derive(Clone)]
+struct Broken;
+
+#[derive(Debug)]
+struct BuildFailed;
+
+impl std::fmt::Display for BuildFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("build failed")
+    }
+}
+
+impl std::error::Error for BuildFailed {}
+
+impl Asset for Broken {
+    type Decoded = Broken;
+    type DecodeError = std::convert::Infallible;
+    type BuildError = BuildFailed;
+    type Fut = std::future::Ready<Result<Broken, std::convert::Infallible>>;
+
+    fn asset_name() -> AssetName {
+        AssetName::new("Broken")
+    }
+
+    fn decode(_: Box<[u8]>, _: &Loader) -> Self::Fut {
+        std::future::ready(Ok(Broken))
+    }
+}
+
+impl<B> AssetBuild<B> for Broken {
+    fn build(_: &mut B, _: Broken) -> Result<Broken, BuildFailed> {
+        Err(BuildFailed)
+    }
+}
+
+fn loader() -> Loader {
+    let source = MemorySource::new();
+    source.insert(id(1), &b"broken"[..]);
+    Loader::builder().with(source).build()
+}
+
+#[test]
+fn build_after_retry() {
+    let loader = loader();
+
+    block_on(async {
+        let mut first = loader.load::<Broken, _>(id(1)).await.unwrap();
+        let mut second = first.clone();
+        let mut third = first.clone();
+        assert!(first.build(&mut ()).is_err());
+
+        // Entry is removed.
+        assert!(loader.retry(id(1)));
+        assert!(second.build(&mut ()).err().unwrap().is_cancelled());
+        assert!(second.build(&mut ()).err().unwrap().is_cancelled());
+
+        // Entry is loading anew.
+        let handle = loader.load::<Broken, _>(id(1));
+        assert!(third.build(&mut ()).err().unwrap().is_cancelled());
+        assert!(handle.await.unwrap().build(&mut ()).is_err());
+    });
+}
+
+#[test]
+fn build_after_forget_failures() {
+    let loader = loader();
+
+    block_on(async {
+        let mut first = loader.load::<Broken, _>(id(1)).await.unwrap();
+        let mut second = first.clone();
+        assert!(first.build(&mut ()).is_err());
+
+        loader.forget_failures();
+        assert!(second.build(&mut ()).err().unwrap().is_cancelled());
+    });
+}
@@ -0,0 +1,117 @@
+//! Strictness of descriptors decoded asynchronously.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use std::convert::Infallible;
+
+use argosy::*;
+use common::*;
+use futures::future::BoxFuture;
+
+/// Asset that decodes its info after `decode` returns.
+#[derive(Clone, Debug)]
+struct Deferred {
+    value: u32,
+}
+
+impl Asset for Deferred {
+    type Decoded = Deferred;
+    type DecodeError = DecodeError;
+    type BuildError = Infallible;
+    type Fut = BoxFuture<'static, Result<Deferred, DecodeError>>;
+
+    fn asset_name() -> AssetName {
+        AssetName::new("Deferred")
+    }
+
+    fn decode(bytes: Box<[u8]>, loader: &Loader) -> Self::Fut {
+        // Loader is not available to the future.
+        let strict = loader.strict_descriptors();
+
+        Box::pin(async move {
+            // Let other decodes run in between.
+            tokio::task::yield_now().await;
+
+            let info: serde_json::Value =
+                serde_json::from_slice(&bytes).map_err(DecodeError::Json)?;
+            if let Some(field) = info
+                .as_object()
+                .and_then(|object| object.keys().find(|key| *key != "value"))
+                .filter(|_| strict)
+            {
+                return Err(DecodeError::UnknownField {
+                    field: field.clone(),
+                    expected: &["value"],
+                });
+            }
+
+            let value = info["value"].as_u64().unwrap_or_default() as u32;
+            Ok(Deferred { value })
+        })
+    }
+}
+
+/// Derived asset that is not trivial.
+#[derive(Clone, Debug, Asset)]
+struct Wrapper {
+    #[asset(external)]
+    number: Number,
+}
+
+impl<B> AssetBuild<B> for Deferred {
+    fn build(_: &mut B, decoded: Deferred) -> Result<Deferred, Infallible> {
+        Ok(decoded)
+    }
+}
+
+#[test]
+fn interleaved_strict_and_lenient_decodes() {
+    let source = MemorySource::new();
+    source.insert_with_path("deferred", id(1), &br#"{ "value": 3, "extra": 1 }"#[..]);
+    let strict = Loader::builder()
+        .with(source.clone())
+        .with_strict_descriptors(true)
+        .build();
+    let lenient = Loader::builder().with(source).build();
+
+    block_on(async {
+        // Both futures are polled on this thread at once.
+        let strict = strict.load::<Deferred, _>("deferred");
+        let lenient = lenient.load::<Deferred, _>("deferred");
+
+        let err = strict.await.err().unwrap();
+        assert!(
+            format!("{err:#}").contains("Unknown field 'extra'"),
+            "{err:#}"
+        );
+        assert_eq!(lenient.await?.build(&mut ())?.value, 3);
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn derived_asset_with_dependencies_in_strict_loader() {
+    let source = numbers([2]);
+    source.insert_with_path("extra", id(3), &br#"{ "number": 2, "extra": 1 }"#[..]);
+    source.insert_with_path("exact", id(4), &br#"{ "number": 2 }"#[..]);
+    let loader = Loader::builder()
+        .with(source)
+        .with_strict_descriptors(true)
+        .build();
+
+    block_on(async {
+        let err = loader.load::<Wrapper, _>("extra").await.err().unwrap();
+        assert!(
+            format!("{err:#}").contains("Unknown field 'extra'"),
+            "{err:#}"
+        );
+
+        let mut exact = loader.load::<Wrapper, _>("exact").await?;
+        assert_eq!(exact.build(&mut ())?.number, Number { value: 2 });
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,7 @@
+//! Checks errors reported for invalid asset definitions.
+
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,37 @@
+use argosy::*;
+
+trait GpuBuilder {}
+
+#[derive(Clone)]
+struct Texture;
+
+impl LeafAsset for Texture {
+    type Decoded = ();
+    type DecodeError = std::convert::Infallible;
+    type BuildError = std::convert::Infallible;
+
+    fn asset_name() -> AssetName {
+        AssetName::new("Texture")
+    }
+
+    fn decode(_: Box<[u8]>) -> Result<(), Self::DecodeError> {
+        Ok(())
+    }
+}
+
+impl AssetBuildVia<dyn GpuBuilder> for Texture {
+    fn build_via(_: &mut (dyn GpuBuilder + 'static), _: ()) -> Result<Self, Self::BuildError> {
+        Ok(Texture)
+    }
+}
+
+impl_asset_build_via!(Texture, dyn GpuBuilder);
+
+struct AudioCtx;
+
+// `AudioCtx` is not in the `GpuBuilder` family.
+fn build(decoded: ()) -> Texture {
+    <Texture as AssetBuild<AudioCtx>>::build(&mut AudioCtx, decoded).unwrap()
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+use std::convert::Infallible;
+
+use argosy::*;
+
+trait GpuBuilder {}
+
+#[derive(Clone, serde::Deserialize)]
+struct Tint(f32);
+
+impl TrivialAsset for Tint {
+    type Error = serde_json::Error;
+
+    fn asset_name() -> AssetName {
+        AssetName::new("Tint")
+    }
+
+    fn decode(bytes: Box<[u8]>) -> Result<Self, Self::Error> {
+        serde_json::from_slice(&bytes)
+    }
+}
+
+impl AssetBuildVia<dyn GpuBuilder> for Tint {
+    fn build_via(_: &mut (dyn GpuBuilder + 'static), tint: Tint) -> Result<Self, Infallible> {
+        Ok(tint)
+    }
+}
+
+// Trivial assets are built with any builder already.
+impl_asset_build_via!(Tint, dyn GpuBuilder);
+
+fn main() {}
@@ -0,0 +1,22 @@
+use argosy::*;
+
+#[derive(Clone, Asset)]
+struct Texture;
+
+#[derive(Clone)]
+struct Icon;
+
+impl From<Texture> for Icon {
+    fn from(_: Texture) -> Self {
+        Icon
+    }
+}
+
+#[derive(Clone, Asset)]
+struct Menu {
+    // Nested wrappers are not supported.
+    #[asset(external(as Texture))]
+    icons: Option<Vec<Icon>>,
+}
+
+fn main() {}
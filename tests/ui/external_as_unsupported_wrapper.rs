@@ -0,0 +1,22 @@
+use argosy::*;
+
+#[derive(Clone, Asset)]
+struct Texture;
+
+#[derive(Clone)]
+struct Icon;
+
+impl From<Texture> for Icon {
+    fn from(_: Texture) -> Self {
+        Icon
+    }
+}
+
+#[derive(Clone, Asset)]
+struct Menu {
+    // Only `Option<_>`, `Vec<_>` and `Arc<[_]>` wrappers are supported.
+    #[asset(external(as Texture))]
+    icon: Box<Icon>,
+}
+
+fn main() {}
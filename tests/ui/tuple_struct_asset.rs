@@ -0,0 +1,6 @@
+use argosy::Asset;
+
+#[derive(Clone, Asset)]
+struct Position(f32, f32);
+
+fn main() {}
@@ -0,0 +1,6 @@
+use argosy::AssetField;
+
+#[derive(Clone, AssetField)]
+struct Position(f32, f32);
+
+fn main() {}
@@ -0,0 +1,40 @@
+use argosy::*;
+
+#[derive(Clone)]
+struct Texture;
+
+impl LeafAsset for Texture {
+    type Decoded = ();
+    type DecodeError = std::convert::Infallible;
+    type BuildError = std::convert::Infallible;
+
+    fn asset_name() -> AssetName {
+        AssetName::new("Texture")
+    }
+
+    fn decode(_: Box<[u8]>) -> Result<(), std::convert::Infallible> {
+        Ok(())
+    }
+}
+
+struct Gpu;
+struct Vulkan;
+
+impl AssetBuild<Gpu> for Texture {
+    fn build(_: &mut Gpu, _: ()) -> Result<Texture, std::convert::Infallible> {
+        Ok(Texture)
+    }
+}
+
+impl AssetBuild<Vulkan> for Texture {
+    fn build(_: &mut Vulkan, _: ()) -> Result<Texture, std::convert::Infallible> {
+        Ok(Texture)
+    }
+}
+
+fn main() {
+    let loader = Loader::builder().build();
+
+    // Asset `Texture` cannot be built with builder `()`.
+    loader.load::<Texture, _>("texture").poll_build(&mut ());
+}
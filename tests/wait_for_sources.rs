@@ -0,0 +1,50 @@
+//! Loads issued before sources are added.
+
+#![cfg(feature = "tokio")]
+
+mod common;
+
+use argosy::*;
+use common::*;
+
+#[test]
+fn load_resolves_once_source_is_pushed() {
+    let loader = Loader::builder().build();
+    loader.set_wait_for_sources(true);
+
+    block_on(async {
+        let mut by_id = loader.load::<Number, _>(id(1));
+        let mut by_path = loader.load::<Number, _>("number2");
+        settle().await;
+        assert!(by_id.poll_loaded().is_none());
+        assert!(by_path.poll_loaded().is_none());
+
+        loader.push_source(numbers([1, 2]));
+
+        assert_eq!(by_id.await?.build(&mut ())?.value, 1);
+        assert_eq!(by_path.await?.build(&mut ())?.value, 2);
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn disabling_wait_completes_pending_loads_as_missing() {
+    let loader = Loader::builder().with(numbers([1])).build();
+    loader.set_wait_for_sources(true);
+
+    block_on(async {
+        let mut by_id = loader.load::<Number, _>(id(2));
+        let mut by_path = loader.load::<Number, _>("number3");
+        settle().await;
+        assert!(by_id.poll_loaded().is_none());
+        assert!(by_path.poll_loaded().is_none());
+
+        loader.set_wait_for_sources(false);
+
+        assert!(by_id.await.err().unwrap().is_not_found());
+        assert!(by_path.await.err().unwrap().is_not_found());
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}